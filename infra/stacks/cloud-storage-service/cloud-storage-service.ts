@@ -39,6 +39,7 @@ type CreateCloudStorageServiceServiceArgs = {
   secretKeyArns: (pulumi.Output<string> | string)[];
   notificationQueueArn: pulumi.Output<string> | string;
   searchEventQueueArn: pulumi.Output<string> | string;
+  convertQueueArn: pulumi.Output<string> | string;
   tags: { [key: string]: string };
 };
 
@@ -72,6 +73,7 @@ export class CloudStorageService extends pulumi.ComponentResource {
       secretKeyArns,
       notificationQueueArn,
       searchEventQueueArn,
+      convertQueueArn,
       tags,
     }: CreateCloudStorageServiceServiceArgs,
     opts?: pulumi.ComponentResourceOptions
@@ -151,6 +153,7 @@ export class CloudStorageService extends pulumi.ComponentResource {
                 pulumi.interpolate`${notificationQueueArn}`,
                 pulumi.interpolate`${searchEventQueueArn}`,
                 pulumi.interpolate`${deleteDocumentQueueArn}`,
+                pulumi.interpolate`${convertQueueArn}`,
               ],
               Effect: 'Allow',
             },
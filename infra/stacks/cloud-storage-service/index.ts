@@ -199,6 +199,22 @@ export const deleteChatHandlerLambdaName = deleteChatHandler.lambda.name;
 export const deleteChatQueueArn = deleteChatHandler.queue.arn;
 export const deleteChatQueueName = deleteChatHandler.queue.name;
 
+const convertServiceStack = new pulumi.StackReference('convert-service-stack', {
+  name: `macro-inc/convert-service/${stack}`,
+});
+
+const convertServiceRoleArn: pulumi.Output<string> = convertServiceStack
+  .getOutput('convertServiceRoleArn')
+  .apply((arn) => arn as string);
+
+const convertQueueName: pulumi.Output<string> = convertServiceStack
+  .getOutput('convertQueueName')
+  .apply((name) => name as string);
+
+const convertQueueArn: pulumi.Output<string> = convertServiceStack
+  .getOutput('convertQueueArn')
+  .apply((arn) => arn as string);
+
 const MACRO_API_TOKENS = getMacroApiToken();
 
 const cloudStorageService = new CloudStorageService(
@@ -226,6 +242,7 @@ const cloudStorageService = new CloudStorageService(
       MACRO_API_TOKENS.macroApiTokenPublicKeyArn,
     ],
     notificationQueueArn,
+    convertQueueArn,
     containerEnvVars: [
       {
         name: 'DATABASE_URL',
@@ -304,6 +321,10 @@ const cloudStorageService = new CloudStorageService(
         name: 'SEARCH_EVENT_QUEUE',
         value: pulumi.interpolate`${searchEventQueueName}`,
       },
+      {
+        name: 'CONVERT_QUEUE',
+        value: pulumi.interpolate`${convertQueueName}`,
+      },
       {
         name: 'COMMS_SERVICE_URL',
         value: `https://comms-service${
@@ -348,6 +369,10 @@ const cloudStorageService = new CloudStorageService(
         name: 'SYNC_SERVICE_URL',
         value: `https://sync-service-${stack}.macroverse.workers.dev`,
       },
+      {
+        name: 'LEXICAL_SERVICE_URL',
+        value: `https://lexical-service-${stack}.macroverse.workers.dev`,
+      },
       {
         name: 'MACRO_API_TOKEN_ISSUER',
         value: pulumi.interpolate`${MACRO_API_TOKENS.macroApiTokenIssuer}`,
@@ -371,22 +396,6 @@ export const cloudStorageServiceSgId = cloudStorageService.serviceSg.id;
 export const cloudStorageServiceAlbSgId = cloudStorageService.serviceAlbSg.id;
 export const cloudStorageServiceUrl = pulumi.interpolate`${cloudStorageService.domain}`;
 
-const convertServiceStack = new pulumi.StackReference('convert-service-stack', {
-  name: `macro-inc/convert-service/${stack}`,
-});
-
-const convertServiceRoleArn: pulumi.Output<string> = convertServiceStack
-  .getOutput('convertServiceRoleArn')
-  .apply((arn) => arn as string);
-
-const convertQueueName: pulumi.Output<string> = convertServiceStack
-  .getOutput('convertQueueName')
-  .apply((name) => name as string);
-
-const convertQueueArn: pulumi.Output<string> = convertServiceStack
-  .getOutput('convertQueueArn')
-  .apply((arn) => arn as string);
-
 // ------------------------------------------- DOCX Unzip -------------------------------------------
 const docxUnzipHandlerEnvVars: DocxUnzipLambdaEnvVars = {
   DATABASE_URL: pulumi.interpolate`${DATABASE_URL_PROXY}`,
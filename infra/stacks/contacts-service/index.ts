@@ -64,11 +64,19 @@ const internalApiKeyArn: pulumi.Output<string> = aws.secretsmanager
   .getSecretVersionOutput({ secretId: INTERNAL_API_SECRET_KEY })
   .apply((secret) => secret.arn);
 
+const AUTHENTICATION_SERVICE_SECRET_KEY = config.require(
+  `authentication_service_secret_key`
+);
+const authenticationServiceSecretKey: pulumi.Output<string> = aws.secretsmanager
+  .getSecretVersionOutput({ secretId: AUTHENTICATION_SERVICE_SECRET_KEY })
+  .apply((secret) => secret.arn);
+
 let MACRO_API_TOKENS = getMacroApiToken();
 
 const secretKeyArns = [
   pulumi.interpolate`${jwtSecretKeyArn}`,
   pulumi.interpolate`${internalApiKeyArn}`,
+  pulumi.interpolate`${authenticationServiceSecretKey}`,
   MACRO_API_TOKENS.macroApiTokenPublicKeyArn,
 ];
 
@@ -105,6 +113,14 @@ let containerEnvVars = [
     name: 'INTERNAL_API_SECRET_KEY',
     value: pulumi.interpolate`${INTERNAL_API_SECRET_KEY}`,
   },
+  {
+    name: 'AUTHENTICATION_SERVICE_SECRET_KEY',
+    value: pulumi.interpolate`${AUTHENTICATION_SERVICE_SECRET_KEY}`,
+  },
+  {
+    name: 'AUTHENTICATION_SERVICE_URL',
+    value: `https://auth-service${stack === 'prod' ? '' : `-${stack}`}.macro.com`,
+  },
   {
     name: 'MACRO_API_TOKEN_ISSUER',
     value: pulumi.interpolate`${MACRO_API_TOKENS.macroApiTokenIssuer}`,
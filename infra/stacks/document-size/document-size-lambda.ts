@@ -0,0 +1,111 @@
+import { Lambda } from '@lambda';
+import * as aws from '@pulumi/aws';
+import * as pulumi from '@pulumi/pulumi';
+import { CLOUD_TRAIL_SNS_TOPIC_ARN, stack } from '@shared';
+
+const LAMBDA_BASE_NAME = 'document_size_handler';
+const CLOUD_STORAGE_BASE = `../../../rust/cloud-storage`;
+const ZIP_LOCATION = `${CLOUD_STORAGE_BASE}/target/lambda/${LAMBDA_BASE_NAME}/bootstrap.zip`;
+
+type EnvVars = {
+  DATABASE_URL: pulumi.Output<string> | string;
+  ENVIRONMENT: pulumi.Output<string> | string;
+  RUST_LOG: pulumi.Output<string> | string;
+};
+
+type Args = {
+  envVars: EnvVars;
+  vpc: {
+    vpcId: pulumi.Output<string> | string;
+    publicSubnetIds: pulumi.Output<string[]> | string[];
+    privateSubnetIds: pulumi.Output<string[]> | string[];
+  };
+  tags: { [key: string]: string };
+};
+
+export class DocumentSizeHandler extends pulumi.ComponentResource {
+  role: aws.iam.Role;
+  lambda: aws.lambda.Function;
+  tags: { [key: string]: string };
+  constructor(
+    name: string,
+    args: Args,
+    opts?: pulumi.ComponentResourceOptions
+  ) {
+    super('my:components:DocumentSizeHandler', name, {}, opts);
+    const { envVars, vpc, tags } = args;
+
+    this.tags = tags;
+
+    this.role = new aws.iam.Role(
+      `${LAMBDA_BASE_NAME}-role`,
+      {
+        name: `${LAMBDA_BASE_NAME}-role-${stack}`,
+        assumeRolePolicy: JSON.stringify({
+          Version: '2012-10-17',
+          Statement: [
+            {
+              Action: 'sts:AssumeRole',
+              Effect: 'Allow',
+              Principal: {
+                Service: 'lambda.amazonaws.com',
+              },
+            },
+          ],
+        }),
+        managedPolicyArns: [
+          aws.iam.ManagedPolicy.AWSLambdaBasicExecutionRole,
+          aws.iam.ManagedPolicy.AWSLambdaRole,
+          aws.iam.ManagedPolicy.AWSLambdaVPCAccessExecutionRole,
+          aws.iam.ManagedPolicy.CloudWatchLogsFullAccess,
+        ],
+        tags: this.tags,
+      },
+      { parent: this }
+    );
+
+    const lambda = new Lambda<EnvVars>(
+      `${LAMBDA_BASE_NAME}-lambda`,
+      {
+        baseName: LAMBDA_BASE_NAME,
+        handlerBase: `${CLOUD_STORAGE_BASE}/${LAMBDA_BASE_NAME}`,
+        zipLocation: ZIP_LOCATION,
+        vpc,
+        envVars,
+        role: this.role,
+        // every invocation holds a db connection so this bounds the connections used
+        reservedConcurrentExecutions: stack === 'prod' ? 50 : 10,
+        tags: this.tags,
+      },
+      { parent: this }
+    );
+
+    this.lambda = lambda.lambda;
+
+    this.setupLambdaAlarms();
+  }
+
+  setupLambdaAlarms() {
+    new aws.cloudwatch.MetricAlarm(
+      `${LAMBDA_BASE_NAME}-error-alarm`,
+      {
+        name: `${LAMBDA_BASE_NAME}-error-count-${stack}`,
+        metricName: 'Errors',
+        namespace: 'AWS/Lambda',
+        statistic: 'Sum',
+        period: 300,
+        evaluationPeriods: 1,
+        threshold: 1,
+        comparisonOperator: 'GreaterThanOrEqualToThreshold',
+        dimensions: {
+          FunctionName: this.lambda.name,
+        },
+        alarmDescription: `Alarm when ${LAMBDA_BASE_NAME} lambda experiences errors.`,
+        actionsEnabled: true,
+        alarmActions: [CLOUD_TRAIL_SNS_TOPIC_ARN],
+        tags: this.tags,
+      },
+      { parent: this }
+    );
+  }
+}
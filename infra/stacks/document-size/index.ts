@@ -0,0 +1,36 @@
+import * as aws from '@pulumi/aws';
+import * as pulumi from '@pulumi/pulumi';
+import { config, stack } from '@shared';
+import { get_coparse_api_vpc } from '@vpc';
+import { DocumentSizeHandler } from './document-size-lambda';
+
+const tags = {
+  environment: stack,
+  tech_lead: 'hutch',
+  project: 'document-size',
+};
+
+const DATABASE_URL = aws.secretsmanager
+  .getSecretVersionOutput({
+    secretId: config.require(`macro_db_proxy_secret_key`),
+  })
+  .apply((secret) => secret.secretString);
+
+const vpc = get_coparse_api_vpc();
+
+const documentSizeHandler = new DocumentSizeHandler(
+  `document-size-handler-${stack}`,
+  {
+    vpc,
+    envVars: {
+      DATABASE_URL: pulumi.interpolate`${DATABASE_URL}`,
+      ENVIRONMENT: stack,
+      RUST_LOG: 'document_size_handler=info',
+    },
+    tags,
+  }
+);
+
+export const documentSizeHandlerLambdaRoleArn = documentSizeHandler.role.arn;
+export const documentSizeHandlerLambdaArn = documentSizeHandler.lambda.arn;
+export const documentSizeHandlerLambdaName = documentSizeHandler.lambda.name;
@@ -34,6 +34,13 @@ export const setupS3EventBridgeTriggers = () => {
     .getOutput('documentTextExtractorLambdaArn')
     .apply((id) => id as string);
 
+  const documentSizeHandlerLambdaArn = new pulumi.StackReference(
+    'document-size',
+    { name: `macro-inc/document-size/${stack}` }
+  )
+    .getOutput('documentSizeHandlerLambdaArn')
+    .apply((id) => id as string);
+
   // Enable EventBridge notifications for the S3 bucket
   new aws.s3.BucketNotification('eventbridge-notification', {
     bucket: bucketId,
@@ -105,6 +112,34 @@ export const setupS3EventBridgeTriggers = () => {
       });
     });
 
+  pulumi
+    .all([bucketId, documentSizeHandlerLambdaArn])
+    .apply(([bucketId, lambdaArn]) => {
+      // Rule for document size Lambda (handles all files)
+      const documentSizeRule = new aws.cloudwatch.EventRule(
+        `document-size-rule-${stack}`,
+        {
+          name: `document-size-rule-${stack}`,
+          description: 'Triggers document size Lambda for all files',
+          eventPattern: JSON.stringify({
+            source: ['aws.s3'],
+            'detail-type': ['Object Created'],
+            detail: {
+              bucket: {
+                name: [bucketId],
+              },
+            },
+          }),
+        }
+      );
+
+      // Add the Lambda as a target
+      new aws.cloudwatch.EventTarget('document-size-target', {
+        rule: documentSizeRule.name,
+        arn: lambdaArn,
+      });
+    });
+
   // Add necessary permissions for EventBridge to invoke Lambda functions
   const createLambdaPermission = (functionArn: string, ruleId: string) => {
     return new aws.lambda.Permission(`eventbridge-permission-${ruleId}`, {
@@ -117,13 +152,18 @@ export const setupS3EventBridgeTriggers = () => {
 
   // Create permissions for all Lambda functions
   pulumi
-    .all([searchUploadHandlerLambdaArn, documentTextExtractorLambdaArn])
-    .apply(([searchUploadHandlerLambdaArn, extractorArn]) => {
+    .all([
+      searchUploadHandlerLambdaArn,
+      documentTextExtractorLambdaArn,
+      documentSizeHandlerLambdaArn,
+    ])
+    .apply(([searchUploadHandlerLambdaArn, extractorArn, documentSizeArn]) => {
       createLambdaPermission(
         searchUploadHandlerLambdaArn,
         `search-upload-rule-${stack}`
       );
       createLambdaPermission(extractorArn, `text-extractor-rule-${stack}`);
+      createLambdaPermission(documentSizeArn, `document-size-rule-${stack}`);
     });
 };
 
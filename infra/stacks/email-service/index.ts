@@ -73,6 +73,12 @@ const INTERNAL_AUTH_KEY = aws.secretsmanager
   })
   .apply((secret) => secret.secretString);
 
+const IMAGE_PROXY_SECRET_KEY = aws.secretsmanager
+  .getSecretVersionOutput({
+    secretId: config.require(`image_proxy_secret_key`),
+  })
+  .apply((secret) => secret.secretString);
+
 const internalAuthKeyArn: pulumi.Output<string> = aws.secretsmanager
   .getSecretVersionOutput({ secretId: config.require(`internal_auth_key`) })
   .apply((secret) => secret.arn);
@@ -369,6 +375,14 @@ const emailService = new EmailService('email-service', {
       name: 'CLOUDFRONT_SIGNER_PRIVATE_KEY',
       value: pulumi.interpolate`${CLOUDFRONT_PRIVATE_KEY}`,
     },
+    {
+      name: 'IMAGE_PROXY_URL',
+      value: `https://unfurl-service${stack === 'prod' ? '' : `-${stack}`}.macro.com/proxy/image`,
+    },
+    {
+      name: 'IMAGE_PROXY_SECRET_KEY',
+      value: pulumi.interpolate`${IMAGE_PROXY_SECRET_KEY}`,
+    },
     {
       name: 'CONTACTS_QUEUE',
       value: pulumi.interpolate`${contactsQueueName}`,
@@ -88,6 +88,18 @@ export class EmailService extends pulumi.ComponentResource {
               Resource: [...secretKeyArns],
               Effect: 'Allow',
             },
+            {
+              // passwords of IMAP links, created when a mailbox is linked
+              Action: [
+                'secretsmanager:CreateSecret',
+                'secretsmanager:GetSecretValue',
+                'secretsmanager:DeleteSecret',
+              ],
+              Resource: [
+                `arn:aws:secretsmanager:*:*:secret:email-imap-${stack}/*`,
+              ],
+              Effect: 'Allow',
+            },
           ],
         },
         tags: this.tags,
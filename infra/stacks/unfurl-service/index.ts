@@ -1,5 +1,7 @@
+import * as aws from '@pulumi/aws';
 import * as pulumi from '@pulumi/pulumi';
-import { stack } from '@shared';
+import { Redis } from '@resources';
+import { config, stack } from '@shared';
 import { get_coparse_api_vpc } from '@vpc';
 import { UnfurlService } from './unfurl-service';
 
@@ -11,6 +13,28 @@ const tags = {
 
 export const coparse_api_vpc = get_coparse_api_vpc();
 
+const unfurlServiceRedis = new Redis('unfurl-service-redis', {
+  vpc: coparse_api_vpc,
+  tags,
+  redisArgs: {
+    nodeType: stack === 'prod' ? 'cache.t4g.medium' : 'cache.t3.micro',
+    port: 6379,
+    engineVersion: '7.1',
+  },
+});
+
+const IMAGE_PROXY_SECRET_KEY = aws.secretsmanager
+  .getSecretVersionOutput({
+    secretId: config.require(`image_proxy_secret_key`),
+  })
+  .apply((secret) => secret.secretString);
+
+const INTERNAL_AUTH_KEY = aws.secretsmanager
+  .getSecretVersionOutput({
+    secretId: config.require(`internal_auth_key`),
+  })
+  .apply((secret) => secret.secretString);
+
 const cloudStorageStack = new pulumi.StackReference('cloud-storage-stack', {
   name: `macro-inc/document-storage/${stack}`,
 });
@@ -44,6 +68,36 @@ const unfurlService = new UnfurlService(`unfurl-service-${stack}`, {
         stack === 'prod' ? 'debug' : 'trace'
       },tower_http=debug`,
     },
+    {
+      name: 'IMAGE_PROXY_SECRET_KEY',
+      value: pulumi.interpolate`${IMAGE_PROXY_SECRET_KEY}`,
+    },
+    {
+      name: 'REDIS_URI',
+      value: pulumi.interpolate`redis://${unfurlServiceRedis.endpoint}`,
+    },
+    {
+      name: 'INTERNAL_API_SECRET_KEY',
+      value: INTERNAL_AUTH_KEY,
+    },
+    {
+      name: 'DOCUMENT_STORAGE_SERVICE_URL',
+      value: `https://cloud-storage${
+        stack === 'prod' ? '' : `-${stack}`
+      }.macro.com`,
+    },
+    {
+      name: 'COMMS_SERVICE_URL',
+      value: `https://comms-service${
+        stack === 'prod' ? '' : `-${stack}`
+      }.macro.com`,
+    },
+    {
+      name: 'EMAIL_SERVICE_URL',
+      value: `https://email-service${
+        stack === 'prod' ? '' : `-${stack}`
+      }.macro.com`,
+    },
   ],
   isPrivate: false,
   tags,
@@ -55,6 +55,8 @@ export async function createMarkdownFile(
     documentName: args?.title ?? '',
     fileType: 'md',
     sha: fakeSha,
+    // the content lives in the sync service, nothing is uploaded
+    sizeBytes: 0,
     projectId: args?.projectId,
   });
 
@@ -102,6 +104,7 @@ export async function createCodeFileFromText({
     documentName: title ?? 'New Code File',
     fileType: extension,
     sha: sha,
+    sizeBytes: buffer.byteLength,
   });
 
   invalidateUserQuota();
@@ -155,6 +158,7 @@ export async function createCodeFileFromTextWithLanguage({
     documentName: title ?? 'New Code File',
     fileType: extension,
     sha: sha,
+    sizeBytes: buffer.byteLength,
   });
 
   invalidateUserQuota();
@@ -190,6 +194,7 @@ export async function createCanvasFileFromJsonString(args: {
     documentName: title ?? 'New Canvas',
     fileType: 'canvas',
     sha: sha,
+    sizeBytes: buffer.byteLength,
     projectId,
   });
   invalidateUserQuota();
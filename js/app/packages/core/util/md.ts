@@ -35,6 +35,7 @@ export async function createFromMarkdownText(args: {
     documentName: title ?? 'New Notebook',
     fileType: 'md',
     sha: sha,
+    sizeBytes: buffer.byteLength,
   });
 
   if (isErr(maybeMd)) return { error: 'Document creation failed.' };
@@ -462,7 +462,7 @@ export const storageServiceClient = {
     // INFO: Typescript trips up on resolving storageServiceClient.createDocument, not sure why
     const maybeDoc = await dssFetch<CreateDocumentResponse>(`/documents`, {
       method: 'POST',
-      body: JSON.stringify({ sha, ...docArgs }),
+      body: JSON.stringify({ sha, sizeBytes: buffer.byteLength, ...docArgs }),
     });
     if (isErr(maybeDoc)) {
       const err = maybeDoc[0];
@@ -41,4 +41,7 @@ Will need to have a corresponding job initiated for the file beforehand. */
   projectId?: CreateDocumentRequestProjectId;
   /** The sha of the document. */
   sha: string;
+  /** The size of the document in bytes.
+The upload url only accepts a document of exactly this size. */
+  sizeBytes: number;
 }
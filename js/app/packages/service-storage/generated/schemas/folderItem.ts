@@ -5,6 +5,7 @@
  * OpenAPI spec version: 0.1.0
  */
 import type { FolderItemFileType } from './folderItemFileType';
+import type { FolderItemSizeBytes } from './folderItemSizeBytes';
 
 export interface FolderItem {
   fileType?: FolderItemFileType;
@@ -18,4 +19,8 @@ This is the `webkitRelativePath` with the name of the file stripped at the end.
   relativePath: string;
   /** The sha of the file. */
   sha: string;
+  /** The size of the file in bytes.
+Required when the file is uploaded through a presigned url, which only accepts a file of
+exactly this size. */
+  sizeBytes?: FolderItemSizeBytes;
 }
@@ -0,0 +1,13 @@
+/**
+ * Generated by orval v7.13.0 🍺
+ * Do not edit manually.
+ * document_storage_service
+ * OpenAPI spec version: 0.1.0
+ */
+
+/**
+ * The size of the file in bytes.
+Required when the file is uploaded through a presigned url, which only accepts a file of
+exactly this size.
+ */
+export type FolderItemSizeBytes = number | null;
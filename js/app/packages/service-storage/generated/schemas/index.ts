@@ -214,6 +214,7 @@ export * from './fileSystemNodeWithIdsOneOfType';
 export * from './fileType';
 export * from './folderItem';
 export * from './folderItemFileType';
+export * from './folderItemSizeBytes';
 export * from './genericErrorResponse';
 export * from './genericResponse';
 export * from './genericResponseMessage';
@@ -348,6 +349,7 @@ export * from './saveBomPart';
 export * from './saveDocumentRequest';
 export * from './saveDocumentRequestNewBom';
 export * from './saveDocumentRequestSha';
+export * from './saveDocumentRequestSizeBytes';
 export * from './saveDocumentResponse';
 export * from './saveDocumentResponseData';
 export * from './saveDocumentResponseDataPresignedUrl';
@@ -6,6 +6,7 @@
  */
 import type { SaveDocumentRequestNewBom } from './saveDocumentRequestNewBom';
 import type { SaveDocumentRequestSha } from './saveDocumentRequestSha';
+import type { SaveDocumentRequestSizeBytes } from './saveDocumentRequestSizeBytes';
 
 export interface SaveDocumentRequest {
   /** The modification data for the document instance.
@@ -18,4 +19,8 @@ Containing the file path and the sha. */
   /** The sha of the new document.
 This is used to generate a presigned url to upload the new document content to s3. */
   sha?: SaveDocumentRequestSha;
+  /** The size of the new document in bytes.
+Required along with the sha when the new document content is uploaded to s3, the upload
+url only accepts content of exactly this size. */
+  sizeBytes?: SaveDocumentRequestSizeBytes;
 }
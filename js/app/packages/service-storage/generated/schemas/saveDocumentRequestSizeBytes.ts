@@ -0,0 +1,13 @@
+/**
+ * Generated by orval v7.13.0 🍺
+ * Do not edit manually.
+ * document_storage_service
+ * OpenAPI spec version: 0.1.0
+ */
+
+/**
+ * The size of the new document in bytes.
+Required along with the sha when the new document content is uploaded to s3, the upload
+url only accepts content of exactly this size.
+ */
+export type SaveDocumentRequestSizeBytes = number | null;
@@ -11,4 +11,6 @@ export interface UploadExtractFolderRequest {
   name?: UploadExtractFolderRequestName;
   parentId?: UploadExtractFolderRequestParentId;
   sha: string;
+  /** The size of the zip in bytes, the upload url only accepts a zip of exactly this size */
+  sizeBytes: number;
 }
@@ -579,7 +579,8 @@ export const createDocumentHandlerBody = zod.object({
   "jobId": zod.string().nullish().describe('Optional job id to be used to track an upload job for the newly created document.\nWill need to have a corresponding job initiated for the file beforehand.'),
   "mimeType": zod.string().nullish().describe('The content type of the document (currently only used for logging matches against file type).'),
   "projectId": zod.string().nullish(),
-  "sha": zod.string().describe('The sha of the document.')
+  "sha": zod.string().describe('The sha of the document.'),
+  "sizeBytes": zod.number().describe('The size of the document in bytes.\nThe upload url only accepts a document of exactly this size.')
 })
 
 export const createDocumentHandlerResponse = zod.object({
@@ -769,7 +770,8 @@ export const saveDocumentHandlerBody = zod.object({
   "path": zod.string().describe('The file path of the bom part content'),
   "sha": zod.string().describe('The sha of the bom part content\nThere is an index on sha for more performant queries based on it.')
 })).nullish().describe('**DOCX ONLY**\nThe updated BOM for the document.\nContaining the file path and the sha.'),
-  "sha": zod.string().nullish().describe('The sha of the new document.\nThis is used to generate a presigned url to upload the new document content to s3.')
+  "sha": zod.string().nullish().describe('The sha of the new document.\nThis is used to generate a presigned url to upload the new document content to s3.'),
+  "sizeBytes": zod.number().nullish().describe('The size of the new document in bytes.\nRequired along with the sha when the new document content is uploaded to s3, the upload\nurl only accepts content of exactly this size.')
 })
 
 export const saveDocumentHandlerResponse = zod.object({
@@ -1649,7 +1651,8 @@ export const uploadFolderHandlerBody = zod.object({
   "fullName": zod.string().describe('The full OS name of the file for deduplication'),
   "name": zod.string().describe('The name of the file, without the extension'),
   "relativePath": zod.string().describe('The relative path of the file.\n\nThis is the `webkitRelativePath` with the name of the file stripped at the end.'),
-  "sha": zod.string().describe('The sha of the file.')
+  "sha": zod.string().describe('The sha of the file.'),
+  "sizeBytes": zod.number().nullish().describe('The size of the file in bytes.\nRequired when the file is uploaded through a presigned url, which only accepts a file of\nexactly this size.')
 })).describe('The content of the folder'),
   "parentId": zod.string().nullish().describe('Optional parent project id to upload the folder into'),
   "rootFolderName": zod.string().describe('The name of the folder you are uploading.\n\nThis is used to help us generate the folder map more easily.'),
@@ -1676,7 +1679,8 @@ export const uploadFolderHandlerResponse = zod.object({
   "fullName": zod.string().describe('The full OS name of the file for deduplication'),
   "name": zod.string().describe('The name of the file, without the extension'),
   "relativePath": zod.string().describe('The relative path of the file.\n\nThis is the `webkitRelativePath` with the name of the file stripped at the end.'),
-  "sha": zod.string().describe('The sha of the file.')
+  "sha": zod.string().describe('The sha of the file.'),
+  "sizeBytes": zod.number().nullish().describe('The size of the file in bytes.\nRequired when the file is uploaded through a presigned url, which only accepts a file of\nexactly this size.')
 }),
   "type": zod.enum(['file'])
 }),zod.object({
@@ -1697,7 +1701,8 @@ Returns a request id for tracking the upload
 export const uploadExtractFolderHandlerBody = zod.object({
   "name": zod.string().nullish(),
   "parentId": zod.string().nullish(),
-  "sha": zod.string()
+  "sha": zod.string(),
+  "sizeBytes": zod.number().describe('The size of the zip in bytes, the upload url only accepts a zip of exactly this size')
 })
 
 export const uploadExtractFolderHandlerResponse = zod.object({
@@ -127,6 +127,7 @@ export async function upload(
   if (ENABLE_FOLDER_UPLOAD && isZip && options?.unzipFolder) {
     const res = await storageServiceClient.projects.createUploadZipRequest({
       sha,
+      sizeBytes: buffer.byteLength,
       name,
       parentId: options?.projectId,
     });
@@ -189,6 +190,7 @@ export async function upload(
   // Create document
   const newfile = await storageServiceClient.createDocument({
     sha,
+    sizeBytes: buffer.byteLength,
     documentName: file.name,
     jobId,
     projectId: options?.projectId,
@@ -0,0 +1,34 @@
+-- Fixture for testing read markers and unread counts
+-- user1 and user2 are in the first channel, user1 alone in the second
+
+INSERT INTO comms_channels (id, name, channel_type, owner_id, created_at)
+VALUES ('aaaaaaaa-0000-0000-0000-000000000001', 'test channel', 'public', 'user1', '2024-01-01T09:00:00Z'),
+       ('aaaaaaaa-0000-0000-0000-000000000002', 'other channel', 'public', 'user1', '2024-01-01T09:00:00Z');
+
+INSERT INTO comms_channel_participants (channel_id, role, user_id, joined_at)
+VALUES ('aaaaaaaa-0000-0000-0000-000000000001', 'owner', 'user1', '2024-01-01T09:00:00Z'),
+       ('aaaaaaaa-0000-0000-0000-000000000001', 'member', 'user2', '2024-01-01T09:00:00Z'),
+       ('aaaaaaaa-0000-0000-0000-000000000002', 'owner', 'user1', '2024-01-01T09:00:00Z');
+
+INSERT INTO comms_messages (id, channel_id, thread_id, sender_id, content, created_at, deleted_at)
+VALUES ('bbbbbbbb-0000-0000-0000-000000000001', 'aaaaaaaa-0000-0000-0000-000000000001', NULL, 'user2',
+        'Message 1', '2024-01-01T10:00:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000002', 'aaaaaaaa-0000-0000-0000-000000000001', NULL, 'user2',
+        'Message 2', '2024-01-01T10:01:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000003', 'aaaaaaaa-0000-0000-0000-000000000001', NULL, 'user1',
+        'Message 3', '2024-01-01T10:02:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000004', 'aaaaaaaa-0000-0000-0000-000000000001', NULL, 'user2',
+        'Message 4', '2024-01-01T10:03:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000005', 'aaaaaaaa-0000-0000-0000-000000000001', NULL, 'user2',
+        'Message 5', '2024-01-01T10:04:00Z', '2024-01-01T10:05:00Z'),
+       -- replies to message 1
+       ('bbbbbbbb-0000-0000-0000-000000000011', 'aaaaaaaa-0000-0000-0000-000000000001',
+        'bbbbbbbb-0000-0000-0000-000000000001', 'user2', 'Reply 1', '2024-01-01T10:05:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000012', 'aaaaaaaa-0000-0000-0000-000000000001',
+        'bbbbbbbb-0000-0000-0000-000000000001', 'user2', 'Reply 2', '2024-01-01T10:06:00Z', NULL),
+       ('bbbbbbbb-0000-0000-0000-000000000021', 'aaaaaaaa-0000-0000-0000-000000000002', NULL, 'user1',
+        'Other message', '2024-01-01T10:00:00Z', NULL);
+
+INSERT INTO comms_entity_mentions (source_entity_type, source_entity_id, entity_type, entity_id)
+VALUES ('message', 'bbbbbbbb-0000-0000-0000-000000000002', 'user', 'user1'),
+       ('message', 'bbbbbbbb-0000-0000-0000-000000000012', 'user', 'user1');
@@ -0,0 +1,2 @@
+pub mod outgoing_webhooks;
+pub mod reminders;
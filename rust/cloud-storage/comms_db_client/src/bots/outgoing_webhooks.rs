@@ -0,0 +1,253 @@
+use crate::model::{BotEventType, OutgoingWebhook};
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct CreateOutgoingWebhookOptions<'a> {
+    pub channel_id: Uuid,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub events: &'a [BotEventType],
+    pub created_by: &'a str,
+}
+
+/// Where and how to deliver an event to an outgoing webhook
+#[derive(Debug, Clone)]
+pub struct DeliveryTarget {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+}
+
+fn event_names(events: &[BotEventType]) -> Vec<String> {
+    events.iter().map(|e| e.as_str().to_string()).collect()
+}
+
+fn parse_events(events: Vec<String>) -> Vec<BotEventType> {
+    events
+        .iter()
+        .filter_map(|e| BotEventType::parse(e))
+        .collect()
+}
+
+/// Registers an outgoing webhook for a channel
+#[tracing::instrument(skip(db, options), fields(channel_id = %options.channel_id), err)]
+pub async fn create_outgoing_webhook(
+    db: &Pool<Postgres>,
+    options: CreateOutgoingWebhookOptions<'_>,
+) -> Result<OutgoingWebhook> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO comms_outgoing_webhooks (id, channel_id, url, secret, events, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING
+            id,
+            channel_id,
+            url,
+            events,
+            created_by,
+            created_at,
+            last_delivered_at,
+            last_failed_at,
+            last_error
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        options.channel_id,
+        options.url,
+        options.secret,
+        &event_names(options.events),
+        options.created_by
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to create outgoing webhook")?;
+
+    Ok(OutgoingWebhook {
+        id: row.id,
+        channel_id: row.channel_id,
+        url: row.url,
+        events: parse_events(row.events),
+        created_by: row.created_by,
+        created_at: row.created_at,
+        last_delivered_at: row.last_delivered_at,
+        last_failed_at: row.last_failed_at,
+        last_error: row.last_error,
+    })
+}
+
+/// Gets the outgoing webhooks of a channel that haven't been revoked, oldest first
+#[tracing::instrument(skip(db), err)]
+pub async fn get_channel_outgoing_webhooks(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+) -> Result<Vec<OutgoingWebhook>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            channel_id,
+            url,
+            events,
+            created_by,
+            created_at,
+            last_delivered_at,
+            last_failed_at,
+            last_error
+        FROM comms_outgoing_webhooks
+        WHERE channel_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at ASC
+        "#,
+        channel_id
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get outgoing webhooks")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| OutgoingWebhook {
+            id: row.id,
+            channel_id: row.channel_id,
+            url: row.url,
+            events: parse_events(row.events),
+            created_by: row.created_by,
+            created_at: row.created_at,
+            last_delivered_at: row.last_delivered_at,
+            last_failed_at: row.last_failed_at,
+            last_error: row.last_error,
+        })
+        .collect())
+}
+
+/// Gets where to deliver an event of a channel
+#[tracing::instrument(skip(db), err)]
+pub async fn get_delivery_targets(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    event: BotEventType,
+) -> Result<Vec<DeliveryTarget>> {
+    sqlx::query_as!(
+        DeliveryTarget,
+        r#"
+        SELECT id, url, secret
+        FROM comms_outgoing_webhooks
+        WHERE channel_id = $1 AND revoked_at IS NULL AND $2 = ANY(events)
+        "#,
+        channel_id,
+        event.as_str()
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get delivery targets")
+}
+
+/// Revokes an outgoing webhook of a channel, no more events are delivered to it.
+/// Returns false if the webhook isn't in the channel or was already revoked.
+#[tracing::instrument(skip(db), err)]
+pub async fn revoke_outgoing_webhook(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    webhook_id: &Uuid,
+    revoked_by: &str,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE comms_outgoing_webhooks
+        SET revoked_at = NOW(), revoked_by = $3
+        WHERE id = $1 AND channel_id = $2 AND revoked_at IS NULL
+        "#,
+        webhook_id,
+        channel_id,
+        revoked_by
+    )
+    .execute(db)
+    .await
+    .context("unable to revoke outgoing webhook")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records the outcome of delivering an event to an outgoing webhook, after any retries
+#[tracing::instrument(skip(db), err)]
+pub async fn record_delivery(
+    db: &Pool<Postgres>,
+    webhook_id: &Uuid,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE comms_outgoing_webhooks
+        SET
+            last_delivered_at = CASE WHEN $2::text IS NULL THEN NOW() ELSE last_delivered_at END,
+            last_failed_at = CASE WHEN $2::text IS NULL THEN last_failed_at ELSE NOW() END,
+            last_error = COALESCE($2, last_error),
+            consecutive_failures = CASE WHEN $2::text IS NULL THEN 0 ELSE consecutive_failures + 1 END
+        WHERE id = $1
+        "#,
+        webhook_id,
+        error
+    )
+    .execute(db)
+    .await
+    .context("unable to record delivery")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_outgoing_webhooks(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("33333333-3333-3333-3333-333333333333");
+
+        let webhook = create_outgoing_webhook(
+            &pool,
+            CreateOutgoingWebhookOptions {
+                channel_id,
+                url: "https://bot.example.com/events",
+                secret: "secret",
+                events: &[BotEventType::MessagePosted, BotEventType::MemberJoined],
+                created_by: "user5",
+            },
+        )
+        .await?;
+        assert_eq!(
+            webhook.events,
+            vec![BotEventType::MessagePosted, BotEventType::MemberJoined]
+        );
+
+        let targets = get_delivery_targets(&pool, &channel_id, BotEventType::MessagePosted).await?;
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].secret, "secret");
+        assert!(
+            get_delivery_targets(&pool, &channel_id, BotEventType::ReactionAdded)
+                .await?
+                .is_empty()
+        );
+
+        record_delivery(&pool, &webhook.id, Some("500 Internal Server Error")).await?;
+        record_delivery(&pool, &webhook.id, None).await?;
+        let webhooks = get_channel_outgoing_webhooks(&pool, &channel_id).await?;
+        assert!(webhooks[0].last_delivered_at.is_some());
+        assert!(webhooks[0].last_failed_at.is_some());
+
+        assert!(revoke_outgoing_webhook(&pool, &channel_id, &webhook.id, "user5").await?);
+        assert!(!revoke_outgoing_webhook(&pool, &channel_id, &webhook.id, "user5").await?);
+        assert!(
+            get_delivery_targets(&pool, &channel_id, BotEventType::MessagePosted)
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+}
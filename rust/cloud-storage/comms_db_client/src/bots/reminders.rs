@@ -0,0 +1,110 @@
+use crate::model::Reminder;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct CreateReminderOptions<'a> {
+    pub channel_id: Uuid,
+    pub thread_id: Option<Uuid>,
+    pub user_id: &'a str,
+    pub content: &'a str,
+    pub remind_at: DateTime<Utc>,
+}
+
+/// Sets a reminder for a user in a channel
+#[tracing::instrument(skip(db), err)]
+pub async fn create_reminder(
+    db: &Pool<Postgres>,
+    options: CreateReminderOptions<'_>,
+) -> Result<Reminder> {
+    sqlx::query_as!(
+        Reminder,
+        r#"
+        INSERT INTO comms_reminders (id, channel_id, thread_id, user_id, content, remind_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, channel_id, thread_id, user_id, content, remind_at, created_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        options.channel_id,
+        options.thread_id,
+        options.user_id,
+        options.content,
+        options.remind_at
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to create reminder")
+}
+
+/// Claims up to limit reminders that are due, marking them delivered. Concurrent callers claim
+/// different reminders, so each is delivered at most once.
+#[tracing::instrument(skip(db), err)]
+pub async fn take_due_reminders(db: &Pool<Postgres>, limit: i64) -> Result<Vec<Reminder>> {
+    sqlx::query_as!(
+        Reminder,
+        r#"
+        UPDATE comms_reminders
+        SET delivered_at = NOW()
+        WHERE id IN (
+            SELECT id FROM comms_reminders
+            WHERE delivered_at IS NULL AND remind_at <= NOW()
+            ORDER BY remind_at ASC
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, channel_id, thread_id, user_id, content, remind_at, created_at
+        "#,
+        limit
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to take due reminders")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_take_due_reminders(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("33333333-3333-3333-3333-333333333333");
+
+        let due = create_reminder(
+            &pool,
+            CreateReminderOptions {
+                channel_id,
+                thread_id: None,
+                user_id: "user1",
+                content: "stand up",
+                remind_at: Utc::now() - chrono::Duration::minutes(1),
+            },
+        )
+        .await?;
+        create_reminder(
+            &pool,
+            CreateReminderOptions {
+                channel_id,
+                thread_id: None,
+                user_id: "user1",
+                content: "later",
+                remind_at: Utc::now() + chrono::Duration::hours(1),
+            },
+        )
+        .await?;
+
+        let taken = take_due_reminders(&pool, 10).await?;
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].id, due.id);
+        assert!(take_due_reminders(&pool, 10).await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Archives a channel, it becomes read-only and is hidden from the channel list.
+/// Returns false if the channel doesn't exist or is already archived.
+#[tracing::instrument(skip(db), err)]
+pub async fn archive_channel(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    user_id: &str,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE comms_channels
+        SET archived_at = NOW(), archived_by = $2, updated_at = NOW()
+        WHERE id = $1 AND archived_at IS NULL
+        "#,
+        channel_id,
+        user_id
+    )
+    .execute(db)
+    .await
+    .context("unable to archive channel")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Restores an archived channel.
+/// Returns false if the channel doesn't exist or isn't archived.
+#[tracing::instrument(skip(db), err)]
+pub async fn unarchive_channel(db: &Pool<Postgres>, channel_id: &Uuid) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE comms_channels
+        SET archived_at = NULL, archived_by = NULL, updated_at = NOW()
+        WHERE id = $1 AND archived_at IS NOT NULL
+        "#,
+        channel_id
+    )
+    .execute(db)
+    .await
+    .context("unable to unarchive channel")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Whether the channel is archived
+#[tracing::instrument(skip(db), err)]
+pub async fn is_channel_archived(db: &Pool<Postgres>, channel_id: &Uuid) -> Result<bool> {
+    let archived = sqlx::query!(
+        r#"
+        SELECT archived_at IS NOT NULL AS "archived!"
+        FROM comms_channels
+        WHERE id = $1
+        "#,
+        channel_id
+    )
+    .map(|row| row.archived)
+    .fetch_optional(db)
+    .await
+    .context("unable to get channel archive status")?;
+
+    Ok(archived.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_archive_channel(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let channel_id = Uuid::parse_str("33333333-3333-3333-3333-333333333333")?;
+
+        assert!(!is_channel_archived(&pool, &channel_id).await?);
+        assert!(archive_channel(&pool, &channel_id, "user5").await?);
+        assert!(is_channel_archived(&pool, &channel_id).await?);
+        // archiving twice is a no-op
+        assert!(!archive_channel(&pool, &channel_id, "user5").await?);
+
+        assert!(unarchive_channel(&pool, &channel_id).await?);
+        assert!(!is_channel_archived(&pool, &channel_id).await?);
+        assert!(!unarchive_channel(&pool, &channel_id).await?);
+
+        Ok(())
+    }
+}
@@ -15,7 +15,8 @@ pub async fn get_channel(db: &Pool<Postgres>, channel_id: &Uuid) -> Result<Chann
             org_id,
             created_at,
             updated_at,
-            owner_id
+            owner_id,
+            archived_at
         FROM comms_channels
         WHERE id = $1
         "#,
@@ -70,10 +70,12 @@ pub async fn get_user_channel_ids(
     Ok(channels)
 }
 
+/// Gets the channels a user is a member of, archived channels only when asked for
 #[tracing::instrument(skip(db))]
 pub async fn get_user_channels_with_participants(
     db: &Pool<Postgres>,
     user_id: &str,
+    include_archived: bool,
 ) -> Result<Vec<ChannelWithParticipants>, sqlx::Error> {
     let rows = sqlx::query!(
         r#"
@@ -108,12 +110,15 @@ pub async fn get_user_channels_with_participants(
             uc.created_at as "created_at!",
             uc.updated_at as "updated_at!",
             uc.owner_id as "owner_id!",
+            uc.archived_at,
             cpj.participants as "participants_json?"
         FROM user_channels uc
         LEFT JOIN channel_participants_json cpj ON cpj.channel_id = uc.id
+        WHERE $2 OR uc.archived_at IS NULL
         ORDER BY uc.created_at DESC
         "#,
-        user_id
+        user_id,
+        include_archived
     )
     .fetch_all(db)
     .await?;
@@ -129,6 +134,7 @@ pub async fn get_user_channels_with_participants(
                 created_at: row.created_at,
                 updated_at: row.updated_at,
                 owner_id: row.owner_id,
+                archived_at: row.archived_at,
             };
 
             let participants = row
@@ -167,7 +173,8 @@ pub async fn get_org_channels(
             org_id,  -- This can be NULL
             created_at as "created_at!",
             updated_at as "updated_at!",
-            owner_id as "owner_id!"
+            owner_id as "owner_id!",
+            archived_at
         FROM comms_channels
         WHERE channel_type = 'organization'::comms_channel_type
         AND org_id = $1::bigint
@@ -0,0 +1,292 @@
+use crate::activity;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+#[allow(unused_imports)]
+use model::comms::{ChannelType, ParticipantRole};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Messages and reactions are inserted this many at a time
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// A message carried over from another chat service, with the time it was originally sent
+#[derive(Debug, Clone)]
+pub struct ImportedMessage {
+    pub id: Uuid,
+    /// the imported message this one replies to, which has to come before it
+    pub thread_id: Option<Uuid>,
+    pub sender_id: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    /// the emoji and the user of each reaction to the message
+    pub reactions: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+pub struct ImportChannelOptions {
+    pub name: String,
+    pub channel_type: ChannelType,
+    pub owner_id: String,
+    pub org_id: Option<i64>,
+    /// the members of the channel, not including the owner
+    pub participants: Vec<String>,
+    /// the messages of the channel, oldest first
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// Creates a channel holding imported messages. It is one transaction, so a failed import leaves
+/// nothing behind.
+#[tracing::instrument(
+    skip(db, options),
+    fields(name = %options.name, messages = options.messages.len()),
+    err
+)]
+pub async fn import_channel(db: &Pool<Postgres>, options: ImportChannelOptions) -> Result<Uuid> {
+    let channel_id = macro_uuid::generate_uuid_v7();
+    let mut transaction = db.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO comms_channels (id, name, owner_id, org_id, channel_type)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        channel_id,
+        options.name,
+        options.owner_id,
+        options.org_id,
+        options.channel_type as ChannelType
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("unable to create channel")?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO comms_channel_participants (channel_id, role, user_id)
+        VALUES ($1, $2, $3)
+        "#,
+        channel_id,
+        ParticipantRole::Owner as ParticipantRole,
+        options.owner_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("unable to create channel participant for owner")?;
+
+    let participants: Vec<String> = options
+        .participants
+        .into_iter()
+        .filter(|p| p != &options.owner_id)
+        .collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO comms_channel_participants (channel_id, role, user_id)
+        SELECT $1, $2, user_id
+        FROM UNNEST($3::text[]) AS user_id
+        ON CONFLICT DO NOTHING
+        "#,
+        channel_id,
+        ParticipantRole::Member as ParticipantRole,
+        &participants
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("unable to create channel participants")?;
+
+    for batch in options.messages.chunks(INSERT_BATCH_SIZE) {
+        let ids: Vec<Uuid> = batch.iter().map(|m| m.id).collect();
+        let thread_ids: Vec<Option<Uuid>> = batch.iter().map(|m| m.thread_id).collect();
+        let sender_ids: Vec<String> = batch.iter().map(|m| m.sender_id.clone()).collect();
+        let contents: Vec<String> = batch.iter().map(|m| m.content.clone()).collect();
+        let created_ats: Vec<DateTime<Utc>> = batch.iter().map(|m| m.created_at).collect();
+        // edited_at is stored without a time zone, in UTC
+        let edited_ats: Vec<Option<NaiveDateTime>> = batch
+            .iter()
+            .map(|m| m.edited_at.map(|t| t.naive_utc()))
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO comms_messages
+                (id, channel_id, thread_id, sender_id, content, created_at, updated_at, edited_at)
+            SELECT m.id, $1, m.thread_id, m.sender_id, m.content, m.created_at, m.created_at, m.edited_at
+            FROM UNNEST($2::uuid[], $3::uuid[], $4::text[], $5::text[], $6::timestamptz[], $7::timestamp[])
+                AS m(id, thread_id, sender_id, content, created_at, edited_at)
+            "#,
+            channel_id,
+            &ids,
+            &thread_ids as &[Option<Uuid>],
+            &sender_ids,
+            &contents,
+            &created_ats,
+            &edited_ats as &[Option<NaiveDateTime>]
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("unable to import messages")?;
+    }
+
+    let reactions: Vec<(Uuid, &str, &str)> = options
+        .messages
+        .iter()
+        .flat_map(|m| {
+            m.reactions
+                .iter()
+                .map(move |(emoji, user_id)| (m.id, emoji.as_str(), user_id.as_str()))
+        })
+        .collect();
+    for batch in reactions.chunks(INSERT_BATCH_SIZE) {
+        let message_ids: Vec<Uuid> = batch.iter().map(|r| r.0).collect();
+        let emojis: Vec<String> = batch.iter().map(|r| r.1.to_string()).collect();
+        let user_ids: Vec<String> = batch.iter().map(|r| r.2.to_string()).collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO comms_reactions (message_id, emoji, user_id)
+            SELECT * FROM UNNEST($1::uuid[], $2::varchar[], $3::text[])
+            ON CONFLICT DO NOTHING
+            "#,
+            &message_ids,
+            &emojis,
+            &user_ids
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("unable to import reactions")?;
+    }
+
+    activity::create_activity::create_activity(&mut *transaction, &channel_id, &options.owner_id)
+        .await
+        .context("unable to create activity for channel")?;
+
+    transaction
+        .commit()
+        .await
+        .context("unable to commit transaction")?;
+
+    Ok(channel_id)
+}
+
+/// The given user ids that belong to existing users of the organization, so imported content is
+/// only attributed to people the importer shares an organization with
+#[tracing::instrument(skip(db, user_ids), fields(user_count = user_ids.len()), err)]
+pub async fn get_org_user_ids(
+    db: &Pool<Postgres>,
+    org_id: i32,
+    user_ids: &[String],
+) -> Result<Vec<String>> {
+    if user_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_scalar!(
+        r#"
+        SELECT id
+        FROM "User"
+        WHERE "organizationId" = $1 AND id = ANY($2)
+        "#,
+        org_id,
+        user_ids
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get organization users")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::get_messages::get_messages;
+    use crate::participants::get_participants::get_participants;
+    use crate::reactions::get_reactions::get_message_reactions;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+
+    #[sqlx::test(migrator = "MACRO_DB_MIGRATIONS")]
+    async fn test_import_channel(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let sent_at = "2024-03-01T09:00:00Z".parse::<DateTime<Utc>>()?;
+        let parent_id = Uuid::now_v7();
+        let reply_id = Uuid::now_v7();
+
+        let channel_id = import_channel(
+            &pool,
+            ImportChannelOptions {
+                name: "imported".to_string(),
+                channel_type: ChannelType::Private,
+                owner_id: "macro|owner@macro.com".to_string(),
+                org_id: None,
+                participants: vec![
+                    "macro|owner@macro.com".to_string(),
+                    "macro|ada@macro.com".to_string(),
+                ],
+                messages: vec![
+                    ImportedMessage {
+                        id: parent_id,
+                        thread_id: None,
+                        sender_id: "macro|ada@macro.com".to_string(),
+                        content: "hello".to_string(),
+                        created_at: sent_at,
+                        edited_at: Some(sent_at + chrono::Duration::minutes(1)),
+                        reactions: vec![("👍".to_string(), "macro|owner@macro.com".to_string())],
+                    },
+                    ImportedMessage {
+                        id: reply_id,
+                        thread_id: Some(parent_id),
+                        sender_id: "macro|owner@macro.com".to_string(),
+                        content: "hi".to_string(),
+                        created_at: sent_at + chrono::Duration::minutes(5),
+                        edited_at: None,
+                        reactions: vec![],
+                    },
+                ],
+            },
+        )
+        .await?;
+
+        let participants = get_participants(&pool, &channel_id).await?;
+        assert_eq!(participants.len(), 2);
+
+        let messages = get_messages(&pool, &channel_id, None, None).await?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, parent_id);
+        assert_eq!(messages[0].created_at, sent_at);
+        assert!(messages[0].edited_at.is_some());
+        assert_eq!(messages[1].thread_id, Some(parent_id));
+
+        let reactions = get_message_reactions(&pool, parent_id).await?;
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].emoji, "👍");
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrator = "MACRO_DB_MIGRATIONS")]
+    async fn test_get_org_user_ids(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        sqlx::query(r#"INSERT INTO "Organization" (id, name) VALUES (1, 'macro'), (2, 'other')"#)
+            .execute(&pool)
+            .await?;
+        sqlx::query(
+            r#"INSERT INTO "User" (id, email, "organizationId") VALUES
+            ('macro|ada@macro.com', 'ada@macro.com', 1),
+            ('macro|bob@other.com', 'bob@other.com', 2),
+            ('macro|cy@macro.com', 'cy@macro.com', NULL)"#,
+        )
+        .execute(&pool)
+        .await?;
+
+        let user_ids = [
+            "macro|ada@macro.com",
+            "macro|bob@other.com",
+            "macro|cy@macro.com",
+            "macro|nobody@macro.com",
+        ]
+        .map(String::from);
+        assert_eq!(
+            get_org_user_ids(&pool, 1, &user_ids).await?,
+            vec!["macro|ada@macro.com"]
+        );
+        assert!(get_org_user_ids(&pool, 1, &[]).await?.is_empty());
+
+        Ok(())
+    }
+}
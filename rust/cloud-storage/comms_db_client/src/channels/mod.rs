@@ -1,3 +1,4 @@
+pub mod archive_channel;
 pub mod create_channel;
 pub mod delete_channel;
 pub mod get_channel;
@@ -6,5 +7,6 @@ pub mod get_channels;
 pub mod get_dm;
 pub mod get_mentions_for_channel;
 pub mod get_private;
+pub mod import_channel;
 pub mod patch_channel;
 pub mod updated_at;
@@ -1,10 +1,14 @@
 pub mod activity;
 pub mod attachments;
+pub mod bots;
 pub mod channels;
 pub mod entity_mentions;
 pub mod mentions;
 pub mod messages;
 pub mod model;
+pub mod moderation;
 pub mod participants;
 pub mod preview;
 pub mod reactions;
+pub mod read_markers;
+pub mod webhooks;
@@ -3,29 +3,53 @@ use anyhow::{Context, Result};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
+/// Deletes a message of a channel, leaving a tombstone that records who deleted it. The content,
+/// edit history and pin of the message are removed.
+/// Returns the tombstone, or None if the message isn't in the channel.
 #[tracing::instrument(skip(db))]
-pub async fn delete_message(db: &Pool<Postgres>, message_id: Uuid) -> Result<Message> {
+pub async fn delete_message(
+    db: &Pool<Postgres>,
+    channel_id: Uuid,
+    message_id: Uuid,
+    deleted_by: &str,
+) -> Result<Option<Message>> {
     let message = sqlx::query_as!(
         Message,
         r#"
-        UPDATE comms_messages
-        SET content = $1, updated_at = NOW(), deleted_at = NOW()
-        WHERE id = $2
-        RETURNING 
-            id, 
-            channel_id,
-            sender_id,
-            content, 
-            created_at,
-            updated_at,
-            thread_id,
-            edited_at as "edited_at: chrono::DateTime<chrono::Utc>",
-            deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>"
+        WITH prior AS (
+            SELECT id FROM comms_messages
+            WHERE id = $1 AND channel_id = $2
+            FOR UPDATE
+        ),
+        history AS (
+            DELETE FROM comms_message_edits WHERE message_id IN (SELECT id FROM prior)
+        )
+        UPDATE comms_messages m
+        SET
+            content = '',
+            updated_at = NOW(),
+            deleted_at = NOW(),
+            deleted_by = $3,
+            pinned_at = NULL,
+            pinned_by = NULL
+        FROM prior
+        WHERE m.id = prior.id
+        RETURNING
+            m.id,
+            m.channel_id,
+            m.sender_id,
+            m.content,
+            m.created_at,
+            m.updated_at,
+            m.thread_id,
+            m.edited_at as "edited_at: chrono::DateTime<chrono::Utc>",
+            m.deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>"
         "#,
-        "".to_string(),
-        message_id
+        message_id,
+        channel_id,
+        deleted_by
     )
-    .fetch_one(db)
+    .fetch_optional(db)
     .await
     .context("unable to delete message")?;
 
@@ -0,0 +1,99 @@
+use crate::model::MessageEdit;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Gets the prior versions of a message, oldest first.
+/// Returns None if the message isn't in the channel.
+#[tracing::instrument(skip(db))]
+pub async fn get_message_edits(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    message_id: &Uuid,
+) -> Result<Option<Vec<MessageEdit>>> {
+    let exists = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM comms_messages WHERE id = $1 AND channel_id = $2
+        ) AS "exists!"
+        "#,
+        message_id,
+        channel_id
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to get message")?;
+
+    if !exists {
+        return Ok(None);
+    }
+
+    let edits = sqlx::query_as!(
+        MessageEdit,
+        r#"
+        SELECT id, message_id, content, written_at, replaced_at
+        FROM comms_message_edits
+        WHERE message_id = $1
+        ORDER BY replaced_at ASC
+        "#,
+        message_id
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get message edits")?;
+
+    Ok(Some(edits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{delete_message::delete_message, patch_message::patch_message};
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("messages_with_context"))
+    )]
+    async fn test_message_edit_history(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+        let message_id = uuid!("bbbbbbbb-0000-0000-0000-000000000001");
+
+        patch_message(&pool, message_id, "Edit 1").await?;
+        // an edit that changes nothing isn't a version
+        patch_message(&pool, message_id, "Edit 1").await?;
+        patch_message(&pool, message_id, "Edit 2").await?;
+
+        let edits = get_message_edits(&pool, &channel_id, &message_id)
+            .await?
+            .expect("message");
+        let contents: Vec<_> = edits.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["Message 1", "Edit 1"]);
+
+        let other_channel = uuid!("aaaaaaaa-0000-0000-0000-000000000002");
+        assert!(
+            get_message_edits(&pool, &other_channel, &message_id)
+                .await?
+                .is_none()
+        );
+
+        assert!(
+            delete_message(&pool, other_channel, message_id, "user1")
+                .await?
+                .is_none()
+        );
+        let deleted = delete_message(&pool, channel_id, message_id, "user1")
+            .await?
+            .expect("message");
+        assert!(deleted.deleted_at.is_some());
+        assert_eq!(deleted.content, "");
+        let edits = get_message_edits(&pool, &channel_id, &message_id)
+            .await?
+            .expect("message");
+        assert!(edits.is_empty());
+
+        Ok(())
+    }
+}
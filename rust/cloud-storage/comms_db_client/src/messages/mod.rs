@@ -5,6 +5,7 @@ pub mod delete_message;
 pub mod get_channel_message;
 pub mod get_count;
 mod get_latest_message;
+pub mod get_message_edits;
 pub mod get_message_owner;
 pub mod get_messages;
 pub mod patch_message;
@@ -4,15 +4,23 @@ use anyhow::{Context, Result};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
+/// Updates the content of a message, keeping the prior version in its edit history
 #[tracing::instrument(skip(db))]
 pub async fn patch_message(
     db: &Pool<Postgres>,
     message_id: Uuid,
     content: &str,
 ) -> Result<Message> {
+    // both statements see the message before the update
     let message = sqlx::query_as!(
         Message,
         r#"
+        WITH prior AS (
+            INSERT INTO comms_message_edits (id, message_id, content, written_at)
+            SELECT $3, id, content, COALESCE(edited_at AT TIME ZONE 'UTC', created_at)
+            FROM comms_messages
+            WHERE id = $2 AND content <> $1 AND deleted_at IS NULL
+        )
         UPDATE comms_messages
         SET content = $1, updated_at = NOW(), edited_at = NOW()
         WHERE id = $2
@@ -28,7 +36,8 @@ pub async fn patch_message(
         deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>"
         "#,
         content,
-        message_id
+        message_id,
+        macro_uuid::generate_uuid_v7()
     )
     .fetch_one(db)
     .await
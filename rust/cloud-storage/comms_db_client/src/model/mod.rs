@@ -108,6 +108,136 @@ pub struct Activity {
     pub interacted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// A prior version of an edited message
+pub struct MessageEdit {
+    pub id: Uuid,
+    pub message_id: Uuid,
+    pub content: String,
+    /// when the version was written
+    pub written_at: chrono::DateTime<chrono::Utc>,
+    /// when an edit replaced the version
+    pub replaced_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// A message a channel admin pinned to the channel
+pub struct PinnedMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    pub pinned_at: chrono::DateTime<chrono::Utc>,
+    /// the admin who pinned the message
+    pub pinned_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// A webhook external services use to post to a channel as a bot
+pub struct IncomingWebhook {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    /// the sender id of the webhook's messages
+    pub bot_id: String,
+    /// the name the bot posts under
+    pub name: String,
+    pub avatar_url: Option<String>,
+    /// the admin who created the webhook
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+/// The channel events an outgoing webhook can subscribe to
+pub enum BotEventType {
+    MessagePosted,
+    ReactionAdded,
+    MemberJoined,
+}
+
+impl BotEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotEventType::MessagePosted => "message_posted",
+            BotEventType::ReactionAdded => "reaction_added",
+            BotEventType::MemberJoined => "member_joined",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "message_posted" => Some(BotEventType::MessagePosted),
+            "reaction_added" => Some(BotEventType::ReactionAdded),
+            "member_joined" => Some(BotEventType::MemberJoined),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// An endpoint channel events are delivered to
+pub struct OutgoingWebhook {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    pub url: String,
+    pub events: Vec<BotEventType>,
+    /// the admin who registered the endpoint
+    pub created_by: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub last_delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_failed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// why the last failed delivery failed
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// A reminder a user set in a channel
+pub struct Reminder {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    pub thread_id: Option<Uuid>,
+    pub user_id: String,
+    pub content: String,
+    pub remind_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// The last message a user read in a channel, or in one of its threads
+pub struct ReadMarker {
+    pub user_id: String,
+    pub channel_id: Uuid,
+    /// the thread the marker is for, none for the channel itself
+    pub thread_id: Option<Uuid>,
+    pub last_read_message_id: Uuid,
+    /// the time the last read message was sent, later messages are unread
+    pub last_read_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Unread messages of a user in a channel, not counting thread replies or their own messages
+pub struct ChannelUnread {
+    pub channel_id: Uuid,
+    /// none if the user hasn't marked a message of the channel as read
+    pub last_read_message_id: Option<Uuid>,
+    pub unread_count: i64,
+    /// unread messages that mention the user
+    pub mention_count: i64,
+    /// threads the user follows that have unread replies
+    pub threads: Vec<ThreadUnread>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+/// Unread replies of a user in a thread they read or replied to
+pub struct ThreadUnread {
+    pub thread_id: Uuid,
+    pub last_read_message_id: Uuid,
+    pub unread_count: i64,
+    /// unread replies that mention the user
+    pub mention_count: i64,
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ChannelPreview {
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use model::comms::{ModerationAction, ModerationActionType};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// The most actions returned in one page
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug)]
+pub struct NewModerationAction<'a> {
+    pub channel_id: Uuid,
+    pub actor_id: &'a str,
+    pub action: ModerationActionType,
+    pub target_message_id: Option<Uuid>,
+    pub target_user_id: Option<&'a str>,
+    pub details: Option<serde_json::Value>,
+}
+
+/// Records a moderation action under the organization of its channel
+#[tracing::instrument(skip(db), err)]
+pub async fn record_action(
+    db: &Pool<Postgres>,
+    action: NewModerationAction<'_>,
+) -> Result<ModerationAction> {
+    sqlx::query_as!(
+        ModerationAction,
+        r#"
+        INSERT INTO comms_moderation_actions (
+            id, channel_id, org_id, actor_id, action, target_message_id, target_user_id, details
+        )
+        SELECT $1, c.id, c.org_id, $3, $4, $5, $6, $7
+        FROM comms_channels c
+        WHERE c.id = $2
+        RETURNING
+            id,
+            channel_id,
+            org_id,
+            actor_id,
+            action AS "action: ModerationActionType",
+            target_message_id,
+            target_user_id,
+            details,
+            created_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        action.channel_id,
+        action.actor_id,
+        action.action as ModerationActionType,
+        action.target_message_id,
+        action.target_user_id,
+        action.details
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to record moderation action")
+}
+
+/// Gets the moderation actions taken in the channels of an organization, newest first
+#[tracing::instrument(skip(db), err)]
+pub async fn get_org_actions(
+    db: &Pool<Postgres>,
+    org_id: i64,
+    before: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<Vec<ModerationAction>> {
+    sqlx::query_as!(
+        ModerationAction,
+        r#"
+        SELECT
+            id,
+            channel_id,
+            org_id,
+            actor_id,
+            action AS "action: ModerationActionType",
+            target_message_id,
+            target_user_id,
+            details,
+            created_at
+        FROM comms_moderation_actions
+        WHERE org_id = $1 AND ($2::timestamptz IS NULL OR created_at < $2)
+        ORDER BY created_at DESC
+        LIMIT $3
+        "#,
+        org_id,
+        before,
+        limit.clamp(1, MAX_LIMIT)
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get moderation actions")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(migrator = "MACRO_DB_MIGRATIONS")]
+    async fn test_record_and_get_org_actions(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("11111111-1111-1111-1111-111111111111");
+        sqlx::query(
+            "INSERT INTO comms_channels (id, name, channel_type, org_id, owner_id)
+             VALUES ($1, 'org channel', 'organization', 7, 'admin')",
+        )
+        .bind(channel_id)
+        .execute(&pool)
+        .await?;
+
+        let action = record_action(
+            &pool,
+            NewModerationAction {
+                channel_id,
+                actor_id: "admin",
+                action: ModerationActionType::SetSlowMode,
+                target_message_id: None,
+                target_user_id: None,
+                details: Some(serde_json::json!({ "slow_mode_seconds": 30 })),
+            },
+        )
+        .await?;
+        assert_eq!(action.org_id, Some(7));
+        assert_eq!(action.action, ModerationActionType::SetSlowMode);
+
+        let actions = get_org_actions(&pool, 7, None, 50).await?;
+        assert_eq!(actions.len(), 1);
+        assert!(get_org_actions(&pool, 8, None, 50).await?.is_empty());
+        assert!(
+            get_org_actions(&pool, 7, Some(action.created_at), 50)
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod actions;
+pub mod pins;
+pub mod slow_mode;
+pub mod thread_lock;
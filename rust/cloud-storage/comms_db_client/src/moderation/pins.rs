@@ -0,0 +1,131 @@
+use crate::model::{Message, PinnedMessage};
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Pins a message to its channel, or unpins it when pinned_by is None. Deleted messages can't be
+/// pinned.
+/// Returns the message, or None if it isn't in the channel or was deleted.
+#[tracing::instrument(skip(db), err)]
+pub async fn set_pinned(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    message_id: &Uuid,
+    pinned_by: Option<&str>,
+) -> Result<Option<Message>> {
+    sqlx::query_as!(
+        Message,
+        r#"
+        UPDATE comms_messages
+        SET
+            pinned_at = CASE WHEN $3::text IS NULL THEN NULL ELSE NOW() END,
+            pinned_by = $3
+        WHERE id = $1 AND channel_id = $2 AND deleted_at IS NULL
+        RETURNING
+            id,
+            channel_id,
+            sender_id,
+            content,
+            created_at,
+            updated_at,
+            thread_id,
+            edited_at as "edited_at: chrono::DateTime<chrono::Utc>",
+            deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>"
+        "#,
+        message_id,
+        channel_id,
+        pinned_by
+    )
+    .fetch_optional(db)
+    .await
+    .context("unable to pin message")
+}
+
+/// Gets the messages pinned to a channel, most recently pinned first
+#[tracing::instrument(skip(db), err)]
+pub async fn get_pinned_messages(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+) -> Result<Vec<PinnedMessage>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            channel_id,
+            sender_id,
+            content,
+            created_at,
+            updated_at,
+            thread_id,
+            edited_at as "edited_at: chrono::DateTime<chrono::Utc>",
+            deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>",
+            pinned_at as "pinned_at!",
+            pinned_by as "pinned_by!"
+        FROM comms_messages
+        WHERE channel_id = $1 AND pinned_at IS NOT NULL AND deleted_at IS NULL
+        ORDER BY pinned_at DESC
+        "#,
+        channel_id
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get pinned messages")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PinnedMessage {
+            message: Message {
+                id: row.id,
+                channel_id: row.channel_id,
+                thread_id: row.thread_id,
+                sender_id: row.sender_id,
+                content: row.content,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                edited_at: row.edited_at,
+                deleted_at: row.deleted_at,
+            },
+            pinned_at: row.pinned_at,
+            pinned_by: row.pinned_by,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("messages_with_context"))
+    )]
+    async fn test_pin_and_unpin(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+        let message_id = uuid!("bbbbbbbb-0000-0000-0000-000000000003");
+
+        assert!(
+            set_pinned(&pool, &channel_id, &message_id, Some("user1"))
+                .await?
+                .is_some()
+        );
+        let pinned = get_pinned_messages(&pool, &channel_id).await?;
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].message.id, message_id);
+        assert_eq!(pinned[0].pinned_by, "user1");
+
+        let other_channel = uuid!("aaaaaaaa-0000-0000-0000-000000000002");
+        assert!(
+            set_pinned(&pool, &other_channel, &message_id, Some("user1"))
+                .await?
+                .is_none()
+        );
+
+        set_pinned(&pool, &channel_id, &message_id, None).await?;
+        assert!(get_pinned_messages(&pool, &channel_id).await?.is_empty());
+
+        Ok(())
+    }
+}
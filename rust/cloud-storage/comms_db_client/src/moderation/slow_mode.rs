@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// The longest slow mode a channel can have, 6 hours
+pub const MAX_SLOW_MODE_SECONDS: i32 = 6 * 60 * 60;
+
+/// Sets the least time between messages of a member of a channel, 0 turns slow mode off.
+/// Returns false if the channel doesn't exist.
+#[tracing::instrument(skip(db), err)]
+pub async fn set_slow_mode(db: &Pool<Postgres>, channel_id: &Uuid, seconds: i32) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE comms_channels
+        SET slow_mode_seconds = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        channel_id,
+        seconds.clamp(0, MAX_SLOW_MODE_SECONDS)
+    )
+    .execute(db)
+    .await
+    .context("unable to set slow mode")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Claims a post to the channel under its slow mode. The last post time of the user is moved to
+/// now only if the slow mode since their last post has run out, in one statement, so concurrent
+/// posts can't both get through.
+/// Returns how long the user has to wait before posting, or None if the post was claimed or the
+/// channel has no slow mode.
+#[tracing::instrument(skip(db), err)]
+pub async fn claim_slow_mode_post(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    user_id: &str,
+) -> Result<Option<Duration>> {
+    let row = sqlx::query!(
+        r#"
+        WITH channel AS (
+            SELECT id, slow_mode_seconds
+            FROM comms_channels
+            WHERE id = $1
+        ),
+        claimed AS (
+            INSERT INTO comms_slow_mode_posts (channel_id, user_id, last_posted_at)
+            SELECT id, $2, NOW()
+            FROM channel
+            WHERE slow_mode_seconds > 0
+            ON CONFLICT (channel_id, user_id) DO UPDATE
+            SET last_posted_at = EXCLUDED.last_posted_at
+            WHERE comms_slow_mode_posts.last_posted_at
+                <= NOW() - (SELECT slow_mode_seconds FROM channel) * INTERVAL '1 second'
+            RETURNING user_id
+        )
+        SELECT
+            c.slow_mode_seconds,
+            EXISTS (SELECT 1 FROM claimed) AS "claimed!",
+            p.last_posted_at AS "last_posted_at?"
+        FROM channel c
+        LEFT JOIN comms_slow_mode_posts p ON p.channel_id = c.id AND p.user_id = $2
+        "#,
+        channel_id,
+        user_id
+    )
+    .fetch_optional(db)
+    .await
+    .context("unable to claim slow mode post")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    if row.claimed || row.slow_mode_seconds <= 0 {
+        return Ok(None);
+    }
+
+    // when a post racing this one claimed first, the last post time this statement sees is from
+    // before that post, so the whole slow mode is left to wait
+    let wait = slow_mode_wait(row.slow_mode_seconds, row.last_posted_at, Utc::now())
+        .unwrap_or_else(|| Duration::seconds(row.slow_mode_seconds.into()));
+    Ok(Some(wait))
+}
+
+/// How long is left of the slow mode since the last message, if any
+pub fn slow_mode_wait(
+    slow_mode_seconds: i32,
+    last_sent_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    if slow_mode_seconds <= 0 {
+        return None;
+    }
+    let next_allowed = last_sent_at? + Duration::seconds(slow_mode_seconds.into());
+    (next_allowed > now).then(|| next_allowed - now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[test]
+    fn test_slow_mode_wait() {
+        let now = Utc::now();
+        let ten_seconds_ago = Some(now - Duration::seconds(10));
+
+        assert_eq!(slow_mode_wait(0, ten_seconds_ago, now), None);
+        assert_eq!(slow_mode_wait(30, None, now), None);
+        assert_eq!(slow_mode_wait(5, ten_seconds_ago, now), None);
+        assert_eq!(
+            slow_mode_wait(30, ten_seconds_ago, now),
+            Some(Duration::seconds(20))
+        );
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_claim_slow_mode_post(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+
+        // no slow mode, nothing to claim
+        assert_eq!(
+            claim_slow_mode_post(&pool, &channel_id, "user1").await?,
+            None
+        );
+        assert_eq!(
+            claim_slow_mode_post(&pool, &channel_id, "user1").await?,
+            None
+        );
+
+        set_slow_mode(&pool, &channel_id, 60).await?;
+        assert_eq!(
+            claim_slow_mode_post(&pool, &channel_id, "user1").await?,
+            None
+        );
+        let wait = claim_slow_mode_post(&pool, &channel_id, "user1")
+            .await?
+            .expect("slow mode");
+        assert!(wait > Duration::seconds(55) && wait <= Duration::seconds(60));
+        // other members have their own slow mode
+        assert_eq!(
+            claim_slow_mode_post(&pool, &channel_id, "user2").await?,
+            None
+        );
+
+        // concurrent posts claim the slow mode once
+        let claims = futures::future::join_all(
+            (0..10).map(|_| claim_slow_mode_post(&pool, &channel_id, "user3")),
+        )
+        .await;
+        let claimed = claims
+            .into_iter()
+            .map(|claim| claim.map(|wait| wait.is_none()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(claimed.iter().filter(|claimed| **claimed).count(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::model::Message;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Locks a thread so members can no longer reply to it, or unlocks it when locked_by is None.
+/// Returns the message the thread starts at, or None if it isn't a top level message of the
+/// channel.
+#[tracing::instrument(skip(db), err)]
+pub async fn set_thread_locked(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    thread_id: &Uuid,
+    locked_by: Option<&str>,
+) -> Result<Option<Message>> {
+    sqlx::query_as!(
+        Message,
+        r#"
+        UPDATE comms_messages
+        SET
+            thread_locked_at = CASE WHEN $3::text IS NULL THEN NULL ELSE NOW() END,
+            thread_locked_by = $3
+        WHERE id = $1 AND channel_id = $2 AND thread_id IS NULL
+        RETURNING
+            id,
+            channel_id,
+            sender_id,
+            content,
+            created_at,
+            updated_at,
+            thread_id,
+            edited_at as "edited_at: chrono::DateTime<chrono::Utc>",
+            deleted_at as "deleted_at: chrono::DateTime<chrono::Utc>"
+        "#,
+        thread_id,
+        channel_id,
+        locked_by
+    )
+    .fetch_optional(db)
+    .await
+    .context("unable to lock thread")
+}
+
+/// Whether a thread was locked by a channel admin
+#[tracing::instrument(skip(db), err)]
+pub async fn is_thread_locked(db: &Pool<Postgres>, thread_id: &Uuid) -> Result<bool> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM comms_messages WHERE id = $1 AND thread_locked_at IS NOT NULL
+        ) AS "locked!"
+        "#,
+        thread_id
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to get thread lock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_lock_thread(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+        let thread_id = uuid!("bbbbbbbb-0000-0000-0000-000000000001");
+        let reply = uuid!("bbbbbbbb-0000-0000-0000-000000000011");
+
+        assert!(!is_thread_locked(&pool, &thread_id).await?);
+        assert!(
+            set_thread_locked(&pool, &channel_id, &thread_id, Some("user1"))
+                .await?
+                .is_some()
+        );
+        assert!(is_thread_locked(&pool, &thread_id).await?);
+
+        // replies don't start threads
+        assert!(
+            set_thread_locked(&pool, &channel_id, &reply, Some("user1"))
+                .await?
+                .is_none()
+        );
+
+        set_thread_locked(&pool, &channel_id, &thread_id, None).await?;
+        assert!(!is_thread_locked(&pool, &thread_id).await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Gets the first message of a channel the user hasn't read, not counting thread replies or their
+/// own messages. Returns None if the user has read the whole channel or isn't in it.
+#[tracing::instrument(skip(db), err)]
+pub async fn get_first_unread_message_id(
+    db: &Pool<Postgres>,
+    user_id: &str,
+    channel_id: &Uuid,
+) -> Result<Option<Uuid>> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT m.id
+        FROM comms_channel_participants cp
+        LEFT JOIN comms_read_markers rm
+            ON rm.user_id = cp.user_id AND rm.channel_id = cp.channel_id AND rm.thread_id IS NULL
+        LEFT JOIN comms_activity a
+            ON a.user_id = cp.user_id AND a.channel_id = cp.channel_id
+        JOIN comms_messages m
+            ON m.channel_id = cp.channel_id
+            AND m.thread_id IS NULL
+            AND m.deleted_at IS NULL
+            AND m.sender_id <> cp.user_id
+            AND m.created_at > COALESCE(rm.last_read_at, a.viewed_at AT TIME ZONE 'UTC', cp.joined_at)
+        WHERE cp.channel_id = $1 AND cp.user_id = $2 AND cp.left_at IS NULL
+        ORDER BY m.created_at ASC
+        LIMIT 1
+        "#,
+        channel_id,
+        user_id
+    )
+    .fetch_optional(db)
+    .await
+    .context("failed to get first unread message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_markers::mark_read::mark_read;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_get_first_unread_message_id(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+
+        assert_eq!(
+            get_first_unread_message_id(&pool, "user1", &channel_id).await?,
+            Some(uuid!("bbbbbbbb-0000-0000-0000-000000000001"))
+        );
+
+        // own messages are skipped
+        mark_read(
+            &pool,
+            "user1",
+            &channel_id,
+            &uuid!("bbbbbbbb-0000-0000-0000-000000000002"),
+        )
+        .await?;
+        assert_eq!(
+            get_first_unread_message_id(&pool, "user1", &channel_id).await?,
+            Some(uuid!("bbbbbbbb-0000-0000-0000-000000000004"))
+        );
+
+        mark_read(
+            &pool,
+            "user1",
+            &channel_id,
+            &uuid!("bbbbbbbb-0000-0000-0000-000000000004"),
+        )
+        .await?;
+        assert_eq!(
+            get_first_unread_message_id(&pool, "user1", &channel_id).await?,
+            None
+        );
+        assert_eq!(
+            get_first_unread_message_id(&pool, "user3", &channel_id).await?,
+            None
+        );
+
+        Ok(())
+    }
+}
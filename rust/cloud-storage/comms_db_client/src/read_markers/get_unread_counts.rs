@@ -0,0 +1,160 @@
+use crate::model::{ChannelUnread, ThreadUnread};
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Gets the unread and mention counts of every channel the user is in, with the threads they
+/// follow that have unread replies. Channels the user never marked read count from the last time
+/// they viewed the channel, or from when they joined it.
+#[tracing::instrument(skip(db), err)]
+pub async fn get_unread_counts(db: &Pool<Postgres>, user_id: &str) -> Result<Vec<ChannelUnread>> {
+    let channels = sqlx::query!(
+        r#"
+        WITH read_at AS (
+            SELECT
+                cp.channel_id,
+                rm.last_read_message_id,
+                COALESCE(rm.last_read_at, a.viewed_at AT TIME ZONE 'UTC', cp.joined_at) AS read_at
+            FROM comms_channel_participants cp
+            LEFT JOIN comms_read_markers rm
+                ON rm.user_id = cp.user_id AND rm.channel_id = cp.channel_id AND rm.thread_id IS NULL
+            LEFT JOIN comms_activity a
+                ON a.user_id = cp.user_id AND a.channel_id = cp.channel_id
+            WHERE cp.user_id = $1 AND cp.left_at IS NULL
+        )
+        SELECT
+            r.channel_id AS "channel_id!",
+            r.last_read_message_id AS "last_read_message_id?",
+            COUNT(m.id) AS "unread_count!",
+            COUNT(m.id) FILTER (WHERE EXISTS (
+                SELECT 1 FROM comms_entity_mentions em
+                WHERE em.source_entity_type = 'message'
+                  AND em.source_entity_id = m.id::text
+                  AND em.entity_type = 'user'
+                  AND em.entity_id = $1
+            )) AS "mention_count!"
+        FROM read_at r
+        LEFT JOIN comms_messages m
+            ON m.channel_id = r.channel_id
+            AND m.thread_id IS NULL
+            AND m.deleted_at IS NULL
+            AND m.sender_id <> $1
+            AND m.created_at > r.read_at
+        GROUP BY r.channel_id, r.last_read_message_id
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await
+    .context("failed to get channel unread counts")?;
+
+    let threads = sqlx::query!(
+        r#"
+        SELECT
+            rm.channel_id,
+            rm.thread_id AS "thread_id!",
+            rm.last_read_message_id,
+            COUNT(m.id) AS "unread_count!",
+            COUNT(m.id) FILTER (WHERE EXISTS (
+                SELECT 1 FROM comms_entity_mentions em
+                WHERE em.source_entity_type = 'message'
+                  AND em.source_entity_id = m.id::text
+                  AND em.entity_type = 'user'
+                  AND em.entity_id = $1
+            )) AS "mention_count!"
+        FROM comms_read_markers rm
+        JOIN comms_channel_participants cp
+            ON cp.channel_id = rm.channel_id AND cp.user_id = rm.user_id AND cp.left_at IS NULL
+        JOIN comms_messages m
+            ON m.thread_id = rm.thread_id
+            AND m.deleted_at IS NULL
+            AND m.sender_id <> $1
+            AND m.created_at > rm.last_read_at
+        WHERE rm.user_id = $1 AND rm.thread_id IS NOT NULL
+        GROUP BY rm.channel_id, rm.thread_id, rm.last_read_message_id
+        ORDER BY rm.thread_id
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await
+    .context("failed to get thread unread counts")?;
+
+    let mut threads_by_channel: HashMap<Uuid, Vec<ThreadUnread>> = HashMap::new();
+    for thread in threads {
+        threads_by_channel
+            .entry(thread.channel_id)
+            .or_default()
+            .push(ThreadUnread {
+                thread_id: thread.thread_id,
+                last_read_message_id: thread.last_read_message_id,
+                unread_count: thread.unread_count,
+                mention_count: thread.mention_count,
+            });
+    }
+
+    Ok(channels
+        .into_iter()
+        .map(|channel| ChannelUnread {
+            threads: threads_by_channel
+                .remove(&channel.channel_id)
+                .unwrap_or_default(),
+            channel_id: channel.channel_id,
+            last_read_message_id: channel.last_read_message_id,
+            unread_count: channel.unread_count,
+            mention_count: channel.mention_count,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_markers::mark_read::mark_read;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    const CHANNEL_ID: Uuid = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+
+    fn channel(unread: &[ChannelUnread], channel_id: Uuid) -> &ChannelUnread {
+        unread
+            .iter()
+            .find(|c| c.channel_id == channel_id)
+            .expect("channel")
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_get_unread_counts(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+
+        // nothing read yet, own and deleted messages and replies don't count
+        let unread = get_unread_counts(&pool, "user1").await?;
+        assert_eq!(unread.len(), 2);
+        let test_channel = channel(&unread, CHANNEL_ID);
+        assert_eq!(test_channel.unread_count, 3);
+        assert_eq!(test_channel.mention_count, 1);
+        assert!(test_channel.threads.is_empty());
+        let other_channel = channel(&unread, uuid!("aaaaaaaa-0000-0000-0000-000000000002"));
+        assert_eq!(other_channel.unread_count, 0);
+
+        let second = uuid!("bbbbbbbb-0000-0000-0000-000000000002");
+        let first_reply = uuid!("bbbbbbbb-0000-0000-0000-000000000011");
+        mark_read(&pool, "user1", &CHANNEL_ID, &second).await?;
+        mark_read(&pool, "user1", &CHANNEL_ID, &first_reply).await?;
+
+        let unread = get_unread_counts(&pool, "user1").await?;
+        let test_channel = channel(&unread, CHANNEL_ID);
+        assert_eq!(test_channel.last_read_message_id, Some(second));
+        assert_eq!(test_channel.unread_count, 1);
+        assert_eq!(test_channel.mention_count, 0);
+        assert_eq!(test_channel.threads.len(), 1);
+        assert_eq!(test_channel.threads[0].unread_count, 1);
+        assert_eq!(test_channel.threads[0].mention_count, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::model::ReadMarker;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Marks the messages of a channel read up to and including the given message. A thread reply
+/// marks its thread, any other message marks the channel. Markers only move forward, marking an
+/// older message leaves the marker as it is.
+/// Returns the marker, or None if the message isn't in the channel.
+#[tracing::instrument(skip(db), err)]
+pub async fn mark_read(
+    db: &Pool<Postgres>,
+    user_id: &str,
+    channel_id: &Uuid,
+    message_id: &Uuid,
+) -> Result<Option<ReadMarker>> {
+    let Some(message) = sqlx::query!(
+        r#"
+        SELECT id, thread_id, created_at
+        FROM comms_messages
+        WHERE id = $1 AND channel_id = $2
+        "#,
+        message_id,
+        channel_id
+    )
+    .fetch_optional(db)
+    .await
+    .context("failed to get message to mark read")?
+    else {
+        return Ok(None);
+    };
+
+    // the conflict targets are the partial unique indexes, one for channels and one for threads
+    let marker = match message.thread_id {
+        None => sqlx::query_as!(
+            ReadMarker,
+            r#"
+            INSERT INTO comms_read_markers (
+                id, user_id, channel_id, thread_id, last_read_message_id, last_read_at
+            )
+            VALUES ($1, $2, $3, NULL, $4, $5)
+            ON CONFLICT (user_id, channel_id) WHERE thread_id IS NULL DO UPDATE
+            SET
+                last_read_message_id = EXCLUDED.last_read_message_id,
+                last_read_at = EXCLUDED.last_read_at,
+                updated_at = NOW()
+            WHERE comms_read_markers.last_read_at <= EXCLUDED.last_read_at
+            RETURNING user_id, channel_id, thread_id, last_read_message_id, last_read_at, updated_at
+            "#,
+            macro_uuid::generate_uuid_v7(),
+            user_id,
+            channel_id,
+            message.id,
+            message.created_at
+        )
+        .fetch_optional(db)
+        .await
+        .context("failed to mark channel read")?,
+        Some(thread_id) => sqlx::query_as!(
+            ReadMarker,
+            r#"
+            INSERT INTO comms_read_markers (
+                id, user_id, channel_id, thread_id, last_read_message_id, last_read_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, thread_id) WHERE thread_id IS NOT NULL DO UPDATE
+            SET
+                last_read_message_id = EXCLUDED.last_read_message_id,
+                last_read_at = EXCLUDED.last_read_at,
+                updated_at = NOW()
+            WHERE comms_read_markers.last_read_at <= EXCLUDED.last_read_at
+            RETURNING user_id, channel_id, thread_id, last_read_message_id, last_read_at, updated_at
+            "#,
+            macro_uuid::generate_uuid_v7(),
+            user_id,
+            channel_id,
+            thread_id,
+            message.id,
+            message.created_at
+        )
+        .fetch_optional(db)
+        .await
+        .context("failed to mark thread read")?,
+    };
+
+    if let Some(marker) = marker {
+        return Ok(Some(marker));
+    }
+
+    // the marker is already past the message
+    sqlx::query_as!(
+        ReadMarker,
+        r#"
+        SELECT user_id, channel_id, thread_id, last_read_message_id, last_read_at, updated_at
+        FROM comms_read_markers
+        WHERE user_id = $1
+          AND channel_id = $2
+          AND thread_id IS NOT DISTINCT FROM $3
+        "#,
+        user_id,
+        channel_id,
+        message.thread_id
+    )
+    .fetch_optional(db)
+    .await
+    .context("failed to get read marker")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    const CHANNEL_ID: Uuid = uuid!("aaaaaaaa-0000-0000-0000-000000000001");
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_mark_read_only_moves_forward(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let third = uuid!("bbbbbbbb-0000-0000-0000-000000000003");
+        let first = uuid!("bbbbbbbb-0000-0000-0000-000000000001");
+
+        let marker = mark_read(&pool, "user1", &CHANNEL_ID, &third)
+            .await?
+            .expect("marker");
+        assert_eq!(marker.last_read_message_id, third);
+        assert_eq!(marker.thread_id, None);
+
+        let marker = mark_read(&pool, "user1", &CHANNEL_ID, &first)
+            .await?
+            .expect("marker");
+        assert_eq!(marker.last_read_message_id, third);
+
+        Ok(())
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("read_markers"))
+    )]
+    async fn test_mark_read_thread_reply(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let reply = uuid!("bbbbbbbb-0000-0000-0000-000000000011");
+
+        let marker = mark_read(&pool, "user1", &CHANNEL_ID, &reply)
+            .await?
+            .expect("marker");
+        assert_eq!(
+            marker.thread_id,
+            Some(uuid!("bbbbbbbb-0000-0000-0000-000000000001"))
+        );
+
+        let other_channel = uuid!("aaaaaaaa-0000-0000-0000-000000000002");
+        assert!(
+            mark_read(&pool, "user1", &other_channel, &reply)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
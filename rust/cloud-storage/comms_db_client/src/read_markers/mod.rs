@@ -0,0 +1,3 @@
+pub mod get_first_unread;
+pub mod get_unread_counts;
+pub mod mark_read;
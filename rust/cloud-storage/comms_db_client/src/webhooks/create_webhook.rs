@@ -0,0 +1,47 @@
+use crate::model::IncomingWebhook;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct CreateWebhookOptions<'a> {
+    pub channel_id: Uuid,
+    pub name: &'a str,
+    pub avatar_url: Option<&'a str>,
+    /// sha256 of the webhook's secret token
+    pub token_hash: &'a str,
+    pub created_by: &'a str,
+}
+
+/// Creates an incoming webhook for a channel
+#[tracing::instrument(skip(db, options), fields(channel_id = %options.channel_id), err)]
+pub async fn create_webhook(
+    db: &Pool<Postgres>,
+    options: CreateWebhookOptions<'_>,
+) -> Result<IncomingWebhook> {
+    sqlx::query_as!(
+        IncomingWebhook,
+        r#"
+        INSERT INTO comms_incoming_webhooks (id, channel_id, name, avatar_url, token_hash, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING
+            id,
+            channel_id,
+            'bot|' || id::text AS "bot_id!",
+            name,
+            avatar_url,
+            created_by,
+            created_at,
+            last_used_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        options.channel_id,
+        options.name,
+        options.avatar_url,
+        options.token_hash,
+        options.created_by
+    )
+    .fetch_one(db)
+    .await
+    .context("unable to create webhook")
+}
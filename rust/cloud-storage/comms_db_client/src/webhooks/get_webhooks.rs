@@ -0,0 +1,33 @@
+use crate::model::IncomingWebhook;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Gets the webhooks of a channel that haven't been revoked, oldest first
+#[tracing::instrument(skip(db), err)]
+pub async fn get_channel_webhooks(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+) -> Result<Vec<IncomingWebhook>> {
+    sqlx::query_as!(
+        IncomingWebhook,
+        r#"
+        SELECT
+            id,
+            channel_id,
+            'bot|' || id::text AS "bot_id!",
+            name,
+            avatar_url,
+            created_by,
+            created_at,
+            last_used_at
+        FROM comms_incoming_webhooks
+        WHERE channel_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at ASC
+        "#,
+        channel_id
+    )
+    .fetch_all(db)
+    .await
+    .context("unable to get webhooks")
+}
@@ -0,0 +1,19 @@
+use uuid::Uuid;
+
+pub mod create_webhook;
+pub mod get_webhooks;
+pub mod revoke_webhook;
+pub mod take_send;
+
+/// The prefix of the sender ids of webhook bots, which can't clash with user ids
+pub const BOT_ID_PREFIX: &str = "bot|";
+
+/// The sender id of the messages of a webhook
+pub fn bot_id(webhook_id: &Uuid) -> String {
+    format!("{BOT_ID_PREFIX}{webhook_id}")
+}
+
+/// Whether a sender id belongs to a webhook bot rather than a user
+pub fn is_bot_id(sender_id: &str) -> bool {
+    sender_id.starts_with(BOT_ID_PREFIX)
+}
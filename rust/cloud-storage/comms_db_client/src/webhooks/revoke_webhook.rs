@@ -0,0 +1,38 @@
+use crate::model::IncomingWebhook;
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// Revokes a webhook of a channel, its token stops working immediately.
+/// Returns None if the webhook isn't in the channel or was already revoked.
+#[tracing::instrument(skip(db), err)]
+pub async fn revoke_webhook(
+    db: &Pool<Postgres>,
+    channel_id: &Uuid,
+    webhook_id: &Uuid,
+    revoked_by: &str,
+) -> Result<Option<IncomingWebhook>> {
+    sqlx::query_as!(
+        IncomingWebhook,
+        r#"
+        UPDATE comms_incoming_webhooks
+        SET revoked_at = NOW(), revoked_by = $3
+        WHERE id = $1 AND channel_id = $2 AND revoked_at IS NULL
+        RETURNING
+            id,
+            channel_id,
+            'bot|' || id::text AS "bot_id!",
+            name,
+            avatar_url,
+            created_by,
+            created_at,
+            last_used_at
+        "#,
+        webhook_id,
+        channel_id,
+        revoked_by
+    )
+    .fetch_optional(db)
+    .await
+    .context("unable to revoke webhook")
+}
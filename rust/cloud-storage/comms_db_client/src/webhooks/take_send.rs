@@ -0,0 +1,271 @@
+use crate::model::IncomingWebhook;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct WebhookSend {
+    pub webhook: IncomingWebhook,
+    /// the sends in the current rate limit window, including this one if it was counted
+    pub window_count: i32,
+    pub window_started_at: DateTime<Utc>,
+    /// the window was already full, so the send wasn't counted
+    pub rate_limited: bool,
+}
+
+/// Authenticates a send to a webhook and counts it against the webhook's rate limit window,
+/// starting a new window once the current one is over. Once a window has `max_sends` sends, later
+/// sends in it are rate limited and leave the webhook untouched. The count is checked and taken
+/// in one statement, so concurrent sends can't go over the limit.
+/// Returns None if the webhook doesn't exist, was revoked or the token hash doesn't match.
+#[tracing::instrument(skip(db, token_hash), err)]
+pub async fn take_send(
+    db: &Pool<Postgres>,
+    webhook_id: &Uuid,
+    token_hash: &str,
+    window: chrono::Duration,
+    max_sends: i32,
+) -> Result<Option<WebhookSend>> {
+    let window_start = Utc::now() - window;
+    let row = sqlx::query!(
+        r#"
+        WITH webhook AS (
+            SELECT
+                id,
+                channel_id,
+                name,
+                avatar_url,
+                created_by,
+                created_at,
+                last_used_at,
+                window_count,
+                window_started_at,
+                window_started_at IS NULL OR window_started_at <= $3 AS window_over
+            FROM comms_incoming_webhooks
+            WHERE id = $1 AND token_hash = $2 AND revoked_at IS NULL
+            FOR UPDATE
+        ),
+        taken AS (
+            UPDATE comms_incoming_webhooks w
+            SET
+                last_used_at = NOW(),
+                window_count = CASE
+                    WHEN webhook.window_over THEN 1
+                    ELSE webhook.window_count + 1
+                END,
+                window_started_at = CASE
+                    WHEN webhook.window_over THEN NOW()
+                    ELSE webhook.window_started_at
+                END
+            FROM webhook
+            WHERE w.id = webhook.id AND (webhook.window_over OR webhook.window_count < $4)
+            RETURNING w.last_used_at, w.window_count, w.window_started_at
+        )
+        SELECT
+            webhook.id,
+            webhook.channel_id,
+            'bot|' || webhook.id::text AS "bot_id!",
+            webhook.name,
+            webhook.avatar_url,
+            webhook.created_by,
+            webhook.created_at,
+            COALESCE(taken.last_used_at, webhook.last_used_at) AS last_used_at,
+            COALESCE(taken.window_count, webhook.window_count) AS "window_count!",
+            -- a full window hasn't ended, so it has a start
+            COALESCE(taken.window_started_at, webhook.window_started_at) AS "window_started_at!",
+            taken.window_count IS NULL AS "rate_limited!"
+        FROM webhook
+        LEFT JOIN taken ON TRUE
+        "#,
+        webhook_id,
+        token_hash,
+        window_start,
+        max_sends
+    )
+    .fetch_optional(db)
+    .await
+    .context("unable to take webhook send")?;
+
+    Ok(row.map(|row| WebhookSend {
+        webhook: IncomingWebhook {
+            id: row.id,
+            channel_id: row.channel_id,
+            bot_id: row.bot_id,
+            name: row.name,
+            avatar_url: row.avatar_url,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        },
+        window_count: row.window_count,
+        window_started_at: row.window_started_at,
+        rate_limited: row.rate_limited,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhooks::{
+        bot_id,
+        create_webhook::{CreateWebhookOptions, create_webhook},
+        get_webhooks::get_channel_webhooks,
+        revoke_webhook::revoke_webhook,
+    };
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use uuid::uuid;
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_webhook_lifecycle(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        const _: &sqlx::migrate::Migrator = &MACRO_DB_MIGRATIONS; // Dummy reference for IDE
+        let channel_id = uuid!("33333333-3333-3333-3333-333333333333");
+        let other_channel_id = uuid!("11111111-1111-1111-1111-111111111111");
+
+        let webhook = create_webhook(
+            &pool,
+            CreateWebhookOptions {
+                channel_id,
+                name: "CI",
+                avatar_url: None,
+                token_hash: "hash",
+                created_by: "user5",
+            },
+        )
+        .await?;
+        assert_eq!(webhook.bot_id, bot_id(&webhook.id));
+        assert_eq!(get_channel_webhooks(&pool, &channel_id).await?.len(), 1);
+
+        let window = chrono::Duration::minutes(1);
+        assert!(
+            take_send(&pool, &webhook.id, "wrong", window, 10)
+                .await?
+                .is_none()
+        );
+        let first = take_send(&pool, &webhook.id, "hash", window, 10)
+            .await?
+            .expect("send");
+        assert_eq!(first.window_count, 1);
+        let second = take_send(&pool, &webhook.id, "hash", window, 10)
+            .await?
+            .expect("send");
+        assert_eq!(second.window_count, 2);
+        assert_eq!(second.window_started_at, first.window_started_at);
+
+        // a zero length window is always over, so every send starts a new one
+        let fresh = take_send(&pool, &webhook.id, "hash", chrono::Duration::zero(), 10)
+            .await?
+            .expect("send");
+        assert_eq!(fresh.window_count, 1);
+
+        // revoking is scoped to the channel
+        assert!(
+            revoke_webhook(&pool, &other_channel_id, &webhook.id, "user1")
+                .await?
+                .is_none()
+        );
+        assert!(
+            revoke_webhook(&pool, &channel_id, &webhook.id, "user5")
+                .await?
+                .is_some()
+        );
+        assert!(get_channel_webhooks(&pool, &channel_id).await?.is_empty());
+        assert!(
+            take_send(&pool, &webhook.id, "hash", window, 10)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_take_send_rate_limit(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let webhook = create_webhook(
+            &pool,
+            CreateWebhookOptions {
+                channel_id: uuid!("33333333-3333-3333-3333-333333333333"),
+                name: "CI",
+                avatar_url: None,
+                token_hash: "hash",
+                created_by: "user5",
+            },
+        )
+        .await?;
+        let window = chrono::Duration::minutes(1);
+
+        let first = take_send(&pool, &webhook.id, "hash", window, 2)
+            .await?
+            .expect("send");
+        let second = take_send(&pool, &webhook.id, "hash", window, 2)
+            .await?
+            .expect("send");
+        assert!(!first.rate_limited && !second.rate_limited);
+        assert_eq!(second.window_count, 2);
+
+        // a full window neither counts the send nor marks the webhook as used
+        let limited = take_send(&pool, &webhook.id, "hash", window, 2)
+            .await?
+            .expect("send");
+        assert!(limited.rate_limited);
+        assert_eq!(limited.window_count, 2);
+        assert_eq!(limited.window_started_at, first.window_started_at);
+        assert_eq!(limited.webhook.last_used_at, second.webhook.last_used_at);
+
+        let stored = sqlx::query!(
+            "SELECT window_count, last_used_at FROM comms_incoming_webhooks WHERE id = $1",
+            webhook.id
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(stored.window_count, 2);
+        assert_eq!(stored.last_used_at, second.webhook.last_used_at);
+
+        // the next window starts counting again
+        let next = take_send(&pool, &webhook.id, "hash", chrono::Duration::zero(), 2)
+            .await?
+            .expect("send");
+        assert!(!next.rate_limited);
+        assert_eq!(next.window_count, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("channels"))
+    )]
+    async fn test_take_send_concurrent(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let webhook = create_webhook(
+            &pool,
+            CreateWebhookOptions {
+                channel_id: uuid!("33333333-3333-3333-3333-333333333333"),
+                name: "CI",
+                avatar_url: None,
+                token_hash: "hash",
+                created_by: "user5",
+            },
+        )
+        .await?;
+        let window = chrono::Duration::minutes(1);
+
+        let sends = futures::future::try_join_all(
+            (0..10).map(|_| take_send(&pool, &webhook.id, "hash", window, 3)),
+        )
+        .await?;
+        let taken = sends
+            .iter()
+            .flatten()
+            .filter(|send| !send.rate_limited)
+            .count();
+        assert_eq!(taken, 3);
+
+        Ok(())
+    }
+}
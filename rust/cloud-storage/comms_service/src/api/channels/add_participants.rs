@@ -4,6 +4,8 @@ use crate::api::extractors::{
     ChannelAdmin, ChannelId, ChannelName, ChannelParticipants, ChannelTypeExtractor,
 };
 use crate::notification as comms_notification;
+use crate::service::bots::events::{self, BotEvent, MemberJoined};
+use crate::service::moderation;
 use anyhow::Result;
 use axum::extract::Json;
 use axum::{extract::State, http::StatusCode};
@@ -34,6 +36,7 @@ pub struct AddParticipantsRequest {
         (status = 200),
         (status = 401, body=String),
         (status = 404, body=String),
+        (status = 403, body=String, description = "The channel is archived"),
         (status = 500, body=String),
     )
 )]
@@ -55,6 +58,8 @@ pub async fn handler(
         ));
     }
 
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
     let participants = to_lowercase(&req.participants);
 
     for participant in participants.iter() {
@@ -93,6 +98,14 @@ pub async fn handler(
         })?;
     tracing::info!(elapsed=?start.elapsed(), "added user channel permissions");
 
+    events::dispatch_event(
+        &ctx,
+        channel_id,
+        BotEvent::MemberJoined(MemberJoined {
+            user_ids: participants.clone(),
+        }),
+    );
+
     // There should always be participants, but better safe than sorry
     if !participants.is_empty() {
         let metadata = CommonChannelMetadata {
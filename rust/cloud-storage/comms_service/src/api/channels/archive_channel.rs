@@ -0,0 +1,127 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::Cached;
+use comms_db_client::{
+    channels::archive_channel::{archive_channel, unarchive_channel},
+    moderation::actions::NewModerationAction,
+};
+use model::comms::ModerationActionType;
+use models_comms::ChannelType;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelAdmin, ChannelId, ChannelTypeExtractor},
+    },
+    service::moderation,
+};
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "archive_channel",
+        path = "/channels/{channel_id}/archive",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        responses(
+            (status = 200, body=String),
+            (status = 400, body=String),
+            (status = 401, body=String),
+            (status = 409, body=String, description = "The channel is already archived"),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn archive_channel_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+    Cached(ChannelTypeExtractor(channel_type)): Cached<ChannelTypeExtractor>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    if channel_type == ChannelType::DirectMessage {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "direct messages can't be archived".to_string(),
+        ));
+    }
+
+    set_archived(&ctx, &admin.context.user_id, channel_id, true).await
+}
+
+#[utoipa::path(
+        delete,
+        tag = "channels",
+        operation_id = "unarchive_channel",
+        path = "/channels/{channel_id}/archive",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        responses(
+            (status = 200, body=String),
+            (status = 401, body=String),
+            (status = 409, body=String, description = "The channel isn't archived"),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn unarchive_channel_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    set_archived(&ctx, &admin.context.user_id, channel_id, false).await
+}
+
+async fn set_archived(
+    ctx: &AppState,
+    user_id: &str,
+    channel_id: Uuid,
+    archived: bool,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let updated = if archived {
+        archive_channel(&ctx.db, &channel_id, user_id).await
+    } else {
+        unarchive_channel(&ctx.db, &channel_id).await
+    }
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to archive channel");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to archive channel".to_string(),
+        )
+    })?;
+
+    if !updated {
+        let message = if archived {
+            "channel is already archived"
+        } else {
+            "channel isn't archived"
+        };
+        return Err((StatusCode::CONFLICT, message.to_string()));
+    }
+
+    moderation::record_and_notify(
+        ctx,
+        NewModerationAction {
+            channel_id,
+            actor_id: user_id,
+            action: if archived {
+                ModerationActionType::ArchiveChannel
+            } else {
+                ModerationActionType::UnarchiveChannel
+            },
+            target_message_id: None,
+            target_user_id: None,
+            details: None,
+        },
+    )
+    .await;
+
+    let message = if archived {
+        "channel archived"
+    } else {
+        "channel unarchived"
+    };
+    Ok((StatusCode::OK, message.to_string()))
+}
@@ -0,0 +1,112 @@
+use axum::{
+    Json,
+    extract::{self, State},
+    http::StatusCode,
+};
+use axum_extra::extract::Cached;
+use comms_db_client::{
+    bots::outgoing_webhooks::{CreateOutgoingWebhookOptions, create_outgoing_webhook},
+    model::{BotEventType, OutgoingWebhook},
+};
+use models_comms::ChannelType;
+use outbound_http::{FetchError, webhook::validate_webhook_url};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelAdmin, ChannelId, ChannelTypeExtractor},
+    },
+    service::webhooks::generate_token,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateOutgoingWebhookRequest {
+    /// the https url the channel's events are posted to
+    pub url: String,
+    /// the events to deliver
+    pub events: Vec<BotEventType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateOutgoingWebhookResponse {
+    pub webhook: OutgoingWebhook,
+    /// the secret deliveries are signed with, it can't be retrieved again
+    pub secret: String,
+}
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "create_outgoing_webhook",
+        path = "/channels/{channel_id}/outgoing_webhooks",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        request_body = CreateOutgoingWebhookRequest,
+        responses(
+            (status = 201, body=CreateOutgoingWebhookResponse),
+            (status = 400, body=String),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn create_outgoing_webhook_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+    Cached(ChannelTypeExtractor(channel_type)): Cached<ChannelTypeExtractor>,
+    extract::Json(req): extract::Json<CreateOutgoingWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateOutgoingWebhookResponse>), (StatusCode, String)> {
+    if channel_type == ChannelType::DirectMessage {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "direct messages can't have webhooks".to_string(),
+        ));
+    }
+
+    let url = validate_webhook_url(&req.url).map_err(|e| match e {
+        FetchError::Blocked => (
+            StatusCode::BAD_REQUEST,
+            "url must be publicly reachable".to_string(),
+        ),
+        _ => (StatusCode::BAD_REQUEST, "url must be https".to_string()),
+    })?;
+
+    if req.events.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "at least one event is required".to_string(),
+        ));
+    }
+    let mut events = req.events;
+    events.sort_by_key(|event| event.as_str());
+    events.dedup();
+
+    let secret = generate_token();
+    let webhook = create_outgoing_webhook(
+        &ctx.db,
+        CreateOutgoingWebhookOptions {
+            channel_id,
+            url: url.as_str(),
+            secret: &secret,
+            events: &events,
+            created_by: &admin.context.user_id,
+        },
+    )
+    .await
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to create outgoing webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to create outgoing webhook".to_string(),
+        )
+    })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateOutgoingWebhookResponse { webhook, secret }),
+    ))
+}
@@ -0,0 +1,111 @@
+use axum::{
+    Json,
+    extract::{self, State},
+    http::StatusCode,
+};
+use axum_extra::extract::Cached;
+use comms_db_client::{
+    model::IncomingWebhook,
+    webhooks::create_webhook::{CreateWebhookOptions, create_webhook},
+};
+use models_comms::ChannelType;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelAdmin, ChannelId, ChannelTypeExtractor},
+    },
+    service::webhooks::{generate_token, hash_token},
+};
+
+/// The longest name a webhook bot can have
+const MAX_NAME_LENGTH: usize = 80;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// the name the bot posts under
+    pub name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    pub webhook: IncomingWebhook,
+    /// the secret token of the webhook, it can't be retrieved again
+    pub token: String,
+    /// the path external services post to, relative to the comms service
+    pub path: String,
+}
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "create_webhook",
+        path = "/channels/{channel_id}/webhooks",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        request_body = CreateWebhookRequest,
+        responses(
+            (status = 201, body=CreateWebhookResponse),
+            (status = 400, body=String),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn create_webhook_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+    Cached(ChannelTypeExtractor(channel_type)): Cached<ChannelTypeExtractor>,
+    extract::Json(req): extract::Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateWebhookResponse>), (StatusCode, String)> {
+    if channel_type == ChannelType::DirectMessage {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "direct messages can't have webhooks".to_string(),
+        ));
+    }
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("name must be between 1 and {MAX_NAME_LENGTH} characters"),
+        ));
+    }
+
+    let token = generate_token();
+    let webhook = create_webhook(
+        &ctx.db,
+        CreateWebhookOptions {
+            channel_id,
+            name,
+            avatar_url: req.avatar_url.as_deref(),
+            token_hash: &hash_token(&token),
+            created_by: &admin.context.user_id,
+        },
+    )
+    .await
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to create webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to create webhook".to_string(),
+        )
+    })?;
+
+    let path = format!("/webhooks/{}/{}", webhook.id, token);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWebhookResponse {
+            webhook,
+            token,
+            path,
+        }),
+    ))
+}
@@ -1,16 +1,19 @@
 use crate::{
     api::{
         context::AppState,
-        extractors::{ChannelParticipants, MessageId, MessageSenderOrAdmin},
+        extractors::{ChannelId, ChannelParticipants, MessageId, MessageSenderOrAdmin},
     },
-    service::{self, sender::notify::notify_message},
+    service::{self, moderation, sender::notify::notify_message},
 };
 use anyhow::Result;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
 };
-use comms_db_client::messages::delete_message::delete_message;
+use comms_db_client::{
+    messages::delete_message::delete_message, moderation::actions::NewModerationAction,
+};
+use model::comms::ModerationActionType;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -33,26 +36,55 @@ pub struct DeleteMessageParams {
             (status = 201, body=String),
             (status = 401, body=String),
             (status = 404, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
             (status = 500, body=String),
         )
     )]
 #[tracing::instrument(skip(ctx, participants))]
 pub async fn delete_message_handler(
     State(ctx): State<AppState>,
-    _message_sender_or_admin: MessageSenderOrAdmin,
+    message_sender_or_admin: MessageSenderOrAdmin,
     ChannelParticipants(participants): ChannelParticipants,
+    ChannelId(channel_id): ChannelId,
     MessageId(message_id): MessageId,
     Path(params): Path<DeleteMessageParams>,
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     tracing::info!("delete_message");
 
-    let message = delete_message(&ctx.db, message_id).await.map_err(|e| {
-        tracing::error!(error=?e, "unable to patch message");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "unable to patch message".to_string(),
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
+    let deleted_by = match &message_sender_or_admin {
+        MessageSenderOrAdmin::MessageSender(sender) => &sender.0.user_id,
+        MessageSenderOrAdmin::ChannelAdmin(admin) => &admin.0.context.user_id,
+    };
+
+    let message = delete_message(&ctx.db, channel_id, message_id, deleted_by)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to delete message");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to delete message".to_string(),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "message not found".to_string()))?;
+
+    // an admin removing someone else's message is moderation, kept for the organization's admins
+    if let MessageSenderOrAdmin::ChannelAdmin(admin) = &message_sender_or_admin {
+        moderation::record_and_notify(
+            &ctx,
+            NewModerationAction {
+                channel_id,
+                actor_id: &admin.0.context.user_id,
+                action: ModerationActionType::DeleteMessage,
+                target_message_id: Some(message.id),
+                target_user_id: Some(&message.sender_id),
+                // only the id, the content of a deleted message isn't kept anywhere
+                details: Some(serde_json::json!({ "message_id": message.id })),
+            },
         )
-    })?;
+        .await;
+    }
     let participants: Vec<_> = participants
         .clone()
         .iter()
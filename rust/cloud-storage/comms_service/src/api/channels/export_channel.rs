@@ -0,0 +1,104 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::Cached;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelId, ChannelMember, ChannelName},
+    },
+    service::export::{ChannelExport, export_channel, render_html},
+    utils::user_name::generate_name_lookup,
+};
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// every message with its reactions and attachments, for tools and archives
+    #[default]
+    Json,
+    /// a transcript to read in a browser
+    Html,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportChannelQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "export_channel",
+        path = "/channels/{channel_id}/export",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("format" = Option<ExportFormat>, Query, description = "json (the default) or html")
+        ),
+        responses(
+            (status = 200, body=ChannelExport),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn export_channel_handler(
+    State(ctx): State<AppState>,
+    ChannelMember(_channel_member): ChannelMember,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+    ChannelName(channel_name): ChannelName,
+    Query(query): Query<ExportChannelQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let mut export = export_channel(&ctx, &channel_id).await.map_err(|e| {
+        tracing::error!(error=?e, "unable to export channel");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to export channel".to_string(),
+        )
+    })?;
+    // direct messages are named after their members
+    export.channel.name = Some(channel_name);
+
+    let response = match query.format {
+        ExportFormat::Json => {
+            let disposition = format!("attachment; filename=\"channel-{channel_id}.json\"");
+            ([(header::CONTENT_DISPOSITION, disposition)], Json(export)).into_response()
+        }
+        ExportFormat::Html => {
+            let senders = export
+                .messages
+                .iter()
+                .map(|m| m.message.sender_id.clone())
+                .chain(export.participants.iter().map(|p| p.user_id.clone()))
+                .collect::<std::collections::HashSet<_>>()
+                .into_iter()
+                .collect();
+            let names = ctx
+                .auth_service_client
+                .get_names(senders)
+                .await
+                .inspect_err(|e| tracing::warn!(error=?e, "unable to get names for export"))
+                .ok()
+                .map(generate_name_lookup);
+
+            let disposition = format!("attachment; filename=\"channel-{channel_id}.html\"");
+            (
+                [
+                    (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                render_html(&export, names.as_ref()),
+            )
+                .into_response()
+        }
+    };
+
+    Ok(response)
+}
@@ -46,7 +46,8 @@ pub async fn get_channel_name_and_type(
             org_id,
             created_at,
             updated_at,
-            owner_id
+            owner_id,
+            archived_at
         FROM comms_channels
         WHERE id = $1
         "#,
@@ -63,6 +64,7 @@ pub async fn get_channel_name_and_type(
         created_at: channel_row.created_at,
         updated_at: channel_row.updated_at,
         owner_id: channel_row.owner_id,
+        archived_at: channel_row.archived_at,
     };
 
     let participants = get_participants(db, channel_id).await?;
@@ -5,7 +5,7 @@ use crate::{
 use anyhow::Result;
 use axum::{
     Json,
-    extract::{Extension, State},
+    extract::{Extension, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
@@ -29,6 +29,13 @@ pub struct GetChannelsResponse {
     pub channels: Vec<ChannelWithLatest>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GetChannelsQuery {
+    /// whether to include archived channels, which are hidden by default
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
 #[derive(Debug, Error)]
 pub enum GetChannelsError {
     #[error(transparent)]
@@ -53,6 +60,9 @@ impl IntoResponse for GetChannelsError {
         path = "/channels",
         tag = "channels",
         operation_id = "get_channels",
+        params(
+            ("include_archived" = Option<bool>, Query, description = "include archived channels")
+        ),
         responses(
             (status = 200, body=GetChannelsResponse),
             (status = 401, body=String),
@@ -64,10 +74,13 @@ impl IntoResponse for GetChannelsError {
 pub async fn get_channels_handler(
     State(app_state): State<AppState>,
     user_context: Extension<UserContext>,
+    Query(query): Query<GetChannelsQuery>,
 ) -> Result<Json<GetChannelsResponse>, GetChannelsError> {
     let db = &app_state.db;
 
-    let channels = get_user_channels_with_participants(db, &user_context.user_id).await?;
+    let channels =
+        get_user_channels_with_participants(db, &user_context.user_id, query.include_archived)
+            .await?;
 
     let channel_ids: Vec<Uuid> = channels.iter().map(|c| c.channel.id).collect();
 
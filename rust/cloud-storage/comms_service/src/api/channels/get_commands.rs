@@ -0,0 +1,31 @@
+use axum::{Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::service::commands::{CommandInfo, commands};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetCommandsResponse {
+    /// the slash commands messages can start with
+    pub commands: Vec<CommandInfo>,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "get_commands",
+        path = "/channels/commands",
+        responses(
+            (status = 200, body=GetCommandsResponse),
+            (status = 401, body=String),
+        )
+    )]
+#[tracing::instrument]
+pub async fn get_commands_handler() -> (StatusCode, Json<GetCommandsResponse>) {
+    (
+        StatusCode::OK,
+        Json(GetCommandsResponse {
+            commands: commands(),
+        }),
+    )
+}
@@ -0,0 +1,52 @@
+use axum::{Json, extract::State, http::StatusCode};
+use comms_db_client::{messages::get_message_edits::get_message_edits, model::MessageEdit};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelId, ChannelMember, MessageId},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetMessageEditsResponse {
+    /// prior versions of the message, oldest first
+    pub edits: Vec<MessageEdit>,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "get_message_edits",
+        path = "/channels/{channel_id}/message/{message_id}/edits",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("message_id" = String, Path, description = "id of the message")
+        ),
+        responses(
+            (status = 200, body=GetMessageEditsResponse),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn get_message_edits_handler(
+    State(ctx): State<AppState>,
+    _channel_member: ChannelMember,
+    ChannelId(channel_id): ChannelId,
+    MessageId(message_id): MessageId,
+) -> Result<(StatusCode, Json<GetMessageEditsResponse>), (StatusCode, String)> {
+    let edits = get_message_edits(&ctx.db, &channel_id, &message_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to get message edits");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get message edits".to_string(),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "message not found".to_string()))?;
+
+    Ok((StatusCode::OK, Json(GetMessageEditsResponse { edits })))
+}
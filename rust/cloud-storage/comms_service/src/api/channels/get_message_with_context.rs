@@ -1,10 +1,17 @@
-use crate::api::context::AppState;
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelId, ChannelMember},
+};
 use axum::{
     Json,
     extract::{Query, State},
     http::StatusCode,
 };
-use comms_db_client::messages::read_message_with_context::get_messages_with_context;
+use axum_extra::extract::Cached;
+use comms_db_client::{
+    messages::{get_latest_channel_message, read_message_with_context::get_messages_with_context},
+    read_markers::get_first_unread::get_first_unread_message_id,
+};
 use model::comms::GetMessageWithContextResponse;
 use serde::Deserialize;
 use utoipa::ToSchema;
@@ -22,6 +29,16 @@ pub struct GetMessageWithContextParams {
     pub after: i64,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetChannelContextParams {
+    /// Number of messages to fetch before the first unread message
+    #[serde(default)]
+    pub before: i64,
+    /// Number of messages to fetch after the first unread message
+    #[serde(default)]
+    pub after: i64,
+}
+
 #[utoipa::path(
     get,
     path = "/channels/messages/context",
@@ -51,18 +68,87 @@ pub async fn handler(
         "get_message_with_context"
     );
 
+    let messages =
+        messages_with_context(&app_state, &params.message_id, params.before, params.after).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetMessageWithContextResponse {
+            messages,
+            first_unread_message_id: None,
+        }),
+    ))
+}
+
+/// Opens a channel at the first message the user hasn't read. Opens at the latest message when
+/// everything is read.
+#[utoipa::path(
+    get,
+    path = "/channels/{channel_id}/messages/context",
+    tag = "channels",
+    operation_id = "get_channel_message_context",
+    params(
+        ("channel_id" = String, Path, description = "ID of the channel"),
+        ("before" = i64, Query, description = "Number of messages to fetch before the first unread message (defaults to 0)"),
+        ("after" = i64, Query, description = "Number of messages to fetch after the first unread message (defaults to 0)"),
+    ),
+    responses(
+        (status = 200, body = GetMessageWithContextResponse, description = "Successfully retrieved messages with context"),
+        (status = 400, body = String, description = "Invalid request parameters"),
+        (status = 401, body = String, description = "Unauthorized"),
+        (status = 404, body = String, description = "Channel has no messages"),
+        (status = 500, body = String, description = "Internal server error"),
+    )
+)]
+#[tracing::instrument(skip(app_state, channel_member), fields(user_id=?channel_member.context.user_id))]
+pub async fn channel_handler(
+    State(app_state): State<AppState>,
+    ChannelMember(channel_member): ChannelMember,
+    Cached(ChannelId(channel_id)): Cached<ChannelId>,
+    Query(params): Query<GetChannelContextParams>,
+) -> Result<(StatusCode, Json<GetMessageWithContextResponse>), (StatusCode, String)> {
     let ctx = &app_state;
 
-    let db_messages =
-        get_messages_with_context(&ctx.db, &params.message_id, params.before, params.after)
+    let first_unread_message_id =
+        get_first_unread_message_id(&ctx.db, &channel_member.context.user_id, &channel_id)
             .await
             .map_err(|err| {
-                tracing::error!(error = ?err, "unable to get messages with context");
+                tracing::error!(error = ?err, "unable to get first unread message");
                 (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
             })?;
 
+    let message_id = match first_unread_message_id {
+        Some(first_unread) => first_unread,
+        // nothing is unread, open at the latest message
+        None => latest_message(ctx, channel_id).await?,
+    };
+
+    let messages = messages_with_context(ctx, &message_id, params.before, params.after).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetMessageWithContextResponse {
+            messages,
+            first_unread_message_id,
+        }),
+    ))
+}
+
+async fn messages_with_context(
+    ctx: &AppState,
+    message_id: &Uuid,
+    before: i64,
+    after: i64,
+) -> Result<Vec<model::comms::Message>, (StatusCode, String)> {
+    let db_messages = get_messages_with_context(&ctx.db, message_id, before, after)
+        .await
+        .map_err(|err| {
+            tracing::error!(error = ?err, "unable to get messages with context");
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        })?;
+
     // Convert from comms_db_client::model::Message to model::comms::Message
-    let messages = db_messages
+    Ok(db_messages
         .into_iter()
         .map(|m| model::comms::Message {
             id: m.id,
@@ -75,10 +161,17 @@ pub async fn handler(
             edited_at: m.edited_at,
             deleted_at: m.deleted_at,
         })
-        .collect();
+        .collect())
+}
 
-    Ok((
-        StatusCode::OK,
-        Json(GetMessageWithContextResponse { messages }),
-    ))
+async fn latest_message(ctx: &AppState, channel_id: Uuid) -> Result<Uuid, (StatusCode, String)> {
+    get_latest_channel_message(&ctx.db, channel_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error = ?err, "unable to get latest message");
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        })?
+        .latest_non_thread_message
+        .map(|message| message.message_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "channel has no messages".to_string()))
 }
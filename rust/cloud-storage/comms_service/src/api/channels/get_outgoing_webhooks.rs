@@ -0,0 +1,53 @@
+use axum::{Json, extract::State, http::StatusCode};
+use comms_db_client::{
+    bots::outgoing_webhooks::get_channel_outgoing_webhooks, model::OutgoingWebhook,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelAdmin, ChannelId},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetOutgoingWebhooksResponse {
+    /// the webhooks the channel's events are delivered to, with their delivery status
+    pub webhooks: Vec<OutgoingWebhook>,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "get_outgoing_webhooks",
+        path = "/channels/{channel_id}/outgoing_webhooks",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        responses(
+            (status = 200, body=GetOutgoingWebhooksResponse),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn get_outgoing_webhooks_handler(
+    State(ctx): State<AppState>,
+    _channel_admin: ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+) -> Result<(StatusCode, Json<GetOutgoingWebhooksResponse>), (StatusCode, String)> {
+    let webhooks = get_channel_outgoing_webhooks(&ctx.db, &channel_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to get outgoing webhooks");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get outgoing webhooks".to_string(),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetOutgoingWebhooksResponse { webhooks }),
+    ))
+}
@@ -0,0 +1,48 @@
+use axum::{Json, extract::State, http::StatusCode};
+use comms_db_client::{model::PinnedMessage, moderation::pins::get_pinned_messages};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelId, ChannelMember},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetPinsResponse {
+    /// pinned messages, most recently pinned first
+    pub pins: Vec<PinnedMessage>,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "get_pins",
+        path = "/channels/{channel_id}/pins",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        responses(
+            (status = 200, body=GetPinsResponse),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn get_pins_handler(
+    State(ctx): State<AppState>,
+    _channel_member: ChannelMember,
+    ChannelId(channel_id): ChannelId,
+) -> Result<(StatusCode, Json<GetPinsResponse>), (StatusCode, String)> {
+    let pins = get_pinned_messages(&ctx.db, &channel_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to get pinned messages");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get pinned messages".to_string(),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(GetPinsResponse { pins })))
+}
@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+};
+use comms_db_client::{model::ChannelUnread, read_markers::get_unread_counts::get_unread_counts};
+use model::user::UserContext;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::context::AppState;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetUnreadResponse {
+    /// unread counts of every channel the user is in
+    pub channels: Vec<ChannelUnread>,
+}
+
+#[utoipa::path(
+        get,
+        path = "/channels/unread",
+        tag = "channels",
+        operation_id = "get_unread",
+        responses(
+            (status = 200, body=GetUnreadResponse),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(app_state, user_context), fields(user_id=?user_context.user_id))]
+pub async fn get_unread_handler(
+    State(app_state): State<AppState>,
+    user_context: Extension<UserContext>,
+) -> Result<(StatusCode, Json<GetUnreadResponse>), (StatusCode, String)> {
+    let channels = get_unread_counts(&app_state.db, &user_context.user_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to get unread counts");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get unread counts".to_string(),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(GetUnreadResponse { channels })))
+}
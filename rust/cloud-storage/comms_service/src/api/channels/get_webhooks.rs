@@ -0,0 +1,48 @@
+use axum::{Json, extract::State, http::StatusCode};
+use comms_db_client::{model::IncomingWebhook, webhooks::get_webhooks::get_channel_webhooks};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelId, ChannelMember},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetWebhooksResponse {
+    /// the webhooks that can post to the channel, members use them to show bot messages
+    pub webhooks: Vec<IncomingWebhook>,
+}
+
+#[utoipa::path(
+        get,
+        tag = "channels",
+        operation_id = "get_webhooks",
+        path = "/channels/{channel_id}/webhooks",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        responses(
+            (status = 200, body=GetWebhooksResponse),
+            (status = 401, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn get_webhooks_handler(
+    State(ctx): State<AppState>,
+    _channel_member: ChannelMember,
+    ChannelId(channel_id): ChannelId,
+) -> Result<(StatusCode, Json<GetWebhooksResponse>), (StatusCode, String)> {
+    let webhooks = get_channel_webhooks(&ctx.db, &channel_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to get webhooks");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get webhooks".to_string(),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(GetWebhooksResponse { webhooks })))
+}
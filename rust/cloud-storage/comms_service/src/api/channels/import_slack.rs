@@ -0,0 +1,198 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+};
+use comms_db_client::channels::import_channel::{get_org_user_ids, import_channel};
+use model::comms::ChannelType;
+use model::user::UserContext;
+use models_opensearch::SearchEntityType;
+use serde::{Deserialize, Serialize};
+use sqs_client::search::{SearchQueueMessage, name::EntityName};
+use std::collections::HashSet;
+use tracing::Instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::context::AppState,
+    service::{
+        self,
+        slack_import::{SlackImportError, member_candidates, parse_export, plan_import},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ImportSlackQuery {
+    /// comma separated names of the channels to import, all of them when missing
+    pub channels: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportedChannel {
+    pub id: Uuid,
+    pub name: String,
+    pub message_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportSlackResponse {
+    /// the channels that were created, owned by the importer
+    pub channels: Vec<ImportedChannel>,
+}
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "import_slack",
+        path = "/channels/import/slack",
+        params(
+            ("channels" = Option<String>, Query, description = "comma separated channels to import")
+        ),
+        request_body(
+            content = Vec<u8>,
+            content_type = "application/zip",
+            description = "A Slack workspace export"
+        ),
+        responses(
+            (status = 201, body=ImportSlackResponse),
+            (status = 400, body=String),
+            (status = 401, body=String),
+            (status = 413, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context, body), fields(user_id=?user_context.user_id))]
+pub async fn import_slack_handler(
+    State(ctx): State<AppState>,
+    user_context: Extension<UserContext>,
+    Query(query): Query<ImportSlackQuery>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ImportSlackResponse>), (StatusCode, String)> {
+    if body.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "upload is empty".to_string()));
+    }
+
+    let only: Option<Vec<String>> = query.channels.map(|channels| {
+        channels
+            .split(',')
+            .map(|name| name.trim().trim_start_matches('#').to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+
+    // unpacking the archive is cpu bound
+    let export = tokio::task::spawn_blocking(move || parse_export(&body))
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to read slack export");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to read slack export".to_string(),
+            )
+        })?
+        .map_err(import_error)?;
+
+    // the export is uploaded by the importer, so its emails only name members who are known to
+    // share the importer's organization
+    let candidates = member_candidates(&export);
+    let mut verified: HashSet<String> = match user_context.organization_id {
+        Some(org_id) => get_org_user_ids(&ctx.db, org_id, &candidates)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to verify slack export members");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to verify slack export members".to_string(),
+                )
+            })?
+            .into_iter()
+            .collect(),
+        None => HashSet::new(),
+    };
+    if candidates.contains(&user_context.user_id) {
+        verified.insert(user_context.user_id.clone());
+    }
+
+    let owner_id = user_context.user_id.clone();
+    let plans = tokio::task::spawn_blocking(move || {
+        plan_import(export, only.as_deref(), &owner_id, &verified)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to plan slack import");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to read slack export".to_string(),
+        )
+    })?
+    .map_err(import_error)?;
+
+    let mut channels = Vec::with_capacity(plans.len());
+    for plan in plans {
+        let name = plan.name.clone();
+        let channel_type = plan.channel_type;
+        let participants = plan.participants.clone();
+        let message_ids: Vec<Uuid> = plan.messages.iter().map(|m| m.id).collect();
+
+        let id = import_channel(&ctx.db, plan).await.map_err(|e| {
+            tracing::error!(error=?e, channel=%name, "unable to import channel");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("unable to import channel {name}"),
+            )
+        })?;
+
+        if channel_type == ChannelType::Private && !participants.is_empty() {
+            ctx.sqs_client
+                .enqueue_contacts_create_channel(participants, &id.to_string())
+                .await
+                .inspect_err(|e| {
+                    tracing::error!(error=?e, "unable to create 'add participant' SQS message");
+                })
+                .ok();
+        }
+
+        tokio::spawn({
+            let sqs_client = ctx.sqs_client.clone();
+            async move {
+                let _ = sqs_client
+                    .send_message_to_search_event_queue(SearchQueueMessage::UpdateEntityName(
+                        EntityName {
+                            entity_id: id,
+                            entity_type: SearchEntityType::Channels,
+                        },
+                    ))
+                    .await
+                    .inspect_err(|e| {
+                        tracing::error!(error=?e, "SEARCH_QUEUE unable to enqueue message");
+                    });
+            }
+            .in_current_span()
+        });
+        for message_id in &message_ids {
+            service::search::send_channel_message_to_search_extractor_queue(
+                &ctx.sqs_client,
+                id,
+                message_id,
+            );
+        }
+
+        channels.push(ImportedChannel {
+            id,
+            name,
+            message_count: message_ids.len(),
+        });
+    }
+
+    Ok((StatusCode::CREATED, Json(ImportSlackResponse { channels })))
+}
+
+fn import_error(e: SlackImportError) -> (StatusCode, String) {
+    let status = match e {
+        SlackImportError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    (status, e.to_string())
+}
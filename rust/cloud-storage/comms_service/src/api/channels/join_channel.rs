@@ -1,4 +1,10 @@
-use crate::api::extractors::{ChannelId, ChannelTypeExtractor};
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelId, ChannelTypeExtractor},
+    },
+    service::bots::events::{self, BotEvent, MemberJoined},
+};
 use anyhow::Result;
 use axum::{
     extract::{Extension, State},
@@ -8,7 +14,6 @@ use axum_extra::extract::Cached;
 use comms_db_client::participants::add_participant::{AddParticipantOptions, add_participant};
 use model::comms::{ChannelType, ParticipantRole};
 use model::user::UserContext;
-use sqlx::PgPool;
 
 #[utoipa::path(
     post,
@@ -27,11 +32,11 @@ use sqlx::PgPool;
     )
 )]
 #[tracing::instrument(
-    skip(db),
+    skip(ctx),
     fields(user_id=?user_ctx.user_id)
 )]
 pub async fn join_channel_handler(
-    State(db): State<PgPool>,
+    State(ctx): State<AppState>,
     Cached(ChannelTypeExtractor(channel_type)): Cached<ChannelTypeExtractor>,
     Cached(ChannelId(channel_id)): Cached<ChannelId>,
     user_ctx: Extension<UserContext>,
@@ -45,7 +50,7 @@ pub async fn join_channel_handler(
     }
 
     add_participant(
-        &db,
+        &ctx.db,
         AddParticipantOptions {
             channel_id: &channel_id,
             user_id: &user_ctx.user_id,
@@ -61,5 +66,13 @@ pub async fn join_channel_handler(
         )
     })?;
 
+    events::dispatch_event(
+        &ctx,
+        channel_id,
+        BotEvent::MemberJoined(MemberJoined {
+            user_ids: vec![user_ctx.user_id.clone()],
+        }),
+    );
+
     Ok(StatusCode::OK)
 }
@@ -0,0 +1,73 @@
+use axum::{
+    Json,
+    extract::{self, State},
+    http::StatusCode,
+};
+use comms_db_client::{model::ReadMarker, read_markers::mark_read::mark_read};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelId, ChannelMember},
+    },
+    service::sender::notify::notify_read_marker,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MarkReadRequest {
+    /// the last message read, a thread reply marks its thread read
+    pub message_id: Uuid,
+}
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "mark_read",
+        path = "/channels/{channel_id}/read",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        request_body = MarkReadRequest,
+        responses(
+            (status = 200, body=ReadMarker),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn mark_read_handler(
+    State(ctx): State<AppState>,
+    ChannelMember(channel_member): ChannelMember,
+    ChannelId(channel_id): ChannelId,
+    extract::Json(req): extract::Json<MarkReadRequest>,
+) -> Result<(StatusCode, Json<ReadMarker>), (StatusCode, String)> {
+    let marker = mark_read(
+        &ctx.db,
+        &channel_member.context.user_id,
+        &channel_id,
+        &req.message_id,
+    )
+    .await
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to mark read");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to mark read".to_string(),
+        )
+    })?
+    .ok_or_else(|| (StatusCode::NOT_FOUND, "message not found".to_string()))?;
+
+    // the marker is already saved, the user's other devices catch up on their next fetch
+    notify_read_marker(&ctx, &marker)
+        .await
+        .inspect_err(|err| {
+            tracing::error!(error=?err, "unable to notify read marker");
+        })
+        .ok();
+
+    Ok((StatusCode::OK, Json(marker)))
+}
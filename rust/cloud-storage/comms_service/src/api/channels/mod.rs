@@ -1,33 +1,54 @@
 use axum::{
     Router,
-    routing::{delete, get, patch, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, patch, post, put},
 };
 use macro_axum_utils::compose_layers;
 
 pub mod add_participants;
+pub mod archive_channel;
 pub mod create_channel;
+pub mod create_outgoing_webhook;
+pub mod create_webhook;
 pub mod delete_channel;
 pub mod delete_message;
+pub mod export_channel;
 pub mod get_channel;
 pub mod get_channel_metadata;
 pub mod get_channel_transcript;
 pub mod get_channels;
+pub mod get_commands;
 pub mod get_mentions;
+pub mod get_message_edits;
 pub mod get_message_with_context;
 pub mod get_or_create_dm;
 pub mod get_or_create_private;
+pub mod get_outgoing_webhooks;
+pub mod get_pins;
+pub mod get_unread;
+pub mod get_webhooks;
+pub mod import_slack;
 pub mod join_channel;
 pub mod leave_channel;
+pub mod lock_thread;
+pub mod mark_read;
 pub mod patch_channel;
 pub mod patch_message;
+pub mod pin_message;
 pub mod post_message;
 pub mod post_reaction;
 pub mod post_typing;
 pub mod remove_participants;
+pub mod revoke_outgoing_webhook;
+pub mod revoke_webhook;
+pub mod set_slow_mode;
 use crate::api::context::AppState;
 
 use tower_http::compression::CompressionLayer;
 
+/// The largest Slack export that can be imported in one upload
+const MAX_IMPORT_BYTES: usize = 100 * 1024 * 1024;
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", post(create_channel::create_channel_handler))
@@ -92,4 +113,76 @@ pub fn router() -> Router<AppState> {
             get(get_channel_transcript::handler_external),
         )
         .route("/messages/context", get(get_message_with_context::handler))
+        .route(
+            "/:channel_id/messages/context",
+            get(get_message_with_context::channel_handler),
+        )
+        .route("/unread", get(get_unread::get_unread_handler))
+        .route("/:channel_id/read", post(mark_read::mark_read_handler))
+        .route(
+            "/:channel_id/message/:message_id/edits",
+            get(get_message_edits::get_message_edits_handler),
+        )
+        .route(
+            "/:channel_id/message/:message_id/pin",
+            post(pin_message::pin_message_handler),
+        )
+        .route(
+            "/:channel_id/message/:message_id/pin",
+            delete(pin_message::unpin_message_handler),
+        )
+        .route("/:channel_id/pins", get(get_pins::get_pins_handler))
+        .route(
+            "/:channel_id/message/:message_id/lock",
+            post(lock_thread::lock_thread_handler),
+        )
+        .route(
+            "/:channel_id/message/:message_id/lock",
+            delete(lock_thread::unlock_thread_handler),
+        )
+        .route(
+            "/:channel_id/slow_mode",
+            put(set_slow_mode::set_slow_mode_handler),
+        )
+        .route(
+            "/:channel_id/webhooks",
+            post(create_webhook::create_webhook_handler),
+        )
+        .route(
+            "/:channel_id/webhooks",
+            get(get_webhooks::get_webhooks_handler),
+        )
+        .route(
+            "/:channel_id/webhooks/:webhook_id",
+            delete(revoke_webhook::revoke_webhook_handler),
+        )
+        .route(
+            "/:channel_id/outgoing_webhooks",
+            post(create_outgoing_webhook::create_outgoing_webhook_handler),
+        )
+        .route(
+            "/:channel_id/outgoing_webhooks",
+            get(get_outgoing_webhooks::get_outgoing_webhooks_handler),
+        )
+        .route(
+            "/:channel_id/outgoing_webhooks/:webhook_id",
+            delete(revoke_outgoing_webhook::revoke_outgoing_webhook_handler),
+        )
+        .route("/commands", get(get_commands::get_commands_handler))
+        .route(
+            "/:channel_id/archive",
+            post(archive_channel::archive_channel_handler),
+        )
+        .route(
+            "/:channel_id/archive",
+            delete(archive_channel::unarchive_channel_handler),
+        )
+        .route(
+            "/:channel_id/export",
+            get(export_channel::export_channel_handler),
+        )
+        .route(
+            "/import/slack",
+            post(import_slack::import_slack_handler).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
 }
@@ -1,6 +1,9 @@
-use crate::api::{
-    context::AppState,
-    extractors::{ChannelId, ChannelOwner, ChannelTypeExtractor},
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelId, ChannelOwner, ChannelTypeExtractor},
+    },
+    service::moderation,
 };
 use anyhow::Result;
 use axum::{Json, extract::State, http::StatusCode};
@@ -20,6 +23,7 @@ use tracing::Instrument;
         ),
         responses(
             (status = 200, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
             (status = 500, body=String),
         )
     )]
@@ -38,6 +42,8 @@ pub async fn patch_channel_handler(
         ));
     }
 
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
     let name = req.channel_name.clone();
 
     patch_channel::patch_channel(&ctx.db, &channel_id, &channel_owner.context.user_id, req)
@@ -44,6 +44,7 @@ pub struct PatchMessageParams {
         responses(
             (status = 201, body=String),
             (status = 401, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
             (status = 404, body=String),
             (status = 500, body=String),
         )
@@ -60,6 +61,8 @@ pub async fn patch_message_handler(
 ) -> Result<(StatusCode, String), (StatusCode, String)> {
     tracing::info!("patch_message");
 
+    service::moderation::ensure_not_archived(&app_state, &channel_id).await?;
+
     if let Some(attachment_ids) = &req.attachment_ids_to_delete
         && !attachment_ids.is_empty()
     {
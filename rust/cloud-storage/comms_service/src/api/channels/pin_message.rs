@@ -0,0 +1,109 @@
+use axum::{Json, extract::State, http::StatusCode};
+use comms_db_client::{
+    model::Message,
+    moderation::{actions::NewModerationAction, pins::set_pinned},
+};
+use model::comms::ModerationActionType;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelAdmin, ChannelId, MessageId},
+    },
+    service::moderation,
+};
+
+#[utoipa::path(
+        post,
+        tag = "channels",
+        operation_id = "pin_message",
+        path = "/channels/{channel_id}/message/{message_id}/pin",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("message_id" = String, Path, description = "id of the message")
+        ),
+        responses(
+            (status = 200, body=Message),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn pin_message_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+    MessageId(message_id): MessageId,
+) -> Result<(StatusCode, Json<Message>), (StatusCode, String)> {
+    set_pin(&ctx, &admin.context.user_id, channel_id, message_id, true).await
+}
+
+#[utoipa::path(
+        delete,
+        tag = "channels",
+        operation_id = "unpin_message",
+        path = "/channels/{channel_id}/message/{message_id}/pin",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("message_id" = String, Path, description = "id of the message")
+        ),
+        responses(
+            (status = 200, body=Message),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn unpin_message_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+    MessageId(message_id): MessageId,
+) -> Result<(StatusCode, Json<Message>), (StatusCode, String)> {
+    set_pin(&ctx, &admin.context.user_id, channel_id, message_id, false).await
+}
+
+async fn set_pin(
+    ctx: &AppState,
+    user_id: &str,
+    channel_id: Uuid,
+    message_id: Uuid,
+    pinned: bool,
+) -> Result<(StatusCode, Json<Message>), (StatusCode, String)> {
+    moderation::ensure_not_archived(ctx, &channel_id).await?;
+
+    let message = set_pinned(&ctx.db, &channel_id, &message_id, pinned.then_some(user_id))
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to pin message");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to pin message".to_string(),
+            )
+        })?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "message not found".to_string()))?;
+
+    moderation::record_and_notify(
+        ctx,
+        NewModerationAction {
+            channel_id,
+            actor_id: user_id,
+            action: if pinned {
+                ModerationActionType::PinMessage
+            } else {
+                ModerationActionType::UnpinMessage
+            },
+            target_message_id: Some(message.id),
+            target_user_id: Some(&message.sender_id),
+            details: None,
+        },
+    )
+    .await;
+
+    Ok((StatusCode::OK, Json(message)))
+}
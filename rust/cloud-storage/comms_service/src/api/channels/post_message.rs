@@ -4,6 +4,9 @@ use crate::{
     api::extractors::{ChannelId, ChannelMember, ChannelParticipants, ChannelTypeExtractor},
     service::{
         self,
+        bots::events::{self, BotEvent},
+        commands::{self, CommandInvocation, CommandOutcome},
+        moderation,
         sender::notify::{self, AttachmentUpdate},
     },
 };
@@ -20,6 +23,7 @@ use comms_db_client::{
     channels::updated_at,
     messages::{add_attachments, create_message, create_message_mentions},
     model::{ActivityType, NewAttachment, SimpleMention},
+    read_markers::mark_read::mark_read,
 };
 use model::comms::ChannelParticipant;
 use model::document_storage_service_internal::UpdateChannelSharePermissionRequest;
@@ -39,7 +43,11 @@ pub struct PostMessageRequest {
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PostMessageResponse {
+    /// id of the message, or of the command run when a slash command replied instead of posting
     pub id: String,
+    /// the reply of a slash command, shown only to the member who ran it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_reply: Option<String>,
 }
 
 #[utoipa::path(
@@ -52,8 +60,12 @@ pub struct PostMessageResponse {
         ),
         responses(
             (status = 201, body=PostMessageResponse),
+            (status = 202, body=PostMessageResponse, description = "A slash command replied"),
+            (status = 400, body=String, description = "Invalid slash command arguments"),
             (status = 401, body=String),
+            (status = 403, body=String, description = "The channel is archived or thread locked"),
             (status = 404, body=String),
+            (status = 429, body=String, description = "Slow mode is on"),
             (status = 500, body=String),
         )
     )]
@@ -67,6 +79,43 @@ pub async fn post_message_handler(
     Cached(ChannelTypeExtractor(channel_type)): Cached<ChannelTypeExtractor>,
     extract::Json(req): extract::Json<PostMessageRequest>,
 ) -> Result<(StatusCode, Json<PostMessageResponse>), (StatusCode, String)> {
+    moderation::ensure_can_post(
+        &ctx,
+        &channel_id,
+        &channel_member.context.user_id,
+        channel_member.role,
+        req.thread_id.as_ref(),
+    )
+    .await?;
+
+    // a registered slash command either rewrites the message or replies in its place
+    let mut content = req.content.clone();
+    if let Some((command, args)) = commands::parse_command(&req.content) {
+        let outcome = command
+            .run(
+                &ctx,
+                CommandInvocation {
+                    channel_id,
+                    thread_id: req.thread_id,
+                    user_id: &channel_member.context.user_id,
+                    args,
+                },
+            )
+            .await?;
+        match outcome {
+            CommandOutcome::Post(command_content) => content = command_content,
+            CommandOutcome::Reply(reply) => {
+                return Ok((
+                    StatusCode::ACCEPTED,
+                    Json(PostMessageResponse {
+                        id: Uuid::now_v7().to_string(),
+                        command_reply: Some(reply),
+                    }),
+                ));
+            }
+        }
+    }
+
     let mut connection = ctx.db.acquire().await.map_err(|e| {
         tracing::error!(error=?e, "unable to acquire connection");
         (
@@ -80,7 +129,7 @@ pub async fn post_message_handler(
         create_message::CreateMessageOptions {
             channel_id,
             sender_id: channel_member.context.user_id.clone(),
-            content: req.content.clone(),
+            content,
             thread_id: req.thread_id,
         },
     )
@@ -237,6 +286,24 @@ pub async fn post_message_handler(
     .ok();
     tracing::debug!("activity upsert took {:?}ms", start_time.elapsed());
 
+    // the sender has read everything up to their own message, a reply marks its thread
+    let start_time = Instant::now();
+    mark_read(
+        &ctx.db,
+        &channel_member.context.user_id,
+        &channel_id,
+        &message.id,
+    )
+    .await
+    .inspect_err(|err| {
+        tracing::error!(error=?err, "unable to mark message read");
+    })
+    .ok();
+    tracing::debug!("read marker took {:?}ms", start_time.elapsed());
+
+    // the attachments are moved into the message below
+    let attachment_entities = req.attachments.clone();
+
     let start_time = Instant::now();
     let maybe_attachments = add_attachments::add_attachments_to_message(
         &ctx.db,
@@ -294,10 +361,21 @@ pub async fn post_message_handler(
         message.id,
     );
 
+    events::dispatch_event(&ctx, channel_id, BotEvent::MessagePosted(message.clone()));
+
+    service::contacts::record_message_interactions(
+        &ctx.sqs_client,
+        &message.sender_id,
+        &participants,
+        &req.mentions,
+        &attachment_entities,
+    );
+
     Ok((
         StatusCode::OK,
         Json(PostMessageResponse {
             id: message.id.to_string(),
+            command_reply: None,
         }),
     ))
 }
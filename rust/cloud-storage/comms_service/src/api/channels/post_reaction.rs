@@ -11,7 +11,11 @@ use crate::{
         context::AppState,
         extractors::{ChannelId, ChannelMember},
     },
-    service::sender::notify::{ReactionUpdate, notify_reactions},
+    service::{
+        bots::events::{self, BotEvent, ReactionAdded},
+        moderation,
+        sender::notify::{ReactionUpdate, notify_reactions},
+    },
 };
 
 use comms_db_client::{
@@ -50,6 +54,7 @@ pub struct PostReactionRequest {
         responses(
             (status = 201, body=String),
             (status = 401, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
             (status = 404, body=String),
             (status = 500, body=String),
         )
@@ -66,6 +71,13 @@ pub async fn post_reaction_handler(
         (StatusCode::BAD_REQUEST, err.to_string())
     })?;
 
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
+    let added = match req.action {
+        ReactionAction::Add => Some(req.emoji.clone()),
+        ReactionAction::Remove => None,
+    };
+
     let req = match req.action {
         ReactionAction::Add => {
             add_reaction(
@@ -115,6 +127,18 @@ pub async fn post_reaction_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
     })?;
 
+    if let Some(emoji) = added {
+        events::dispatch_event(
+            &ctx,
+            channel_id,
+            BotEvent::ReactionAdded(ReactionAdded {
+                message_id,
+                user_id: channel_member.context.user_id.clone(),
+                emoji,
+            }),
+        );
+    }
+
     tokio::spawn(async move {
         upsert_activity(
             &ctx.db,
@@ -1,5 +1,6 @@
 use crate::api::context::AppState;
 use crate::api::extractors::{ChannelAdmin, ChannelId, ChannelTypeExtractor};
+use crate::service::moderation;
 use anyhow::Result;
 use axum::extract::Json;
 use axum::{extract::State, http::StatusCode};
@@ -31,6 +32,7 @@ pub struct RemoveParticipantsRequest {
         (status = 200),
         (status = 401, body=String),
         (status = 404, body=String),
+        (status = 403, body=String, description = "The channel is archived"),
         (status = 500, body=String),
     )
 )]
@@ -50,6 +52,8 @@ pub async fn handler(
         ));
     }
 
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
     for participant in req.participants.iter() {
         remove_participant(
             &ctx.db,
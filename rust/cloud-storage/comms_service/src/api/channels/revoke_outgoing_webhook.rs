@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use comms_db_client::bots::outgoing_webhooks::revoke_outgoing_webhook;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelAdmin, ChannelId},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RevokeOutgoingWebhookParams {
+    pub channel_id: Uuid,
+    pub webhook_id: Uuid,
+}
+
+#[utoipa::path(
+        delete,
+        tag = "channels",
+        operation_id = "revoke_outgoing_webhook",
+        path = "/channels/{channel_id}/outgoing_webhooks/{webhook_id}",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("webhook_id" = String, Path, description = "id of the outgoing webhook")
+        ),
+        responses(
+            (status = 200),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn revoke_outgoing_webhook_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+    Path(params): Path<RevokeOutgoingWebhookParams>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let revoked = revoke_outgoing_webhook(
+        &ctx.db,
+        &channel_id,
+        &params.webhook_id,
+        &admin.context.user_id,
+    )
+    .await
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to revoke outgoing webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to revoke outgoing webhook".to_string(),
+        )
+    })?;
+
+    if !revoked {
+        return Err((StatusCode::NOT_FOUND, "webhook not found".to_string()));
+    }
+
+    Ok(StatusCode::OK)
+}
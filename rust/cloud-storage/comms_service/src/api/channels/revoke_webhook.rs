@@ -0,0 +1,62 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use comms_db_client::{model::IncomingWebhook, webhooks::revoke_webhook::revoke_webhook};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::{
+    context::AppState,
+    extractors::{ChannelAdmin, ChannelId},
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RevokeWebhookParams {
+    pub channel_id: Uuid,
+    pub webhook_id: Uuid,
+}
+
+#[utoipa::path(
+        delete,
+        tag = "channels",
+        operation_id = "revoke_webhook",
+        path = "/channels/{channel_id}/webhooks/{webhook_id}",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel"),
+            ("webhook_id" = String, Path, description = "id of the webhook")
+        ),
+        responses(
+            (status = 200, body=IncomingWebhook),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn revoke_webhook_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+    Path(params): Path<RevokeWebhookParams>,
+) -> Result<(StatusCode, Json<IncomingWebhook>), (StatusCode, String)> {
+    let webhook = revoke_webhook(
+        &ctx.db,
+        &channel_id,
+        &params.webhook_id,
+        &admin.context.user_id,
+    )
+    .await
+    .map_err(|err| {
+        tracing::error!(error=?err, "unable to revoke webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to revoke webhook".to_string(),
+        )
+    })?
+    .ok_or_else(|| (StatusCode::NOT_FOUND, "webhook not found".to_string()))?;
+
+    Ok((StatusCode::OK, Json(webhook)))
+}
@@ -0,0 +1,88 @@
+use axum::{
+    extract::{self, State},
+    http::StatusCode,
+};
+use comms_db_client::moderation::{
+    actions::NewModerationAction,
+    slow_mode::{MAX_SLOW_MODE_SECONDS, set_slow_mode},
+};
+use model::comms::ModerationActionType;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        context::AppState,
+        extractors::{ChannelAdmin, ChannelId},
+    },
+    service::moderation,
+};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetSlowModeRequest {
+    /// the seconds members wait between messages, 0 turns slow mode off
+    pub seconds: i32,
+}
+
+#[utoipa::path(
+        put,
+        tag = "channels",
+        operation_id = "set_slow_mode",
+        path = "/channels/{channel_id}/slow_mode",
+        params(
+            ("channel_id" = String, Path, description = "id of the channel")
+        ),
+        request_body = SetSlowModeRequest,
+        responses(
+            (status = 200, body=String),
+            (status = 400, body=String),
+            (status = 401, body=String),
+            (status = 404, body=String),
+            (status = 403, body=String, description = "The channel is archived"),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx))]
+pub async fn set_slow_mode_handler(
+    State(ctx): State<AppState>,
+    ChannelAdmin(admin): ChannelAdmin,
+    ChannelId(channel_id): ChannelId,
+    extract::Json(req): extract::Json<SetSlowModeRequest>,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    if !(0..=MAX_SLOW_MODE_SECONDS).contains(&req.seconds) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("slow mode must be between 0 and {MAX_SLOW_MODE_SECONDS} seconds"),
+        ));
+    }
+
+    moderation::ensure_not_archived(&ctx, &channel_id).await?;
+
+    let updated = set_slow_mode(&ctx.db, &channel_id, req.seconds)
+        .await
+        .map_err(|err| {
+            tracing::error!(error=?err, "unable to set slow mode");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to set slow mode".to_string(),
+            )
+        })?;
+    if !updated {
+        return Err((StatusCode::NOT_FOUND, "channel not found".to_string()));
+    }
+
+    moderation::record_and_notify(
+        &ctx,
+        NewModerationAction {
+            channel_id,
+            actor_id: &admin.context.user_id,
+            action: ModerationActionType::SetSlowMode,
+            target_message_id: None,
+            target_user_id: None,
+            details: Some(serde_json::json!({ "slow_mode_seconds": req.seconds })),
+        },
+    )
+    .await;
+
+    Ok((StatusCode::OK, "slow mode set".to_string()))
+}
@@ -26,4 +26,6 @@ pub struct AppState {
     pub auth_service_client: Arc<authentication_service_client::AuthServiceClient>,
     pub permissions_token_secret: LocalOrRemoteSecret<DocumentPermissionJwtSecretKey>,
     pub frecency_storage: FrecencyPgStorage,
+    /// delivers channel events to outgoing webhooks
+    pub outgoing_webhook_fetcher: outbound_http::Fetcher,
 }
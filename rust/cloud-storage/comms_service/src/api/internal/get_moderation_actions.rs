@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Json, Response},
+};
+use comms_db_client::moderation::actions::get_org_actions;
+use model::{
+    comms::{GetModerationActionsRequest, GetModerationActionsResponse},
+    response::ErrorResponse,
+};
+use reqwest::StatusCode;
+
+use crate::api::context::AppState;
+
+/// The page size when the caller doesn't give one
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Gets the moderation actions taken in an organization's channels, for its admins to review
+#[tracing::instrument(skip(ctx))]
+pub async fn handler(
+    State(ctx): State<AppState>,
+    Query(req): Query<GetModerationActionsRequest>,
+) -> Result<Response, Response> {
+    tracing::info!("get_moderation_actions");
+
+    let actions = get_org_actions(
+        &ctx.db,
+        req.org_id,
+        req.before,
+        req.limit.unwrap_or(DEFAULT_LIMIT),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to get moderation actions");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: &e.to_string(),
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetModerationActionsResponse { actions }),
+    )
+        .into_response())
+}
@@ -8,6 +8,7 @@ pub mod get_channel_mentions;
 mod get_channel_message;
 mod get_channel_participants;
 mod get_channels_history;
+mod get_moderation_actions;
 mod get_user_channel_ids;
 pub mod remove_user_from_org_channels;
 
@@ -64,6 +65,7 @@ pub fn router(app_state: AppState) -> Router<AppState> {
             "/delete_mentions_by_source",
             delete(delete_mentions_by_source::handler),
         )
+        .route("/moderation_actions", get(get_moderation_actions::handler))
         .route("/health", get(async move || "healthy"))
         .layer(compose_layers![
             from_fn_with_state(app_state.clone(), auth::internal_access::handler),
@@ -6,7 +6,7 @@ use utoipa_swagger_ui::SwaggerUi;
 
 mod activity;
 mod attachments;
-mod channels;
+pub mod channels;
 pub mod context;
 mod extractors;
 mod health;
@@ -15,6 +15,7 @@ mod mentions;
 mod middleware;
 mod preview;
 mod swagger;
+mod webhooks;
 
 type Service = IntoMakeService<Router>;
 
@@ -32,6 +33,7 @@ pub fn service(app_state: AppState) -> Service {
             middleware::decode_jwt,
         ))
         .nest("/internal", internal::router(app_state.clone()))
+        .nest("/webhooks", webhooks::router())
         .with_state(app_state)
         .merge(health::router().layer(cors.clone()))
         .merge(SwaggerUi::new("/docs").url("/api-doc/openapi.json", swagger::ApiDoc::openapi()))
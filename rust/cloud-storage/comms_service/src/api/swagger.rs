@@ -4,16 +4,30 @@ use crate::api::{
     channels::{
         add_participants::AddParticipantsRequest,
         create_channel::{CreateChannelRequest, CreateChannelResponse},
+        create_outgoing_webhook::{CreateOutgoingWebhookRequest, CreateOutgoingWebhookResponse},
+        create_webhook::{CreateWebhookRequest, CreateWebhookResponse},
         delete_message::DeleteMessageParams,
+        export_channel::ExportFormat,
         get_channel::GetChannelResponse,
         get_channels::GetChannelsResponse,
+        get_commands::GetCommandsResponse,
+        get_message_edits::GetMessageEditsResponse,
         get_or_create_dm::{GetOrCreateDmRequest, GetOrCreateDmResponse},
         get_or_create_private::{GetOrCreatePrivateRequest, GetOrCreatePrivateResponse},
+        get_outgoing_webhooks::GetOutgoingWebhooksResponse,
+        get_pins::GetPinsResponse,
+        get_unread::GetUnreadResponse,
+        get_webhooks::GetWebhooksResponse,
+        import_slack::{ImportSlackResponse, ImportedChannel},
+        mark_read::MarkReadRequest,
         patch_message::{PatchMessageParams, PatchMessageRequest},
         post_message::{PostMessageRequest, PostMessageResponse},
         post_reaction::{PostReactionRequest, ReactionAction},
         post_typing::PostTypingRequest,
         remove_participants::RemoveParticipantsRequest,
+        revoke_outgoing_webhook::RevokeOutgoingWebhookParams,
+        revoke_webhook::RevokeWebhookParams,
+        set_slow_mode::SetSlowModeRequest,
     },
     mentions::{
         CreateEntityMentionRequest, CreateEntityMentionResponse, DeleteEntityMentionRequest,
@@ -25,8 +39,9 @@ use crate::api::{
 use crate::api::extractors::ParticipantAccess;
 use comms_db_client::channels::patch_channel::PatchChannelOptions;
 use comms_db_client::model::{
-    Activity, ActivityType, CountedReaction, EntityMention, Message, NewAttachment, Reaction,
-    SimpleMention,
+    Activity, ActivityType, Attachment, BotEventType, ChannelUnread, CountedReaction,
+    EntityMention, IncomingWebhook, Message, MessageEdit, NewAttachment, OutgoingWebhook,
+    PinnedMessage, Reaction, ReadMarker, SimpleMention, ThreadUnread,
 };
 use model::comms::{
     Channel, ChannelParticipant, ChannelType, ChannelWithLatest, ChannelWithParticipants,
@@ -38,11 +53,18 @@ use model::version::CommunicationServiceApiVersion;
 use utoipa::OpenApi;
 
 use super::channels::{
-    add_participants, create_channel, delete_channel, delete_message, get_channel, get_channels,
-    get_mentions, get_message_with_context, get_or_create_dm, get_or_create_private, join_channel,
-    leave_channel, patch_channel, patch_message, post_message, post_reaction, post_typing,
-    remove_participants,
+    add_participants, archive_channel, create_channel, create_outgoing_webhook, create_webhook,
+    delete_channel, delete_message, export_channel, get_channel, get_channels, get_commands,
+    get_mentions, get_message_edits, get_message_with_context, get_or_create_dm,
+    get_or_create_private, get_outgoing_webhooks, get_pins, get_unread, get_webhooks, import_slack,
+    join_channel, leave_channel, lock_thread, mark_read, patch_channel, patch_message, pin_message,
+    post_message, post_reaction, post_typing, remove_participants, revoke_outgoing_webhook,
+    revoke_webhook, set_slow_mode,
 };
+use super::webhooks::post_webhook_message;
+use crate::service::commands::CommandInfo;
+use crate::service::export::{ChannelExport, ExportedMessage};
+use crate::service::webhooks::{WebhookField, WebhookMessage};
 
 use super::attachments::references;
 use super::attachments::references::GetAttachmentReferencesResponse;
@@ -88,6 +110,28 @@ use super::preview::get_batch_preview;
             delete_mention_handler,
             get_mentions::handler,
             get_message_with_context::handler,
+            get_message_with_context::channel_handler,
+            get_unread::get_unread_handler,
+            mark_read::mark_read_handler,
+            get_message_edits::get_message_edits_handler,
+            pin_message::pin_message_handler,
+            pin_message::unpin_message_handler,
+            get_pins::get_pins_handler,
+            lock_thread::lock_thread_handler,
+            lock_thread::unlock_thread_handler,
+            set_slow_mode::set_slow_mode_handler,
+            create_webhook::create_webhook_handler,
+            get_webhooks::get_webhooks_handler,
+            revoke_webhook::revoke_webhook_handler,
+            post_webhook_message::post_webhook_message_handler,
+            create_outgoing_webhook::create_outgoing_webhook_handler,
+            get_outgoing_webhooks::get_outgoing_webhooks_handler,
+            revoke_outgoing_webhook::revoke_outgoing_webhook_handler,
+            get_commands::get_commands_handler,
+            archive_channel::archive_channel_handler,
+            archive_channel::unarchive_channel_handler,
+            export_channel::export_channel_handler,
+            import_slack::import_slack_handler,
         ),
         components(
             schemas(
@@ -152,6 +196,42 @@ use super::preview::get_batch_preview;
                 ChannelWithParticipants,
                 LatestMessage,
                 GetMessageWithContextResponse,
+
+                GetUnreadResponse,
+                ChannelUnread,
+                ThreadUnread,
+                MarkReadRequest,
+                ReadMarker,
+
+                GetMessageEditsResponse,
+                MessageEdit,
+                GetPinsResponse,
+                PinnedMessage,
+                SetSlowModeRequest,
+
+                CreateWebhookRequest,
+                CreateWebhookResponse,
+                GetWebhooksResponse,
+                RevokeWebhookParams,
+                IncomingWebhook,
+                WebhookMessage,
+                WebhookField,
+
+                CreateOutgoingWebhookRequest,
+                CreateOutgoingWebhookResponse,
+                GetOutgoingWebhooksResponse,
+                RevokeOutgoingWebhookParams,
+                OutgoingWebhook,
+                BotEventType,
+                GetCommandsResponse,
+                CommandInfo,
+
+                ChannelExport,
+                ExportedMessage,
+                ExportFormat,
+                Attachment,
+                ImportSlackResponse,
+                ImportedChannel,
             ),
         ),
         tags(
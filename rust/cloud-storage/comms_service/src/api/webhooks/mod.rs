@@ -0,0 +1,13 @@
+use axum::{Router, routing::post};
+
+use crate::api::context::AppState;
+
+pub mod post_webhook_message;
+
+/// Routes external services call with a webhook token instead of a user's token
+pub fn router() -> Router<AppState> {
+    Router::new().route(
+        "/:webhook_id/:token",
+        post(post_webhook_message::post_webhook_message_handler),
+    )
+}
@@ -0,0 +1,116 @@
+use axum::{
+    Json,
+    extract::{self, Path, State},
+    http::StatusCode,
+};
+use comms_db_client::webhooks::take_send::take_send;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    api::{channels::post_message::PostMessageResponse, context::AppState},
+    service::{
+        bots::{BotMessage, post_bot_message},
+        moderation,
+        webhooks::{
+            MAX_SENDS_PER_WINDOW, RATE_LIMIT_WINDOW_SECONDS, WebhookMessage, hash_token,
+            render_content,
+        },
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct PostWebhookMessageParams {
+    pub webhook_id: Uuid,
+    pub token: String,
+}
+
+#[utoipa::path(
+        post,
+        tag = "webhooks",
+        operation_id = "post_webhook_message",
+        path = "/webhooks/{webhook_id}/{token}",
+        params(
+            ("webhook_id" = String, Path, description = "id of the webhook"),
+            ("token" = String, Path, description = "secret token of the webhook")
+        ),
+        request_body = WebhookMessage,
+        responses(
+            (status = 201, body=PostMessageResponse),
+            (status = 400, body=String),
+            (status = 403, body=String, description = "The channel is archived or thread locked"),
+            (status = 404, body=String, description = "The webhook doesn't exist or was revoked"),
+            (status = 429, body=String),
+            (status = 500, body=String),
+        )
+    )]
+#[tracing::instrument(skip(ctx, params), fields(webhook_id = %params.webhook_id))]
+pub async fn post_webhook_message_handler(
+    State(ctx): State<AppState>,
+    Path(params): Path<PostWebhookMessageParams>,
+    extract::Json(req): extract::Json<WebhookMessage>,
+) -> Result<(StatusCode, Json<PostMessageResponse>), (StatusCode, String)> {
+    let send = take_send(
+        &ctx.db,
+        &params.webhook_id,
+        &hash_token(&params.token),
+        chrono::Duration::seconds(RATE_LIMIT_WINDOW_SECONDS),
+        MAX_SENDS_PER_WINDOW,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to authenticate webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to authenticate webhook".to_string(),
+        )
+    })?
+    .ok_or_else(|| (StatusCode::NOT_FOUND, "webhook not found".to_string()))?;
+
+    // checked before the content is rendered, rate limited sends aren't worth the work
+    if send.rate_limited {
+        let window_ends_at =
+            send.window_started_at + chrono::Duration::seconds(RATE_LIMIT_WINDOW_SECONDS);
+        let seconds = (window_ends_at - chrono::Utc::now()).num_seconds().max(1);
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("rate limited, wait {seconds} seconds"),
+        ));
+    }
+
+    let content = render_content(&req).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let webhook = send.webhook;
+    let channel_id = webhook.channel_id;
+
+    // bots are held to the channel's thread locks and slow mode like any member
+    moderation::ensure_can_post(
+        &ctx,
+        &channel_id,
+        &webhook.bot_id,
+        None,
+        req.thread_id.as_ref(),
+    )
+    .await?;
+
+    let message = post_bot_message(
+        &ctx,
+        BotMessage {
+            channel_id,
+            bot_id: &webhook.bot_id,
+            content,
+            thread_id: req.thread_id,
+            entities: req.entities,
+            mentions: vec![],
+        },
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PostMessageResponse {
+            id: message.id.to_string(),
+            command_reply: None,
+        }),
+    ))
+}
@@ -122,6 +122,11 @@ async fn main() -> anyhow::Result<()> {
         config.connection_gateway_url.clone(),
     );
 
+    // deliveries only reach public addresses and don't follow redirects, so a registered
+    // endpoint can't point them at the internal network
+    let outgoing_webhook_fetcher =
+        outbound_http::Fetcher::new().context("unable to build outgoing webhook fetcher")?;
+
     let listener = TcpListener::bind(format!("0.0.0.0:{}", config.port))
         .await
         .context("failed to bind to port")?;
@@ -132,7 +137,7 @@ async fn main() -> anyhow::Result<()> {
         &config.port
     );
 
-    let service = api::service(AppState {
+    let app_state = AppState {
         jwt_validation_args,
         internal_auth_key: secretsmanager_client::LocalOrRemoteSecret::Local(
             config.internal_auth_key.clone(),
@@ -146,7 +151,12 @@ async fn main() -> anyhow::Result<()> {
         connection_gateway_client: Arc::new(connection_gateway_client),
         permissions_token_secret,
         frecency_storage: FrecencyPgStorage::new(macro_db),
-    });
+        outgoing_webhook_fetcher,
+    };
+
+    service::commands::spawn_reminder_worker(app_state.clone());
+
+    let service = api::service(app_state);
 
     axum::serve(listener, service)
         .await
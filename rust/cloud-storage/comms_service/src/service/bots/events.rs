@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use comms_db_client::{
+    bots::outgoing_webhooks::{DeliveryTarget, get_delivery_targets, record_delivery},
+    model::{BotEventType, Message},
+};
+use outbound_http::{
+    FetchError,
+    webhook::{self, is_retryable},
+};
+use serde::{Deserialize, Serialize};
+use std::{iter::once, time::Duration};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::api::context::AppState;
+
+/// How long to wait before each retry of a failed delivery
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(2),
+    Duration::from_secs(10),
+    Duration::from_secs(60),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+/// A channel event delivered to outgoing webhooks
+pub enum BotEvent {
+    MessagePosted(Message),
+    ReactionAdded(ReactionAdded),
+    MemberJoined(MemberJoined),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionAdded {
+    pub message_id: Uuid,
+    pub user_id: String,
+    pub emoji: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberJoined {
+    pub user_ids: Vec<String>,
+}
+
+impl BotEvent {
+    pub fn event_type(&self) -> BotEventType {
+        match self {
+            BotEvent::MessagePosted(_) => BotEventType::MessagePosted,
+            BotEvent::ReactionAdded(_) => BotEventType::ReactionAdded,
+            BotEvent::MemberJoined(_) => BotEventType::MemberJoined,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BotEventEnvelope<'a> {
+    id: Uuid,
+    channel_id: Uuid,
+    created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a BotEvent,
+}
+
+#[derive(Debug)]
+enum DeliveryError {
+    /// the endpoint may accept the event later
+    Retryable(String),
+    /// the endpoint rejected the event
+    Permanent(String),
+}
+
+/// Delivers an event to the outgoing webhooks of the channel that subscribe to it, in the
+/// background
+#[tracing::instrument(skip(ctx, event))]
+pub fn dispatch_event(ctx: &AppState, channel_id: Uuid, event: BotEvent) {
+    let ctx = ctx.clone();
+    tokio::spawn(
+        async move {
+            let targets = match get_delivery_targets(&ctx.db, &channel_id, event.event_type()).await
+            {
+                Ok(targets) => targets,
+                Err(e) => {
+                    tracing::error!(error=?e, "unable to get delivery targets");
+                    return;
+                }
+            };
+            if targets.is_empty() {
+                return;
+            }
+
+            let envelope = BotEventEnvelope {
+                id: Uuid::now_v7(),
+                channel_id,
+                created_at: Utc::now(),
+                event: &event,
+            };
+            let body = match serde_json::to_vec(&envelope) {
+                Ok(body) => body,
+                Err(e) => {
+                    tracing::error!(error=?e, "unable to serialize bot event");
+                    return;
+                }
+            };
+
+            futures::future::join_all(
+                targets
+                    .iter()
+                    .map(|target| deliver(&ctx, target, &envelope.id, &body)),
+            )
+            .await;
+        }
+        .in_current_span(),
+    );
+}
+
+/// Delivers an event to one webhook, retrying with backoff, and records the outcome
+#[tracing::instrument(skip(ctx, target, body), fields(webhook_id = %target.id))]
+async fn deliver(ctx: &AppState, target: &DeliveryTarget, event_id: &Uuid, body: &[u8]) {
+    let mut error = None;
+    for (attempt, delay) in once(Duration::ZERO).chain(RETRY_DELAYS).enumerate() {
+        tokio::time::sleep(delay).await;
+        match send(&ctx.outgoing_webhook_fetcher, target, event_id, body).await {
+            Ok(()) => {
+                error = None;
+                break;
+            }
+            Err(DeliveryError::Permanent(e)) => {
+                error = Some(e);
+                break;
+            }
+            Err(DeliveryError::Retryable(e)) => {
+                tracing::warn!(attempt, error = e, "bot event delivery failed");
+                error = Some(e);
+            }
+        }
+    }
+
+    record_delivery(&ctx.db, &target.id, error.as_deref())
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error=?e, "unable to record delivery");
+        })
+        .ok();
+}
+
+async fn send(
+    fetcher: &outbound_http::Fetcher,
+    target: &DeliveryTarget,
+    event_id: &Uuid,
+    body: &[u8],
+) -> Result<(), DeliveryError> {
+    let status = webhook::deliver(fetcher, &target.url, &target.secret, event_id, body)
+        .await
+        .map_err(|e| match e {
+            // the url can't be reached however many times it is retried
+            FetchError::InvalidUrl | FetchError::Blocked => DeliveryError::Permanent(e.to_string()),
+            e => DeliveryError::Retryable(e.to_string()),
+        })?;
+
+    if status.is_success() {
+        Ok(())
+    } else if is_retryable(status) {
+        Err(DeliveryError::Retryable(status.to_string()))
+    } else {
+        Err(DeliveryError::Permanent(status.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_envelope() {
+        let event = BotEvent::MemberJoined(MemberJoined {
+            user_ids: vec!["macro|a@macro.com".to_string()],
+        });
+        assert_eq!(event.event_type(), BotEventType::MemberJoined);
+
+        let envelope = serde_json::to_value(BotEventEnvelope {
+            id: Uuid::nil(),
+            channel_id: Uuid::nil(),
+            created_at: DateTime::from_timestamp(0, 0).unwrap(),
+            event: &event,
+        })
+        .unwrap();
+        assert_eq!(envelope["type"], "member_joined");
+        assert_eq!(envelope["data"]["user_ids"][0], "macro|a@macro.com");
+        assert_eq!(envelope["channel_id"], Uuid::nil().to_string());
+    }
+}
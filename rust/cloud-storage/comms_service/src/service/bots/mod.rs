@@ -0,0 +1,187 @@
+use axum::http::StatusCode;
+use comms_db_client::{
+    channels::{get_channel_info::get_channel_info, updated_at},
+    messages::{add_attachments, create_message, create_message_mentions},
+    model::{Message, NewAttachment, SimpleMention},
+    participants::get_participants::get_participants,
+};
+use model_notifications::CommonChannelMetadata;
+use uuid::Uuid;
+
+use crate::{
+    api::{channels::post_message::dispatch_notification_task, context::AppState},
+    service::{
+        self,
+        sender::notify::{self, AttachmentUpdate},
+    },
+    utils::channel_name::resolve_channel_name,
+};
+
+pub mod events;
+
+/// The sender id of messages posted by the built in slash commands
+pub const COMMANDS_BOT_ID: &str = "bot|commands";
+
+#[derive(Debug)]
+pub struct BotMessage<'a> {
+    pub channel_id: Uuid,
+    /// the sender id of the bot, see [comms_db_client::webhooks::bot_id]
+    pub bot_id: &'a str,
+    pub content: String,
+    pub thread_id: Option<Uuid>,
+    /// Macro items the message links to
+    pub entities: Vec<NewAttachment>,
+    pub mentions: Vec<SimpleMention>,
+}
+
+/// Posts a message as a bot, through the same realtime, notification, search and bot event
+/// paths as a member's message
+#[tracing::instrument(
+    skip(ctx, message),
+    fields(channel_id = %message.channel_id, bot_id = message.bot_id)
+)]
+pub async fn post_bot_message(
+    ctx: &AppState,
+    message: BotMessage<'_>,
+) -> Result<Message, (StatusCode, String)> {
+    let BotMessage {
+        channel_id,
+        bot_id,
+        content,
+        thread_id,
+        entities,
+        mentions,
+    } = message;
+
+    // reminders set before the channel was archived aren't posted to it
+    service::moderation::ensure_not_archived(ctx, &channel_id).await?;
+
+    let channel_info = get_channel_info(&ctx.db, &channel_id).await.map_err(|e| {
+        tracing::error!(error=?e, "unable to get channel info");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to get channel info".to_string(),
+        )
+    })?;
+    let channel_participants = get_participants(&ctx.db, &channel_id).await.map_err(|e| {
+        tracing::error!(error=?e, "unable to get participants");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to get participants".to_string(),
+        )
+    })?;
+
+    let message = create_message::create_message(
+        &ctx.db,
+        create_message::CreateMessageOptions {
+            channel_id,
+            sender_id: bot_id.to_string(),
+            content,
+            thread_id,
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to create message");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to create message".to_string(),
+        )
+    })?;
+
+    updated_at::updated_at(&ctx.db, &channel_id)
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error=?e, "unable to update channel updated_at");
+        })
+        .ok();
+
+    if !mentions.is_empty() {
+        create_message_mentions::create_message_mentions(
+            &ctx.db,
+            create_message_mentions::CreateMessageMentionOptions {
+                message_id: message.id,
+                mentions: mentions.clone(),
+            },
+        )
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error=?e, "unable to create mentions");
+        })
+        .ok();
+    }
+
+    let participants: Vec<String> = channel_participants
+        .iter()
+        .map(|p| p.user_id.clone())
+        .collect();
+
+    notify::notify_message(ctx, message.clone(), &participants)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to notify message");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to deliver message".to_string(),
+            )
+        })?;
+
+    let attachments =
+        add_attachments::add_attachments_to_message(&ctx.db, &message.id, &channel_id, entities)
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error=?err, "unable to add attachments to message");
+            })
+            .unwrap_or_default();
+
+    if !attachments.is_empty() {
+        notify::notify_attachments(
+            ctx,
+            AttachmentUpdate {
+                channel_id,
+                message_id: message.id,
+                attachments,
+            },
+        )
+        .await
+        .inspect_err(|err| {
+            tracing::error!(error=?err, "failed to notify about attachment");
+        })
+        .ok();
+    }
+
+    let channel_name = resolve_channel_name(
+        &channel_info.channel_type,
+        channel_info.name.as_deref(),
+        &channel_participants,
+        &channel_id,
+        bot_id,
+        None,
+    );
+
+    dispatch_notification_task(
+        ctx,
+        channel_id,
+        CommonChannelMetadata {
+            channel_type: channel_info.channel_type,
+            channel_name,
+        },
+        channel_participants,
+        message.clone(),
+        mentions,
+    );
+
+    service::search::send_channel_message_to_search_extractor_queue(
+        &ctx.sqs_client,
+        channel_id,
+        message.id,
+    );
+
+    events::dispatch_event(
+        ctx,
+        channel_id,
+        events::BotEvent::MessagePosted(message.clone()),
+    );
+
+    Ok(message)
+}
@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::context::AppState;
+
+mod poll;
+mod remind;
+mod summarize;
+
+pub use remind::spawn_reminder_worker;
+
+/// The commands members can run by starting a message with `/name`
+static COMMANDS: &[&dyn SlashCommand] = &[&poll::Poll, &remind::Remind, &summarize::Summarize];
+
+#[derive(Debug)]
+pub struct CommandInvocation<'a> {
+    pub channel_id: Uuid,
+    pub thread_id: Option<Uuid>,
+    pub user_id: &'a str,
+    /// everything after the command name
+    pub args: &'a str,
+}
+
+#[derive(Debug)]
+pub enum CommandOutcome {
+    /// post this content as the member's message, in place of the command
+    Post(String),
+    /// answer only the member who ran the command, nothing is posted
+    Reply(String),
+}
+
+#[async_trait]
+pub trait SlashCommand: Send + Sync {
+    /// what follows the slash, lowercase
+    fn name(&self) -> &'static str;
+    fn usage(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    async fn run(
+        &self,
+        ctx: &AppState,
+        invocation: CommandInvocation<'_>,
+    ) -> Result<CommandOutcome, (StatusCode, String)>;
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CommandInfo {
+    pub name: String,
+    pub usage: String,
+    pub description: String,
+}
+
+/// The registered commands, for clients to suggest
+pub fn commands() -> Vec<CommandInfo> {
+    COMMANDS
+        .iter()
+        .map(|command| CommandInfo {
+            name: command.name().to_string(),
+            usage: command.usage().to_string(),
+            description: command.description().to_string(),
+        })
+        .collect()
+}
+
+/// Finds the registered command a message runs, with its arguments. A message starting with
+/// anything else, like a file path, is an ordinary message.
+pub fn parse_command(content: &str) -> Option<(&'static dyn SlashCommand, &str)> {
+    let rest = content.trim_start().strip_prefix('/')?;
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    COMMANDS
+        .iter()
+        .find(|command| command.name().eq_ignore_ascii_case(name))
+        .map(|command| (*command, args.trim()))
+}
+
+/// Parses a duration like `30m`, `2h`, `1d` or `1h30m`, in minutes, hours, days and weeks
+fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: i64 = digits.parse().ok()?;
+        digits.clear();
+        let unit = match c.to_ascii_lowercase() {
+            'm' => chrono::Duration::try_minutes(amount)?,
+            'h' => chrono::Duration::try_hours(amount)?,
+            'd' => chrono::Duration::try_days(amount)?,
+            'w' => chrono::Duration::try_weeks(amount)?,
+            _ => return None,
+        };
+        total = total.checked_add(&unit)?;
+    }
+    // a trailing number without a unit isn't a duration
+    (digits.is_empty() && total > chrono::Duration::zero()).then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let (command, args) = parse_command("/poll Lunch? | Tacos | Pizza").expect("poll");
+        assert_eq!(command.name(), "poll");
+        assert_eq!(args, "Lunch? | Tacos | Pizza");
+
+        let (command, args) = parse_command("  /SUMMARIZE").expect("summarize");
+        assert_eq!(command.name(), "summarize");
+        assert_eq!(args, "");
+
+        assert!(parse_command("/usr/bin/env is the path").is_none());
+        assert!(parse_command("/shrug").is_none());
+        assert!(parse_command("run /poll later").is_none());
+        assert!(parse_command("/").is_none());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(chrono::Duration::minutes(30)));
+        assert_eq!(parse_duration("1h30m"), Some(chrono::Duration::minutes(90)));
+        assert_eq!(parse_duration("2D"), Some(chrono::Duration::days(2)));
+        assert_eq!(parse_duration("1w"), Some(chrono::Duration::weeks(1)));
+        assert_eq!(parse_duration("30"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5s"), None);
+        assert_eq!(parse_duration("tomorrow"), None);
+        assert_eq!(parse_duration("99999999999999999999d"), None);
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+
+use super::{CommandInvocation, CommandOutcome, SlashCommand};
+use crate::api::context::AppState;
+
+const MAX_OPTIONS: usize = 10;
+const OPTION_EMOJI: [&str; MAX_OPTIONS] =
+    ["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣", "9️⃣", "🔟"];
+
+/// Posts a poll members vote on with reactions
+pub struct Poll;
+
+#[async_trait]
+impl SlashCommand for Poll {
+    fn name(&self) -> &'static str {
+        "poll"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/poll question | option | option"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ask the channel a question, members vote by reacting"
+    }
+
+    async fn run(
+        &self,
+        _ctx: &AppState,
+        invocation: CommandInvocation<'_>,
+    ) -> Result<CommandOutcome, (StatusCode, String)> {
+        render_poll(invocation.args)
+            .map(CommandOutcome::Post)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))
+    }
+}
+
+fn render_poll(args: &str) -> Result<String, String> {
+    let mut parts = args.split('|').map(str::trim);
+    let question = parts
+        .next()
+        .filter(|question| !question.is_empty())
+        .ok_or_else(|| format!("usage: {}", Poll.usage()))?;
+    let options: Vec<&str> = parts.filter(|option| !option.is_empty()).collect();
+    if !(2..=MAX_OPTIONS).contains(&options.len()) {
+        return Err(format!("a poll needs between 2 and {MAX_OPTIONS} options"));
+    }
+
+    let options = options
+        .iter()
+        .zip(OPTION_EMOJI)
+        .map(|(option, emoji)| format!("{emoji} {option}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(format!(
+        "📊 **{question}**\n\n{options}\n\nReact with an option's number to vote"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_poll() {
+        assert_eq!(
+            render_poll("Lunch? | Tacos | Pizza |").as_deref(),
+            Ok("📊 **Lunch?**\n\n1️⃣ Tacos\n2️⃣ Pizza\n\nReact with an option's number to vote")
+        );
+        assert!(render_poll("").is_err());
+        assert!(render_poll("Lunch? | Tacos").is_err());
+        assert!(render_poll("| Tacos | Pizza").is_err());
+        assert!(render_poll(&format!("Pick{}", " | x".repeat(MAX_OPTIONS + 1))).is_err());
+    }
+}
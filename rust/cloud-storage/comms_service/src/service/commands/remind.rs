@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::Utc;
+use comms_db_client::{
+    bots::reminders::{CreateReminderOptions, create_reminder, take_due_reminders},
+    model::SimpleMention,
+};
+use std::time::Duration;
+
+use super::{CommandInvocation, CommandOutcome, SlashCommand, parse_duration};
+use crate::{
+    api::context::AppState,
+    service::bots::{BotMessage, COMMANDS_BOT_ID, post_bot_message},
+};
+
+/// How often due reminders are looked for
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// The most reminders delivered at once
+const BATCH_SIZE: i64 = 100;
+const MAX_DELAY_DAYS: i64 = 365;
+
+/// Reminds the member about something in the channel later
+pub struct Remind;
+
+#[async_trait]
+impl SlashCommand for Remind {
+    fn name(&self) -> &'static str {
+        "remind"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/remind me in 1h30m what to be reminded about"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get a reminder in this channel after some minutes (m), hours (h), days (d) or weeks (w)"
+    }
+
+    async fn run(
+        &self,
+        ctx: &AppState,
+        invocation: CommandInvocation<'_>,
+    ) -> Result<CommandOutcome, (StatusCode, String)> {
+        let (delay, content) =
+            parse_reminder(invocation.args).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+        let reminder = create_reminder(
+            &ctx.db,
+            CreateReminderOptions {
+                channel_id: invocation.channel_id,
+                thread_id: invocation.thread_id,
+                user_id: invocation.user_id,
+                content,
+                remind_at: Utc::now() + delay,
+            },
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to create reminder");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to create reminder".to_string(),
+            )
+        })?;
+
+        Ok(CommandOutcome::Reply(format!(
+            "I'll remind you at {}",
+            reminder.remind_at.format("%Y-%m-%d %H:%M UTC")
+        )))
+    }
+}
+
+/// Splits `[me] [in] <duration> <content>` into the delay and what to remind about
+fn parse_reminder(args: &str) -> Result<(chrono::Duration, &str), String> {
+    let usage = || format!("usage: {}", Remind.usage());
+
+    let args = args.strip_prefix("me ").unwrap_or(args).trim_start();
+    let args = args.strip_prefix("in ").unwrap_or(args).trim_start();
+    let (when, content) = args.split_once(char::is_whitespace).ok_or_else(usage)?;
+
+    let delay = parse_duration(when).ok_or_else(usage)?;
+    if delay > chrono::Duration::days(MAX_DELAY_DAYS) {
+        return Err(format!(
+            "reminders can be at most {MAX_DELAY_DAYS} days away"
+        ));
+    }
+
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(usage());
+    }
+
+    Ok((delay, content))
+}
+
+/// Posts reminders to their channels as they come due, mentioning the member who set them
+pub fn spawn_reminder_worker(ctx: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            deliver_due_reminders(&ctx).await;
+        }
+    });
+}
+
+#[tracing::instrument(skip(ctx))]
+async fn deliver_due_reminders(ctx: &AppState) {
+    let reminders = match take_due_reminders(&ctx.db, BATCH_SIZE).await {
+        Ok(reminders) => reminders,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to take due reminders");
+            return;
+        }
+    };
+
+    for reminder in reminders {
+        post_bot_message(
+            ctx,
+            BotMessage {
+                channel_id: reminder.channel_id,
+                bot_id: COMMANDS_BOT_ID,
+                content: format!("⏰ Reminder: {}", reminder.content),
+                thread_id: reminder.thread_id,
+                entities: vec![],
+                mentions: vec![SimpleMention {
+                    entity_type: "user".to_string(),
+                    entity_id: reminder.user_id.clone(),
+                }],
+            },
+        )
+        .await
+        .inspect_err(|(_, e)| {
+            tracing::error!(error=?e, reminder_id=%reminder.id, "unable to deliver reminder");
+        })
+        .ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reminder() {
+        assert_eq!(
+            parse_reminder("me in 1h30m  check the deploy "),
+            Ok((chrono::Duration::minutes(90), "check the deploy"))
+        );
+        assert_eq!(
+            parse_reminder("2d review the doc"),
+            Ok((chrono::Duration::days(2), "review the doc"))
+        );
+        assert!(parse_reminder("me in 1h").is_err());
+        assert!(parse_reminder("tomorrow review the doc").is_err());
+        assert!(parse_reminder("in 400d far away").is_err());
+        assert!(parse_reminder("").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use ai::{simple_completion::simple_completion, types::Model};
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::Utc;
+use comms_db_client::model::SimpleMention;
+use tracing::Instrument;
+
+use super::{CommandInvocation, CommandOutcome, SlashCommand, parse_duration};
+use crate::{
+    api::{channels::get_channel_transcript::get_channel_transcript, context::AppState},
+    service::bots::{BotMessage, COMMANDS_BOT_ID, post_bot_message},
+};
+
+const SUMMARY_MODEL: Model = Model::OpenAiGpt41;
+const SYSTEM_PROMPT: &str = "You summarize a channel conversation for a member catching up. Cover \
+    the main topics, decisions and open questions in a few short bullet points, naming who said \
+    what when it matters. The request section contains the conversation.";
+/// The transcript is limited to this many messages, matching its prompt
+const MAX_MESSAGES: i64 = 1000;
+const MAX_WINDOW_DAYS: i64 = 30;
+
+/// Summarizes the channel's recent conversation
+pub struct Summarize;
+
+#[async_trait]
+impl SlashCommand for Summarize {
+    fn name(&self) -> &'static str {
+        "summarize"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/summarize [3d]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Post a summary of the conversation over the last day, or over the time given"
+    }
+
+    async fn run(
+        &self,
+        ctx: &AppState,
+        invocation: CommandInvocation<'_>,
+    ) -> Result<CommandOutcome, (StatusCode, String)> {
+        let window = match invocation.args {
+            "" => chrono::Duration::days(1),
+            args => parse_duration(args)
+                .ok_or_else(|| (StatusCode::BAD_REQUEST, format!("usage: {}", self.usage())))?,
+        };
+        if window > chrono::Duration::days(MAX_WINDOW_DAYS) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("can summarize at most the last {MAX_WINDOW_DAYS} days"),
+            ));
+        }
+
+        // the completion can take a while, the summary is posted when it's ready
+        let ctx = ctx.clone();
+        let channel_id = invocation.channel_id;
+        let thread_id = invocation.thread_id;
+        let requested_by = invocation.user_id.to_string();
+        tokio::spawn(
+            async move {
+                let transcript = match get_channel_transcript(
+                    &ctx.db,
+                    &channel_id,
+                    Some(Utc::now() - window),
+                    Some(MAX_MESSAGES),
+                )
+                .await
+                {
+                    Ok(transcript) => transcript,
+                    Err(e) => {
+                        tracing::error!(error=?e, "unable to get channel transcript");
+                        return;
+                    }
+                };
+
+                let summary =
+                    match simple_completion(SYSTEM_PROMPT, &transcript, SUMMARY_MODEL).await {
+                        Ok(summary) => summary,
+                        Err(e) => {
+                            tracing::error!(error=?e, "unable to summarize channel");
+                            return;
+                        }
+                    };
+
+                post_bot_message(
+                    &ctx,
+                    BotMessage {
+                        channel_id,
+                        bot_id: COMMANDS_BOT_ID,
+                        content: format!("**Summary of the conversation**\n\n{}", summary.trim()),
+                        thread_id,
+                        entities: vec![],
+                        // lets the member who asked know it's ready
+                        mentions: vec![SimpleMention {
+                            entity_type: "user".to_string(),
+                            entity_id: requested_by,
+                        }],
+                    },
+                )
+                .await
+                .inspect_err(|(_, e)| {
+                    tracing::error!(error=?e, "unable to post summary");
+                })
+                .ok();
+            }
+            .in_current_span(),
+        );
+
+        Ok(CommandOutcome::Reply(
+            "Summarizing, the summary will be posted here shortly".to_string(),
+        ))
+    }
+}
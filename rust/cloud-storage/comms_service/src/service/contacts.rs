@@ -0,0 +1,69 @@
+use comms_db_client::model::{NewAttachment, SimpleMention};
+use model::contacts::InteractionKind;
+
+/// Records the interactions of a posted message with the contacts service. Mentioned users are
+/// mentioned by the sender, and the other participants of the channel are shared the items
+/// attached to the message.
+#[tracing::instrument(skip(sqs_client, participants, mentions, attachments))]
+pub fn record_message_interactions(
+    sqs_client: &sqs_client::SQS,
+    sender_id: &str,
+    participants: &[String],
+    mentions: &[SimpleMention],
+    attachments: &[NewAttachment],
+) {
+    let mentioned = mentions
+        .iter()
+        .filter(|mention| mention.entity_type == "user")
+        .map(|mention| mention.entity_id.clone())
+        .filter(|user_id| user_id != sender_id)
+        .collect::<Vec<_>>();
+
+    let shared_with = if attachments
+        .iter()
+        .any(|attachment| attachment.entity_type != "user")
+    {
+        participants
+            .iter()
+            .filter(|user_id| *user_id != sender_id)
+            .cloned()
+            .collect::<Vec<_>>()
+    } else {
+        vec![]
+    };
+
+    for (kind, counterparts) in [
+        (InteractionKind::Mention, mentioned),
+        (InteractionKind::DocumentShare, shared_with),
+    ] {
+        if counterparts.is_empty() {
+            continue;
+        }
+        enqueue_interaction(sqs_client, sender_id, counterparts, kind);
+    }
+}
+
+fn enqueue_interaction(
+    sqs_client: &sqs_client::SQS,
+    user_id: &str,
+    counterparts: Vec<String>,
+    kind: InteractionKind,
+) {
+    use tracing::Instrument;
+
+    tracing::trace!(?kind, "enqueueing interaction to contacts");
+
+    tokio::spawn({
+        let sqs_client = sqs_client.clone();
+        let user_id = user_id.to_string();
+        async move {
+            let _ = sqs_client
+                .enqueue_contacts_record_interaction(&user_id, counterparts, kind)
+                .await
+                .inspect_err(|e| {
+                    tracing::error!(error=?e, "CONTACTS_QUEUE unable to enqueue interaction");
+                });
+        }
+        .in_current_span()
+    });
+}
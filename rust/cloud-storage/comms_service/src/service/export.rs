@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use comms_db_client::{
+    attachments::get_attachments::get_attachments,
+    channels::get_channel::get_channel,
+    messages::get_messages::get_messages,
+    model::{Attachment, CountedReaction, Message},
+    participants::get_participants::get_participants,
+    reactions::{get_reactions::get_messages_reactions, group_reactions_by_message},
+};
+use html_utils::escape_html;
+use model::comms::{Channel, ChannelParticipant};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{api::context::AppState, utils::user_name::id_to_display_name};
+
+/// Bumped when the shape of an export changes, so importers can tell versions apart
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Everything in a channel, as downloaded by its members
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChannelExport {
+    pub version: u32,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub channel: Channel,
+    pub participants: Vec<ChannelParticipant>,
+    /// all messages of the channel, including thread replies, oldest first
+    pub messages: Vec<ExportedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExportedMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    pub reactions: Vec<CountedReaction>,
+    pub attachments: Vec<Attachment>,
+}
+
+/// Gathers a channel's messages with their reactions and attachments
+#[tracing::instrument(skip(ctx), err)]
+pub async fn export_channel(ctx: &AppState, channel_id: &Uuid) -> Result<ChannelExport> {
+    let (channel, participants, messages, attachments) = tokio::try_join!(
+        get_channel(&ctx.db, channel_id),
+        async { Ok::<_, anyhow::Error>(get_participants(&ctx.db, channel_id).await?) },
+        get_messages(&ctx.db, channel_id, None, None),
+        get_attachments(&ctx.db, channel_id),
+    )?;
+
+    let reactions =
+        get_messages_reactions(&ctx.db, messages.iter().map(|m| m.id).collect()).await?;
+    let mut reactions = group_reactions_by_message(reactions);
+
+    let mut attachments_by_message: HashMap<Uuid, Vec<Attachment>> = HashMap::new();
+    for attachment in attachments {
+        attachments_by_message
+            .entry(attachment.message_id)
+            .or_default()
+            .push(attachment);
+    }
+
+    let messages = messages
+        .into_iter()
+        .map(|message| ExportedMessage {
+            reactions: reactions
+                .remove(&message.id.to_string())
+                .unwrap_or_default(),
+            attachments: attachments_by_message
+                .remove(&message.id)
+                .unwrap_or_default(),
+            message,
+        })
+        .collect();
+
+    Ok(ChannelExport {
+        version: EXPORT_VERSION,
+        exported_at: chrono::Utc::now(),
+        channel,
+        participants,
+        messages,
+    })
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;max-width:48rem;margin:2rem auto;\
+    color:#1f2328}.message{margin:1rem 0}.meta{color:#656d76;font-size:.85rem}\
+    .content{white-space:pre-wrap;margin:.25rem 0}.thread{margin-left:1.5rem;\
+    padding-left:1rem;border-left:2px solid #d0d7de}.reactions,.attachments{font-size:.85rem}\
+    .deleted{color:#656d76;font-style:italic}";
+
+/// Renders an export as a standalone HTML transcript, with thread replies under the message they
+/// reply to
+pub fn render_html(export: &ChannelExport, names: Option<&HashMap<String, String>>) -> String {
+    let title = escape_html(export.channel.name.as_deref().unwrap_or("Channel"));
+
+    let mut replies: HashMap<Uuid, Vec<&ExportedMessage>> = HashMap::new();
+    for message in &export.messages {
+        if let Some(thread_id) = message.message.thread_id {
+            replies.entry(thread_id).or_default().push(message);
+        }
+    }
+
+    let mut body = String::new();
+    for message in export
+        .messages
+        .iter()
+        .filter(|m| m.message.thread_id.is_none())
+    {
+        body.push_str(&render_message(message, names));
+        if let Some(replies) = replies.get(&message.message.id) {
+            body.push_str("<div class=\"thread\">");
+            for reply in replies {
+                body.push_str(&render_message(reply, names));
+            }
+            body.push_str("</div>");
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\
+        <style>{HTML_STYLE}</style></head><body><h1>{title}</h1>\
+        <p class=\"meta\">Exported {exported_at}</p>{body}</body></html>\n",
+        exported_at = export.exported_at.to_rfc3339(),
+    )
+}
+
+fn render_message(message: &ExportedMessage, names: Option<&HashMap<String, String>>) -> String {
+    let sender = escape_html(&id_to_display_name(&message.message.sender_id, names));
+    let sent_at = message.message.created_at.format("%Y-%m-%d %H:%M UTC");
+    let edited = if message.message.edited_at.is_some() {
+        " (edited)"
+    } else {
+        ""
+    };
+
+    let content = if message.message.deleted_at.is_some() {
+        "<p class=\"deleted\">This message was deleted</p>".to_string()
+    } else {
+        format!(
+            "<p class=\"content\">{}</p>",
+            escape_html(&message.message.content)
+        )
+    };
+
+    let reactions = if message.reactions.is_empty() {
+        String::new()
+    } else {
+        let reactions = message
+            .reactions
+            .iter()
+            .map(|r| format!("{} {}", escape_html(&r.emoji), r.users.len()))
+            .collect::<Vec<_>>()
+            .join(" · ");
+        format!("<div class=\"reactions\">{reactions}</div>")
+    };
+
+    let attachments = if message.attachments.is_empty() {
+        String::new()
+    } else {
+        let attachments = message
+            .attachments
+            .iter()
+            .map(|a| {
+                format!(
+                    "<li>{}: <code>{}</code></li>",
+                    escape_html(&a.entity_type),
+                    escape_html(&a.entity_id)
+                )
+            })
+            .collect::<String>();
+        format!("<ul class=\"attachments\">{attachments}</ul>")
+    };
+
+    format!(
+        "<div class=\"message\" id=\"{id}\"><div class=\"meta\"><strong>{sender}</strong> \
+        {sent_at}{edited}</div>{content}{reactions}{attachments}</div>",
+        id = message.message.id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::comms::ChannelType;
+
+    fn message(id: Uuid, thread_id: Option<Uuid>, content: &str) -> ExportedMessage {
+        let now = chrono::Utc::now();
+        ExportedMessage {
+            message: Message {
+                id,
+                channel_id: Uuid::nil(),
+                thread_id,
+                sender_id: "macro|ada@macro.com".to_string(),
+                content: content.to_string(),
+                created_at: now,
+                updated_at: now,
+                edited_at: None,
+                deleted_at: None,
+            },
+            reactions: vec![],
+            attachments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_render_html() {
+        let now = chrono::Utc::now();
+        let parent_id = Uuid::new_v4();
+        let mut parent = message(parent_id, None, "lunch <now>?");
+        parent.reactions = vec![CountedReaction {
+            emoji: "👍".to_string(),
+            users: vec!["macro|bob@macro.com".to_string()],
+        }];
+        let reply = message(Uuid::new_v4(), Some(parent_id), "on my way");
+
+        let export = ChannelExport {
+            version: EXPORT_VERSION,
+            exported_at: now,
+            channel: Channel {
+                id: Uuid::nil(),
+                name: Some("food".to_string()),
+                channel_type: ChannelType::Public,
+                org_id: None,
+                created_at: now,
+                updated_at: now,
+                owner_id: "macro|ada@macro.com".to_string(),
+                archived_at: None,
+            },
+            participants: vec![],
+            // replies can come before later top level messages
+            messages: vec![parent, reply, message(Uuid::new_v4(), None, "later")],
+        };
+
+        let html = render_html(&export, None);
+        assert!(html.contains("<title>food</title>"));
+        assert!(html.contains("lunch &lt;now&gt;?"));
+        assert!(html.contains("<strong>ada</strong>"));
+        assert!(html.contains("👍 1"));
+
+        let thread = html.find("class=\"thread\"").unwrap();
+        assert!(html.find("on my way").unwrap() > thread);
+        assert!(html.find("later").unwrap() > html.find("on my way").unwrap());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod bots;
+pub mod commands;
+pub mod contacts;
+pub mod export;
+pub mod moderation;
 pub mod search;
 pub mod sender;
+pub mod slack_import;
+pub mod webhooks;
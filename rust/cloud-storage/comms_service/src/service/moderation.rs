@@ -0,0 +1,118 @@
+use axum::http::StatusCode;
+use comms_db_client::channels::archive_channel::is_channel_archived;
+use comms_db_client::moderation::{
+    actions::{NewModerationAction, record_action},
+    slow_mode::claim_slow_mode_post,
+    thread_lock::is_thread_locked,
+};
+use model::comms::ParticipantRole;
+use uuid::Uuid;
+
+use crate::{
+    api::context::AppState,
+    service::sender::notify::{ModerationUpdate, notify_moderation},
+};
+
+/// Whether a role can moderate a channel
+pub fn is_moderator(role: Option<ParticipantRole>) -> bool {
+    matches!(role, Some(ParticipantRole::Owner | ParticipantRole::Admin))
+}
+
+/// Checks the channel isn't archived, archived channels are read-only for everyone
+#[tracing::instrument(skip(ctx))]
+pub async fn ensure_not_archived(
+    ctx: &AppState,
+    channel_id: &Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let archived = is_channel_archived(&ctx.db, channel_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to get channel archive status");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get channel archive status".to_string(),
+            )
+        })?;
+    if archived {
+        return Err((StatusCode::FORBIDDEN, "channel is archived".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Checks a member may post to the channel, or to a thread of it, under the channel's moderation.
+/// Moderators are exempt, except from the channel being archived. Passing the check counts as
+/// the member's post for the channel's slow mode.
+#[tracing::instrument(skip(ctx))]
+pub async fn ensure_can_post(
+    ctx: &AppState,
+    channel_id: &Uuid,
+    user_id: &str,
+    role: Option<ParticipantRole>,
+    thread_id: Option<&Uuid>,
+) -> Result<(), (StatusCode, String)> {
+    ensure_not_archived(ctx, channel_id).await?;
+
+    if is_moderator(role) {
+        return Ok(());
+    }
+
+    if let Some(thread_id) = thread_id {
+        let locked = is_thread_locked(&ctx.db, thread_id).await.map_err(|e| {
+            tracing::error!(error=?e, "unable to get thread lock");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to get thread lock".to_string(),
+            )
+        })?;
+        if locked {
+            return Err((StatusCode::FORBIDDEN, "thread is locked".to_string()));
+        }
+    }
+
+    // claimed last, so a post turned away for anything else doesn't use up the slow mode
+    let wait = claim_slow_mode_post(&ctx.db, channel_id, user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to check slow mode");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to check slow mode".to_string(),
+            )
+        })?;
+    if let Some(wait) = wait {
+        // round up so the client doesn't retry a moment too early
+        let seconds = (wait.num_milliseconds() + 999) / 1000;
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("slow mode is on, wait {seconds} seconds"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Records a moderation action for the channel's organization and tells the channel about it.
+/// The action already took effect, so failures are logged rather than returned.
+#[tracing::instrument(skip(ctx))]
+pub async fn record_and_notify(ctx: &AppState, action: NewModerationAction<'_>) {
+    let update = ModerationUpdate {
+        channel_id: action.channel_id,
+        action: action.action,
+        message_id: action.target_message_id,
+    };
+
+    record_action(&ctx.db, action)
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error=?e, "unable to record moderation action");
+        })
+        .ok();
+
+    notify_moderation(ctx, update)
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error=?e, "unable to notify moderation action");
+        })
+        .ok();
+}
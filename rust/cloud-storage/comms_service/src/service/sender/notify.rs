@@ -1,6 +1,7 @@
 use anyhow::Result;
-use comms_db_client::model::{Attachment, CountedReaction, Message, TypingAction};
+use comms_db_client::model::{Attachment, CountedReaction, Message, ReadMarker, TypingAction};
 use comms_db_client::participants::get_participants::get_participants;
+use model::comms::ModerationActionType;
 use model_entity::EntityType;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -102,3 +103,41 @@ pub async fn notify_attachments(ctx: &AppState, update: AttachmentUpdate) -> Res
 
     Ok(())
 }
+
+/// Sends a read marker to the user's own connections, so their other devices catch up
+pub async fn notify_read_marker(ctx: &AppState, marker: &ReadMarker) -> Result<()> {
+    ctx.connection_gateway_client
+        .batch_send_message(
+            "comms_read_marker".to_string(),
+            serde_json::to_value(marker)?,
+            vec![EntityType::User.with_entity_str(&marker.user_id)],
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModerationUpdate {
+    pub channel_id: Uuid,
+    pub action: ModerationActionType,
+    /// the message that was deleted, pinned or unpinned, or the thread that was locked
+    pub message_id: Option<Uuid>,
+}
+
+pub async fn notify_moderation(ctx: &AppState, update: ModerationUpdate) -> Result<()> {
+    let participants = get_participants(&ctx.db, &update.channel_id).await?;
+
+    ctx.connection_gateway_client
+        .batch_send_message(
+            "comms_moderation".to_string(),
+            serde_json::to_value(update)?,
+            participants
+                .iter()
+                .map(|p| EntityType::User.with_entity_str(&p.user_id))
+                .collect(),
+        )
+        .await?;
+
+    Ok(())
+}
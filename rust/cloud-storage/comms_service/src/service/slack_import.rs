@@ -0,0 +1,684 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read},
+};
+
+use chrono::{DateTime, Utc};
+use comms_db_client::channels::import_channel::{ImportChannelOptions, ImportedMessage};
+use models_comms::ChannelType;
+use serde::Deserialize;
+use uuid::{NoContext, Timestamp, Uuid};
+
+/// The sender of imported messages whose Slack user isn't a verified member, like integrations
+/// and people outside the importer's organization. The Slack name is kept in the message.
+pub const SLACK_IMPORT_BOT_ID: &str = "bot|slack-import";
+/// The most json an export can unpack to, guarding against archives that expand without bound
+const MAX_UNPACKED_BYTES: u64 = 512 * 1024 * 1024;
+/// The most messages one upload can import
+pub const MAX_IMPORTED_MESSAGES: usize = 100_000;
+/// Reactions are stored in a varchar(32)
+const MAX_EMOJI_LENGTH: usize = 32;
+
+/// Message subtypes that carry conversation, the others are channel events like joins
+const IMPORTED_SUBTYPES: &[&str] = &[
+    "bot_message",
+    "thread_broadcast",
+    "file_share",
+    "me_message",
+];
+
+/// The unicode emoji of common Slack reaction names, the others are kept as `:name:`
+const EMOJI: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("thumbsup", "👍"),
+    ("-1", "👎"),
+    ("thumbsdown", "👎"),
+    ("heart", "❤️"),
+    ("joy", "😂"),
+    ("smile", "😄"),
+    ("slightly_smiling_face", "🙂"),
+    ("grinning", "😀"),
+    ("laughing", "😆"),
+    ("sweat_smile", "😅"),
+    ("sob", "😭"),
+    ("thinking_face", "🤔"),
+    ("tada", "🎉"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("100", "💯"),
+    ("pray", "🙏"),
+    ("raised_hands", "🙌"),
+    ("clap", "👏"),
+    ("wave", "👋"),
+    ("ok_hand", "👌"),
+    ("muscle", "💪"),
+    ("rocket", "🚀"),
+    ("star", "⭐"),
+    ("sparkles", "✨"),
+    ("bulb", "💡"),
+    ("warning", "⚠️"),
+    ("white_check_mark", "✅"),
+    ("heavy_check_mark", "✔️"),
+    ("x", "❌"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SlackImportError {
+    #[error("upload is not a zip archive")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("unable to read the archive")]
+    Io(#[from] std::io::Error),
+    #[error("{0} is not valid Slack export json")]
+    Json(String),
+    #[error("export is too large to import at once")]
+    TooLarge,
+    #[error("export has no channels to import")]
+    NoChannels,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackUser {
+    id: String,
+    name: String,
+    real_name: Option<String>,
+    deleted: bool,
+    is_bot: bool,
+    profile: SlackProfile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackProfile {
+    email: Option<String>,
+    display_name: Option<String>,
+    real_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackChannel {
+    name: String,
+    members: Vec<String>,
+    #[serde(skip)]
+    is_private: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackMessage {
+    subtype: Option<String>,
+    user: Option<String>,
+    /// the name a bot or integration posted under
+    username: Option<String>,
+    text: String,
+    ts: String,
+    thread_ts: Option<String>,
+    edited: Option<SlackEdited>,
+    reactions: Vec<SlackReaction>,
+    files: Vec<SlackFile>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackEdited {
+    ts: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackReaction {
+    name: String,
+    users: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SlackFile {
+    name: Option<String>,
+    title: Option<String>,
+    permalink: Option<String>,
+}
+
+/// The users, channels and messages of a Slack workspace export
+#[derive(Debug, Default)]
+pub struct SlackExport {
+    users: HashMap<String, SlackUser>,
+    channels: Vec<SlackChannel>,
+    /// the messages of each channel, by channel name
+    messages: HashMap<String, Vec<SlackMessage>>,
+}
+
+impl SlackExport {
+    /// The names of the channels in the export
+    pub fn channel_names(&self) -> Vec<&str> {
+        self.channels.iter().map(|c| c.name.as_str()).collect()
+    }
+}
+
+/// Reads a Slack export zip: `users.json`, `channels.json` and `groups.json` for public and
+/// private channels, and a folder of daily message files per channel. Direct messages aren't
+/// imported.
+pub fn parse_export(zip: &[u8]) -> Result<SlackExport, SlackImportError> {
+    parse_export_within(zip, MAX_UNPACKED_BYTES)
+}
+
+fn parse_export_within(zip: &[u8], max_unpacked: u64) -> Result<SlackExport, SlackImportError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip))?;
+    let mut export = SlackExport::default();
+    let mut unpacked = 0;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        let path = file.name().to_string();
+        if file.is_dir() || !path.ends_with(".json") {
+            continue;
+        }
+
+        // the sizes a zip declares can't be trusted, so only the bytes read count, and no entry
+        // can read past what is left of the budget
+        let mut json = Vec::new();
+        let read = file
+            .take(max_unpacked - unpacked + 1)
+            .read_to_end(&mut json)?;
+        unpacked += read as u64;
+        if unpacked > max_unpacked {
+            return Err(SlackImportError::TooLarge);
+        }
+        let invalid = |_| SlackImportError::Json(path.clone());
+
+        match path.split('/').collect::<Vec<_>>().as_slice() {
+            ["users.json"] => {
+                let users: Vec<SlackUser> = serde_json::from_slice(&json).map_err(invalid)?;
+                export
+                    .users
+                    .extend(users.into_iter().map(|u| (u.id.clone(), u)));
+            }
+            [file @ ("channels.json" | "groups.json")] => {
+                let mut channels: Vec<SlackChannel> =
+                    serde_json::from_slice(&json).map_err(invalid)?;
+                for channel in &mut channels {
+                    channel.is_private = *file == "groups.json";
+                }
+                export.channels.extend(channels);
+            }
+            [channel, _day] => {
+                let messages: Vec<SlackMessage> = serde_json::from_slice(&json).map_err(invalid)?;
+                export
+                    .messages
+                    .entry(channel.to_string())
+                    .or_default()
+                    .extend(messages);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(export)
+}
+
+/// The member ids the export's users would be imported as, matched by email. Only the ones
+/// verified to be members of the importer's organization are used by [`plan_import`].
+pub fn member_candidates(export: &SlackExport) -> Vec<String> {
+    let mut candidates: Vec<String> = export.users.values().filter_map(member_candidate).collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+fn member_candidate(user: &SlackUser) -> Option<String> {
+    if user.is_bot {
+        return None;
+    }
+    let email = user.profile.email.as_deref()?.trim();
+    (!email.is_empty()).then(|| format!("macro|{}", email.to_lowercase()))
+}
+
+/// Builds the channels to create from an export, only the named ones if any are given. Slack
+/// users are matched to members by email, but only the `verified` members are attributed
+/// messages and reactions or added to the channels. Anyone else is imported by name only, since
+/// the export is uploaded by the importer and can claim any email. The importer owns the channels.
+pub fn plan_import(
+    export: SlackExport,
+    only: Option<&[String]>,
+    owner_id: &str,
+    verified: &HashSet<String>,
+) -> Result<Vec<ImportChannelOptions>, SlackImportError> {
+    let member_ids: HashMap<&str, String> = export
+        .users
+        .values()
+        .filter_map(|u| Some((u.id.as_str(), member_candidate(u)?)))
+        .filter(|(_, member_id)| verified.contains(member_id))
+        .collect();
+    let names: HashMap<&str, &str> = export
+        .users
+        .values()
+        .map(|u| (u.id.as_str(), display_name(u)))
+        .collect();
+
+    let channels: Vec<&SlackChannel> = export
+        .channels
+        .iter()
+        .filter(|c| only.is_none_or(|only| only.contains(&c.name)))
+        .collect();
+    if channels.is_empty() {
+        return Err(SlackImportError::NoChannels);
+    }
+
+    let total: usize = channels
+        .iter()
+        .map(|c| export.messages.get(&c.name).map_or(0, Vec::len))
+        .sum();
+    if total > MAX_IMPORTED_MESSAGES {
+        return Err(SlackImportError::TooLarge);
+    }
+
+    let plans = channels
+        .into_iter()
+        .map(|channel| {
+            let participants = channel
+                .members
+                .iter()
+                .filter(|id| export.users.get(*id).is_none_or(|u| !u.deleted))
+                .filter_map(|id| member_ids.get(id.as_str()).cloned())
+                .collect();
+
+            let mut messages: Vec<&SlackMessage> = export
+                .messages
+                .get(&channel.name)
+                .map(|m| m.iter().collect())
+                .unwrap_or_default();
+            // thread parents are sent before their replies
+            messages.sort_by_key(|m| parse_ts(&m.ts));
+
+            ImportChannelOptions {
+                name: channel.name.clone(),
+                channel_type: if channel.is_private {
+                    ChannelType::Private
+                } else {
+                    ChannelType::Public
+                },
+                owner_id: owner_id.to_string(),
+                org_id: None,
+                participants,
+                messages: convert_messages(&messages, &member_ids, &names),
+            }
+        })
+        .collect();
+
+    Ok(plans)
+}
+
+fn convert_messages(
+    messages: &[&SlackMessage],
+    member_ids: &HashMap<&str, String>,
+    names: &HashMap<&str, &str>,
+) -> Vec<ImportedMessage> {
+    let mut ids_by_ts: HashMap<&str, Uuid> = HashMap::new();
+    let mut imported = Vec::with_capacity(messages.len());
+
+    for message in messages {
+        if message
+            .subtype
+            .as_deref()
+            .is_some_and(|s| !IMPORTED_SUBTYPES.contains(&s))
+        {
+            continue;
+        }
+        let Some(created_at) = parse_ts(&message.ts) else {
+            continue;
+        };
+
+        let mut content = convert_text(&message.text, names);
+        for file in &message.files {
+            let title = file
+                .title
+                .as_deref()
+                .or(file.name.as_deref())
+                .unwrap_or("file");
+            let file = match &file.permalink {
+                Some(link) => format!("📎 [{title}]({link})"),
+                None => format!("📎 {title}"),
+            };
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(&file);
+        }
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        let member_id = message
+            .user
+            .as_deref()
+            .and_then(|user| member_ids.get(user));
+        let sender_id = match member_id {
+            Some(member_id) => member_id.clone(),
+            None => {
+                let name = message
+                    .username
+                    .as_deref()
+                    .or_else(|| message.user.as_deref().and_then(|u| names.get(u).copied()))
+                    .unwrap_or("Slack");
+                content = format!("**{name}:** {content}");
+                SLACK_IMPORT_BOT_ID.to_string()
+            }
+        };
+
+        let thread_id = message
+            .thread_ts
+            .as_deref()
+            .filter(|thread_ts| *thread_ts != message.ts)
+            .and_then(|thread_ts| ids_by_ts.get(thread_ts).copied());
+
+        let reactions = message
+            .reactions
+            .iter()
+            .filter_map(|r| Some((emoji(&r.name)?, &r.users)))
+            .flat_map(|(emoji, users)| {
+                users
+                    .iter()
+                    .filter_map(|u| member_ids.get(u.as_str()))
+                    .map(move |u| (emoji.clone(), u.clone()))
+            })
+            .collect();
+
+        // ids keep the original order of the messages
+        let id = Uuid::new_v7(Timestamp::from_unix(
+            NoContext,
+            created_at.timestamp() as u64,
+            created_at.timestamp_subsec_nanos(),
+        ));
+        ids_by_ts.insert(&message.ts, id);
+
+        imported.push(ImportedMessage {
+            id,
+            thread_id,
+            sender_id,
+            content,
+            created_at,
+            edited_at: message.edited.as_ref().and_then(|e| parse_ts(&e.ts)),
+            reactions,
+        });
+    }
+
+    imported
+}
+
+fn display_name(user: &SlackUser) -> &str {
+    [
+        user.profile.display_name.as_deref(),
+        user.profile.real_name.as_deref(),
+        user.real_name.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|name| !name.trim().is_empty())
+    .unwrap_or(&user.name)
+}
+
+/// Parses a Slack timestamp, seconds since the epoch with microseconds like `1709283600.000100`
+fn parse_ts(ts: &str) -> Option<DateTime<Utc>> {
+    let (seconds, micros) = ts.split_once('.').unwrap_or((ts, "0"));
+    let seconds = seconds.parse().ok()?;
+    let micros: u32 = format!("{micros:0<6}").get(..6)?.parse().ok()?;
+    DateTime::from_timestamp(seconds, micros * 1000)
+}
+
+/// The emoji of a Slack reaction name, without its skin tone
+fn emoji(name: &str) -> Option<String> {
+    let name = name.split("::").next().unwrap_or(name);
+    let emoji = EMOJI
+        .iter()
+        .find(|(slack, _)| *slack == name)
+        .map(|(_, emoji)| emoji.to_string())
+        .unwrap_or_else(|| format!(":{name}:"));
+    (emoji.chars().count() <= MAX_EMOJI_LENGTH).then_some(emoji)
+}
+
+/// Converts Slack's message markup to plain markdown: `<@U1>` mentions become names, `<url|label>`
+/// links become markdown links, and escaped characters are restored
+fn convert_text(text: &str, names: &HashMap<&str, &str>) -> String {
+    let mut converted = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        converted.push_str(&rest[..start]);
+
+        let inner = &rest[start + 1..start + end];
+        let (target, label) = match inner.split_once('|') {
+            Some((target, label)) => (target, Some(label)),
+            None => (inner, None),
+        };
+        match target.chars().next() {
+            Some('@') => {
+                let id = &target[1..];
+                let name = names.get(id).copied().or(label).unwrap_or(id);
+                converted.push('@');
+                converted.push_str(name);
+            }
+            Some('#') => {
+                converted.push('#');
+                converted.push_str(label.unwrap_or(&target[1..]));
+            }
+            Some('!') => match (label, &target[1..]) {
+                (Some(label), _) => converted.push_str(label),
+                (None, special @ ("here" | "channel" | "everyone")) => {
+                    converted.push('@');
+                    converted.push_str(special);
+                }
+                _ => {}
+            },
+            _ => match label {
+                Some(label) if target.starts_with("mailto:") => converted.push_str(label),
+                Some(label) => converted.push_str(&format!("[{label}]({target})")),
+                None => converted.push_str(target),
+            },
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+    converted.push_str(rest);
+
+    converted
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn export_zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_convert_text() {
+        let names = HashMap::from([("U1", "Ada")]);
+
+        assert_eq!(
+            convert_text("hi <@U1>, see <#C1|general> and <!here>", &names),
+            "hi @Ada, see #general and @here"
+        );
+        assert_eq!(
+            convert_text("<https://macro.com|Macro> or <https://example.com>", &names),
+            "[Macro](https://macro.com) or https://example.com"
+        );
+        assert_eq!(
+            convert_text("<mailto:a@b.com|a@b.com> &lt;3 &amp;&gt;", &names),
+            "a@b.com <3 &>"
+        );
+        assert_eq!(convert_text("<@U2|bob> a < b", &names), "@bob a < b");
+    }
+
+    #[test]
+    fn test_parse_ts() {
+        let ts = parse_ts("1709283600.000100").unwrap();
+        assert_eq!(ts.timestamp(), 1709283600);
+        assert_eq!(ts.timestamp_subsec_micros(), 100);
+        assert_eq!(parse_ts("1709283600").unwrap().timestamp(), 1709283600);
+        assert!(parse_ts("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_emoji() {
+        assert_eq!(emoji("+1").as_deref(), Some("👍"));
+        assert_eq!(emoji("thumbsup::skin-tone-3").as_deref(), Some("👍"));
+        assert_eq!(emoji("partyparrot").as_deref(), Some(":partyparrot:"));
+        assert_eq!(emoji(&"a".repeat(40)), None);
+    }
+
+    #[test]
+    fn test_plan_import() {
+        let zip = export_zip(&[
+            (
+                "users.json",
+                r#"[
+                    {"id": "U1", "name": "ada", "profile": {"email": "Ada@Macro.com", "display_name": "Ada"}},
+                    {"id": "U2", "name": "bob", "profile": {"email": "bob@macro.com"}},
+                    {"id": "B1", "name": "deploybot", "is_bot": true, "profile": {}}
+                ]"#,
+            ),
+            (
+                "channels.json",
+                r#"[{"id": "C1", "name": "general", "members": ["U1", "U2"]}]"#,
+            ),
+            (
+                "groups.json",
+                r#"[{"id": "G1", "name": "secret", "members": ["U1"]}]"#,
+            ),
+            (
+                "general/2024-03-01.json",
+                r#"[
+                    {"type": "message", "user": "U2", "text": "reply", "ts": "1709283700.000200", "thread_ts": "1709283600.000100"},
+                    {"type": "message", "user": "U1", "text": "hello <@U2>", "ts": "1709283600.000100", "thread_ts": "1709283600.000100",
+                     "reactions": [{"name": "+1", "users": ["U2", "U9"], "count": 2}],
+                     "files": [{"title": "plan.pdf", "permalink": "https://slack.com/files/plan.pdf"}]},
+                    {"type": "message", "subtype": "channel_join", "user": "U2", "text": "<@U2> has joined", "ts": "1709283500.000000"},
+                    {"type": "message", "subtype": "bot_message", "username": "deploybot", "text": "deployed", "ts": "1709283800.000000"}
+                ]"#,
+            ),
+        ]);
+
+        let export = parse_export(&zip).unwrap();
+        let mut names = export.channel_names();
+        names.sort();
+        assert_eq!(names, vec!["general", "secret"]);
+
+        assert_eq!(
+            member_candidates(&export),
+            vec!["macro|ada@macro.com", "macro|bob@macro.com"]
+        );
+
+        // bob isn't in the importer's organization, so the export can't speak for him
+        let verified = HashSet::from(["macro|ada@macro.com".to_string()]);
+        let only = vec!["general".to_string()];
+        let plans = plan_import(export, Some(&only), "macro|owner@macro.com", &verified).unwrap();
+        assert_eq!(plans.len(), 1);
+
+        let plan = &plans[0];
+        assert_eq!(plan.channel_type, ChannelType::Public);
+        assert_eq!(plan.participants, vec!["macro|ada@macro.com"]);
+
+        let messages = &plan.messages;
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].sender_id, "macro|ada@macro.com");
+        assert_eq!(
+            messages[0].content,
+            "hello @bob\n📎 [plan.pdf](https://slack.com/files/plan.pdf)"
+        );
+        assert!(messages[0].reactions.is_empty());
+        assert_eq!(messages[1].sender_id, SLACK_IMPORT_BOT_ID);
+        assert_eq!(messages[1].content, "**bob:** reply");
+        assert_eq!(messages[1].thread_id, Some(messages[0].id));
+        assert!(messages[0].id < messages[1].id);
+        assert_eq!(messages[2].sender_id, SLACK_IMPORT_BOT_ID);
+        assert_eq!(messages[2].content, "**deploybot:** deployed");
+    }
+
+    #[test]
+    fn test_plan_import_verified_reactions() {
+        let zip = export_zip(&[
+            (
+                "users.json",
+                r#"[
+                    {"id": "U1", "name": "ada", "profile": {"email": "ada@macro.com"}},
+                    {"id": "U2", "name": "bob", "profile": {"email": "bob@macro.com"}}
+                ]"#,
+            ),
+            (
+                "channels.json",
+                r#"[{"id": "C1", "name": "general", "members": ["U1", "U2"]}]"#,
+            ),
+            (
+                "general/2024-03-01.json",
+                r#"[{"type": "message", "user": "U1", "text": "hello", "ts": "1709283600.000100",
+                     "reactions": [{"name": "+1", "users": ["U1", "U2"], "count": 2}]}]"#,
+            ),
+        ]);
+
+        let verified = HashSet::from([
+            "macro|ada@macro.com".to_string(),
+            "macro|bob@macro.com".to_string(),
+        ]);
+        let plans = plan_import(
+            parse_export(&zip).unwrap(),
+            None,
+            "macro|owner@macro.com",
+            &verified,
+        )
+        .unwrap();
+        assert_eq!(
+            plans[0].participants.len(),
+            2,
+            "verified members are added to the channel"
+        );
+        assert_eq!(
+            plans[0].messages[0].reactions,
+            vec![
+                ("👍".to_string(), "macro|ada@macro.com".to_string()),
+                ("👍".to_string(), "macro|bob@macro.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_export_counts_unpacked_bytes() {
+        let zip = export_zip(&[
+            ("users.json", "[]"),
+            ("channels.json", &format!("[{}]", " ".repeat(100))),
+        ]);
+        assert!(parse_export_within(&zip, 200).is_ok());
+        assert!(matches!(
+            parse_export_within(&zip, 50),
+            Err(SlackImportError::TooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_plan_import_no_channels() {
+        let zip = export_zip(&[("users.json", "[]")]);
+        let export = parse_export(&zip).unwrap();
+        assert!(matches!(
+            plan_import(export, None, "macro|owner@macro.com", &HashSet::new()),
+            Err(SlackImportError::NoChannels)
+        ));
+        assert!(matches!(
+            parse_export(b"not a zip"),
+            Err(SlackImportError::Zip(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+use comms_db_client::model::NewAttachment;
+use rand::{Rng, distr::Alphanumeric};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The length of a webhook's secret token
+const TOKEN_LENGTH: usize = 40;
+/// The window the webhook rate limit counts sends over
+pub const RATE_LIMIT_WINDOW_SECONDS: i64 = 60;
+/// The most sends a webhook can make in one window
+pub const MAX_SENDS_PER_WINDOW: i32 = 30;
+/// The longest message a webhook can post, once rendered
+const MAX_CONTENT_LENGTH: usize = 10_000;
+const MAX_FIELDS: usize = 25;
+const MAX_ENTITIES: usize = 10;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// A message an external service posts through an incoming webhook
+pub struct WebhookMessage {
+    /// markdown body of the message
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// http(s) link the title points to
+    #[serde(default)]
+    pub title_link: Option<String>,
+    /// name and value pairs, eg. the branch and status of a build
+    #[serde(default)]
+    pub fields: Vec<WebhookField>,
+    /// Macro items the message links to, attached to the message
+    #[serde(default)]
+    pub entities: Vec<NewAttachment>,
+    /// reply in a thread of the channel
+    #[serde(default)]
+    pub thread_id: Option<Uuid>,
+}
+
+/// Generates the secret token of a new webhook
+pub fn generate_token() -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// The hash stored for a webhook token, the token itself is never stored
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Renders a webhook message to the markdown content of a channel message
+pub fn render_content(message: &WebhookMessage) -> Result<String, String> {
+    if message.fields.len() > MAX_FIELDS {
+        return Err(format!("a message can have at most {MAX_FIELDS} fields"));
+    }
+    if message.entities.len() > MAX_ENTITIES {
+        return Err(format!("a message can link at most {MAX_ENTITIES} items"));
+    }
+
+    let mut blocks: Vec<String> = Vec::new();
+
+    if let Some(title) = non_empty(message.title.as_deref()) {
+        match non_empty(message.title_link.as_deref()) {
+            Some(link) if is_http_link(link) => blocks.push(format!("**[{title}]({link})**")),
+            Some(_) => return Err("title_link must be an http(s) link".to_string()),
+            None => blocks.push(format!("**{title}**")),
+        }
+    }
+
+    if let Some(text) = non_empty(message.text.as_deref()) {
+        blocks.push(text.to_string());
+    }
+
+    if !message.fields.is_empty() {
+        blocks.push(
+            message
+                .fields
+                .iter()
+                .map(|field| format!("**{}**: {}", field.name.trim(), field.value.trim()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+
+    let content = blocks.join("\n\n");
+    if content.is_empty() && message.entities.is_empty() {
+        return Err("message has no content".to_string());
+    }
+    if content.chars().count() > MAX_CONTENT_LENGTH {
+        return Err(format!(
+            "message is longer than {MAX_CONTENT_LENGTH} characters"
+        ));
+    }
+
+    Ok(content)
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn is_http_link(link: &str) -> bool {
+    link.starts_with("https://") || link.starts_with("http://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> WebhookMessage {
+        WebhookMessage {
+            text: None,
+            title: None,
+            title_link: None,
+            fields: vec![],
+            entities: vec![],
+            thread_id: None,
+        }
+    }
+
+    #[test]
+    fn test_render_content() {
+        let rendered = render_content(&WebhookMessage {
+            text: Some("All checks passed".to_string()),
+            title: Some("Build #42".to_string()),
+            title_link: Some("https://ci.example.com/42".to_string()),
+            fields: vec![
+                WebhookField {
+                    name: "branch".to_string(),
+                    value: "main".to_string(),
+                },
+                WebhookField {
+                    name: "duration".to_string(),
+                    value: " 3m ".to_string(),
+                },
+            ],
+            ..message()
+        });
+        assert_eq!(
+            rendered.as_deref(),
+            Ok(
+                "**[Build #42](https://ci.example.com/42)**\n\nAll checks passed\n\n**branch**: main\n**duration**: 3m"
+            )
+        );
+
+        assert_eq!(
+            render_content(&WebhookMessage {
+                title: Some("Deploy".to_string()),
+                ..message()
+            })
+            .as_deref(),
+            Ok("**Deploy**")
+        );
+    }
+
+    #[test]
+    fn test_render_content_rejects_invalid_messages() {
+        assert!(render_content(&message()).is_err());
+        assert!(
+            render_content(&WebhookMessage {
+                text: Some("   ".to_string()),
+                ..message()
+            })
+            .is_err()
+        );
+        assert!(
+            render_content(&WebhookMessage {
+                title: Some("Build".to_string()),
+                title_link: Some("javascript:alert(1)".to_string()),
+                ..message()
+            })
+            .is_err()
+        );
+        assert!(
+            render_content(&WebhookMessage {
+                text: Some("x".repeat(MAX_CONTENT_LENGTH + 1)),
+                ..message()
+            })
+            .is_err()
+        );
+        // a message can be just links to Macro items
+        assert_eq!(
+            render_content(&WebhookMessage {
+                entities: vec![NewAttachment {
+                    entity_type: "document".to_string(),
+                    entity_id: "doc".to_string(),
+                }],
+                ..message()
+            })
+            .as_deref(),
+            Ok("")
+        );
+    }
+
+    #[test]
+    fn test_token() {
+        let token = generate_token();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), hash_token("other"));
+        assert_eq!(hash_token("").len(), 64);
+    }
+}
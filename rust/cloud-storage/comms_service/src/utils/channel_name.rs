@@ -106,6 +106,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         let channel_without_name = Channel {
@@ -116,6 +117,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         let participants_with_name = make_participants(&channel_with_name.id);
@@ -149,6 +151,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         let participants = make_participants(&direct_message_channel.id);
@@ -175,6 +178,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         // Test the preview for a user who is not in the channel
@@ -202,6 +206,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         let public_channel = Channel {
@@ -212,6 +217,7 @@ mod tests {
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             owner_id: "test".to_string(),
+            archived_at: None,
         };
 
         let org_participants = make_participants(&organization_channel.id);
@@ -7,6 +7,7 @@ pub mod create_welcome_message;
 pub mod error;
 pub mod mentions;
 pub mod messages;
+pub mod moderation;
 pub mod organization;
 pub mod participants;
 pub mod permissions;
@@ -0,0 +1,33 @@
+use super::CommsServiceClient;
+use crate::error::{ClientError, ResponseExt};
+use model::comms::{GetModerationActionsRequest, GetModerationActionsResponse};
+
+impl CommsServiceClient {
+    /// Get the moderation actions taken in the channels of an organization, newest first
+    #[tracing::instrument(skip(self))]
+    pub async fn get_moderation_actions(
+        &self,
+        req: GetModerationActionsRequest,
+    ) -> Result<GetModerationActionsResponse, ClientError> {
+        let response = self
+            .client
+            .get(format!("{}/internal/moderation_actions", self.url))
+            .query(&req)
+            .send()
+            .await
+            .map_client_error()
+            .await?;
+
+        let result = response
+            .json::<GetModerationActionsResponse>()
+            .await
+            .map_err(|e| {
+                ClientError::Generic(anyhow::anyhow!(
+                    "unable to parse response from get_moderation_actions: {}",
+                    e.to_string()
+                ))
+            })?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,16 @@
+-- time decayed affinity between two users, per kind of interaction.
+-- score is the decayed sum of interaction weights as of last_interaction_at
+CREATE TABLE affinities (
+	user1 TEXT NOT NULL,
+	user2 TEXT NOT NULL,
+	kind TEXT NOT NULL,
+	score DOUBLE PRECISION NOT NULL,
+	interaction_count INTEGER NOT NULL DEFAULT 1,
+	last_interaction_at timestamptz NOT NULL,
+	created_at timestamptz NOT NULL DEFAULT now(),
+	updated_at timestamptz NOT NULL DEFAULT now(),
+	PRIMARY KEY (user1, user2, kind),
+	CONSTRAINT affinities_check CHECK (user1 <= user2 COLLATE "C")
+);
+
+CREATE INDEX idx_affinities_user2 ON affinities(user2);
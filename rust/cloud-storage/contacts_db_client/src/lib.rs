@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres, Transaction};
 
@@ -75,6 +76,94 @@ pub async fn get_contacts(db: &Pool<Postgres>, user: &str) -> Result<Vec<String>
     Ok(users)
 }
 
+/// The affinity of a user to one of their contacts, for one kind of interaction
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Affinity {
+    pub contact: String,
+    pub kind: String,
+    /// The decayed sum of interaction weights as of `last_interaction_at`
+    pub score: f64,
+    pub interaction_count: i32,
+    pub last_interaction_at: DateTime<Utc>,
+}
+
+/// Records an interaction of the given kind between each pair of users. The existing score of a
+/// pair is decayed by the time since its last interaction before the weight is added, halving
+/// every `half_life_seconds`.
+pub async fn record_interactions(
+    transaction: &mut Transaction<'_, Postgres>,
+    pairs: Vec<(String, String)>,
+    kind: &str,
+    weight: f64,
+    occurred_at: DateTime<Utc>,
+    half_life_seconds: f64,
+) -> Result<(), sqlx::Error> {
+    let mut pairs: Vec<(String, String)> = pairs
+        .into_iter()
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| if a < b { (a, b) } else { (b, a) })
+        .collect();
+    // a pair can only be upserted once per statement
+    pairs.sort();
+    pairs.dedup();
+
+    if pairs.is_empty() {
+        return Ok(());
+    }
+
+    let (users1, users2): (Vec<String>, Vec<String>) = pairs.into_iter().unzip();
+
+    sqlx::query(
+        "
+        INSERT INTO affinities (user1, user2, kind, score, last_interaction_at)
+        SELECT user1, user2, $3, $4, $5
+        FROM UNNEST($1::text[], $2::text[]) AS pairs(user1, user2)
+        ON CONFLICT (user1, user2, kind) DO UPDATE SET
+            score = affinities.score * POWER(
+                0.5,
+                GREATEST(
+                    EXTRACT(EPOCH FROM (EXCLUDED.last_interaction_at - affinities.last_interaction_at))::double precision,
+                    0
+                ) / $6
+            ) + EXCLUDED.score,
+            interaction_count = affinities.interaction_count + 1,
+            last_interaction_at = GREATEST(affinities.last_interaction_at, EXCLUDED.last_interaction_at),
+            updated_at = now()
+        ",
+    )
+    .bind(users1)
+    .bind(users2)
+    .bind(kind)
+    .bind(weight)
+    .bind(occurred_at)
+    .bind(half_life_seconds)
+    .execute(transaction.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the affinities of a user to each of their contacts, one row per contact and kind
+pub async fn get_affinities(db: &Pool<Postgres>, user: &str) -> Result<Vec<Affinity>> {
+    let affinities = sqlx::query_as::<_, Affinity>(
+        "
+        SELECT
+            CASE WHEN user1 = $1 THEN user2 ELSE user1 END AS contact,
+            kind,
+            score,
+            interaction_count,
+            last_interaction_at
+        FROM affinities
+        WHERE user1 = $1 OR user2 = $1
+        ",
+    )
+    .bind(user)
+    .fetch_all(db)
+    .await?;
+
+    Ok(affinities)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,4 +309,49 @@ mod tests {
         assert_eq!(&expectations, &reality);
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn test_record_interactions(pool: PgPool) -> sqlx::Result<()> {
+        let user1 = "05E6766A-7972-4116-8BAD-2038E57D5ADF".to_string();
+        let user2 = "CD7230E3-7718-4692-9C32-7C76BD70C076".to_string();
+        let now = Utc::now();
+
+        let mut transaction = pool.begin().await?;
+        // the pair is recorded once per call, in either order, and self pairs are skipped
+        record_interactions(
+            &mut transaction,
+            vec![
+                (user2.clone(), user1.clone()),
+                (user1.clone(), user2.clone()),
+                (user1.clone(), user1.clone()),
+            ],
+            "email",
+            1.0,
+            now - chrono::Duration::days(30),
+            30.0 * 24.0 * 60.0 * 60.0,
+        )
+        .await?;
+        record_interactions(
+            &mut transaction,
+            vec![(user1.clone(), user2.clone())],
+            "email",
+            1.0,
+            now,
+            30.0 * 24.0 * 60.0 * 60.0,
+        )
+        .await?;
+        transaction.commit().await?;
+
+        let affinities = get_affinities(&pool, &user2).await.unwrap();
+        assert_eq!(affinities.len(), 1);
+
+        let affinity = &affinities[0];
+        assert_eq!(affinity.contact, user1);
+        assert_eq!(affinity.kind, "email");
+        assert_eq!(affinity.interaction_count, 2);
+        // the first interaction decayed by one half life
+        assert!((affinity.score - 1.5).abs() < 0.001);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use contacts_db_client::Affinity;
+use macro_user_id::user_id::MacroUserId;
+use model::contacts::InteractionKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// The time it takes for the weight of an interaction to halve
+pub const HALF_LIFE_SECONDS: f64 = 30.0 * 24.0 * 60.0 * 60.0;
+
+/// The weight a single interaction adds to the affinity of two users
+pub fn interaction_weight(kind: InteractionKind) -> f64 {
+    match kind {
+        InteractionKind::Email => 1.0,
+        InteractionKind::Channel => 0.5,
+        InteractionKind::DocumentShare => 3.0,
+        InteractionKind::Mention => 2.0,
+    }
+}
+
+/// What suggestions are ranked for
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionPurpose {
+    /// The people to share an item with
+    #[default]
+    Share,
+    /// The people to @-mention
+    Mention,
+}
+
+impl SuggestionPurpose {
+    /// How much each kind of interaction counts towards the purpose
+    fn multiplier(&self, kind: InteractionKind) -> f64 {
+        match (self, kind) {
+            (SuggestionPurpose::Share, InteractionKind::DocumentShare) => 1.5,
+            (SuggestionPurpose::Share, InteractionKind::Email) => 1.2,
+            (SuggestionPurpose::Share, InteractionKind::Mention) => 1.0,
+            (SuggestionPurpose::Share, InteractionKind::Channel) => 0.8,
+            (SuggestionPurpose::Mention, InteractionKind::Mention) => 1.5,
+            (SuggestionPurpose::Mention, InteractionKind::Channel) => 1.2,
+            (SuggestionPurpose::Mention, InteractionKind::DocumentShare) => 1.0,
+            (SuggestionPurpose::Mention, InteractionKind::Email) => 0.6,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, PartialEq)]
+pub struct Suggestion {
+    pub user_id: String,
+    /// The decayed affinity of the user to the suggested person, higher is closer
+    pub score: f64,
+    pub interaction_count: i32,
+    pub last_interaction_at: DateTime<Utc>,
+}
+
+/// Decays a score recorded at `at` to its value at `now`
+pub fn decay(score: f64, at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let elapsed_seconds = (now - at).num_seconds().max(0) as f64;
+    score * 0.5_f64.powf(elapsed_seconds / HALF_LIFE_SECONDS)
+}
+
+/// Checks if the display name of a contact, or the email they are known by, contains the query.
+/// The query is expected to be lowercase.
+fn matches_query(contact: &str, names: &HashMap<String, String>, query: &str) -> bool {
+    let name_matches = names
+        .get(contact)
+        .is_some_and(|name| name.to_lowercase().contains(query));
+    let email_matches = MacroUserId::parse_from_str(contact)
+        .is_ok_and(|user_id| user_id.email_part().as_ref().to_lowercase().contains(query));
+
+    name_matches || email_matches
+}
+
+/// Ranks the contacts of a user for a purpose, by their decayed affinity summed over every kind of
+/// interaction. Only contacts whose display name in `names` or email contains the query are ranked.
+pub fn rank(
+    affinities: &[Affinity],
+    purpose: SuggestionPurpose,
+    query: Option<&str>,
+    names: &HashMap<String, String>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let query = query
+        .map(|query| query.trim().to_lowercase())
+        .filter(|query| !query.is_empty());
+
+    let mut suggestions: HashMap<&str, Suggestion> = HashMap::new();
+    for affinity in affinities {
+        let Some(kind) = InteractionKind::parse(&affinity.kind) else {
+            continue;
+        };
+        if query
+            .as_deref()
+            .is_some_and(|query| !matches_query(&affinity.contact, names, query))
+        {
+            continue;
+        }
+
+        let score =
+            decay(affinity.score, affinity.last_interaction_at, now) * purpose.multiplier(kind);
+        let suggestion = suggestions
+            .entry(affinity.contact.as_str())
+            .or_insert_with(|| Suggestion {
+                user_id: affinity.contact.clone(),
+                score: 0.0,
+                interaction_count: 0,
+                last_interaction_at: affinity.last_interaction_at,
+            });
+        suggestion.score += score;
+        suggestion.interaction_count += affinity.interaction_count;
+        suggestion.last_interaction_at = suggestion
+            .last_interaction_at
+            .max(affinity.last_interaction_at);
+    }
+
+    let mut suggestions: Vec<Suggestion> = suggestions.into_values().collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.last_interaction_at.cmp(&a.last_interaction_at))
+            .then_with(|| a.user_id.cmp(&b.user_id))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn affinity(contact: &str, kind: InteractionKind, score: f64, at: DateTime<Utc>) -> Affinity {
+        Affinity {
+            contact: contact.to_string(),
+            kind: kind.as_str().to_string(),
+            score,
+            interaction_count: 1,
+            last_interaction_at: at,
+        }
+    }
+
+    #[test]
+    fn test_decay() {
+        let now = Utc::now();
+        assert_eq!(decay(4.0, now, now), 4.0);
+        let decayed = decay(4.0, now - Duration::days(60), now);
+        assert!((decayed - 1.0).abs() < 0.001);
+        // interactions in the future don't grow
+        assert_eq!(decay(4.0, now + Duration::days(1), now), 4.0);
+    }
+
+    #[test]
+    fn test_rank() {
+        let now = Utc::now();
+        let affinities = vec![
+            affinity("macro|ann@macro.com", InteractionKind::Mention, 2.0, now),
+            affinity("macro|ann@macro.com", InteractionKind::Email, 1.0, now),
+            affinity(
+                "macro|bob@macro.com",
+                InteractionKind::DocumentShare,
+                3.0,
+                now - Duration::days(1),
+            ),
+            // a close contact, but long ago
+            affinity(
+                "macro|cat@macro.com",
+                InteractionKind::DocumentShare,
+                30.0,
+                now - Duration::days(365),
+            ),
+            affinity("macro|dan@macro.com", InteractionKind::Email, 1.0, now),
+        ];
+
+        let names = HashMap::from([(
+            "macro|dan@macro.com".to_string(),
+            "Daniel Smith".to_string(),
+        )]);
+
+        let share = rank(&affinities, SuggestionPurpose::Share, None, &names, now, 10);
+        let share_ids: Vec<&str> = share.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(
+            share_ids,
+            vec![
+                "macro|bob@macro.com",
+                "macro|ann@macro.com",
+                "macro|dan@macro.com",
+                "macro|cat@macro.com",
+            ]
+        );
+        assert_eq!(share[1].interaction_count, 2);
+
+        let mention = rank(
+            &affinities,
+            SuggestionPurpose::Mention,
+            None,
+            &names,
+            now,
+            2,
+        );
+        let mention_ids: Vec<&str> = mention.iter().map(|s| s.user_id.as_str()).collect();
+        assert_eq!(
+            mention_ids,
+            vec!["macro|ann@macro.com", "macro|bob@macro.com"]
+        );
+
+        let filter = |query| {
+            rank(
+                &affinities,
+                SuggestionPurpose::Share,
+                Some(query),
+                &names,
+                now,
+                10,
+            )
+            .into_iter()
+            .map(|s| s.user_id)
+            .collect::<Vec<_>>()
+        };
+
+        // by email
+        assert_eq!(filter(" ANN "), vec!["macro|ann@macro.com"]);
+        // by display name
+        assert_eq!(filter("smith"), vec!["macro|dan@macro.com"]);
+        // the id prefix is not part of the email
+        assert!(filter("macro|").is_empty());
+    }
+}
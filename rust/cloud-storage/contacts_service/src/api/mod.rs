@@ -1,17 +1,22 @@
+use crate::affinity::{self, Suggestion, SuggestionPurpose};
 use crate::api::context::AppState;
 use anyhow::Context;
 use async_trait::async_trait;
-use axum::extract::{Json, State};
+use authentication_service_client::AuthServiceClient;
+use axum::extract::{Json, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::{Extension, Router};
+use chrono::Utc;
+use contacts_db_client::Affinity;
 use model::user::UserContext;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::instrument;
-use utoipa::{OpenApi, ToSchema};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 
 pub(crate) mod context;
@@ -43,17 +48,47 @@ pub struct GetContactsResponse {
     contacts: Vec<String>,
 }
 
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct GetSuggestionsResponse {
+    suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetSuggestionsParams {
+    /// What the suggestions are for, share or mention. Defaults to share.
+    purpose: Option<SuggestionPurpose>,
+    /// Only suggest contacts whose display name or email contains the query
+    query: Option<String>,
+    /// Limit the number of suggestions returned. Defaults to 10. Max 50.
+    limit: Option<usize>,
+}
+
+const DEFAULT_SUGGESTIONS_LIMIT: usize = 10;
+const MAX_SUGGESTIONS_LIMIT: usize = 50;
+
 #[async_trait]
 pub trait ContactsService: Send + Sync + std::fmt::Debug + 'static {
     async fn query_contacts(&self, db: &PgPool, user_id: &str) -> Option<Vec<String>>;
+    async fn query_affinities(&self, db: &PgPool, user_id: &str) -> Option<Vec<Affinity>>;
+    /// Gets the display names of users, keyed by user id
+    async fn query_names(&self, user_ids: Vec<String>) -> Option<HashMap<String, String>>;
 }
 
 #[cfg(test)]
 #[derive(Clone, Debug)]
 pub struct MockService;
 
-#[derive(Clone, Debug, Default)]
-pub struct Service;
+#[derive(Clone)]
+pub struct Service {
+    pub auth_service_client: AuthServiceClient,
+}
+
+impl std::fmt::Debug for Service {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Service").finish_non_exhaustive()
+    }
+}
 
 #[cfg(test)]
 #[async_trait]
@@ -89,6 +124,45 @@ impl ContactsService for MockService {
 
         None
     }
+
+    async fn query_affinities(&self, _db: &PgPool, user_id: &str) -> Option<Vec<Affinity>> {
+        if user_id != "a2b9b60f-a7f0-4bee-bcf1-0851eeec1c05" {
+            return None;
+        }
+
+        let now = Utc::now();
+        let affinities = [
+            ("0bcabd1a-1bf5-48d7-b334-5f7e59e8a9ff", "channel", 4.0),
+            (
+                "3a90b186-0288-4819-8e1a-8e10cb685c0c",
+                "document_share",
+                3.0,
+            ),
+            ("3a90b186-0288-4819-8e1a-8e10cb685c0c", "mention", 2.0),
+            ("e3cf7c46-60c9-413a-8f27-57c91c3297cf", "email", 1.0),
+        ]
+        .into_iter()
+        .map(|(contact, kind, score)| Affinity {
+            contact: contact.to_string(),
+            kind: kind.to_string(),
+            score,
+            interaction_count: 1,
+            last_interaction_at: now,
+        })
+        .collect();
+
+        Some(affinities)
+    }
+
+    async fn query_names(&self, user_ids: Vec<String>) -> Option<HashMap<String, String>> {
+        let names = user_ids
+            .into_iter()
+            .filter(|user_id| user_id == "e3cf7c46-60c9-413a-8f27-57c91c3297cf")
+            .map(|user_id| (user_id, "Erin Carter".to_string()))
+            .collect();
+
+        Some(names)
+    }
 }
 
 #[async_trait]
@@ -97,6 +171,38 @@ impl ContactsService for Service {
         let contacts = contacts_db_client::get_contacts(db, user_id).await;
         contacts.ok()
     }
+
+    async fn query_affinities(&self, db: &PgPool, user_id: &str) -> Option<Vec<Affinity>> {
+        contacts_db_client::get_affinities(db, user_id)
+            .await
+            .inspect_err(|e| tracing::error!(error=?e, "unable to get affinities"))
+            .ok()
+    }
+
+    async fn query_names(&self, user_ids: Vec<String>) -> Option<HashMap<String, String>> {
+        let names = self
+            .auth_service_client
+            .get_names(user_ids)
+            .await
+            .inspect_err(|e| tracing::error!(error=?e, "unable to get names"))
+            .ok()?;
+
+        let names = names
+            .names
+            .into_iter()
+            .filter_map(|name| {
+                let display_name = [name.first_name, name.last_name]
+                    .into_iter()
+                    .flatten()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (!display_name.is_empty()).then_some((name.id, display_name))
+            })
+            .collect();
+
+        Some(names)
+    }
 }
 
 #[utoipa::path(get,
@@ -127,6 +233,64 @@ pub async fn handler(
     (StatusCode::OK, Json(Some(GetContactsResponse { contacts })))
 }
 
+/// Suggests the contacts of the user to share with or mention, ranked by how closely and how
+/// recently the user interacted with them
+#[utoipa::path(get,
+    tag = "contacts",
+    operation_id = "get_contact_suggestions",
+    path = "/contacts/suggestions",
+    params(GetSuggestionsParams),
+    responses(
+    (status = 200, body=GetSuggestionsResponse),
+    (status = 401, body=String),
+    (status = 500, body=String)))
+]
+#[instrument(skip(db, contacts, user_context), level = "info")]
+pub async fn suggestions_handler(
+    State(db): State<PgPool>,
+    State(contacts): State<Arc<dyn ContactsService>>,
+    user_context: Extension<UserContext>,
+    Query(params): Query<GetSuggestionsParams>,
+) -> impl IntoResponse {
+    let user_id = &user_context.user_id.to_lowercase();
+    let Some(affinities) = contacts.query_affinities(&db, user_id).await else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(None));
+    };
+
+    // names are only needed to filter by the query. Without them, contacts are matched by email.
+    let names = match params.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => {
+            let contact_ids = affinities
+                .iter()
+                .map(|affinity| affinity.contact.clone())
+                .collect::<HashSet<_>>();
+            contacts
+                .query_names(contact_ids.into_iter().collect())
+                .await
+                .unwrap_or_default()
+        }
+        _ => HashMap::new(),
+    };
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SUGGESTIONS_LIMIT)
+        .min(MAX_SUGGESTIONS_LIMIT);
+    let suggestions = affinity::rank(
+        &affinities,
+        params.purpose.unwrap_or_default(),
+        params.query.as_deref(),
+        &names,
+        Utc::now(),
+        limit,
+    );
+
+    (
+        StatusCode::OK,
+        Json(Some(GetSuggestionsResponse { suggestions })),
+    )
+}
+
 fn api_router(app_state: AppState) -> Router {
     contacts_router()
         .layer(axum::middleware::from_fn_with_state(
@@ -142,7 +306,9 @@ fn test_api_router() -> Router {
 }
 
 fn contacts_router() -> Router<AppState> {
-    Router::new().route("/contacts", get(handler))
+    Router::new()
+        .route("/contacts", get(handler))
+        .route("/contacts/suggestions", get(suggestions_handler))
 }
 
 #[cfg(test)]
@@ -221,6 +387,51 @@ mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_get_suggestions() {
+        let user_id = "a2b9b60f-a7f0-4bee-bcf1-0851eeec1c05";
+        let get = |uri: &'static str| {
+            let api = test_api_router().layer(Extension(UserContext {
+                user_id: user_id.to_string(),
+                permissions: None,
+                organization_id: None,
+                fusion_user_id: "".to_string(),
+            }));
+            async move {
+                let response = api
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                let body: GetSuggestionsResponse = serde_json::from_slice(&body).unwrap();
+                body.suggestions
+                    .into_iter()
+                    .map(|suggestion| suggestion.user_id)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            get("/contacts/suggestions").await,
+            vec![
+                "3a90b186-0288-4819-8e1a-8e10cb685c0c",
+                "0bcabd1a-1bf5-48d7-b334-5f7e59e8a9ff",
+                "e3cf7c46-60c9-413a-8f27-57c91c3297cf",
+            ]
+        );
+        assert_eq!(
+            get("/contacts/suggestions?purpose=mention&limit=1").await,
+            vec!["3a90b186-0288-4819-8e1a-8e10cb685c0c"]
+        );
+        assert_eq!(
+            get("/contacts/suggestions?query=CARTER").await,
+            vec!["e3cf7c46-60c9-413a-8f27-57c91c3297cf"]
+        );
+        // ids are not matched
+        assert!(get("/contacts/suggestions?query=e3cf").await.is_empty());
+    }
+
     async fn run_with_id(_pool: PgPool, user_id: &str) -> GetContactsResponse {
         let api = test_api_router().layer(Extension(UserContext {
             user_id: user_id.to_string(),
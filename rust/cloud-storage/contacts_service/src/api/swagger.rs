@@ -1,6 +1,7 @@
 use utoipa::OpenApi;
 
-use super::GetContactsResponse;
+use super::{GetContactsResponse, GetSuggestionsResponse};
+use crate::affinity::{Suggestion, SuggestionPurpose};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -9,10 +10,14 @@ use super::GetContactsResponse;
         ),
         paths(
             super::handler,
+            super::suggestions_handler,
         ),
         components(
             schemas(
-                GetContactsResponse
+                GetContactsResponse,
+                GetSuggestionsResponse,
+                Suggestion,
+                SuggestionPurpose,
             ),
         ),
         tags(
@@ -14,6 +14,10 @@ pub struct Config {
     pub queue_max_messages: i32,
     /// The notification queue wait time seconds
     pub queue_wait_time_seconds: i32,
+    /// The url of the authentication service
+    pub auth_service_url: String,
+    /// The internal api key of the authentication service, a secret name outside of local
+    pub auth_service_secret_key: String,
 }
 
 impl Config {
@@ -39,6 +43,12 @@ impl Config {
             .parse::<i32>()
             .unwrap();
 
+        let auth_service_url = std::env::var("AUTHENTICATION_SERVICE_URL")
+            .context("AUTHENTICATION_SERVICE_URL must be provided")?;
+
+        let auth_service_secret_key = std::env::var("AUTHENTICATION_SERVICE_SECRET_KEY")
+            .context("AUTHENTICATION_SERVICE_SECRET_KEY must be provided")?;
+
         let environment = Environment::new_or_prod();
 
         Ok(Config {
@@ -48,6 +58,8 @@ impl Config {
             queue_url,
             queue_wait_time_seconds,
             queue_max_messages,
+            auth_service_url,
+            auth_service_secret_key,
         })
     }
 
@@ -60,6 +72,8 @@ impl Config {
             queue_url: "".to_string(),
             queue_max_messages: 0,
             queue_wait_time_seconds: 0,
+            auth_service_url: "".to_string(),
+            auth_service_secret_key: "".to_string(),
         }
     }
 }
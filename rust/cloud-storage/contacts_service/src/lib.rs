@@ -1,3 +1,4 @@
+pub mod affinity;
 pub mod graph;
 pub mod queue;
 pub mod user;
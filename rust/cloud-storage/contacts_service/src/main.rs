@@ -1,3 +1,4 @@
+mod affinity;
 mod api;
 mod config;
 mod graph;
@@ -94,12 +95,28 @@ async fn main() -> anyhow::Result<()> {
         JwtValidationArgs::new_with_secret_manager(config.environment, &secretsmanager_client)
             .await?;
 
+    let auth_service_secret_key = match config.environment {
+        Environment::Local => config.auth_service_secret_key.clone(),
+        _ => secretsmanager_client
+            .get_secret_value(&config.auth_service_secret_key)
+            .await
+            .context("unable to get secret")?
+            .to_string(),
+    };
+
+    let auth_service_client = authentication_service_client::AuthServiceClient::new(
+        auth_service_secret_key,
+        config.auth_service_url.clone(),
+    );
+
     api::setup_and_serve(AppState {
         config: Arc::new(config),
         db,
         jwt_args,
         internal_api_secret,
-        contacts_service: Arc::new(Service),
+        contacts_service: Arc::new(Service {
+            auth_service_client,
+        }),
     })
     .await?;
     Ok(())
@@ -1,10 +1,13 @@
+use crate::affinity::{HALF_LIFE_SECONDS, interaction_weight};
 use crate::user::{
     Connection, Group, UserVertex, create_connections_message, create_user, unpack_connections,
     unpack_users,
 };
-use contacts_db_client::create_connections;
+use chrono::Utc;
+use contacts_db_client::{create_connections, record_interactions};
 use model::contacts::{
-    AddParticipantsMessageBody, ConnectionsMessage, CreateGroupMessageBody, Message,
+    AddParticipantsMessageBody, ConnectionsMessage, CreateGroupMessageBody, InteractionKind,
+    Message, RecordInteractionMessageBody,
 };
 use sqlx::{Pool, Postgres};
 use sqs_worker::SQSWorker;
@@ -45,6 +48,56 @@ pub async fn create_group(body: &CreateGroupMessageBody) -> Vec<(String, String)
         .collect()
 }
 
+/// The pairs of an interaction, between the user and each of the counterparts
+pub fn interaction_pairs(body: &RecordInteractionMessageBody) -> Vec<(String, String)> {
+    let user_id = body.user_id.to_lowercase();
+    body.counterparts
+        .iter()
+        .map(|counterpart| counterpart.to_lowercase())
+        .filter(|counterpart| *counterpart != user_id)
+        .map(|counterpart| (user_id.clone(), counterpart))
+        .collect()
+}
+
+/// Creates the connections between each pair and records an interaction of the given kind for
+/// them, so contacts and their affinities stay in step
+async fn write_connections(
+    db: &Pool<Postgres>,
+    connection_pairs: Vec<(String, String)>,
+    kind: InteractionKind,
+) {
+    if connection_pairs.is_empty() {
+        return;
+    }
+
+    let affinity_pairs = connection_pairs
+        .iter()
+        .map(|(a, b)| (a.to_lowercase(), b.to_lowercase()))
+        .collect();
+
+    let mut transaction = db.begin().await.unwrap();
+    let _ = create_connections(&mut transaction, connection_pairs)
+        .await
+        .inspect_err(|e| {
+            tracing::error!("couldn't create connections: {:?}", e);
+        });
+    let _ = record_interactions(
+        &mut transaction,
+        affinity_pairs,
+        kind.as_str(),
+        interaction_weight(kind),
+        Utc::now(),
+        HALF_LIFE_SECONDS,
+    )
+    .await
+    .inspect_err(|e| {
+        tracing::error!("couldn't record interactions: {:?}", e);
+    });
+    let _ = transaction.commit().await.inspect_err(|e| {
+        tracing::error!("transaction error: {:?}", e);
+    });
+}
+
 #[derive(Debug)]
 pub struct MessageQueue {
     sqs: SQSWorker,
@@ -54,51 +107,50 @@ pub struct MessageQueue {
 async fn connections_message_handler(conmsg: &ConnectionsMessage, queue: &MessageQueue) {
     let users = unpack_users(conmsg).await;
     let connections = unpack_connections(conmsg, &users).await;
-    let db = &queue.db;
 
     tracing::info!("Writing connections to DB");
-    let mut transaction = db.begin().await.unwrap();
     let connection_pairs: Vec<(String, String)> = connections
         .into_iter()
         .map(|e| (e.a.data.id.to_string(), e.b.data.id.to_string()))
         .collect();
-    let _ = create_connections(&mut transaction, connection_pairs)
-        .await
-        .inspect_err(|e| {
-            tracing::error!("couldn't create connections: {:?}", e);
-        });
-    let _ = transaction.commit().await.inspect_err(|e| {
-        tracing::error!("transaction error: {:?}", e);
-    });
+    write_connections(&queue.db, connection_pairs, InteractionKind::Email).await;
 }
 
 #[instrument(level = "info", skip(queue))]
 async fn add_participants_handler(body: &AddParticipantsMessageBody, queue: &MessageQueue) {
     tracing::info!("adding participants");
-    let db = &queue.db;
     let connection_pairs = add_participants(body).await;
-    let mut transaction = db.begin().await.unwrap();
-    let _ = create_connections(&mut transaction, connection_pairs)
-        .await
-        .inspect_err(|e| {
-            tracing::error!("couldn't create connections: {:?}", e);
-        });
-    let _ = transaction.commit().await.inspect_err(|e| {
-        tracing::error!("transaction error: {:?}", e);
-    });
+    write_connections(&queue.db, connection_pairs, InteractionKind::Channel).await;
 }
 
 #[instrument(level = "info", skip(queue))]
 async fn create_group_handler(body: &CreateGroupMessageBody, queue: &MessageQueue) {
     tracing::info!("creating group");
-    let db = &queue.db;
     let connection_pairs = create_group(body).await;
-    let mut transaction = db.begin().await.unwrap();
-    let _ = create_connections(&mut transaction, connection_pairs)
-        .await
-        .inspect_err(|e| {
-            tracing::error!("couldn't create connections: {:?}", e);
-        });
+    write_connections(&queue.db, connection_pairs, InteractionKind::Channel).await;
+}
+
+#[instrument(level = "info", skip(queue))]
+async fn record_interaction_handler(body: &RecordInteractionMessageBody, queue: &MessageQueue) {
+    tracing::info!("recording interaction");
+    let pairs = interaction_pairs(body);
+    if pairs.is_empty() {
+        return;
+    }
+
+    let mut transaction = queue.db.begin().await.unwrap();
+    let _ = record_interactions(
+        &mut transaction,
+        pairs,
+        body.kind.as_str(),
+        interaction_weight(body.kind),
+        Utc::now(),
+        HALF_LIFE_SECONDS,
+    )
+    .await
+    .inspect_err(|e| {
+        tracing::error!("couldn't record interactions: {:?}", e);
+    });
     let _ = transaction.commit().await.inspect_err(|e| {
         tracing::error!("transaction error: {:?}", e);
     });
@@ -129,6 +181,7 @@ pub async fn process(msg: &Message, queue: &MessageQueue) -> anyhow::Result<()>
         Message::AddConnection(con) => connections_message_handler(con, queue).await,
         Message::AddParticipants(body) => add_participants_handler(body, queue).await,
         Message::CreateGroup(body) => create_group_handler(body, queue).await,
+        Message::RecordInteraction(body) => record_interaction_handler(body, queue).await,
     };
     Ok(())
 }
@@ -522,6 +575,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_interaction_pairs() {
+        let input_json = include_str!("../tests/fixtures/record_interaction.json");
+
+        let body = match message_from_json(input_json) {
+            Some(Message::RecordInteraction(body)) => body,
+            _ => panic!("Message not matched properly"),
+        };
+        assert_eq!(body.kind, InteractionKind::Mention);
+
+        // the user is never paired with themselves
+        let pairs = interaction_pairs(&body);
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    "macro|paul@macro.com".to_string(),
+                    "macro|john@macro.com".to_string()
+                ),
+                (
+                    "macro|paul@macro.com".to_string(),
+                    "macro|ringo@macro.com".to_string()
+                ),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_add_participants_lowercase() {
         // full group, including new participants
@@ -29,6 +29,13 @@ pub fn get_lok_filter_from_file_types(
                 "unsupported conversion of {from_file_type} to {to_file_type}"
             )),
         },
+        FileType::Html => match to_file_type {
+            FileType::Docx => Ok("MS Word 2007 XML".to_string()),
+            FileType::Pdf => Ok("writer_web_pdf_Export".to_string()),
+            _ => Err(anyhow::anyhow!(
+                "unsupported conversion of {from_file_type} to {to_file_type}"
+            )),
+        },
         FileType::Pptx => match to_file_type {
             FileType::Pdf => Ok("impress_pdf_Export".to_string()),
             _ => Err(anyhow::anyhow!(
@@ -0,0 +1,146 @@
+use anyhow::Context;
+use aws_lambda_events::event::eventbridge::EventBridgeEvent;
+use lambda_runtime::{
+    Error, LambdaEvent,
+    tracing::{self},
+};
+
+/// The stored object an event is about
+#[derive(Debug, PartialEq, Eq)]
+enum StoredObject<'a> {
+    /// An uploaded document instance, stored at `user_id/document_id/document_version_id.file_type`
+    DocumentInstance {
+        document_id: &'a str,
+        document_version_id: i64,
+        file_type: Option<&'a str>,
+    },
+    /// A docx bom part, stored under its sha
+    BomPart { sha: &'a str },
+}
+
+impl<'a> StoredObject<'a> {
+    /// Gets the object stored under the key.
+    /// Returns None for keys that are not document content, e.g. temp files.
+    fn from_key(key: &'a str) -> Option<Self> {
+        let parts: Vec<&str> = key.split('/').collect();
+
+        match parts.as_slice() {
+            [sha] if sha.len() == 64 && sha.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Some(Self::BomPart { sha })
+            }
+            [_user_id, document_id, file] => {
+                let (document_version_id, file_type) = match file.split_once('.') {
+                    Some((document_version_id, file_type)) => {
+                        (document_version_id, Some(file_type))
+                    }
+                    None => (*file, None),
+                };
+
+                Some(Self::DocumentInstance {
+                    document_id,
+                    document_version_id: document_version_id.parse().ok()?,
+                    file_type,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Handles the Eventbridge event
+#[tracing::instrument(skip(db))]
+pub async fn handler(db: &sqlx::PgPool, event: LambdaEvent<EventBridgeEvent>) -> Result<(), Error> {
+    tracing::trace!("processing event");
+
+    let object = event
+        .payload
+        .detail
+        .get("object")
+        .context("expected object")?;
+
+    let key = object
+        .get("key")
+        .and_then(|key| key.as_str())
+        .context("expected object key")?;
+
+    let size_bytes = object
+        .get("size")
+        .and_then(|size| size.as_i64())
+        .context("expected object size")?;
+
+    let key = match urlencoding::decode(key) {
+        Ok(decoded) => decoded.to_string(),
+        Err(e) => {
+            tracing::warn!(error=?e, key=%key, "unable to decode key");
+            return Ok(());
+        }
+    };
+
+    let Some(stored_object) = StoredObject::from_key(&key) else {
+        tracing::trace!(key=%key, "skipping object that is not document content");
+        return Ok(());
+    };
+
+    tracing::trace!(stored_object=?stored_object, size_bytes, "recording size");
+
+    match stored_object {
+        StoredObject::DocumentInstance {
+            document_id,
+            document_version_id,
+            file_type,
+        } => {
+            macro_db_client::storage_usage::record_document_instance_size(
+                db,
+                document_id,
+                document_version_id,
+                file_type,
+                size_bytes,
+            )
+            .await?
+        }
+        StoredObject::BomPart { sha } => {
+            macro_db_client::storage_usage::upsert_sha_sizes(db, &[(sha.to_string(), size_bytes)])
+                .await?
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_object_from_key() {
+        assert_eq!(
+            StoredObject::from_key("macro|user@user.com/d1/12.pdf"),
+            Some(StoredObject::DocumentInstance {
+                document_id: "d1",
+                document_version_id: 12,
+                file_type: Some("pdf"),
+            })
+        );
+        assert_eq!(
+            StoredObject::from_key("macro|user@user.com/d1/12"),
+            Some(StoredObject::DocumentInstance {
+                document_id: "d1",
+                document_version_id: 12,
+                file_type: None,
+            })
+        );
+
+        let sha = "a".repeat(64);
+        assert_eq!(
+            StoredObject::from_key(&sha),
+            Some(StoredObject::BomPart { sha: &sha })
+        );
+
+        assert_eq!(StoredObject::from_key("temp_files/abc.pdf"), None);
+        assert_eq!(
+            StoredObject::from_key("macro|user@user.com/d1/latest.pdf"),
+            None
+        );
+        assert_eq!(StoredObject::from_key("not-a-sha"), None);
+    }
+}
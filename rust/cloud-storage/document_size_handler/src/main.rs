@@ -0,0 +1,35 @@
+use anyhow::Context;
+use aws_lambda_events::eventbridge::EventBridgeEvent;
+use handler::handler;
+use lambda_runtime::{Error, LambdaEvent, run, service_fn, tracing};
+use macro_entrypoint::MacroEntrypoint;
+use sqlx::postgres::PgPoolOptions;
+
+mod handler;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    MacroEntrypoint::default().init();
+
+    tracing::trace!("initiating lambda");
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be provided")?;
+
+    // Each invocation handles a single object
+    let db = PgPoolOptions::new()
+        .min_connections(1)
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .context("could not connect to db")?;
+
+    tracing::trace!("initialized db connection");
+
+    let func = service_fn(move |event: LambdaEvent<EventBridgeEvent>| {
+        let db = db.clone();
+
+        async move { handler(&db, event).await }
+    });
+
+    run(func).await
+}
@@ -92,6 +92,7 @@ pub(in crate::api) async fn create_document_handler(
         create_document_v2::CreateDocumentParams {
             id: req.id.as_deref(),
             sha: &req.sha,
+            size_bytes: req.size_bytes,
             document_name: &document_name,
             owner: &user_context.user_id,
             file_type,
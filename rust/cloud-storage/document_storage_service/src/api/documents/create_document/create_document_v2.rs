@@ -12,6 +12,7 @@ use uuid::Uuid;
 pub struct CreateDocumentParams<'a> {
     pub id: Option<&'a str>,
     pub sha: &'a str,
+    pub size_bytes: i64,
     pub document_name: &'a str,
     pub owner: &'a str,
     pub file_type: Option<FileType>,
@@ -33,6 +34,7 @@ pub async fn create_document(
     let CreateDocumentParams {
         id,
         sha,
+        size_bytes,
         document_name,
         owner,
         file_type,
@@ -129,7 +131,7 @@ pub async fn create_document(
     let mime_type = content_type.mime_type().to_string();
 
     let presigned_url: String = match file_type {
-        Some(FileType::Docx) => ctx.s3_client.put_docx_upload_presigned_url(key.as_str(), sha, content_type).await.map_err(|err| {
+        Some(FileType::Docx) => ctx.s3_client.put_docx_upload_presigned_url(key.as_str(), sha, content_type, size_bytes).await.map_err(|err| {
             tracing::error!(error=?err, key=?key, document_id=?document_metadata.document_id, "unable to generate presigned url");
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -137,7 +139,7 @@ pub async fn create_document(
                 Some(document_metadata.document_id.clone()),
             )
         })?,
-        _ => ctx.s3_client.put_document_storage_presigned_url(key.as_str(), sha, content_type).await.map_err(|err| {
+        _ => ctx.s3_client.put_document_storage_presigned_url(key.as_str(), sha, content_type, size_bytes).await.map_err(|err| {
             tracing::error!(error=?err, key=?key, document_id=?document_metadata.document_id, "unable to generate presigned url");
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -1,19 +1,44 @@
 use std::{
     io::{Cursor, Write},
     str::FromStr,
+    time::Duration,
 };
 
-use crate::{api::context::ApiContext, service::s3::TEMP_FILE_PREFIX};
+use crate::{
+    api::context::ApiContext,
+    model::{
+        request::documents::export::{ExportDocumentQueryParams, ExportFormat},
+        response::documents::export::{DocumentExportStatusUpdate, ExportDocumentJobResponse},
+    },
+    service::{
+        conn_gateway::update_document_export_state,
+        export::{
+            archive::sanitize_name, markdown::markdown_to_html, pdf::apply_modification_data,
+        },
+        s3::TEMP_FILE_PREFIX,
+    },
+};
+use anyhow::Context;
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 use futures::StreamExt;
+use macro_db_client::{
+    document::build_pdf_modification_data::{
+        get_complete_pdf_modification_data, get_pdf_modification_data_for_document,
+    },
+    job::export_job::ExportJob,
+};
 use macro_middleware::cloud_storage::ensure_access::document::DocumentAccessExtractor;
 use model::{
-    document::{DocumentBasic, FileType, response::LocationResponseData},
+    convert::ConvertQueueMessage,
+    document::{
+        DocumentBasic, FileType, FileTypeExt, build_cloud_storage_bucket_document_key,
+        response::LocationResponseData,
+    },
     response::{ErrorResponse, GenericErrorResponse},
     user::UserContext,
 };
@@ -26,6 +51,15 @@ use super::location::{
     get_cloudfront_signed_options, get_presigned_url, get_presigned_url_by_type,
 };
 
+/// The prefix for files generated by exports
+pub(in crate::api) static EXPORT_FILE_PREFIX: &str = "export";
+
+/// How often to check if the convert service has finished converting an export
+const CONVERT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for the convert service before giving up on an export
+const CONVERT_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[allow(unused, reason = "this could probably be removed wait for tests")]
 // The document_id field here is not used within the function handler body. Instead we use
 // DocumentBasic::document_id which is the same thing (it comes from the same path) but it is
@@ -41,19 +75,89 @@ pub struct ExportDocumentResponse {
     pub presigned_url: String,
 }
 
-/// Generates a presigned url to download the raw content of the document
-/// For files with modification layers, they will not be applied in the downloaded file.
+/// How the exported file for a document is produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::api) enum ExportPlan {
+    /// The stored content of the document is exported as is
+    Original,
+    /// The pdf is exported with its modification data applied. Only used when the pdf has
+    /// modifications, otherwise the original pdf is exported.
+    AnnotatedPdf,
+    /// The markdown document is rendered to the provided format
+    Markdown(ExportFormat),
+}
+
+impl ExportPlan {
+    /// Gets the export plan for a file type and requested format.
+    /// Returns None if the file type cannot be exported to the format.
+    pub(in crate::api) fn new(
+        file_type: FileType,
+        format: Option<ExportFormat>,
+        has_modifications: bool,
+    ) -> Option<Self> {
+        match (file_type, format) {
+            (FileType::Pdf, None | Some(ExportFormat::Pdf)) if has_modifications => {
+                Some(ExportPlan::AnnotatedPdf)
+            }
+            (FileType::Md, None) => Some(ExportPlan::Markdown(ExportFormat::Md)),
+            (FileType::Md, Some(format)) => Some(ExportPlan::Markdown(format)),
+            (_, None) => Some(ExportPlan::Original),
+            (file_type, Some(format)) if file_type.as_str() == format.extension() => {
+                Some(ExportPlan::Original)
+            }
+            _ => None,
+        }
+    }
+
+    /// The format the convert service has to produce for the export, if any. Conversions are too
+    /// slow to wait for within a request, so these exports run as export jobs.
+    pub(in crate::api) fn conversion_format(&self) -> Option<ExportFormat> {
+        match self {
+            ExportPlan::Markdown(format @ (ExportFormat::Docx | ExportFormat::Pdf)) => {
+                Some(*format)
+            }
+            _ => None,
+        }
+    }
+
+    /// The file extension of the exported file
+    pub(in crate::api) fn extension(&self, file_type: FileType) -> &'static str {
+        match self {
+            ExportPlan::Original => file_type.as_str(),
+            ExportPlan::AnnotatedPdf => FileType::Pdf.as_str(),
+            ExportPlan::Markdown(format) => format.extension(),
+        }
+    }
+}
+
+/// The document being exported
+pub(in crate::api) struct ExportDocument<'a> {
+    pub document_id: &'a str,
+    pub owner: &'a str,
+    pub document_name: &'a str,
+    pub file_type: FileType,
+}
+
+/// Generates a presigned url to download the content of the document.
+/// Pdfs are exported with their highlights and comments applied and markdown documents can be
+/// exported as html, docx or pdf.
+/// Markdown exported to docx or pdf has to go through the convert service, so it runs in the
+/// background instead. The response contains the id of the export job, and the result is sent to
+/// the user over the connection gateway as a `document_export` message.
 #[utoipa::path(
         tag = "document",
         get,
         path = "/documents/{document_id}/export",
         operation_id = "export_document",
         params(
-            ("document_id" = String, Path, description = "Document ID")
+            ("document_id" = String, Path, description = "Document ID"),
+            ("format" = Option<ExportFormat>, Query, description = "The format to export the document to. Defaults to the file type of the document."),
         ),
         responses(
             (status = 200, body=ExportDocumentResponse),
+            (status = 202, body=ExportDocumentJobResponse),
             (status = 304, body=GenericErrorResponse),
+            (status = 400, body=GenericErrorResponse),
             (status = 401, body=GenericErrorResponse),
             (status = 404, body=GenericErrorResponse),
             (status = 500, body=GenericErrorResponse),
@@ -66,6 +170,7 @@ pub async fn handler(
     State(state): State<ApiContext>,
     user_context: Extension<UserContext>,
     document_context: Extension<DocumentBasic>,
+    Query(params): Query<ExportDocumentQueryParams>,
 ) -> Result<Response, Response> {
     tracing::info!("export document");
     if let Some(file_type) = document_context.file_type.as_deref() {
@@ -80,9 +185,73 @@ pub async fn handler(
                 .into_response()
         })?;
 
-        let presigned_url = match file_type {
-            FileType::Docx => export_docx_document(&state, &document_context.document_id).await,
-            _ => {
+        let has_modifications = has_modifications(&state, &document_context.document_id, file_type)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to get modification data");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "unable to export document",
+                    }),
+                )
+                    .into_response()
+            })?;
+
+        let plan =
+            ExportPlan::new(file_type, params.format, has_modifications).ok_or_else(|| {
+                tracing::warn!(format=?params.format, "unsupported export format");
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        message: "cannot export document to the requested format",
+                    }),
+                )
+                    .into_response()
+            })?;
+
+        if let Some(format) = plan.conversion_format() {
+            let export_id = macro_uuid::generate_uuid_v7();
+            macro_db_client::job::export_job::create_document_export_job(
+                &state.db,
+                export_id,
+                &user_context.user_id,
+                &document_context.document_id,
+                format.extension(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to create export job");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "unable to export document",
+                    }),
+                )
+                    .into_response()
+            })?;
+
+            return Ok((
+                StatusCode::ACCEPTED,
+                Json(ExportDocumentJobResponse {
+                    export_id: export_id.to_string(),
+                }),
+            )
+                .into_response());
+        }
+
+        let document = ExportDocument {
+            document_id: &document_context.document_id,
+            owner: &document_context.owner,
+            document_name: &document_context.document_name,
+            file_type,
+        };
+
+        let presigned_url = match (plan, file_type) {
+            (ExportPlan::Original, FileType::Docx) => {
+                export_docx_document(&state, &document_context.document_id).await
+            }
+            (ExportPlan::Original, _) => {
                 export_basic_document(
                     &state,
                     &document_context.owner,
@@ -91,6 +260,10 @@ pub async fn handler(
                 )
                 .await
             }
+            _ => match upload_generated_document(&state, &document, plan).await {
+                Ok(key) => sign_temp_file_url(&state, &key).await,
+                Err(e) => Err(e),
+            },
         }
         .map_err(|e| {
             tracing::error!(error=?e, "unable to export document");
@@ -145,58 +318,317 @@ async fn export_basic_document(
     }
 }
 
-async fn export_docx_document(state: &ApiContext, document_id: &str) -> anyhow::Result<String> {
-    let docx_key = format!("{TEMP_FILE_PREFIX}/{document_id}.docx");
-    let exists = state.s3_client.exists(&docx_key).await?;
+/// Generates the exported file and uploads it to the temp files. Returns the key of the file.
+async fn upload_generated_document(
+    state: &ApiContext,
+    document: &ExportDocument<'_>,
+    plan: ExportPlan,
+) -> anyhow::Result<String> {
+    let content = export_content(state, document, plan).await?;
 
-    if !exists {
-        tracing::trace!("document does not exist in s3, downloading bom parts");
-        let latest_document_bom_parts =
-            macro_db_client::document::get_document_bom(state.db.clone(), document_id).await?;
-
-        let shared_s3_client = &state.s3_client;
-
-        #[expect(clippy::type_complexity, reason = "too annoying to fix now")]
-        let downloaded_bom_parts: Vec<Result<(String, Vec<u8>), (String, anyhow::Error)>> =
-            futures::stream::iter(latest_document_bom_parts.iter())
-                .then(|bp| async move {
-                    let content = shared_s3_client
-                        .get_document(bp.sha.as_str())
-                        .await
-                        .map_err(|e| (bp.sha.clone(), e))?;
-
-                    Ok((bp.path.clone(), content))
-                })
-                .collect()
-                .await;
-
-        if downloaded_bom_parts.iter().any(|r| r.is_err()) {
-            return Err(anyhow::anyhow!("unable to download bom parts"));
+    let key = format!(
+        "{TEMP_FILE_PREFIX}{EXPORT_FILE_PREFIX}/{}/{}.{}",
+        macro_uuid::generate_uuid_v7(),
+        sanitize_name(document.document_name),
+        plan.extension(document.file_type)
+    );
+    state.s3_client.upload_document(&key, content).await?;
+
+    Ok(key)
+}
+
+/// Runs a document export job. Returns an error if the export failed.
+#[tracing::instrument(skip(state, job), fields(export_id=%job.id))]
+pub(in crate::api) async fn run_document_export(
+    state: &ApiContext,
+    job: &ExportJob,
+    document_id: &str,
+) -> anyhow::Result<()> {
+    let format = job
+        .format
+        .as_deref()
+        .and_then(ExportFormat::from_extension)
+        .ok_or_else(|| anyhow::anyhow!("export job has no valid format"))?;
+
+    let document_context =
+        macro_db_client::document::get_basic_document(&state.db, document_id).await?;
+    let file_type = document_context
+        .file_type
+        .as_deref()
+        .map(FileType::from_str)
+        .transpose()?
+        .ok_or_else(|| anyhow::anyhow!("document has no file type"))?;
+    let has_modifications = has_modifications(state, document_id, file_type).await?;
+    let plan = ExportPlan::new(file_type, Some(format), has_modifications)
+        .ok_or_else(|| anyhow::anyhow!("unsupported export format"))?;
+
+    let document = ExportDocument {
+        document_id: &document_context.document_id,
+        owner: &document_context.owner,
+        document_name: &document_context.document_name,
+        file_type,
+    };
+    let key = upload_generated_document(state, &document, plan).await?;
+    macro_db_client::job::export_job::complete_export_job(
+        &state.db,
+        job.id,
+        job.attempts,
+        &key,
+        &[],
+    )
+    .await?;
+    let presigned_url = sign_temp_file_url(state, &key).await?;
+
+    update_document_export_state(
+        &state.conn_gateway_client,
+        &job.user_id,
+        DocumentExportStatusUpdate::Completed {
+            export_id: job.id.to_string(),
+            document_id: document_id.to_string(),
+            presigned_url,
+        },
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Produces the content of an exported document
+#[tracing::instrument(skip(state, document), fields(document_id=document.document_id))]
+pub(in crate::api) async fn export_content(
+    state: &ApiContext,
+    document: &ExportDocument<'_>,
+    plan: ExportPlan,
+) -> anyhow::Result<Vec<u8>> {
+    match plan {
+        ExportPlan::Original => match document.file_type {
+            FileType::Docx => build_docx(state, document.document_id).await,
+            file_type => {
+                let key =
+                    document_content_key(state, document.owner, document.document_id, file_type)
+                        .await?;
+                state.s3_client.get_document(&key).await
+            }
+        },
+        ExportPlan::AnnotatedPdf => {
+            let key =
+                document_content_key(state, document.owner, document.document_id, FileType::Pdf)
+                    .await?;
+            let pdf = state.s3_client.get_document(&key).await?;
+
+            let initial_modification_data =
+                get_pdf_modification_data_for_document(&state.db, document.document_id).await?;
+            let modification_data = get_complete_pdf_modification_data(
+                &state.db,
+                document.document_id,
+                Some(initial_modification_data),
+            )
+            .await?;
+
+            // Pdf parsing and writing is cpu bound
+            tokio::task::spawn_blocking(move || apply_modification_data(&pdf, &modification_data))
+                .await?
+        }
+        ExportPlan::Markdown(format) => {
+            let markdown = get_markdown(state, document).await?;
+            if format == ExportFormat::Md {
+                return Ok(markdown.into_bytes());
+            }
+
+            let html = markdown_to_html(document.document_name, &markdown);
+            match format {
+                ExportFormat::Html => Ok(html.into_bytes()),
+                ExportFormat::Docx => convert_html(state, html, FileType::Docx).await,
+                ExportFormat::Pdf => convert_html(state, html, FileType::Pdf).await,
+                ExportFormat::Md => unreachable!("handled above"),
+            }
         }
+    }
+}
+
+/// Checks if a document has modification data that has to be applied to its exported content.
+/// Only pdfs have modification data.
+pub(in crate::api) async fn has_modifications(
+    state: &ApiContext,
+    document_id: &str,
+    file_type: FileType,
+) -> anyhow::Result<bool> {
+    if file_type != FileType::Pdf {
+        return Ok(false);
+    }
+
+    let initial_modification_data =
+        get_pdf_modification_data_for_document(&state.db, document_id).await?;
+    let modification_data =
+        get_complete_pdf_modification_data(&state.db, document_id, Some(initial_modification_data))
+            .await?;
 
-        let downloaded_bom_parts: Vec<(String, Vec<u8>)> = downloaded_bom_parts
-            .into_iter()
-            .filter_map(|r| r.ok())
-            .collect();
+    let has_highlights = modification_data
+        .highlights
+        .as_ref()
+        .is_some_and(|highlights| highlights.values().any(|page| !page.is_empty()));
 
-        let writer = Cursor::new(Vec::new());
-        let mut zip = zip::ZipWriter::new(writer);
+    Ok(has_highlights || !modification_data.placeables.is_empty())
+}
+
+/// Gets the s3 key of the current content of a document.
+/// Static files always live at their first version while editable files use their latest.
+async fn document_content_key(
+    state: &ApiContext,
+    owner: &str,
+    document_id: &str,
+    file_type: FileType,
+) -> anyhow::Result<String> {
+    let (document_version_id, _) = if file_type.is_static() {
+        macro_db_client::document::get_document_version_id(&state.db, document_id).await?
+    } else {
+        macro_db_client::document::get_latest_document_version_id(&state.db, document_id).await?
+    };
 
-        for part in downloaded_bom_parts {
-            zip.start_file::<_, ()>(part.0, zip::write::FileOptions::default())?;
-            zip.write_all(&part.1)?;
+    Ok(build_cloud_storage_bucket_document_key(
+        owner,
+        document_id,
+        document_version_id,
+        Some(file_type.as_str()),
+    ))
+}
+
+/// Gets the markdown of a document.
+/// Documents that are live in the sync service are rendered by the lexical service, otherwise the
+/// stored markdown is used.
+async fn get_markdown(state: &ApiContext, document: &ExportDocument<'_>) -> anyhow::Result<String> {
+    let in_sync_service = state
+        .sync_service_client
+        .exists(document.document_id)
+        .await
+        .inspect_err(|e| tracing::warn!(error=?e, "unable to check sync service"))
+        .unwrap_or(false);
+
+    if in_sync_service {
+        return state
+            .lexical_client
+            .get_markdown(document.document_id)
+            .await;
+    }
+
+    let key =
+        document_content_key(state, document.owner, document.document_id, FileType::Md).await?;
+    let content = state.s3_client.get_document(&key).await?;
+    String::from_utf8(content).context("markdown document is not valid utf-8")
+}
+
+/// Converts an html file with the convert service and waits for the result.
+/// This can take up to [CONVERT_TIMEOUT], so it is only used by export jobs.
+#[tracing::instrument(skip(state, html))]
+async fn convert_html(state: &ApiContext, html: String, to: FileType) -> anyhow::Result<Vec<u8>> {
+    let job_id = macro_uuid::generate_uuid_v7().to_string();
+    let from_key = format!(
+        "{TEMP_FILE_PREFIX}{EXPORT_FILE_PREFIX}/{job_id}/IN.{}",
+        FileType::Html.as_str()
+    );
+    let to_key = format!(
+        "{TEMP_FILE_PREFIX}{EXPORT_FILE_PREFIX}/{job_id}/OUT.{}",
+        to.as_str()
+    );
+
+    let result = async {
+        state
+            .s3_client
+            .upload_document(&from_key, html.into_bytes())
+            .await?;
+
+        let bucket = state.s3_client.get_document_storage_bucket().to_string();
+        state
+            .sqs_client
+            .enqueue_convert_queue_message(ConvertQueueMessage {
+                job_id: job_id.clone(),
+                from_bucket: bucket.clone(),
+                to_bucket: bucket,
+                from_key: from_key.clone(),
+                to_key: to_key.clone(),
+            })
+            .await?;
+
+        let start = tokio::time::Instant::now();
+        while !state.s3_client.exists(&to_key).await? {
+            if start.elapsed() > CONVERT_TIMEOUT {
+                anyhow::bail!("timed out waiting for conversion {job_id}");
+            }
+            tokio::time::sleep(CONVERT_POLL_INTERVAL).await;
         }
 
-        let writer = zip.finish()?;
-        let data = writer.into_inner();
+        state.s3_client.get_document(&to_key).await
+    }
+    .await;
+
+    // The converted content is returned to be exported on its own, so neither file is needed
+    // anymore whether or not the conversion worked
+    if let Err(e) = state.s3_client.delete_keys(vec![from_key, to_key]).await {
+        tracing::warn!(error=?e, "unable to delete conversion files");
+    }
+
+    result
+}
+
+/// Rebuilds a docx file from the latest bom parts of the document
+async fn build_docx(state: &ApiContext, document_id: &str) -> anyhow::Result<Vec<u8>> {
+    let latest_document_bom_parts =
+        macro_db_client::document::get_document_bom(state.db.clone(), document_id).await?;
+
+    let shared_s3_client = &state.s3_client;
+
+    #[expect(clippy::type_complexity, reason = "too annoying to fix now")]
+    let downloaded_bom_parts: Vec<Result<(String, Vec<u8>), (String, anyhow::Error)>> =
+        futures::stream::iter(latest_document_bom_parts.iter())
+            .then(|bp| async move {
+                let content = shared_s3_client
+                    .get_document(bp.sha.as_str())
+                    .await
+                    .map_err(|e| (bp.sha.clone(), e))?;
+
+                Ok((bp.path.clone(), content))
+            })
+            .collect()
+            .await;
+
+    if downloaded_bom_parts.iter().any(|r| r.is_err()) {
+        return Err(anyhow::anyhow!("unable to download bom parts"));
+    }
+
+    let downloaded_bom_parts: Vec<(String, Vec<u8>)> = downloaded_bom_parts
+        .into_iter()
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let writer = Cursor::new(Vec::new());
+    let mut zip = zip::ZipWriter::new(writer);
+
+    for part in downloaded_bom_parts {
+        zip.start_file::<_, ()>(part.0, zip::write::FileOptions::default())?;
+        zip.write_all(&part.1)?;
+    }
+
+    let writer = zip.finish()?;
+    Ok(writer.into_inner())
+}
 
+async fn export_docx_document(state: &ApiContext, document_id: &str) -> anyhow::Result<String> {
+    let docx_key = format!("{TEMP_FILE_PREFIX}/{document_id}.docx");
+    let exists = state.s3_client.exists(&docx_key).await?;
+
+    if !exists {
+        tracing::trace!("document does not exist in s3, downloading bom parts");
+        let data = build_docx(state, document_id).await?;
         state.s3_client.upload_document(&docx_key, data).await?;
     }
 
-    sign_docx_url(state, &docx_key).await
+    sign_temp_file_url(state, &docx_key).await
 }
 
-async fn sign_docx_url(state: &ApiContext, key: &str) -> anyhow::Result<String> {
+pub(in crate::api) async fn sign_temp_file_url(
+    state: &ApiContext,
+    key: &str,
+) -> anyhow::Result<String> {
     let encoded_key = urlencoding::encode(key);
 
     let signed_options = get_cloudfront_signed_options(
@@ -213,3 +645,84 @@ async fn sign_docx_url(state: &ApiContext, key: &str) -> anyhow::Result<String>
 
     Ok(signed_url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_plan() {
+        assert_eq!(
+            ExportPlan::new(FileType::Pdf, None, true),
+            Some(ExportPlan::AnnotatedPdf)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Pdf, Some(ExportFormat::Pdf), true),
+            Some(ExportPlan::AnnotatedPdf)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Pdf, None, false),
+            Some(ExportPlan::Original)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Pdf, Some(ExportFormat::Pdf), false),
+            Some(ExportPlan::Original)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Pdf, Some(ExportFormat::Docx), false),
+            None
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Md, None, false),
+            Some(ExportPlan::Markdown(ExportFormat::Md))
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Md, Some(ExportFormat::Docx), false),
+            Some(ExportPlan::Markdown(ExportFormat::Docx))
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Docx, None, false),
+            Some(ExportPlan::Original)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Docx, Some(ExportFormat::Docx), false),
+            Some(ExportPlan::Original)
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Docx, Some(ExportFormat::Pdf), false),
+            None
+        );
+        assert_eq!(
+            ExportPlan::new(FileType::Png, Some(ExportFormat::Html), false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_export_plan_conversion_format() {
+        assert_eq!(ExportPlan::Original.conversion_format(), None);
+        assert_eq!(ExportPlan::AnnotatedPdf.conversion_format(), None);
+        assert_eq!(
+            ExportPlan::Markdown(ExportFormat::Html).conversion_format(),
+            None
+        );
+        assert_eq!(
+            ExportPlan::Markdown(ExportFormat::Docx).conversion_format(),
+            Some(ExportFormat::Docx)
+        );
+        assert_eq!(
+            ExportPlan::Markdown(ExportFormat::Pdf).conversion_format(),
+            Some(ExportFormat::Pdf)
+        );
+    }
+
+    #[test]
+    fn test_export_plan_extension() {
+        assert_eq!(ExportPlan::Original.extension(FileType::Png), "png");
+        assert_eq!(ExportPlan::AnnotatedPdf.extension(FileType::Pdf), "pdf");
+        assert_eq!(
+            ExportPlan::Markdown(ExportFormat::Html).extension(FileType::Md),
+            "html"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    api::context::ApiContext,
+    model::{
+        request::documents::version::DocumentVersionDiffQueryParams,
+        response::documents::version::{
+            DocumentVersionDiff, DocumentVersionDiffResponse, DocumentVersionDiffResponseData,
+        },
+    },
+    service::version_diff::{self, VersionDiffKind},
+};
+use anyhow::Context;
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+};
+use macro_middleware::cloud_storage::ensure_access::document::DocumentAccessExtractor;
+use model::{
+    document::{BomPart, DocumentBasic, DocumentMetadata, build_cloud_storage_bucket_document_key},
+    response::{GenericErrorResponse, GenericResponse},
+    user::UserContext,
+};
+use models_permissions::share_permission::access_level::ViewAccessLevel;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    pub document_id: String,
+}
+
+/// Computes a structured diff between two versions of a document.
+/// Markdown and plain text documents are diffed by line, canvas documents by node and edge and
+/// docx documents by bom part.
+#[utoipa::path(
+        tag = "document",
+        get,
+        path = "/documents/{document_id}/diff",
+        operation_id = "get_document_version_diff",
+        params(
+            ("document_id" = String, Path, description = "Document ID"),
+            ("from_version_id" = i64, Query, description = "The document version id to diff from"),
+            ("to_version_id" = i64, Query, description = "The document version id to diff to"),
+        ),
+        responses(
+            (status = 200, body=DocumentVersionDiffResponse),
+            (status = 400, body=GenericErrorResponse),
+            (status = 401, body=GenericErrorResponse),
+            (status = 404, body=GenericErrorResponse),
+            (status = 500, body=GenericErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(state, user_context, document_context, _access), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    _access: DocumentAccessExtractor<ViewAccessLevel>,
+    State(state): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    document_context: Extension<DocumentBasic>,
+    Path(Params { document_id }): Path<Params>,
+    Query(params): Query<DocumentVersionDiffQueryParams>,
+) -> Response {
+    let Some(diff_kind) = document_context
+        .try_file_type()
+        .and_then(VersionDiffKind::for_file_type)
+    else {
+        tracing::warn!(file_type=?document_context.file_type, "attempted to diff unsupported file type");
+        return GenericResponse::builder()
+            .message("cannot diff versions of this file type")
+            .is_error(true)
+            .send(StatusCode::BAD_REQUEST);
+    };
+
+    let from = match get_document_version(&state, &document_id, params.from_version_id).await {
+        Ok(from) => from,
+        Err(response) => return response,
+    };
+    let to = match get_document_version(&state, &document_id, params.to_version_id).await {
+        Ok(to) => to,
+        Err(response) => return response,
+    };
+
+    let diff = match diff_versions(&state, diff_kind, &from, &to).await {
+        Ok(diff) => diff,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to diff document versions");
+            return GenericResponse::builder()
+                .message("unable to diff document versions")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    GenericResponse::builder()
+        .data(&DocumentVersionDiffResponseData {
+            from_version_id: from.document_version_id,
+            to_version_id: to.document_version_id,
+            diff,
+        })
+        .send(StatusCode::OK)
+}
+
+/// Gets the metadata for a document version, mapping a missing version to a 404
+pub(in crate::api::documents) async fn get_document_version(
+    state: &ApiContext,
+    document_id: &str,
+    document_version_id: i64,
+) -> Result<DocumentMetadata, Response> {
+    macro_db_client::document::get_document_version(&state.db, document_id, document_version_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, document_version_id, "unable to get document version");
+            let status_code = match e.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::RowNotFound) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            GenericResponse::builder()
+                .message("unable to get document version")
+                .is_error(true)
+                .send(status_code)
+        })
+}
+
+async fn diff_versions(
+    state: &ApiContext,
+    diff_kind: VersionDiffKind,
+    from: &DocumentMetadata,
+    to: &DocumentMetadata,
+) -> anyhow::Result<DocumentVersionDiff> {
+    match diff_kind {
+        VersionDiffKind::Bom => {
+            let from_parts = bom_parts(from)?;
+            let to_parts = bom_parts(to)?;
+            Ok(DocumentVersionDiff::Bom {
+                parts: version_diff::diff_bom_parts(&from_parts, &to_parts),
+            })
+        }
+        VersionDiffKind::Canvas => {
+            let (from_content, to_content) = futures::try_join!(
+                get_version_content(state, from),
+                get_version_content(state, to)
+            )?;
+            version_diff::diff_canvas(&from_content, &to_content)
+        }
+        VersionDiffKind::Text => {
+            let (from_content, to_content) = futures::try_join!(
+                get_version_content(state, from),
+                get_version_content(state, to)
+            )?;
+            Ok(DocumentVersionDiff::Text {
+                hunks: version_diff::diff_text(
+                    &String::from_utf8_lossy(&from_content),
+                    &String::from_utf8_lossy(&to_content),
+                ),
+            })
+        }
+    }
+}
+
+/// Parses the bom parts out of a docx document version
+pub(in crate::api::documents) fn bom_parts(
+    document: &DocumentMetadata,
+) -> anyhow::Result<Vec<BomPart>> {
+    match document.document_bom.as_ref() {
+        Some(serde_json::Value::Null) | None => Ok(Vec::new()),
+        Some(document_bom) => serde_json::from_value(document_bom.clone())
+            .context("unable to parse document bom parts"),
+    }
+}
+
+/// Downloads the raw content of a document version from s3
+async fn get_version_content(
+    state: &ApiContext,
+    document: &DocumentMetadata,
+) -> anyhow::Result<Vec<u8>> {
+    let key = build_cloud_storage_bucket_document_key(
+        &document.owner,
+        &document.document_id,
+        document.document_version_id,
+        document.file_type.as_deref(),
+    );
+
+    state.s3_client.get_document(&key).await.with_context(|| {
+        format!(
+            "unable to get content for version {}",
+            document.document_version_id
+        )
+    })
+}
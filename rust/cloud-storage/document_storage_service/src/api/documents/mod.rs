@@ -20,6 +20,7 @@ pub(in crate::api) mod get_document_permissions;
 pub(in crate::api) mod get_document_processing_result;
 pub(in crate::api) mod get_document_text;
 pub(in crate::api) mod get_document_version;
+pub(in crate::api) mod get_document_version_diff;
 pub(in crate::api) mod get_document_views;
 pub(in crate::api) mod get_documents_metadata;
 pub(in crate::api) mod get_full_pdf_modification_data;
@@ -31,6 +32,7 @@ pub(in crate::api) mod location;
 pub(in crate::api) mod permissions_token;
 pub(in crate::api) mod pre_save;
 pub(in crate::api) mod put_document_update;
+pub(in crate::api) mod restore_document_version;
 pub(in crate::api) mod revert_delete_document;
 pub(in crate::api) mod save_document;
 pub(in crate::api) mod simple_save;
@@ -68,6 +70,10 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
                     .layer(axum::middleware::from_fn_with_state(
                         state.clone(),
                         macro_middleware::user_permissions::validate_user_quota::document_handler,
+                    ))
+                    .layer(axum::middleware::from_fn_with_state(
+                        state.clone(),
+                        macro_middleware::user_permissions::validate_user_quota::storage_handler,
                     )),
             ),
         )
@@ -127,6 +133,10 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
                     .layer(axum::middleware::from_fn_with_state(
                         state.clone(),
                         macro_middleware::user_permissions::validate_user_quota::document_handler,
+                    ))
+                    .layer(axum::middleware::from_fn_with_state(
+                        state.clone(),
+                        macro_middleware::user_permissions::validate_user_quota::storage_copy_handler,
                     )),
             ),
         )
@@ -143,10 +153,20 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
             "/:document_id/export",
             get(export_document::handler).layer(ensure_document_exists_middleware.clone()),
         )
+        .route(
+            "/:document_id/diff",
+            get(get_document_version_diff::handler)
+                .layer(ensure_document_exists_middleware.clone()),
+        )
         .route(
             "/:document_id/:document_version_id",
             get(get_document_version::handler).layer(ensure_document_exists_middleware.clone()),
         )
+        .route(
+            "/:document_id/:document_version_id/restore",
+            post(restore_document_version::handler)
+                .layer(ensure_document_exists_middleware.clone()),
+        )
         .route(
             "/:document_id",
             patch(edit_document::edit_document_handler)
@@ -14,14 +14,12 @@ use axum::{
     response::IntoResponse,
 };
 
-#[allow(unused_imports)]
-use futures::stream::StreamExt;
 use macro_middleware::cloud_storage::ensure_access::document::DocumentAccessExtractor;
 use model::{response::GenericErrorResponse, user::UserContext};
 
 use model::{
-    document::{ContentType, DocumentBasic, FileType},
-    response::{GenericResponse, PresignedUrl},
+    document::{DocumentBasic, FileType},
+    response::GenericResponse,
 };
 
 use models_permissions::share_permission::access_level::EditAccessLevel;
@@ -33,7 +31,7 @@ pub struct Params {
 }
 
 /// Takes the docx document bom parts and generates presigned urls to upload
-/// any new content
+/// any new content. Docx files are no longer saved, so there is never new content to upload.
 #[utoipa::path(
         tag = "document",
         put,
@@ -48,16 +46,16 @@ pub struct Params {
             (status = 500, body=GenericErrorResponse),
         ),
     )]
-#[tracing::instrument(skip(state, document_context, user_context, req), fields(user_id=?user_context.user_id))]
+#[tracing::instrument(skip(_state, document_context, user_context, _req), fields(user_id=?user_context.user_id))]
 #[allow(deprecated, reason = "we just want deprecated to show up in utoipa")]
 #[deprecated(note = "we no longer support editing docx files as they are now converted to pdf.")]
 pub async fn presave_document_handler(
     access: DocumentAccessExtractor<EditAccessLevel>,
-    State(state): State<ApiContext>,
+    State(_state): State<ApiContext>,
     user_context: Extension<UserContext>,
     document_context: Extension<DocumentBasic>,
     Path(Params { document_id }): Path<Params>,
-    Json(req): Json<PreSaveDocumentRequest>,
+    Json(_req): Json<PreSaveDocumentRequest>,
 ) -> impl IntoResponse {
     tracing::trace!("pre saving document");
     // Ensure we have a valid file type
@@ -86,71 +84,12 @@ pub async fn presave_document_handler(
             .send(StatusCode::BAD_REQUEST);
     }
 
-    tracing::trace!("finding non existing shas");
-    // Check all bom parts in redis to see if they exist in s3
-    let non_existing_shas = match state
-        .redis_client
-        .find_non_existing_shas(&req.new_bom)
-        .await
-    {
-        Ok(non_existing_bom_parts) => non_existing_bom_parts,
-        Err(e) => {
-            tracing::error!(error=?e, "unable to find non existing shas");
-            return GenericResponse::builder()
-                .message("unable to find non existing shas")
-                .is_error(true)
-                .send(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    };
-
-    if non_existing_shas.is_empty() {
-        return GenericResponse::builder()
-            .data(&PreSaveDocumentResponseData {
-                presigned_urls: vec![],
-            })
-            .is_error(false)
-            .send(StatusCode::OK);
-    }
-
-    // Generate presigned urls for any parts that are not in s3
-    let shared_s3_client = &state.s3_client;
-    tracing::trace!("generating presigned urls");
-    let presigned_urls: Vec<anyhow::Result<PresignedUrl>> =
-        futures::stream::iter(non_existing_shas.iter())
-            .then(|bp| async move {
-                match shared_s3_client
-                    .put_document_storage_presigned_url(&bp.sha, &bp.sha, ContentType::Default)
-                    .await
-                {
-                    Ok(presigned_url) => Ok(PresignedUrl {
-                        sha: bp.sha.clone(),
-                        presigned_url,
-                    }),
-                    Err(err) => {
-                        tracing::error!(
-                            error=?err,
-                            sha=%bp.sha,
-                            "error generating presigned url for sha",
-                        );
-                        Err(err)
-                    }
-                }
-            })
-            .collect::<Vec<anyhow::Result<PresignedUrl>>>()
-            .await;
-
-    // If any of the presigned urls failed, return an error
-    if presigned_urls.iter().filter(|r| r.is_err()).count() > 0 {
-        return GenericResponse::builder()
-            .message("unable to generate presigned urls")
-            .is_error(true)
-            .send(StatusCode::INTERNAL_SERVER_ERROR);
-    }
-
-    let presigned_urls: Vec<PresignedUrl> =
-        presigned_urls.into_iter().filter_map(|r| r.ok()).collect();
-
+    // Since docx are now converted to pdf, saving never stores new bom parts, so there is
+    // nothing to upload
     GenericResponse::builder()
-        .data(&PreSaveDocumentResponseData { presigned_urls })
+        .data(&PreSaveDocumentResponseData {
+            presigned_urls: vec![],
+        })
+        .is_error(false)
         .send(StatusCode::OK)
 }
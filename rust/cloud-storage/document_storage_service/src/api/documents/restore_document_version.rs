@@ -0,0 +1,252 @@
+use crate::{
+    api::{
+        context::ApiContext,
+        documents::{get_document_version_diff, utils},
+    },
+    model::response::documents::save::{SaveDocumentResponse, SaveDocumentResponseData},
+};
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+};
+use macro_middleware::cloud_storage::ensure_access::document::DocumentAccessExtractor;
+use model::{
+    document::{
+        DocumentBasic, DocumentMetadata, FileType, FileTypeExt, SaveBomPart,
+        build_cloud_storage_bucket_document_key, response::DocumentResponseMetadata,
+    },
+    response::{GenericErrorResponse, GenericResponse},
+    user::UserContext,
+};
+use models_permissions::share_permission::access_level::EditAccessLevel;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Params {
+    pub document_id: String,
+    pub document_version_id: i64,
+}
+
+/// Restores a previous version of a document.
+/// The restored content is saved as a new version so the document history is preserved.
+#[utoipa::path(
+        tag = "document",
+        post,
+        path = "/documents/{document_id}/{document_version_id}/restore",
+        operation_id = "restore_document_version",
+        params(
+            ("document_id" = String, Path, description = "Document ID"),
+            ("document_version_id" = i64, Path, description = "The document version id to restore")
+        ),
+        responses(
+            (status = 200, body=SaveDocumentResponse),
+            (status = 400, body=GenericErrorResponse),
+            (status = 401, body=GenericErrorResponse),
+            (status = 404, body=GenericErrorResponse),
+            (status = 500, body=GenericErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(state, user_context, document_context, _access), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    _access: DocumentAccessExtractor<EditAccessLevel>,
+    State(state): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    document_context: Extension<DocumentBasic>,
+    Path(Params {
+        document_id,
+        document_version_id,
+    }): Path<Params>,
+) -> Response {
+    if document_context.deleted_at.is_some() {
+        return GenericResponse::builder()
+            .message("cannot modify deleted document")
+            .is_error(true)
+            .send(StatusCode::BAD_REQUEST);
+    }
+
+    let Some(file_type) = document_context.try_file_type() else {
+        tracing::error!(file_type=?document_context.file_type, "cannot restore file type");
+        return GenericResponse::builder()
+            .message("cannot restore versions of this file type")
+            .is_error(true)
+            .send(StatusCode::BAD_REQUEST);
+    };
+
+    let in_sync_service = if file_type == FileType::Md {
+        match state.sync_service_client.exists(&document_id).await {
+            Ok(in_sync_service) => in_sync_service,
+            Err(e) => {
+                tracing::error!(error=?e, "unable to check sync service");
+                return GenericResponse::builder()
+                    .message("unable to restore document version")
+                    .is_error(true)
+                    .send(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    } else {
+        false
+    };
+
+    if !can_restore(file_type, in_sync_service) {
+        tracing::error!(file_type=?file_type, in_sync_service, "cannot restore file type");
+        return GenericResponse::builder()
+            .message("cannot restore versions of this file type")
+            .is_error(true)
+            .send(StatusCode::BAD_REQUEST);
+    }
+
+    let version = match get_document_version_diff::get_document_version(
+        &state,
+        &document_id,
+        document_version_id,
+    )
+    .await
+    {
+        Ok(version) => version,
+        Err(response) => return response,
+    };
+
+    let restored = match restore_version(&state, file_type, &version).await {
+        Ok(restored) => restored,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to restore document version");
+            return GenericResponse::builder()
+                .message("unable to restore document version")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let document_metadata = match DocumentResponseMetadata::from_document_metadata(&restored) {
+        Ok(document_metadata) => document_metadata,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to get document response metadata");
+            return GenericResponse::builder()
+                .message("unable to get document response metadata")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    GenericResponse::builder()
+        .data(&SaveDocumentResponseData {
+            document_metadata,
+            presigned_url: None,
+        })
+        .send(StatusCode::OK)
+}
+
+/// Whether versions of the document can be restored.
+/// Markdown that is live in the sync service is read from there rather than from its versions, so
+/// restoring one wouldn't change the document.
+fn can_restore(file_type: FileType, in_sync_service: bool) -> bool {
+    !file_type.is_image() && !(file_type == FileType::Md && in_sync_service)
+}
+
+/// Creates a new version of the document with the content of the provided version
+#[tracing::instrument(skip(state, version), fields(document_version_id=version.document_version_id))]
+async fn restore_version(
+    state: &ApiContext,
+    file_type: FileType,
+    version: &DocumentMetadata,
+) -> anyhow::Result<DocumentMetadata> {
+    match file_type {
+        FileType::Docx => {
+            let bom_parts: Vec<SaveBomPart> = get_document_version_diff::bom_parts(version)?
+                .into_iter()
+                .map(|part| SaveBomPart {
+                    sha: part.sha,
+                    path: part.path,
+                })
+                .collect();
+            let shas: Vec<String> = bom_parts.iter().map(|part| part.sha.clone()).collect();
+
+            let restored = macro_db_client::document::save_document(
+                &state.db,
+                &version.document_id,
+                file_type,
+                None,
+                None,
+                Some(bom_parts),
+            )
+            .await?;
+
+            // The restored bom references the same parts so their ref counts need to be bumped
+            if let Err(e) = state.redis_client.increment_counts(shas).await {
+                tracing::error!(error=?e, "unable to increment sha ref count");
+            }
+
+            Ok(restored)
+        }
+        // Static files are always located from their first version so only the modification
+        // data needs to be carried over
+        FileType::Pdf => {
+            macro_db_client::document::save_document(
+                &state.db,
+                &version.document_id,
+                file_type,
+                version.sha.as_deref(),
+                version.modification_data.clone(),
+                None,
+            )
+            .await
+        }
+        _ => {
+            let restored = macro_db_client::document::save_document(
+                &state.db,
+                &version.document_id,
+                file_type,
+                version.sha.as_deref(),
+                None,
+                None,
+            )
+            .await?;
+
+            let source_key = build_cloud_storage_bucket_document_key(
+                &version.owner,
+                &version.document_id,
+                version.document_version_id,
+                Some(file_type.as_str()),
+            );
+            let destination_key = build_cloud_storage_bucket_document_key(
+                &restored.owner,
+                &restored.document_id,
+                restored.document_version_id,
+                Some(file_type.as_str()),
+            );
+
+            if let Err(e) = state
+                .s3_client
+                .copy_document(&source_key, &destination_key)
+                .await
+            {
+                utils::cleanup_document_version_on_error(
+                    &state.db,
+                    &restored.document_id,
+                    restored.document_version_id,
+                    file_type.as_str(),
+                )
+                .await;
+                return Err(e);
+            }
+
+            Ok(restored)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_restore() {
+        assert!(can_restore(FileType::Pdf, false));
+        assert!(can_restore(FileType::Docx, false));
+        assert!(can_restore(FileType::Md, false));
+        assert!(!can_restore(FileType::Md, true));
+        assert!(!can_restore(FileType::Png, false));
+    }
+}
@@ -122,11 +122,21 @@ pub async fn save_document_handler(
                     .is_error(true)
                     .send(StatusCode::BAD_REQUEST);
             }
+            // Monaco files are uploaded through a presigned url bound to their size
+            if (file_type == FileType::Py || file_type == FileType::Js) && req.size_bytes.is_none()
+            {
+                tracing::error!("requested to save file no size");
+                return GenericResponse::builder()
+                    .message("sizeBytes is required")
+                    .is_error(true)
+                    .send(StatusCode::BAD_REQUEST);
+            }
         } // Standard editable files do not require modification data to be present but do require
           // a sha
     }
 
     let sha = req.sha.clone();
+    let size_bytes = req.size_bytes;
 
     let document_metadata: DocumentResponseMetadata = match save_document(
         &ctx.db,
@@ -166,11 +176,12 @@ pub async fn save_document_handler(
             document_metadata.document_version_id,
             Some(file_type.as_str()),
         );
-        // We've already validated that the sha is present for monaco files
+        // We've already validated that the sha and size are present for monaco files
         let sha = sha.unwrap();
+        let size_bytes = size_bytes.unwrap();
         match ctx
             .s3_client
-            .put_document_storage_presigned_url(&key, &sha, file_type.into())
+            .put_document_storage_presigned_url(&key, &sha, file_type.into(), size_bytes)
             .await
         {
             Ok(presigned_url) => Some(presigned_url),
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use macro_db_client::job::export_job::{
+    ExportJob, claim_export_job, fail_abandoned_export_jobs, fail_export_job,
+};
+
+use crate::{
+    api::{
+        context::ApiContext, documents::export_document::run_document_export,
+        projects::export_project::run_project_export,
+    },
+    model::response::{
+        documents::export::DocumentExportStatusUpdate, projects::export::ProjectExportStatusUpdate,
+    },
+    service::conn_gateway::{update_document_export_state, update_project_export_state},
+};
+
+/// How long to wait before looking for export jobs again when there are none to run
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Runs export jobs one at a time. Jobs are claimed from the database, so any number of service
+/// instances can run the worker, and a job left behind by a stopped instance is picked up again.
+pub(crate) async fn run(state: ApiContext) {
+    tracing::info!("export worker started");
+    loop {
+        match fail_abandoned_export_jobs(&state.db).await {
+            Ok(jobs) => {
+                for job in jobs {
+                    tracing::warn!(export_id=%job.id, "export job abandoned");
+                    send_failed(&state, &job).await;
+                }
+            }
+            Err(e) => tracing::error!(error=?e, "unable to fail abandoned export jobs"),
+        }
+
+        match claim_export_job(&state.db).await {
+            Ok(Some(job)) => run_job(&state, &job).await,
+            Ok(None) => tokio::time::sleep(IDLE_POLL_INTERVAL).await,
+            Err(e) => {
+                tracing::error!(error=?e, "unable to claim export job");
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+#[tracing::instrument(skip_all, fields(export_id=%job.id))]
+async fn run_job(state: &ApiContext, job: &ExportJob) {
+    let result = match (&job.project_id, &job.document_id) {
+        (Some(project_id), _) => run_project_export(state, job, project_id).await,
+        (None, Some(document_id)) => run_document_export(state, job, document_id).await,
+        (None, None) => Err(anyhow::anyhow!("export job has nothing to export")),
+    };
+
+    if let Err(e) = result {
+        tracing::error!(error=?e, "export job failed");
+        match fail_export_job(&state.db, job.id, job.attempts).await {
+            Ok(true) => send_failed(state, job).await,
+            Ok(false) => {
+                tracing::warn!("export job was claimed again, leaving it to its new worker")
+            }
+            Err(e) => {
+                tracing::error!(error=?e, "unable to fail export job");
+                send_failed(state, job).await;
+            }
+        }
+    }
+}
+
+async fn send_failed(state: &ApiContext, job: &ExportJob) {
+    let export_id = job.id.to_string();
+    match (&job.project_id, &job.document_id) {
+        (Some(project_id), _) => {
+            update_project_export_state(
+                &state.conn_gateway_client,
+                &job.user_id,
+                ProjectExportStatusUpdate::Failed {
+                    export_id,
+                    project_id: project_id.clone(),
+                },
+            )
+            .await
+        }
+        (None, Some(document_id)) => {
+            update_document_export_state(
+                &state.conn_gateway_client,
+                &job.user_id,
+                DocumentExportStatusUpdate::Failed {
+                    export_id,
+                    document_id: document_id.clone(),
+                },
+            )
+            .await
+        }
+        (None, None) => {}
+    }
+}
@@ -15,6 +15,7 @@ use utoipa_swagger_ui::SwaggerUi;
 
 // Utilities
 pub(crate) mod context;
+pub(crate) mod export_worker;
 mod saved_views;
 mod util;
 
@@ -35,6 +36,7 @@ mod notification;
 mod pins;
 mod projects;
 mod recents;
+mod storage;
 mod user;
 mod user_document_view_location;
 
@@ -143,6 +145,7 @@ fn api_router(state: ApiContext) -> Router {
                 }),
             )),
         )
+        .nest("/storage", storage::router())
         .nest("/mentions", mentions::router(state.clone()))
         .nest(
             "/annotations",
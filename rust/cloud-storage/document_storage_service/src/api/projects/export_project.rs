@@ -0,0 +1,363 @@
+use std::{
+    collections::HashSet,
+    io::{Seek, Write},
+    str::FromStr,
+};
+
+use crate::{
+    api::{
+        context::ApiContext,
+        documents::export_document::{
+            EXPORT_FILE_PREFIX, ExportDocument, ExportPlan, export_content, has_modifications,
+            sign_temp_file_url,
+        },
+    },
+    model::{
+        request::documents::export::{ExportFormat, ExportProjectRequest},
+        response::projects::export::{
+            ExportProjectResponse, ExportProjectResponseData, ProjectExportStatusUpdate,
+        },
+    },
+    service::{
+        conn_gateway::update_project_export_state,
+        export::archive::{ExportArchive, sanitize_name},
+        s3::TEMP_FILE_PREFIX,
+    },
+};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+};
+use macro_db_client::job::export_job::ExportJob;
+use macro_middleware::cloud_storage::ensure_access::project::ProjectAccessLevelExtractor;
+use model::{
+    document::{BasicDocument, FileType},
+    response::{GenericErrorResponse, GenericResponse},
+    user::UserContext,
+};
+use models_permissions::share_permission::access_level::ViewAccessLevel;
+
+/// The maximum number of documents that can be exported at once
+const MAX_EXPORT_DOCUMENTS: usize = 500;
+
+/// A progress update is sent every time this many documents have been processed
+const PROGRESS_UPDATE_INTERVAL: usize = 10;
+
+#[derive(serde::Deserialize)]
+pub struct Params {
+    pub id: String,
+}
+
+/// A folder of the exported project tree
+struct ExportFolder {
+    /// The index of the parent folder, None for the project being exported
+    parent: Option<usize>,
+    name: String,
+}
+
+/// A document to export along with the index of the folder it belongs to
+struct ExportEntry {
+    folder: usize,
+    document: BasicDocument,
+}
+
+/// The folders and documents of a project being exported
+struct ExportTree {
+    folders: Vec<ExportFolder>,
+    entries: Vec<ExportEntry>,
+}
+
+/// Exports a project and all of its sub-projects into a zip archive.
+/// The folder structure of the project is preserved. The export runs in the background as an
+/// export job and progress is sent to the user over the connection gateway as `project_export` messages. The
+/// final message contains a presigned url to download the archive.
+#[utoipa::path(
+        tag = "project",
+        post,
+        path = "/projects/{id}/export",
+        operation_id = "export_project",
+        params(
+            ("id" = String, Path, description = "ID of the project")
+        ),
+        request_body = ExportProjectRequest,
+        responses(
+            (status = 202, body=ExportProjectResponse),
+            (status = 400, body=GenericErrorResponse),
+            (status = 401, body=GenericErrorResponse),
+            (status = 500, body=GenericErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(state, user_context, request), fields(user_id=?user_context.user_id))]
+pub async fn export_project_handler(
+    _access: ProjectAccessLevelExtractor<ViewAccessLevel>,
+    State(state): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(Params { id }): Path<Params>,
+    request: Option<Json<ExportProjectRequest>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
+    let project = match macro_db_client::projects::get_project::get_project_by_id(
+        state.db.clone(),
+        &id,
+    )
+    .await
+    {
+        Ok(project) => project,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to get project");
+            return GenericResponse::builder()
+                .message("unable to get project")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let tree = match collect_tree(&state, &project.id, &project.name).await {
+        Ok(tree) => tree,
+        Err(e) => {
+            tracing::error!(error=?e, "unable to get project contents");
+            return GenericResponse::builder()
+                .message("unable to get project contents")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let document_count = tree.entries.len();
+    if document_count > MAX_EXPORT_DOCUMENTS {
+        return GenericResponse::builder()
+            .message(&format!(
+                "cannot export more than {MAX_EXPORT_DOCUMENTS} documents at once"
+            ))
+            .is_error(true)
+            .send(StatusCode::BAD_REQUEST);
+    }
+
+    let export_id = macro_uuid::generate_uuid_v7();
+    if let Err(e) = macro_db_client::job::export_job::create_project_export_job(
+        &state.db,
+        export_id,
+        &user_context.user_id,
+        &project.id,
+        request
+            .markdown_format
+            .as_ref()
+            .map(ExportFormat::extension),
+        document_count as i32,
+    )
+    .await
+    {
+        tracing::error!(error=?e, "unable to create export job");
+        return GenericResponse::builder()
+            .message("unable to export project")
+            .is_error(true)
+            .send(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    GenericResponse::builder()
+        .data(&ExportProjectResponseData {
+            export_id: export_id.to_string(),
+            document_count,
+        })
+        .send(StatusCode::ACCEPTED)
+}
+
+/// Walks the project tree, collecting its folders and the documents to export
+async fn collect_tree(
+    state: &ApiContext,
+    project_id: &str,
+    project_name: &str,
+) -> anyhow::Result<ExportTree> {
+    let mut transaction = state.db.begin().await?;
+
+    let mut tree = ExportTree {
+        folders: vec![ExportFolder {
+            parent: None,
+            name: project_name.to_string(),
+        }],
+        entries: Vec::new(),
+    };
+    let mut visited = HashSet::from([project_id.to_string()]);
+    let mut pending = vec![(project_id.to_string(), 0)];
+
+    while let Some((project_id, folder)) = pending.pop() {
+        let documents = macro_db_client::projects::get_project::get_sub_items::get_sub_documents(
+            &mut transaction,
+            &project_id,
+        )
+        .await?;
+        tree.entries.extend(
+            documents
+                .into_iter()
+                .map(|document| ExportEntry { folder, document }),
+        );
+
+        let sub_projects = macro_db_client::projects::get_project::get_sub_items::get_sub_projects(
+            &mut transaction,
+            &project_id,
+        )
+        .await?;
+        for sub_project in sub_projects {
+            if visited.insert(sub_project.id.clone()) {
+                tree.folders.push(ExportFolder {
+                    parent: Some(folder),
+                    name: sub_project.name,
+                });
+                pending.push((sub_project.id, tree.folders.len() - 1));
+            }
+        }
+    }
+
+    transaction.commit().await?;
+
+    Ok(tree)
+}
+
+/// Runs a project export job. The archive is written to a temporary file and streamed to s3 so
+/// large projects are never held in memory. Returns an error if the export failed.
+#[tracing::instrument(skip(state, job), fields(export_id=%job.id))]
+pub(in crate::api) async fn run_project_export(
+    state: &ApiContext,
+    job: &ExportJob,
+    project_id: &str,
+) -> anyhow::Result<()> {
+    let markdown_format = job
+        .format
+        .as_deref()
+        .map(|format| {
+            ExportFormat::from_extension(format)
+                .ok_or_else(|| anyhow::anyhow!("export job has an invalid format"))
+        })
+        .transpose()?;
+
+    let project =
+        macro_db_client::projects::get_project::get_project_by_id(state.db.clone(), project_id)
+            .await?;
+    let tree = collect_tree(state, &project.id, &project.name).await?;
+    let total = tree.entries.len();
+
+    let mut archive = ExportArchive::from_writer(tempfile::NamedTempFile::new()?);
+    let mut folders: Vec<String> = Vec::with_capacity(tree.folders.len());
+    for folder in &tree.folders {
+        // Parents are always collected before their sub folders
+        let parent = folder
+            .parent
+            .map(|parent| folders[parent].clone())
+            .unwrap_or_default();
+        folders.push(archive.add_folder(&parent, &folder.name)?);
+    }
+
+    let mut failed_document_ids = Vec::new();
+    for (index, entry) in tree.entries.iter().enumerate() {
+        if let Err(e) = export_entry(
+            state,
+            &mut archive,
+            &folders[entry.folder],
+            &entry.document,
+            markdown_format,
+        )
+        .await
+        {
+            tracing::error!(error=?e, document_id=%entry.document.document_id, "unable to export document");
+            failed_document_ids.push(entry.document.document_id.clone());
+        }
+
+        let processed = index + 1;
+        if processed % PROGRESS_UPDATE_INTERVAL == 0 && processed < total {
+            macro_db_client::job::export_job::update_export_job_progress(
+                &state.db,
+                job.id,
+                job.attempts,
+                processed as i32,
+                total as i32,
+            )
+            .await?;
+            update_project_export_state(
+                &state.conn_gateway_client,
+                &job.user_id,
+                ProjectExportStatusUpdate::InProgress {
+                    export_id: job.id.to_string(),
+                    project_id: project.id.clone(),
+                    processed,
+                    total,
+                },
+            )
+            .await;
+        }
+    }
+
+    let key = format!(
+        "{TEMP_FILE_PREFIX}{EXPORT_FILE_PREFIX}/{}/{}.zip",
+        job.id,
+        sanitize_name(&project.name)
+    );
+    // The temporary file is removed once it is dropped
+    let file = archive.finish()?;
+    state.s3_client.upload_file(&key, file.path()).await?;
+    macro_db_client::job::export_job::complete_export_job(
+        &state.db,
+        job.id,
+        job.attempts,
+        &key,
+        &failed_document_ids,
+    )
+    .await?;
+    let presigned_url = sign_temp_file_url(state, &key).await?;
+
+    update_project_export_state(
+        &state.conn_gateway_client,
+        &job.user_id,
+        ProjectExportStatusUpdate::Completed {
+            export_id: job.id.to_string(),
+            project_id: project.id,
+            presigned_url,
+            failed_document_ids,
+        },
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn export_entry<W: Write + Seek>(
+    state: &ApiContext,
+    archive: &mut ExportArchive<W>,
+    folder: &str,
+    document: &BasicDocument,
+    markdown_format: Option<ExportFormat>,
+) -> anyhow::Result<()> {
+    let file_type = document
+        .file_type
+        .as_deref()
+        .map(FileType::from_str)
+        .transpose()?
+        .ok_or_else(|| anyhow::anyhow!("document has no file type"))?;
+
+    let format = match file_type {
+        FileType::Md => markdown_format,
+        _ => None,
+    };
+    let has_modifications = has_modifications(state, &document.document_id, file_type).await?;
+    let plan = ExportPlan::new(file_type, format, has_modifications)
+        .ok_or_else(|| anyhow::anyhow!("unsupported export format"))?;
+
+    let export_document = ExportDocument {
+        document_id: &document.document_id,
+        owner: &document.owner,
+        document_name: &document.document_name,
+        file_type,
+    };
+    let content = export_content(state, &export_document, plan).await?;
+
+    archive.add_file(
+        folder,
+        &document.document_name,
+        Some(plan.extension(file_type)),
+        &content,
+    )?;
+
+    Ok(())
+}
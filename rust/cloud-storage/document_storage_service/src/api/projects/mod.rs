@@ -9,6 +9,7 @@ use tower::ServiceBuilder;
 pub(in crate::api) mod create_project;
 pub(in crate::api) mod delete_project;
 pub(in crate::api) mod edit_project;
+pub(in crate::api) mod export_project;
 pub(in crate::api) mod get_batch_preview;
 pub(in crate::api) mod get_project;
 pub(in crate::api) mod get_projects;
@@ -78,8 +79,12 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
                         macro_middleware::auth::ensure_user_exists::handler,
                     ))
                     .layer(axum::middleware::from_fn_with_state(
-                        state,
+                        state.clone(),
                         macro_middleware::user_permissions::attach_user_permissions::handler,
+                    ))
+                    .layer(axum::middleware::from_fn_with_state(
+                        state,
+                        macro_middleware::user_permissions::validate_user_quota::storage_handler,
                     )), // TODO: get item count from front end and/or handle during the extract step
                         // .layer(axum::middleware::from_fn(
                         //     middleware::check_user_document_count::handler_upload_folder,
@@ -91,6 +96,11 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
             patch(edit_project::edit_project_handler)
                 .layer(ensure_project_exists_middleware.clone()),
         )
+        .route(
+            "/:id/export",
+            post(export_project::export_project_handler)
+                .layer(ensure_project_exists_middleware.clone()),
+        )
         .route(
             "/:id/revert_delete",
             put(revert_delete_project::handler).layer(ensure_project_exists_middleware.clone()),
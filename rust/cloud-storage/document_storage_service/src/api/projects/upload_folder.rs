@@ -1,4 +1,4 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use crate::{
     api::context::{ApiContext, InternalFlag},
@@ -87,7 +87,11 @@ pub async fn upload_extract_folder_handler(
 
     let presigned_url = match ctx
         .s3_client
-        .put_upload_zip_staging_presigned_url(request.key.as_str(), req.sha.as_str())
+        .put_upload_zip_staging_presigned_url(
+            request.key.as_str(),
+            req.sha.as_str(),
+            req.size_bytes,
+        )
         .await
     {
         Ok(presigned_url) => presigned_url,
@@ -149,6 +153,20 @@ async fn upload_folder_handler_inner(
     internal: bool,
     req: UploadFolderRequest,
 ) -> impl IntoResponse + use<> {
+    // Files uploaded through presigned urls are bound to their size
+    if !internal && req.content.iter().any(|item| item.size_bytes.is_none()) {
+        return GenericResponse::builder()
+            .is_error(true)
+            .message("sizeBytes is required")
+            .send(StatusCode::BAD_REQUEST);
+    }
+    // Keyed by sha since that is what the documents keep of the file
+    let sizes: HashMap<String, i64> = req
+        .content
+        .iter()
+        .filter_map(|item| Some((item.sha.clone(), item.size_bytes?)))
+        .collect();
+
     let file_system = match FileSystemNode::build_file_system(&req.root_folder_name, req.content) {
         Ok(fs) => fs,
         Err(err) => {
@@ -210,7 +228,7 @@ async fn upload_folder_handler_inner(
 
     tracing::trace!(documents=?documents, "got documents to upload");
 
-    let destination_map = match build_documents(&s3_client, &documents, internal).await {
+    let destination_map = match build_documents(&s3_client, &documents, &sizes, internal).await {
         Ok(res) => res,
         Err(err) => {
             tracing::error!(error=?err, "error building s3 destination map");
@@ -263,6 +281,7 @@ async fn upload_folder_handler_inner(
 async fn build_documents(
     s3_client: &service::s3::S3,
     documents: &Vec<DocumentMetadata>,
+    sizes: &HashMap<String, i64>,
     internal: bool,
 ) -> anyhow::Result<S3DestinationMap> {
     let mut result: S3DestinationMap = S3DestinationMap::new();
@@ -284,12 +303,13 @@ async fn build_documents(
 
             match internal {
                 false => {
+                    let size_bytes = *sizes.get(&sha).context("document needs a size")?;
                     let presigned_url: String = match file_type {
-                        Some(FileType::Docx) => s3_client.put_docx_upload_presigned_url(key.as_str(), sha.as_str(), ContentType::Docx).await.map_err(|err| {
+                        Some(FileType::Docx) => s3_client.put_docx_upload_presigned_url(key.as_str(), sha.as_str(), ContentType::Docx, size_bytes).await.map_err(|err| {
                             tracing::error!(error=?err, key=?key, document_id=?document.document_id, "unable to generate presigned url");
                             err
                         })?,
-                        _ => s3_client.put_document_storage_presigned_url(key.as_str(), sha.as_str(), file_type.into()).await.map_err(|err| {
+                        _ => s3_client.put_document_storage_presigned_url(key.as_str(), sha.as_str(), file_type.into(), size_bytes).await.map_err(|err| {
                             tracing::error!(error=?err, key=?key, document_id=?document.document_id, "unable to generate presigned url");
                             err
                         })?,
@@ -0,0 +1,69 @@
+use crate::{
+    api::context::ApiContext,
+    model::response::storage::{StorageUsageResponse, StorageUsageResponseData},
+    service::storage_usage::size_unsized_documents,
+};
+use axum::{Extension, extract::State, http::StatusCode, response::Response};
+use macro_user_id::user_id::MacroUserId;
+use model::{
+    response::{GenericErrorResponse, GenericResponse},
+    user::UserContext,
+};
+
+/// Gets the storage used by the user against their plan, broken down by file type and project.
+/// Also includes the storage used by the user's teams and organization.
+#[utoipa::path(
+        tag = "storage",
+        get,
+        path = "/storage/usage",
+        operation_id = "get_storage_usage",
+        responses(
+            (status = 200, body=StorageUsageResponse),
+            (status = 401, body=GenericErrorResponse),
+            (status = 500, body=GenericErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn get_storage_usage_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+) -> Response {
+    match get_storage_usage(&ctx, &user_context.user_id).await {
+        Ok(data) => GenericResponse::builder().data(&data).send(StatusCode::OK),
+        Err(e) => {
+            tracing::error!(error=?e, "unable to get storage usage");
+            GenericResponse::builder()
+                .message("unable to get storage usage")
+                .is_error(true)
+                .send(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn get_storage_usage(
+    ctx: &ApiContext,
+    user_id: &str,
+) -> anyhow::Result<StorageUsageResponseData> {
+    let user_id = MacroUserId::parse_from_str(user_id)?.lowercase();
+
+    // An incomplete usage is still useful so failing to size uploads is not fatal
+    if let Err(e) = size_unsized_documents(&ctx.db, &ctx.s3_client, &user_id).await {
+        tracing::error!(error=?e, "unable to size documents");
+    }
+
+    let (quota, by_file_type, by_project, teams, organization) = tokio::try_join!(
+        macro_db_client::storage_usage::get_user_storage_quota(&ctx.db, &user_id),
+        macro_db_client::storage_usage::get_user_storage_usage_by_file_type(&ctx.db, &user_id),
+        macro_db_client::storage_usage::get_user_storage_usage_by_project(&ctx.db, &user_id),
+        macro_db_client::storage_usage::get_team_storage_usages_for_user(&ctx.db, &user_id),
+        macro_db_client::storage_usage::get_organization_storage_usage_for_user(&ctx.db, &user_id),
+    )?;
+
+    Ok(StorageUsageResponseData {
+        quota,
+        by_file_type,
+        by_project,
+        teams,
+        organization,
+    })
+}
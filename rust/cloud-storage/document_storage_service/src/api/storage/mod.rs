@@ -0,0 +1,13 @@
+use super::context::ApiContext;
+use axum::{Router, routing::get};
+
+// needs to be public in api crate for swagger
+pub(in crate::api) mod get_storage_usage;
+
+pub fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/usage", get(get_storage_usage::get_storage_usage_handler))
+        .layer(axum::middleware::from_fn(
+            macro_middleware::auth::ensure_user_exists::handler,
+        ))
+}
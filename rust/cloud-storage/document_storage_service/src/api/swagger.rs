@@ -26,13 +26,14 @@ use crate::{
             self,
             recently_deleted::{RecentlyDeletedResponse, RecentlyDeletedResponseData},
         },
-        saved_views, threads, user_document_view_location,
+        saved_views, storage, threads, user_document_view_location,
     },
     model::{
         request::{
             documents::{
                 copy::{CopyDocumentQueryParams, CopyDocumentRequest},
                 edit::EditDocumentRequestV2,
+                export::{ExportFormat, ExportProjectRequest},
                 preview::GetBatchPreviewRequest,
                 save::{PreSaveDocumentRequest, SaveDocumentRequest},
                 user_document_view_location::UpsertUserDocumentViewLocationRequest,
@@ -44,6 +45,7 @@ use crate::{
             activity::{GetActivitiesResponse, UserActivitiesResponse},
             documents::{
                 create::{CreateBulkDocumentResponse, CreateBulkDocumentResponseData},
+                export::{DocumentExportStatusUpdate, ExportDocumentJobResponse},
                 get::{
                     GetDocumentKeyResponse, GetDocumentKeyResponseData,
                     GetDocumentPermissionsResponseDataV2, GetDocumentProcessingResult,
@@ -57,10 +59,19 @@ use crate::{
                     SaveDocumentResponseData,
                 },
                 user_document_view_location::UserDocumentViewLocationResponse,
+                version::{
+                    BomPartChange, CanvasItemChange, DiffChange, DocumentVersionDiff,
+                    DocumentVersionDiffResponse, DocumentVersionDiffResponseData, TextDiffHunk,
+                    TextDiffLine, TextDiffTag,
+                },
             },
             history::GetUserHistoryResponse,
             instructions::{CreateInstructionsDocumentResponse, GetInstructionsDocumentResponse},
             pin::{GetPinsResponse, UserPinsResponse},
+            projects::export::{
+                ExportProjectResponse, ExportProjectResponseData, ProjectExportStatusUpdate,
+            },
+            storage::{StorageUsageResponse, StorageUsageResponseData},
             user_views::UserViewsResponse,
         },
     },
@@ -105,6 +116,10 @@ use models_soup::item::SoupItem;
 use models_soup::item::SoupItemType;
 use models_soup::project::SoupProject;
 use soup::inbound::axum_router::{PostSoupRequest, SoupApiItem, SoupApiSort, SoupPage};
+use user_quota::storage::{
+    FileTypeStorageUsage, OrganizationStorageUsage, ProjectStorageUsage, StorageLimitBehavior,
+    StorageQuota, TeamStorageUsage,
+};
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
@@ -132,6 +147,8 @@ use utoipa::OpenApi;
         documents::get_user_documents::get_user_documents_handler,
         documents::get_document::handler,
         documents::get_document_version::handler,
+        documents::get_document_version_diff::handler,
+        documents::restore_document_version::handler,
         documents::create_document::create_document_handler,
         documents::copy_document::copy_document_handler,
         documents::save_document::save_document_handler,
@@ -197,6 +214,10 @@ use utoipa::OpenApi;
         projects::get_batch_preview::get_batch_preview_handler,
         projects::get_project::get_project_handler,
         projects::revert_delete_project::handler,
+        projects::export_project::export_project_handler,
+
+        // storage
+        storage::get_storage_usage::get_storage_usage_handler,
 
         // threads
         threads::edit_thread::edit_thread_handler,
@@ -252,6 +273,15 @@ use utoipa::OpenApi;
             PreSaveDocumentRequest,
             PreSaveDocumentResponseData,
             PreSaveDocumentResponse, // pre save
+            DocumentVersionDiffResponse,
+            DocumentVersionDiffResponseData,
+            DocumentVersionDiff,
+            TextDiffHunk,
+            TextDiffLine,
+            TextDiffTag,
+            CanvasItemChange,
+            BomPartChange,
+            DiffChange, // version diff
             GetActivitiesResponse,
             UserActivitiesResponse,
             Activity, // Get recent ativity
@@ -271,6 +301,21 @@ use utoipa::OpenApi;
             DocumentPermissionsToken,
             DocumentPermissionsTokenRequest,
             ExportDocumentResponse,
+            ExportDocumentJobResponse,
+            DocumentExportStatusUpdate,
+            ExportFormat,
+            ExportProjectRequest,
+            ExportProjectResponse,
+            ExportProjectResponseData,
+            ProjectExportStatusUpdate, // export
+            StorageUsageResponse,
+            StorageUsageResponseData,
+            StorageQuota,
+            StorageLimitBehavior,
+            FileTypeStorageUsage,
+            ProjectStorageUsage,
+            TeamStorageUsage,
+            OrganizationStorageUsage, // storage
             SyncServiceVersionID,
             SoupItem,
             SoupApiItem,
@@ -0,0 +1,134 @@
+/// backfill_storage_usage.rs is used to size the content that was stored before sizes were
+/// recorded on upload, so that it counts towards storage usage.
+/// This covers raw uploads as well as docx bom parts, which count as nothing until sized.
+/// Required environment variables:
+/// - DATABASE_URL
+/// - DOCUMENT_STORAGE_BUCKET
+use anyhow::Context;
+use aws_sdk_s3 as s3;
+use futures::StreamExt;
+use macro_entrypoint::MacroEntrypoint;
+use model::document::build_cloud_storage_bucket_document_key;
+use sqlx::postgres::PgPoolOptions;
+
+/// The number of unsized shas fetched from the db at once
+const PAGE_SIZE: i64 = 1000;
+
+/// The number of head requests made to s3 at once
+const SIZE_CONCURRENCY: usize = 25;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    MacroEntrypoint::default().init();
+
+    println!("Starting backfill_storage_usage");
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL not set")?;
+    let db = PgPoolOptions::new()
+        .min_connections(1)
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .context("could not connect to db")?;
+
+    let bucket =
+        std::env::var("DOCUMENT_STORAGE_BUCKET").context("DOCUMENT_STORAGE_BUCKET not set")?;
+
+    let s3_client = s3::Client::new(
+        &aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region("us-east-1")
+            .load()
+            .await,
+    );
+
+    let mut after_sha = String::new();
+    let mut total = 0;
+    loop {
+        let instances = macro_db_client::storage_usage::get_all_unsized_document_instances(
+            &db, &after_sha, PAGE_SIZE,
+        )
+        .await
+        .context("Failed to get unsized document instances")?;
+
+        let Some(last) = instances.last() else {
+            break;
+        };
+        after_sha = last.sha.clone();
+
+        let keys = instances.into_iter().map(|instance| {
+            let key = build_cloud_storage_bucket_document_key(
+                &instance.owner,
+                &instance.document_id,
+                instance.document_version_id,
+                instance.file_type.as_deref(),
+            );
+            (instance.sha, key)
+        });
+
+        total += size_shas(&db, &s3_client, &bucket, keys).await?;
+        println!("sized {total} document instances");
+    }
+
+    // bom parts are stored under their sha
+    let mut after_sha = String::new();
+    let mut total = 0;
+    loop {
+        let shas =
+            macro_db_client::storage_usage::get_unsized_bom_part_shas(&db, &after_sha, PAGE_SIZE)
+                .await
+                .context("Failed to get unsized bom parts")?;
+
+        let Some(last) = shas.last() else {
+            break;
+        };
+        after_sha = last.clone();
+
+        let keys = shas.into_iter().map(|sha| (sha.clone(), sha));
+
+        total += size_shas(&db, &s3_client, &bucket, keys).await?;
+        println!("sized {total} bom parts");
+    }
+
+    println!("Completed");
+
+    Ok(())
+}
+
+/// Looks up the size of the content stored under each key and records it for its sha.
+/// Returns the number of shas that were sized.
+async fn size_shas(
+    db: &sqlx::PgPool,
+    s3_client: &s3::Client,
+    bucket: &str,
+    keys: impl Iterator<Item = (String, String)>,
+) -> anyhow::Result<usize> {
+    let sha_sizes: Vec<(String, i64)> = futures::stream::iter(keys)
+        .map(|(sha, key)| async move {
+            let size = s3_client
+                .head_object()
+                .bucket(bucket)
+                .key(&key)
+                .send()
+                .await
+                .map(|output| output.content_length());
+
+            match size {
+                Ok(Some(size)) => Some((sha, size)),
+                Ok(None) => None,
+                Err(e) => {
+                    eprintln!("unable to size {key}: {e:?}");
+                    None
+                }
+            }
+        })
+        .buffer_unordered(SIZE_CONCURRENCY)
+        .filter_map(|sha_size| async move { sha_size })
+        .collect()
+        .await;
+
+    macro_db_client::storage_usage::upsert_sha_sizes(db, &sha_sizes)
+        .await
+        .context("Failed to save sizes")?;
+
+    Ok(sha_sizes.len())
+}
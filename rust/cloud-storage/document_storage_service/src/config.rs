@@ -41,6 +41,7 @@ env_var! {
         pub RedisUri,
         pub NotificationQueue,
         pub SearchEventQueue,
+        pub ConvertQueue,
         pub CommsServiceUrl,
         pub EmailServiceUrl,
         pub ConnectionGatewayUrl,
@@ -48,6 +49,7 @@ env_var! {
         pub UploadStagingBucket,
         pub SyncServiceUrl,
         pub SyncServiceAuthKey,
+        pub LexicalServiceUrl,
     }
 }
 
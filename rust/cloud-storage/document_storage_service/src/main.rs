@@ -11,6 +11,7 @@ use dynamodb_client::DynamodbClient;
 use email::{domain::service::EmailServiceImpl, outbound::EmailPgRepo};
 use email_service_client::EmailServiceClient;
 use frecency::{domain::services::FrecencyQueryServiceImpl, outbound::postgres::FrecencyPgStorage};
+use lexical_client::LexicalClient;
 use macro_auth::middleware::decode_jwt::JwtValidationArgs;
 use macro_entrypoint::MacroEntrypoint;
 use macro_env_var::env_var;
@@ -117,7 +118,8 @@ async fn main() -> anyhow::Result<()> {
 
     let sqs_client = sqs_client::SQS::new(aws_sdk_sqs::Client::new(&aws_config))
         .search_event_queue(&config.vars.search_event_queue)
-        .document_delete_queue(&config.vars.document_delete_queue);
+        .document_delete_queue(&config.vars.document_delete_queue)
+        .convert_queue(&config.vars.convert_queue);
 
     tracing::trace!("initialized sqs client");
 
@@ -173,10 +175,15 @@ async fn main() -> anyhow::Result<()> {
     };
 
     let sync_service_client = SyncServiceClient::new(
-        sync_service_auth_key,
+        sync_service_auth_key.clone(),
         config.vars.sync_service_url.as_ref().to_string(),
     );
 
+    let lexical_client = LexicalClient::new(
+        sync_service_auth_key,
+        config.vars.lexical_service_url.as_ref().to_string(),
+    );
+
     let jwt_validation_args =
         JwtValidationArgs::new_with_secret_manager(config.environment, &secretsmanager_client)
             .await?;
@@ -209,11 +216,14 @@ async fn main() -> anyhow::Result<()> {
         comms_service_client: Arc::new(comms_service_client),
         conn_gateway_client: Arc::new(conn_gateway_client),
         sync_service_client: Arc::new(sync_service_client),
+        lexical_client: Arc::new(lexical_client),
         config: Arc::new(config),
         jwt_validation_args,
         dss_auth_key,
     };
 
+    tokio::spawn(api::export_worker::run(api_context.clone()));
+
     api::setup_and_serve(api_context).await?;
 
     Ok(())
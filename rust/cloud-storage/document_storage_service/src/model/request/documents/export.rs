@@ -0,0 +1,48 @@
+use utoipa::ToSchema;
+
+/// The format a document can be exported to
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, Copy, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Md,
+    Html,
+    Docx,
+    Pdf,
+}
+
+impl ExportFormat {
+    /// The file extension of the exported file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Md => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Docx => "docx",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+
+    /// Gets the format with the file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        [
+            ExportFormat::Md,
+            ExportFormat::Html,
+            ExportFormat::Docx,
+            ExportFormat::Pdf,
+        ]
+        .into_iter()
+        .find(|format| format.extension() == extension)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug)]
+pub struct ExportDocumentQueryParams {
+    /// The format to export the document to. Defaults to the file type of the document.
+    pub format: Option<ExportFormat>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProjectRequest {
+    /// The format markdown documents are exported to. Defaults to markdown.
+    pub markdown_format: Option<ExportFormat>,
+}
@@ -1,8 +1,10 @@
 pub mod copy;
 pub mod edit;
+pub mod export;
 pub mod get_user_documents;
 pub mod location;
 pub mod preview;
 pub mod save;
 pub mod user_document_view_location;
 pub mod user_mentions;
+pub mod version;
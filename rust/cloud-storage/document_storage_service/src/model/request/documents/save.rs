@@ -8,6 +8,10 @@ pub struct SaveDocumentRequest {
     /// The sha of the new document.
     /// This is used to generate a presigned url to upload the new document content to s3.
     pub sha: Option<String>,
+    /// The size of the new document in bytes.
+    /// Required along with the sha when the new document content is uploaded to s3, the upload
+    /// url only accepts content of exactly this size.
+    pub size_bytes: Option<i64>,
     /// **DOCX ONLY**
     /// The updated BOM for the document.
     /// Containing the file path and the sha.
@@ -0,0 +1,7 @@
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug)]
+pub struct DocumentVersionDiffQueryParams {
+    /// The document version id to diff from
+    pub from_version_id: i64,
+    /// The document version id to diff to
+    pub to_version_id: i64,
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDocumentJobResponse {
+    /// The id of the export job. The result of the export references this id.
+    pub export_id: String,
+}
+
+/// The result of a document export job, sent over the connection gateway
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DocumentExportStatusUpdate {
+    #[serde(rename_all = "camelCase")]
+    Completed {
+        export_id: String,
+        document_id: String,
+        /// The presigned url to download the exported document
+        presigned_url: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Failed {
+        export_id: String,
+        document_id: String,
+    },
+}
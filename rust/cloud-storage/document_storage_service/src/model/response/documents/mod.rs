@@ -1,5 +1,7 @@
 pub mod create;
+pub mod export;
 pub mod get;
 pub mod preview;
 pub mod save;
 pub mod user_document_view_location;
+pub mod version;
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DocumentVersionDiffResponse {
+    /// Indicates if an error occurred
+    pub error: bool,
+    /// Data to be returned
+    pub data: DocumentVersionDiffResponseData,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentVersionDiffResponseData {
+    /// The document version id the diff starts from
+    pub from_version_id: i64,
+    /// The document version id the diff ends at
+    pub to_version_id: i64,
+    /// The changes between the two versions
+    pub diff: DocumentVersionDiff,
+}
+
+/// A structured diff between two versions of the same document
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocumentVersionDiff {
+    /// Line based diff used for markdown and plain text documents
+    Text {
+        /// The changed regions of the document along with surrounding context
+        hunks: Vec<TextDiffHunk>,
+    },
+    /// Node and edge level diff used for canvas documents
+    Canvas {
+        /// The nodes that were added, removed or modified
+        nodes: Vec<CanvasItemChange>,
+        /// The edges that were added, removed or modified
+        edges: Vec<CanvasItemChange>,
+    },
+    /// Part level diff used for docx documents
+    Bom {
+        /// The bom parts that were added, removed or modified
+        parts: Vec<BomPartChange>,
+    },
+}
+
+/// How an item changed between two versions
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// How a single line changed between two versions
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDiffTag {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDiffHunk {
+    /// The 1-based line the hunk starts at in the old version
+    pub old_start: usize,
+    /// The number of lines of the old version covered by the hunk
+    pub old_lines: usize,
+    /// The 1-based line the hunk starts at in the new version
+    pub new_start: usize,
+    /// The number of lines of the new version covered by the hunk
+    pub new_lines: usize,
+    /// The lines in the hunk
+    pub lines: Vec<TextDiffLine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDiffLine {
+    pub tag: TextDiffTag,
+    /// The 1-based line number in the old version, if the line exists there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_line_number: Option<usize>,
+    /// The 1-based line number in the new version, if the line exists there
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_line_number: Option<usize>,
+    /// The content of the line without its trailing newline
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasItemChange {
+    /// The id of the canvas node or edge
+    pub id: String,
+    pub change: DiffChange,
+    /// The item in the old version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    /// The item in the new version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BomPartChange {
+    /// The path of the part inside of the docx
+    pub path: String,
+    pub change: DiffChange,
+    /// The sha of the part in the old version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_sha: Option<String>,
+    /// The sha of the part in the new version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_sha: Option<String>,
+}
@@ -4,4 +4,6 @@ pub mod documents;
 pub mod history;
 pub mod instructions;
 pub mod pin;
+pub mod projects;
+pub mod storage;
 pub mod user_views;
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ExportProjectResponse {
+    /// Indicates if an error occurred
+    pub error: bool,
+    /// Data to be returned
+    pub data: ExportProjectResponseData,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProjectResponseData {
+    /// The id of the export job. Progress updates reference this id.
+    pub export_id: String,
+    /// The number of documents that will be exported
+    pub document_count: usize,
+}
+
+/// Progress updates sent over the connection gateway while a project export runs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProjectExportStatusUpdate {
+    #[serde(rename_all = "camelCase")]
+    InProgress {
+        export_id: String,
+        project_id: String,
+        /// The number of documents processed so far
+        processed: usize,
+        /// The total number of documents in the export
+        total: usize,
+    },
+    #[serde(rename_all = "camelCase")]
+    Completed {
+        export_id: String,
+        project_id: String,
+        /// The presigned url to download the zip archive
+        presigned_url: String,
+        /// The documents that could not be exported and were left out of the archive
+        failed_document_ids: Vec<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Failed {
+        export_id: String,
+        project_id: String,
+    },
+}
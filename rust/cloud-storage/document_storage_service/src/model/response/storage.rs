@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use user_quota::storage::{
+    FileTypeStorageUsage, OrganizationStorageUsage, ProjectStorageUsage, StorageQuota,
+    TeamStorageUsage,
+};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsageResponseData {
+    /// The storage used by the user measured against their plan
+    pub quota: StorageQuota,
+    /// The storage used by the user grouped by file type.
+    /// Content shared between file types counts towards each of them.
+    pub by_file_type: Vec<FileTypeStorageUsage>,
+    /// The storage used by the user grouped by the project directly containing the documents
+    pub by_project: Vec<ProjectStorageUsage>,
+    /// The storage used by each team the user is a member of
+    pub teams: Vec<TeamStorageUsage>,
+    /// The storage used by the organization of the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<OrganizationStorageUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct StorageUsageResponse {
+    /// Indicates if an error occurred
+    pub error: bool,
+    /// Data to be returned
+    pub data: StorageUsageResponseData,
+}
@@ -1,3 +1,6 @@
+use crate::model::response::{
+    documents::export::DocumentExportStatusUpdate, projects::export::ProjectExportStatusUpdate,
+};
 use connection_gateway_client::client::ConnectionGatewayClient;
 use model::annotations::AnnotationIncrementalUpdate;
 use model_entity::EntityType;
@@ -29,3 +32,59 @@ pub async fn update_live_comment_state(
         }
     }
 }
+
+#[tracing::instrument(skip(client, message))]
+pub async fn update_project_export_state(
+    client: &ConnectionGatewayClient,
+    user_id: &str,
+    message: ProjectExportStatusUpdate,
+) -> () {
+    if cfg!(feature = "disable_connection_gateway") {
+        tracing::info!("bypassing connection gateway");
+    } else {
+        let entities = vec![EntityType::User.with_entity_str(user_id)];
+
+        match serde_json::to_value(message) {
+            Ok(message) => {
+                client
+                    .batch_send_message("project_export".to_string(), message, entities)
+                    .await
+                    .inspect_err(|e| {
+                        tracing::error!(error = ?e, "failed to send message to connection gateway");
+                    })
+                    .ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "failed to serialize message");
+            }
+        }
+    }
+}
+
+#[tracing::instrument(skip(client, message))]
+pub async fn update_document_export_state(
+    client: &ConnectionGatewayClient,
+    user_id: &str,
+    message: DocumentExportStatusUpdate,
+) -> () {
+    if cfg!(feature = "disable_connection_gateway") {
+        tracing::info!("bypassing connection gateway");
+    } else {
+        let entities = vec![EntityType::User.with_entity_str(user_id)];
+
+        match serde_json::to_value(message) {
+            Ok(message) => {
+                client
+                    .batch_send_message("document_export".to_string(), message, entities)
+                    .await
+                    .inspect_err(|e| {
+                        tracing::error!(error = ?e, "failed to send message to connection gateway");
+                    })
+                    .ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "failed to serialize message");
+            }
+        }
+    }
+}
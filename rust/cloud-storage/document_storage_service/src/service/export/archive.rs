@@ -0,0 +1,169 @@
+use std::{
+    collections::HashSet,
+    io::{Cursor, Seek, Write},
+};
+
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+/// The name used for items whose name is empty once sanitized
+const UNTITLED: &str = "Untitled";
+
+/// A zip archive that mirrors a project tree, written to any seekable writer such as a file.
+/// Names are sanitized and de-duplicated so sibling items with the same name do not overwrite
+/// each other.
+pub struct ExportArchive<W: Write + Seek = Cursor<Vec<u8>>> {
+    zip: ZipWriter<W>,
+    used_paths: HashSet<String>,
+}
+
+impl Default for ExportArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExportArchive {
+    /// Creates an archive that is written to memory
+    pub fn new() -> Self {
+        Self::from_writer(Cursor::new(Vec::new()))
+    }
+}
+
+impl<W: Write + Seek> ExportArchive<W> {
+    pub fn from_writer(writer: W) -> Self {
+        Self {
+            zip: ZipWriter::new(writer),
+            used_paths: HashSet::new(),
+        }
+    }
+
+    /// Adds a folder under the provided parent folder.
+    /// Returns the path of the folder inside of the archive.
+    pub fn add_folder(&mut self, parent: &str, name: &str) -> anyhow::Result<String> {
+        let path = self.unique_path(parent, name, None);
+        self.zip
+            .add_directory(format!("{path}/"), SimpleFileOptions::default())?;
+        Ok(path)
+    }
+
+    /// Adds a file under the provided parent folder.
+    /// Returns the path of the file inside of the archive.
+    pub fn add_file(
+        &mut self,
+        parent: &str,
+        name: &str,
+        extension: Option<&str>,
+        content: &[u8],
+    ) -> anyhow::Result<String> {
+        let path = self.unique_path(parent, name, extension);
+        self.zip
+            .start_file(path.as_str(), SimpleFileOptions::default())?;
+        self.zip.write_all(content)?;
+        Ok(path)
+    }
+
+    /// Finishes writing the archive and returns the writer
+    pub fn finish(self) -> anyhow::Result<W> {
+        Ok(self.zip.finish()?)
+    }
+
+    fn unique_path(&mut self, parent: &str, name: &str, extension: Option<&str>) -> String {
+        let name = sanitize_name(name);
+        let build = |suffix: Option<usize>| {
+            let name = match suffix {
+                Some(suffix) => format!("{name} ({suffix})"),
+                None => name.clone(),
+            };
+            let file_name = match extension {
+                Some(extension) => format!("{name}.{extension}"),
+                None => name,
+            };
+            if parent.is_empty() {
+                file_name
+            } else {
+                format!("{parent}/{file_name}")
+            }
+        };
+
+        let mut path = build(None);
+        let mut suffix = 1;
+        // Zip paths are compared case insensitively so extraction works on every platform
+        while !self.used_paths.insert(path.to_lowercase()) {
+            path = build(Some(suffix));
+            suffix += 1;
+        }
+        path
+    }
+}
+
+/// Strips characters that are not allowed in file names on common platforms
+pub fn sanitize_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let sanitized = sanitized.trim().trim_matches('.').trim();
+
+    if sanitized.is_empty() {
+        UNTITLED.to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("report"), "report");
+        assert_eq!(sanitize_name("a/b\\c:d"), "a_b_c_d");
+        assert_eq!(sanitize_name("  .. "), UNTITLED);
+        assert_eq!(sanitize_name(""), UNTITLED);
+    }
+
+    #[test]
+    fn test_archive_preserves_folders() -> anyhow::Result<()> {
+        let mut archive = ExportArchive::new();
+        let root = archive.add_folder("", "Project")?;
+        let folder = archive.add_folder(&root, "Notes")?;
+        let first = archive.add_file(&folder, "doc", Some("md"), b"one")?;
+        let second = archive.add_file(&folder, "Doc", Some("md"), b"two")?;
+        let third = archive.add_file(&folder, "doc", Some("pdf"), b"three")?;
+
+        assert_eq!(first, "Project/Notes/doc.md");
+        assert_eq!(second, "Project/Notes/Doc (1).md");
+        assert_eq!(third, "Project/Notes/doc.pdf");
+
+        let content = archive.finish()?.into_inner();
+        let mut zip = zip::ZipArchive::new(Cursor::new(content))?;
+        let mut file = zip.by_name("Project/Notes/Doc (1).md")?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        assert_eq!(text, "two");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_writes_to_file() -> anyhow::Result<()> {
+        let mut archive = ExportArchive::from_writer(tempfile::tempfile()?);
+        let root = archive.add_folder("", "Project")?;
+        archive.add_file(&root, "doc", Some("md"), b"one")?;
+
+        let file = archive.finish()?;
+        let mut zip = zip::ZipArchive::new(file)?;
+        let mut file = zip.by_name("Project/doc.md")?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        assert_eq!(text, "one");
+
+        Ok(())
+    }
+}
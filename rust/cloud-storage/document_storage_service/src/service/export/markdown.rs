@@ -0,0 +1,45 @@
+use html_utils::escape_html;
+use pulldown_cmark::{Options, Parser, html};
+
+/// Renders a markdown document as a standalone html page
+pub fn markdown_to_html(title: &str, markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(markdown, options);
+
+    let mut body = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut body, parser);
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html(
+            "Notes <draft>",
+            "# Heading\n\n- [x] done\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n~~old~~\n",
+        );
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Notes &lt;draft&gt;</title>"));
+        assert!(html.contains("<h1>Heading</h1>"));
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<del>old</del>"));
+        assert!(html.contains("type=\"checkbox\""));
+    }
+
+    #[test]
+    fn test_markdown_to_html_escapes_raw_text() {
+        let html = markdown_to_html("title", "a & b");
+        assert!(html.contains("<p>a &amp; b</p>"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod archive;
+pub mod markdown;
+pub mod pdf;
@@ -0,0 +1,460 @@
+use anyhow::Context;
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat, dictionary};
+use model::{
+    annotations::HighlightType,
+    document::modification_data::{
+        Color, Comment, Highlight, Payload, PdfModificationData, Placeable,
+    },
+};
+
+/// The default page box used when a page does not define one (US letter)
+const DEFAULT_PAGE_BOX: [f32; 4] = [0.0, 0.0, 612.0, 792.0];
+
+/// The size of the sticky note icon used for comment threads
+const THREAD_ICON_SIZE: f32 = 24.0;
+
+/// Flattens the modification data of a pdf into the pdf itself.
+/// Highlights are written as highlight, underline or strikeout annotations and comment threads
+/// are written as text annotations so the exported file renders them in any pdf viewer.
+pub fn apply_modification_data(
+    pdf: &[u8],
+    modification_data: &PdfModificationData,
+) -> anyhow::Result<Vec<u8>> {
+    let mut document = Document::load_mem(pdf).context("unable to load pdf")?;
+
+    // get_pages is 1-indexed while the modification data is 0-indexed
+    let pages: Vec<ObjectId> = document.get_pages().into_values().collect();
+
+    let mut annotations: Vec<(ObjectId, Dictionary)> = Vec::new();
+
+    if let Some(highlights) = modification_data.highlights.as_ref() {
+        for highlight in highlights.values().flatten() {
+            let Some(page_id) = pages.get(highlight.page_num as usize).copied() else {
+                tracing::warn!(page_num = highlight.page_num, "highlight page out of range");
+                continue;
+            };
+            let page_box = page_box(&document, page_id);
+            if let Some(annotation) = highlight_annotation(highlight, page_box) {
+                annotations.push((page_id, annotation));
+            }
+        }
+    }
+
+    for placeable in modification_data
+        .placeables
+        .iter()
+        .filter(|placeable| !placeable.was_deleted)
+    {
+        let Payload::Thread(thread) = &placeable.payload else {
+            continue;
+        };
+        let page = placeable.page_range.first().copied().unwrap_or(thread.page);
+        let Some(page_id) = usize::try_from(page)
+            .ok()
+            .and_then(|page| pages.get(page))
+            .copied()
+        else {
+            tracing::warn!(page, "thread page out of range");
+            continue;
+        };
+        let page_box = page_box(&document, page_id);
+        annotations.push((
+            page_id,
+            thread_annotation(placeable, &thread.comments, thread.is_resolved, page_box),
+        ));
+    }
+
+    for (page_id, annotation) in annotations {
+        let annotation_id = document.add_object(annotation);
+        add_page_annotation(&mut document, page_id, annotation_id)?;
+    }
+
+    let mut output = Vec::new();
+    document
+        .save_to(&mut output)
+        .context("unable to save pdf")?;
+
+    Ok(output)
+}
+
+/// Builds the markup annotation for a highlight.
+/// Highlight rects are stored as fractions of the page with a top left origin.
+fn highlight_annotation(highlight: &Highlight, page_box: [f32; 4]) -> Option<Dictionary> {
+    if highlight.rects.is_empty() {
+        return None;
+    }
+
+    let [x0, y0, x1, y1] = page_box;
+    let (width, height) = (x1 - x0, y1 - y0);
+
+    let mut quad_points = Vec::with_capacity(highlight.rects.len() * 8);
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+    for rect in &highlight.rects {
+        let left = x0 + rect.left as f32 * width;
+        let right = left + rect.width as f32 * width;
+        let top = y1 - rect.top as f32 * height;
+        let bottom = top - rect.height as f32 * height;
+
+        min_x = min_x.min(left);
+        min_y = min_y.min(bottom);
+        max_x = max_x.max(right);
+        max_y = max_y.max(top);
+
+        // Quad points are ordered top left, top right, bottom left, bottom right
+        quad_points.extend([left, top, right, top, left, bottom, right, bottom]);
+    }
+
+    let subtype = match highlight.highlight_type {
+        HighlightType::Highlight => "Highlight",
+        HighlightType::Underline => "Underline",
+        HighlightType::Strikeout => "StrikeOut",
+    };
+
+    let mut annotation = markup_annotation(subtype, [min_x, min_y, max_x, max_y], &highlight.color);
+    annotation.set(
+        "QuadPoints",
+        quad_points
+            .into_iter()
+            .map(Object::from)
+            .collect::<Vec<_>>(),
+    );
+
+    if let Some(thread) = highlight.thread.as_ref() {
+        annotation.set("Contents", text_string(&thread_contents(&thread.comments)));
+    } else if !highlight.text.is_empty() {
+        annotation.set("Contents", text_string(&highlight.text));
+    }
+
+    Some(annotation)
+}
+
+/// Builds a sticky note annotation for a comment thread placed on the page
+fn thread_annotation(
+    placeable: &Placeable,
+    comments: &[Comment],
+    is_resolved: bool,
+    page_box: [f32; 4],
+) -> Dictionary {
+    let [x0, y0, x1, y1] = page_box;
+    let left = x0 + placeable.position.x_pct as f32 * (x1 - x0);
+    let top = y1 - placeable.position.y_pct as f32 * (y1 - y0);
+
+    let color = Color {
+        red: 255,
+        green: 212,
+        blue: 0,
+        alpha: None,
+    };
+    let mut annotation = markup_annotation(
+        "Text",
+        [left, top - THREAD_ICON_SIZE, left + THREAD_ICON_SIZE, top],
+        &color,
+    );
+    annotation.set("Name", "Comment");
+    let contents = thread_contents(comments);
+    let contents = if is_resolved {
+        format!("[Resolved]\n\n{contents}")
+    } else {
+        contents
+    };
+    annotation.set("Contents", text_string(&contents));
+    if let Some(author) = comments.first().map(|comment| comment.sender.as_str()) {
+        annotation.set("T", text_string(author));
+    }
+
+    annotation
+}
+
+fn markup_annotation(subtype: &str, rect: [f32; 4], color: &Color) -> Dictionary {
+    let mut annotation = dictionary! {
+        "Type" => "Annot",
+        "Subtype" => subtype,
+        "Rect" => rect.into_iter().map(Object::from).collect::<Vec<_>>(),
+        "C" => vec![
+            Object::from(color.red as f32 / 255.0),
+            Object::from(color.green as f32 / 255.0),
+            Object::from(color.blue as f32 / 255.0),
+        ],
+        // Print flag so annotations are kept when the exported file is printed
+        "F" => 4,
+    };
+    if let Some(alpha) = color.alpha {
+        annotation.set("CA", alpha as f32);
+    }
+    annotation
+}
+
+/// Flattens the comments of a thread into a single block of text
+fn thread_contents(comments: &[Comment]) -> String {
+    comments
+        .iter()
+        .map(|comment| format!("{}: {}", comment.sender, comment.content))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Encodes text as a utf-16 pdf text string so non-ascii characters survive
+fn text_string(text: &str) -> Object {
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+/// Gets the visible box of a page, walking up the page tree for inherited values
+fn page_box(document: &Document, page_id: ObjectId) -> [f32; 4] {
+    let mut current = document.get_dictionary(page_id).ok();
+    while let Some(dictionary) = current {
+        for key in [b"CropBox".as_slice(), b"MediaBox".as_slice()] {
+            let page_box = dictionary
+                .get_deref(key, document)
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|values| {
+                    let values: Vec<f32> = values
+                        .iter()
+                        .filter_map(|value| document.dereference(value).ok())
+                        .filter_map(|(_, value)| value.as_float().ok())
+                        .collect();
+                    <[f32; 4]>::try_from(values).ok()
+                });
+            if let Some([a, b, c, d]) = page_box {
+                return [a.min(c), b.min(d), a.max(c), b.max(d)];
+            }
+        }
+        current = dictionary
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+            .and_then(|parent| document.get_dictionary(parent))
+            .ok();
+    }
+    DEFAULT_PAGE_BOX
+}
+
+/// Appends an annotation reference to the annotations of a page
+fn add_page_annotation(
+    document: &mut Document,
+    page_id: ObjectId,
+    annotation_id: ObjectId,
+) -> anyhow::Result<()> {
+    let annotations_ref = document
+        .get_dictionary(page_id)
+        .context("unable to get page")?
+        .get(b"Annots")
+        .and_then(Object::as_reference)
+        .ok();
+
+    if let Some(annotations_ref) = annotations_ref {
+        let annotations = document
+            .get_object_mut(annotations_ref)
+            .and_then(Object::as_array_mut)
+            .context("unable to get page annotations")?;
+        annotations.push(Object::Reference(annotation_id));
+        return Ok(());
+    }
+
+    let page = document
+        .get_dictionary_mut(page_id)
+        .context("unable to get page")?;
+    match page.get_mut(b"Annots").and_then(Object::as_array_mut) {
+        Ok(annotations) => annotations.push(Object::Reference(annotation_id)),
+        Err(_) => page.set("Annots", vec![Object::Reference(annotation_id)]),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{Stream, content::Content};
+    use model::document::modification_data::{
+        AllowableEdits, HighlightRect, PlaceablePosition, Thread,
+    };
+    use std::collections::HashMap;
+
+    fn test_pdf(page_count: usize) -> Vec<u8> {
+        let mut document = Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let content_id = document.add_object(Stream::new(
+            dictionary! {},
+            Content { operations: vec![] }.encode().unwrap(),
+        ));
+
+        let kids: Vec<Object> = (0..page_count)
+            .map(|_| {
+                document
+                    .add_object(dictionary! {
+                        "Type" => "Page",
+                        "Parent" => pages_id,
+                        "Contents" => content_id,
+                    })
+                    .into()
+            })
+            .collect();
+
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => page_count as i64,
+                "MediaBox" => vec![0.into(), 0.into(), 600.into(), 800.into()],
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        document.trailer.set("Root", catalog_id);
+
+        let mut output = Vec::new();
+        document.save_to(&mut output).unwrap();
+        output
+    }
+
+    fn page_annotations(pdf: &[u8], page: u32) -> Vec<Dictionary> {
+        let document = Document::load_mem(pdf).unwrap();
+        let page_id = document.get_pages()[&page];
+        document
+            .get_page_annotations(page_id)
+            .unwrap()
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn color() -> Color {
+        Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+            alpha: Some(0.5),
+        }
+    }
+
+    #[test]
+    fn test_apply_highlights() -> anyhow::Result<()> {
+        let highlight = Highlight {
+            page_num: 1,
+            rects: vec![HighlightRect {
+                top: 0.5,
+                left: 0.25,
+                width: 0.5,
+                height: 0.1,
+            }],
+            color: color(),
+            highlight_type: HighlightType::Underline,
+            thread: None,
+            text: "underlined".to_string(),
+            page_viewport: None,
+            has_temp_thread: None,
+            uuid: None,
+        };
+        let modification_data = PdfModificationData {
+            highlights: Some(HashMap::from([(1, vec![highlight])])),
+            ..Default::default()
+        };
+
+        let output = apply_modification_data(&test_pdf(2), &modification_data)?;
+
+        assert!(page_annotations(&output, 1).is_empty());
+        let annotations = page_annotations(&output, 2);
+        assert_eq!(annotations.len(), 1);
+
+        let annotation = &annotations[0];
+        assert_eq!(annotation.get(b"Subtype")?.as_name()?, b"Underline");
+        let rect: Vec<f32> = annotation
+            .get(b"Rect")?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_float().unwrap())
+            .collect();
+        assert_eq!(rect, vec![150.0, 320.0, 450.0, 400.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_threads() -> anyhow::Result<()> {
+        let thread = Thread {
+            head_id: "head".to_string(),
+            page: 0,
+            comments: vec![Comment {
+                sender: "macro|user@macro.com".to_string(),
+                content: "looks good ✅".to_string(),
+                id: "1".to_string(),
+                edit_date: chrono::Utc::now(),
+            }],
+            is_resolved: false,
+        };
+        let placeable = |was_deleted: bool| Placeable {
+            allowable_edits: AllowableEdits {
+                allow_resize: false,
+                allow_translate: true,
+                allow_rotate: false,
+                allow_delete: true,
+                lock_aspect_ratio: false,
+            },
+            was_edited: false,
+            was_deleted,
+            page_range: vec![0],
+            position: PlaceablePosition {
+                x_pct: 0.1,
+                y_pct: 0.1,
+                width_pct: 0.0,
+                height_pct: 0.0,
+                rotation: 0.0,
+            },
+            should_lock_on_save: false,
+            original_page: 0,
+            original_index: -1,
+            payload: Payload::Thread(thread.clone()),
+        };
+        let modification_data = PdfModificationData {
+            placeables: vec![placeable(false), placeable(true)],
+            ..Default::default()
+        };
+
+        let output = apply_modification_data(&test_pdf(1), &modification_data)?;
+
+        let annotations = page_annotations(&output, 1);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].get(b"Subtype")?.as_name()?, b"Text");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_out_of_range_page() -> anyhow::Result<()> {
+        let highlight = Highlight {
+            page_num: 5,
+            rects: vec![HighlightRect {
+                top: 0.0,
+                left: 0.0,
+                width: 1.0,
+                height: 1.0,
+            }],
+            color: color(),
+            highlight_type: HighlightType::Highlight,
+            thread: None,
+            text: String::new(),
+            page_viewport: None,
+            has_temp_thread: None,
+            uuid: None,
+        };
+        let modification_data = PdfModificationData {
+            highlights: Some(HashMap::from([(5, vec![highlight])])),
+            ..Default::default()
+        };
+
+        let output = apply_modification_data(&test_pdf(1), &modification_data)?;
+        assert!(page_annotations(&output, 1).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_invalid_pdf() {
+        assert!(apply_modification_data(b"not a pdf", &PdfModificationData::default()).is_err());
+    }
+}
@@ -1,2 +1,5 @@
 pub mod conn_gateway;
+pub mod export;
 pub mod s3;
+pub mod storage_usage;
+pub mod version_diff;
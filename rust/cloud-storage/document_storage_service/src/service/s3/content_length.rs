@@ -0,0 +1,28 @@
+use anyhow::Context;
+use aws_sdk_s3::{self as s3};
+
+/// Gets the size in bytes of a given key in the bucket.
+/// Returns None if the key does not exist.
+#[tracing::instrument(skip(client))]
+pub(in crate::service::s3) async fn content_length(
+    client: &s3::Client,
+    bucket: &str,
+    key: &str,
+) -> anyhow::Result<Option<i64>> {
+    #[cfg(feature = "local")]
+    {
+        return Ok(None);
+    }
+    let resp = client.head_object().bucket(bucket).key(key).send().await;
+
+    match resp {
+        Ok(output) => Ok(output.content_length()),
+        Err(e) => {
+            if e.as_service_error().map(|e| e.is_not_found()) == Some(true) {
+                return Ok(None);
+            }
+
+            Err(e).context("failed to perform head object operation")
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod content_length;
 mod copy_document;
 mod delete;
 mod exists;
@@ -66,11 +67,19 @@ impl S3Client {
             .await
     }
 
+    /// Uploads the file at the path, streaming it from disk instead of loading it into memory
+    pub async fn upload_file(&self, key: &str, path: &std::path::Path) -> anyhow::Result<()> {
+        let stream = ByteStream::from_path(path).await?;
+        upload_document::upload_document(&self.inner, &self.document_storage_bucket, key, stream)
+            .await
+    }
+
     pub async fn put_document_storage_presigned_url(
         &self,
         key: &str,
         sha: &str,
         content_type: ContentType,
+        content_length: i64,
     ) -> anyhow::Result<String> {
         put_presigned_url::put_presigned_url(
             &self.inner,
@@ -78,6 +87,7 @@ impl S3Client {
             key,
             sha,
             content_type,
+            content_length,
         )
         .await
     }
@@ -88,6 +98,7 @@ impl S3Client {
         key: &str,
         sha: &str,
         content_type: ContentType,
+        content_length: i64,
     ) -> anyhow::Result<String> {
         put_presigned_url::put_presigned_url(
             &self.inner,
@@ -95,6 +106,7 @@ impl S3Client {
             key,
             sha,
             content_type,
+            content_length,
         )
         .await
     }
@@ -104,6 +116,7 @@ impl S3Client {
         &self,
         key: &str,
         sha: &str,
+        content_length: i64,
     ) -> anyhow::Result<String> {
         put_presigned_url::put_presigned_url(
             &self.inner,
@@ -111,6 +124,7 @@ impl S3Client {
             key,
             sha,
             FileType::Zip.into(),
+            content_length,
         )
         .await
     }
@@ -140,10 +154,20 @@ impl S3Client {
         .await
     }
 
+    /// Deletes the objects stored at the keys
+    pub async fn delete_keys(&self, keys: Vec<String>) -> anyhow::Result<()> {
+        delete::delete_objects(&self.inner, &self.document_storage_bucket, keys).await
+    }
+
     pub async fn exists(&self, key: &str) -> anyhow::Result<bool> {
         exists::exists(&self.inner, &self.document_storage_bucket, key).await
     }
 
+    /// Gets the size in bytes of a document key. Returns None if the key does not exist.
+    pub async fn content_length(&self, key: &str) -> anyhow::Result<Option<i64>> {
+        content_length::content_length(&self.inner, &self.document_storage_bucket, key).await
+    }
+
     /// Gets all the keys in a folder
     /// Returns a list of the (file_name, file_type) for each file in the folder
     #[tracing::instrument(skip(self))]
@@ -15,6 +15,7 @@ pub(in crate::service::s3) async fn put_presigned_url(
     key: &str,
     sha: &str,
     content_type: ContentType,
+    content_length: i64,
 ) -> anyhow::Result<String> {
     #[cfg(feature = "local")]
     {
@@ -37,6 +38,8 @@ pub(in crate::service::s3) async fn put_presigned_url(
         .key(key)
         .content_type(content_type.mime_type())
         .checksum_sha256(base64_encoded_sha)
+        // binds the upload to the declared size so it can be checked against the storage quota
+        .content_length(content_length)
         .presigned(PresigningConfig::expires_in(expiry_duration)?)
         .await?;
 
@@ -0,0 +1,65 @@
+use futures::StreamExt;
+use macro_db_client::storage_usage::UnsizedDocumentInstance;
+use macro_user_id::{lowercased::Lowercase, user_id::MacroUserId};
+use model::document::build_cloud_storage_bucket_document_key;
+use sqlx::PgPool;
+
+use crate::service::s3::S3;
+
+/// The maximum number of document instances sized in a single pass
+const MAX_UNSIZED_DOCUMENT_INSTANCES: i64 = 500;
+
+/// The number of head requests made to s3 at once
+const SIZE_CONCURRENCY: usize = 10;
+
+/// Raw uploads go straight to s3 through presigned urls so their size is not known when the
+/// document is created. This looks up the size of any uploads of the user that have not been
+/// sized yet and records them so they count towards the user's storage usage.
+#[tracing::instrument(skip(db, s3_client))]
+pub async fn size_unsized_documents(
+    db: &PgPool,
+    s3_client: &S3,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<()> {
+    let unsized_instances = macro_db_client::storage_usage::get_unsized_document_instances(
+        db,
+        user_id,
+        MAX_UNSIZED_DOCUMENT_INSTANCES,
+    )
+    .await?;
+
+    if unsized_instances.is_empty() {
+        return Ok(());
+    }
+
+    let sha_sizes: Vec<(String, i64)> = futures::stream::iter(unsized_instances)
+        .map(|instance| async move {
+            let key = document_instance_key(&instance);
+            match s3_client.content_length(&key).await {
+                Ok(Some(size)) => Some((instance.sha, size)),
+                Ok(None) => {
+                    tracing::warn!(key=%key, "document instance does not exist");
+                    None
+                }
+                Err(e) => {
+                    tracing::error!(error=?e, key=%key, "unable to get document instance size");
+                    None
+                }
+            }
+        })
+        .buffer_unordered(SIZE_CONCURRENCY)
+        .filter_map(|sha_size| async move { sha_size })
+        .collect()
+        .await;
+
+    macro_db_client::storage_usage::upsert_sha_sizes(db, &sha_sizes).await
+}
+
+fn document_instance_key(instance: &UnsizedDocumentInstance) -> String {
+    build_cloud_storage_bucket_document_key(
+        &instance.owner,
+        &instance.document_id,
+        instance.document_version_id,
+        instance.file_type.as_deref(),
+    )
+}
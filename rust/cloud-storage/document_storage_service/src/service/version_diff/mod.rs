@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context;
+use model::document::{BomPart, FileType, FileTypeExt};
+use serde::Deserialize;
+use similar::{ChangeTag, TextDiff};
+
+use crate::model::response::documents::version::{
+    BomPartChange, CanvasItemChange, DiffChange, DocumentVersionDiff, TextDiffHunk, TextDiffLine,
+    TextDiffTag,
+};
+
+/// The number of unchanged lines included around each text hunk
+const TEXT_DIFF_CONTEXT_LINES: usize = 3;
+
+/// The kind of diff that can be produced for a file type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionDiffKind {
+    Text,
+    Canvas,
+    Bom,
+}
+
+impl VersionDiffKind {
+    /// Returns the kind of diff supported for the file type, if any
+    pub fn for_file_type(file_type: FileType) -> Option<Self> {
+        match file_type {
+            FileType::Docx => Some(VersionDiffKind::Bom),
+            FileType::Canvas => Some(VersionDiffKind::Canvas),
+            FileType::Pdf => None,
+            _ if file_type.is_text_content() => Some(VersionDiffKind::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Computes a line based diff between two versions of a text document.
+/// Only changed regions are returned, each with a few lines of surrounding context.
+pub fn diff_text(from: &str, to: &str) -> Vec<TextDiffHunk> {
+    let diff = TextDiff::from_lines(from, to);
+
+    diff.grouped_ops(TEXT_DIFF_CONTEXT_LINES)
+        .iter()
+        .filter_map(|group| {
+            let first = group.first()?;
+            let last = group.last()?;
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| TextDiffLine {
+                    tag: match change.tag() {
+                        ChangeTag::Equal => TextDiffTag::Equal,
+                        ChangeTag::Insert => TextDiffTag::Insert,
+                        ChangeTag::Delete => TextDiffTag::Delete,
+                    },
+                    old_line_number: change.old_index().map(|i| i + 1),
+                    new_line_number: change.new_index().map(|i| i + 1),
+                    content: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                })
+                .collect();
+
+            Some(TextDiffHunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize, Default)]
+struct CanvasContent {
+    #[serde(default)]
+    nodes: Vec<serde_json::Value>,
+    #[serde(default)]
+    edges: Vec<serde_json::Value>,
+}
+
+/// Computes a node and edge level diff between two versions of a canvas document.
+/// Items are matched on their `id` field.
+pub fn diff_canvas(from: &[u8], to: &[u8]) -> anyhow::Result<DocumentVersionDiff> {
+    let from: CanvasContent =
+        serde_json::from_slice(from).context("unable to parse old canvas version")?;
+    let to: CanvasContent =
+        serde_json::from_slice(to).context("unable to parse new canvas version")?;
+
+    Ok(DocumentVersionDiff::Canvas {
+        nodes: diff_canvas_items(from.nodes, to.nodes),
+        edges: diff_canvas_items(from.edges, to.edges),
+    })
+}
+
+fn diff_canvas_items(
+    from: Vec<serde_json::Value>,
+    to: Vec<serde_json::Value>,
+) -> Vec<CanvasItemChange> {
+    let key_by_id = |items: Vec<serde_json::Value>| -> BTreeMap<String, serde_json::Value> {
+        items
+            .into_iter()
+            .filter_map(|item| {
+                let id = item.get("id")?.as_str()?.to_string();
+                Some((id, item))
+            })
+            .collect()
+    };
+
+    let mut from = key_by_id(from);
+    let to = key_by_id(to);
+
+    let mut changes = Vec::new();
+    for (id, after) in to {
+        match from.remove(&id) {
+            Some(before) if before == after => {}
+            Some(before) => changes.push(CanvasItemChange {
+                id,
+                change: DiffChange::Modified,
+                before: Some(before),
+                after: Some(after),
+            }),
+            None => changes.push(CanvasItemChange {
+                id,
+                change: DiffChange::Added,
+                before: None,
+                after: Some(after),
+            }),
+        }
+    }
+
+    changes.extend(from.into_iter().map(|(id, before)| CanvasItemChange {
+        id,
+        change: DiffChange::Removed,
+        before: Some(before),
+        after: None,
+    }));
+
+    changes.sort_by(|a, b| a.id.cmp(&b.id));
+    changes
+}
+
+/// Computes a part level diff between two docx boms.
+/// Parts are matched on their path and compared by sha.
+pub fn diff_bom_parts(from: &[BomPart], to: &[BomPart]) -> Vec<BomPartChange> {
+    let mut from: HashMap<&str, &str> = from
+        .iter()
+        .map(|part| (part.path.as_str(), part.sha.as_str()))
+        .collect();
+
+    let mut changes = Vec::new();
+    for part in to {
+        match from.remove(part.path.as_str()) {
+            Some(from_sha) if from_sha == part.sha => {}
+            Some(from_sha) => changes.push(BomPartChange {
+                path: part.path.clone(),
+                change: DiffChange::Modified,
+                from_sha: Some(from_sha.to_string()),
+                to_sha: Some(part.sha.clone()),
+            }),
+            None => changes.push(BomPartChange {
+                path: part.path.clone(),
+                change: DiffChange::Added,
+                from_sha: None,
+                to_sha: Some(part.sha.clone()),
+            }),
+        }
+    }
+
+    changes.extend(from.into_iter().map(|(path, sha)| BomPartChange {
+        path: path.to_string(),
+        change: DiffChange::Removed,
+        from_sha: Some(sha.to_string()),
+        to_sha: None,
+    }));
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bom_part(path: &str, sha: &str) -> BomPart {
+        BomPart {
+            id: format!("{path}-{sha}"),
+            sha: sha.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_kind_for_file_type() {
+        assert_eq!(
+            VersionDiffKind::for_file_type(FileType::Md),
+            Some(VersionDiffKind::Text)
+        );
+        assert_eq!(
+            VersionDiffKind::for_file_type(FileType::Txt),
+            Some(VersionDiffKind::Text)
+        );
+        assert_eq!(
+            VersionDiffKind::for_file_type(FileType::Canvas),
+            Some(VersionDiffKind::Canvas)
+        );
+        assert_eq!(
+            VersionDiffKind::for_file_type(FileType::Docx),
+            Some(VersionDiffKind::Bom)
+        );
+        assert_eq!(VersionDiffKind::for_file_type(FileType::Pdf), None);
+        assert_eq!(VersionDiffKind::for_file_type(FileType::Png), None);
+    }
+
+    #[test]
+    fn test_diff_text_identical() {
+        assert!(diff_text("a\nb\nc\n", "a\nb\nc\n").is_empty());
+    }
+
+    #[test]
+    fn test_diff_text_changed_line() {
+        let hunks = diff_text("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(hunks.len(), 1);
+
+        let hunk = &hunks[0];
+        assert_eq!(hunk.old_start, 1);
+        assert_eq!(hunk.old_lines, 3);
+        assert_eq!(hunk.new_start, 1);
+        assert_eq!(hunk.new_lines, 4);
+
+        let lines: Vec<(TextDiffTag, &str)> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.tag, l.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (TextDiffTag::Equal, "a"),
+                (TextDiffTag::Delete, "b"),
+                (TextDiffTag::Insert, "B"),
+                (TextDiffTag::Equal, "c"),
+                (TextDiffTag::Insert, "d"),
+            ]
+        );
+        assert_eq!(hunk.lines[1].old_line_number, Some(2));
+        assert_eq!(hunk.lines[1].new_line_number, None);
+        assert_eq!(hunk.lines[4].new_line_number, Some(4));
+    }
+
+    #[test]
+    fn test_diff_text_separate_hunks() {
+        let from: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let to: String = (1..=20)
+            .map(|i| match i {
+                2 | 19 => format!("changed {i}\n"),
+                _ => format!("{i}\n"),
+            })
+            .collect();
+
+        let hunks = diff_text(&from, &to);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].old_start, 1);
+        assert_eq!(hunks[1].old_start, 16);
+    }
+
+    #[test]
+    fn test_diff_canvas() -> anyhow::Result<()> {
+        let from = serde_json::json!({
+            "nodes": [
+                { "id": "a", "x": 0 },
+                { "id": "b", "x": 0 },
+                { "id": "c", "x": 0 },
+            ],
+            "edges": [{ "id": "e1", "from": "a", "to": "b" }],
+        });
+        let to = serde_json::json!({
+            "nodes": [
+                { "id": "a", "x": 0 },
+                { "id": "b", "x": 10 },
+                { "id": "d", "x": 0 },
+            ],
+            "edges": [],
+        });
+
+        let diff = diff_canvas(&serde_json::to_vec(&from)?, &serde_json::to_vec(&to)?)?;
+        let DocumentVersionDiff::Canvas { nodes, edges } = diff else {
+            panic!("expected canvas diff");
+        };
+
+        let nodes: Vec<(&str, DiffChange)> =
+            nodes.iter().map(|n| (n.id.as_str(), n.change)).collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("b", DiffChange::Modified),
+                ("c", DiffChange::Removed),
+                ("d", DiffChange::Added),
+            ]
+        );
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].change, DiffChange::Removed);
+        assert_eq!(edges[0].after, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_canvas_invalid() {
+        assert!(diff_canvas(b"not json", b"{}").is_err());
+    }
+
+    #[test]
+    fn test_diff_bom_parts() {
+        let from = vec![
+            bom_part("word/document.xml", "sha-1"),
+            bom_part("word/styles.xml", "sha-2"),
+            bom_part("word/media/image1.png", "sha-3"),
+        ];
+        let to = vec![
+            bom_part("word/document.xml", "sha-4"),
+            bom_part("word/styles.xml", "sha-2"),
+            bom_part("word/media/image2.png", "sha-5"),
+        ];
+
+        let changes = diff_bom_parts(&from, &to);
+        assert_eq!(
+            changes,
+            vec![
+                BomPartChange {
+                    path: "word/document.xml".to_string(),
+                    change: DiffChange::Modified,
+                    from_sha: Some("sha-1".to_string()),
+                    to_sha: Some("sha-4".to_string()),
+                },
+                BomPartChange {
+                    path: "word/media/image1.png".to_string(),
+                    change: DiffChange::Removed,
+                    from_sha: Some("sha-3".to_string()),
+                    to_sha: None,
+                },
+                BomPartChange {
+                    path: "word/media/image2.png".to_string(),
+                    change: DiffChange::Added,
+                    from_sha: None,
+                    to_sha: Some("sha-5".to_string()),
+                },
+            ]
+        );
+    }
+}
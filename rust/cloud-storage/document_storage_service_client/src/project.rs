@@ -0,0 +1,47 @@
+use super::DocumentStorageServiceClient;
+use anyhow::Result;
+use model::project::response::{GetProjectResponse, GetProjectResponseData};
+use reqwest::StatusCode;
+
+impl DocumentStorageServiceClient {
+    /// Get a project and the user's access level to it using JWT authentication (calls external API).
+    /// Returns `None` when the project doesn't exist or the user can't view it.
+    #[tracing::instrument(skip(self, jwt_token))]
+    pub async fn get_project_external(
+        &self,
+        project_id: &str,
+        jwt_token: &str,
+    ) -> Result<Option<GetProjectResponseData>> {
+        let res = self
+            .external_request(
+                reqwest::Method::GET,
+                &format!("/projects/{}", project_id),
+                jwt_token,
+            )
+            .send()
+            .await?;
+
+        let status_code = res.status();
+
+        if matches!(
+            status_code,
+            StatusCode::NOT_FOUND | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Ok(None);
+        }
+
+        if !status_code.is_success() {
+            let body = res.text().await.unwrap_or("no body".to_string());
+            tracing::error!(
+                body=%body,
+                status=%status_code,
+                project_id=%project_id,
+                "external API error when fetching project"
+            );
+            return Err(anyhow::anyhow!("HTTP {}: {}", status_code, body));
+        }
+
+        let response = res.json::<GetProjectResponse>().await?;
+        Ok(Some(response.data))
+    }
+}
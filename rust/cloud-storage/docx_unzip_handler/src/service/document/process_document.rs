@@ -145,6 +145,17 @@ pub async fn process(
 
     tracing::trace!("bom parts saved to db");
 
+    let sha_sizes: Vec<(String, i64)> = bom_parts
+        .iter()
+        .map(|bp| (bp.sha.clone(), bp.content.len() as i64))
+        .collect();
+
+    macro_db_client::storage_usage::upsert_sha_sizes(&ctx.db, &sha_sizes)
+        .await
+        .context("unable to save bom part sizes to db")?;
+
+    tracing::trace!("bom part sizes saved to db");
+
     tracing::trace!("incrementing sha counts");
     ctx.redis_client.increment_counts(shas).await?;
 
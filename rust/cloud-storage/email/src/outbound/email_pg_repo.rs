@@ -253,6 +253,7 @@ impl EmailRepo for EmailPgRepo {
     ) -> Result<Option<Link>, Self::Err> {
         let provider: DbUserProvider = match provider {
             UserProvider::Gmail => DbUserProvider::Gmail,
+            UserProvider::Imap => DbUserProvider::Imap,
         };
 
         let db_link = sqlx::query_as!(
@@ -154,6 +154,7 @@ impl ThreadPreviewCursorDbRow {
 #[dg(forward = crate::domain::models::UserProvider)]
 pub enum DbUserProvider {
     Gmail,
+    Imap,
 }
 
 #[derive(Debug, Clone)]
@@ -125,11 +125,17 @@ fn escape_like_pattern(s: &str) -> String {
         .replace('_', r"\_")
 }
 
+/// Hides snoozed threads from the inbox views until their snooze time has passed
+const NOT_SNOOZED_FILTER: &str = " AND (t.snoozed_until IS NULL OR t.snoozed_until <= NOW())";
+
 /// Builds thread-level WHERE conditions based on the view type
 fn build_view_thread_filter(view: &PreviewView) -> String {
     match view {
         PreviewView::StandardLabel(PreviewViewStandardLabel::Inbox) => {
-            " AND t.inbox_visible = TRUE AND t.latest_inbound_message_ts IS NOT NULL".to_string()
+            format!(
+                " AND t.inbox_visible = TRUE AND t.latest_inbound_message_ts IS NOT NULL{}",
+                NOT_SNOOZED_FILTER
+            )
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Sent) => {
             " AND t.latest_outbound_message_ts IS NOT NULL".to_string()
@@ -149,11 +155,11 @@ fn build_view_thread_filter(view: &PreviewView) -> String {
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Important) => {
             // Important threads have at least one message with IMPORTANT label
-            String::new()
+            NOT_SNOOZED_FILTER.to_string()
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Other) => {
             // Other inbox: inbox_visible but not in primary (no IMPORTANT or CATEGORY_PERSONAL)
-            " AND t.inbox_visible = TRUE".to_string()
+            format!(" AND t.inbox_visible = TRUE{}", NOT_SNOOZED_FILTER)
         }
         PreviewView::UserLabel(_label_name) => {
             // User labels are filtered at message level
@@ -169,7 +175,9 @@ fn build_view_message_filter(view: &PreviewView, link_id_param: &str) -> String
             " AND m.is_draft = FALSE".to_string()
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Sent) => {
-            " AND m.is_sent = TRUE".to_string()
+            // messages waiting in the outbox count as sent
+            " AND (m.is_sent = TRUE OR EXISTS (SELECT 1 FROM email_outbox o WHERE o.message_id = m.id))"
+                .to_string()
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Drafts) => {
             " AND m.is_draft = TRUE".to_string()
@@ -220,15 +228,19 @@ fn build_view_message_filter(view: &PreviewView, link_id_param: &str) -> String
     }
 }
 
-/// Returns the appropriate timestamp field to use for sorting based on the view
+/// Returns the appropriate timestamp field to use for sorting based on the view.
+/// In the views that hide snoozed threads, a resurfaced thread sorts by when its snooze ended.
 fn get_sort_timestamp_field(view: &PreviewView) -> &'static str {
     match view {
         PreviewView::StandardLabel(PreviewViewStandardLabel::Sent) => {
             "t.latest_outbound_message_ts"
         }
         PreviewView::StandardLabel(PreviewViewStandardLabel::Inbox) => {
-            "t.latest_inbound_message_ts"
+            "GREATEST(t.latest_inbound_message_ts, t.snoozed_until)"
         }
+        PreviewView::StandardLabel(
+            PreviewViewStandardLabel::Important | PreviewViewStandardLabel::Other,
+        ) => "GREATEST(t.latest_non_spam_message_ts, t.snoozed_until)",
         _ => "t.latest_non_spam_message_ts",
     }
 }
@@ -84,23 +84,27 @@ pub(crate) async fn important_preview_cursor(
                 ait.subject,
                 ait.snippet,
                 ait.from_contact_id,
-                ait.internal_date_ts as created_at,
-                ait.internal_date_ts as updated_at,
+                -- a resurfaced thread sorts by when its snooze ended rather than its latest message
+                GREATEST(ait.internal_date_ts, t.snoozed_until) as created_at,
+                GREATEST(ait.internal_date_ts, t.snoozed_until) as updated_at,
                 uh.updated_at as viewed_at,
                 -- This CASE statement dynamically selects the correct timestamp for sorting.
-                -- For 'updated_at' and 'created_at', we use the important message timestamp
+                -- For 'updated_at' and 'created_at', we use the important message timestamp, or when the thread's snooze ended if later
                 -- For 'viewed_at', we use the history timestamp.
                 -- For 'viewed_updated', we use the history timestamp if it exists, otherwise the important message timestamp.
                 CASE $5 -- sort_method_str
                     WHEN 'viewed_at' THEN COALESCE(uh."updated_at", '1970-01-01 00:00:00+00')
-                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, ait.internal_date_ts)
-                    ELSE ait.internal_date_ts
+                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, GREATEST(ait.internal_date_ts, t.snoozed_until))
+                    ELSE GREATEST(ait.internal_date_ts, t.snoozed_until)
                 END AS effective_ts
             FROM AllImportantThreads ait
+            JOIN email_threads t ON t.id = ait.thread_id
             -- This has to be a left join to support all sort methods.
             LEFT JOIN email_user_history uh ON uh.thread_id = ait.thread_id AND uh.link_id = $1
             -- user_history updated_at (aka last time opened) has to exist when viewed_at sort method is selected.
             WHERE ait.is_draft = FALSE 
+              -- snoozed threads resurface once their snooze time has passed
+              AND (t.snoozed_until IS NULL OR t.snoozed_until <= NOW())
         )
         SELECT
                isk.thread_id as "id!",
@@ -55,13 +55,14 @@ pub(crate) async fn new_inbox_preview_cursor(
                 t.provider_id,
                 t.link_id,
                 t.is_read,
-                t.latest_inbound_message_ts AS created_at,
-                t.latest_inbound_message_ts AS updated_at,
+                -- a resurfaced thread sorts by when its snooze ended rather than its latest message
+                GREATEST(t.latest_inbound_message_ts, t.snoozed_until) AS created_at,
+                GREATEST(t.latest_inbound_message_ts, t.snoozed_until) AS updated_at,
                 uh.updated_at AS viewed_at,
                 CASE $5 -- sort_method_str
                     WHEN 'viewed_at' THEN COALESCE(uh."updated_at", '1970-01-01 00:00:00+00')
-                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, t.latest_inbound_message_ts)
-                    ELSE t.latest_inbound_message_ts
+                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, GREATEST(t.latest_inbound_message_ts, t.snoozed_until))
+                    ELSE GREATEST(t.latest_inbound_message_ts, t.snoozed_until)
                 END AS effective_ts
             FROM email_threads t
             LEFT JOIN email_user_history uh ON uh.thread_id = t.id AND uh.link_id = t.link_id
@@ -69,14 +70,16 @@ pub(crate) async fn new_inbox_preview_cursor(
                 t.link_id = $1
               AND t.inbox_visible = TRUE
               AND t.latest_inbound_message_ts IS NOT NULL
+              -- snoozed threads resurface once their snooze time has passed
+              AND (t.snoozed_until IS NULL OR t.snoozed_until <= NOW())
               
               -- The cursor logic is moved inside this subquery for maximum efficiency.
               AND (($3::timestamptz IS NULL) OR (
                   -- This CASE must exactly match the one that defines `effective_ts`
                   CASE $5 -- sort_method_str
                       WHEN 'viewed_at' THEN COALESCE(uh."updated_at", '1970-01-01 00:00:00+00')
-                      WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, t.latest_inbound_message_ts)
-                      ELSE t.latest_inbound_message_ts
+                      WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, GREATEST(t.latest_inbound_message_ts, t.snoozed_until))
+                      ELSE GREATEST(t.latest_inbound_message_ts, t.snoozed_until)
                   END, t.id
               ) < ($3::timestamptz, $4::uuid))
             ORDER BY effective_ts DESC, t.updated_at DESC -- fall back to updated_at if effective_ts is the same
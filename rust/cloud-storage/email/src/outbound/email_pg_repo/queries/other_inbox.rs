@@ -55,19 +55,22 @@ pub(crate) async fn other_inbox_preview_cursor(
                 t.link_id,
                 t.inbox_visible,
                 t.is_read,
-                t.latest_non_spam_message_ts AS created_at,
-                t.latest_non_spam_message_ts AS updated_at,
+                -- a resurfaced thread sorts by when its snooze ended rather than its latest message
+                GREATEST(t.latest_non_spam_message_ts, t.snoozed_until) AS created_at,
+                GREATEST(t.latest_non_spam_message_ts, t.snoozed_until) AS updated_at,
                 uh.updated_at AS viewed_at,
                 CASE $5 -- sort_method_str
                     WHEN 'viewed_at' THEN COALESCE(uh."updated_at", '1970-01-01 00:00:00+00')
-                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, t.latest_non_spam_message_ts)
-                    ELSE t.latest_non_spam_message_ts
+                    WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, GREATEST(t.latest_non_spam_message_ts, t.snoozed_until))
+                    ELSE GREATEST(t.latest_non_spam_message_ts, t.snoozed_until)
                 END AS effective_ts
             FROM email_threads t
             LEFT JOIN email_user_history uh ON uh.thread_id = t.id AND uh.link_id = t.link_id
             WHERE
                 t.link_id = $1
               AND t.latest_non_spam_message_ts IS NOT NULL
+              -- snoozed threads resurface once their snooze time has passed
+              AND (t.snoozed_until IS NULL OR t.snoozed_until <= NOW())
               -- Inclusion Criteria: Must have a category label.
               AND EXISTS (
                   SELECT 1 FROM email_messages m JOIN email_message_labels ml ON m.id = ml.message_id JOIN email_labels l ON ml.label_id = l.id
@@ -83,8 +86,8 @@ pub(crate) async fn other_inbox_preview_cursor(
               AND (($3::timestamptz IS NULL) OR (
                   CASE $5 -- sort_method_str
                       WHEN 'viewed_at' THEN COALESCE(uh."updated_at", '1970-01-01 00:00:00+00')
-                      WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, t.latest_non_spam_message_ts)
-                      ELSE t.latest_non_spam_message_ts
+                      WHEN 'viewed_updated' THEN COALESCE(uh.updated_at, GREATEST(t.latest_non_spam_message_ts, t.snoozed_until))
+                      ELSE GREATEST(t.latest_non_spam_message_ts, t.snoozed_until)
                   END, t.id
               ) < ($3::timestamptz, $4::uuid))
             ORDER BY effective_ts DESC, t.updated_at DESC
@@ -91,7 +91,8 @@ pub(crate) async fn sent_preview_cursor(
                    m.is_draft
             FROM email_messages m
             WHERE m.thread_id = t.id
-              AND m.is_sent = TRUE -- This condition is specific to the "Sent" view
+              -- This condition is specific to the "Sent" view. Messages waiting in the outbox count as sent.
+              AND (m.is_sent = TRUE OR EXISTS (SELECT 1 FROM email_outbox o WHERE o.message_id = m.id))
               AND NOT EXISTS (
                 SELECT 1 FROM email_message_labels ml JOIN email_labels l ON ml.label_id = l.id
                 WHERE ml.message_id = m.id AND l.name = 'TRASH' AND l.link_id = t.link_id
@@ -623,3 +623,172 @@ async fn test_dynamic_query_pagination(pool: Pool<Postgres>) -> anyhow::Result<(
 
     Ok(())
 }
+
+#[sqlx::test(
+    migrator = "MACRO_DB_MIGRATIONS",
+    fixtures(path = "../../../fixtures", scripts("email_dynamic_query"))
+)]
+async fn test_dynamic_query_inbox_view_hides_snoozed_threads(
+    pool: Pool<Postgres>,
+) -> anyhow::Result<()> {
+    let link_id = Uuid::parse_str("aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa")?;
+    let snoozed_thread_id = Uuid::parse_str("20000001-0000-0000-0000-000000000001")?;
+    let view = PreviewView::StandardLabel(PreviewViewStandardLabel::Inbox);
+    let filter = Arc::new(Expr::Literal(EmailLiteral::Sender(Email::Partial(
+        "example.com".to_string(),
+    ))));
+
+    sqlx::query("UPDATE email_threads SET snoozed_until = NOW() + INTERVAL '1 day' WHERE id = $1")
+        .bind(snoozed_thread_id)
+        .execute(&pool)
+        .await?;
+
+    let query = Query::new(None, SimpleSortMethod::UpdatedAt, filter.clone());
+    let results = dynamic::dynamic_email_thread_cursor(&pool, &link_id, 50, &view, query).await?;
+
+    assert_eq!(results.len(), 3, "Snoozed thread should be hidden");
+    assert!(
+        !results.iter().any(|r| r.id == snoozed_thread_id),
+        "Should not include the snoozed thread"
+    );
+
+    // once the snooze time has passed the thread shows up again
+    sqlx::query(
+        "UPDATE email_threads SET snoozed_until = NOW() - INTERVAL '1 minute' WHERE id = $1",
+    )
+    .bind(snoozed_thread_id)
+    .execute(&pool)
+    .await?;
+
+    let query = Query::new(None, SimpleSortMethod::UpdatedAt, filter);
+    let results = dynamic::dynamic_email_thread_cursor(&pool, &link_id, 50, &view, query).await?;
+
+    assert_eq!(results.len(), 4, "Thread should resurface after its snooze");
+    assert!(results.iter().any(|r| r.id == snoozed_thread_id));
+
+    Ok(())
+}
+
+async fn snooze_thread(pool: &Pool<Postgres>, thread_id: Uuid, until: &str) -> anyhow::Result<()> {
+    sqlx::query(&format!(
+        "UPDATE email_threads SET snoozed_until = {until} WHERE id = $1"
+    ))
+    .bind(thread_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[sqlx::test(
+    migrator = "MACRO_DB_MIGRATIONS",
+    fixtures(path = "../../../fixtures", scripts("email_dynamic_query"))
+)]
+async fn test_new_inbox_hides_snoozed_threads_until_they_resurface(
+    pool: Pool<Postgres>,
+) -> anyhow::Result<()> {
+    let link_id = Uuid::parse_str("aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa")?;
+    // the oldest inbox thread, so resurfacing has to move it to the top
+    let snoozed_thread_id = Uuid::parse_str("20000007-0000-0000-0000-000000000007")?;
+    let query = Query::new(None, SimpleSortMethod::UpdatedAt, ());
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() + INTERVAL '1 day'").await?;
+    let results = queries::new_inbox::new_inbox_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 3, "Snoozed thread should be hidden");
+    assert!(!results.iter().any(|r| r.id == snoozed_thread_id));
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() - INTERVAL '1 minute'").await?;
+    let results = queries::new_inbox::new_inbox_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 4, "Thread should resurface after its snooze");
+    assert_eq!(
+        results[0].id, snoozed_thread_id,
+        "Resurfaced thread should sort by when its snooze ended"
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(
+    migrator = "MACRO_DB_MIGRATIONS",
+    fixtures(path = "../../../fixtures", scripts("email_dynamic_query"))
+)]
+async fn test_important_hides_snoozed_threads_until_they_resurface(
+    pool: Pool<Postgres>,
+) -> anyhow::Result<()> {
+    let link_id = Uuid::parse_str("aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa")?;
+    let newer_thread_id = Uuid::parse_str("20000001-0000-0000-0000-000000000001")?;
+    let snoozed_thread_id = Uuid::parse_str("20000005-0000-0000-0000-000000000005")?;
+    let query = Query::new(None, SimpleSortMethod::UpdatedAt, ());
+
+    // mark thread 1 important as well so there is something to sort against
+    sqlx::query(
+        "INSERT INTO email_message_labels (message_id, label_id) VALUES ('30000001-0000-0000-0000-000000000001', '10000002-0000-0000-0000-000000000002')",
+    )
+    .execute(&pool)
+    .await?;
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() + INTERVAL '1 day'").await?;
+    let results = queries::important::important_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 1, "Snoozed thread should be hidden");
+    assert_eq!(results[0].id, newer_thread_id);
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() - INTERVAL '1 minute'").await?;
+    let results = queries::important::important_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 2, "Thread should resurface after its snooze");
+    assert_eq!(
+        results[0].id, snoozed_thread_id,
+        "Resurfaced thread should sort by when its snooze ended"
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(
+    migrator = "MACRO_DB_MIGRATIONS",
+    fixtures(path = "../../../fixtures", scripts("email_dynamic_query"))
+)]
+async fn test_other_inbox_hides_snoozed_threads_until_they_resurface(
+    pool: Pool<Postgres>,
+) -> anyhow::Result<()> {
+    let link_id = Uuid::parse_str("aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa")?;
+    let newer_thread_id = Uuid::parse_str("20000001-0000-0000-0000-000000000001")?;
+    let snoozed_thread_id = Uuid::parse_str("20000007-0000-0000-0000-000000000007")?;
+    let query = Query::new(None, SimpleSortMethod::UpdatedAt, ());
+
+    // put threads 1 and 7 in the "Other" inbox
+    sqlx::query(
+        "INSERT INTO email_labels (id, link_id, provider_label_id, name, message_list_visibility, label_list_visibility, type, created_at)
+         VALUES ('10000008-0000-0000-0000-000000000008', 'aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa', 'CATEGORY_PROMOTIONS', 'CATEGORY_PROMOTIONS', 'Show', 'LabelShow', 'System', NOW())",
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        "INSERT INTO email_message_labels (message_id, label_id) VALUES
+         ('30000001-0000-0000-0000-000000000001', '10000008-0000-0000-0000-000000000008'),
+         ('30000007-0000-0000-0000-000000000007', '10000008-0000-0000-0000-000000000008')",
+    )
+    .execute(&pool)
+    .await?;
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() + INTERVAL '1 day'").await?;
+    let results =
+        queries::other_inbox::other_inbox_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 1, "Snoozed thread should be hidden");
+    assert_eq!(results[0].id, newer_thread_id);
+
+    snooze_thread(&pool, snoozed_thread_id, "NOW() - INTERVAL '1 minute'").await?;
+    let results =
+        queries::other_inbox::other_inbox_preview_cursor(&pool, &link_id, 50, &query).await?;
+
+    assert_eq!(results.len(), 2, "Thread should resurface after its snooze");
+    assert_eq!(
+        results[0].id, snoozed_thread_id,
+        "Resurfaced thread should sort by when its snooze ended"
+    );
+
+    Ok(())
+}
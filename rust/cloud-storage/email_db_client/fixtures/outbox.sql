@@ -0,0 +1,48 @@
+INSERT INTO email_messages (id,
+                            thread_id,
+                            link_id,
+                            provider_id,
+                            is_sent,
+                            from_contact_id,
+                            internal_date_ts,
+                            has_attachments,
+                            is_read,
+                            is_starred,
+                            is_draft,
+                            created_at,
+                            updated_at)
+VALUES ('20000000-0000-0000-0000-000000000001',
+        '10000000-0000-0000-0000-000000000001',
+        '00000000-0000-0000-0000-000000000001',
+        NULL,
+        FALSE,
+        NULL,
+        NOW(),
+        FALSE,
+        TRUE,
+        FALSE,
+        TRUE,
+        NOW(),
+        NOW()),
+       ('20000000-0000-0000-0000-000000000002',
+        '10000000-0000-0000-0000-000000000001',
+        '00000000-0000-0000-0000-000000000001',
+        NULL,
+        FALSE,
+        NULL,
+        NOW(),
+        FALSE,
+        TRUE,
+        FALSE,
+        TRUE,
+        NOW(),
+        NOW());
+
+-- the first message's undo window has passed, the second's hasn't
+INSERT INTO email_outbox (message_id, link_id, send_at)
+VALUES ('20000000-0000-0000-0000-000000000001',
+        '00000000-0000-0000-0000-000000000001',
+        NOW() - INTERVAL '1 minute'),
+       ('20000000-0000-0000-0000-000000000002',
+        '00000000-0000-0000-0000-000000000001',
+        NOW() + INTERVAL '1 hour');
@@ -384,5 +384,61 @@ pub async fn fetch_attachment_upload_metadata_by_id(
     Ok(row)
 }
 
+/// fetch the attachments of imported messages to upload to Macro. the user chose to import the
+/// messages, so only the mime type filters apply. attachments that were already uploaded are
+/// skipped, so importing the same archive again doesn't upload them twice.
+#[tracing::instrument(skip(db, message_provider_ids), err)]
+pub async fn fetch_attachments_for_import(
+    db: &Pool<Postgres>,
+    link_id: Uuid,
+    message_provider_ids: &[String],
+) -> anyhow::Result<Vec<AttachmentUploadMetadata>> {
+    if message_provider_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let query = format!(
+        r#"
+        SELECT
+            a.id AS attachment_db_id,
+            m.provider_id as email_provider_id,
+            a.provider_attachment_id as provider_attachment_id,
+            a.filename as filename,
+            a.mime_type as mime_type,
+            m.internal_date_ts as internal_date_ts
+        FROM email_attachments a
+        JOIN email_messages m ON a.message_id = m.id
+        LEFT JOIN document_email de ON de.email_attachment_id = a.id
+        WHERE m.link_id = $1
+            AND m.provider_id = ANY($2)
+            AND de.email_attachment_id IS NULL
+            -- attachment mime type filters injected below
+            {}
+        ORDER BY a.id
+        "#,
+        ATTACHMENT_MIME_TYPE_FILTERS
+    );
+
+    let rows = sqlx::query(&query)
+        .bind(link_id)
+        .bind(message_provider_ids)
+        .fetch_all(db)
+        .await?;
+
+    let attachments = rows
+        .into_iter()
+        .map(|row| AttachmentUploadMetadata {
+            attachment_db_id: row.get("attachment_db_id"),
+            email_provider_id: row.get("email_provider_id"),
+            provider_attachment_id: row.get("provider_attachment_id"),
+            filename: row.get("filename"),
+            mime_type: row.get("mime_type"),
+            internal_date_ts: row.get("internal_date_ts"),
+        })
+        .collect();
+
+    Ok(attachments)
+}
+
 #[cfg(test)]
 mod test;
@@ -1,5 +1,6 @@
 pub mod delete;
 pub mod get;
 pub mod normalize;
+pub mod people;
 pub mod upsert_message;
 pub mod upsert_sync;
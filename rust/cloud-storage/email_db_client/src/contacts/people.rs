@@ -0,0 +1,396 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use models_email::service::person::{LinkContact, Person};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+use std::collections::{HashMap, HashSet};
+
+/// Fetches every contact of a link, with the person it was merged into
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_link_contacts(pool: &PgPool, link_id: Uuid) -> anyhow::Result<Vec<LinkContact>> {
+    sqlx::query_as!(
+        LinkContact,
+        r#"
+        SELECT id, email_address, name, sfs_photo_url AS photo_url, person_id
+        FROM email_contacts
+        WHERE link_id = $1
+        ORDER BY email_address
+        "#,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch contacts for link_id {}", link_id))
+}
+
+struct PersonRow {
+    id: Uuid,
+    link_id: Uuid,
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Fetches the people of a link with their contacts, by name
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_people(pool: &PgPool, link_id: Uuid) -> anyhow::Result<Vec<Person>> {
+    let rows = sqlx::query_as!(
+        PersonRow,
+        r#"
+        SELECT id, link_id, name, created_at, updated_at
+        FROM email_contact_people
+        WHERE link_id = $1
+        ORDER BY LOWER(name), created_at
+        "#,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch people for link_id {}", link_id))?;
+
+    let contacts = sqlx::query_as!(
+        LinkContact,
+        r#"
+        SELECT id, email_address, name, sfs_photo_url AS photo_url, person_id
+        FROM email_contacts
+        WHERE link_id = $1 AND person_id IS NOT NULL
+        ORDER BY email_address
+        "#,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch merged contacts for link_id {}", link_id))?;
+
+    Ok(assemble_people(rows, contacts))
+}
+
+/// Fetches a person of a link with its contacts.
+/// Returns None if the person doesn't exist or belongs to another link.
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_person(
+    pool: &PgPool,
+    link_id: Uuid,
+    person_id: Uuid,
+) -> anyhow::Result<Option<Person>> {
+    let Some(row) = sqlx::query_as!(
+        PersonRow,
+        r#"
+        SELECT id, link_id, name, created_at, updated_at
+        FROM email_contact_people
+        WHERE id = $1 AND link_id = $2
+        "#,
+        person_id,
+        link_id
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to fetch person {}", person_id))?
+    else {
+        return Ok(None);
+    };
+
+    let contacts = sqlx::query_as!(
+        LinkContact,
+        r#"
+        SELECT id, email_address, name, sfs_photo_url AS photo_url, person_id
+        FROM email_contacts
+        WHERE person_id = $1
+        ORDER BY email_address
+        "#,
+        person_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch contacts of person {}", person_id))?;
+
+    Ok(assemble_people(vec![row], contacts).pop())
+}
+
+fn assemble_people(rows: Vec<PersonRow>, contacts: Vec<LinkContact>) -> Vec<Person> {
+    let mut contacts_by_person: HashMap<Uuid, Vec<LinkContact>> = HashMap::new();
+    for contact in contacts {
+        if let Some(person_id) = contact.person_id {
+            contacts_by_person
+                .entry(person_id)
+                .or_default()
+                .push(contact);
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| Person {
+            contacts: contacts_by_person.remove(&row.id).unwrap_or_default(),
+            id: row.id,
+            link_id: row.link_id,
+            name: row.name,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+        .collect()
+}
+
+/// Fetches the ids of the contacts of a link by their email address
+#[tracing::instrument(skip(pool, email_addresses), level = "info", err)]
+pub async fn fetch_contact_ids_by_emails(
+    pool: &PgPool,
+    link_id: Uuid,
+    email_addresses: &[String],
+) -> anyhow::Result<HashMap<String, Uuid>> {
+    if email_addresses.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, email_address
+        FROM email_contacts
+        WHERE link_id = $1 AND email_address = ANY($2)
+        "#,
+        link_id,
+        email_addresses
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch contact ids for link_id {}", link_id))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.email_address, row.id))
+        .collect())
+}
+
+/// Merges contacts of a link into one person. Contacts that already belong to a person bring the
+/// rest of its contacts along, and the people are merged into the oldest one. A new person is
+/// named after the longest name of its contacts unless a name is given.
+/// Returns the id of the person, or None if any of the contacts doesn't belong to the link.
+#[tracing::instrument(skip(pool), err)]
+pub async fn merge_contacts(
+    pool: &PgPool,
+    link_id: Uuid,
+    contact_ids: &[Uuid],
+    name: Option<&str>,
+) -> anyhow::Result<Option<Uuid>> {
+    let contact_ids: Vec<Uuid> = contact_ids
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut transaction = pool.begin().await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, person_id
+        FROM email_contacts
+        WHERE link_id = $1 AND id = ANY($2)
+        FOR UPDATE
+        "#,
+        link_id,
+        &contact_ids
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to lock contacts to merge")?;
+
+    if rows.len() != contact_ids.len() {
+        return Ok(None);
+    }
+
+    // uuid v7 ids sort by creation
+    let mut person_ids: Vec<Uuid> = rows.iter().filter_map(|row| row.person_id).collect();
+    person_ids.sort();
+    person_ids.dedup();
+
+    let person_id = match person_ids.first() {
+        Some(person_id) => *person_id,
+        None => sqlx::query_scalar!(
+            r#"
+            INSERT INTO email_contact_people (id, link_id, name)
+            VALUES (
+                $1,
+                $2,
+                (
+                    SELECT name FROM email_contacts
+                    WHERE id = ANY($3) AND name IS NOT NULL
+                    ORDER BY LENGTH(name) DESC, name
+                    LIMIT 1
+                )
+            )
+            RETURNING id
+            "#,
+            macro_uuid::generate_uuid_v7(),
+            link_id,
+            &contact_ids
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to insert person")?,
+    };
+    let merged_person_ids = person_ids.get(1..).unwrap_or_default();
+
+    sqlx::query!(
+        r#"
+        UPDATE email_contacts
+        SET person_id = $1
+        WHERE link_id = $2 AND (id = ANY($3) OR person_id = ANY($4))
+        "#,
+        person_id,
+        link_id,
+        &contact_ids,
+        merged_person_ids
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to merge contacts")?;
+
+    if !merged_person_ids.is_empty() {
+        sqlx::query!(
+            r#"
+            DELETE FROM email_contact_people
+            WHERE id = ANY($1)
+            "#,
+            merged_person_ids
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to delete merged people")?;
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE email_contact_people
+        SET name = COALESCE($2, name), updated_at = NOW()
+        WHERE id = $1
+        "#,
+        person_id,
+        name
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to update person")?;
+
+    transaction.commit().await?;
+
+    Ok(Some(person_id))
+}
+
+/// Splits contacts off a person of a link. A person left with fewer than two contacts is removed,
+/// leaving its last contact on its own.
+/// Returns false if the person doesn't exist or belongs to another link.
+#[tracing::instrument(skip(pool), err)]
+pub async fn split_contacts(
+    pool: &PgPool,
+    link_id: Uuid,
+    person_id: Uuid,
+    contact_ids: &[Uuid],
+) -> anyhow::Result<bool> {
+    let mut transaction = pool.begin().await?;
+
+    let exists = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM email_contact_people WHERE id = $1 AND link_id = $2
+        ) AS "exists!"
+        "#,
+        person_id,
+        link_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .with_context(|| format!("Failed to fetch person {}", person_id))?;
+
+    if !exists {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE email_contacts
+        SET person_id = NULL
+        WHERE person_id = $1 AND id = ANY($2)
+        "#,
+        person_id,
+        contact_ids
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to split contacts")?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM email_contact_people p
+        WHERE p.id = $1
+          AND (SELECT COUNT(*) FROM email_contacts c WHERE c.person_id = p.id) < 2
+        "#,
+        person_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to remove person")?;
+
+    transaction.commit().await?;
+
+    Ok(true)
+}
+
+/// Fetches the pairs of contacts of a link that were dismissed as duplicates
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_dismissed_duplicates(
+    pool: &PgPool,
+    link_id: Uuid,
+) -> anyhow::Result<HashSet<(Uuid, Uuid)>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT contact_id_a, contact_id_b
+        FROM email_contact_duplicate_dismissals
+        WHERE link_id = $1
+        "#,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch dismissed duplicates for link_id {}",
+            link_id
+        )
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.contact_id_a, row.contact_id_b))
+        .collect())
+}
+
+/// Dismisses a pair of contacts of a link as duplicates, so they are no longer suggested.
+/// Returns false if either contact doesn't belong to the link.
+#[tracing::instrument(skip(pool), err)]
+pub async fn dismiss_duplicate(
+    pool: &PgPool,
+    link_id: Uuid,
+    contact_ids: (Uuid, Uuid),
+) -> anyhow::Result<bool> {
+    let (contact_id_a, contact_id_b) =
+        models_email::service::person::contact_pair(contact_ids.0, contact_ids.1);
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO email_contact_duplicate_dismissals (link_id, contact_id_a, contact_id_b)
+        SELECT $1, $2, $3
+        WHERE (
+            SELECT COUNT(*) FROM email_contacts WHERE link_id = $1 AND id IN ($2, $3)
+        ) = 2
+        ON CONFLICT (contact_id_a, contact_id_b) DO UPDATE SET created_at = NOW()
+        "#,
+        link_id,
+        contact_id_a,
+        contact_id_b
+    )
+    .execute(pool)
+    .await
+    .context("Failed to dismiss duplicate")?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,160 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use models_email::service::follow_up::{FollowUpMessage, FollowUpReminder};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+/// Sets the follow up reminder of a thread, replacing any existing one.
+/// Returns None if the thread doesn't exist or belongs to another link.
+#[tracing::instrument(skip(pool), err)]
+pub async fn upsert_follow_up_reminder(
+    pool: &PgPool,
+    link_id: Uuid,
+    thread_id: Uuid,
+    remind_at: DateTime<Utc>,
+) -> anyhow::Result<Option<FollowUpReminder>> {
+    sqlx::query_as!(
+        FollowUpReminder,
+        r#"
+        INSERT INTO email_follow_up_reminders (id, link_id, thread_id, remind_at)
+        SELECT $1, t.link_id, t.id, $4
+        FROM email_threads t
+        WHERE t.id = $3 AND t.link_id = $2
+        ON CONFLICT (thread_id) DO UPDATE SET
+            remind_at = EXCLUDED.remind_at,
+            fired = FALSE,
+            updated_at = NOW()
+        RETURNING id, link_id, thread_id, remind_at, fired, created_at, updated_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        link_id,
+        thread_id,
+        remind_at,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to upsert follow up reminder for thread {} with link_id {}",
+            thread_id, link_id
+        )
+    })
+}
+
+/// Deletes the follow up reminder of a thread. Returns false if the thread had none.
+#[tracing::instrument(skip(pool), err)]
+pub async fn delete_follow_up_reminder(
+    pool: &PgPool,
+    link_id: Uuid,
+    thread_id: Uuid,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM email_follow_up_reminders
+        WHERE thread_id = $1 AND link_id = $2
+        "#,
+        thread_id,
+        link_id,
+    )
+    .execute(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to delete follow up reminder for thread {} with link_id {}",
+            thread_id, link_id
+        )
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Marks a due reminder as fired. Returns None if the reminder doesn't exist, isn't due yet, or
+/// was already fired, so a reminder delivered more than once is only acted on a single time.
+#[tracing::instrument(skip(pool), err)]
+pub async fn claim_follow_up_reminder(
+    pool: &PgPool,
+    link_id: Uuid,
+    reminder_id: Uuid,
+) -> anyhow::Result<Option<FollowUpReminder>> {
+    sqlx::query_as!(
+        FollowUpReminder,
+        r#"
+        UPDATE email_follow_up_reminders
+        SET
+            fired = TRUE,
+            updated_at = NOW()
+        WHERE id = $1
+          AND link_id = $2
+          AND fired = FALSE
+          AND remind_at <= NOW()
+        RETURNING id, link_id, thread_id, remind_at, fired, created_at, updated_at
+        "#,
+        reminder_id,
+        link_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to claim follow up reminder {}", reminder_id))
+}
+
+/// Whether a message was received in a thread after the last message that was sent in it
+#[tracing::instrument(skip(pool), err)]
+pub async fn thread_has_reply(pool: &PgPool, thread_id: Uuid) -> anyhow::Result<bool> {
+    let has_reply = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM email_messages m
+            WHERE m.thread_id = $1
+              AND m.is_sent = FALSE
+              AND m.is_draft = FALSE
+              AND m.internal_date_ts > COALESCE(
+                  (
+                      SELECT MAX(s.internal_date_ts)
+                      FROM email_messages s
+                      WHERE s.thread_id = $1 AND s.is_sent = TRUE
+                  ),
+                  '-infinity'::timestamptz
+              )
+        ) AS "has_reply!"
+        "#,
+        thread_id,
+    )
+    .fetch_one(pool)
+    .await
+    .with_context(|| format!("Failed to check for replies in thread {}", thread_id))?;
+
+    Ok(has_reply)
+}
+
+/// Fetches the most recent message the user sent in a thread
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_latest_sent_message(
+    pool: &PgPool,
+    thread_id: Uuid,
+) -> anyhow::Result<Option<FollowUpMessage>> {
+    sqlx::query_as!(
+        FollowUpMessage,
+        r#"
+        SELECT
+            m.id AS message_id,
+            m.subject,
+            m.snippet
+        FROM email_messages m
+        WHERE m.thread_id = $1
+          AND m.is_sent = TRUE
+          AND m.is_draft = FALSE
+        ORDER BY m.internal_date_ts DESC NULLS LAST
+        LIMIT 1
+        "#,
+        thread_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch latest sent message of thread {}",
+            thread_id
+        )
+    })
+}
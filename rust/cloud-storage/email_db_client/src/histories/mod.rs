@@ -43,6 +43,27 @@ pub async fn fetch_history_id_for_link(
     Ok(result.map(|r| r.history_id))
 }
 
+/// Fetches the stored sync cursor of a link. For Gmail links this is the history id.
+#[tracing::instrument(skip(pool), level = "info")]
+pub async fn fetch_history_id_by_link_id(
+    pool: &PgPool,
+    link_id: Uuid,
+) -> anyhow::Result<Option<String>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT history_id
+        FROM email_gmail_histories
+        WHERE link_id = $1
+        "#,
+        link_id
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to fetch history_id for link_id {}", link_id))?;
+
+    Ok(result.map(|r| r.history_id))
+}
+
 #[tracing::instrument(skip(pool), level = "info")]
 pub async fn upsert_gmail_history(
     pool: &PgPool,
@@ -0,0 +1,83 @@
+use anyhow::Context;
+use models_email::{db, service};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+/// Stores the IMAP/SMTP settings of a link
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn insert_imap_account(
+    pool: &PgPool,
+    account: &service::imap_account::ImapAccount,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO email_imap_accounts (
+            link_id,
+            imap_host,
+            imap_port,
+            imap_security,
+            smtp_host,
+            smtp_port,
+            smtp_security,
+            username,
+            password_secret_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        account.link_id,
+        account.imap_host,
+        i32::from(account.imap_port),
+        db::imap_account::ConnectionSecurity::from(account.imap_security) as _,
+        account.smtp_host,
+        i32::from(account.smtp_port),
+        db::imap_account::ConnectionSecurity::from(account.smtp_security) as _,
+        account.username,
+        account.password_secret_id,
+    )
+    .execute(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to insert imap account for link_id {}",
+            account.link_id
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Fetches the IMAP/SMTP settings of a link. Returns None for links that are not backed by IMAP.
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_imap_account(
+    pool: &PgPool,
+    link_id: Uuid,
+) -> anyhow::Result<Option<service::imap_account::ImapAccount>> {
+    let result = sqlx::query_as!(
+        db::imap_account::ImapAccount,
+        r#"
+        SELECT
+            link_id,
+            imap_host,
+            imap_port,
+            imap_security as "imap_security: _",
+            smtp_host,
+            smtp_port,
+            smtp_security as "smtp_security: _",
+            username,
+            password_secret_id,
+            created_at,
+            updated_at
+        FROM email_imap_accounts
+        WHERE link_id = $1
+        "#,
+        link_id
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to fetch imap account for link_id {}", link_id))?;
+
+    result
+        .map(service::imap_account::ImapAccount::try_from)
+        .transpose()
+        .context("Invalid port in imap account")
+}
@@ -1,13 +1,18 @@
 pub mod attachments;
 pub mod backfill;
 pub mod contacts;
+pub mod follow_ups;
 pub mod histories;
+pub mod imap_accounts;
 pub mod labels;
 pub mod links;
 pub mod messages;
 pub mod parse;
+pub mod rules;
 pub mod settings;
 pub mod sfs_mappings;
+pub mod subscriptions;
 pub mod sync_tokens;
+pub mod templates;
 pub mod threads;
 pub mod user_history;
@@ -132,3 +132,36 @@ pub async fn fetch_link_by_id(pool: &PgPool, link_id: Uuid) -> anyhow::Result<Op
 
     Ok(db_link.map(link::Link::try_from).transpose()?)
 }
+
+/// Fetches every link of the given provider that has sync enabled.
+#[tracing::instrument(skip(pool), level = "info")]
+pub async fn fetch_active_links_by_provider(
+    pool: &PgPool,
+    provider: service::link::UserProvider,
+) -> anyhow::Result<Vec<link::Link>> {
+    let db_links = sqlx::query_as!(
+        DbLink,
+        r#"
+        SELECT id, macro_id, fusionauth_user_id, email_address, provider as "provider: _",
+               is_sync_active, created_at, updated_at
+        FROM email_links
+        WHERE provider = $1 AND is_sync_active = true
+        "#,
+        DbUserProvider::mirror(provider) as _
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch active email_links for provider {}",
+            provider.as_str()
+        )
+    })?;
+
+    let service_links: Result<Vec<_>, _> = db_links
+        .into_iter()
+        .map(service::link::Link::try_from)
+        .collect();
+
+    Ok(service_links?)
+}
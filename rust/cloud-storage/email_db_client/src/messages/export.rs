@@ -0,0 +1,142 @@
+use crate::parse::db_to_service::map_db_attachment_to_service;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use models_email::db;
+use models_email::service::message::ExportMessage;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+use std::collections::HashMap;
+
+struct ExportMessageRow {
+    id: Uuid,
+    thread_id: Uuid,
+    provider_id: Option<String>,
+    subject: Option<String>,
+    from_email: Option<String>,
+    internal_date_ts: Option<DateTime<Utc>>,
+    body_text: Option<String>,
+    body_html_sanitized: Option<String>,
+    headers_jsonb: Option<serde_json::Value>,
+}
+
+/// Fetches the messages of a thread of the link for export, oldest first. Drafts are left out.
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_thread_export_messages(
+    pool: &PgPool,
+    link_id: Uuid,
+    thread_id: Uuid,
+) -> anyhow::Result<Vec<ExportMessage>> {
+    let rows = sqlx::query_as!(
+        ExportMessageRow,
+        r#"
+        SELECT
+            m.id,
+            m.thread_id,
+            m.provider_id,
+            m.subject,
+            c.email_address as "from_email?",
+            m.internal_date_ts,
+            m.body_text,
+            m.body_html_sanitized,
+            m.headers_jsonb
+        FROM email_messages m
+        LEFT JOIN email_contacts c ON c.id = m.from_contact_id
+        WHERE m.thread_id = $1 AND m.link_id = $2 AND m.is_draft = FALSE
+        ORDER BY m.internal_date_ts ASC
+        "#,
+        thread_id,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch export messages for thread {}", thread_id))?;
+
+    with_attachments(pool, rows).await
+}
+
+/// Fetches a page of the messages of the link with a label for export, most recent first.
+/// Drafts are left out.
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_label_export_messages(
+    pool: &PgPool,
+    link_id: Uuid,
+    label_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<ExportMessage>> {
+    let rows = sqlx::query_as!(
+        ExportMessageRow,
+        r#"
+        SELECT
+            m.id,
+            m.thread_id,
+            m.provider_id,
+            m.subject,
+            c.email_address as "from_email?",
+            m.internal_date_ts,
+            m.body_text,
+            m.body_html_sanitized,
+            m.headers_jsonb
+        FROM email_messages m
+        JOIN email_message_labels ml ON ml.message_id = m.id
+        LEFT JOIN email_contacts c ON c.id = m.from_contact_id
+        WHERE ml.label_id = $1 AND m.link_id = $2 AND m.is_draft = FALSE
+        ORDER BY m.internal_date_ts DESC NULLS LAST, m.id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        label_id,
+        link_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch export messages for label {}", label_id))?;
+
+    with_attachments(pool, rows).await
+}
+
+async fn with_attachments(
+    pool: &PgPool,
+    rows: Vec<ExportMessageRow>,
+) -> anyhow::Result<Vec<ExportMessage>> {
+    let message_ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+
+    let db_attachments = sqlx::query_as!(
+        db::attachment::Attachment,
+        r#"
+        SELECT id, message_id, provider_attachment_id, filename, mime_type, size_bytes, content_id, created_at
+        FROM email_attachments
+        WHERE message_id = ANY($1)
+        ORDER BY filename NULLS LAST
+        "#,
+        &message_ids
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to fetch attachments of export messages")?;
+
+    let mut attachments = HashMap::new();
+    for attachment in db_attachments {
+        attachments
+            .entry(attachment.message_id)
+            .or_insert_with(Vec::new)
+            .push(map_db_attachment_to_service(attachment));
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ExportMessage {
+            attachments: attachments.remove(&row.id).unwrap_or_default(),
+            db_id: row.id,
+            thread_db_id: row.thread_id,
+            provider_id: row.provider_id,
+            subject: row.subject,
+            from_email: row.from_email,
+            internal_date_ts: row.internal_date_ts,
+            body_text: row.body_text,
+            body_html_sanitized: row.body_html_sanitized,
+            headers_json: row.headers_jsonb,
+        })
+        .collect())
+}
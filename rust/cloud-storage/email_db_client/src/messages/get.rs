@@ -349,6 +349,72 @@ where
     Ok(message_id)
 }
 
+/// Returns the global ids that already belong to a message of the link
+#[tracing::instrument(skip(pool, global_ids), level = "debug")]
+pub async fn find_existing_global_ids(
+    pool: &PgPool,
+    link_id: Uuid,
+    global_ids: &[String],
+) -> anyhow::Result<std::collections::HashSet<String>> {
+    if global_ids.is_empty() {
+        return Ok(std::collections::HashSet::new());
+    }
+
+    let existing = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT global_id as "global_id!"
+        FROM email_messages
+        WHERE link_id = $1 AND global_id = ANY($2)
+        "#,
+        link_id,
+        global_ids
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to check existing global ids for link_id {}",
+            link_id
+        )
+    })?;
+
+    Ok(existing.into_iter().collect())
+}
+
+/// Returns the thread of the oldest message of the link with one of the global ids, if any
+#[tracing::instrument(skip(pool, global_ids), level = "debug")]
+pub async fn get_thread_id_by_global_ids(
+    pool: &PgPool,
+    link_id: Uuid,
+    global_ids: &[String],
+) -> anyhow::Result<Option<Uuid>> {
+    if global_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let thread_id = sqlx::query_scalar!(
+        r#"
+        SELECT thread_id
+        FROM email_messages
+        WHERE link_id = $1 AND global_id = ANY($2)
+        ORDER BY internal_date_ts ASC NULLS LAST
+        LIMIT 1
+        "#,
+        link_id,
+        global_ids
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch thread by global ids for link_id {}",
+            link_id
+        )
+    })?;
+
+    Ok(thread_id)
+}
+
 /// fetch draft message and sender contact info from database for sending
 #[tracing::instrument(skip(pool), level = "info")]
 pub async fn get_message_to_send(
@@ -2,7 +2,7 @@ use crate::attachments::{marco, provider};
 use crate::messages::replying_to_id;
 use crate::messages::scheduled::{delete_scheduled_message, upsert_scheduled_message};
 use crate::parse::service_to_db::{addresses_from_message, map_message_to_send_to_db};
-use crate::{contacts, labels, parse, threads};
+use crate::{contacts, labels, parse, subscriptions, threads};
 use anyhow::Context;
 use chrono::Utc;
 use models_email::email::db::address::UpsertedRecipients;
@@ -62,6 +62,15 @@ pub async fn insert_message_with_tx(
             .context("Failed to insert attachments")?;
     }
 
+    if let Some(list_unsubscribe) = &message.list_unsubscribe
+        && !message.is_sent
+        && !message.is_draft
+    {
+        subscriptions::upsert_list_message(tx, link_id, message_db_id, list_unsubscribe)
+            .await
+            .context("Failed to insert list message")?;
+    }
+
     if update_thread_metadata {
         threads::update::update_thread_metadata(tx, thread_db_id, link_id)
             .await
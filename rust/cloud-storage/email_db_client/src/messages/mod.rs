@@ -1,9 +1,11 @@
 pub mod delete;
+pub mod export;
 pub mod get;
 pub mod get_parsed;
 pub mod get_parsed_search;
 pub mod get_simple_messages;
 pub mod insert;
+pub mod outbox;
 pub mod replying_to_id;
 pub mod scheduled;
 pub mod update;
@@ -0,0 +1,297 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use models_email::service;
+use models_email::service::message::OutboxMessage;
+use sqlx::types::Uuid;
+
+/// Puts a message in the outbox, or moves its send time if it is already there.
+/// Returns false if the message was already picked up for sending and can't be changed anymore.
+#[tracing::instrument(skip(tx))]
+pub async fn upsert_outbox_message(
+    tx: &mut sqlx::PgConnection,
+    link_id: Uuid,
+    message_id: Uuid,
+    send_at: DateTime<Utc>,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO email_outbox (link_id, message_id, send_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (message_id) DO UPDATE SET
+            send_at = EXCLUDED.send_at,
+            updated_at = NOW()
+        WHERE email_outbox.dispatched = FALSE
+        "#,
+        link_id,
+        message_id,
+        send_at,
+    )
+    .execute(&mut *tx)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to upsert outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Retrieves a message in the outbox. Returns None if the message isn't in the outbox.
+#[tracing::instrument(skip(db))]
+pub async fn get_outbox_message(
+    db: &sqlx::PgPool,
+    link_id: Uuid,
+    message_id: Uuid,
+) -> anyhow::Result<Option<OutboxMessage>> {
+    sqlx::query_as!(
+        OutboxMessage,
+        r#"
+        SELECT link_id, message_id, send_at, dispatched
+        FROM email_outbox
+        WHERE link_id = $1 AND message_id = $2
+        "#,
+        link_id,
+        message_id,
+    )
+    .fetch_optional(db)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to retrieve outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })
+}
+
+/// How long a claimed outbox message is left to the worker that claimed it. Longer than a send
+/// can take, so it only runs out if the worker stopped before it could finish.
+pub const OUTBOX_CLAIM_LEASE_SECONDS: i32 = 15 * 60;
+
+/// Marks a due outbox message as dispatched so it can no longer be cancelled, claims it for
+/// sending and returns it in the same shape as a scheduled message. Returns None if the message
+/// isn't in the outbox, isn't due yet or is already claimed, so a message is only sent once even
+/// when the queue delivers it more than once. A claim is released when the send fails, or runs
+/// out after [OUTBOX_CLAIM_LEASE_SECONDS] if the worker stopped before it finished.
+#[tracing::instrument(skip(db))]
+pub async fn claim_outbox_message(
+    db: &sqlx::PgPool,
+    link_id: Uuid,
+    message_id: Uuid,
+) -> anyhow::Result<Option<service::message::ScheduledMessage>> {
+    let record = sqlx::query!(
+        r#"
+        UPDATE email_outbox
+        SET
+            dispatched = TRUE,
+            claimed_at = NOW(),
+            updated_at = NOW()
+        WHERE link_id = $1
+            AND message_id = $2
+            AND send_at <= NOW()
+            AND (claimed_at IS NULL OR claimed_at < NOW() - $3::INT * INTERVAL '1 second')
+        RETURNING link_id, message_id, send_at
+        "#,
+        link_id,
+        message_id,
+        OUTBOX_CLAIM_LEASE_SECONDS,
+    )
+    .fetch_optional(db)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to claim outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })?;
+
+    Ok(record.map(|r| service::message::ScheduledMessage {
+        link_id: r.link_id,
+        message_id: r.message_id,
+        send_time: r.send_at,
+        sent: false,
+    }))
+}
+
+/// Releases the claim on an outbox message whose send failed, so it can be claimed again right
+/// away. The message stays dispatched, so it still can't be cancelled.
+#[tracing::instrument(skip(db))]
+pub async fn release_outbox_message(
+    db: &sqlx::PgPool,
+    link_id: Uuid,
+    message_id: Uuid,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE email_outbox
+        SET
+            claimed_at = NULL,
+            updated_at = NOW()
+        WHERE link_id = $1 AND message_id = $2
+        "#,
+        link_id,
+        message_id,
+    )
+    .execute(db)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to release outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Takes a message out of the outbox before it is sent, leaving it as a draft.
+/// Returns false if the message isn't in the outbox or was already picked up for sending.
+#[tracing::instrument(skip(tx))]
+pub async fn cancel_outbox_message(
+    tx: &mut sqlx::PgConnection,
+    link_id: Uuid,
+    message_id: Uuid,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM email_outbox
+        WHERE link_id = $1 AND message_id = $2 AND dispatched = FALSE
+        "#,
+        link_id,
+        message_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to cancel outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes a message from the outbox once it has been sent
+#[tracing::instrument(skip(executor))]
+pub async fn delete_outbox_message<'e, E>(
+    executor: E,
+    link_id: Uuid,
+    message_id: Uuid,
+) -> anyhow::Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+{
+    sqlx::query!(
+        r#"
+        DELETE FROM email_outbox
+        WHERE link_id = $1 AND message_id = $2
+        "#,
+        link_id,
+        message_id,
+    )
+    .execute(executor)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to delete outbox message with link_id {} and message_id {}",
+            link_id, message_id
+        )
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_db_migrator::MACRO_DB_MIGRATIONS;
+    use sqlx::types::uuid::uuid;
+    use sqlx::{Pool, Postgres};
+
+    const LINK_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000001");
+    const DUE_MESSAGE_ID: Uuid = uuid!("20000000-0000-0000-0000-000000000001");
+    const PENDING_MESSAGE_ID: Uuid = uuid!("20000000-0000-0000-0000-000000000002");
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("links", "threads", "outbox"))
+    )]
+    async fn test_claim_outbox_message_once(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        let claimed = claim_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID).await?;
+        assert_eq!(claimed.map(|m| m.message_id), Some(DUE_MESSAGE_ID));
+
+        // the queue delivering the message again while it is being sent doesn't send it twice
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID)
+                .await?
+                .is_none()
+        );
+
+        // it can't be cancelled once claimed
+        let mut conn = pool.acquire().await?;
+        assert!(!cancel_outbox_message(&mut conn, LINK_ID, DUE_MESSAGE_ID).await?);
+
+        // a failed send releases it to be retried
+        release_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID).await?;
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID)
+                .await?
+                .is_some()
+        );
+
+        // a claim whose worker stopped runs out
+        sqlx::query("UPDATE email_outbox SET claimed_at = NOW() - INTERVAL '1 day'")
+            .execute(&pool)
+            .await?;
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID)
+                .await?
+                .is_some()
+        );
+
+        // once sent it is gone for good
+        delete_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID).await?;
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, DUE_MESSAGE_ID)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(
+        migrator = "MACRO_DB_MIGRATIONS",
+        fixtures(path = "../../fixtures", scripts("links", "threads", "outbox"))
+    )]
+    async fn test_cancel_outbox_message_before_send_at(pool: Pool<Postgres>) -> anyhow::Result<()> {
+        // the undo window hasn't passed yet
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, PENDING_MESSAGE_ID)
+                .await?
+                .is_none()
+        );
+
+        let mut conn = pool.acquire().await?;
+        assert!(cancel_outbox_message(&mut conn, LINK_ID, PENDING_MESSAGE_ID).await?);
+        assert!(
+            get_outbox_message(&pool, LINK_ID, PENDING_MESSAGE_ID)
+                .await?
+                .is_none()
+        );
+
+        // nothing is left to send once the window passes
+        sqlx::query("UPDATE email_outbox SET send_at = NOW() - INTERVAL '1 minute'")
+            .execute(&pool)
+            .await?;
+        assert!(
+            claim_outbox_message(&pool, LINK_ID, PENDING_MESSAGE_ID)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
@@ -133,8 +133,8 @@ pub async fn get_scheduled_message(
     }))
 }
 
-/// Retrieves scheduled messages for drafts that have not been sent yet. used for populating
-/// messages in get thread by id endpoint
+/// Retrieves scheduled messages for drafts that have not been sent yet, including messages
+/// waiting in the outbox. used for populating messages in get thread by id endpoint
 #[tracing::instrument(skip(db))]
 pub async fn get_scheduled_message_no_auth(
     db: &sqlx::PgPool,
@@ -142,9 +142,18 @@ pub async fn get_scheduled_message_no_auth(
 ) -> anyhow::Result<Option<db::message::ScheduledMessage>> {
     let record = sqlx::query!(
         r#"
-        SELECT link_id, message_id, send_time, sent
+        SELECT
+            link_id AS "link_id!",
+            message_id AS "message_id!",
+            send_time AS "send_time!",
+            sent AS "sent!"
         FROM email_scheduled_messages
         WHERE message_id = $1 and sent = false
+        UNION ALL
+        SELECT link_id, message_id, send_at, FALSE
+        FROM email_outbox
+        WHERE message_id = $1
+        LIMIT 1
         "#,
         message_id,
     )
@@ -166,6 +166,7 @@ pub fn map_db_message_to_message_to_send(
         attachments_macro: None,
         headers_json: db_message.headers_jsonb,
         send_time: None,
+        auto_forwarded: false,
     }
 }
 
@@ -215,6 +216,7 @@ pub fn map_attachmentless_db_message_to_service(
         attachments: Vec::new(),
         attachments_macro: Vec::new(),
         headers_json: db_message.headers_jsonb,
+        list_unsubscribe: None,
         created_at: db_message.created_at,
         updated_at: db_message.updated_at,
     }
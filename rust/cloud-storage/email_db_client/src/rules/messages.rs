@@ -0,0 +1,139 @@
+use anyhow::Context;
+use models_email::service::rule::RuleMessage;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+struct RuleMessageRow {
+    id: Uuid,
+    thread_id: Uuid,
+    provider_id: Option<String>,
+    from_email: Option<String>,
+    recipient_emails: Vec<String>,
+    subject: Option<String>,
+    body_text: Option<String>,
+    has_attachments: bool,
+    label_ids: Vec<Uuid>,
+    is_auto_forwarded: bool,
+}
+
+impl From<RuleMessageRow> for RuleMessage {
+    fn from(row: RuleMessageRow) -> Self {
+        RuleMessage {
+            db_id: row.id,
+            thread_db_id: row.thread_id,
+            provider_id: row.provider_id,
+            from_email: row.from_email,
+            recipient_emails: row.recipient_emails,
+            subject: row.subject,
+            body_text: row.body_text,
+            has_attachments: row.has_attachments,
+            label_ids: row.label_ids,
+            is_auto_forwarded: row.is_auto_forwarded,
+        }
+    }
+}
+
+/// Fetches the received messages with the given ids in the shape rules are evaluated against.
+/// Sent messages and drafts are never returned.
+#[tracing::instrument(skip(pool, message_ids), fields(message_count = message_ids.len()), err)]
+pub async fn fetch_rule_messages_by_ids(
+    pool: &PgPool,
+    link_id: Uuid,
+    message_ids: &[Uuid],
+) -> anyhow::Result<Vec<RuleMessage>> {
+    if message_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query_as!(
+        RuleMessageRow,
+        r#"
+        SELECT
+            m.id,
+            m.thread_id,
+            m.provider_id,
+            fc.email_address as "from_email?",
+            ARRAY(
+                SELECT c.email_address::TEXT
+                FROM email_message_recipients r
+                JOIN email_contacts c ON c.id = r.contact_id
+                WHERE r.message_id = m.id AND r.recipient_type IN ('TO', 'CC')
+            ) as "recipient_emails!",
+            m.subject,
+            m.body_text,
+            m.has_attachments,
+            ARRAY(
+                SELECT ml.label_id FROM email_message_labels ml WHERE ml.message_id = m.id
+            ) as "label_ids!",
+            COALESCE(jsonb_path_exists(
+                m.headers_jsonb,
+                '$[*] ? (@.name like_regex "^x-macro-forwarded$" flag "i")'
+            ), FALSE) as "is_auto_forwarded!"
+        FROM email_messages m
+        LEFT JOIN email_contacts fc ON fc.id = m.from_contact_id
+        WHERE m.link_id = $1
+          AND m.id = ANY($2)
+          AND m.is_sent = FALSE
+          AND m.is_draft = FALSE
+        "#,
+        link_id,
+        message_ids
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch rule messages for link_id {}", link_id))?;
+
+    Ok(rows.into_iter().map(RuleMessage::from).collect())
+}
+
+/// Fetches a page of a link's received messages, newest first, in the shape rules are
+/// evaluated against. Sent messages and drafts are never returned.
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_rule_messages_paginated(
+    pool: &PgPool,
+    link_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<RuleMessage>> {
+    let rows = sqlx::query_as!(
+        RuleMessageRow,
+        r#"
+        SELECT
+            m.id,
+            m.thread_id,
+            m.provider_id,
+            fc.email_address as "from_email?",
+            ARRAY(
+                SELECT c.email_address::TEXT
+                FROM email_message_recipients r
+                JOIN email_contacts c ON c.id = r.contact_id
+                WHERE r.message_id = m.id AND r.recipient_type IN ('TO', 'CC')
+            ) as "recipient_emails!",
+            m.subject,
+            m.body_text,
+            m.has_attachments,
+            ARRAY(
+                SELECT ml.label_id FROM email_message_labels ml WHERE ml.message_id = m.id
+            ) as "label_ids!",
+            COALESCE(jsonb_path_exists(
+                m.headers_jsonb,
+                '$[*] ? (@.name like_regex "^x-macro-forwarded$" flag "i")'
+            ), FALSE) as "is_auto_forwarded!"
+        FROM email_messages m
+        LEFT JOIN email_contacts fc ON fc.id = m.from_contact_id
+        WHERE m.link_id = $1
+          AND m.is_sent = FALSE
+          AND m.is_draft = FALSE
+        ORDER BY m.internal_date_ts DESC NULLS LAST, m.id
+        LIMIT $2 OFFSET $3
+        "#,
+        link_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch rule messages for link_id {}", link_id))?;
+
+    Ok(rows.into_iter().map(RuleMessage::from).collect())
+}
@@ -0,0 +1,211 @@
+use anyhow::Context;
+use models_email::{db, service};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+pub mod messages;
+
+/// Fetches every rule of a link, in the order they are evaluated
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_rules_by_link_id(
+    pool: &PgPool,
+    link_id: Uuid,
+) -> anyhow::Result<Vec<service::rule::Rule>> {
+    let db_rules = sqlx::query_as!(
+        db::rule::Rule,
+        r#"
+        SELECT
+            id,
+            link_id,
+            name,
+            is_enabled,
+            position,
+            match_all,
+            stop_processing,
+            conditions,
+            actions,
+            created_at,
+            updated_at
+        FROM email_rules
+        WHERE link_id = $1
+        ORDER BY position, created_at
+        "#,
+        link_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch rules for link_id {}", link_id))?;
+
+    db_rules
+        .into_iter()
+        .map(service::rule::Rule::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid rule in db")
+}
+
+/// Fetches a rule of a link. Returns None if the rule doesn't exist or belongs to another link.
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_rule_by_id(
+    pool: &PgPool,
+    rule_id: Uuid,
+    link_id: Uuid,
+) -> anyhow::Result<Option<service::rule::Rule>> {
+    let db_rule = sqlx::query_as!(
+        db::rule::Rule,
+        r#"
+        SELECT
+            id,
+            link_id,
+            name,
+            is_enabled,
+            position,
+            match_all,
+            stop_processing,
+            conditions,
+            actions,
+            created_at,
+            updated_at
+        FROM email_rules
+        WHERE id = $1 AND link_id = $2
+        "#,
+        rule_id,
+        link_id
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to fetch rule {} for link_id {}", rule_id, link_id))?;
+
+    db_rule
+        .map(service::rule::Rule::try_from)
+        .transpose()
+        .context("Invalid rule in db")
+}
+
+/// Inserts a rule. Rules without a position are evaluated after every existing rule.
+#[tracing::instrument(skip(pool), err)]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "mirrors the columns of the table"
+)]
+pub async fn insert_rule(
+    pool: &PgPool,
+    link_id: Uuid,
+    name: &str,
+    is_enabled: bool,
+    position: Option<i32>,
+    match_all: bool,
+    stop_processing: bool,
+    conditions: &[service::rule::RuleCondition],
+    actions: &[service::rule::RuleAction],
+) -> anyhow::Result<service::rule::Rule> {
+    let db_rule = sqlx::query_as!(
+        db::rule::Rule,
+        r#"
+        INSERT INTO email_rules (
+            id, link_id, name, is_enabled, position, match_all, stop_processing, conditions, actions
+        )
+        VALUES (
+            $1, $2, $3, $4,
+            COALESCE($5, (SELECT COALESCE(MAX(position) + 1, 0) FROM email_rules WHERE link_id = $2)),
+            $6, $7, $8, $9
+        )
+        RETURNING
+            id,
+            link_id,
+            name,
+            is_enabled,
+            position,
+            match_all,
+            stop_processing,
+            conditions,
+            actions,
+            created_at,
+            updated_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        link_id,
+        name,
+        is_enabled,
+        position,
+        match_all,
+        stop_processing,
+        serde_json::to_value(conditions)?,
+        serde_json::to_value(actions)?,
+    )
+    .fetch_one(pool)
+    .await
+    .with_context(|| format!("Failed to insert rule for link_id {}", link_id))?;
+
+    Ok(service::rule::Rule::try_from(db_rule)?)
+}
+
+/// Replaces the editable fields of a rule. Returns None if the rule doesn't exist.
+#[tracing::instrument(skip(pool, rule), fields(rule_id = %rule.id, link_id = %rule.link_id), err)]
+pub async fn update_rule(
+    pool: &PgPool,
+    rule: &service::rule::Rule,
+) -> anyhow::Result<Option<service::rule::Rule>> {
+    let db_rule = sqlx::query_as!(
+        db::rule::Rule,
+        r#"
+        UPDATE email_rules
+        SET
+            name = $3,
+            is_enabled = $4,
+            position = $5,
+            match_all = $6,
+            stop_processing = $7,
+            conditions = $8,
+            actions = $9,
+            updated_at = NOW()
+        WHERE id = $1 AND link_id = $2
+        RETURNING
+            id,
+            link_id,
+            name,
+            is_enabled,
+            position,
+            match_all,
+            stop_processing,
+            conditions,
+            actions,
+            created_at,
+            updated_at
+        "#,
+        rule.id,
+        rule.link_id,
+        rule.name,
+        rule.is_enabled,
+        rule.position,
+        rule.match_all,
+        rule.stop_processing,
+        serde_json::to_value(&rule.conditions)?,
+        serde_json::to_value(&rule.actions)?,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to update rule {}", rule.id))?;
+
+    db_rule
+        .map(service::rule::Rule::try_from)
+        .transpose()
+        .context("Invalid rule in db")
+}
+
+/// Deletes a rule. Returns false if the rule doesn't exist.
+#[tracing::instrument(skip(pool), err)]
+pub async fn delete_rule(pool: &PgPool, rule_id: Uuid, link_id: Uuid) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM email_rules
+        WHERE id = $1 AND link_id = $2
+        "#,
+        rule_id,
+        link_id
+    )
+    .execute(pool)
+    .await
+    .with_context(|| format!("Failed to delete rule {}", rule_id))?;
+
+    Ok(result.rows_affected() > 0)
+}
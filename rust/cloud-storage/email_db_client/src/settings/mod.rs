@@ -13,16 +13,18 @@ pub async fn patch_settings(
     let result = sqlx::query_as!(
         db::settings::Settings,
         r#"
-        INSERT INTO email_settings (link_id, signature_on_replies_forwards)
-        VALUES ($1, $2)
+        INSERT INTO email_settings (link_id, signature_on_replies_forwards, undo_send_seconds)
+        VALUES ($1, $2, $3)
         ON CONFLICT (link_id)
         DO UPDATE SET
             signature_on_replies_forwards = EXCLUDED.signature_on_replies_forwards,
+            undo_send_seconds = EXCLUDED.undo_send_seconds,
             updated_at = NOW()
-        RETURNING link_id, signature_on_replies_forwards
+        RETURNING link_id, signature_on_replies_forwards, undo_send_seconds
         "#,
         db_settings.link_id,
         db_settings.signature_on_replies_forwards,
+        db_settings.undo_send_seconds,
     )
     .fetch_one(pool)
     .await?;
@@ -39,7 +41,7 @@ pub async fn fetch_settings(
     let result = sqlx::query_as!(
         db::settings::Settings,
         r#"
-        SELECT link_id, signature_on_replies_forwards
+        SELECT link_id, signature_on_replies_forwards, undo_send_seconds
         FROM email_settings
         WHERE link_id = $1
         "#,
@@ -0,0 +1,261 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use models_email::service::subscription::{
+    ListUnsubscribe, Subscription, SubscriptionTarget, SubscriptionThread,
+};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+/// Records the mailing list a received message was sent by
+#[tracing::instrument(skip(tx, list_unsubscribe), err)]
+pub async fn upsert_list_message(
+    tx: &mut sqlx::PgConnection,
+    link_id: Uuid,
+    message_id: Uuid,
+    list_unsubscribe: &ListUnsubscribe,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO email_list_messages (message_id, link_id, list_key, list_id, unsubscribe_url, unsubscribe_mailto, one_click)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (message_id) DO UPDATE SET
+            list_key = EXCLUDED.list_key,
+            list_id = EXCLUDED.list_id,
+            unsubscribe_url = EXCLUDED.unsubscribe_url,
+            unsubscribe_mailto = EXCLUDED.unsubscribe_mailto,
+            one_click = EXCLUDED.one_click
+        "#,
+        message_id,
+        link_id,
+        list_unsubscribe.list_key,
+        list_unsubscribe.list_id,
+        list_unsubscribe.http_url,
+        list_unsubscribe.mailto,
+        list_unsubscribe.one_click,
+    )
+    .execute(&mut *tx)
+    .await
+    .with_context(|| format!("Failed to upsert list message {}", message_id))?;
+
+    Ok(())
+}
+
+/// Fetches the mailing lists the link receives mail from, the ones with the most recent mail first
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_subscriptions(
+    pool: &PgPool,
+    link_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<Subscription>> {
+    sqlx::query_as!(
+        Subscription,
+        r#"
+        WITH stats AS (
+            SELECT
+                l.list_key,
+                COUNT(DISTINCT m.thread_id) as thread_count,
+                COUNT(*) as message_count,
+                COUNT(*) FILTER (WHERE m.internal_date_ts > NOW() - INTERVAL '30 days') as recent_message_count,
+                COUNT(*) FILTER (WHERE NOT m.is_read) as unread_message_count,
+                MAX(m.internal_date_ts) as last_received_at
+            FROM email_list_messages l
+            JOIN email_messages m ON m.id = l.message_id
+            WHERE l.link_id = $1
+            GROUP BY l.list_key
+        ),
+        latest AS (
+            SELECT DISTINCT ON (l.list_key)
+                l.list_key,
+                l.list_id,
+                l.unsubscribe_url,
+                l.unsubscribe_mailto,
+                l.one_click,
+                c.email_address,
+                c.name
+            FROM email_list_messages l
+            JOIN email_messages m ON m.id = l.message_id
+            LEFT JOIN email_contacts c ON c.id = m.from_contact_id
+            WHERE l.link_id = $1
+            ORDER BY l.list_key, m.internal_date_ts DESC NULLS LAST
+        )
+        SELECT
+            s.list_key as "list_key!",
+            latest.list_id,
+            latest.email_address as "sender_email?",
+            latest.name as "sender_name?",
+            s.thread_count as "thread_count!",
+            s.message_count as "message_count!",
+            s.recent_message_count as "recent_message_count!",
+            s.unread_message_count as "unread_message_count!",
+            s.last_received_at,
+            (latest.one_click OR latest.unsubscribe_mailto IS NOT NULL) as "can_unsubscribe!",
+            latest.one_click as "one_click!",
+            u.unsubscribed_at as "unsubscribed_at?"
+        FROM stats s
+        JOIN latest ON latest.list_key = s.list_key
+        LEFT JOIN email_list_unsubscribes u ON u.link_id = $1 AND u.list_key = s.list_key
+        ORDER BY s.recent_message_count DESC, s.last_received_at DESC NULLS LAST, s.list_key
+        LIMIT $2 OFFSET $3
+        "#,
+        link_id,
+        limit,
+        offset,
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch subscriptions for link_id {}", link_id))
+}
+
+/// Fetches the threads containing messages of a mailing list, most recent first
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_subscription_threads(
+    pool: &PgPool,
+    link_id: Uuid,
+    list_key: &str,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<SubscriptionThread>> {
+    sqlx::query_as!(
+        SubscriptionThread,
+        r#"
+        SELECT
+            threads.thread_id as "thread_id!",
+            latest.subject,
+            latest.snippet,
+            threads.last_received_at,
+            threads.is_read as "is_read!"
+        FROM (
+            SELECT
+                m.thread_id,
+                MAX(m.internal_date_ts) as last_received_at,
+                BOOL_AND(m.is_read) as is_read
+            FROM email_list_messages l
+            JOIN email_messages m ON m.id = l.message_id
+            WHERE l.link_id = $1 AND l.list_key = $2
+            GROUP BY m.thread_id
+            ORDER BY last_received_at DESC NULLS LAST, m.thread_id
+            LIMIT $3 OFFSET $4
+        ) threads
+        JOIN LATERAL (
+            SELECT m.subject, m.snippet
+            FROM email_list_messages l
+            JOIN email_messages m ON m.id = l.message_id
+            WHERE m.thread_id = threads.thread_id AND l.list_key = $2
+            ORDER BY m.internal_date_ts DESC NULLS LAST
+            LIMIT 1
+        ) latest ON TRUE
+        ORDER BY threads.last_received_at DESC NULLS LAST, threads.thread_id
+        "#,
+        link_id,
+        list_key,
+        limit,
+        offset,
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch threads of list {} for link_id {}",
+            list_key, link_id
+        )
+    })
+}
+
+/// Fetches how to unsubscribe from a mailing list, from its most recent message
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_subscription_target(
+    pool: &PgPool,
+    link_id: Uuid,
+    list_key: &str,
+) -> anyhow::Result<Option<SubscriptionTarget>> {
+    sqlx::query_as!(
+        SubscriptionTarget,
+        r#"
+        SELECT
+            l.list_key,
+            l.list_id,
+            c.email_address as "sender_email?",
+            l.unsubscribe_url as http_url,
+            l.unsubscribe_mailto as mailto,
+            l.one_click
+        FROM email_list_messages l
+        JOIN email_messages m ON m.id = l.message_id
+        LEFT JOIN email_contacts c ON c.id = m.from_contact_id
+        WHERE l.link_id = $1 AND l.list_key = $2
+        ORDER BY m.internal_date_ts DESC NULLS LAST
+        LIMIT 1
+        "#,
+        link_id,
+        list_key,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch unsubscribe target of list {} for link_id {}",
+            list_key, link_id
+        )
+    })
+}
+
+/// Records that the user unsubscribed from a mailing list. Returns when they unsubscribed.
+#[tracing::instrument(skip(pool), err)]
+pub async fn upsert_list_unsubscribe(
+    pool: &PgPool,
+    link_id: Uuid,
+    list_key: &str,
+    archive_rule_id: Option<Uuid>,
+) -> anyhow::Result<DateTime<Utc>> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO email_list_unsubscribes (link_id, list_key, archive_rule_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (link_id, list_key) DO UPDATE SET
+            archive_rule_id = COALESCE(EXCLUDED.archive_rule_id, email_list_unsubscribes.archive_rule_id),
+            unsubscribed_at = NOW()
+        RETURNING unsubscribed_at
+        "#,
+        link_id,
+        list_key,
+        archive_rule_id,
+    )
+    .fetch_one(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to record unsubscribe from list {} for link_id {}",
+            list_key, link_id
+        )
+    })?;
+
+    Ok(result.unsubscribed_at)
+}
+
+/// Fetches the rule archiving mail from a mailing list the user unsubscribed from, if any
+#[tracing::instrument(skip(pool), err)]
+pub async fn fetch_archive_rule_id(
+    pool: &PgPool,
+    link_id: Uuid,
+    list_key: &str,
+) -> anyhow::Result<Option<Uuid>> {
+    let archive_rule_id = sqlx::query_scalar!(
+        r#"
+        SELECT archive_rule_id
+        FROM email_list_unsubscribes
+        WHERE link_id = $1 AND list_key = $2
+        "#,
+        link_id,
+        list_key,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch archive rule of list {} for link_id {}",
+            list_key, link_id
+        )
+    })?;
+
+    Ok(archive_rule_id.flatten())
+}
@@ -0,0 +1,237 @@
+use anyhow::Context;
+use models_email::{db, service};
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+/// Fetches the templates of a link and the templates shared with the teams of its user, by name
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_templates_for_link(
+    pool: &PgPool,
+    link_id: Uuid,
+    macro_id: &str,
+) -> anyhow::Result<Vec<service::template::Template>> {
+    let db_templates = sqlx::query_as!(
+        db::template::Template,
+        r#"
+        SELECT
+            t.id,
+            t.link_id,
+            t.team_id,
+            t.name,
+            t.subject,
+            t.body_html,
+            t.body_text,
+            t.variables,
+            t.created_at,
+            t.updated_at
+        FROM email_templates t
+        WHERE t.link_id = $1
+           OR t.team_id IN (SELECT team_id FROM team_user WHERE user_id = $2)
+        ORDER BY LOWER(t.name), t.created_at
+        "#,
+        link_id,
+        macro_id
+    )
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to fetch templates for link_id {}", link_id))?;
+
+    db_templates
+        .into_iter()
+        .map(service::template::Template::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .context("Invalid template in db")
+}
+
+/// Fetches a template the link can use, its own or one shared with a team of its user.
+/// Returns None if the template doesn't exist or can't be used by the link.
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn fetch_template_for_link(
+    pool: &PgPool,
+    template_id: Uuid,
+    link_id: Uuid,
+    macro_id: &str,
+) -> anyhow::Result<Option<service::template::Template>> {
+    let db_template = sqlx::query_as!(
+        db::template::Template,
+        r#"
+        SELECT
+            t.id,
+            t.link_id,
+            t.team_id,
+            t.name,
+            t.subject,
+            t.body_html,
+            t.body_text,
+            t.variables,
+            t.created_at,
+            t.updated_at
+        FROM email_templates t
+        WHERE t.id = $1
+          AND (
+            t.link_id = $2
+            OR t.team_id IN (SELECT team_id FROM team_user WHERE user_id = $3)
+          )
+        "#,
+        template_id,
+        link_id,
+        macro_id
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| {
+        format!(
+            "Failed to fetch template {} for link_id {}",
+            template_id, link_id
+        )
+    })?;
+
+    db_template
+        .map(service::template::Template::try_from)
+        .transpose()
+        .context("Invalid template in db")
+}
+
+/// Whether a user is a member of a team
+#[tracing::instrument(skip(pool), level = "info", err)]
+pub async fn is_team_member(pool: &PgPool, team_id: Uuid, macro_id: &str) -> anyhow::Result<bool> {
+    let is_member = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM team_user WHERE team_id = $1 AND user_id = $2
+        ) AS "exists!"
+        "#,
+        team_id,
+        macro_id
+    )
+    .fetch_one(pool)
+    .await
+    .with_context(|| format!("Failed to check membership of team {}", team_id))?;
+
+    Ok(is_member)
+}
+
+/// Inserts a template
+#[tracing::instrument(skip(pool, subject, body_html, body_text), err)]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "mirrors the columns of the table"
+)]
+pub async fn insert_template(
+    pool: &PgPool,
+    link_id: Uuid,
+    team_id: Option<Uuid>,
+    name: &str,
+    subject: Option<&str>,
+    body_html: &str,
+    body_text: Option<&str>,
+    variables: &[service::template::TemplateVariable],
+) -> anyhow::Result<service::template::Template> {
+    let db_template = sqlx::query_as!(
+        db::template::Template,
+        r#"
+        INSERT INTO email_templates (
+            id, link_id, team_id, name, subject, body_html, body_text, variables
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING
+            id,
+            link_id,
+            team_id,
+            name,
+            subject,
+            body_html,
+            body_text,
+            variables,
+            created_at,
+            updated_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        link_id,
+        team_id,
+        name,
+        subject,
+        body_html,
+        body_text,
+        serde_json::to_value(variables)?,
+    )
+    .fetch_one(pool)
+    .await
+    .with_context(|| format!("Failed to insert template for link_id {}", link_id))?;
+
+    Ok(service::template::Template::try_from(db_template)?)
+}
+
+/// Replaces the editable fields of a template. Only the link that owns a template can update it.
+/// Returns None if the template doesn't exist.
+#[tracing::instrument(skip(pool, template), fields(template_id = %template.id, link_id = %template.link_id), err)]
+pub async fn update_template(
+    pool: &PgPool,
+    template: &service::template::Template,
+) -> anyhow::Result<Option<service::template::Template>> {
+    let db_template = sqlx::query_as!(
+        db::template::Template,
+        r#"
+        UPDATE email_templates
+        SET
+            team_id = $3,
+            name = $4,
+            subject = $5,
+            body_html = $6,
+            body_text = $7,
+            variables = $8,
+            updated_at = NOW()
+        WHERE id = $1 AND link_id = $2
+        RETURNING
+            id,
+            link_id,
+            team_id,
+            name,
+            subject,
+            body_html,
+            body_text,
+            variables,
+            created_at,
+            updated_at
+        "#,
+        template.id,
+        template.link_id,
+        template.team_id,
+        template.name,
+        template.subject,
+        template.body_html,
+        template.body_text,
+        serde_json::to_value(&template.variables)?,
+    )
+    .fetch_optional(pool)
+    .await
+    .with_context(|| format!("Failed to update template {}", template.id))?;
+
+    db_template
+        .map(service::template::Template::try_from)
+        .transpose()
+        .context("Invalid template in db")
+}
+
+/// Deletes a template. Only the link that owns a template can delete it.
+/// Returns false if the template doesn't exist.
+#[tracing::instrument(skip(pool), err)]
+pub async fn delete_template(
+    pool: &PgPool,
+    template_id: Uuid,
+    link_id: Uuid,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM email_templates
+        WHERE id = $1 AND link_id = $2
+        "#,
+        template_id,
+        link_id
+    )
+    .execute(pool)
+    .await
+    .with_context(|| format!("Failed to delete template {}", template_id))?;
+
+    Ok(result.rows_affected() > 0)
+}
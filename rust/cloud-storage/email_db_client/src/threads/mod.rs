@@ -1,4 +1,5 @@
 pub mod delete;
 pub mod get;
 pub mod insert;
+pub mod project;
 pub mod update;
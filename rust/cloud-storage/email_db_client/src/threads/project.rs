@@ -0,0 +1,94 @@
+use anyhow::Context;
+use sqlx::PgPool;
+use sqlx::types::Uuid;
+
+/// Moves a thread into a project owned by the user. Creates the thread's share permission if
+/// it doesn't exist yet, the same way it is created when the thread is first shared.
+/// Returns false if the project doesn't exist, is deleted or is owned by someone else.
+#[tracing::instrument(skip(pool), err)]
+pub async fn add_thread_to_project(
+    pool: &PgPool,
+    thread_id: Uuid,
+    project_id: &str,
+    macro_id: &str,
+) -> anyhow::Result<bool> {
+    let thread_id = thread_id.to_string();
+
+    let mut tx = pool.begin().await.context("Failed to begin transaction")?;
+
+    let project_exists = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM "Project"
+            WHERE id = $1 AND "userId" = $2 AND "deletedAt" IS NULL
+        ) as "exists!"
+        "#,
+        project_id,
+        macro_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .context("Failed to check project")?;
+
+    if !project_exists {
+        return Ok(false);
+    }
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE "EmailThreadPermission"
+        SET "projectId" = $2
+        WHERE "threadId" = $1
+        "#,
+        thread_id,
+        project_id
+    )
+    .execute(&mut *tx)
+    .await
+    .context("Failed to update thread project")?;
+
+    if updated.rows_affected() == 0 {
+        let share_permission_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO "SharePermission" ("isPublic", "publicAccessLevel", "createdAt", "updatedAt")
+            VALUES (FALSE, NULL, NOW(), NOW())
+            RETURNING id
+            "#
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to insert share permission")?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO "EmailThreadPermission" ("threadId", "sharePermissionId", "userId", "projectId")
+            VALUES ($1, $2, $3, $4)
+            "#,
+            thread_id,
+            share_permission_id,
+            macro_id,
+            project_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to insert thread permission")?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO "UserItemAccess" ("id", "user_id", "item_id", "item_type", "access_level", "created_at", "updated_at")
+            VALUES ($1, $2, $3, 'thread', 'owner', NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            macro_uuid::generate_uuid_v7(),
+            macro_id,
+            thread_id
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to insert user item access")?;
+    }
+
+    tx.commit().await.context("Failed to commit transaction")?;
+
+    Ok(true)
+}
@@ -141,6 +141,73 @@ pub async fn update_thread_read_status(
     Ok(())
 }
 
+/// Snoozes a thread until the passed time, hiding it from the inbox views until then.
+/// Passing None unsnoozes the thread. Returns false if the thread doesn't exist.
+#[tracing::instrument(skip(db))]
+pub async fn update_thread_snoozed_until(
+    db: &PgPool,
+    thread_id: Uuid,
+    link_id: Uuid,
+    snoozed_until: Option<DateTime<Utc>>,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE email_threads
+        SET
+            snoozed_until = $1,
+            updated_at = NOW()
+        WHERE
+            id = $2 AND
+            link_id = $3
+        "#,
+        snoozed_until,
+        thread_id,
+        link_id,
+    )
+    .execute(db)
+    .await
+    .context(format!(
+        "Failed to update snoozed_until to {:?} for thread ID {} with link_id {}",
+        snoozed_until, thread_id, link_id
+    ))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Bumps a thread's outbound timestamps when a message is queued in the outbox, so the thread
+/// shows up in the sent view before the provider has sent the message
+#[tracing::instrument(skip(conn))]
+pub async fn update_thread_outbound_ts(
+    conn: &mut sqlx::PgConnection,
+    thread_id: Uuid,
+    link_id: Uuid,
+    outbound_ts: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE email_threads
+        SET
+            latest_outbound_message_ts = GREATEST(latest_outbound_message_ts, $1),
+            latest_non_spam_message_ts = GREATEST(latest_non_spam_message_ts, $1),
+            updated_at = NOW()
+        WHERE
+            id = $2 AND
+            link_id = $3
+        "#,
+        outbound_ts,
+        thread_id,
+        link_id,
+    )
+    .execute(conn)
+    .await
+    .context(format!(
+        "Failed to update outbound timestamp for thread ID {} with link_id {}",
+        thread_id, link_id
+    ))?;
+
+    Ok(())
+}
+
 /// Updates a thread's provider_id
 #[tracing::instrument(skip(conn))]
 pub async fn update_thread_provider_id(
@@ -4,23 +4,34 @@ use lambda_runtime::{
     Error, LambdaEvent,
     tracing::{self},
 };
-use models_email::service::pubsub::ScheduledPubsubMessage;
+use models_email::service::pubsub::{FollowUpPubsubMessage, ScheduledPubsubMessage};
 
 #[tracing::instrument(skip(ctx, _event))]
 pub async fn handler(
     ctx: context::Context,
     _event: LambdaEvent<EventBridgeEvent>,
 ) -> Result<(), Error> {
-    // grab all messages with passed send_time that have not been sent already
+    // grab all messages with passed send_time that have not been sent already, along with
+    // outbox messages whose undo window has passed. outbox rows are removed once sent, and a
+    // claimed row is only picked up again once its claim runs out, so a message that is being
+    // sent isn't sent again. the 15 minutes match OUTBOX_CLAIM_LEASE_SECONDS in email_db_client.
+    // this runs every minute, so a message is sent up to a minute after its undo window passes
     let notifications = sqlx::query_as!(
         ScheduledPubsubMessage,
         r#"
         SELECT
-            link_id, message_id
+            link_id AS "link_id!", message_id AS "message_id!"
         FROM email_scheduled_messages
         WHERE
             send_time < now()
             AND sent = FALSE
+        UNION ALL
+        SELECT
+            link_id, message_id
+        FROM email_outbox
+        WHERE
+            send_at < now()
+            AND (claimed_at IS NULL OR claimed_at < now() - INTERVAL '15 minutes')
         "#,
     )
     .fetch_all(&ctx.db)
@@ -47,5 +58,45 @@ pub async fn handler(
         };
     }
 
+    // grab all follow up reminders that are due and haven't been processed yet
+    let follow_ups = sqlx::query_as!(
+        FollowUpPubsubMessage,
+        r#"
+        SELECT
+            link_id, id AS reminder_id
+        FROM email_follow_up_reminders
+        WHERE
+            remind_at < now()
+            AND fired = FALSE
+        "#,
+    )
+    .fetch_all(&ctx.db)
+    .await
+    .unwrap_or_else(|e| {
+        tracing::error!("Error fetching follow up reminders: {}", e);
+        Vec::new()
+    });
+
+    if !follow_ups.is_empty() {
+        tracing::info!(follow_ups = ?follow_ups, "Sending follow up reminder pubsub messages");
+    }
+
+    for follow_up in follow_ups.into_iter() {
+        let reminder_id = follow_up.reminder_id;
+        let link_id = follow_up.link_id;
+        if let Err(e) = ctx
+            .sqs_client
+            .enqueue_email_follow_up_message(follow_up)
+            .await
+        {
+            tracing::error!(
+                error = ?e,
+                link_id = link_id.to_string(),
+                reminder_id = reminder_id.to_string(),
+                "Error enqueueing follow up reminder",
+            );
+        };
+    }
+
     Ok(())
 }
@@ -1,4 +1,7 @@
-use crate::{config::Config, util::redis::RedisClient};
+use crate::{
+    config::Config,
+    util::{mail_provider::MailProviderFactory, redis::RedisClient},
+};
 use axum::extract::FromRef;
 use document_storage_service_client::DocumentStorageServiceClient;
 use email::{domain::service::EmailServiceImpl, inbound::EmailPreviewState, outbound::EmailPgRepo};
@@ -14,6 +17,7 @@ pub(crate) struct ApiContext {
     pub db: sqlx::Pool<sqlx::Postgres>,
     pub auth_service_client: Arc<authentication_service_client::AuthServiceClient>,
     pub gmail_client: Arc<gmail_client::GmailClient>,
+    pub mail_providers: Arc<MailProviderFactory>,
     pub redis_client: Arc<RedisClient>,
     pub sqs_client: Arc<sqs_client::SQS>,
     pub s3_client: Arc<s3_client::S3>,
@@ -22,6 +26,7 @@ pub(crate) struct ApiContext {
     pub jwt_args: JwtValidationArgs,
     pub config: Arc<Config>,
     pub internal_auth_key: LocalOrRemoteSecret<InternalApiSecretKey>,
+    pub fetcher: outbound_http::Fetcher,
     pub email_service: EmailPreviewState<
         EmailServiceImpl<EmailPgRepo, FrecencyQueryServiceImpl<FrecencyPgStorage>>,
     >,
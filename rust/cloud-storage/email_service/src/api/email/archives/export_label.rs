@@ -0,0 +1,128 @@
+use super::{MAX_IMPORT_BYTES, error, export_message, file_response};
+use crate::api::context::ApiContext;
+use axum::Extension;
+use axum::extract::{self, Path, State};
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::Response;
+use mail_provider::MailProvider;
+use mail_provider::archive;
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use sqlx::types::chrono::Utc;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The most messages exported for a label in one page
+const MAX_EXPORT_MESSAGES: i64 = 1000;
+
+/// The header holding the offset of the next page, when there is one
+pub(crate) const NEXT_OFFSET_HEADER: &str = "x-next-offset";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct ExportLabelParams {
+    pub offset: Option<i64>,
+}
+
+/// Export a page of the messages with a label as an mbox file, starting from the most recent.
+/// A page holds up to 1000 messages, and stops early so the file stays small enough to import
+/// again. The offset of the next page is returned in the X-Next-Offset header while more
+/// messages remain.
+#[utoipa::path(
+    get,
+    tag = "Archives",
+    path = "/email/archives/labels/{id}",
+    operation_id = "export_label",
+    params(
+        ("id" = Uuid, Path, description = "Label ID."),
+        ("offset" = i64, Query, description = "Offset for pagination. Default is 0."),
+    ),
+    responses(
+            (status = 200, content_type = "application/mbox", body=Vec<u8>, headers(
+                ("X-Next-Offset" = i64, description = "The offset of the next page, if there are more messages.")
+            )),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, provider), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    provider: Extension<Arc<dyn MailProvider>>,
+    Path(label_id): Path<Uuid>,
+    extract::Query(params): extract::Query<ExportLabelParams>,
+) -> Result<Response, Response> {
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    email_db_client::labels::get::fetch_label_by_id(&ctx.db, label_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch label");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "unable to fetch label")
+        })?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "label not found"))?;
+
+    // one extra message tells us whether there is another page
+    let messages = email_db_client::messages::export::fetch_label_export_messages(
+        &ctx.db,
+        link.id,
+        label_id,
+        MAX_EXPORT_MESSAGES + 1,
+        offset,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch label messages");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch label messages",
+        )
+    })?;
+
+    // Messages come most recent first, but are written to the file oldest first
+    let mut entries: Vec<Vec<u8>> = Vec::new();
+    let mut size = 0;
+    for message in messages.iter().take(MAX_EXPORT_MESSAGES as usize) {
+        let raw = export_message(&ctx, provider.as_ref(), &link, message)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, message_id=%message.db_id, "unable to build message");
+                error(StatusCode::INTERNAL_SERVER_ERROR, "unable to export label")
+            })?;
+
+        let mut entry = Vec::new();
+        archive::append_mbox_message(
+            &mut entry,
+            &raw,
+            message.from_email.as_deref().unwrap_or_default(),
+            message.internal_date_ts.unwrap_or_else(Utc::now),
+        );
+
+        // the rest goes in the next page, so every page can be imported again
+        if !entries.is_empty() && size + entry.len() > MAX_IMPORT_BYTES {
+            break;
+        }
+        size += entry.len();
+        entries.push(entry);
+    }
+
+    let next_offset = (entries.len() < messages.len()).then(|| offset + entries.len() as i64);
+    let mbox: Vec<u8> = entries.into_iter().rev().flatten().collect();
+
+    let mut response = file_response(
+        "application/mbox",
+        format!("label-{label_id}-{offset}.mbox"),
+        mbox,
+    );
+    if let Some(next_offset) = next_offset {
+        response
+            .headers_mut()
+            .insert(NEXT_OFFSET_HEADER, HeaderValue::from(next_offset));
+    }
+
+    Ok(response)
+}
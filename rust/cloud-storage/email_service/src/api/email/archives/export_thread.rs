@@ -0,0 +1,78 @@
+use super::{error, export_message, file_response};
+use crate::api::context::ApiContext;
+use axum::Extension;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use mail_provider::MailProvider;
+use mail_provider::archive;
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Export a thread as an .eml file. The messages of the thread are wrapped in a multipart/digest
+/// message, which can be imported again.
+#[utoipa::path(
+    get,
+    tag = "Archives",
+    path = "/email/archives/threads/{id}",
+    operation_id = "export_thread",
+    params(
+        ("id" = Uuid, Path, description = "Thread ID."),
+    ),
+    responses(
+            (status = 200, content_type = "message/rfc822", body=Vec<u8>),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, provider), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    provider: Extension<Arc<dyn MailProvider>>,
+    Path(thread_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let messages = email_db_client::messages::export::fetch_thread_export_messages(
+        &ctx.db, link.id, thread_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch thread messages");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch thread messages",
+        )
+    })?;
+
+    let Some(subject) = messages.first().map(|message| message.subject.clone()) else {
+        return Err(error(StatusCode::NOT_FOUND, "thread not found"));
+    };
+
+    let mut raw_messages = Vec::with_capacity(messages.len());
+    for message in &messages {
+        let raw = export_message(&ctx, provider.as_ref(), &link, message)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, message_id=%message.db_id, "unable to build message");
+                error(StatusCode::INTERNAL_SERVER_ERROR, "unable to export thread")
+            })?;
+        raw_messages.push(raw);
+    }
+
+    let subject = subject.unwrap_or_default();
+    let eml = archive::build_digest(&subject, raw_messages).map_err(|e| {
+        tracing::error!(error=?e, "unable to build thread digest");
+        error(StatusCode::INTERNAL_SERVER_ERROR, "unable to export thread")
+    })?;
+
+    Ok(file_response(
+        "message/rfc822",
+        format!("thread-{thread_id}.eml"),
+        eml,
+    ))
+}
@@ -0,0 +1,100 @@
+use super::error;
+use crate::api::context::ApiContext;
+use axum::body::Bytes;
+use axum::extract::{self, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct ImportArchiveParams {
+    /// Import received messages without adding them to the inbox
+    #[serde(default)]
+    pub archive: bool,
+}
+
+/// The response returned from the import archive endpoint
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ImportArchiveResponse {
+    /// The threads messages were imported into
+    pub thread_ids: Vec<Uuid>,
+    pub imported_message_count: usize,
+    /// Messages that were already in the mailbox
+    pub skipped_message_count: usize,
+    /// Messages that couldn't be parsed or imported
+    pub failed_message_count: usize,
+}
+
+/// Import an mbox archive or a single .eml message into the mailbox.
+#[utoipa::path(
+    post,
+    tag = "Archives",
+    path = "/email/archives/import",
+    operation_id = "import_archive",
+    params(
+        ("archive" = bool, Query, description = "Import received messages without adding them to the inbox. Default is false."),
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "An mbox file or .eml message."),
+    responses(
+            (status = 200, body=ImportArchiveResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 413, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    extract::Query(params): extract::Query<ImportArchiveParams>,
+    body: Bytes,
+) -> Result<Response, Response> {
+    if body.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "upload is empty"));
+    }
+
+    let summary = crate::util::import_archive::import_archive(
+        &ctx.db,
+        &ctx.dss_client,
+        &ctx.sqs_client,
+        &link,
+        &body,
+        params.archive,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to import archive");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to import archive",
+        )
+    })?;
+
+    if summary.imported_message_count == 0
+        && summary.skipped_message_count == 0
+        && summary.failed_message_count == 0
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "no messages found in upload",
+        ));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportArchiveResponse {
+            thread_ids: summary.thread_ids,
+            imported_message_count: summary.imported_message_count,
+            skipped_message_count: summary.skipped_message_count,
+            failed_message_count: summary.failed_message_count,
+        }),
+    )
+        .into_response())
+}
@@ -0,0 +1,146 @@
+use crate::api::ApiContext;
+use crate::pubsub::util::check_gmail_rate_limit;
+use axum::Json;
+use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use mail_provider::MailProvider;
+use mail_provider::archive::{self, ExportedAttachment};
+use model::response::ErrorResponse;
+use models_email::gmail::Header;
+use models_email::gmail::operations::GmailApiOperation;
+use models_email::service::link::Link;
+use models_email::service::message::ExportMessage;
+
+pub(crate) mod export_label;
+pub(crate) mod export_thread;
+pub(crate) mod import;
+
+/// The largest archive that can be imported in one upload
+const MAX_IMPORT_BYTES: usize = 50 * 1024 * 1024;
+
+pub fn router(state: ApiContext) -> Router<ApiContext> {
+    Router::new()
+        .route("/threads/:id", get(export_thread::handler))
+        .route("/labels/:id", get(export_label::handler))
+        // exported attachments are fetched from the provider
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::api::middleware::mail_provider::attach_mail_provider,
+        ))
+        .route(
+            "/import",
+            post(import::handler).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.email_service,
+            crate::api::middleware::link::attach_link_context,
+        ))
+}
+
+fn error(status_code: StatusCode, message: &'static str) -> Response {
+    (status_code, Json(ErrorResponse { message })).into_response()
+}
+
+/// A response that downloads as a file
+fn file_response(content_type: &'static str, filename: String, body: Vec<u8>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Rebuilds a stored message as a raw message. Attachments are fetched from the provider, the ones
+/// that can't be fetched, e.g. those of imported messages, are left out.
+async fn export_message(
+    ctx: &ApiContext,
+    provider: &dyn MailProvider,
+    link: &Link,
+    message: &ExportMessage,
+) -> anyhow::Result<Vec<u8>> {
+    let mut attachments = Vec::new();
+
+    if let Some(provider_id) = &message.provider_id {
+        for attachment in &message.attachments {
+            let Some(attachment_id) = &attachment.provider_id else {
+                continue;
+            };
+
+            let data = async {
+                check_gmail_rate_limit(
+                    &ctx.redis_client,
+                    link.id,
+                    GmailApiOperation::MessagesAttachmentsGet,
+                    false,
+                )
+                .await
+                .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+                provider
+                    .get_attachment_data(provider_id, attachment_id)
+                    .await
+            }
+            .await;
+
+            match data {
+                Ok(data) => attachments.push(ExportedAttachment {
+                    filename: attachment.filename.clone().unwrap_or_default(),
+                    mime_type: attachment
+                        .mime_type
+                        .clone()
+                        .unwrap_or_else(|| "application/octet-stream".to_string()),
+                    data,
+                }),
+                Err(e) => {
+                    tracing::warn!(error=?e, message_id=%message.db_id, "unable to fetch attachment for export");
+                }
+            }
+        }
+    }
+
+    archive::build_message(
+        &message_headers(message),
+        message.body_text.as_deref(),
+        message.body_html_sanitized.as_deref(),
+        &attachments,
+    )
+}
+
+/// The headers of a message as received, or the basic headers for messages without stored headers
+fn message_headers(message: &ExportMessage) -> Vec<Header> {
+    let headers: Vec<Header> = message
+        .headers_json
+        .clone()
+        .and_then(|headers| serde_json::from_value(headers).ok())
+        .unwrap_or_default();
+
+    if !headers.is_empty() {
+        return headers;
+    }
+
+    [
+        ("From", message.from_email.clone()),
+        ("Subject", message.subject.clone()),
+        (
+            "Date",
+            message.internal_date_ts.map(|date| date.to_rfc2822()),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        value.map(|value| Header {
+            name: name.to_string(),
+            value,
+        })
+    })
+    .collect()
+}
@@ -5,11 +5,13 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
 use cloudfront_sign::{SignedOptions, get_signed_url};
+use mail_provider::MailProvider;
 use model::response::ErrorResponse;
 use model::user::UserContext;
 use models_email::email::service::attachment;
 use models_email::email::service::link::Link;
 use models_email::service;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use utoipa::ToSchema;
 use uuid::Uuid;
@@ -37,13 +39,13 @@ pub struct GetAttachmentResponse {
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, link, gmail_token), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id
+#[tracing::instrument(skip(ctx, user_context, link, provider), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id
 ))]
 pub async fn handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Path(attachment_id): Path<Uuid>,
 ) -> Result<Response, Response> {
     // get attachment metadata from db
@@ -116,18 +118,15 @@ pub async fn handler(
             })?;
         presigned_request.to_string()
     } else {
-        // Object doesn't exist, need to fetch from Gmail and upload
-        // fetch attachment data from gmail api
-        let attachment_data = ctx
-            .gmail_client
+        // Object doesn't exist, need to fetch from the provider and upload
+        let attachment_data = provider
             .get_attachment_data(
-                gmail_token.as_str(),
                 &message_provider_id,
                 db_attachment.provider_id.as_ref().unwrap(),
             )
             .await
             .map_err(|e| {
-                tracing::warn!(error=?e, "error fetching attachment from Gmail API");
+                tracing::warn!(error=?e, "error fetching attachment from mail provider");
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse {
@@ -4,8 +4,10 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use mail_provider::MailProvider;
 use model::response::ErrorResponse;
 use models_email::email::service::link::Link;
+use std::sync::Arc;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 use utoipa::ToSchema;
@@ -59,11 +61,11 @@ pub struct GetAttachmentDocumentIDResponse {
             (status = 500, body = ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx))]
+#[tracing::instrument(skip(ctx, provider))]
 pub async fn handler(
     State(ctx): State<ApiContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Path(attachment_id): Path<Uuid>,
 ) -> Result<Json<GetAttachmentDocumentIDResponse>, GetAttachmentDocumentIdError> {
     // return ID if attachment already exists in Macro
@@ -96,9 +98,8 @@ pub async fn handler(
 
     let document_id = upload_attachment(
         &ctx.redis_client,
-        &ctx.gmail_client,
+        &**provider,
         &ctx.dss_client,
-        &gmail_token,
         &link,
         &attachment_metadata,
     )
@@ -19,7 +19,7 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
                 ))
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
-                    crate::api::middleware::gmail_token::attach_gmail_token,
+                    crate::api::middleware::mail_provider::attach_mail_provider,
                 )),
         )
 }
@@ -0,0 +1,126 @@
+use super::error;
+use crate::api::context::ApiContext;
+use axum::extract::{self, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::person::{self, DuplicateCandidate};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 200;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct ListDuplicatesParams {
+    /// The most candidates to return. Default is 50, max is 200.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListDuplicatesResponse {
+    /// pairs of contacts that are likely the same person, most likely first
+    pub candidates: Vec<DuplicateCandidate>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct DismissDuplicateRequest {
+    /// the two contacts that aren't the same person
+    pub contact_ids: Vec<Uuid>,
+}
+
+/// List pairs of the user's contacts that are likely the same person, judged by their names and
+/// addresses.
+#[utoipa::path(
+    get,
+    tag = "Contacts",
+    path = "/email/contacts/duplicates",
+    operation_id = "list_contact_duplicates",
+    params(
+        ("limit" = Option<usize>, Query, description = "The most candidates to return. Default is 50, max is 200."),
+    ),
+    responses(
+            (status = 200, body=ListDuplicatesResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn list_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    extract::Query(params): extract::Query<ListDuplicatesParams>,
+) -> Result<Response, Response> {
+    let (contacts, dismissed) = tokio::try_join!(
+        email_db_client::contacts::people::fetch_link_contacts(&ctx.db, link.id),
+        email_db_client::contacts::people::fetch_dismissed_duplicates(&ctx.db, link.id),
+    )
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch contacts");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch contacts",
+        )
+    })?;
+
+    let mut candidates = person::find_duplicate_candidates(&contacts, &dismissed);
+    candidates.truncate(params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT));
+
+    Ok((StatusCode::OK, Json(ListDuplicatesResponse { candidates })).into_response())
+}
+
+/// Dismiss a pair of contacts as duplicates, so they are no longer suggested.
+#[utoipa::path(
+    post,
+    tag = "Contacts",
+    path = "/email/contacts/duplicates/dismiss",
+    operation_id = "dismiss_contact_duplicate",
+    request_body = DismissDuplicateRequest,
+    responses(
+            (status = 200, body=EmptyResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn dismiss_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Json(req): Json<DismissDuplicateRequest>,
+) -> Result<Response, Response> {
+    let [a, b] = req.contact_ids[..] else {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "exactly two contacts are needed to dismiss",
+        ));
+    };
+    if a == b {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "a contact can't be a duplicate of itself",
+        ));
+    }
+
+    let found = email_db_client::contacts::people::dismiss_duplicate(&ctx.db, link.id, (a, b))
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to dismiss duplicate");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to dismiss duplicate",
+            )
+        })?;
+
+    if !found {
+        return Err(error(StatusCode::NOT_FOUND, "contact not found"));
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse::default())).into_response())
+}
@@ -1,9 +1,39 @@
 use crate::api::ApiContext;
+use axum::Json;
 use axum::Router;
-use axum::routing::get;
+use axum::extract::DefaultBodyLimit;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use model::response::ErrorResponse;
 
+pub(crate) mod duplicates;
 pub(crate) mod list;
+pub(crate) mod people;
+pub(crate) mod vcard;
 
-pub fn router() -> Router<ApiContext> {
-    Router::new().route("/", get(list::list_contacts_handler))
+/// The largest vCard file that can be imported in one upload
+const MAX_IMPORT_BYTES: usize = 5 * 1024 * 1024;
+
+pub fn router(state: ApiContext) -> Router<ApiContext> {
+    Router::new()
+        .route("/people", get(people::list_handler))
+        .route("/people/:id/split", post(people::split_handler))
+        .route("/merge", post(people::merge_handler))
+        .route("/duplicates", get(duplicates::list_handler))
+        .route("/duplicates/dismiss", post(duplicates::dismiss_handler))
+        .route("/vcard", get(vcard::export_handler))
+        .route(
+            "/vcard",
+            post(vcard::import_handler).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.email_service,
+            crate::api::middleware::link::attach_link_context,
+        ))
+        .route("/", get(list::list_contacts_handler))
+}
+
+fn error(status_code: StatusCode, message: &'static str) -> Response {
+    (status_code, Json(ErrorResponse { message })).into_response()
 }
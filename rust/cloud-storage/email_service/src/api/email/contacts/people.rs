@@ -0,0 +1,210 @@
+use super::error;
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::person::Person;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The most contacts that can be merged or split in one request
+const MAX_CONTACTS: usize = 100;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListPeopleResponse {
+    /// the people contacts were merged into, by name
+    pub people: Vec<Person>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct MergeContactsRequest {
+    /// the contacts that are the same person, at least two
+    pub contact_ids: Vec<Uuid>,
+    /// the name of the person, defaults to the longest name of the contacts
+    pub name: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PersonResponse {
+    pub person: Person,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct SplitPersonRequest {
+    /// the contacts that aren't the person
+    pub contact_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct SplitPersonResponse {
+    /// the person, unless it was left with a single contact and removed
+    pub person: Option<Person>,
+}
+
+/// List the people the user's contacts were merged into.
+#[utoipa::path(
+    get,
+    tag = "Contacts",
+    path = "/email/contacts/people",
+    operation_id = "list_contact_people",
+    responses(
+            (status = 200, body=ListPeopleResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn list_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+) -> Result<Response, Response> {
+    let people = email_db_client::contacts::people::fetch_people(&ctx.db, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch people");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "unable to fetch people")
+        })?;
+
+    Ok((StatusCode::OK, Json(ListPeopleResponse { people })).into_response())
+}
+
+/// Merge contacts that are the same person. Contacts that were already merged bring the rest of
+/// their person along.
+#[utoipa::path(
+    post,
+    tag = "Contacts",
+    path = "/email/contacts/merge",
+    operation_id = "merge_contacts",
+    request_body = MergeContactsRequest,
+    responses(
+            (status = 200, body=PersonResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn merge_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Json(req): Json<MergeContactsRequest>,
+) -> Result<Response, Response> {
+    let contact_ids: HashSet<Uuid> = req.contact_ids.iter().copied().collect();
+    if contact_ids.len() < 2 {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "at least two contacts are needed to merge",
+        ));
+    }
+    if contact_ids.len() > MAX_CONTACTS {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "too many contacts to merge at once",
+        ));
+    }
+
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    let person_id =
+        email_db_client::contacts::people::merge_contacts(&ctx.db, link.id, &req.contact_ids, name)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to merge contacts");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to merge contacts",
+                )
+            })?
+            .ok_or_else(|| error(StatusCode::NOT_FOUND, "contact not found"))?;
+
+    let person = fetch_person(&ctx, &link, person_id)
+        .await?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "person not found"))?;
+
+    Ok((StatusCode::OK, Json(PersonResponse { person })).into_response())
+}
+
+/// Split contacts off a person they were merged into.
+#[utoipa::path(
+    post,
+    tag = "Contacts",
+    path = "/email/contacts/people/{id}/split",
+    operation_id = "split_contact_person",
+    params(
+        ("id" = Uuid, Path, description = "Person ID."),
+    ),
+    request_body = SplitPersonRequest,
+    responses(
+            (status = 200, body=SplitPersonResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn split_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(person_id): Path<Uuid>,
+    Json(req): Json<SplitPersonRequest>,
+) -> Result<Response, Response> {
+    if req.contact_ids.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "no contacts to split"));
+    }
+    if req.contact_ids.len() > MAX_CONTACTS {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "too many contacts to split at once",
+        ));
+    }
+
+    let found = email_db_client::contacts::people::split_contacts(
+        &ctx.db,
+        link.id,
+        person_id,
+        &req.contact_ids,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to split contacts");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to split contacts",
+        )
+    })?;
+
+    if !found {
+        return Err(error(StatusCode::NOT_FOUND, "person not found"));
+    }
+
+    let person = fetch_person(&ctx, &link, person_id).await?;
+
+    Ok((StatusCode::OK, Json(SplitPersonResponse { person })).into_response())
+}
+
+async fn fetch_person(
+    ctx: &ApiContext,
+    link: &Link,
+    person_id: Uuid,
+) -> Result<Option<Person>, Response> {
+    email_db_client::contacts::people::fetch_person(&ctx.db, link.id, person_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch person");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "unable to fetch person")
+        })
+}
@@ -0,0 +1,235 @@
+use super::error;
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::contact::Contact;
+use models_email::service::link::Link;
+use models_email::service::vcard::{self, VCard};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+/// The most cards that can be imported in one upload
+const MAX_IMPORT_CARDS: usize = 5000;
+/// The longest email address and name a contact can have
+const MAX_EMAIL_LEN: usize = 320;
+const MAX_NAME_LEN: usize = 255;
+
+/// The response returned from the import vCard endpoint
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ImportVCardResponse {
+    /// Cards with at least one email address
+    pub card_count: usize,
+    /// Contacts created or updated from the cards
+    pub contact_count: u64,
+    /// Cards with several email addresses, whose contacts were merged into one person
+    pub person_count: usize,
+}
+
+/// Export the user's contacts as a vCard 4.0 file. Contacts merged into a person are exported as
+/// one card.
+#[utoipa::path(
+    get,
+    tag = "Contacts",
+    path = "/email/contacts/vcard",
+    operation_id = "export_contacts_vcard",
+    responses(
+            (status = 200, body=String, content_type = "text/vcard"),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn export_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+) -> Result<Response, Response> {
+    let (people, contacts) = tokio::try_join!(
+        email_db_client::contacts::people::fetch_people(&ctx.db, link.id),
+        email_db_client::contacts::people::fetch_link_contacts(&ctx.db, link.id),
+    )
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch contacts");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch contacts",
+        )
+    })?;
+
+    let people_cards = people.into_iter().map(|person| VCard {
+        uid: Some(format!("urn:uuid:{}", person.id)),
+        full_name: person.name.or_else(|| {
+            person
+                .contacts
+                .iter()
+                .find_map(|contact| contact.name.clone())
+        }),
+        photo_url: person
+            .contacts
+            .iter()
+            .find_map(|contact| contact.photo_url.clone()),
+        emails: person
+            .contacts
+            .into_iter()
+            .map(|contact| contact.email_address)
+            .collect(),
+    });
+
+    // addresses of automated senders aren't worth keeping as contacts
+    let contact_cards = contacts
+        .into_iter()
+        .filter(|contact| contact.person_id.is_none())
+        .filter(|contact| !email_utils::is_generic_email(&contact.email_address))
+        .map(|contact| VCard {
+            uid: Some(format!("urn:uuid:{}", contact.id)),
+            full_name: contact.name,
+            emails: vec![contact.email_address],
+            photo_url: contact.photo_url,
+        });
+
+    let cards: Vec<VCard> = people_cards.chain(contact_cards).collect();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/vcard; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"contacts.vcf\"",
+            ),
+        ],
+        vcard::write_vcards(&cards),
+    )
+        .into_response())
+}
+
+/// Import contacts from a vCard 3.0 or 4.0 file. The email addresses of a card become contacts
+/// named after it, and a card with several addresses is merged into one person.
+#[utoipa::path(
+    post,
+    tag = "Contacts",
+    path = "/email/contacts/vcard",
+    operation_id = "import_contacts_vcard",
+    request_body(content = String, content_type = "text/vcard", description = "A vCard file."),
+    responses(
+            (status = 200, body=ImportVCardResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 413, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn import_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    body: String,
+) -> Result<Response, Response> {
+    let cards: Vec<VCard> = vcard::parse_vcards(&body)
+        .into_iter()
+        .filter_map(|mut card| {
+            card.emails.retain(|email| email.len() <= MAX_EMAIL_LEN);
+            card.full_name = card
+                .full_name
+                .map(|name| name.chars().take(MAX_NAME_LEN).collect());
+            (!card.emails.is_empty()).then_some(card)
+        })
+        .collect();
+    if cards.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "no cards with an email address found",
+        ));
+    }
+    if cards.len() > MAX_IMPORT_CARDS {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "too many cards to import at once",
+        ));
+    }
+
+    // an address can only be upserted once, the first card it is on names it. Photos are only
+    // taken from the provider, never fetched from an uploaded url.
+    let mut seen = HashSet::new();
+    let contacts: Vec<Contact> = cards
+        .iter()
+        .flat_map(|card| card.emails.iter().map(move |email| (card, email)))
+        .filter(|(_, email)| seen.insert(email.as_str()))
+        .map(|(card, email)| Contact {
+            id: None,
+            link_id: link.id,
+            name: card.full_name.clone(),
+            email_address: Some(email.clone()),
+            original_photo_url: None,
+            sfs_photo_url: None,
+        })
+        .collect();
+
+    let contact_count = email_db_client::contacts::upsert_sync::upsert_contacts(&ctx.db, &contacts)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to import contacts");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to import contacts",
+            )
+        })?;
+
+    let emails: Vec<String> = cards
+        .iter()
+        .filter(|card| card.emails.len() > 1)
+        .flat_map(|card| card.emails.iter().cloned())
+        .collect();
+    let contact_ids =
+        email_db_client::contacts::people::fetch_contact_ids_by_emails(&ctx.db, link.id, &emails)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch imported contacts");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to fetch imported contacts",
+                )
+            })?;
+
+    let mut person_count = 0;
+    for card in cards.iter().filter(|card| card.emails.len() > 1) {
+        let card_contact_ids: Vec<_> = card
+            .emails
+            .iter()
+            .filter_map(|email| contact_ids.get(email).copied())
+            .collect();
+        if card_contact_ids.len() < 2 {
+            continue;
+        }
+
+        email_db_client::contacts::people::merge_contacts(
+            &ctx.db,
+            link.id,
+            &card_contact_ids,
+            card.full_name.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to merge imported contacts");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to merge imported contacts",
+            )
+        })?;
+        person_count += 1;
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportVCardResponse {
+            card_count: cards.len(),
+            contact_count,
+            person_count,
+        }),
+    )
+        .into_response())
+}
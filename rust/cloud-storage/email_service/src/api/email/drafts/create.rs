@@ -114,7 +114,7 @@ pub async fn handler(
     }
 }
 
-async fn insert_draft(
+pub(crate) async fn insert_draft(
     tx: &mut sqlx::PgConnection,
     draft: &mut message::MessageToSend,
     from_email: &str,
@@ -1,5 +1,6 @@
 use crate::api::ApiContext;
 use crate::api::email::sync::enable::{EnableSyncError, enable_gmail_sync};
+use crate::util::mail_provider::{MailProviderFactory, connection_security};
 use anyhow::Context;
 use axum::{
     Extension,
@@ -9,11 +10,17 @@ use axum::{
 };
 use email::domain::models::UserProvider;
 use email::domain::ports::EmailRepo;
+use imap_client::{ImapConfig, SmtpConfig};
+use macro_env::Environment;
+use macro_user_id::email::EmailStr;
+use macro_user_id::user_id::MacroUserIdStr;
 use model::response::ErrorResponse;
 use model::user::axum_extractor::MacroUserExtractor;
 use models_email::email::service::backfill::{
     BackfillJobStatus, BackfillOperation, BackfillPubsubMessage,
 };
+use models_email::email::service::imap_account::{ConnectionSecurity, ImapAccount};
+use models_email::email::service::link::{self, Link};
 use strum_macros::AsRefStr;
 use thiserror::Error;
 use utoipa::ToSchema;
@@ -33,6 +40,12 @@ pub enum InitError {
     #[error("Enable sync error")]
     EnableSync(#[from] EnableSyncError),
 
+    #[error("Invalid mail server settings: {0}")]
+    InvalidImapAccount(&'static str),
+
+    #[error("Invalid input")]
+    Parse(#[from] macro_user_id::error::ParseErr),
+
     #[error("Database query error")]
     QueryError(#[from] anyhow::Error),
 }
@@ -46,7 +59,9 @@ impl IntoResponse for InitError {
             }
             _ => {
                 let status_code = match &self {
-                    InitError::AlreadyInitialized => StatusCode::BAD_REQUEST,
+                    InitError::AlreadyInitialized
+                    | InitError::InvalidImapAccount(_)
+                    | InitError::Parse(_) => StatusCode::BAD_REQUEST,
                     InitError::TooManyJobs => StatusCode::TOO_MANY_REQUESTS,
                     InitError::EnqueueError | InitError::QueryError(_) => {
                         StatusCode::INTERNAL_SERVER_ERROR
@@ -118,79 +133,246 @@ pub async fn handler(
         None => {
             let link = enable_gmail_sync(&ctx, &user_context, Some(gmail_token.as_str())).await?;
 
-            // users can only have 3 jobs within past 24h and one backfill job per link in progress at a time
-            let recent_jobs =
-                email_db_client::backfill::job::get::get_recent_jobs_by_fusionauth_user_id(
-                    &ctx.db,
-                    &link.fusionauth_user_id,
-                )
-                .await
-                .context("Failed to fetch jobs by macro id")?;
+            let backfill_job_id = start_init_backfill(&ctx, &link).await?;
+
+            Ok((
+                StatusCode::OK,
+                Json(InitResponse {
+                    link_id: link.id,
+                    backfill_job_id,
+                }),
+            )
+                .into_response())
+        }
+    }
+}
 
-            if recent_jobs.len() >= 3 && !link.email_address.0.as_ref().ends_with("@macro.com") {
-                tracing::info!(user_id = %user_context.user_id, "Too many jobs error");
-                email_db_client::links::delete::delete_link_by_id(&ctx.db, link.id)
-                    .await
-                    .context("Failed to delete link")?;
+/// The IMAP and SMTP settings of the mailbox to link
+#[derive(serde::Deserialize, ToSchema)]
+pub struct InitImapRequest {
+    /// The address of the mailbox, used as the sender of sent messages
+    pub email_address: String,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub imap_security: ConnectionSecurity,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: ConnectionSecurity,
+    /// The username used to log in to both servers
+    pub username: String,
+    /// The password used to log in to both servers. Usually an app specific password.
+    pub password: String,
+}
 
-                return Err(InitError::TooManyJobs);
+/// Initialize email functionality for a mailbox accessed over IMAP and SMTP. Checks that the servers
+/// accept the settings, then populates initial threads and starts watching the inbox.
+#[utoipa::path(
+    post,
+    tag = "Init",
+    path = "/email/init/imap",
+    operation_id = "init_imap_user",
+    request_body = InitImapRequest,
+    responses(
+            (status = 200, body=InitResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn imap_handler(
+    State(ctx): State<ApiContext>,
+    MacroUserExtractor {
+        macro_user_id,
+        user_context,
+        ..
+    }: MacroUserExtractor,
+    Json(body): Json<InitImapRequest>,
+) -> Result<Response, InitError> {
+    tracing::info!(user_id = %user_context.user_id, "Init imap called");
+    let pg_repo = email::outbound::EmailPgRepo::new(ctx.db.clone());
+    let existing_link = pg_repo
+        .link_by_fusionauth_and_macro_id(
+            &user_context.fusion_user_id,
+            macro_user_id,
+            UserProvider::Imap,
+        )
+        .await
+        .context("Failed to fetch existing link")?;
+
+    if existing_link.is_some() {
+        return Err(InitError::AlreadyInitialized);
+    }
+
+    // the password would be sent in the clear, only the local test server is reached this way
+    if !matches!(ctx.config.environment, Environment::Local)
+        && [body.imap_security, body.smtp_security].contains(&ConnectionSecurity::None)
+    {
+        return Err(InitError::InvalidImapAccount(
+            "unencrypted connections are not supported",
+        ));
+    }
+
+    MailProviderFactory::verify_imap_account(
+        ImapConfig {
+            host: body.imap_host.clone(),
+            port: body.imap_port,
+            security: connection_security(body.imap_security),
+            username: body.username.clone(),
+            password: body.password.clone(),
+        },
+        SmtpConfig {
+            host: body.smtp_host.clone(),
+            port: body.smtp_port,
+            security: connection_security(body.smtp_security),
+            username: body.username.clone(),
+            password: body.password.clone(),
+        },
+    )
+    .await
+    .map_err(|e| {
+        tracing::info!(error = ?e, "mail servers rejected imap account");
+        InitError::InvalidImapAccount("unable to log in to the mail servers")
+    })?;
+
+    let link = email_db_client::links::insert::upsert_link(
+        &ctx.db,
+        link::Link {
+            id: macro_uuid::generate_uuid_v7(),
+            macro_id: MacroUserIdStr::try_from(user_context.user_id.clone())?,
+            fusionauth_user_id: user_context.fusion_user_id.clone(),
+            email_address: EmailStr::try_from(body.email_address)?,
+            provider: link::UserProvider::Imap,
+            is_sync_active: true,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        },
+    )
+    .await
+    .context("Failed to upsert link")?;
+
+    let mut password_secret_id = None;
+    let result = async {
+        let secret_id = ctx
+            .mail_providers
+            .store_imap_password(link.id, &body.password)
+            .await?;
+        password_secret_id = Some(secret_id.clone());
+
+        email_db_client::imap_accounts::insert_imap_account(
+            &ctx.db,
+            &ImapAccount {
+                link_id: link.id,
+                imap_host: body.imap_host,
+                imap_port: body.imap_port,
+                imap_security: body.imap_security,
+                smtp_host: body.smtp_host,
+                smtp_port: body.smtp_port,
+                smtp_security: body.smtp_security,
+                username: body.username,
+                password_secret_id: secret_id,
+            },
+        )
+        .await?;
+
+        start_init_backfill(&ctx, &link).await
+    }
+    .await;
+
+    let backfill_job_id = match result {
+        Ok(backfill_job_id) => backfill_job_id,
+        Err(e) => {
+            // the imap account is deleted along with the link
+            if let Err(e) =
+                email_db_client::links::delete::delete_link_by_id(&ctx.db, link.id).await
+            {
+                tracing::error!(error = ?e, link_id = %link.id, "Failed to delete imap link");
+            }
+            if let Some(secret_id) = password_secret_id
+                && let Err(e) = ctx.mail_providers.delete_imap_password(&secret_id).await
+            {
+                tracing::error!(error = ?e, link_id = %link.id, "Failed to delete imap password");
             }
+            return Err(e);
+        }
+    };
 
-            // create job to backfill user's inbox history
-            let backfill_job = email_db_client::backfill::job::insert::create_backfill_job(
-                &ctx.db,
-                link.id,
-                link.fusionauth_user_id.as_str(),
-                None,
-            )
+    Ok((
+        StatusCode::OK,
+        Json(InitResponse {
+            link_id: link.id,
+            backfill_job_id,
+        }),
+    )
+        .into_response())
+}
+
+/// Creates the job that populates the initial threads of a new link and enqueues its first step.
+/// Returns the id of the job.
+async fn start_init_backfill(ctx: &ApiContext, link: &Link) -> Result<Uuid, InitError> {
+    // users can only have 3 jobs within past 24h and one backfill job per link in progress at a time
+    let recent_jobs = email_db_client::backfill::job::get::get_recent_jobs_by_fusionauth_user_id(
+        &ctx.db,
+        &link.fusionauth_user_id,
+    )
+    .await
+    .context("Failed to fetch jobs by macro id")?;
+
+    if recent_jobs.len() >= 3 && !link.email_address.0.as_ref().ends_with("@macro.com") {
+        tracing::info!(user_id = %link.macro_id, "Too many jobs error");
+        email_db_client::links::delete::delete_link_by_id(&ctx.db, link.id)
             .await
-            .context("Failed to create backfill job")?;
+            .context("Failed to delete link")?;
 
-            let ps_message = BackfillPubsubMessage {
-                link_id: link.id,
-                job_id: backfill_job.id,
-                backfill_operation: BackfillOperation::Init,
-            };
+        return Err(InitError::TooManyJobs);
+    }
+
+    // create job to backfill user's inbox history
+    let backfill_job = email_db_client::backfill::job::insert::create_backfill_job(
+        &ctx.db,
+        link.id,
+        link.fusionauth_user_id.as_str(),
+        None,
+    )
+    .await
+    .context("Failed to create backfill job")?;
 
-            if let Err(e) = ctx
-                .sqs_client
-                .enqueue_email_backfill_message(ps_message)
+    let ps_message = BackfillPubsubMessage {
+        link_id: link.id,
+        job_id: backfill_job.id,
+        backfill_operation: BackfillOperation::Init,
+    };
+
+    if let Err(e) = ctx
+        .sqs_client
+        .enqueue_email_backfill_message(ps_message)
+        .await
+    {
+        // Log the error
+        tracing::error!(error = ?e, backfill_id = %backfill_job.id, "Failed to enqueue backfill message");
+
+        // Update the job status to Failed
+        let db_pool = ctx.db.clone();
+        let job_id = backfill_job.id;
+        tokio::spawn(async move {
+            if let Err(update_err) =
+                email_db_client::backfill::job::update::update_backfill_job_status(
+                    &db_pool,
+                    job_id,
+                    BackfillJobStatus::Failed,
+                )
                 .await
             {
-                // Log the error
-                tracing::error!(error = ?e, backfill_id = %backfill_job.id, "Failed to enqueue backfill message");
-
-                // Update the job status to Failed
-                let db_pool = ctx.db.clone();
-                let job_id = backfill_job.id;
-                tokio::spawn(async move {
-                    if let Err(update_err) =
-                        email_db_client::backfill::job::update::update_backfill_job_status(
-                            &db_pool,
-                            job_id,
-                            BackfillJobStatus::Failed,
-                        )
-                        .await
-                    {
-                        tracing::error!(
-                            error = ?update_err,
-                            backfill_id = %job_id,
-                            "Failed to update backfill job status to Failed"
-                        );
-                    }
-                });
-
-                return Err(InitError::EnqueueError);
+                tracing::error!(
+                    error = ?update_err,
+                    backfill_id = %job_id,
+                    "Failed to update backfill job status to Failed"
+                );
             }
+        });
 
-            Ok((
-                StatusCode::OK,
-                Json(InitResponse {
-                    link_id: link.id,
-                    backfill_job_id: backfill_job.id,
-                }),
-            )
-                .into_response())
-        }
+        return Err(InitError::EnqueueError);
     }
+
+    Ok(backfill_job.id)
 }
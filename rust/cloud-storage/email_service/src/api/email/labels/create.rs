@@ -3,10 +3,12 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use mail_provider::{LabelError, MailProvider};
 use model::response::ErrorResponse;
 use model::user::UserContext;
 use models_email::service;
 use models_email::service::link::Link;
+use std::sync::Arc;
 use utoipa::ToSchema;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -36,22 +38,21 @@ pub struct CreateLabelResponse {
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, link, gmail_token), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+#[tracing::instrument(skip(ctx, user_context, link, provider), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
 pub async fn handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Json(request_body): Json<CreateLabelRequest>,
 ) -> Result<Response, Response> {
-    let created_label = ctx
-        .gmail_client
-        .create_label(gmail_token.as_str(), link.id, &request_body.label_name)
+    let created_label = provider
+        .create_label(link.id, &request_body.label_name)
         .await
         .map_err(|e| {
-            tracing::error!(error=?e, "gmail call to create label failed");
+            tracing::error!(error=?e, "provider call to create label failed");
             match e {
-                gmail_client::GmailError::Conflict(_) => (
+                LabelError::Conflict => (
                     StatusCode::CONFLICT,
                     Json(ErrorResponse {
                         message: "label with that name already exists",
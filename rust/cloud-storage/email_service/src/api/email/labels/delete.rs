@@ -3,9 +3,11 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use mail_provider::{LabelError, MailProvider};
 use model::response::{EmptyResponse, ErrorResponse};
 use model::user::UserContext;
 use models_email::service::link::Link;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Delete a label.
@@ -25,12 +27,12 @@ use uuid::Uuid;
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, link, gmail_token), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+#[tracing::instrument(skip(ctx, user_context, link, provider), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
 pub async fn handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Path(label_id): Path<Uuid>,
 ) -> Result<Response, Response> {
     let label = email_db_client::labels::get::fetch_label_by_id(&ctx.db, label_id, link.id)
@@ -55,18 +57,15 @@ pub async fn handler(
                 .into_response()
         })?;
 
-    let gmail_result = ctx
-        .gmail_client
-        .delete_label(gmail_token.as_str(), &label.provider_label_id)
-        .await;
+    let provider_result = provider.delete_label(&label.provider_label_id).await;
 
-    if let Err(e) = &gmail_result {
+    if let Err(e) = &provider_result {
         match e {
-            gmail_client::GmailError::NotFound(_) => {
+            LabelError::NotFound => {
                 tracing::warn!(
                     label_id = %label_id,
                     provider_label_id = %label.provider_label_id,
-                    "Label not found in Gmail, but continuing with database deletion"
+                    "Label not found in provider, but continuing with database deletion"
                 );
             }
             _ => {
@@ -74,12 +73,12 @@ pub async fn handler(
                     error = ?e,
                     label_id = %label_id,
                     provider_label_id = %label.provider_label_id,
-                    "Gmail API call to delete label failed"
+                    "Provider call to delete label failed"
                 );
                 return Err((
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse {
-                        message: "delete label gmail api call failed",
+                        message: "delete label provider call failed",
                     }),
                 )
                     .into_response());
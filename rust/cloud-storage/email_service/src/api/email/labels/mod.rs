@@ -13,7 +13,7 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
         .route("/:id", delete(delete::handler))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
-            crate::api::middleware::gmail_token::attach_gmail_token,
+            crate::api::middleware::mail_provider::attach_mail_provider,
         ))
         .route("/", get(list::handler))
         .layer(axum::middleware::from_fn_with_state(
@@ -5,8 +5,7 @@ use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
-use futures::{StreamExt, stream};
-use gmail_client::GmailClient;
+use mail_provider::MailProvider;
 use model::response::ErrorResponse;
 use model::user::UserContext;
 use models_email::email::service::message::SimpleMessage;
@@ -14,6 +13,7 @@ use models_email::service;
 use models_email::service::link::Link;
 use sqlx::PgPool;
 use sqlx::types::Uuid;
+use std::sync::Arc;
 use utoipa::ToSchema;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
@@ -46,11 +46,11 @@ pub struct UpdateLabelBatchResponse {
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, gmail_token, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+#[tracing::instrument(skip(ctx, user_context, provider, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
 pub async fn handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     link: Extension<Link>,
     Json(body): Json<UpdateLabelBatchRequest>,
 ) -> Result<Response, Response> {
@@ -121,13 +121,10 @@ pub async fn handler(
         tracing::warn!(message_ids=?missing_ids, "unable to find messages in db");
     }
 
-    let gmail_access_token = gmail_token.as_str();
-
     let (successful_ids, failed_ids) = if body.value {
         add_label_to_messages(
             &ctx.db,
-            gmail_access_token,
-            &ctx.gmail_client,
+            provider.as_ref(),
             db_messages,
             label.provider_label_id.as_str(),
         )
@@ -145,8 +142,7 @@ pub async fn handler(
     } else {
         remove_label_from_messages(
             &ctx.db,
-            gmail_access_token,
-            &ctx.gmail_client,
+            provider.as_ref(),
             db_messages,
             label.provider_label_id.as_str(),
         )
@@ -213,11 +209,10 @@ pub async fn handler(
         .into_response())
 }
 
-// add a given label to a batch of messages in gmail and db
+// add a given label to a batch of messages in the provider and db
 pub async fn add_label_to_messages(
     db: &PgPool,
-    gmail_access_token: &str,
-    gmail_client: &GmailClient,
+    provider: &dyn MailProvider,
     messages: Vec<SimpleMessage>,
     provider_label_id: &str,
 ) -> anyhow::Result<(Vec<Uuid>, Vec<Uuid>)> {
@@ -229,64 +224,15 @@ pub async fn add_label_to_messages(
         return Err(anyhow!("Provider label ID cannot be empty"));
     }
 
-    // First clone for the Gmail API operations
-    let api_provider_label_id = provider_label_id.to_string();
-
-    // Second clone for the database operations
-    let db_provider_label_id = provider_label_id.to_string();
-
-    // Use into_iter() on a clone of messages to avoid lifetime issues
-    let label_tasks = messages.clone().into_iter().map(move |message| {
-        let gmail_client = gmail_client.clone();
-        let gmail_access_token = gmail_access_token.to_string();
-        let provider_label_id = api_provider_label_id.clone();
-
-        async move {
-            let labels_to_add = vec![provider_label_id.clone()];
-            let labels_to_remove = vec![];
-
-            // First update in Gmail
-            let gmail_result = gmail_client
-                .modify_message_labels(
-                    &gmail_access_token,
-                    &message.provider_id.clone().unwrap_or_default(),
-                    &labels_to_add,
-                    &labels_to_remove,
-                )
-                .await;
-
-            if let Err(e) = gmail_result {
-                tracing::error!(
-                    error = ?e,
-                    message_id = %message.db_id,
-                    provider_id = %message.provider_id.unwrap_or_default(),
-                    "Failed to modify labels in Gmail"
-                );
-                return (message.db_id, Err(e));
-            }
-
-            (message.db_id, Ok(()))
-        }
-    });
-
-    // Process tasks with limited concurrency
-    const MAX_CONCURRENT: usize = 10;
-    let results = stream::iter(label_tasks)
-        .buffer_unordered(MAX_CONCURRENT)
-        .collect::<Vec<_>>()
+    // Update in the provider first
+    let (mut successful_msg_ids, mut failed_msg_ids) = provider
+        .batch_modify_labels(
+            provider_id_tuples(&messages),
+            vec![provider_label_id.to_string()],
+            vec![],
+        )
         .await;
 
-    // Separate successful and failed messages
-    let mut successful_msg_ids = Vec::new();
-    let mut failed_msg_ids = Vec::new();
-
-    for (msg_id, result) in results {
-        match result {
-            Ok(_) => successful_msg_ids.push(msg_id),
-            Err(_) => failed_msg_ids.push(msg_id),
-        }
-    }
-
     // If there are any successful messages, update the database in bulk
     if !successful_msg_ids.is_empty() {
         let link_id = messages.first().map(|m| m.link_id).unwrap_or_default();
@@ -295,7 +241,7 @@ pub async fn add_label_to_messages(
         match email_db_client::labels::insert::insert_message_labels_batch(
             db,
             &successful_msg_ids,
-            &db_provider_label_id,
+            provider_label_id,
             link_id,
         )
         .await
@@ -305,7 +251,7 @@ pub async fn add_label_to_messages(
                 tracing::error!(
                     error = ?e,
                     message_ids = successful_msg_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
-                    provider_label_id = %db_provider_label_id,
+                    provider_label_id = %provider_label_id,
                     "Failed to add label to messages in database"
                 );
                 // Move all messages to failed if database update fails
@@ -321,11 +267,10 @@ pub async fn add_label_to_messages(
     Ok((successful_msg_ids, failed_msg_ids))
 }
 
-/// Remove a label from multiple messages in gmail and in db concurrently
+/// Remove a label from multiple messages in the provider and in db
 pub async fn remove_label_from_messages(
     db: &PgPool,
-    gmail_access_token: &str,
-    gmail_client: &GmailClient,
+    provider: &dyn MailProvider,
     messages: Vec<SimpleMessage>,
     provider_label_id: &str,
 ) -> anyhow::Result<(Vec<Uuid>, Vec<Uuid>)> {
@@ -337,64 +282,15 @@ pub async fn remove_label_from_messages(
         return Err(anyhow!("Provider label ID cannot be empty"));
     }
 
-    // First clone for the Gmail API operations
-    let api_provider_label_id = provider_label_id.to_string();
-
-    // Second clone for the database operations
-    let db_provider_label_id = provider_label_id.to_string();
-
-    // Use into_iter() on a clone of messages to avoid lifetime issues
-    let label_tasks = messages.clone().into_iter().map(move |message| {
-        let gmail_client = gmail_client.clone();
-        let gmail_access_token = gmail_access_token.to_string();
-        let provider_label_id = api_provider_label_id.clone();
-
-        async move {
-            let labels_to_add = vec![];
-            let labels_to_remove = vec![provider_label_id.clone()];
-
-            // Update in Gmail - remove the label
-            let gmail_result = gmail_client
-                .modify_message_labels(
-                    &gmail_access_token,
-                    &message.provider_id.clone().unwrap_or_default(),
-                    &labels_to_add,
-                    &labels_to_remove,
-                )
-                .await;
-
-            if let Err(e) = gmail_result {
-                tracing::error!(
-                    error = ?e,
-                    message_id = %message.db_id,
-                    provider_id = %message.provider_id.unwrap_or_default(),
-                    "Failed to remove label in Gmail"
-                );
-                return (message.db_id, Err(e));
-            }
-
-            (message.db_id, Ok(()))
-        }
-    });
-
-    // Process tasks with limited concurrency
-    const MAX_CONCURRENT: usize = 10;
-    let results = stream::iter(label_tasks)
-        .buffer_unordered(MAX_CONCURRENT)
-        .collect::<Vec<_>>()
+    // Update in the provider first - remove the label
+    let (mut successful_msg_ids, mut failed_msg_ids) = provider
+        .batch_modify_labels(
+            provider_id_tuples(&messages),
+            vec![],
+            vec![provider_label_id.to_string()],
+        )
         .await;
 
-    // Separate successful and failed messages
-    let mut successful_msg_ids = Vec::new();
-    let mut failed_msg_ids = Vec::new();
-
-    for (msg_id, result) in results {
-        match result {
-            Ok(_) => successful_msg_ids.push(msg_id),
-            Err(_) => failed_msg_ids.push(msg_id),
-        }
-    }
-
     // If there are any successful messages, update the database in bulk
     if !successful_msg_ids.is_empty() {
         let link_id = messages.first().map(|m| m.link_id).unwrap_or_default();
@@ -403,7 +299,7 @@ pub async fn remove_label_from_messages(
         match email_db_client::labels::delete::delete_message_labels_batch(
             db,
             &successful_msg_ids,
-            &db_provider_label_id,
+            provider_label_id,
             link_id,
         )
         .await
@@ -413,7 +309,7 @@ pub async fn remove_label_from_messages(
                 tracing::error!(
                     error = ?e,
                     message_ids = successful_msg_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
-                    provider_label_id = %db_provider_label_id,
+                    provider_label_id = %provider_label_id,
                     "Failed to remove label from messages in database"
                 );
                 // Move all messages to failed if database update fails
@@ -428,3 +324,16 @@ pub async fn remove_label_from_messages(
 
     Ok((successful_msg_ids, failed_msg_ids))
 }
+
+/// The (db id, provider id) pairs of messages
+fn provider_id_tuples(messages: &[SimpleMessage]) -> Vec<(Uuid, String)> {
+    messages
+        .iter()
+        .map(|message| {
+            (
+                message.db_id,
+                message.provider_id.clone().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
@@ -1,9 +1,10 @@
 pub(crate) mod get;
 pub(crate) mod labels;
+pub(crate) mod outbox;
 pub(crate) mod send;
 
 use axum::Router;
-use axum::routing::{get, patch, post};
+use axum::routing::{delete, get, patch, post};
 
 use crate::api::ApiContext;
 
@@ -15,17 +16,18 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
             "/",
             post(send::send_handler).layer(axum::middleware::from_fn_with_state(
                 state.clone(),
-                crate::api::middleware::gmail_token::attach_gmail_token,
+                crate::api::middleware::mail_provider::attach_mail_provider,
             )),
         )
         .route(
             "/labels",
             patch(labels::handler).layer(axum::middleware::from_fn_with_state(
                 state.clone(),
-                crate::api::middleware::gmail_token::attach_gmail_token,
+                crate::api::middleware::mail_provider::attach_mail_provider,
             )),
         )
         .route("/batch", post(get::batch_handler))
+        .route("/:id/outbox", delete(outbox::cancel_handler))
         .layer(axum::middleware::from_fn_with_state(
             state.email_service,
             crate::api::middleware::link::attach_link_context,
@@ -0,0 +1,101 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::service::link::Link;
+use uuid::Uuid;
+
+fn error(status_code: StatusCode, message: &'static str) -> Response {
+    (status_code, Json(ErrorResponse { message })).into_response()
+}
+
+/// Cancel sending a message that is waiting in the outbox. The message is kept as a draft.
+#[utoipa::path(
+    delete,
+    tag = "Messages",
+    path = "/email/messages/{id}/outbox",
+    operation_id = "cancel_outbox_message",
+    params(
+        ("id" = Uuid, Path, description = "Message ID."),
+    ),
+    responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 409, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn cancel_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(message_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let message = email_db_client::messages::get_simple_messages::get_simple_message(
+        &ctx.db,
+        &message_id,
+        &link.fusionauth_user_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch message");
+        error(StatusCode::INTERNAL_SERVER_ERROR, "unable to fetch message")
+    })?
+    .ok_or_else(|| error(StatusCode::NOT_FOUND, "message not found"))?;
+
+    let result = async {
+        let mut tx = ctx.db.begin().await?;
+
+        let cancelled =
+            email_db_client::messages::outbox::cancel_outbox_message(&mut tx, link.id, message_id)
+                .await?;
+
+        if cancelled {
+            // the thread no longer has a pending outbound message
+            email_db_client::threads::update::update_thread_metadata(
+                &mut tx,
+                message.thread_db_id,
+                link.id,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        anyhow::Ok(cancelled)
+    }
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to cancel outbox message");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to cancel outbox message",
+        )
+    })?;
+
+    if result {
+        return Ok((StatusCode::OK, Json(EmptyResponse::default())).into_response());
+    }
+
+    // tell apart messages that are no longer in the outbox from ones that are being sent
+    let outbox_message =
+        email_db_client::messages::outbox::get_outbox_message(&ctx.db, link.id, message_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch outbox message");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to fetch outbox message",
+                )
+            })?;
+
+    match outbox_message {
+        Some(_) => Err(error(StatusCode::CONFLICT, "message is already being sent")),
+        None => Err(error(StatusCode::NOT_FOUND, "message is not in the outbox")),
+    }
+}
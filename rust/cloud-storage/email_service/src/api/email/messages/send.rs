@@ -1,4 +1,5 @@
 use crate::api::context::ApiContext;
+use crate::api::email::drafts::create::insert_draft;
 use crate::api::email::validation::{self, ValidationError};
 use crate::util::gmail::send;
 use anyhow::Context;
@@ -9,12 +10,14 @@ use axum::{Extension, Json};
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use email_db_client::messages::insert::insert_message_to_send;
+use mail_provider::MailProvider;
 use model::response::ErrorResponse;
 use model::user::UserContext;
 use models_email::email::service::address::ContactInfo;
 use models_email::email::service::{message, thread};
 use models_email::service::link::Link;
-use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::types::chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 use utoipa::ToSchema;
@@ -27,6 +30,9 @@ pub enum SendMessageError {
     #[error("Sender contact not found")]
     SenderContactNotFound,
 
+    #[error("Message is already being sent")]
+    MessageAlreadySending,
+
     #[error("Failed to decode base64 HTML body")]
     Base64DecodeError(#[from] base64::DecodeError),
 
@@ -38,6 +44,9 @@ pub enum SendMessageError {
 
     #[error("A database transaction error occurred")]
     TransactionError(#[from] sqlx::Error),
+
+    #[error("A database error occurred")]
+    DatabaseError(anyhow::Error),
 }
 
 impl IntoResponse for SendMessageError {
@@ -45,12 +54,13 @@ impl IntoResponse for SendMessageError {
         let status_code = match &self {
             SendMessageError::Validation(e) => e.status_code(),
             SendMessageError::SenderContactNotFound => StatusCode::INTERNAL_SERVER_ERROR,
+            SendMessageError::MessageAlreadySending => StatusCode::CONFLICT,
             SendMessageError::Base64DecodeError(_) | SendMessageError::Utf8Error(_) => {
                 StatusCode::BAD_REQUEST
             }
-            SendMessageError::GmailSendError(_) | SendMessageError::TransactionError(_) => {
-                StatusCode::INTERNAL_SERVER_ERROR
-            }
+            SendMessageError::GmailSendError(_)
+            | SendMessageError::TransactionError(_)
+            | SendMessageError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         if status_code.is_server_error() {
@@ -74,9 +84,16 @@ pub struct SendMessageRequest {
 #[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
 pub struct SendMessageResponse {
     pub message: message::MessageToSend,
+    /// set when the message was put in the outbox instead of being sent right away. until then
+    /// the send can be cancelled, or the message edited by sending it again with the same db_id
+    pub send_at: Option<DateTime<Utc>>,
 }
 
-/// Send an email message.
+/// Send an email message. If the user has an undo window configured, the message is put in the
+/// outbox and sent by the scheduled worker once the window has passed. The worker checks the
+/// outbox every minute, so the message goes out up to a minute after the window passes, e.g.
+/// 10 to 70 seconds after sending with a 10 second window. The window is how long the send can be
+/// undone for, not when the message is delivered.
 #[utoipa::path(
     post,
     tag = "Messages",
@@ -86,16 +103,18 @@ pub struct SendMessageResponse {
 
     responses(
             (status = 201, body=SendMessageResponse),
+            (status = 202, body=SendMessageResponse),
             (status = 400, body=ErrorResponse),
             (status = 401, body=ErrorResponse),
+            (status = 409, body=ErrorResponse),
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, gmail_token, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+#[tracing::instrument(skip(ctx, user_context, provider, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
 pub async fn send_handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     link: Extension<Link>,
     Json(request_body): Json<SendMessageRequest>,
 ) -> Result<Response, SendMessageError> {
@@ -122,11 +141,6 @@ pub async fn send_handler(
         photo_url: sender_contact.photo_url,
     };
 
-    // Generate email headers that are used for threading
-    let (parent_message_id, references) =
-        send::generate_email_threading_headers(&ctx.db, message_to_send.replying_to_id, link.id)
-            .await;
-
     // html comes in as a base64 encoded string, need to decode before inserting
     if let Some(html_body) = message_to_send.body_html {
         let decoded_html = URL_SAFE_NO_PAD.decode(html_body.as_bytes())?;
@@ -136,14 +150,39 @@ pub async fn send_handler(
         message_to_send.body_html = Some(decoded_html_str);
     }
 
+    // a message the scheduled worker already picked up from the outbox can't be changed anymore
+    if let Some(db_id) = message_to_send.db_id {
+        let outbox_message =
+            email_db_client::messages::outbox::get_outbox_message(&ctx.db, link.id, db_id)
+                .await
+                .map_err(SendMessageError::DatabaseError)?;
+
+        if outbox_message.is_some_and(|outbox_message| outbox_message.dispatched) {
+            return Err(SendMessageError::MessageAlreadySending);
+        }
+    }
+
+    let settings = email_db_client::settings::fetch_settings(&ctx.db, link.id)
+        .await
+        .map_err(SendMessageError::DatabaseError)?;
+
+    if settings.undo_send_seconds > 0 {
+        let send_at = Utc::now() + Duration::seconds(settings.undo_send_seconds.into());
+        return queue_in_outbox(&ctx, &link, message_to_send, send_at).await;
+    }
+
+    // Generate email headers that are used for threading
+    let (parent_message_id, references) =
+        send::generate_email_threading_headers(&ctx.db, message_to_send.replying_to_id, link.id)
+            .await;
+
     // if we are creating a new thread, we need to have a ts for the message in the db less than
     // the actual sent time. this is so the value gets updated in the webhook when gmail sends us the
     // processed message post-send
     let before_send_ts = Utc::now();
 
-    ctx.gmail_client
+    provider
         .send_message(
-            gmail_token.as_str(),
             &mut message_to_send,
             &from_contact,
             parent_message_id,
@@ -163,6 +202,7 @@ pub async fn send_handler(
                 StatusCode::CREATED,
                 Json(SendMessageResponse {
                     message: message_to_send,
+                    send_at: None,
                 }),
             )
                 .into_response())
@@ -176,6 +216,89 @@ pub async fn send_handler(
     }
 }
 
+/// Stores the message as a draft and puts it in the outbox, to be sent at `send_at`
+async fn queue_in_outbox(
+    ctx: &ApiContext,
+    link: &Link,
+    mut message_to_send: message::MessageToSend,
+    send_at: DateTime<Utc>,
+) -> Result<Response, SendMessageError> {
+    // the outbox replaces any schedule the draft had
+    message_to_send.send_time = None;
+
+    let mut tx = ctx.db.begin().await?;
+
+    let result = insert_outbox_message(
+        &mut tx,
+        &mut message_to_send,
+        link.email_address.0.as_ref(),
+        send_at,
+    )
+    .await;
+
+    match result {
+        Ok(true) => {
+            tx.commit().await?;
+            Ok((
+                StatusCode::ACCEPTED,
+                Json(SendMessageResponse {
+                    message: message_to_send,
+                    send_at: Some(send_at),
+                }),
+            )
+                .into_response())
+        }
+        Ok(false) => {
+            tx.rollback().await?;
+            Err(SendMessageError::MessageAlreadySending)
+        }
+        Err(e) => {
+            if let Err(rollback_err) = tx.rollback().await {
+                tracing::error!(error=?rollback_err, "Failed to rollback transaction after outbox insert failure");
+            }
+            Err(SendMessageError::DatabaseError(e))
+        }
+    }
+}
+
+/// Returns false if the message was already picked up from the outbox for sending
+async fn insert_outbox_message(
+    tx: &mut sqlx::PgConnection,
+    message_to_send: &mut message::MessageToSend,
+    from_email: &str,
+    send_at: DateTime<Utc>,
+) -> anyhow::Result<bool> {
+    insert_draft(tx, message_to_send, from_email).await?;
+
+    // both are populated when inserting the draft
+    let message_id = message_to_send.db_id.context("draft has no db id")?;
+    let thread_id = message_to_send
+        .thread_db_id
+        .context("draft has no thread db id")?;
+
+    let queued = email_db_client::messages::outbox::upsert_outbox_message(
+        tx,
+        message_to_send.link_id,
+        message_id,
+        send_at,
+    )
+    .await?;
+
+    if !queued {
+        return Ok(false);
+    }
+
+    email_db_client::threads::update::update_thread_outbound_ts(
+        tx,
+        thread_id,
+        message_to_send.link_id,
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(true)
+}
+
 async fn insert_sent_message(
     tx: &mut sqlx::PgConnection,
     message_to_send: &mut message::MessageToSend,
@@ -192,7 +315,7 @@ async fn insert_sent_message(
         None => {
             let thread = thread::Thread {
                 db_id: None,
-                provider_id: Some(thread_provider_id.clone().unwrap()), // safe bc it always gets populated in gmail_client
+                provider_id: Some(thread_provider_id.clone().unwrap()), // safe bc it always gets populated by the mail provider
                 link_id,
                 // if we're creating a thread with a sent message, it's not visible in the inbox
                 inbox_visible: true,
@@ -245,5 +368,12 @@ async fn insert_sent_message(
     .await
     .context("unable to insert message to send")?;
 
+    // a message sent right away no longer needs to wait in the outbox
+    if let Some(db_id) = message_to_send.db_id {
+        email_db_client::messages::outbox::delete_outbox_message(&mut *tx, link_id, db_id)
+            .await
+            .context("unable to delete outbox message")?;
+    }
+
     Ok(())
 }
@@ -3,6 +3,7 @@ use axum::routing::post;
 
 use crate::api::ApiContext;
 
+pub(crate) mod archives;
 pub(crate) mod attachments;
 pub(crate) mod backfill;
 pub(crate) mod contacts;
@@ -11,27 +12,35 @@ pub(crate) mod init;
 pub(crate) mod labels;
 pub(crate) mod links;
 pub(crate) mod messages;
+pub(crate) mod rules;
 pub(crate) mod settings;
+pub(crate) mod subscriptions;
 pub(crate) mod sync;
+pub(crate) mod templates;
 pub(crate) mod threads;
 pub(crate) mod validation;
 
 pub fn router(state: ApiContext) -> Router<ApiContext> {
     Router::new()
+        .nest("/archives", archives::router(state.clone()))
         .nest("/attachments", attachments::router(state.clone()))
         .nest("/labels", labels::router(state.clone()))
         .nest("/threads", threads::router(state.clone()))
         .nest("/drafts", drafts::router(state.clone()))
         .nest("/messages", messages::router(state.clone()))
         .nest("/links", links::router())
-        .nest("/contacts", contacts::router())
+        .nest("/contacts", contacts::router(state.clone()))
         .nest("/backfill", backfill::router(state.clone()))
+        .nest("/rules", rules::router(state.clone()))
         .nest("/settings", settings::router(state.clone()))
+        .nest("/subscriptions", subscriptions::router(state.clone()))
         .nest("/sync", sync::router(state.clone()))
+        .nest("/templates", templates::router(state.clone()))
         // deleting all user info from the db can take a long time - prevent connection from dropping
         .layer(axum::middleware::from_fn(
             macro_middleware::connection_drop_prevention_handler,
         ))
+        .route("/init/imap", post(init::imap_handler))
         .route(
             "/init",
             post(init::handler).layer(axum::middleware::from_fn_with_state(
@@ -0,0 +1,77 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::gmail::webhook::{ApplyRulePayload, WebhookOperation, WebhookPubsubMessage};
+use models_email::service::link::Link;
+use uuid::Uuid;
+
+/// Apply an email rule to existing messages. Runs in the background, forwarding and
+/// notification actions are skipped.
+#[utoipa::path(
+    post,
+    tag = "Rules",
+    path = "/email/rules/{id}/apply",
+    operation_id = "apply_rule",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID."),
+    ),
+    responses(
+            (status = 202, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let rule = email_db_client::rules::fetch_rule_by_id(&ctx.db, rule_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch rule");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "unable to fetch rule",
+                }),
+            )
+                .into_response()
+        })?;
+
+    if rule.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "rule not found",
+            }),
+        )
+            .into_response());
+    }
+
+    ctx.sqs_client
+        .enqueue_gmail_webhook_notification(WebhookPubsubMessage {
+            link_id: link.id,
+            operation: WebhookOperation::ApplyRule(ApplyRulePayload { rule_id }),
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to enqueue apply rule operation");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "unable to apply rule",
+                }),
+            )
+                .into_response()
+        })?;
+
+    Ok((StatusCode::ACCEPTED, Json(EmptyResponse::default())).into_response())
+}
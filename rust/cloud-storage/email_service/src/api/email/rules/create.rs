@@ -0,0 +1,89 @@
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service;
+use models_email::service::link::Link;
+use models_email::service::rule::{RuleAction, RuleCondition};
+use utoipa::ToSchema;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct CreateRuleRequest {
+    pub name: String,
+    #[serde(default = "serde_utils::default_true")]
+    pub is_enabled: bool,
+    /// defaults to after every existing rule
+    pub position: Option<i32>,
+    /// whether every condition has to match, or any of them
+    #[serde(default = "serde_utils::default_true")]
+    pub match_all: bool,
+    #[serde(default)]
+    pub stop_processing: bool,
+    pub conditions: Vec<RuleCondition>,
+    pub actions: Vec<RuleAction>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct RuleResponse {
+    pub rule: service::rule::Rule,
+}
+
+/// Create an email rule. The rule applies to messages received from now on, use the apply
+/// endpoint to run it on existing messages.
+#[utoipa::path(
+    post,
+    tag = "Rules",
+    path = "/email/rules",
+    operation_id = "create_rule",
+    request_body = CreateRuleRequest,
+    responses(
+            (status = 201, body=RuleResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Json(request_body): Json<CreateRuleRequest>,
+) -> Result<Response, Response> {
+    super::validate_rule(
+        &ctx.db,
+        link.id,
+        &request_body.name,
+        &request_body.conditions,
+        &request_body.actions,
+    )
+    .await?;
+
+    let rule = email_db_client::rules::insert_rule(
+        &ctx.db,
+        link.id,
+        request_body.name.trim(),
+        request_body.is_enabled,
+        request_body.position,
+        request_body.match_all,
+        request_body.stop_processing,
+        &request_body.conditions,
+        &request_body.actions,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to insert rule");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to insert rule",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((StatusCode::CREATED, Json(RuleResponse { rule })).into_response())
+}
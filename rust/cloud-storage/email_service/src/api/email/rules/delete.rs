@@ -0,0 +1,58 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::service::link::Link;
+use uuid::Uuid;
+
+/// Delete an email rule.
+#[utoipa::path(
+    delete,
+    tag = "Rules",
+    path = "/email/rules/{id}",
+    operation_id = "delete_rule",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID."),
+    ),
+    responses(
+            (status = 204, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(rule_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let deleted = email_db_client::rules::delete_rule(&ctx.db, rule_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to delete rule");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "unable to delete rule",
+                }),
+            )
+                .into_response()
+        })?;
+
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "rule not found",
+            }),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::NO_CONTENT, Json(EmptyResponse::default())).into_response())
+}
@@ -0,0 +1,50 @@
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service;
+use models_email::service::link::Link;
+use utoipa::ToSchema;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListRulesResponse {
+    /// the rules, in the order they are evaluated
+    pub rules: Vec<service::rule::Rule>,
+}
+
+/// List user email rules.
+#[utoipa::path(
+    get,
+    tag = "Rules",
+    path = "/email/rules",
+    operation_id = "list_rules",
+    responses(
+            (status = 200, body=ListRulesResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+) -> Result<Response, Response> {
+    let rules = email_db_client::rules::fetch_rules_by_link_id(&ctx.db, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch rules");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "unable to fetch rules",
+                }),
+            )
+                .into_response()
+        })?;
+
+    Ok((StatusCode::OK, Json(ListRulesResponse { rules })).into_response())
+}
@@ -0,0 +1,109 @@
+pub mod apply;
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod patch;
+pub mod preview;
+
+use axum::Json;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post};
+use macro_user_id::email::EmailStr;
+use model::response::ErrorResponse;
+use models_email::service::rule::{RuleAction, RuleCondition};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::ApiContext;
+
+pub fn router(state: ApiContext) -> Router<ApiContext> {
+    Router::new()
+        .route("/", get(list::handler))
+        .route("/", post(create::handler))
+        .route("/preview", post(preview::handler))
+        .route("/:id", patch(patch::handler))
+        .route("/:id", delete(delete::handler))
+        .route("/:id/apply", post(apply::handler))
+        .layer(axum::middleware::from_fn_with_state(
+            state.email_service,
+            crate::api::middleware::link::attach_link_context,
+        ))
+}
+
+fn bad_request(message: &'static str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { message })).into_response()
+}
+
+/// Validates the conditions and actions of a rule. Labels have to belong to the link.
+async fn validate_rule(
+    db: &PgPool,
+    link_id: Uuid,
+    name: &str,
+    conditions: &[RuleCondition],
+    actions: &[RuleAction],
+) -> Result<(), Response> {
+    if name.trim().is_empty() {
+        return Err(bad_request("rule name cannot be empty"));
+    }
+
+    if conditions.is_empty() {
+        return Err(bad_request("rule must have at least one condition"));
+    }
+
+    if actions.is_empty() {
+        return Err(bad_request("rule must have at least one action"));
+    }
+
+    let has_empty_value = conditions.iter().any(|condition| match condition {
+        RuleCondition::From { value }
+        | RuleCondition::Recipient { value }
+        | RuleCondition::SubjectContains { value }
+        | RuleCondition::BodyContains { value } => value.trim().is_empty(),
+        RuleCondition::HasAttachment | RuleCondition::HasLabel { .. } => false,
+    });
+    if has_empty_value {
+        return Err(bad_request("rule condition value cannot be empty"));
+    }
+
+    let has_invalid_forward = actions.iter().any(|action| match action {
+        RuleAction::Forward { email } => EmailStr::parse_from_str(email).is_err(),
+        _ => false,
+    });
+    if has_invalid_forward {
+        return Err(bad_request("invalid forwarding address"));
+    }
+
+    let label_ids = conditions
+        .iter()
+        .filter_map(|condition| match condition {
+            RuleCondition::HasLabel { label_id } => Some(*label_id),
+            _ => None,
+        })
+        .chain(actions.iter().filter_map(|action| match action {
+            RuleAction::ApplyLabel { label_id } => Some(*label_id),
+            _ => None,
+        }));
+
+    for label_id in label_ids {
+        let label = email_db_client::labels::get::fetch_label_by_id(db, label_id, link_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch label");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "unable to fetch label",
+                    }),
+                )
+                    .into_response()
+            })?;
+
+        if label.is_none() {
+            return Err(bad_request("rule references a label that does not exist"));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use crate::api::context::ApiContext;
+use crate::api::email::rules::create::RuleResponse;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::rule::{RuleAction, RuleCondition};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Fields that are left out are not changed
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PatchRuleRequest {
+    pub name: Option<String>,
+    pub is_enabled: Option<bool>,
+    pub position: Option<i32>,
+    pub match_all: Option<bool>,
+    pub stop_processing: Option<bool>,
+    pub conditions: Option<Vec<RuleCondition>>,
+    pub actions: Option<Vec<RuleAction>>,
+}
+
+fn internal_error(message: &'static str) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse { message }),
+    )
+        .into_response()
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            message: "rule not found",
+        }),
+    )
+        .into_response()
+}
+
+/// Update an email rule.
+#[utoipa::path(
+    patch,
+    tag = "Rules",
+    path = "/email/rules/{id}",
+    operation_id = "patch_rule",
+    params(
+        ("id" = Uuid, Path, description = "Rule ID."),
+    ),
+    request_body = PatchRuleRequest,
+    responses(
+            (status = 200, body=RuleResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(rule_id): Path<Uuid>,
+    Json(request_body): Json<PatchRuleRequest>,
+) -> Result<Response, Response> {
+    let mut rule = email_db_client::rules::fetch_rule_by_id(&ctx.db, rule_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch rule");
+            internal_error("unable to fetch rule")
+        })?
+        .ok_or_else(not_found)?;
+
+    if let Some(name) = request_body.name {
+        rule.name = name.trim().to_string();
+    }
+    if let Some(is_enabled) = request_body.is_enabled {
+        rule.is_enabled = is_enabled;
+    }
+    if let Some(position) = request_body.position {
+        rule.position = position;
+    }
+    if let Some(match_all) = request_body.match_all {
+        rule.match_all = match_all;
+    }
+    if let Some(stop_processing) = request_body.stop_processing {
+        rule.stop_processing = stop_processing;
+    }
+    if let Some(conditions) = request_body.conditions {
+        rule.conditions = conditions;
+    }
+    if let Some(actions) = request_body.actions {
+        rule.actions = actions;
+    }
+
+    super::validate_rule(
+        &ctx.db,
+        link.id,
+        &rule.name,
+        &rule.conditions,
+        &rule.actions,
+    )
+    .await?;
+
+    let rule = email_db_client::rules::update_rule(&ctx.db, &rule)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to update rule");
+            internal_error("unable to update rule")
+        })?
+        .ok_or_else(not_found)?;
+
+    Ok((StatusCode::OK, Json(RuleResponse { rule })).into_response())
+}
@@ -0,0 +1,126 @@
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::rule::{Rule, RuleCondition};
+use sqlx::types::chrono::Utc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How many of the most recent received messages a preview is evaluated against
+const PREVIEW_MESSAGE_LIMIT: i64 = 500;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PreviewRuleRequest {
+    /// whether every condition has to match, or any of them
+    #[serde(default = "serde_utils::default_true")]
+    pub match_all: bool,
+    pub conditions: Vec<RuleCondition>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PreviewRuleThread {
+    pub thread_id: Uuid,
+    /// the messages of the thread the rule would apply to
+    pub message_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PreviewRuleResponse {
+    /// the matching threads, most recent first
+    pub threads: Vec<PreviewRuleThread>,
+    /// how many messages the rule was evaluated against
+    pub scanned_message_count: usize,
+}
+
+/// Preview which existing threads a rule's conditions match, without applying any actions.
+#[utoipa::path(
+    post,
+    tag = "Rules",
+    path = "/email/rules/preview",
+    operation_id = "preview_rule",
+    request_body = PreviewRuleRequest,
+    responses(
+            (status = 200, body=PreviewRuleResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Json(request_body): Json<PreviewRuleRequest>,
+) -> Result<Response, Response> {
+    if request_body.conditions.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "rule must have at least one condition",
+            }),
+        )
+            .into_response());
+    }
+
+    let messages = email_db_client::rules::messages::fetch_rule_messages_paginated(
+        &ctx.db,
+        link.id,
+        PREVIEW_MESSAGE_LIMIT,
+        0,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch messages");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to fetch messages",
+            }),
+        )
+            .into_response()
+    })?;
+
+    let rule = Rule {
+        id: Uuid::nil(),
+        link_id: link.id,
+        name: String::new(),
+        is_enabled: true,
+        position: 0,
+        match_all: request_body.match_all,
+        stop_processing: false,
+        conditions: request_body.conditions,
+        actions: Vec::new(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    // messages are newest first, so threads keep the order of their most recent match
+    let mut threads: Vec<PreviewRuleThread> = Vec::new();
+    for message in messages.iter().filter(|message| rule.matches(message)) {
+        match threads
+            .iter_mut()
+            .find(|thread| thread.thread_id == message.thread_db_id)
+        {
+            Some(thread) => thread.message_ids.push(message.db_id),
+            None => threads.push(PreviewRuleThread {
+                thread_id: message.thread_db_id,
+                message_ids: vec![message.db_id],
+            }),
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(PreviewRuleResponse {
+            threads,
+            scanned_message_count: messages.len(),
+        }),
+    )
+        .into_response())
+}
@@ -10,8 +10,14 @@ use strum_macros::AsRefStr;
 use thiserror::Error;
 use utoipa::ToSchema;
 
+/// The longest sent messages can be held in the outbox
+const MAX_UNDO_SEND_SECONDS: i32 = 120;
+
 #[derive(Debug, Error, AsRefStr)]
 pub enum PatchSettingsError {
+    #[error("undo_send_seconds must be between 0 and {MAX_UNDO_SEND_SECONDS}")]
+    InvalidUndoSendSeconds,
+
     #[error("Failed to update settings")]
     DatabaseError(#[from] anyhow::Error),
 }
@@ -19,6 +25,7 @@ pub enum PatchSettingsError {
 impl IntoResponse for PatchSettingsError {
     fn into_response(self) -> Response {
         let status_code = match &self {
+            PatchSettingsError::InvalidUndoSendSeconds => StatusCode::BAD_REQUEST,
             PatchSettingsError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -56,6 +63,14 @@ pub async fn patch_settings_handler(
     link: Extension<Link>,
     Json(api_settings): Json<PatchSettingsRequest>,
 ) -> Result<Json<PatchSettingsResponse>, PatchSettingsError> {
+    if api_settings
+        .settings
+        .undo_send_seconds
+        .is_some_and(|seconds| !(0..=MAX_UNDO_SEND_SECONDS).contains(&seconds))
+    {
+        return Err(PatchSettingsError::InvalidUndoSendSeconds);
+    }
+
     let service_settings = service::settings::Settings::new(api_settings.settings, link.id);
 
     let updated_settings =
@@ -0,0 +1,66 @@
+use super::{error, pagination};
+use crate::api::context::ApiContext;
+use axum::extract::{self, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::subscription::Subscription;
+use utoipa::ToSchema;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct ListSubscriptionsParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListSubscriptionsResponse {
+    pub subscriptions: Vec<Subscription>,
+}
+
+/// List the mailing lists the user receives mail from, with volume stats. Lists with the most
+/// mail in the last 30 days come first.
+#[utoipa::path(
+    get,
+    tag = "Subscriptions",
+    path = "/email/subscriptions",
+    operation_id = "list_subscriptions",
+    params(
+        ("limit" = i64, Query, description = "Limit for pagination. Default is 50."),
+        ("offset" = i64, Query, description = "Offset for pagination. Default is 0."),
+    ),
+    responses(
+            (status = 200, body=ListSubscriptionsResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    extract::Query(params): extract::Query<ListSubscriptionsParams>,
+) -> Result<Response, Response> {
+    let (limit, offset) = pagination(params.limit, params.offset);
+
+    let subscriptions =
+        email_db_client::subscriptions::fetch_subscriptions(&ctx.db, link.id, limit, offset)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch subscriptions");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to fetch subscriptions",
+                )
+            })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ListSubscriptionsResponse { subscriptions }),
+    )
+        .into_response())
+}
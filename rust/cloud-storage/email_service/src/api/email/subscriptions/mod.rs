@@ -0,0 +1,47 @@
+pub mod list;
+pub mod threads;
+pub mod unsubscribe;
+
+use axum::Json;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use model::response::ErrorResponse;
+
+use crate::api::ApiContext;
+
+/// The default number of subscriptions or threads to return
+const DEFAULT_LIMIT: i64 = 50;
+/// The max number of subscriptions or threads that can be returned in a response
+const MAX_LIMIT: i64 = 200;
+
+pub fn router(state: ApiContext) -> Router<ApiContext> {
+    Router::new()
+        .route("/unsubscribe", post(unsubscribe::handler))
+        // mailto unsubscribes are sent through the provider
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::api::middleware::mail_provider::attach_mail_provider,
+        ))
+        .route("/", get(list::handler))
+        .route("/threads", get(threads::handler))
+        .layer(axum::middleware::from_fn_with_state(
+            state.email_service,
+            crate::api::middleware::link::attach_link_context,
+        ))
+}
+
+fn error(status_code: StatusCode, message: &'static str) -> Response {
+    (status_code, Json(ErrorResponse { message })).into_response()
+}
+
+/// Applies the defaults and bounds to pagination parameters, returning (limit, offset)
+fn pagination(limit: Option<i64>, offset: Option<i64>) -> (i64, i64) {
+    (
+        limit
+            .filter(|&limit| 0 < limit && limit <= MAX_LIMIT)
+            .unwrap_or(DEFAULT_LIMIT),
+        offset.filter(|&offset| offset >= 0).unwrap_or(0),
+    )
+}
@@ -0,0 +1,73 @@
+use super::{error, pagination};
+use crate::api::context::ApiContext;
+use axum::extract::{self, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::subscription::SubscriptionThread;
+use utoipa::ToSchema;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct ListSubscriptionThreadsParams {
+    /// the list key of the subscription
+    pub list_key: String,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListSubscriptionThreadsResponse {
+    pub threads: Vec<SubscriptionThread>,
+}
+
+/// List the threads containing mail from a mailing list, most recent first.
+#[utoipa::path(
+    get,
+    tag = "Subscriptions",
+    path = "/email/subscriptions/threads",
+    operation_id = "list_subscription_threads",
+    params(
+        ("list_key" = String, Query, description = "List key of the subscription."),
+        ("limit" = i64, Query, description = "Limit for pagination. Default is 50."),
+        ("offset" = i64, Query, description = "Offset for pagination. Default is 0."),
+    ),
+    responses(
+            (status = 200, body=ListSubscriptionThreadsResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    extract::Query(params): extract::Query<ListSubscriptionThreadsParams>,
+) -> Result<Response, Response> {
+    let (limit, offset) = pagination(params.limit, params.offset);
+
+    let threads = email_db_client::subscriptions::fetch_subscription_threads(
+        &ctx.db,
+        link.id,
+        &params.list_key,
+        limit,
+        offset,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch subscription threads");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch subscription threads",
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ListSubscriptionThreadsResponse { threads }),
+    )
+        .into_response())
+}
@@ -0,0 +1,229 @@
+use super::error;
+use crate::api::context::ApiContext;
+use crate::util::unsubscribe::{mailto_unsubscribe, one_click_unsubscribe};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use mail_provider::MailProvider;
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::gmail::webhook::{ApplyRulePayload, WebhookOperation, WebhookPubsubMessage};
+use models_email::service::link::Link;
+use models_email::service::rule::{RuleAction, RuleCondition};
+use models_email::service::subscription::SubscriptionTarget;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct UnsubscribeRequest {
+    /// the list key of the subscription
+    pub list_key: String,
+    /// also archive existing and future mail from the sender of the list, through a rule
+    #[serde(default)]
+    pub archive: bool,
+}
+
+/// How the user was unsubscribed
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsubscribeMethod {
+    /// an RFC 8058 one-click unsubscribe request was sent to the list
+    OneClick,
+    /// an unsubscribe message was sent to the list
+    Mailto,
+    /// the user has to open the unsubscribe page of the list
+    Link,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct UnsubscribeResponse {
+    pub method: UnsubscribeMethod,
+    /// the unsubscribe page to open, for the link method
+    pub url: Option<String>,
+    pub unsubscribed_at: DateTime<Utc>,
+    /// the rule archiving mail from the list
+    pub archive_rule_id: Option<Uuid>,
+}
+
+/// Unsubscribe from a mailing list. Uses one-click unsubscribe when the list supports it, and
+/// otherwise sends an unsubscribe message. Lists that can only be unsubscribed from through their
+/// web page return the page to open instead.
+#[utoipa::path(
+    post,
+    tag = "Subscriptions",
+    path = "/email/subscriptions/unsubscribe",
+    operation_id = "unsubscribe",
+    request_body = UnsubscribeRequest,
+    responses(
+            (status = 200, body=UnsubscribeResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+            (status = 502, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, provider, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    provider: Extension<Arc<dyn MailProvider>>,
+    Json(request_body): Json<UnsubscribeRequest>,
+) -> Result<Response, Response> {
+    let target = email_db_client::subscriptions::fetch_subscription_target(
+        &ctx.db,
+        link.id,
+        &request_body.list_key,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch subscription");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to fetch subscription",
+        )
+    })?
+    .ok_or_else(|| error(StatusCode::NOT_FOUND, "subscription not found"))?;
+
+    let method = unsubscribe(&ctx, provider.as_ref(), &link, &target).await?;
+
+    let archive_rule_id = if request_body.archive {
+        archive_rule(&ctx, &link, &target).await?
+    } else {
+        None
+    };
+
+    // the unsubscribe page can't be followed up on, so the list counts as unsubscribed from
+    // once the user was sent there
+    let unsubscribed_at = email_db_client::subscriptions::upsert_list_unsubscribe(
+        &ctx.db,
+        link.id,
+        &target.list_key,
+        archive_rule_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to record unsubscribe");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to record unsubscribe",
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(UnsubscribeResponse {
+            method,
+            url: match method {
+                UnsubscribeMethod::Link => target.http_url,
+                _ => None,
+            },
+            unsubscribed_at,
+            archive_rule_id,
+        }),
+    )
+        .into_response())
+}
+
+/// Unsubscribes with the best method the list supports. A failed one-click unsubscribe falls
+/// back to the mailto address of the list, and then to its unsubscribe page.
+async fn unsubscribe(
+    ctx: &ApiContext,
+    provider: &dyn MailProvider,
+    link: &Link,
+    target: &SubscriptionTarget,
+) -> Result<UnsubscribeMethod, Response> {
+    if target.one_click
+        && let Some(http_url) = &target.http_url
+    {
+        match one_click_unsubscribe(&ctx.fetcher, http_url).await {
+            Ok(()) => return Ok(UnsubscribeMethod::OneClick),
+            Err(e) => tracing::warn!(error=?e, "one-click unsubscribe failed"),
+        }
+    }
+
+    if let Some(mailto) = &target.mailto {
+        return mailto_unsubscribe(&ctx.db, provider, link, mailto)
+            .await
+            .map(|_| UnsubscribeMethod::Mailto)
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to send unsubscribe message");
+                error(StatusCode::BAD_GATEWAY, "unable to unsubscribe")
+            });
+    }
+
+    if target.http_url.is_some() {
+        return Ok(UnsubscribeMethod::Link);
+    }
+
+    Err(error(StatusCode::BAD_GATEWAY, "unable to unsubscribe"))
+}
+
+/// Creates a rule archiving mail from the sender of the list, and applies it to existing mail.
+/// Reuses the rule created when unsubscribing from the list before.
+async fn archive_rule(
+    ctx: &ApiContext,
+    link: &Link,
+    target: &SubscriptionTarget,
+) -> Result<Option<Uuid>, Response> {
+    let Some(sender_email) = &target.sender_email else {
+        return Ok(None);
+    };
+
+    let existing_rule_id =
+        email_db_client::subscriptions::fetch_archive_rule_id(&ctx.db, link.id, &target.list_key)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch archive rule");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to fetch archive rule",
+                )
+            })?;
+
+    if existing_rule_id.is_some() {
+        return Ok(existing_rule_id);
+    }
+
+    let rule = email_db_client::rules::insert_rule(
+        &ctx.db,
+        link.id,
+        &format!(
+            "Archive {}",
+            target.list_id.as_deref().unwrap_or(sender_email)
+        ),
+        true,
+        None,
+        true,
+        false,
+        &[RuleCondition::From {
+            value: sender_email.clone(),
+        }],
+        &[RuleAction::Archive],
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to insert archive rule");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to insert archive rule",
+        )
+    })?;
+
+    // archiving existing mail happens in the background, a failure leaves the rule for new mail
+    if let Err(e) = ctx
+        .sqs_client
+        .enqueue_gmail_webhook_notification(WebhookPubsubMessage {
+            link_id: link.id,
+            operation: WebhookOperation::ApplyRule(ApplyRulePayload { rule_id: rule.id }),
+        })
+        .await
+    {
+        tracing::error!(error=?e, rule_id=%rule.id, "unable to enqueue apply rule operation");
+    }
+
+    Ok(Some(rule.id))
+}
@@ -0,0 +1,89 @@
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service;
+use models_email::service::link::Link;
+use models_email::service::template::TemplateVariable;
+use sqlx::types::chrono::Utc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Variables are written as `{{name}}`. Besides the declared variables, templates can use
+/// `first_name`, `recipient_name`, `recipient_email`, `sender_email` and `signature`.
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    /// the team to share the template with
+    pub team_id: Option<Uuid>,
+    /// leave out for a snippet
+    pub subject: Option<String>,
+    pub body_html: String,
+    pub body_text: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct TemplateResponse {
+    pub template: service::template::Template,
+}
+
+/// Create an email template or snippet.
+#[utoipa::path(
+    post,
+    tag = "Templates",
+    path = "/email/templates",
+    operation_id = "create_template",
+    request_body = CreateTemplateRequest,
+    responses(
+            (status = 201, body=TemplateResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Json(request_body): Json<CreateTemplateRequest>,
+) -> Result<Response, Response> {
+    let now = Utc::now();
+    let template = service::template::Template {
+        id: Uuid::nil(),
+        link_id: link.id,
+        team_id: request_body.team_id,
+        name: request_body.name.trim().to_string(),
+        subject: request_body.subject,
+        body_html: request_body.body_html,
+        body_text: request_body.body_text,
+        variables: request_body.variables,
+        created_at: now,
+        updated_at: now,
+    };
+
+    super::validate_template(&ctx, &link, &template).await?;
+
+    let template = email_db_client::templates::insert_template(
+        &ctx.db,
+        link.id,
+        template.team_id,
+        &template.name,
+        template.subject.as_deref(),
+        &template.body_html,
+        template.body_text.as_deref(),
+        &template.variables,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to insert template");
+        super::internal_error("unable to insert template")
+    })?;
+
+    Ok((StatusCode::CREATED, Json(TemplateResponse { template })).into_response())
+}
@@ -0,0 +1,46 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::service::link::Link;
+use uuid::Uuid;
+
+/// Delete an email template. Templates shared with a team can only be deleted by their owner.
+#[utoipa::path(
+    delete,
+    tag = "Templates",
+    path = "/email/templates/{id}",
+    operation_id = "delete_template",
+    params(
+        ("id" = Uuid, Path, description = "Template ID."),
+    ),
+    responses(
+            (status = 204, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(template_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let deleted = email_db_client::templates::delete_template(&ctx.db, template_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to delete template");
+            super::internal_error("unable to delete template")
+        })?;
+
+    if !deleted {
+        return Err(super::not_found());
+    }
+
+    Ok((StatusCode::NO_CONTENT, Json(EmptyResponse::default())).into_response())
+}
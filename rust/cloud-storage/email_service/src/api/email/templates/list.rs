@@ -0,0 +1,48 @@
+use crate::api::context::ApiContext;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service;
+use models_email::service::link::Link;
+use utoipa::ToSchema;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ListTemplatesResponse {
+    /// the user's templates and the templates shared with their teams, by name
+    pub templates: Vec<service::template::Template>,
+}
+
+/// List the email templates and snippets the user can use.
+#[utoipa::path(
+    get,
+    tag = "Templates",
+    path = "/email/templates",
+    operation_id = "list_templates",
+    responses(
+            (status = 200, body=ListTemplatesResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+) -> Result<Response, Response> {
+    let templates = email_db_client::templates::fetch_templates_for_link(
+        &ctx.db,
+        link.id,
+        link.macro_id.0.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch templates");
+        super::internal_error("unable to fetch templates")
+    })?;
+
+    Ok((StatusCode::OK, Json(ListTemplatesResponse { templates })).into_response())
+}
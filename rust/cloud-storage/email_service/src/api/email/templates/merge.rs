@@ -0,0 +1,241 @@
+use crate::api::context::ApiContext;
+use crate::api::email::drafts::create::insert_draft;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use macro_user_id::email::EmailStr;
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::address::ContactInfo;
+use models_email::service::link::Link;
+use models_email::service::message::MessageToSend;
+use sqlx::types::chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The most recipients a single mail merge can have
+const MAX_MERGE_RECIPIENTS: usize = 500;
+
+/// The default time between the scheduled sends of a mail merge, so they aren't sent in a burst
+const DEFAULT_INTERVAL_SECONDS: u32 = 30;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct MergeRecipient {
+    pub contact: ContactInfo,
+    /// values of the template's variables for this recipient, these take precedence over the
+    /// values shared by every recipient
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct MergeTemplateRequest {
+    pub recipients: Vec<MergeRecipient>,
+    /// values of the template's variables shared by every recipient
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+    /// when the first message is sent, defaults to now
+    pub send_time: Option<DateTime<Utc>>,
+    /// seconds between the sends of consecutive messages
+    pub interval_seconds: Option<u32>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ScheduledMergeMessage {
+    pub email: String,
+    pub draft_id: Option<Uuid>,
+    pub send_time: DateTime<Utc>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MergeSkipReason {
+    InvalidEmail,
+    /// the recipient appears earlier in the list
+    Duplicate,
+    /// variables the template uses that have no value for the recipient
+    MissingVariables {
+        names: Vec<String>,
+    },
+    /// the recipient received too many mail merge messages recently
+    RateLimited,
+    /// the message could not be scheduled
+    Failed,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct SkippedMergeRecipient {
+    pub email: String,
+    pub reason: MergeSkipReason,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct MergeTemplateResponse {
+    pub scheduled: Vec<ScheduledMergeMessage>,
+    pub skipped: Vec<SkippedMergeRecipient>,
+}
+
+/// Mail merge an email template. Creates a personalised draft for every recipient, scheduled to be
+/// sent one after another. Recipients that can't be sent to are skipped and reported.
+#[utoipa::path(
+    post,
+    tag = "Templates",
+    path = "/email/templates/{id}/merge",
+    operation_id = "merge_template",
+    params(
+        ("id" = Uuid, Path, description = "Template ID."),
+    ),
+    request_body = MergeTemplateRequest,
+    responses(
+            (status = 201, body=MergeTemplateResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(template_id): Path<Uuid>,
+    Json(request_body): Json<MergeTemplateRequest>,
+) -> Result<Response, Response> {
+    if request_body.recipients.is_empty() {
+        return Err(super::bad_request(
+            "mail merge must have at least one recipient",
+        ));
+    }
+
+    if request_body.recipients.len() > MAX_MERGE_RECIPIENTS {
+        return Err(super::bad_request("mail merge has too many recipients"));
+    }
+
+    let template = super::fetch_template(&ctx, &link, template_id).await?;
+
+    if template.subject.is_none() {
+        return Err(super::bad_request("snippets cannot be mail merged"));
+    }
+
+    let now = Utc::now();
+    let first_send_time = request_body
+        .send_time
+        .map_or(now, |send_time| send_time.max(now));
+    let interval = Duration::seconds(
+        request_body
+            .interval_seconds
+            .unwrap_or(DEFAULT_INTERVAL_SECONDS)
+            .into(),
+    );
+
+    let signature = super::fetch_signature(&ctx, &link).await;
+
+    let mut seen = HashSet::new();
+    let mut scheduled = Vec::new();
+    let mut skipped = Vec::new();
+
+    for recipient in request_body.recipients {
+        let email = recipient.contact.email.trim().to_string();
+        let skip = |reason| SkippedMergeRecipient {
+            email: email.clone(),
+            reason,
+        };
+
+        if EmailStr::parse_from_str(&email).is_err() {
+            skipped.push(skip(MergeSkipReason::InvalidEmail));
+            continue;
+        }
+
+        if !seen.insert(email.to_lowercase()) {
+            skipped.push(skip(MergeSkipReason::Duplicate));
+            continue;
+        }
+
+        let mut fields = request_body.fields.clone();
+        fields.extend(recipient.fields);
+
+        let contact = ContactInfo {
+            email: email.clone(),
+            ..recipient.contact
+        };
+        let values =
+            super::template_values(&ctx, &link, &signature, Some(&contact), &fields).await?;
+
+        let rendered = match template.render(&values) {
+            Ok(rendered) => rendered,
+            Err(names) => {
+                skipped.push(skip(MergeSkipReason::MissingVariables { names }));
+                continue;
+            }
+        };
+
+        let Some(slot) = ctx
+            .redis_client
+            .take_mail_merge_recipient_slot(link.id, &email)
+            .await
+        else {
+            skipped.push(skip(MergeSkipReason::RateLimited));
+            continue;
+        };
+
+        let send_time = first_send_time + interval * scheduled.len() as i32;
+        let mut draft = MessageToSend {
+            db_id: None,
+            provider_id: None,
+            replying_to_id: None,
+            provider_thread_id: None,
+            thread_db_id: None,
+            link_id: link.id,
+            subject: rendered.subject.unwrap_or_default(),
+            to: Some(vec![contact]),
+            cc: None,
+            bcc: None,
+            body_text: rendered.body_text,
+            body_html: Some(rendered.body_html),
+            body_macro: None,
+            attachments: None,
+            attachments_macro: None,
+            headers_json: None,
+            send_time: Some(send_time),
+            auto_forwarded: false,
+        };
+
+        // every recipient gets its own transaction, so one failure doesn't undo the others
+        let result = async {
+            let mut tx = ctx.db.begin().await?;
+            insert_draft(&mut tx, &mut draft, link.email_address.0.as_ref()).await?;
+            tx.commit().await?;
+            anyhow::Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => scheduled.push(ScheduledMergeMessage {
+                email,
+                draft_id: draft.db_id,
+                send_time,
+            }),
+            Err(e) => {
+                tracing::error!(error=?e, "unable to schedule mail merge message");
+                // the message was never scheduled, so it doesn't count against the recipient
+                if let Err(e) = ctx
+                    .redis_client
+                    .release_mail_merge_recipient_slot(slot)
+                    .await
+                {
+                    tracing::warn!(error=?e, "unable to release mail merge rate limit slot");
+                }
+                skipped.push(skip(MergeSkipReason::Failed));
+            }
+        }
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MergeTemplateResponse { scheduled, skipped }),
+    )
+        .into_response())
+}
@@ -0,0 +1,228 @@
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod merge;
+pub mod patch;
+pub mod render;
+
+use axum::Json;
+use axum::Router;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post};
+use model::response::ErrorResponse;
+use models_email::service::address::ContactInfo;
+use models_email::service::link::{Link, UserProvider};
+use models_email::service::template::{self, TemplateValue, TemplateVariable};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::api::ApiContext;
+use crate::util::gmail::auth::fetch_gmail_access_token_from_link;
+
+pub fn router(state: ApiContext) -> Router<ApiContext> {
+    Router::new()
+        .route("/", get(list::handler))
+        .route("/", post(create::handler))
+        .route("/:id", patch(patch::handler))
+        .route("/:id", delete(delete::handler))
+        .route("/:id/render", post(render::handler))
+        .route("/:id/merge", post(merge::handler))
+        .layer(axum::middleware::from_fn_with_state(
+            state.email_service,
+            crate::api::middleware::link::attach_link_context,
+        ))
+}
+
+fn bad_request(message: &'static str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { message })).into_response()
+}
+
+fn internal_error(message: &'static str) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse { message }),
+    )
+        .into_response()
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            message: "template not found",
+        }),
+    )
+        .into_response()
+}
+
+/// Validates the contents of a template. Every variable it uses has to be a builtin or declared,
+/// and it can only be shared with a team of its owner.
+async fn validate_template(
+    ctx: &ApiContext,
+    link: &Link,
+    template: &template::Template,
+) -> Result<(), Response> {
+    if template.name.trim().is_empty() {
+        return Err(bad_request("template name cannot be empty"));
+    }
+
+    if template.body_html.trim().is_empty() {
+        return Err(bad_request("template body cannot be empty"));
+    }
+
+    let mut declared = HashSet::new();
+    for TemplateVariable { name, .. } in &template.variables {
+        if !template::is_valid_variable_name(name) {
+            return Err(bad_request(
+                "variable names can only contain lowercase letters, digits and underscores",
+            ));
+        }
+        if template::BUILTIN_VARIABLES.contains(&name.as_str()) {
+            return Err(bad_request("variable name is reserved"));
+        }
+        if !declared.insert(name.as_str()) {
+            return Err(bad_request("variable names must be unique"));
+        }
+    }
+
+    let has_undeclared = template.used_variables().iter().any(|name| {
+        !declared.contains(name.as_str()) && !template::BUILTIN_VARIABLES.contains(&name.as_str())
+    });
+    if has_undeclared {
+        return Err(bad_request("template uses a variable that is not declared"));
+    }
+
+    if let Some(team_id) = template.team_id {
+        let is_member =
+            email_db_client::templates::is_team_member(&ctx.db, team_id, link.macro_id.0.as_ref())
+                .await
+                .map_err(|e| {
+                    tracing::error!(error=?e, "unable to check team membership");
+                    internal_error("unable to check team membership")
+                })?;
+
+        if !is_member {
+            return Err(bad_request(
+                "template can only be shared with your own teams",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetches a template the link can use, its own or one shared with a team of its user
+async fn fetch_template(
+    ctx: &ApiContext,
+    link: &Link,
+    template_id: Uuid,
+) -> Result<template::Template, Response> {
+    email_db_client::templates::fetch_template_for_link(
+        &ctx.db,
+        template_id,
+        link.id,
+        link.macro_id.0.as_ref(),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to fetch template");
+        internal_error("unable to fetch template")
+    })?
+    .ok_or_else(not_found)
+}
+
+/// The signature of the link's email address, empty when it has none or it can't be fetched.
+/// Only Gmail links have a signature.
+async fn fetch_signature(ctx: &ApiContext, link: &Link) -> String {
+    if link.provider != UserProvider::Gmail {
+        return String::new();
+    }
+
+    let access_token =
+        match fetch_gmail_access_token_from_link(link, &ctx.redis_client, &ctx.auth_service_client)
+            .await
+        {
+            Ok(access_token) => access_token,
+            Err(e) => {
+                tracing::warn!(error=?e, "unable to fetch gmail access token for signature");
+                return String::new();
+            }
+        };
+
+    ctx.gmail_client
+        .get_email_signature(&access_token, link.email_address.0.as_ref())
+        .await
+        .inspect_err(|e| tracing::warn!(error=?e, "unable to fetch email signature"))
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// The values of the variables of a template sent to a recipient. Custom fields take precedence
+/// over the builtin variables, so a greeting can use a nickname over the contact's name.
+async fn template_values(
+    ctx: &ApiContext,
+    link: &Link,
+    signature: &str,
+    recipient: Option<&ContactInfo>,
+    fields: &HashMap<String, String>,
+) -> Result<HashMap<String, TemplateValue>, Response> {
+    let mut values = HashMap::from([
+        (
+            template::SENDER_EMAIL.to_string(),
+            TemplateValue::text(link.email_address.0.as_ref()),
+        ),
+        (
+            template::SIGNATURE.to_string(),
+            TemplateValue::html(signature),
+        ),
+    ]);
+
+    if let Some(recipient) = recipient {
+        values.insert(
+            template::RECIPIENT_EMAIL.to_string(),
+            TemplateValue::text(&recipient.email),
+        );
+
+        // fall back to the name we know the contact by
+        let name = match recipient
+            .name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+        {
+            Some(name) => Some(name.trim().to_string()),
+            None => email_db_client::contacts::get::fetch_contact_by_email(
+                &ctx.db,
+                link.id,
+                &recipient.email,
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to fetch contact");
+                internal_error("unable to fetch contact")
+            })?
+            .and_then(|contact| contact.name)
+            .filter(|name| !name.trim().is_empty()),
+        };
+
+        if let Some(name) = name {
+            if let Some(first_name) = template::first_name(&name) {
+                values.insert(
+                    template::FIRST_NAME.to_string(),
+                    TemplateValue::text(first_name),
+                );
+            }
+            values.insert(
+                template::RECIPIENT_NAME.to_string(),
+                TemplateValue::text(name.trim()),
+            );
+        }
+    }
+
+    for (name, value) in fields {
+        values.insert(name.clone(), TemplateValue::text(value));
+    }
+
+    Ok(values)
+}
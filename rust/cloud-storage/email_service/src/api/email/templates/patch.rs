@@ -0,0 +1,102 @@
+use crate::api::context::ApiContext;
+use crate::api::email::templates::create::TemplateResponse;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::link::Link;
+use models_email::service::template::TemplateVariable;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Fields that are left out are not changed
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct PatchTemplateRequest {
+    pub name: Option<String>,
+    /// shares the template with the team
+    pub team_id: Option<Uuid>,
+    /// stops sharing the template with its team
+    pub unshare: Option<bool>,
+    /// an empty subject turns the template into a snippet
+    pub subject: Option<String>,
+    pub body_html: Option<String>,
+    /// an empty text body removes it
+    pub body_text: Option<String>,
+    pub variables: Option<Vec<TemplateVariable>>,
+}
+
+/// Update an email template. Templates shared with a team can only be updated by their owner.
+#[utoipa::path(
+    patch,
+    tag = "Templates",
+    path = "/email/templates/{id}",
+    operation_id = "patch_template",
+    params(
+        ("id" = Uuid, Path, description = "Template ID."),
+    ),
+    request_body = PatchTemplateRequest,
+    responses(
+            (status = 200, body=TemplateResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 403, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(template_id): Path<Uuid>,
+    Json(request_body): Json<PatchTemplateRequest>,
+) -> Result<Response, Response> {
+    let mut template = super::fetch_template(&ctx, &link, template_id).await?;
+
+    if template.link_id != link.id {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                message: "only the owner of a template can update it",
+            }),
+        )
+            .into_response());
+    }
+
+    if let Some(name) = request_body.name {
+        template.name = name.trim().to_string();
+    }
+    if let Some(team_id) = request_body.team_id {
+        template.team_id = Some(team_id);
+    }
+    if request_body.unshare == Some(true) {
+        template.team_id = None;
+    }
+    if let Some(subject) = request_body.subject {
+        template.subject = (!subject.is_empty()).then_some(subject);
+    }
+    if let Some(body_html) = request_body.body_html {
+        template.body_html = body_html;
+    }
+    if let Some(body_text) = request_body.body_text {
+        template.body_text = (!body_text.is_empty()).then_some(body_text);
+    }
+    if let Some(variables) = request_body.variables {
+        template.variables = variables;
+    }
+
+    super::validate_template(&ctx, &link, &template).await?;
+
+    let template = email_db_client::templates::update_template(&ctx.db, &template)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to update template");
+            super::internal_error("unable to update template")
+        })?
+        .ok_or_else(super::not_found)?;
+
+    Ok((StatusCode::OK, Json(TemplateResponse { template })).into_response())
+}
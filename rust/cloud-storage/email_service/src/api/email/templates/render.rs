@@ -0,0 +1,148 @@
+use crate::api::context::ApiContext;
+use crate::api::email::drafts::create::insert_draft;
+use crate::api::email::validation;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::ErrorResponse;
+use model::user::UserContext;
+use models_email::service::address::ContactInfo;
+use models_email::service::link::Link;
+use models_email::service::message::MessageToSend;
+use models_email::service::template::RenderedTemplate;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct RenderTemplateRequest {
+    /// the recipient variables are filled in from the first to address
+    pub to: Option<Vec<ContactInfo>>,
+    pub cc: Option<Vec<ContactInfo>>,
+    pub bcc: Option<Vec<ContactInfo>>,
+    /// values of the template's variables, by name
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+    /// makes the draft a reply to the message
+    pub replying_to_id: Option<Uuid>,
+    /// schedules the draft to be sent
+    pub send_time: Option<DateTime<Utc>>,
+    /// whether to create a draft, snippets are only rendered to be inserted into an existing draft
+    #[serde(default = "serde_utils::default_true")]
+    pub create_draft: bool,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct RenderTemplateResponse {
+    pub rendered: RenderedTemplate,
+    /// the created draft
+    pub draft: Option<MessageToSend>,
+}
+
+/// Render an email template, and create a draft from it.
+#[utoipa::path(
+    post,
+    tag = "Templates",
+    path = "/email/templates/{id}/render",
+    operation_id = "render_template",
+    params(
+        ("id" = Uuid, Path, description = "Template ID."),
+    ),
+    request_body = RenderTemplateRequest,
+    responses(
+            (status = 200, body=RenderTemplateResponse),
+            (status = 201, body=RenderTemplateResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, request_body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(template_id): Path<Uuid>,
+    Json(request_body): Json<RenderTemplateRequest>,
+) -> Result<Response, Response> {
+    let template = super::fetch_template(&ctx, &link, template_id).await?;
+
+    let signature = super::fetch_signature(&ctx, &link).await;
+    let recipient = request_body.to.as_ref().and_then(|to| to.first());
+    let values =
+        super::template_values(&ctx, &link, &signature, recipient, &request_body.fields).await?;
+
+    let rendered = template.render(&values).map_err(|missing| {
+        let message = format!("missing values for variables: {}", missing.join(", "));
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { message: &message }),
+        )
+            .into_response()
+    })?;
+
+    if !request_body.create_draft {
+        return Ok((
+            StatusCode::OK,
+            Json(RenderTemplateResponse {
+                rendered,
+                draft: None,
+            }),
+        )
+            .into_response());
+    }
+
+    let mut draft = MessageToSend {
+        db_id: None,
+        provider_id: None,
+        replying_to_id: request_body.replying_to_id,
+        provider_thread_id: None,
+        thread_db_id: None,
+        link_id: link.id,
+        subject: rendered.subject.clone().unwrap_or_default(),
+        to: request_body.to,
+        cc: request_body.cc,
+        bcc: request_body.bcc,
+        body_text: rendered.body_text.clone(),
+        body_html: Some(rendered.body_html.clone()),
+        body_macro: None,
+        attachments: None,
+        attachments_macro: None,
+        headers_json: None,
+        send_time: request_body.send_time,
+        auto_forwarded: false,
+    };
+
+    validation::validate_replying_to_id(&ctx.db, &mut draft, &link)
+        .await
+        .map_err(|e| (e.status_code(), e.to_string()).into_response())?;
+
+    let mut tx = ctx.db.begin().await.map_err(|e| {
+        tracing::error!(error=?e, "unable to begin transaction");
+        super::internal_error("unable to create draft")
+    })?;
+
+    insert_draft(&mut tx, &mut draft, link.email_address.0.as_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to insert draft");
+            super::internal_error("unable to create draft")
+        })?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error=?e, "unable to commit transaction");
+        super::internal_error("unable to create draft")
+    })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RenderTemplateResponse {
+            rendered,
+            draft: Some(draft),
+        }),
+    )
+        .into_response())
+}
@@ -5,12 +5,14 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
 use email_db_client::threads::update::update_inbox_visible_status;
+use mail_provider::MailProvider;
 use model::response::{EmptyResponse, ErrorResponse};
 use model::user::UserContext;
 use models_email::email::service::link::Link;
 use models_email::service::label::system_labels;
 use models_email::service::message::Message;
 use sqlx::types::Uuid;
+use std::sync::Arc;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 use utoipa::ToSchema;
@@ -67,16 +69,15 @@ pub struct ArchiveThreadRequest {
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, link, gmail_token, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+#[tracing::instrument(skip(ctx, user_context, link, provider, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
 pub async fn archived_handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Path(thread_id): Path<Uuid>,
     Json(body): Json<ArchiveThreadRequest>,
 ) -> Result<Response, ArchiveThreadError> {
-    let gmail_access_token = gmail_token.as_str();
     let is_archiving = body.value;
 
     let thread =
@@ -164,13 +165,12 @@ pub async fn archived_handler(
         }
     }
 
-    // async send requests to gmail async. if they fail, revert db changes we made earlier. we make
+    // async send requests to the provider async. if they fail, revert db changes we made earlier. we make
     // the calls async at Teo's request because they are slow and doing it sync causes this endpoint
     // to take >300ms
 
     let db_clone = ctx.db.clone();
-    let gmail_client_clone = ctx.gmail_client.clone();
-    let gmail_access_token_clone = gmail_access_token.to_string();
+    let provider_clone = provider.0.clone();
     let thread_id_clone = thread_id;
     let link_id_clone = link.id;
     let message_db_ids_clone = message_db_ids.clone();
@@ -187,20 +187,15 @@ pub async fn archived_handler(
         .collect();
 
     tokio::spawn(async move {
-        let (success_ids, failed_ids) = gmail_client_clone
-            .batch_modify_labels(
-                &gmail_access_token_clone,
-                message_tuples,
-                labels_to_add,
-                labels_to_remove,
-            )
+        let (success_ids, failed_ids) = provider_clone
+            .batch_modify_labels(message_tuples, labels_to_add, labels_to_remove)
             .await;
 
         if !failed_ids.is_empty() {
             tracing::error!(
                 failed_ids = ?failed_ids,
                 success_ids = ?success_ids,
-                "Gmail API failed to modify labels for some messages, reverting database changes"
+                "Provider failed to modify labels for some messages, reverting database changes"
             );
 
             let mut revert_tx = match db_clone.begin().await {
@@ -252,7 +247,7 @@ pub async fn archived_handler(
                         tracing::error!(error = ?e, "Unable to commit transaction for revert");
                     } else {
                         tracing::info!(
-                            "Successfully reverted database changes after Gmail API failure"
+                            "Successfully reverted database changes after provider failure"
                         );
                     }
                 }
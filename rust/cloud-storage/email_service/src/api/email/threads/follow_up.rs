@@ -0,0 +1,169 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::email::service::link::Link;
+use models_email::service::follow_up::FollowUpReminder;
+use sqlx::types::Uuid;
+use sqlx::types::chrono::{DateTime, Duration, Utc};
+use utoipa::ToSchema;
+
+/// The longest a follow up reminder can be set for
+const MAX_FOLLOW_UP_DAYS: u32 = 365;
+
+/// Provide either `remind_at` or `days`
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct SetFollowUpRequest {
+    /// remind the user at this time if nobody replied by then
+    pub remind_at: Option<DateTime<Utc>>,
+    /// remind the user after this many days if nobody replied by then
+    pub days: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct FollowUpResponse {
+    pub reminder: FollowUpReminder,
+}
+
+fn error(status_code: StatusCode, message: &'static str) -> Response {
+    (status_code, Json(ErrorResponse { message })).into_response()
+}
+
+/// Remind the user to follow up on a thread they sent a message in, if nobody replies in time.
+/// Replaces any existing reminder of the thread.
+#[utoipa::path(
+    put,
+    tag = "Threads",
+    path = "/email/threads/{id}/follow_up",
+    operation_id = "set_thread_follow_up",
+    params(
+        ("id" = Uuid, Path, description = "Thread ID."),
+    ),
+    request_body = SetFollowUpRequest,
+    responses(
+            (status = 200, body=FollowUpResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn set_follow_up_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(thread_id): Path<Uuid>,
+    Json(body): Json<SetFollowUpRequest>,
+) -> Result<Response, Response> {
+    let remind_at = match (body.remind_at, body.days) {
+        (Some(remind_at), None) => remind_at,
+        (None, Some(days)) if (1..=MAX_FOLLOW_UP_DAYS).contains(&days) => {
+            Utc::now() + Duration::days(days.into())
+        }
+        (None, Some(_)) => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "days must be between 1 and 365",
+            ));
+        }
+        _ => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "exactly one of remind_at and days is required",
+            ));
+        }
+    };
+
+    if remind_at <= Utc::now() || remind_at > Utc::now() + Duration::days(MAX_FOLLOW_UP_DAYS.into())
+    {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "remind_at must be in the future and within a year",
+        ));
+    }
+
+    email_db_client::threads::get::get_thread_by_id_and_link_id(&ctx.db, thread_id, link.id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch thread");
+            error(StatusCode::INTERNAL_SERVER_ERROR, "unable to fetch thread")
+        })?
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, "thread not found"))?;
+
+    // only a message the user sent can go unanswered
+    email_db_client::follow_ups::fetch_latest_sent_message(&ctx.db, thread_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "unable to fetch sent message");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "unable to fetch sent message",
+            )
+        })?
+        .ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "thread has no messages sent by the user",
+            )
+        })?;
+
+    let reminder = email_db_client::follow_ups::upsert_follow_up_reminder(
+        &ctx.db, link.id, thread_id, remind_at,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to set follow up reminder");
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unable to set follow up reminder",
+        )
+    })?
+    .ok_or_else(|| error(StatusCode::NOT_FOUND, "thread not found"))?;
+
+    Ok((StatusCode::OK, Json(FollowUpResponse { reminder })).into_response())
+}
+
+/// Remove the follow up reminder of a thread.
+#[utoipa::path(
+    delete,
+    tag = "Threads",
+    path = "/email/threads/{id}/follow_up",
+    operation_id = "delete_thread_follow_up",
+    params(
+        ("id" = Uuid, Path, description = "Thread ID."),
+    ),
+    responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn delete_follow_up_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(thread_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    let deleted =
+        email_db_client::follow_ups::delete_follow_up_reminder(&ctx.db, link.id, thread_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to delete follow up reminder");
+                error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "unable to delete follow up reminder",
+                )
+            })?;
+
+    if !deleted {
+        return Err(error(StatusCode::NOT_FOUND, "follow up reminder not found"));
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse::default())).into_response())
+}
@@ -1,4 +1,5 @@
 use crate::api::context::ApiContext;
+use crate::util::image_proxy::proxy_message_images;
 use anyhow::Context;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
@@ -147,7 +148,14 @@ pub async fn get_thread_handler(
         .clone()
         .messages
         .into_iter()
-        .map(|message| async move { MessageWithBodyReplyless::from(message) })
+        .map(|message| {
+            let config = ctx.config.clone();
+            async move {
+                let mut message = MessageWithBodyReplyless::from(message);
+                proxy_message_images(&config, &mut message);
+                message
+            }
+        })
         .collect();
 
     let result: Vec<MessageWithBodyReplyless> = join_all(tasks).await;
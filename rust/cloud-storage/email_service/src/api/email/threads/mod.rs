@@ -1,9 +1,11 @@
 pub(crate) mod archived;
+pub(crate) mod follow_up;
 pub(crate) mod get;
 pub(crate) mod seen;
+pub(crate) mod snooze;
 
 use axum::Router;
-use axum::routing::{get, patch, post};
+use axum::routing::{get, patch, post, put};
 use tower::ServiceBuilder;
 
 use crate::api::ApiContext;
@@ -19,7 +21,7 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
             "/:id/seen",
             post(seen::seen_handler).layer(axum::middleware::from_fn_with_state(
                 state.clone(),
-                crate::api::middleware::gmail_token::attach_gmail_token,
+                crate::api::middleware::mail_provider::attach_mail_provider,
             )),
         )
         .route("/:id/messages", get(get::get_thread_messages_handler))
@@ -28,10 +30,18 @@ pub fn router(state: ApiContext) -> Router<ApiContext> {
             patch(archived::archived_handler).layer(ServiceBuilder::new().layer(
                 axum::middleware::from_fn_with_state(
                     state.clone(),
-                    crate::api::middleware::gmail_token::attach_gmail_token,
+                    crate::api::middleware::mail_provider::attach_mail_provider,
                 ),
             )),
         )
+        .route(
+            "/:id/snooze",
+            put(snooze::snooze_handler).delete(snooze::unsnooze_handler),
+        )
+        .route(
+            "/:id/follow_up",
+            put(follow_up::set_follow_up_handler).delete(follow_up::delete_follow_up_handler),
+        )
         .layer(axum::middleware::from_fn_with_state(
             state.email_service,
             crate::api::middleware::link::attach_link_context,
@@ -5,11 +5,13 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use mail_provider::MailProvider;
 use model::response::{EmptyResponse, ErrorResponse};
 use model::user::UserContext;
 use models_email::email::service;
 use models_email::service::link::Link;
 use sqlx::types::Uuid;
+use std::sync::Arc;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 
@@ -62,12 +64,12 @@ pub struct PathParams {
             (status = 500, body=ErrorResponse),
     )
 )]
-#[tracing::instrument(skip(ctx, user_context, gmail_token))]
+#[tracing::instrument(skip(ctx, user_context, provider))]
 pub async fn seen_handler(
     State(ctx): State<ApiContext>,
     user_context: Extension<UserContext>,
     link: Extension<Link>,
-    gmail_token: Extension<String>,
+    provider: Extension<Arc<dyn MailProvider>>,
     Path(PathParams { id: thread_id }): Path<PathParams>,
 ) -> Result<Response, SeenThreadError> {
     let messages = email_db_client::messages::get_simple_messages::get_simple_messages_for_thread(
@@ -85,13 +87,10 @@ pub async fn seen_handler(
         .await
         .context("Failed to upsert user history")?;
 
-    let gmail_access_token = gmail_token.as_str();
-
     // remove UNREAD label from thread's messages that are currently unread
     let (successful_ids, failed_ids) = remove_label_from_messages(
         &ctx.db,
-        gmail_access_token,
-        &ctx.gmail_client,
+        provider.as_ref(),
         messages.iter().filter(|m| !m.is_read).cloned().collect(),
         service::label::system_labels::UNREAD,
     )
@@ -0,0 +1,119 @@
+use crate::api::context::ApiContext;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use model::response::{EmptyResponse, ErrorResponse};
+use model::user::UserContext;
+use models_email::email::service::link::Link;
+use sqlx::types::Uuid;
+use sqlx::types::chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, ToSchema)]
+pub struct SnoozeThreadRequest {
+    /// the thread is hidden from the inbox until this time
+    pub snoozed_until: DateTime<Utc>,
+}
+
+async fn update_snoozed_until(
+    ctx: &ApiContext,
+    link: &Link,
+    thread_id: Uuid,
+    snoozed_until: Option<DateTime<Utc>>,
+) -> Result<Response, Response> {
+    let updated = email_db_client::threads::update::update_thread_snoozed_until(
+        &ctx.db,
+        thread_id,
+        link.id,
+        snoozed_until,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to update thread snooze");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to update thread snooze",
+            }),
+        )
+            .into_response()
+    })?;
+
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "thread not found",
+            }),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse::default())).into_response())
+}
+
+/// Snooze a thread, hiding it from the inbox until the given time.
+#[utoipa::path(
+    put,
+    tag = "Threads",
+    path = "/email/threads/{id}/snooze",
+    operation_id = "snooze_thread",
+    params(
+        ("id" = Uuid, Path, description = "Thread ID."),
+    ),
+    request_body = SnoozeThreadRequest,
+    responses(
+            (status = 200, body=EmptyResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link, body), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn snooze_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(thread_id): Path<Uuid>,
+    Json(body): Json<SnoozeThreadRequest>,
+) -> Result<Response, Response> {
+    if body.snoozed_until <= Utc::now() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "snoozed_until must be in the future",
+            }),
+        )
+            .into_response());
+    }
+
+    update_snoozed_until(&ctx, &link, thread_id, Some(body.snoozed_until)).await
+}
+
+/// Unsnooze a thread, returning it to the inbox immediately.
+#[utoipa::path(
+    delete,
+    tag = "Threads",
+    path = "/email/threads/{id}/snooze",
+    operation_id = "unsnooze_thread",
+    params(
+        ("id" = Uuid, Path, description = "Thread ID."),
+    ),
+    responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+    )
+)]
+#[tracing::instrument(skip(ctx, user_context, link), fields(user_id=user_context.user_id, fusionauth_user_id=user_context.fusion_user_id))]
+pub async fn unsnooze_handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    link: Extension<Link>,
+    Path(thread_id): Path<Uuid>,
+) -> Result<Response, Response> {
+    update_snoozed_until(&ctx, &link, thread_id, None).await
+}
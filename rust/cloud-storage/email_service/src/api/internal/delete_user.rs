@@ -4,6 +4,7 @@ use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use model::response::ErrorResponse;
+use models_email::email::service::link::UserProvider;
 use sqs_client::search::SearchQueueMessage;
 use sqs_client::search::email::EmailLinkMessage;
 
@@ -39,24 +40,47 @@ pub async fn handler(
             tracing::error!(error=?e, link_id=?link.id, "Failed to update backfill job statuses");
         };
 
-        let gmail_access_token = match crate::util::gmail::auth::fetch_gmail_access_token_from_link(
-            &link,
-            &ctx.redis_client,
-            &ctx.auth_service_client,
-        )
-        .await
-        {
-            Ok(token) => Some(token),
-            Err(e) => {
-                tracing::error!(error=?e, link_id=?link.id, "unable to fetch access token - skipping stop watch");
-                None
-            }
-        };
+        match link.provider {
+            UserProvider::Gmail => {
+                let gmail_access_token =
+                    match crate::util::gmail::auth::fetch_gmail_access_token_from_link(
+                        &link,
+                        &ctx.redis_client,
+                        &ctx.auth_service_client,
+                    )
+                    .await
+                    {
+                        Ok(token) => Some(token),
+                        Err(e) => {
+                            tracing::error!(error=?e, link_id=?link.id, "unable to fetch access token - skipping stop watch");
+                            None
+                        }
+                    };
 
-        if let Some(token) = gmail_access_token
-            && let Err(e) = ctx.gmail_client.stop_watch(&token).await
-        {
-            tracing::error!(error=?e, link_id=?link.id, "gmail call to stop watch failed");
+                if let Some(token) = gmail_access_token
+                    && let Err(e) = ctx.gmail_client.stop_watch(&token).await
+                {
+                    tracing::error!(error=?e, link_id=?link.id, "gmail call to stop watch failed");
+                }
+            }
+            // the imap account is deleted along with the link but its password is not
+            UserProvider::Imap => {
+                match email_db_client::imap_accounts::fetch_imap_account(&ctx.db, link.id).await {
+                    Ok(Some(account)) => {
+                        if let Err(e) = ctx
+                            .mail_providers
+                            .delete_imap_password(&account.password_secret_id)
+                            .await
+                        {
+                            tracing::error!(error=?e, link_id=?link.id, "unable to delete imap password");
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::error!(error=?e, link_id=?link.id, "unable to fetch imap account");
+                    }
+                }
+            }
         }
 
         if let Err(e) =
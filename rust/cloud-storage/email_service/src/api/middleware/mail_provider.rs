@@ -0,0 +1,37 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{Extension, Json, extract::Request, middleware::Next, response::Response};
+use mail_provider::MailProvider;
+use model::response::ErrorResponse;
+use models_email::service::link::Link;
+use std::sync::Arc;
+
+use crate::api::context::ApiContext;
+
+/// Attaches the mail provider of the link. Must be layered inside attach_link_context.
+pub(in crate::api) async fn attach_mail_provider(
+    State(ctx): State<ApiContext>,
+    link: Extension<Link>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    let provider: Arc<dyn MailProvider> = ctx
+        .mail_providers
+        .for_link(&link)
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, link_id=%link.id, "unable to build mail provider");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    message: "unable to connect to mail provider",
+                }),
+            )
+                .into_response()
+        })?
+        .into();
+
+    req.extensions_mut().insert(provider);
+    Ok(next.run(req).await)
+}
@@ -1,2 +1,3 @@
 pub mod gmail_token;
 pub mod link;
+pub mod mail_provider;
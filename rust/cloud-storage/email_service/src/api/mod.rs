@@ -19,7 +19,10 @@ mod middleware;
 mod swagger;
 
 pub async fn setup_and_serve(state: ApiContext) -> anyhow::Result<()> {
-    let cors = macro_cors::cors_layer();
+    // the app reads the next page of a label export from its response headers
+    let cors = macro_cors::cors_layer().expose_headers([axum::http::HeaderName::from_static(
+        email::archives::export_label::NEXT_OFFSET_HEADER,
+    )]);
 
     let env = state.config.environment;
     let port = state.config.port;
@@ -1,19 +1,47 @@
+use crate::api::email::archives::import::ImportArchiveResponse;
 use crate::api::email::attachments::get::GetAttachmentResponse;
 use crate::api::email::attachments::get_document_id::GetAttachmentDocumentIDResponse;
 use crate::api::email::backfill::cancel::CancelBackfillParams;
 use crate::api::email::backfill::get::{GetActiveBackfillJobResponse, GetBackfillJobResponse};
+use crate::api::email::contacts::duplicates::{DismissDuplicateRequest, ListDuplicatesResponse};
 use crate::api::email::contacts::list::ListContactsResponse;
+use crate::api::email::contacts::people::{
+    ListPeopleResponse, MergeContactsRequest, PersonResponse, SplitPersonRequest,
+    SplitPersonResponse,
+};
+use crate::api::email::contacts::vcard::ImportVCardResponse;
 use crate::api::email::drafts::create::{CreateDraftRequest, CreateDraftResponse};
-use crate::api::email::init::InitResponse;
+use crate::api::email::init::{InitImapRequest, InitResponse};
 use crate::api::email::labels::create::CreateLabelRequest;
 use crate::api::email::labels::create::CreateLabelResponse;
 use crate::api::email::labels::list::ListLabelsResponse;
 use crate::api::email::links::list::ListLinksResponse;
 use crate::api::email::messages::labels::{UpdateLabelBatchRequest, UpdateLabelBatchResponse};
 use crate::api::email::messages::send::{SendMessageRequest, SendMessageResponse};
+use crate::api::email::rules::create::{CreateRuleRequest, RuleResponse};
+use crate::api::email::rules::list::ListRulesResponse;
+use crate::api::email::rules::patch::PatchRuleRequest;
+use crate::api::email::rules::preview::{
+    PreviewRuleRequest, PreviewRuleResponse, PreviewRuleThread,
+};
 use crate::api::email::settings::patch::{PatchSettingsRequest, PatchSettingsResponse};
+use crate::api::email::subscriptions::list::ListSubscriptionsResponse;
+use crate::api::email::subscriptions::threads::ListSubscriptionThreadsResponse;
+use crate::api::email::subscriptions::unsubscribe::{
+    UnsubscribeMethod, UnsubscribeRequest, UnsubscribeResponse,
+};
+use crate::api::email::templates::create::{CreateTemplateRequest, TemplateResponse};
+use crate::api::email::templates::list::ListTemplatesResponse;
+use crate::api::email::templates::merge::{
+    MergeRecipient, MergeSkipReason, MergeTemplateRequest, MergeTemplateResponse,
+    ScheduledMergeMessage, SkippedMergeRecipient,
+};
+use crate::api::email::templates::patch::PatchTemplateRequest;
+use crate::api::email::templates::render::{RenderTemplateRequest, RenderTemplateResponse};
 use crate::api::email::threads::archived::ArchiveThreadRequest;
+use crate::api::email::threads::follow_up::{FollowUpResponse, SetFollowUpRequest};
 use crate::api::email::threads::get::GetThreadResponse;
+use crate::api::email::threads::snooze::SnoozeThreadRequest;
 use crate::api::{email, health};
 use ::email::inbound;
 use ::email::inbound::{ApiPaginatedThreadCursor, ApiSortMethod, GetPreviewsCursorParams};
@@ -22,10 +50,18 @@ use models_email::api::settings::Settings;
 use models_email::email::service;
 use models_email::email::service::address::ContactInfoWithInteraction;
 use models_email::email::service::backfill::BackfillJob;
+use models_email::email::service::imap_account::ConnectionSecurity;
 use models_email::email::service::link::Link;
+use models_email::email::service::person::{
+    DuplicateCandidate, DuplicateReason, LinkContact, Person,
+};
 use models_email::email::service::thread::{PreviewView, PreviewViewStandardLabel};
+use models_email::service::follow_up::FollowUpReminder;
 use models_email::service::label::Label;
 use models_email::service::message::{MessageToSend, ParsedMessage};
+use models_email::service::rule::{Rule, RuleAction, RuleCondition};
+use models_email::service::subscription::{Subscription, SubscriptionThread};
+use models_email::service::template::{RenderedTemplate, Template, TemplateVariable};
 use models_email::service::thread::{APIThread, ThreadPreviewCursor};
 use utoipa::OpenApi;
 
@@ -36,31 +72,62 @@ use utoipa::OpenApi;
     ),
     paths(
         health::health_handler,
+        email::archives::import::handler,
+        email::archives::export_thread::handler,
+        email::archives::export_label::handler,
         email::attachments::get::handler,
         email::attachments::get_document_id::handler,
         email::backfill::cancel::handler,
         email::backfill::get::handler,
         email::backfill::get::active_handler,
         email::init::handler,
+        email::init::imap_handler,
         email::drafts::create::handler,
         email::drafts::delete::handler,
         email::messages::get::handler,
         email::messages::get::batch_handler,
         email::messages::labels::handler,
         email::messages::send::send_handler,
+        email::messages::outbox::cancel_handler,
         email::threads::seen::seen_handler,
         email::threads::get::get_thread_handler,
         email::threads::get::get_thread_messages_handler,
         email::threads::archived::archived_handler,
+        email::threads::snooze::snooze_handler,
+        email::threads::snooze::unsnooze_handler,
+        email::threads::follow_up::set_follow_up_handler,
+        email::threads::follow_up::delete_follow_up_handler,
         inbound::cursor_handler,
         email::links::list::list_links_handler,
         email::labels::create::handler,
         email::labels::delete::handler,
         email::labels::list::handler,
+        email::rules::list::handler,
+        email::rules::create::handler,
+        email::rules::patch::handler,
+        email::rules::delete::handler,
+        email::rules::preview::handler,
+        email::rules::apply::handler,
         email::contacts::list::list_contacts_handler,
+        email::contacts::people::list_handler,
+        email::contacts::people::merge_handler,
+        email::contacts::people::split_handler,
+        email::contacts::duplicates::list_handler,
+        email::contacts::duplicates::dismiss_handler,
+        email::contacts::vcard::export_handler,
+        email::contacts::vcard::import_handler,
         email::sync::enable::enable_handler,
         email::sync::disable::disable_handler,
         email::settings::patch::patch_settings_handler,
+        email::subscriptions::list::handler,
+        email::subscriptions::threads::handler,
+        email::subscriptions::unsubscribe::handler,
+        email::templates::list::handler,
+        email::templates::create::handler,
+        email::templates::patch::handler,
+        email::templates::delete::handler,
+        email::templates::render::handler,
+        email::templates::merge::handler,
     ),
     components(
         schemas(
@@ -75,11 +142,48 @@ use utoipa::OpenApi;
             CreateDraftResponse,
             // Init types
             InitResponse,
+            InitImapRequest,
+            ConnectionSecurity,
             // Label types
             CreateLabelRequest,
             CreateLabelResponse,
             ListLabelsResponse,
             Label,
+            // Rule types
+            CreateRuleRequest,
+            PatchRuleRequest,
+            RuleResponse,
+            ListRulesResponse,
+            PreviewRuleRequest,
+            PreviewRuleResponse,
+            PreviewRuleThread,
+            Rule,
+            RuleCondition,
+            RuleAction,
+            // Subscription types
+            ListSubscriptionsResponse,
+            ListSubscriptionThreadsResponse,
+            UnsubscribeRequest,
+            UnsubscribeResponse,
+            UnsubscribeMethod,
+            Subscription,
+            SubscriptionThread,
+            // Template types
+            CreateTemplateRequest,
+            PatchTemplateRequest,
+            TemplateResponse,
+            ListTemplatesResponse,
+            RenderTemplateRequest,
+            RenderTemplateResponse,
+            MergeTemplateRequest,
+            MergeTemplateResponse,
+            MergeRecipient,
+            ScheduledMergeMessage,
+            SkippedMergeRecipient,
+            MergeSkipReason,
+            Template,
+            TemplateVariable,
+            RenderedTemplate,
             // Message types
             UpdateLabelBatchRequest,
             UpdateLabelBatchResponse,
@@ -90,6 +194,10 @@ use utoipa::OpenApi;
             // Thread types
             GetThreadResponse,
             ArchiveThreadRequest,
+            SnoozeThreadRequest,
+            SetFollowUpRequest,
+            FollowUpResponse,
+            FollowUpReminder,
             APIThread,
             ThreadPreviewCursor,
             // Preview types
@@ -97,6 +205,8 @@ use utoipa::OpenApi;
             ApiPaginatedThreadCursor,
             PreviewView,
             PreviewViewStandardLabel,
+            // Archive types
+            ImportArchiveResponse,
             // Attachment types
             GetAttachmentResponse,
             GetAttachmentDocumentIDResponse,
@@ -107,6 +217,18 @@ use utoipa::OpenApi;
             // Contact types
             ListContactsResponse,
             ContactInfoWithInteraction,
+            ListPeopleResponse,
+            MergeContactsRequest,
+            PersonResponse,
+            SplitPersonRequest,
+            SplitPersonResponse,
+            ListDuplicatesResponse,
+            DismissDuplicateRequest,
+            ImportVCardResponse,
+            Person,
+            LinkContact,
+            DuplicateCandidate,
+            DuplicateReason,
             // Sort/filter types
             ApiSortMethod,
             // Legacy service types (keeping for backward compatibility)
@@ -62,7 +62,9 @@ impl AttachmentProcessor {
         let (sha256_hex, sha256_base64) = Self::calculate_hashes(&data);
 
         // 3. Get a presigned URL from the Document Storage Service.
-        let (presigned_url, content_type) = self.get_presigned_url(attachment, &sha256_hex).await?;
+        let (presigned_url, content_type) = self
+            .get_presigned_url(attachment, &sha256_hex, data.len() as i64)
+            .await?;
 
         // 4. Upload the data to the presigned URL (e.g., S3).
         self.upload_to_storage(&presigned_url, &content_type, &sha256_base64, data)
@@ -103,6 +105,7 @@ impl AttachmentProcessor {
         &self,
         attachment: &AttachmentUploadMetadata,
         sha256_hex: &str,
+        size_bytes: i64,
     ) -> anyhow::Result<(String, String)> {
         let file_name = attachment
             .filename
@@ -121,6 +124,7 @@ impl AttachmentProcessor {
                 CreateDocumentRequest {
                     id: None,
                     sha: sha256_hex.to_string(),
+                    size_bytes,
                     document_name: file_name,
                     file_type: Some(file_type),
                     mime_type: Some(attachment.mime_type.clone()),
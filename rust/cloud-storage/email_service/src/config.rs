@@ -108,6 +108,12 @@ pub struct Config {
 
     // How long presigned urls should be valid for attachments
     pub presigned_url_ttl_secs: u64,
+
+    /// The url of the unfurl service image proxy that remote email images are loaded through
+    pub image_proxy_url: String,
+
+    /// The secret image proxy urls are signed with, shared with the unfurl service
+    pub image_proxy_secret_key: String,
 }
 
 env_var! { pub struct CloudfrontSignerPrivateKey; }
@@ -237,6 +243,12 @@ impl Config {
             .parse::<u64>()
             .unwrap();
 
+        let image_proxy_url =
+            std::env::var("IMAGE_PROXY_URL").context("IMAGE_PROXY_URL must be provided")?;
+
+        let image_proxy_secret_key = std::env::var("IMAGE_PROXY_SECRET_KEY")
+            .context("IMAGE_PROXY_SECRET_KEY must be provided")?;
+
         Ok(Config {
             macro_db_url: database_url,
             port,
@@ -272,6 +284,8 @@ impl Config {
             cloudfront_signer_public_key_id,
             cloudfront_signer_private_key,
             presigned_url_ttl_secs,
+            image_proxy_url,
+            image_proxy_secret_key,
         })
     }
 }
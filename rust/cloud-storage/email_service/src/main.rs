@@ -199,12 +199,21 @@ async fn main() -> anyhow::Result<()> {
         config.connection_gateway_url.clone(),
     );
 
+    let mail_providers = util::mail_provider::MailProviderFactory::new(
+        db.clone(),
+        gmail_client.clone(),
+        auth_service_client.clone(),
+        redis_client.clone(),
+        secretsmanager_client.clone(),
+    );
+
     for worker in webhook_workers {
         let db_webhook = db.clone();
         let sqs_client_webhook = sqs_client.clone();
         let gmail_client_webhook = gmail_client.clone();
         let auth_service_client_webhook = auth_service_client.clone();
         let redis_client_webhook = redis_client.clone();
+        let mail_providers_webhook = mail_providers.clone();
         let macro_notify_client_webhook = macro_notify_client.clone();
         let sfs_client_webhook = sfs_client.clone();
         let connection_gateway_client_webhook = connection_gateway_client.clone();
@@ -217,6 +226,7 @@ async fn main() -> anyhow::Result<()> {
                 gmail_client_webhook,
                 auth_service_client_webhook,
                 redis_client_webhook,
+                mail_providers_webhook,
                 macro_notify_client_webhook,
                 sfs_client_webhook,
                 connection_gateway_client_webhook,
@@ -237,6 +247,7 @@ async fn main() -> anyhow::Result<()> {
         let gmail_client_backfill = gmail_client.clone();
         let auth_service_client_backfill = auth_service_client.clone();
         let redis_client_backfill = redis_client.clone();
+        let mail_providers_backfill = mail_providers.clone();
         let macro_notify_client_backfill = macro_notify_client.clone();
         let sfs_client_backfill = sfs_client.clone();
         let connection_gateway_client_backfill = connection_gateway_client.clone();
@@ -249,6 +260,7 @@ async fn main() -> anyhow::Result<()> {
                 gmail_client_backfill,
                 auth_service_client_backfill,
                 redis_client_backfill,
+                mail_providers_backfill,
                 macro_notify_client_backfill,
                 sfs_client_backfill,
                 connection_gateway_client_backfill,
@@ -281,16 +293,29 @@ async fn main() -> anyhow::Result<()> {
     });
 
     let db_scheduled = db.clone();
-    let gmail_client_scheduled = gmail_client.clone();
-    let auth_service_client_scheduled = auth_service_client.clone();
-    let redis_client_scheduled = redis_client.clone();
+    let mail_providers_scheduled = mail_providers.clone();
+    let macro_notify_client_scheduled = macro_notify_client.clone();
     tokio::spawn(async move {
         pubsub::scheduled::worker::run_worker(
             scheduled_worker,
             db_scheduled,
-            gmail_client_scheduled,
-            auth_service_client_scheduled,
-            redis_client_scheduled,
+            mail_providers_scheduled,
+            macro_notify_client_scheduled,
+            config.notifications_enabled,
+        )
+        .await;
+    });
+
+    let db_imap_watcher = db.clone();
+    let sqs_client_imap_watcher = sqs_client.clone();
+    let redis_client_imap_watcher = redis_client.clone();
+    let mail_providers_imap_watcher = mail_providers.clone();
+    tokio::spawn(async move {
+        pubsub::imap_watcher::worker::run_worker(
+            db_imap_watcher,
+            sqs_client_imap_watcher,
+            redis_client_imap_watcher,
+            mail_providers_imap_watcher,
         )
         .await;
     });
@@ -318,6 +343,8 @@ async fn main() -> anyhow::Result<()> {
         JwtValidationArgs::new_with_secret_manager(config.environment, &secretsmanager_client)
             .await?;
 
+    let fetcher = outbound_http::Fetcher::new().context("unable to build fetcher")?;
+
     api::setup_and_serve(ApiContext {
         db: db.clone(),
         config: Arc::new(config),
@@ -326,10 +353,12 @@ async fn main() -> anyhow::Result<()> {
         sqs_client: Arc::new(sqs_client),
         sfs_client: Arc::new(sfs_client),
         gmail_client: Arc::new(gmail_client),
+        mail_providers: Arc::new(mail_providers),
         s3_client: Arc::new(s3_client),
         dss_client: Arc::new(dss_client),
         jwt_args,
         internal_auth_key: LocalOrRemoteSecret::Local(internal_auth_key),
+        fetcher,
         email_service: EmailPreviewState::new(EmailServiceImpl::new(
             EmailPgRepo::new(db.clone()),
             FrecencyQueryServiceImpl::new(FrecencyPgStorage::new(db)),
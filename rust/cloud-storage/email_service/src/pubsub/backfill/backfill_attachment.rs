@@ -1,5 +1,6 @@
 use crate::pubsub::context::PubSubContext;
 use crate::util::upload_attachment::upload_attachment;
+use mail_provider::MailProvider;
 use models_email::service::backfill::BackfillAttachmentPayload;
 use models_email::service::link;
 use models_email::service::pubsub::{DetailedError, FailureReason, ProcessingError};
@@ -8,10 +9,10 @@ use uuid::Uuid;
 /// this step is invoked by the UpdateMetadata step. it uploads the specified attachment as a
 /// Macro document for the user. first checks the attachment doesn't already exist by querying
 /// document_email table before fetching and uploading the attachment data.
-#[tracing::instrument(skip(ctx, access_token))]
+#[tracing::instrument(skip(ctx, provider))]
 pub async fn backfill_attachment(
     ctx: &PubSubContext,
-    access_token: &str,
+    provider: &dyn MailProvider,
     link: &link::Link,
     p: &BackfillAttachmentPayload,
 ) -> Result<(), ProcessingError> {
@@ -22,9 +23,8 @@ pub async fn backfill_attachment(
 
     upload_attachment(
         &ctx.redis_client,
-        &ctx.gmail_client,
+        provider,
         &ctx.dss_client,
-        access_token,
         link,
         &p.metadata,
     )
@@ -32,7 +32,7 @@ pub async fn backfill_attachment(
     .map_err(|e| {
         ProcessingError::NonRetryable(DetailedError {
             reason: FailureReason::GmailApiFailed,
-            source: e.context("Failed to fetch attachment data from Gmail"),
+            source: e.context("Failed to fetch attachment data from provider"),
         })
     })?;
 
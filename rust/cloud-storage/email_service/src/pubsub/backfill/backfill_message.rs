@@ -2,6 +2,7 @@ use crate::pubsub::backfill::increment_counters;
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::check_gmail_rate_limit;
 use crate::util::process_pre_insert::process_message_pre_insert;
+use mail_provider::MailProvider;
 use models_email::email::service::backfill::{BackfillMessagePayload, BackfillPubsubMessage};
 use models_email::email::service::link;
 use models_email::email::service::pubsub::{DetailedError, FailureReason, ProcessingError};
@@ -10,10 +11,10 @@ use models_email::gmail::operations::GmailApiOperation;
 /// This step is invoked by BackfillThread once for each message in the thread.
 /// Creates a message object in the database. If the message is the last message in
 /// the thread to be processed, it sends an UpdateThreadMetadata message for the thread.
-#[tracing::instrument(skip(ctx, access_token))]
+#[tracing::instrument(skip(ctx, provider))]
 pub async fn backfill_message(
     ctx: &PubSubContext,
-    access_token: &str,
+    provider: &dyn MailProvider,
     data: &BackfillPubsubMessage,
     link: &link::Link,
     p: &BackfillMessagePayload,
@@ -26,17 +27,13 @@ pub async fn backfill_message(
     )
     .await?;
 
-    // get message from gmail
-    let mut message = match ctx
-        .gmail_client
-        .get_message(access_token, &p.message_provider_id, link.id)
-        .await
-    {
+    // get message from the provider
+    let mut message = match provider.get_message(&p.message_provider_id, link.id).await {
         Ok(Some(message)) => message,
         Ok(None) => {
             return Err(ProcessingError::NonRetryable(DetailedError {
                 reason: FailureReason::MessageNotFoundInProvider,
-                source: anyhow::anyhow!("Message {} not found in Gmail", p.message_provider_id),
+                source: anyhow::anyhow!("Message {} not found in provider", p.message_provider_id),
             }));
         }
         Err(e) => {
@@ -1,6 +1,7 @@
 use crate::pubsub::backfill::increment_counters::incr_completed_threads;
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::check_gmail_rate_limit;
+use mail_provider::MailProvider;
 use models_email::email::service::backfill::{
     BackfillMessagePayload, BackfillOperation, BackfillPubsubMessage, BackfillThreadPayload,
 };
@@ -12,10 +13,10 @@ use std::collections::HashSet;
 /// This step is invoked by ListThreads for each thread being backfilled.
 /// Creates the thread object in the database, fetches the message ids for the thread
 /// from the gmail api, and sends a BackfillMessage message for each message_id.
-#[tracing::instrument(skip(ctx, access_token))]
+#[tracing::instrument(skip(ctx, provider))]
 pub async fn backfill_thread(
     ctx: &PubSubContext,
-    access_token: &str,
+    provider: &dyn MailProvider,
     data: &BackfillPubsubMessage,
     link: &link::Link,
     p: &BackfillThreadPayload,
@@ -52,9 +53,8 @@ pub async fn backfill_thread(
     )
     .await?;
     // fetch all message_ids of the thread
-    let message_ids = match ctx
-        .gmail_client
-        .get_message_ids_for_thread(access_token, &thread_provider_id)
+    let message_ids = match provider
+        .get_message_ids_for_thread(&thread_provider_id)
         .await
     {
         Ok(ids) => ids,
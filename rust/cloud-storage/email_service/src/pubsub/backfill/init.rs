@@ -1,7 +1,9 @@
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::check_gmail_rate_limit;
+use crate::util::gmail::auth::fetch_gmail_access_token_from_link;
 use crate::util::process_pre_insert::sync_labels::sync_labels;
 use crate::util::sync_contacts::sync_contacts;
+use mail_provider::MailProvider;
 use models_email::email::service::backfill::{
     BackfillJobStatus, BackfillOperation, BackfillPubsubMessage,
 };
@@ -13,10 +15,10 @@ use std::cmp::min;
 
 /// This step is invoked via the API when a new job is created.
 /// Populates total_threads value in backfill_job row, and sends the first ListThreads message.
-#[tracing::instrument(skip(ctx, access_token))]
+#[tracing::instrument(skip(ctx, provider))]
 pub async fn init_backfill(
     ctx: &PubSubContext,
-    access_token: &str,
+    provider: &dyn MailProvider,
     data: &BackfillPubsubMessage,
     link: &link::Link,
     backfill_job: &backfill::BackfillJob,
@@ -24,25 +26,16 @@ pub async fn init_backfill(
     tracing::info!("Initializing backfill job");
 
     // ensure we have the user's labels in the db
-    sync_labels(&ctx.db, &ctx.gmail_client, access_token, link.id)
-        .await
-        .map_err(|e| {
-            ProcessingError::Retryable(DetailedError {
-                reason: FailureReason::DatabaseQueryFailed,
-                source: e.context("Failed to sync labels"),
-            })
-        })?;
+    sync_labels(&ctx.db, provider, link.id).await.map_err(|e| {
+        ProcessingError::Retryable(DetailedError {
+            reason: FailureReason::DatabaseQueryFailed,
+            source: e.context("Failed to sync labels"),
+        })
+    })?;
 
-    if let Err(e) = sync_contacts(
-        link,
-        &ctx.db,
-        &ctx.gmail_client,
-        &ctx.sqs_client,
-        access_token,
-    )
-    .await
-    {
-        tracing::error!(error = ?e, "Failed to sync contacts");
+    // contacts are only available through the Google People API
+    if link.provider == link::UserProvider::Gmail {
+        sync_gmail_contacts(ctx, link).await;
     }
 
     let threads_requested_limit = backfill_job.threads_requested_limit;
@@ -55,11 +48,7 @@ pub async fn init_backfill(
     )
     .await?;
     // get the total number of threads the user has in their account
-    let total_threads = match ctx
-        .gmail_client
-        .get_profile_threads_total(access_token)
-        .await
-    {
+    let total_threads = match provider.get_total_threads().await {
         Ok(list) => list,
         Err(e) => {
             // Construct the structured Retryable error and return immediately.
@@ -133,3 +122,28 @@ pub async fn init_backfill(
 
     Ok(())
 }
+
+async fn sync_gmail_contacts(ctx: &PubSubContext, link: &link::Link) {
+    let access_token =
+        match fetch_gmail_access_token_from_link(link, &ctx.redis_client, &ctx.auth_service_client)
+            .await
+        {
+            Ok(access_token) => access_token,
+            Err(e) => {
+                tracing::error!(error = ?e, "Failed to fetch access token to sync contacts");
+                return;
+            }
+        };
+
+    if let Err(e) = sync_contacts(
+        link,
+        &ctx.db,
+        &ctx.gmail_client,
+        &ctx.sqs_client,
+        &access_token,
+    )
+    .await
+    {
+        tracing::error!(error = ?e, "Failed to sync contacts");
+    }
+}
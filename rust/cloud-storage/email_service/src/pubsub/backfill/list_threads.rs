@@ -1,5 +1,6 @@
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::check_gmail_rate_limit;
+use mail_provider::MailProvider;
 use models_email::email::service::backfill::{
     BackfillJob, BackfillOperation, BackfillPubsubMessage, BackfillThreadPayload,
 };
@@ -19,7 +20,7 @@ const BACKFILL_THREAD_BATCH_SIZE: u32 = 500;
 /// to be created, looping until all threads requiring population have been listed.
 pub async fn list_threads(
     ctx: &PubSubContext,
-    access_token: &str,
+    provider: &dyn MailProvider,
     data: &BackfillPubsubMessage,
     link: &link::Link,
     p: &ListThreadsPayload,
@@ -41,13 +42,8 @@ pub async fn list_threads(
     )
     .await?;
     // get batch of thread ids
-    let thread_list = match ctx
-        .gmail_client
-        .list_threads(
-            access_token,
-            num_threads_to_list as u32,
-            p.next_page_token.as_deref(),
-        )
+    let thread_list = match provider
+        .list_threads(num_threads_to_list as u32, p.next_page_token.as_deref())
         .await
     {
         Ok(list) => list,
@@ -3,7 +3,6 @@ use crate::pubsub::backfill::{
     update_metadata,
 };
 use crate::pubsub::context::PubSubContext;
-use crate::util::gmail::auth::fetch_gmail_access_token_from_link;
 use anyhow::Context;
 use models_email::email::service::backfill::{
     BackfillJobStatus, BackfillOperation, BackfillPubsubMessage,
@@ -92,34 +91,32 @@ async fn inner_process_message(
         }
     };
 
-    let access_token =
-        fetch_gmail_access_token_from_link(&link, &ctx.redis_client, &ctx.auth_service_client)
-            .await
-            .map_err(|e| {
-                ProcessingError::NonRetryable(DetailedError {
-                    reason: FailureReason::AccessTokenFetchFailed,
-                    source: e.context("Failed to fetch access token from link"),
-                })
-            })?;
+    let provider = ctx.mail_providers.for_link(&link).await.map_err(|e| {
+        ProcessingError::NonRetryable(DetailedError {
+            reason: FailureReason::AccessTokenFetchFailed,
+            source: e.context("Failed to build mail provider for link"),
+        })
+    })?;
+    let provider = provider.as_ref();
 
     match &data.backfill_operation {
         BackfillOperation::Init => {
-            init::init_backfill(ctx, &access_token, data, &link, &backfill_job).await?
+            init::init_backfill(ctx, provider, data, &link, &backfill_job).await?
         }
         BackfillOperation::ListThreads(p) => {
-            list_threads::list_threads(ctx, &access_token, data, &link, p, &backfill_job).await?
+            list_threads::list_threads(ctx, provider, data, &link, p, &backfill_job).await?
         }
         BackfillOperation::BackfillThread(p) => {
-            backfill_thread::backfill_thread(ctx, &access_token, data, &link, p).await?
+            backfill_thread::backfill_thread(ctx, provider, data, &link, p).await?
         }
         BackfillOperation::BackfillMessage(p) => {
-            backfill_message::backfill_message(ctx, &access_token, data, &link, p).await?
+            backfill_message::backfill_message(ctx, provider, data, &link, p).await?
         }
         BackfillOperation::UpdateThreadMetadata(p) => {
             update_metadata::update_thread_metadata(ctx, data, &link, p).await?
         }
         BackfillOperation::BackfillAttachment(p) => {
-            backfill_attachment::backfill_attachment(ctx, &access_token, &link, p).await?
+            backfill_attachment::backfill_attachment(ctx, provider, &link, p).await?
         }
     };
 
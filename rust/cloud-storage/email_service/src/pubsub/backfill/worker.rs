@@ -1,5 +1,6 @@
 use crate::pubsub::backfill::process;
 use crate::pubsub::context::PubSubContext;
+use crate::util::mail_provider::MailProviderFactory;
 use crate::util::redis::RedisClient;
 use authentication_service_client::AuthServiceClient;
 use connection_gateway_client::client::ConnectionGatewayClient;
@@ -17,6 +18,7 @@ pub async fn run_worker(
     gmail_client: gmail_client::GmailClient,
     auth_service_client: AuthServiceClient,
     redis_client: RedisClient,
+    mail_providers: MailProviderFactory,
     macro_notify_client: MacroNotifyClient,
     sfs_client: StaticFileServiceClient,
     connection_gateway_client: ConnectionGatewayClient,
@@ -30,6 +32,7 @@ pub async fn run_worker(
         gmail_client,
         auth_service_client,
         redis_client,
+        mail_providers,
         macro_notify_client,
         sfs_client,
         connection_gateway_client,
@@ -1,3 +1,4 @@
+use crate::util::mail_provider::MailProviderFactory;
 use crate::util::redis::RedisClient;
 use authentication_service_client::AuthServiceClient;
 use connection_gateway_client::client::ConnectionGatewayClient;
@@ -15,6 +16,7 @@ pub struct PubSubContext {
     pub gmail_client: GmailClient,
     pub auth_service_client: AuthServiceClient,
     pub redis_client: RedisClient,
+    pub mail_providers: MailProviderFactory,
     pub macro_notify_client: MacroNotifyClient,
     pub sfs_client: StaticFileServiceClient,
     pub connection_gateway_client: ConnectionGatewayClient,
@@ -0,0 +1,11 @@
+use crate::util::mail_provider::MailProviderFactory;
+use crate::util::redis::RedisClient;
+use sqlx::PgPool;
+
+#[derive(Clone)]
+pub struct ImapWatcherContext {
+    pub db: PgPool,
+    pub sqs_client: sqs_client::SQS,
+    pub redis_client: RedisClient,
+    pub mail_providers: MailProviderFactory,
+}
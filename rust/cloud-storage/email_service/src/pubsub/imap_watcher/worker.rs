@@ -0,0 +1,186 @@
+use crate::pubsub::imap_watcher::context::ImapWatcherContext;
+use crate::util::mail_provider::MailProviderFactory;
+use crate::util::redis::RedisClient;
+use imap_client::IdleEvent;
+use models_email::gmail::webhook::{WebhookOperation, WebhookPubsubMessage};
+use models_email::service::link::{Link, UserProvider};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often we look for IMAP links that nobody is watching
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a watch lease lasts without being renewed
+const LEASE_TTL_SECS: u64 = 90;
+
+/// How often a held watch lease is renewed
+const LEASE_RENEW_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long we IDLE before re-issuing the command. Servers may drop idle connections after
+/// 30 minutes, and every timeout also triggers a sync of the mailboxes we are not idling on.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How long we wait before reconnecting after a connection error
+const RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// The mailbox we IDLE on. IDLE only reports changes to the selected mailbox.
+const WATCHED_MAILBOX: &str = "INBOX";
+
+/// Watches the inbox of every active IMAP link with IDLE and enqueues an ImapMessage webhook
+/// operation whenever it changes. IMAP has no push notifications like the Gmail watch, so a
+/// connection is held open per link. A redis lease ensures only one instance of the service
+/// watches each link.
+pub async fn run_worker(
+    db: PgPool,
+    sqs_client: sqs_client::SQS,
+    redis_client: RedisClient,
+    mail_providers: MailProviderFactory,
+) {
+    let ctx = ImapWatcherContext {
+        db,
+        sqs_client,
+        redis_client,
+        mail_providers,
+    };
+
+    let owner = macro_uuid::generate_uuid_v7();
+    let watching: Arc<Mutex<HashSet<Uuid>>> = Arc::default();
+
+    loop {
+        match email_db_client::links::get::fetch_active_links_by_provider(
+            &ctx.db,
+            UserProvider::Imap,
+        )
+        .await
+        {
+            Ok(links) => {
+                for link in links {
+                    if watching.lock().unwrap().contains(&link.id) {
+                        continue;
+                    }
+
+                    match ctx
+                        .redis_client
+                        .acquire_imap_watch_lease(link.id, owner, LEASE_TTL_SECS)
+                        .await
+                    {
+                        Ok(true) => {
+                            watching.lock().unwrap().insert(link.id);
+                            tokio::spawn({
+                                let ctx = ctx.clone();
+                                let watching = watching.clone();
+                                async move {
+                                    let link_id = link.id;
+                                    watch_link(&ctx, link, owner).await;
+                                    watching.lock().unwrap().remove(&link_id);
+                                }
+                            });
+                        }
+                        Ok(false) => {}
+                        Err(e) => {
+                            tracing::error!(error=?e, link_id=%link.id, "unable to acquire imap watch lease");
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!(error=?e, "unable to fetch imap links");
+            }
+        }
+
+        tokio::time::sleep(DISCOVERY_INTERVAL).await;
+    }
+}
+
+/// Watches a link until the lease is lost or the link is no longer synced
+#[tracing::instrument(skip(ctx, link), fields(link_id=%link.id))]
+async fn watch_link(ctx: &ImapWatcherContext, link: Link, owner: Uuid) {
+    tracing::info!("watching imap link");
+
+    let renew_lease = async {
+        loop {
+            tokio::time::sleep(LEASE_RENEW_INTERVAL).await;
+            match ctx
+                .redis_client
+                .acquire_imap_watch_lease(link.id, owner, LEASE_TTL_SECS)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::warn!("imap watch lease was taken over");
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(error=?e, "unable to renew imap watch lease");
+                }
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = renew_lease => {}
+        _ = idle_until_inactive(ctx, &link) => {}
+    }
+
+    if let Err(e) = ctx
+        .redis_client
+        .release_imap_watch_lease(link.id, owner)
+        .await
+    {
+        tracing::warn!(error=?e, "unable to release imap watch lease");
+    }
+
+    tracing::info!("stopped watching imap link");
+}
+
+/// IDLEs on the link's inbox, reconnecting on errors, until the link is no longer synced
+async fn idle_until_inactive(ctx: &ImapWatcherContext, link: &Link) {
+    loop {
+        match idle(ctx, link).await {
+            Ok(()) => return,
+            Err(e) => {
+                tracing::warn!(error=?e, "imap watch connection failed, reconnecting");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Holds an IDLE connection and enqueues a sync for every change.
+/// Returns once the link is no longer synced.
+async fn idle(ctx: &ImapWatcherContext, link: &Link) -> anyhow::Result<()> {
+    let client = ctx.mail_providers.imap_client(link).await?;
+    let mut session = client.connect().await?;
+
+    // pick up changes made while nobody was watching
+    enqueue_sync(ctx, link.id).await?;
+
+    loop {
+        let event = session.idle(WATCHED_MAILBOX, IDLE_TIMEOUT).await?;
+        if event == IdleEvent::Timeout {
+            tracing::debug!("imap idle timed out, syncing other mailboxes");
+        }
+
+        enqueue_sync(ctx, link.id).await?;
+
+        let is_sync_active = email_db_client::links::get::fetch_link_by_id(&ctx.db, link.id)
+            .await?
+            .is_some_and(|link| link.is_sync_active);
+        if !is_sync_active {
+            session.logout().await?;
+            return Ok(());
+        }
+    }
+}
+
+async fn enqueue_sync(ctx: &ImapWatcherContext, link_id: Uuid) -> anyhow::Result<()> {
+    ctx.sqs_client
+        .enqueue_gmail_webhook_notification(WebhookPubsubMessage {
+            link_id,
+            operation: WebhookOperation::ImapMessage,
+        })
+        .await
+}
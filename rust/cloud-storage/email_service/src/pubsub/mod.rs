@@ -1,5 +1,6 @@
 pub(crate) mod backfill;
 pub(crate) mod context;
+pub(crate) mod imap_watcher;
 pub(crate) mod refresh;
 pub(crate) mod scheduled;
 pub(crate) mod sfs_uploader;
@@ -3,7 +3,7 @@ use crate::pubsub::util::{fetch_access_token_for_link, fetch_link};
 use crate::util::sync_contacts::sync_contacts;
 use anyhow::{Context, anyhow};
 use models_email::email::service::pubsub::RefreshMessage;
-use models_email::service::link::Link;
+use models_email::service::link::{Link, UserProvider};
 use sqs_worker::cleanup_message;
 // --- Main Orchestrator Function ---
 
@@ -18,6 +18,12 @@ pub async fn process_message(
     // Step 2: Fetch the user's link details from the database
     let link = fetch_link(&ctx.db, notification_data.link_id).await?;
 
+    // IMAP links have no watch subscription to renew, they are watched by the imap watcher
+    if link.provider != UserProvider::Gmail {
+        cleanup_message(&ctx.sqs_worker, message).await?;
+        return Ok(());
+    }
+
     // Step 3: Get a valid Gmail access token
     let gmail_access_token =
         fetch_access_token_for_link(&ctx.redis_client, &ctx.auth_service_client, &link).await?;
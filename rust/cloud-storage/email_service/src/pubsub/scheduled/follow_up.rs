@@ -0,0 +1,79 @@
+use crate::pubsub::scheduled::context::ScheduledContext;
+use crate::pubsub::util::fetch_link;
+use anyhow::Context;
+use model_notifications::{
+    NewEmailMetadata, NotificationEntity, NotificationEvent, NotificationQueueMessage,
+};
+use models_email::service::pubsub::FollowUpPubsubMessage;
+use sqs_worker::cleanup_message;
+
+/// Notifies the user about a due follow up reminder, unless someone replied to the thread since
+/// the user last sent a message in it.
+/// The reminder is claimed before anything else happens, so a reminder that is enqueued more than
+/// once only ever results in a single notification.
+#[tracing::instrument(skip(ctx, message))]
+pub async fn process_follow_up(
+    ctx: ScheduledContext,
+    message: &aws_sdk_sqs::types::Message,
+    data: FollowUpPubsubMessage,
+) -> anyhow::Result<()> {
+    let Some(reminder) = email_db_client::follow_ups::claim_follow_up_reminder(
+        &ctx.db,
+        data.link_id,
+        data.reminder_id,
+    )
+    .await
+    .context("Failed to claim follow up reminder")?
+    else {
+        tracing::info!("Follow up reminder was removed or already processed");
+        cleanup_message(&ctx.sqs_worker, message).await?;
+        return Ok(());
+    };
+
+    let has_reply = email_db_client::follow_ups::thread_has_reply(&ctx.db, reminder.thread_id)
+        .await
+        .context("Failed to check thread for replies")?;
+
+    if has_reply || !ctx.notifications_enabled {
+        cleanup_message(&ctx.sqs_worker, message).await?;
+        return Ok(());
+    }
+
+    let Some(sent_message) =
+        email_db_client::follow_ups::fetch_latest_sent_message(&ctx.db, reminder.thread_id)
+            .await
+            .context("Failed to fetch latest sent message")?
+    else {
+        tracing::warn!(thread_id = %reminder.thread_id, "Follow up reminder on a thread without sent messages");
+        cleanup_message(&ctx.sqs_worker, message).await?;
+        return Ok(());
+    };
+
+    let link = fetch_link(&ctx.db, data.link_id).await?;
+
+    let notification_queue_message = NotificationQueueMessage {
+        notification_entity: NotificationEntity::new_email(sent_message.message_id.to_string()),
+        notification_event: NotificationEvent::EmailFollowUp(NewEmailMetadata {
+            sender: None,
+            to_email: link.email_address.0.as_ref().to_string(),
+            thread_id: reminder.thread_id.to_string(),
+            subject: sent_message.subject.unwrap_or_default(),
+            snippet: sent_message.snippet.unwrap_or_default(),
+        }),
+        sender_id: Some(link.macro_id.to_string()),
+        recipient_ids: Some(vec![link.macro_id.to_string()]),
+        is_important_v0: Some(false),
+    };
+
+    if let Err(e) = ctx
+        .macro_notify_client
+        .send_notification(notification_queue_message)
+        .await
+    {
+        tracing::error!(error=?e, "unable to send follow up notification");
+    }
+
+    cleanup_message(&ctx.sqs_worker, message).await?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub(crate) mod context;
+pub(crate) mod follow_up;
 pub(crate) mod process;
 pub(crate) mod worker;
@@ -1,10 +1,12 @@
 use crate::pubsub::scheduled::context::ScheduledContext;
-use crate::pubsub::util::{fetch_access_token_for_link, fetch_link};
+use crate::pubsub::scheduled::follow_up;
+use crate::pubsub::util::fetch_link;
 use crate::util::gmail::send::generate_email_threading_headers;
 use anyhow::Context;
+use email_db_client::messages::outbox::{claim_outbox_message, release_outbox_message};
 use email_db_client::messages::scheduled::get_scheduled_message;
 use models_email::service::message::MessageToSend;
-use models_email::service::pubsub::ScheduledPubsubMessage;
+use models_email::service::pubsub::{ScheduledPubsubMessage, ScheduledQueueMessage};
 use sqlx_core::any::AnyConnectionBackend;
 use sqs_worker::cleanup_message;
 
@@ -13,24 +15,57 @@ pub async fn process_message(
     message: &aws_sdk_sqs::types::Message,
 ) -> anyhow::Result<()> {
     // Parse the incoming message
-    let data = extract_scheduled_message(message)?;
+    match extract_scheduled_message(message)? {
+        ScheduledQueueMessage::Send(data) => send_scheduled_message(ctx, message, data).await,
+        ScheduledQueueMessage::FollowUp(data) => {
+            follow_up::process_follow_up(ctx, message, data).await
+        }
+    }
+}
 
+async fn send_scheduled_message(
+    ctx: ScheduledContext,
+    message: &aws_sdk_sqs::types::Message,
+    data: ScheduledPubsubMessage,
+) -> anyhow::Result<()> {
     let link = fetch_link(&ctx.db, data.link_id).await?;
-    let gmail_access_token =
-        fetch_access_token_for_link(&ctx.redis_client, &ctx.auth_service_client, &link).await?;
+    let provider = ctx
+        .mail_providers
+        .for_link(&link)
+        .await
+        .context("Unable to build mail provider")?;
 
     // Get scheduled message from database
+    let mut from_outbox = false;
     let scheduled_message =
         match get_scheduled_message(&ctx.db, data.link_id, data.message_id).await {
             Ok(Some(msg)) => msg,
             Ok(None) => {
-                tracing::error!(
-                    link_id = ?data.link_id,
-                    message_id = ?data.message_id,
-                    "Scheduled message not found"
-                );
-                cleanup_message(&ctx.sqs_worker, message).await?;
-                return Ok(());
+                // messages sent with an undo window wait in the outbox instead
+                let outbox_message = claim_outbox_message(&ctx.db, data.link_id, data.message_id)
+                    .await
+                    .context(format!(
+                        "Failed to claim outbox message for message_id {}",
+                        data.message_id
+                    ))?;
+
+                match outbox_message {
+                    Some(msg) => {
+                        from_outbox = true;
+                        msg
+                    }
+                    None => {
+                        // expected when the user cancelled the send within the undo window, or
+                        // when another worker is already sending it
+                        tracing::info!(
+                            link_id = ?data.link_id,
+                            message_id = ?data.message_id,
+                            "Scheduled message not found"
+                        );
+                        cleanup_message(&ctx.sqs_worker, message).await?;
+                        return Ok(());
+                    }
+                }
             }
             Err(e) => {
                 return Err(e).context(format!(
@@ -50,7 +85,7 @@ pub async fn process_message(
             )
             .await
             .context(format!(
-                "Failed to fetch message to gmail api for message_id {}",
+                "Failed to fetch message to send for message_id {}",
                 data.message_id
             ))?;
 
@@ -59,10 +94,9 @@ pub async fn process_message(
             generate_email_threading_headers(&ctx.db, message_to_send.replying_to_id, data.link_id)
                 .await;
 
-        // send message to gmail api
-        ctx.gmail_client
+        // send message through the provider
+        let sent = provider
             .send_message(
-                gmail_access_token.as_str(),
                 &mut message_to_send,
                 &sender_contact,
                 parent_message_id,
@@ -70,9 +104,25 @@ pub async fn process_message(
             )
             .await
             .context(format!(
-                "Failed to send message to gmail api for message_id {}",
+                "Failed to send message through provider for message_id {}",
                 data.message_id
-            ))?;
+            ));
+
+        if let Err(e) = sent {
+            // let the retry of this queue message claim it again
+            if from_outbox
+                && let Err(release_err) =
+                    release_outbox_message(&ctx.db, data.link_id, data.message_id).await
+            {
+                tracing::error!(
+                    error = ?release_err,
+                    link_id = ?data.link_id,
+                    message_id = ?data.message_id,
+                    "Failed to release outbox message after send failure"
+                );
+            }
+            return Err(e);
+        }
 
         let mut tx = ctx
             .db
@@ -112,14 +162,14 @@ pub async fn process_message(
 #[tracing::instrument(skip(message))]
 fn extract_scheduled_message(
     message: &aws_sdk_sqs::types::Message,
-) -> anyhow::Result<ScheduledPubsubMessage> {
+) -> anyhow::Result<ScheduledQueueMessage> {
     let message_body = message.body().context("message body not found")?;
 
     serde_json::from_str(message_body)
-        .context("Failed to deserialize message body to ScheduledPubsubMessage")
+        .context("Failed to deserialize message body to ScheduledQueueMessage")
 }
 
-/// Mark both the scheduled message and the regular message as sent
+/// Mark both the scheduled or outbox message and the regular message as sent
 ///
 /// This function handles both database updates in a single transaction
 #[expect(
@@ -143,6 +193,18 @@ async fn mark_messages_as_sent(
         message.db_id.unwrap()
     ))?;
 
+    // take message out of the outbox, if it was sent with an undo window
+    email_db_client::messages::outbox::delete_outbox_message(
+        tx.as_mut(),
+        message.link_id,
+        message.db_id.unwrap(),
+    )
+    .await
+    .context(format!(
+        "Failed to delete outbox message for message_id {}",
+        message.db_id.unwrap()
+    ))?;
+
     // mark message as non-draft
     email_db_client::messages::update::mark_message_as_sent(
         tx.as_mut(),
@@ -1,24 +1,24 @@
 use crate::pubsub::scheduled::context::ScheduledContext;
 use crate::pubsub::scheduled::process;
-use crate::util::redis::RedisClient;
-use authentication_service_client::AuthServiceClient;
+use crate::util::mail_provider::MailProviderFactory;
 use futures::StreamExt;
+use macro_notify::MacroNotifyClient;
 use sqlx::PgPool;
 
 /// method that ingests sqs messages and calls the process function for each
 pub async fn run_worker(
     worker: sqs_worker::SQSWorker,
     db: PgPool,
-    gmail_client: gmail_client::GmailClient,
-    auth_service_client: AuthServiceClient,
-    redis_client: RedisClient,
+    mail_providers: MailProviderFactory,
+    macro_notify_client: MacroNotifyClient,
+    notifications_enabled: bool,
 ) {
     let ctx = ScheduledContext {
         db,
         sqs_worker: worker.clone(),
-        gmail_client,
-        auth_service_client,
-        redis_client,
+        mail_providers,
+        macro_notify_client,
+        notifications_enabled,
     };
     loop {
         let worker_result = tokio::spawn({
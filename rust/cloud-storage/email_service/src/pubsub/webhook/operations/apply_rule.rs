@@ -0,0 +1,95 @@
+use crate::pubsub::context::PubSubContext;
+use crate::pubsub::util::{cg_refresh_email, check_gmail_rate_limit};
+use crate::pubsub::webhook::process;
+use crate::util::rules::{self, RuleTrigger};
+use models_email::email::service::link;
+use models_email::gmail::operations::GmailApiOperation;
+use models_email::gmail::webhook::ApplyRulePayload;
+use models_email::service::pubsub::{DetailedError, FailureReason, ProcessingError};
+use std::result;
+
+const PAGE_SIZE: i64 = 100;
+
+/// Applies a rule to the messages a link already has, newest first
+#[tracing::instrument(skip(ctx))]
+pub async fn apply_rule(
+    ctx: &PubSubContext,
+    link: &link::Link,
+    payload: &ApplyRulePayload,
+) -> result::Result<(), ProcessingError> {
+    let rule = match email_db_client::rules::fetch_rule_by_id(&ctx.db, payload.rule_id, link.id)
+        .await
+        .map_err(|e| {
+            ProcessingError::Retryable(DetailedError {
+                reason: FailureReason::DatabaseQueryFailed,
+                source: e.context("Failed to fetch rule".to_string()),
+            })
+        })? {
+        Some(rule) => rule,
+        None => {
+            tracing::debug!(rule_id = %payload.rule_id, link_id = %link.id,
+                "Rule was deleted before it could be applied");
+            return Ok(());
+        }
+    };
+
+    let provider = process::fetch_pubsub_mail_provider(ctx, link).await?;
+    let rules = [rule];
+
+    let mut offset = 0;
+    loop {
+        let messages = email_db_client::rules::messages::fetch_rule_messages_paginated(
+            &ctx.db, link.id, PAGE_SIZE, offset,
+        )
+        .await
+        .map_err(|e| {
+            ProcessingError::Retryable(DetailedError {
+                reason: FailureReason::DatabaseQueryFailed,
+                source: e.context("Failed to fetch messages".to_string()),
+            })
+        })?;
+
+        if messages.is_empty() {
+            break;
+        }
+
+        check_gmail_rate_limit(
+            &ctx.redis_client,
+            link.id,
+            GmailApiOperation::MessagesModify,
+            false,
+        )
+        .await?;
+
+        rules::apply_rules(
+            &ctx.db,
+            provider.as_ref(),
+            link,
+            &rules,
+            &messages,
+            RuleTrigger::Retroactive,
+        )
+        .await
+        .map_err(|e| {
+            ProcessingError::Retryable(DetailedError {
+                reason: FailureReason::DatabaseQueryFailed,
+                source: e.context("Failed to apply rule".to_string()),
+            })
+        })?;
+
+        if (messages.len() as i64) < PAGE_SIZE {
+            break;
+        }
+        offset += PAGE_SIZE;
+    }
+
+    // trigger FE inbox refresh
+    cg_refresh_email(
+        &ctx.connection_gateway_client,
+        link.macro_id.as_ref(),
+        "apply_rule",
+    )
+    .await;
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::check_gmail_rate_limit;
-use crate::pubsub::webhook::process::fetch_pubsub_gmail_token;
+use crate::pubsub::webhook::process::fetch_pubsub_mail_provider;
 use crate::util::process_pre_insert::sync_labels::sync_labels;
+use mail_provider::MailProvider;
 use models_email::gmail::history::InboxChanges;
 use models_email::gmail::operations::GmailApiOperation;
 use models_email::gmail::webhook::{
@@ -21,7 +22,7 @@ pub async fn gmail_message(
     link: &Link,
     payload: &GmailMessagePayload,
 ) -> result::Result<(), ProcessingError> {
-    let gmail_access_token = fetch_pubsub_gmail_token(ctx, link).await?;
+    let provider = fetch_pubsub_mail_provider(ctx, link).await?;
 
     // get the user's latest history_id in the database
     // if it's GTE this message's history id, do nothing - db is already updated or being updated
@@ -55,6 +56,18 @@ pub async fn gmail_message(
         return Ok(());
     }
 
+    process_inbox_changes(ctx, link, provider.as_ref(), &db_history_id).await
+}
+
+/// Syncs the link's labels, then fetches the changes since db_history_id from the provider
+/// and sends off pubsub messages for each change.
+#[tracing::instrument(skip(ctx, provider, db_history_id))]
+pub(in crate::pubsub) async fn process_inbox_changes(
+    ctx: &PubSubContext,
+    link: &Link,
+    provider: &dyn MailProvider,
+    db_history_id: &str,
+) -> result::Result<(), ProcessingError> {
     // ensure user's labels are synced before we start processing changes
     check_gmail_rate_limit(
         &ctx.redis_client,
@@ -63,14 +76,12 @@ pub async fn gmail_message(
         false,
     )
     .await?;
-    sync_labels(&ctx.db, &ctx.gmail_client, &gmail_access_token, link.id)
-        .await
-        .map_err(|e| {
-            ProcessingError::Retryable(DetailedError {
-                reason: FailureReason::GmailApiFailed,
-                source: e.context("Failed to sync labels"),
-            })
-        })?;
+    sync_labels(&ctx.db, provider, link.id).await.map_err(|e| {
+        ProcessingError::Retryable(DetailedError {
+            reason: FailureReason::GmailApiFailed,
+            source: e.context("Failed to sync labels"),
+        })
+    })?;
 
     // the history.list call in gmail api fetches all changes SINCE the history_id we pass to it.
     // we pass the db_history_id, aka history_id at the time of the last update. once
@@ -83,16 +94,12 @@ pub async fn gmail_message(
         false,
     )
     .await?;
-    let inbox_changes = ctx
-        .gmail_client
-        .get_history(&gmail_access_token, &db_history_id)
-        .await
-        .map_err(|e| {
-            ProcessingError::Retryable(DetailedError {
-                reason: FailureReason::GmailApiFailed,
-                source: e.context(format!("unable to get history for link id: {}", link.id)),
-            })
-        })?;
+    let inbox_changes = provider.get_history(db_history_id).await.map_err(|e| {
+        ProcessingError::Retryable(DetailedError {
+            reason: FailureReason::GmailApiFailed,
+            source: e.context(format!("unable to get history for link id: {}", link.id)),
+        })
+    })?;
 
     // Update the history_id in the database immediately to prevent duplicate processing.
     // The db history_id is used to determine which inbox changes need processing when
@@ -135,7 +142,10 @@ pub async fn gmail_message(
 
 /// Builds pubsub messages from history data
 #[tracing::instrument]
-fn build_pubsub_messages(link_id: Uuid, inbox_changes: InboxChanges) -> Vec<WebhookPubsubMessage> {
+pub(in crate::pubsub) fn build_pubsub_messages(
+    link_id: Uuid,
+    inbox_changes: InboxChanges,
+) -> Vec<WebhookPubsubMessage> {
     let mut pubsub_messages = Vec::new();
 
     // Process messages to upsert
@@ -0,0 +1,53 @@
+use crate::pubsub::context::PubSubContext;
+use crate::pubsub::webhook::operations::gmail_message::process_inbox_changes;
+use crate::pubsub::webhook::process::fetch_pubsub_mail_provider;
+use models_email::service::link::{Link, UserProvider};
+use models_email::service::pubsub::{DetailedError, FailureReason, ProcessingError};
+use std::result;
+
+// handle a notification from the imap watcher that the user's mailbox may have changed.
+// unlike gmail there is no history id to compare against, the stored sync state is diffed
+// against the mailbox instead.
+#[tracing::instrument(skip(ctx))]
+pub async fn imap_message(ctx: &PubSubContext, link: &Link) -> result::Result<(), ProcessingError> {
+    if link.provider != UserProvider::Imap {
+        return Err(ProcessingError::NonRetryable(DetailedError {
+            reason: FailureReason::LinkNotFound,
+            source: anyhow::anyhow!("Link {} is not an IMAP link", link.id),
+        }));
+    }
+
+    let provider = fetch_pubsub_mail_provider(ctx, link).await?;
+
+    let db_sync_state = email_db_client::histories::fetch_history_id_by_link_id(&ctx.db, link.id)
+        .await
+        .map_err(|e| {
+            ProcessingError::Retryable(DetailedError {
+                reason: FailureReason::DatabaseQueryFailed,
+                source: e.context("Failed to fetch sync state from db".to_string()),
+            })
+        })?;
+
+    let Some(db_sync_state) = db_sync_state else {
+        // the first notification records the current state, earlier messages are backfilled
+        let sync_state = provider.get_current_history_id().await.map_err(|e| {
+            ProcessingError::Retryable(DetailedError {
+                reason: FailureReason::GmailApiFailed,
+                source: e.context("Failed to get current imap sync state".to_string()),
+            })
+        })?;
+
+        email_db_client::histories::upsert_gmail_history(&ctx.db, link.id, &sync_state)
+            .await
+            .map_err(|e| {
+                ProcessingError::Retryable(DetailedError {
+                    reason: FailureReason::DatabaseQueryFailed,
+                    source: e.context("Failed to store imap sync state".to_string()),
+                })
+            })?;
+
+        return Ok(());
+    };
+
+    process_inbox_changes(ctx, link, provider.as_ref(), &db_sync_state).await
+}
@@ -1,4 +1,6 @@
+pub(in crate::pubsub) mod apply_rule;
 pub(in crate::pubsub) mod delete_message;
 pub(in crate::pubsub) mod gmail_message;
+pub(in crate::pubsub) mod imap_message;
 pub(in crate::pubsub) mod update_labels;
 pub(in crate::pubsub) mod upsert_message;
@@ -22,7 +22,7 @@ pub async fn update_labels(
     link: &link::Link,
     payload: &UpdateLabelsPayload,
 ) -> result::Result<(), ProcessingError> {
-    let gmail_access_token = process::fetch_pubsub_gmail_token(ctx, link).await?;
+    let provider = process::fetch_pubsub_mail_provider(ctx, link).await?;
     let provider_message_id = &payload.provider_message_id;
 
     // fetch simple message to get db_id from provider_id
@@ -63,20 +63,19 @@ pub async fn update_labels(
         false,
     )
     .await?;
-    let gmail_message_labels = match ctx
-        .gmail_client
-        .get_message_label_ids(&gmail_access_token, &payload.provider_message_id, link.id)
+    let gmail_message_labels = match provider
+        .get_message_label_ids(&payload.provider_message_id, link.id)
         .await
         .map_err(|e| {
             ProcessingError::Retryable(DetailedError {
                 reason: FailureReason::GmailApiFailed,
-                source: e.context("Failed to get message from gmail api".to_string()),
+                source: e.context("Failed to get message from provider".to_string()),
             })
         })? {
         Some(labels) => labels,
         None => {
             tracing::debug!(provider_message_id = %payload.provider_message_id, link_id = %link.id,
-                "Message not found in gmail when attempting to update labels");
+                "Message not found in provider when attempting to update labels");
             return Ok(());
         }
     };
@@ -2,12 +2,14 @@ use crate::pubsub::context::PubSubContext;
 use crate::pubsub::util::{cg_refresh_email, check_gmail_rate_limit};
 use crate::pubsub::webhook::process;
 use crate::util::process_pre_insert::{process_message_pre_insert, process_threads_pre_insert};
+use crate::util::rules::{self, RuleTrigger};
 use crate::util::upload_attachment::upload_attachment;
 use email_db_client::threads;
 use email_db_client::threads::get::get_outbound_threads_by_thread_ids;
 use email_utils::dedupe_emails;
 use futures::future::join_all;
 use insight_service_client::InsightContextProvider;
+use mail_provider::MailProvider;
 use model::contacts::ConnectionsMessage;
 use model::insight_context::email_insights::{
     EMAIL_INSIGHT_PROVIDER_SOURCE_NAME, EmailInfo, GenerateEmailInsightContext, NewMessagePayload,
@@ -39,7 +41,7 @@ pub async fn upsert_message(
     link: &link::Link,
     payload: &UpsertMessagePayload,
 ) -> result::Result<(), ProcessingError> {
-    let gmail_access_token = process::fetch_pubsub_gmail_token(ctx, link).await?;
+    let provider = process::fetch_pubsub_mail_provider(ctx, link).await?;
 
     // we have to fetch the message to get its provider thread id
     check_gmail_rate_limit(
@@ -49,22 +51,21 @@ pub async fn upsert_message(
         false,
     )
     .await?;
-    let message = match ctx
-        .gmail_client
-        .get_message(&gmail_access_token, &payload.provider_message_id, link.id)
+    let message = match provider
+        .get_message(&payload.provider_message_id, link.id)
         .await
         .map_err(|e| {
             // retryable because we don't return an error if message doesn't exist, so this means
-            // it had to be some sort of internal gmail api error
+            // it had to be some sort of internal provider error
             ProcessingError::Retryable(DetailedError {
                 reason: FailureReason::GmailApiFailed,
-                source: e.context("Failed to get message from gmail api".to_string()),
+                source: e.context("Failed to get message from provider".to_string()),
             })
         })? {
         Some(msg) => msg,
         None => {
             tracing::debug!(provider_message_id = %payload.provider_message_id, link_id = %link.id,
-                "Message not found in gmail when attempting to upsert");
+                "Message not found in provider when attempting to upsert");
             return Ok(());
         }
     };
@@ -128,7 +129,7 @@ pub async fn upsert_message(
                 })
             })?;
     } else {
-        fetch_and_insert_thread(ctx, &gmail_access_token, link.id, &provider_thread_id)
+        fetch_and_insert_thread(ctx, provider.as_ref(), link.id, &provider_thread_id)
             .await
             .map_err(|e| {
                 ProcessingError::Retryable(DetailedError {
@@ -146,12 +147,16 @@ pub async fn upsert_message(
     )
     .await;
 
+    // rules run before notifying, since they can archive a message or suppress its notification
+    let suppressed_message_ids =
+        apply_rules_to_new_messages(ctx, provider.as_ref(), link, &new_message_provider_ids).await;
+
     // notify downstream services of new messages
-    notify_for_new_messages(ctx, link, new_message_provider_ids).await?;
+    notify_for_new_messages(ctx, link, new_message_provider_ids, &suppressed_message_ids).await?;
 
     handle_attachment_upload(
         ctx,
-        &gmail_access_token,
+        provider.as_ref(),
         link,
         payload,
         message_attachment_count,
@@ -170,10 +175,65 @@ pub async fn upsert_message(
     Ok(())
 }
 
-#[tracing::instrument(skip(ctx, gmail_access_token))]
+/// Applies the link's rules to its new messages. Rules are best effort, a failure is logged and
+/// doesn't fail the upsert. Returns the db ids of messages whose notification is suppressed.
+#[tracing::instrument(skip(ctx, provider, link), fields(link_id = %link.id))]
+async fn apply_rules_to_new_messages(
+    ctx: &PubSubContext,
+    provider: &dyn MailProvider,
+    link: &link::Link,
+    new_message_provider_ids: &[String],
+) -> HashSet<Uuid> {
+    if new_message_provider_ids.is_empty() {
+        return HashSet::new();
+    }
+
+    let result = async {
+        let rules = email_db_client::rules::fetch_rules_by_link_id(&ctx.db, link.id).await?;
+        if !rules.iter().any(|rule| rule.is_enabled) {
+            return anyhow::Ok(HashSet::new());
+        }
+
+        let message_ids = email_db_client::messages::get::get_message_thread_ids_by_provider_ids(
+            &ctx.db,
+            link.id,
+            new_message_provider_ids,
+        )
+        .await?
+        .into_iter()
+        .map(|(message_id, _thread_id)| message_id)
+        .collect::<Vec<_>>();
+
+        // sent messages and drafts aren't returned, rules only apply to received messages
+        let messages = email_db_client::rules::messages::fetch_rule_messages_by_ids(
+            &ctx.db,
+            link.id,
+            &message_ids,
+        )
+        .await?;
+
+        rules::apply_rules(
+            &ctx.db,
+            provider,
+            link,
+            &rules,
+            &messages,
+            RuleTrigger::Ingestion,
+        )
+        .await
+    }
+    .await;
+
+    result.unwrap_or_else(|e| {
+        tracing::error!(error = ?e, "Failed to apply rules to new messages");
+        HashSet::new()
+    })
+}
+
+#[tracing::instrument(skip(ctx, provider))]
 async fn handle_attachment_upload(
     ctx: &PubSubContext,
-    gmail_access_token: &str,
+    provider: &dyn MailProvider,
     link: &link::Link,
     payload: &UpsertMessagePayload,
     message_attachment_count: usize,
@@ -214,9 +274,8 @@ async fn handle_attachment_upload(
         // keep processing if it fails, best effort
         if let Err(e) = upload_attachment(
             &ctx.redis_client,
-            &ctx.gmail_client,
+            provider,
             &ctx.dss_client,
-            gmail_access_token,
             link,
             &attachment,
         )
@@ -382,10 +441,10 @@ async fn generate_email_insights_for_new_messages(
 }
 
 /// Process and insert email threads by handling attachments and images
-#[tracing::instrument(skip(ctx, gmail_access_token))]
+#[tracing::instrument(skip(ctx, provider))]
 async fn fetch_and_insert_thread(
     ctx: &PubSubContext,
-    gmail_access_token: &str,
+    provider: &dyn MailProvider,
     link_id: Uuid,
     provider_thread_id: &str,
 ) -> anyhow::Result<()> {
@@ -397,18 +456,13 @@ async fn fetch_and_insert_thread(
         false,
     )
     .await?;
-    let mut threads = ctx
-        .gmail_client
-        .get_threads(
-            link_id,
-            gmail_access_token,
-            &vec![provider_thread_id.to_string()],
-        )
+    let mut threads = provider
+        .get_threads(link_id, &[provider_thread_id.to_string()])
         .await
         .map_err(|e| {
             ProcessingError::NonRetryable(DetailedError {
                 reason: FailureReason::GmailApiFailed,
-                source: e.context("Failed to get threads from gmail api".to_string()),
+                source: e.context("Failed to get threads from provider".to_string()),
             })
         })?;
 
@@ -473,11 +527,12 @@ async fn process_and_insert_message(
 }
 
 /// Notify downstream services about new messages in a user's inbox
-#[tracing::instrument(skip(ctx, link, new_message_provider_ids))]
+#[tracing::instrument(skip(ctx, link, new_message_provider_ids, suppressed_message_ids))]
 async fn notify_for_new_messages(
     ctx: &PubSubContext,
     link: &link::Link,
     new_message_provider_ids: Vec<String>,
+    suppressed_message_ids: &HashSet<Uuid>,
 ) -> result::Result<(), ProcessingError> {
     if new_message_provider_ids.is_empty() {
         return Ok(());
@@ -498,7 +553,7 @@ async fn notify_for_new_messages(
         })?;
 
     // notify user of new messages
-    send_notifications(ctx, link, new_message_provider_ids).await?;
+    send_notifications(ctx, link, new_message_provider_ids, suppressed_message_ids).await?;
 
     if !new_message_db_ids.is_empty() {
         // send message to search text extractor queue
@@ -529,18 +584,20 @@ async fn notify_for_new_messages(
 }
 
 /// Send notifications for new inbound email messages
-#[tracing::instrument(skip(ctx, link, new_message_provider_ids))]
+#[tracing::instrument(skip(ctx, link, new_message_provider_ids, suppressed_message_ids))]
 async fn send_notifications(
     ctx: &PubSubContext,
     link: &link::Link,
     new_message_provider_ids: Vec<String>,
+    suppressed_message_ids: &HashSet<Uuid>,
 ) -> result::Result<(), ProcessingError> {
     if !ctx.notifications_enabled || new_message_provider_ids.is_empty() {
         return Ok(());
     }
 
     let notifiable_messages =
-        filter_notifiable_messages(ctx, link, new_message_provider_ids).await?;
+        filter_notifiable_messages(ctx, link, new_message_provider_ids, suppressed_message_ids)
+            .await?;
 
     if notifiable_messages.is_empty() {
         return Ok(());
@@ -603,11 +660,12 @@ async fn send_notifications(
 }
 
 // filter out messages we don't want to send notifications for
-#[tracing::instrument(skip(ctx, link, new_message_provider_ids))]
+#[tracing::instrument(skip(ctx, link, new_message_provider_ids, suppressed_message_ids))]
 async fn filter_notifiable_messages(
     ctx: &PubSubContext,
     link: &link::Link,
     new_message_provider_ids: Vec<String>,
+    suppressed_message_ids: &HashSet<Uuid>,
 ) -> result::Result<Vec<SimpleMessage>, ProcessingError> {
     let new_messages = email_db_client::messages::get_simple_messages::get_simple_messages(
         &ctx.db,
@@ -622,10 +680,11 @@ async fn filter_notifiable_messages(
         })
     })?;
 
-    // 1. filter out sent and draft messages
+    // 1. filter out sent and draft messages, and messages a rule suppressed notifications for
     let inbound_messages: Vec<SimpleMessage> = new_messages
         .into_iter()
         .filter(|message| !(message.is_sent || message.is_draft))
+        .filter(|message| !suppressed_message_ids.contains(&message.db_id))
         .collect();
 
     if inbound_messages.is_empty() {
@@ -1,12 +1,13 @@
 use crate::pubsub::context::PubSubContext;
 use crate::pubsub::webhook::error_handlers::prefix_error_source;
+use crate::pubsub::webhook::operations::apply_rule::apply_rule;
 use crate::pubsub::webhook::operations::delete_message::delete_message;
 use crate::pubsub::webhook::operations::gmail_message::gmail_message;
+use crate::pubsub::webhook::operations::imap_message::imap_message;
 use crate::pubsub::webhook::operations::update_labels::update_labels;
 use crate::pubsub::webhook::operations::upsert_message::upsert_message;
 use anyhow::{Context, Result, anyhow};
-use models_email::email::service::cache;
-use models_email::email::service::link::UserProvider;
+use mail_provider::MailProvider;
 use models_email::gmail::webhook::{WebhookOperation, WebhookPubsubMessage};
 use models_email::service::link::Link;
 use models_email::service::pubsub::{DetailedError, FailureReason, ProcessingError};
@@ -105,6 +106,18 @@ async fn inner_process_message(
                 .map_err(|e| prefix_error_source(e, "remove_labels"))?;
             tracing::debug!("Successfully processed update labels operation");
         }
+        WebhookOperation::ImapMessage => {
+            imap_message(ctx, &link)
+                .await
+                .map_err(|e| prefix_error_source(e, "imap_message"))?;
+            tracing::debug!("Successfully processed imap message operation");
+        }
+        WebhookOperation::ApplyRule(payload) => {
+            apply_rule(ctx, &link, payload)
+                .await
+                .map_err(|e| prefix_error_source(e, "apply_rule"))?;
+            tracing::debug!("Successfully processed apply rule operation");
+        }
     }
 
     Ok(())
@@ -124,27 +137,14 @@ fn extract_webhook_message(
     Ok(backfill_message)
 }
 
-pub async fn fetch_pubsub_gmail_token(
+pub async fn fetch_pubsub_mail_provider(
     ctx: &PubSubContext,
     link: &Link,
-) -> result::Result<String, ProcessingError> {
-    let cache_key = cache::TokenCacheKey::new(
-        &link.fusionauth_user_id,
-        link.macro_id.0.as_ref(),
-        UserProvider::Gmail,
-    );
-
-    let gmail_access_token = crate::util::gmail::auth::fetch_gmail_access_token(
-        &cache_key,
-        &ctx.redis_client,
-        &ctx.auth_service_client,
-    )
-    .await
-    .map_err(|e| {
+) -> result::Result<Box<dyn MailProvider>, ProcessingError> {
+    ctx.mail_providers.for_link(link).await.map_err(|e| {
         ProcessingError::NonRetryable(DetailedError {
             reason: FailureReason::AccessTokenFetchFailed,
-            source: e.context("Failed to fetch gmail access token".to_string()),
+            source: e.context("Failed to build mail provider".to_string()),
         })
-    })?;
-    Ok(gmail_access_token)
+    })
 }
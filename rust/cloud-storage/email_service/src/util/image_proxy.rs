@@ -0,0 +1,43 @@
+use crate::config::Config;
+use gmail_client::sanitizer::block_trackers_and_proxy_images;
+use models_email::service::message::MessageWithBodyReplyless;
+use sqlx::types::chrono::Utc;
+
+/// Removes the tracking pixels from the html bodies of a message, and loads its remaining remote
+/// images through the image proxy. Images already stored in the static file service are left as
+/// they are.
+pub fn proxy_message_images(config: &Config, message: &mut MessageWithBodyReplyless) {
+    if message.inner.body_html_sanitized.is_none() {
+        return;
+    }
+
+    let expires = image_proxy::expires_at(Utc::now().timestamp());
+    let proxy_url = |url: &str| {
+        (!url.starts_with(&config.static_file_service_url)).then(|| {
+            image_proxy::proxy_url(
+                &config.image_proxy_url,
+                &config.image_proxy_secret_key,
+                url,
+                expires,
+            )
+        })
+    };
+
+    if let Some(html) = &message.inner.body_html_sanitized {
+        match block_trackers_and_proxy_images(html, proxy_url) {
+            Ok(proxied) => {
+                message.trackers_blocked = proxied.trackers_blocked;
+                message.inner.body_html_sanitized = Some(proxied.html);
+            }
+            Err(e) => tracing::error!(error=?e, "unable to proxy message images"),
+        }
+    }
+
+    // body_replyless is html as well when the message has an html body
+    if let Some(html) = &message.body_replyless {
+        match block_trackers_and_proxy_images(html, proxy_url) {
+            Ok(proxied) => message.body_replyless = Some(proxied.html),
+            Err(e) => tracing::error!(error=?e, "unable to proxy message images"),
+        }
+    }
+}
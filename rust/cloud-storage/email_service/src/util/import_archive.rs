@@ -0,0 +1,290 @@
+use crate::util::upload_attachment::upload_attachment_data;
+use anyhow::Context;
+use document_storage_service_client::DocumentStorageServiceClient;
+use gmail_client::{find_header, parse_address_header};
+use mail_provider::archive::{self, ArchivedMessage};
+use models_email::email::service::link::Link;
+use models_email::gmail::ThreadResource;
+use models_email::service::label::system_labels;
+use models_opensearch::SearchEntityType;
+use sqlx::PgPool;
+use sqs_client::search::SearchQueueMessage;
+use sqs_client::search::email::EmailThreadMessage;
+use sqs_client::search::name::EntityName;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// The outcome of importing an archive
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    /// the threads messages were imported into
+    pub thread_ids: Vec<Uuid>,
+    pub imported_message_count: usize,
+    /// messages that were already in the mailbox
+    pub skipped_message_count: usize,
+    /// messages that couldn't be parsed or inserted
+    pub failed_message_count: usize,
+}
+
+/// Imports the messages of an mbox file or .eml message into the mailbox of the link.
+/// Messages are grouped into threads by their threading headers and are added to an existing
+/// thread when they belong to a conversation that is already in the mailbox. Messages that are
+/// already in the mailbox are skipped, so importing the same archive twice doesn't duplicate mail.
+/// Messages sent from the address of the link are imported as sent, other messages go to the
+/// inbox unless `archive` is set. Imported messages are marked as read.
+#[tracing::instrument(skip(db, dss_client, sqs_client, link, data), fields(link_id = %link.id, size = data.len()), err)]
+pub async fn import_archive(
+    db: &PgPool,
+    dss_client: &DocumentStorageServiceClient,
+    sqs_client: &sqs_client::SQS,
+    link: &Link,
+    data: &[u8],
+    archive: bool,
+) -> anyhow::Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    // group the messages into threads, keeping the order of the archive
+    let mut thread_keys = Vec::new();
+    let mut threads: HashMap<String, Vec<ArchivedMessage>> = HashMap::new();
+    let mut seen_ids = HashSet::new();
+
+    for message in archive::read_archive(data) {
+        let mut message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!(error=?e, "unable to parse archived message");
+                summary.failed_message_count += 1;
+                continue;
+            }
+        };
+
+        // exports often contain a message once per folder it was in
+        if !seen_ids.insert(message.resource.id.clone()) {
+            summary.skipped_message_count += 1;
+            continue;
+        }
+
+        message.resource.label_ids = import_label_ids(link, &message, archive);
+
+        let thread_key = message.resource.thread_id.clone();
+        threads
+            .entry(thread_key.clone())
+            .or_insert_with(|| {
+                thread_keys.push(thread_key);
+                Vec::new()
+            })
+            .push(message);
+    }
+
+    for thread_key in thread_keys {
+        let messages = threads.remove(&thread_key).unwrap_or_default();
+        let message_count = messages.len();
+
+        match import_thread(db, dss_client, link, thread_key, messages).await {
+            Ok(Some((thread_id, imported))) => {
+                summary.imported_message_count += imported;
+                summary.skipped_message_count += message_count - imported;
+                summary.thread_ids.push(thread_id);
+                notify_search(sqs_client, link, thread_id).await;
+            }
+            Ok(None) => summary.skipped_message_count += message_count,
+            Err(e) => {
+                tracing::error!(error=?e, "unable to import thread");
+                summary.failed_message_count += message_count;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Inserts the messages of a thread of the archive and uploads their attachments.
+/// Returns the db id of the thread and the number of inserted messages, or None if every message
+/// was already in the mailbox.
+async fn import_thread(
+    db: &PgPool,
+    dss_client: &DocumentStorageServiceClient,
+    link: &Link,
+    thread_key: String,
+    messages: Vec<ArchivedMessage>,
+) -> anyhow::Result<Option<(Uuid, usize)>> {
+    let mut raw_messages = HashMap::new();
+    let mut referenced_ids = HashSet::new();
+    let mut resources = Vec::with_capacity(messages.len());
+    for message in messages {
+        // global ids are stored with the angle brackets of the Message-ID header
+        referenced_ids.extend(message.referenced_ids.iter().map(|id| format!("<{id}>")));
+        raw_messages.insert(message.resource.id.clone(), message.raw);
+        resources.push(message.resource);
+    }
+
+    let mut thread = gmail_client::map_thread_resource_to_service(
+        ThreadResource {
+            id: thread_key.clone(),
+            messages: resources,
+        },
+        link.id,
+    )
+    .await
+    .context("Failed to map thread")?;
+
+    let global_ids: Vec<String> = thread
+        .messages
+        .iter()
+        .filter_map(|message| message.global_id.clone())
+        .filter(|global_id| !global_id.is_empty())
+        .collect();
+
+    let existing_ids =
+        email_db_client::messages::get::find_existing_global_ids(db, link.id, &global_ids)
+            .await
+            .context("Failed to check for existing messages")?;
+
+    thread.messages.retain(|message| {
+        message
+            .global_id
+            .as_ref()
+            .is_none_or(|global_id| !existing_ids.contains(global_id))
+    });
+
+    if thread.messages.is_empty() {
+        return Ok(None);
+    }
+
+    // the conversation may already be in the mailbox, e.g. when importing into a synced account
+    let existing_thread_id =
+        match email_db_client::threads::get::get_threads_by_link_id_and_provider_ids(
+            db,
+            link.id,
+            &HashSet::from([thread_key]),
+        )
+        .await
+        .context("Failed to check for an existing thread")?
+        .into_values()
+        .next()
+        {
+            Some(thread_id) => Some(thread_id),
+            None => {
+                let conversation_ids: Vec<String> =
+                    global_ids.into_iter().chain(referenced_ids).collect();
+                email_db_client::messages::get::get_thread_id_by_global_ids(
+                    db,
+                    link.id,
+                    &conversation_ids,
+                )
+                .await
+                .context("Failed to look up thread of referenced messages")?
+            }
+        };
+
+    let provider_ids: Vec<String> = thread
+        .messages
+        .iter()
+        .filter_map(|message| message.provider_id.clone())
+        .collect();
+    let imported = thread.messages.len();
+
+    let thread_id = match existing_thread_id {
+        Some(thread_id) => {
+            for mut message in thread.messages {
+                email_db_client::messages::insert::insert_message(
+                    db,
+                    thread_id,
+                    &mut message,
+                    link.id,
+                    true,
+                )
+                .await
+                .context("Failed to insert message into existing thread")?;
+            }
+            thread_id
+        }
+        None => email_db_client::threads::insert::insert_thread_and_messages(db, thread, link.id)
+            .await
+            .context("Failed to insert thread")?,
+    };
+
+    if !cfg!(feature = "disable_attachment_upload") {
+        upload_attachments(db, dss_client, link, &provider_ids, &raw_messages).await;
+    }
+
+    Ok(Some((thread_id, imported)))
+}
+
+/// Uploads the attachments of imported messages from the raw messages, since imported messages
+/// can't be fetched from the provider. Failures are logged and don't fail the import.
+async fn upload_attachments(
+    db: &PgPool,
+    dss_client: &DocumentStorageServiceClient,
+    link: &Link,
+    provider_ids: &[String],
+    raw_messages: &HashMap<String, Vec<u8>>,
+) {
+    let attachments =
+        match email_db_client::attachments::provider::upload::fetch_attachments_for_import(
+            db,
+            link.id,
+            provider_ids,
+        )
+        .await
+        {
+            Ok(attachments) => attachments,
+            Err(e) => {
+                tracing::error!(error=?e, "Failed to fetch imported attachments to upload");
+                return;
+            }
+        };
+
+    for attachment in attachments {
+        let Some(raw) = raw_messages.get(&attachment.email_provider_id) else {
+            continue;
+        };
+
+        let result = match archive::attachment_data(raw, &attachment.provider_attachment_id) {
+            Ok(data) => upload_attachment_data(dss_client, link, &attachment, data)
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            tracing::error!(error=?e, attachment_id=%attachment.attachment_db_id, "Failed to upload imported attachment to Macro");
+        }
+    }
+}
+
+/// The labels of an imported message. Imported messages are never unread.
+fn import_label_ids(link: &Link, message: &ArchivedMessage, archive: bool) -> Vec<String> {
+    let is_sent = find_header(&message.resource.payload.headers, "From").is_some_and(|from| {
+        parse_address_header(from)
+            .iter()
+            .any(|(_, email)| email.eq_ignore_ascii_case(link.email_address.0.as_ref()))
+    });
+
+    if is_sent {
+        vec![system_labels::SENT.to_string()]
+    } else if archive {
+        Vec::new()
+    } else {
+        vec![system_labels::INBOX.to_string()]
+    }
+}
+
+async fn notify_search(sqs_client: &sqs_client::SQS, link: &Link, thread_id: Uuid) {
+    let messages = [
+        SearchQueueMessage::ExtractEmailThreadMessage(EmailThreadMessage {
+            thread_id: thread_id.to_string(),
+            macro_user_id: link.macro_id.to_string(),
+        }),
+        SearchQueueMessage::UpdateEntityName(EntityName {
+            entity_id: thread_id,
+            entity_type: SearchEntityType::Emails,
+        }),
+    ];
+
+    for message in messages {
+        if let Err(e) = sqs_client.send_message_to_search_event_queue(message).await {
+            tracing::error!(error=?e, %thread_id, "Failed to notify search about imported thread");
+        }
+    }
+}
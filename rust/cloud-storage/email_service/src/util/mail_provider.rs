@@ -0,0 +1,155 @@
+use crate::util::gmail::auth::fetch_gmail_access_token_from_link;
+use crate::util::redis::RedisClient;
+use anyhow::Context;
+use authentication_service_client::AuthServiceClient;
+use gmail_client::GmailClient;
+use imap_client::{ImapClient, ImapConfig, SmtpClient, SmtpConfig};
+use macro_env::Environment;
+use mail_provider::{GmailProvider, ImapProvider, MailProvider};
+use models_email::email::service::imap_account::ConnectionSecurity;
+use models_email::email::service::link::{Link, UserProvider};
+use secretsmanager_client::{SecretManager, SecretsManager};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Builds the mail provider for a link, resolving the credentials of the link.
+#[derive(Clone)]
+pub struct MailProviderFactory {
+    db: PgPool,
+    gmail_client: GmailClient,
+    auth_service_client: AuthServiceClient,
+    redis_client: RedisClient,
+    secretsmanager_client: SecretsManager,
+}
+
+impl MailProviderFactory {
+    pub fn new(
+        db: PgPool,
+        gmail_client: GmailClient,
+        auth_service_client: AuthServiceClient,
+        redis_client: RedisClient,
+        secretsmanager_client: SecretsManager,
+    ) -> Self {
+        Self {
+            db,
+            gmail_client,
+            auth_service_client,
+            redis_client,
+            secretsmanager_client,
+        }
+    }
+
+    /// Builds the provider for the link. Gmail links use a cached access token, IMAP links
+    /// read their server settings from the db and their password from secrets manager.
+    #[tracing::instrument(skip(self, link), fields(link_id=%link.id, provider=?link.provider), err)]
+    pub async fn for_link(&self, link: &Link) -> anyhow::Result<Box<dyn MailProvider>> {
+        match link.provider {
+            UserProvider::Gmail => {
+                let access_token = fetch_gmail_access_token_from_link(
+                    link,
+                    &self.redis_client,
+                    &self.auth_service_client,
+                )
+                .await?;
+
+                Ok(Box::new(GmailProvider::new(
+                    self.gmail_client.clone(),
+                    access_token,
+                )))
+            }
+            UserProvider::Imap => {
+                let (imap_config, smtp_config) = self.imap_configs(link).await?;
+
+                Ok(Box::new(ImapProvider::new(
+                    ImapClient::new(imap_config),
+                    SmtpClient::new(smtp_config)?,
+                    link.email_address.0.as_ref().to_string(),
+                )))
+            }
+        }
+    }
+
+    /// Checks that the IMAP and SMTP servers accept the settings of a new IMAP link and that the
+    /// IMAP server supports what syncing needs
+    #[tracing::instrument(skip(imap_config, smtp_config), fields(imap_host=%imap_config.host, smtp_host=%smtp_config.host), err)]
+    pub async fn verify_imap_account(
+        imap_config: ImapConfig,
+        smtp_config: SmtpConfig,
+    ) -> anyhow::Result<()> {
+        let session = ImapClient::new(imap_config).connect().await?;
+        anyhow::ensure!(
+            session.has_capability("IDLE"),
+            "imap server does not support IDLE which is required for watching the inbox"
+        );
+        session.logout().await?;
+
+        SmtpClient::new(smtp_config)?.test_connection().await
+    }
+
+    /// Stores the password of an IMAP link in secrets manager.
+    /// Returns the id of the secret to store on the account.
+    pub async fn store_imap_password(
+        &self,
+        link_id: Uuid,
+        password: &str,
+    ) -> anyhow::Result<String> {
+        let name = format!("email-imap-{}/{}", Environment::new_or_prod(), link_id);
+        self.secretsmanager_client
+            .create_secret(&name, password)
+            .await
+            .context("unable to store imap password")
+    }
+
+    /// Deletes the password of an IMAP link from secrets manager
+    pub async fn delete_imap_password(&self, secret_id: &str) -> anyhow::Result<()> {
+        self.secretsmanager_client
+            .delete_secret(secret_id)
+            .await
+            .context("unable to delete imap password")
+    }
+
+    /// Builds the IMAP client of an IMAP link, used to watch the mailbox for changes
+    pub async fn imap_client(&self, link: &Link) -> anyhow::Result<ImapClient> {
+        let (imap_config, _) = self.imap_configs(link).await?;
+        Ok(ImapClient::new(imap_config))
+    }
+
+    async fn imap_configs(&self, link: &Link) -> anyhow::Result<(ImapConfig, SmtpConfig)> {
+        let account = email_db_client::imap_accounts::fetch_imap_account(&self.db, link.id)
+            .await?
+            .with_context(|| format!("no imap account for link {}", link.id))?;
+
+        let password = self
+            .secretsmanager_client
+            .get_secret_value(&account.password_secret_id)
+            .await
+            .context("unable to get imap password")?
+            .to_string();
+
+        let imap_config = ImapConfig {
+            host: account.imap_host,
+            port: account.imap_port,
+            security: connection_security(account.imap_security),
+            username: account.username.clone(),
+            password: password.clone(),
+        };
+
+        let smtp_config = SmtpConfig {
+            host: account.smtp_host,
+            port: account.smtp_port,
+            security: connection_security(account.smtp_security),
+            username: account.username,
+            password,
+        };
+
+        Ok((imap_config, smtp_config))
+    }
+}
+
+pub(crate) fn connection_security(security: ConnectionSecurity) -> imap_client::ConnectionSecurity {
+    match security {
+        ConnectionSecurity::Tls => imap_client::ConnectionSecurity::Tls,
+        ConnectionSecurity::StartTls => imap_client::ConnectionSecurity::StartTls,
+        ConnectionSecurity::None => imap_client::ConnectionSecurity::None,
+    }
+}
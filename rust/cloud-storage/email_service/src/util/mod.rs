@@ -1,6 +1,11 @@
 pub mod backfill;
 pub mod gmail;
+pub mod image_proxy;
+pub mod import_archive;
+pub mod mail_provider;
 pub mod process_pre_insert;
 pub mod redis;
+pub mod rules;
 pub mod sync_contacts;
+pub mod unsubscribe;
 pub mod upload_attachment;
@@ -96,7 +96,7 @@ pub async fn store_message_images(
     Ok(())
 }
 
-// extracts the src/srcset attributes from all <img> tags in the passed HTML
+// extracts the src/srcset attributes from all <img> tags in the passed HTML, except tracking pixels
 fn extract_all_image_urls(html_content: &str) -> anyhow::Result<HashSet<String>> {
     let document = Html::parse_document(html_content);
     let mut image_urls = HashSet::new();
@@ -105,6 +105,19 @@ fn extract_all_image_urls(html_content: &str) -> anyhow::Result<HashSet<String>>
         .map_err(|e| anyhow::anyhow!("Failed to parse 'img' selector: {:?}", e))?;
 
     for element in document.select(&img_selector) {
+        // fetching a tracking pixel would report the email as opened
+        let attr = |name| element.value().attr(name);
+        if attr("src").is_some_and(|src| {
+            gmail_client::sanitizer::is_tracker_image(
+                src,
+                attr("width"),
+                attr("height"),
+                attr("style"),
+            )
+        }) {
+            continue;
+        }
+
         if let Some(src) = element.value().attr("src") {
             let src_trimmed = src.trim();
             if !src_trimmed.is_empty() && src_trimmed.starts_with("http") {
@@ -538,6 +551,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_all_image_urls_skips_tracking_pixels() {
+        let html_content = r#"
+        <html>
+        <body>
+            <img src="https://example.com/logo.png" alt="Logo">
+            <img src="https://example.com/open.gif?id=1" width="1" height="1">
+            <img src="https://mailtrack.io/trace/mail/abc.png" srcset="https://mailtrack.io/trace/mail/abc@2x.png 2x">
+        </body>
+        </html>
+        "#;
+
+        let result = extract_all_image_urls(html_content).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from(["https://example.com/logo.png".to_string()])
+        );
+    }
+
     #[test]
     fn test_extract_all_image_urls_with_malformed_srcset() {
         let html_content = r#"
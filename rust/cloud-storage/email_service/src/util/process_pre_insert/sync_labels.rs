@@ -1,16 +1,15 @@
 use anyhow::Context;
-use gmail_client::GmailClient;
+use mail_provider::MailProvider;
 use models_email::email::service::label::Label;
 use sqlx::PgPool;
 use std::collections::HashMap;
 use uuid::Uuid;
 
-// syncs our db labels with gmail - adds new labels, updates changed labels, deletes removed labels
-#[tracing::instrument(skip(db, gmail_client, access_token), level = "info")]
+// syncs our db labels with the provider - adds new labels, updates changed labels, deletes removed labels
+#[tracing::instrument(skip(db, provider), level = "info")]
 pub async fn sync_labels(
     db: &PgPool,
-    gmail_client: &GmailClient,
-    access_token: &str,
+    provider: &dyn MailProvider,
     link_id: Uuid,
 ) -> anyhow::Result<()> {
     // Step 1: Fetch all labels from both sources
@@ -18,10 +17,10 @@ pub async fn sync_labels(
         .await
         .context("Failed to fetch labels from database")?;
 
-    let gmail_labels = gmail_client
-        .fetch_user_labels(access_token, link_id)
+    let gmail_labels = provider
+        .fetch_user_labels(link_id)
         .await
-        .context("Failed to fetch labels from Gmail API")?;
+        .context("Failed to fetch labels from provider")?;
 
     // Create maps for easier comparison
     let db_label_map: HashMap<String, Label> = db_labels
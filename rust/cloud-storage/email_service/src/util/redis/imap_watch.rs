@@ -0,0 +1,70 @@
+use crate::util::redis::RedisClient;
+use anyhow::Context;
+use uuid::Uuid;
+
+impl RedisClient {
+    fn imap_watch_lease_key(link_id: Uuid) -> String {
+        format!("imap_watch_lease:{}", link_id)
+    }
+
+    /// Acquires or renews the lease on watching a link's mailbox, so that only one instance of
+    /// the service holds an IDLE connection per link. Returns false if another owner holds it.
+    pub async fn acquire_imap_watch_lease(
+        &self,
+        link_id: Uuid,
+        owner: Uuid,
+        ttl_secs: u64,
+    ) -> anyhow::Result<bool> {
+        let key = Self::imap_watch_lease_key(link_id);
+
+        let mut redis_connection = self
+            .inner
+            .get_multiplexed_async_connection()
+            .await
+            .context("unable to connect to redis")?;
+
+        let script = r#"
+        local current = redis.call('GET', KEYS[1])
+        if current == false or current == ARGV[1] then
+            redis.call('SET', KEYS[1], ARGV[1], 'EX', ARGV[2])
+            return 1
+        end
+        return 0
+    "#;
+
+        let acquired: i32 = redis::Script::new(script)
+            .key(&key)
+            .arg(owner.to_string())
+            .arg(ttl_secs)
+            .invoke_async(&mut redis_connection)
+            .await?;
+
+        Ok(acquired == 1)
+    }
+
+    /// Releases the lease on watching a link's mailbox if it is still held by the owner
+    pub async fn release_imap_watch_lease(&self, link_id: Uuid, owner: Uuid) -> anyhow::Result<()> {
+        let key = Self::imap_watch_lease_key(link_id);
+
+        let mut redis_connection = self
+            .inner
+            .get_multiplexed_async_connection()
+            .await
+            .context("unable to connect to redis")?;
+
+        let script = r#"
+        if redis.call('GET', KEYS[1]) == ARGV[1] then
+            return redis.call('DEL', KEYS[1])
+        end
+        return 0
+    "#;
+
+        let _deleted: i32 = redis::Script::new(script)
+            .key(&key)
+            .arg(owner.to_string())
+            .invoke_async(&mut redis_connection)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 pub mod access_token;
 pub mod backfill;
+pub mod imap_watch;
 pub mod rate_limit;
 
 #[derive(Clone)]
@@ -1,9 +1,24 @@
 use crate::util::redis::RedisClient;
+use anyhow::Context;
 use models_email::gmail::operations::GmailApiOperation;
-use redis::Script;
+use redis::{AsyncCommands, Script};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+/// The number of mail merge messages a recipient can receive from a link within the window
+pub const MAIL_MERGE_RECIPIENT_LIMIT: u32 = 2;
+
+/// The sliding window of mail merge messages to a recipient, one day
+pub const MAIL_MERGE_RECIPIENT_WINDOW_SECS: u32 = 24 * 60 * 60;
+
+/// A mail merge message recorded against the rate limit of its recipient. It can be released if
+/// the message ends up not being scheduled.
+#[derive(Debug)]
+pub struct MailMergeRecipientSlot {
+    redis_key: String,
+    member_id: String,
+}
+
 impl RedisClient {
     /// Checks if a Gmail API operation is rate-limited and returns the status.
     /// Uses a 60-second sliding window implemented via a Lua script for efficiency and atomicity.
@@ -19,13 +34,90 @@ impl RedisClient {
     /// Returns `true` if the operation should be rate limited (blocked), `false` otherwise.
     ///
     pub async fn is_rate_limited(&self, user_id: Uuid, operation: GmailApiOperation) -> bool {
+        let redis_key = format!("gmail-ratelimit:log:{}", user_id);
+
         let cost = operation.cost();
+        self.is_key_rate_limited(
+            &redis_key,
+            self.rate_limit_units,
+            self.rate_limit_secs,
+            cost,
+            &rate_limit_member_id(cost),
+        )
+        .await
+    }
+
+    /// Takes a slot for a mail merge message to a recipient. A recipient receives at most
+    /// [`MAIL_MERGE_RECIPIENT_LIMIT`] mail merge messages from a link within
+    /// [`MAIL_MERGE_RECIPIENT_WINDOW_SECS`], so repeated merges don't flood them.
+    ///
+    /// # Returns
+    /// Returns `None` if the message should not be scheduled. Otherwise returns the slot, which
+    /// has to be released with [`RedisClient::release_mail_merge_recipient_slot`] if the message
+    /// is not scheduled after all.
+    pub async fn take_mail_merge_recipient_slot(
+        &self,
+        link_id: Uuid,
+        recipient_email: &str,
+    ) -> Option<MailMergeRecipientSlot> {
+        let slot = MailMergeRecipientSlot {
+            redis_key: format!(
+                "mail-merge-ratelimit:log:{}:{}",
+                link_id,
+                recipient_email.to_lowercase()
+            ),
+            member_id: rate_limit_member_id(1),
+        };
+
+        if self
+            .is_key_rate_limited(
+                &slot.redis_key,
+                MAIL_MERGE_RECIPIENT_LIMIT,
+                MAIL_MERGE_RECIPIENT_WINDOW_SECS,
+                1,
+                &slot.member_id,
+            )
+            .await
+        {
+            return None;
+        }
+
+        Some(slot)
+    }
+
+    /// Gives back a slot taken for a mail merge message that was not scheduled
+    pub async fn release_mail_merge_recipient_slot(
+        &self,
+        slot: MailMergeRecipientSlot,
+    ) -> anyhow::Result<()> {
+        let mut redis_connection = self
+            .inner
+            .get_multiplexed_async_connection()
+            .await
+            .context("unable to connect to redis")?;
+
+        redis_connection
+            .zrem::<&str, &str, ()>(&slot.redis_key, &slot.member_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Records a request of the given cost against the sliding window of a key as `member_id`,
+    /// unless it would exceed the limit. If anything goes wrong, return false and hope for the best
+    async fn is_key_rate_limited(
+        &self,
+        redis_key: &str,
+        limit_units: u32,
+        window_secs: u32,
+        cost: u32,
+        member_id: &str,
+    ) -> bool {
         let mut con = match self.inner.get_multiplexed_async_connection().await {
             Ok(conn) => conn,
             Err(e) => {
                 tracing::warn!(
-                    "Failed to get Redis connection for rate limiting user_id {}: {}",
-                    user_id,
+                    "Failed to get Redis connection for rate limiting key {}: {}",
+                    redis_key,
                     e
                 );
                 return false;
@@ -34,30 +126,28 @@ impl RedisClient {
 
         let lua_script = get_rate_limit_script_with_usage();
 
-        let redis_key = format!("gmail-ratelimit:log:{}", user_id);
         let now_micros = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
             .as_micros() as u64;
-        let member_id = format!("{}:{}", cost, Uuid::new_v4());
 
         let script = Script::new(lua_script);
 
         let (is_limited, _current_units): (i32, u32) = match script
-            .key(&redis_key)
-            .arg(self.rate_limit_units)
-            .arg(self.rate_limit_secs * 1_000_000)
+            .key(redis_key)
+            .arg(limit_units)
+            .arg(window_secs as u64 * 1_000_000)
             .arg(now_micros)
             .arg(cost)
-            .arg(&member_id)
+            .arg(member_id)
             .invoke_async(&mut con)
             .await
         {
             Ok(res) => res,
             Err(e) => {
                 tracing::warn!(
-                    "Failed to execute rate limit script for user_id {}: {}",
-                    user_id.to_string(),
+                    "Failed to execute rate limit script for key {}: {}",
+                    redis_key,
                     e
                 );
                 return false;
@@ -68,6 +158,11 @@ impl RedisClient {
     }
 }
 
+/// A unique member of a rate limit window for a request of the given cost, formatted as "cost:uuid"
+fn rate_limit_member_id(cost: u32) -> String {
+    format!("{}:{}", cost, Uuid::new_v4())
+}
+
 /// Returns the raw Lua script for an atomic, cost-based sliding window rate limiter.
 ///
 /// The script is designed to be executed atomically on the Redis server. It tracks the
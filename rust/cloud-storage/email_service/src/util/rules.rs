@@ -0,0 +1,284 @@
+use anyhow::Context;
+use mail_provider::MailProvider;
+use models_email::email::service::address::ContactInfo;
+use models_email::email::service::link::Link;
+use models_email::email::service::message::MessageToSend;
+use models_email::service::label::system_labels;
+use models_email::service::rule::{Rule, RuleAction, RuleMessage, matching_actions};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Whether rules run on newly received messages, or retroactively on existing ones.
+/// Retroactive runs never forward messages or touch notifications, since those only make sense
+/// at the moment a message arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleTrigger {
+    Ingestion,
+    Retroactive,
+}
+
+impl RuleTrigger {
+    fn allows(self, action: &RuleAction) -> bool {
+        match action {
+            RuleAction::Forward { .. } | RuleAction::SuppressNotification => {
+                self == RuleTrigger::Ingestion
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Applies the actions of the rules matching each message. Failures for a single message are
+/// logged and don't stop the remaining messages from being processed.
+/// Returns the db ids of the messages whose new email notification should be suppressed.
+#[tracing::instrument(skip(db, provider, link, rules, messages), fields(link_id = %link.id, message_count = messages.len()), err)]
+pub async fn apply_rules(
+    db: &PgPool,
+    provider: &dyn MailProvider,
+    link: &Link,
+    rules: &[Rule],
+    messages: &[RuleMessage],
+    trigger: RuleTrigger,
+) -> anyhow::Result<HashSet<Uuid>> {
+    let mut suppressed = HashSet::new();
+
+    let message_actions = messages
+        .iter()
+        .map(|message| {
+            let actions = matching_actions(rules, message)
+                .into_iter()
+                .filter(|action| trigger.allows(action))
+                .collect::<Vec<_>>();
+            (message, actions)
+        })
+        .filter(|(_, actions)| !actions.is_empty())
+        .collect::<Vec<_>>();
+
+    if message_actions.is_empty() {
+        return Ok(suppressed);
+    }
+
+    // rules reference labels by db id, providers by provider id
+    let provider_label_ids: HashMap<Uuid, String> =
+        email_db_client::labels::get::fetch_labels_by_link_id(db, link.id)
+            .await
+            .context("Failed to fetch labels")?
+            .into_iter()
+            .filter_map(|label| label.id.map(|id| (id, label.provider_label_id)))
+            .collect();
+
+    for (message, actions) in message_actions {
+        if actions.contains(&RuleAction::SuppressNotification) {
+            suppressed.insert(message.db_id);
+        }
+
+        if let Err(e) =
+            apply_actions(db, provider, link, &provider_label_ids, message, &actions).await
+        {
+            tracing::error!(error = ?e, message_id = %message.db_id, "Failed to apply rule actions");
+        }
+    }
+
+    Ok(suppressed)
+}
+
+async fn apply_actions(
+    db: &PgPool,
+    provider: &dyn MailProvider,
+    link: &Link,
+    provider_label_ids: &HashMap<Uuid, String>,
+    message: &RuleMessage,
+    actions: &[RuleAction],
+) -> anyhow::Result<()> {
+    let mut labels_to_add = Vec::new();
+    let mut labels_to_remove = Vec::new();
+
+    for action in actions {
+        match action {
+            RuleAction::ApplyLabel { label_id } => match provider_label_ids.get(label_id) {
+                Some(provider_label_id) => labels_to_add.push(provider_label_id.clone()),
+                None => tracing::warn!(%label_id, "Rule references a label that no longer exists"),
+            },
+            RuleAction::Star => labels_to_add.push(system_labels::STARRED.to_string()),
+            RuleAction::Archive => labels_to_remove.push(system_labels::INBOX.to_string()),
+            RuleAction::MarkRead => labels_to_remove.push(system_labels::UNREAD.to_string()),
+            RuleAction::Forward { .. }
+            | RuleAction::SuppressNotification
+            | RuleAction::AddToProject { .. } => {}
+        }
+    }
+
+    if !labels_to_add.is_empty() || !labels_to_remove.is_empty() {
+        let provider_id = message
+            .provider_id
+            .as_deref()
+            .context("Message has no provider id")?;
+
+        provider
+            .modify_message_labels(provider_id, &labels_to_add, &labels_to_remove)
+            .await
+            .context("Failed to modify message labels in provider")?;
+
+        update_db_labels(
+            db,
+            link,
+            message,
+            actions,
+            &labels_to_add,
+            &labels_to_remove,
+        )
+        .await
+        .context("Failed to update message labels in db")?;
+    }
+
+    for action in actions {
+        match action {
+            RuleAction::Forward { email } => forward_message(db, provider, link, message, email)
+                .await
+                .with_context(|| format!("Failed to forward message to {}", email))?,
+            RuleAction::AddToProject { project_id } => {
+                let added = email_db_client::threads::project::add_thread_to_project(
+                    db,
+                    message.thread_db_id,
+                    project_id,
+                    link.macro_id.as_ref(),
+                )
+                .await
+                .context("Failed to add thread to project")?;
+
+                if !added {
+                    tracing::warn!(%project_id, "Rule references a project the user doesn't own");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn update_db_labels(
+    db: &PgPool,
+    link: &Link,
+    message: &RuleMessage,
+    actions: &[RuleAction],
+    labels_to_add: &[String],
+    labels_to_remove: &[String],
+) -> anyhow::Result<()> {
+    let message_ids = vec![message.db_id];
+
+    if actions.contains(&RuleAction::MarkRead) {
+        email_db_client::messages::update::update_message_read_status_batch(
+            db,
+            message_ids.clone(),
+            &link.fusionauth_user_id,
+            true,
+        )
+        .await?;
+    }
+
+    if actions.contains(&RuleAction::Star) {
+        email_db_client::messages::update::update_message_starred_status_batch(
+            db,
+            message_ids.clone(),
+            &link.fusionauth_user_id,
+            true,
+        )
+        .await?;
+    }
+
+    let mut tx = db.begin().await?;
+
+    for provider_label_id in labels_to_add {
+        email_db_client::labels::insert::insert_message_labels_batch(
+            &mut *tx,
+            &message_ids,
+            provider_label_id,
+            link.id,
+        )
+        .await?;
+    }
+
+    for provider_label_id in labels_to_remove {
+        email_db_client::labels::delete::delete_message_labels_batch(
+            &mut *tx,
+            &message_ids,
+            provider_label_id,
+            link.id,
+        )
+        .await?;
+    }
+
+    // archiving and marking as read change how the thread shows up in the inbox
+    email_db_client::threads::update::update_thread_metadata(
+        &mut tx,
+        message.thread_db_id,
+        link.id,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+async fn forward_message(
+    db: &PgPool,
+    provider: &dyn MailProvider,
+    link: &Link,
+    message: &RuleMessage,
+    email: &str,
+) -> anyhow::Result<()> {
+    let sender_contact = email_db_client::contacts::get::fetch_contact_by_email(
+        db,
+        link.id,
+        link.email_address.0.as_ref(),
+    )
+    .await?;
+
+    let from_contact = ContactInfo {
+        email: link.email_address.0.as_ref().to_string(),
+        name: sender_contact.as_ref().and_then(|c| c.name.clone()),
+        photo_url: sender_contact.and_then(|c| c.photo_url),
+    };
+
+    let subject = message.subject.clone().unwrap_or_default();
+
+    let body_text = format!(
+        "---------- Forwarded message ---------\nFrom: {}\nSubject: {}\n\n{}",
+        message.from_email.as_deref().unwrap_or_default(),
+        subject,
+        message.body_text.as_deref().unwrap_or_default()
+    );
+
+    let mut message_to_send = MessageToSend {
+        db_id: None,
+        provider_id: None,
+        replying_to_id: None,
+        provider_thread_id: None,
+        thread_db_id: None,
+        link_id: link.id,
+        subject: format!("Fwd: {}", subject),
+        to: Some(vec![ContactInfo {
+            email: email.to_string(),
+            name: None,
+            photo_url: None,
+        }]),
+        cc: None,
+        bcc: None,
+        body_text: Some(body_text),
+        body_html: None,
+        body_macro: None,
+        attachments: None,
+        attachments_macro: None,
+        headers_json: None,
+        send_time: None,
+        // tags the copy so rules skip it if it is forwarded back to a linked account
+        auto_forwarded: true,
+    };
+
+    provider
+        .send_message(&mut message_to_send, &from_contact, None, None)
+        .await
+}
@@ -0,0 +1,118 @@
+use anyhow::Context;
+use mail_provider::MailProvider;
+use models_email::email::service::address::ContactInfo;
+use models_email::email::service::link::Link;
+use models_email::email::service::message::MessageToSend;
+use outbound_http::Fetcher;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use sqlx::PgPool;
+use url::Url;
+
+/// Unsubscribes from a mailing list with an RFC 8058 one-click POST to its unsubscribe url.
+/// The sender controls the url, so it is posted through the fetcher, which only reaches public
+/// addresses and doesn't follow redirects.
+#[tracing::instrument(skip(fetcher), err)]
+pub async fn one_click_unsubscribe(fetcher: &Fetcher, http_url: &str) -> anyhow::Result<()> {
+    let url = Url::parse(http_url).context("Invalid unsubscribe url")?;
+    if url.scheme() != "https" {
+        anyhow::bail!("One-click unsubscribe requires an https url");
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-www-form-urlencoded"),
+    );
+
+    let status = fetcher
+        .post(
+            url.as_str(),
+            headers,
+            b"List-Unsubscribe=One-Click".to_vec(),
+        )
+        .await
+        .context("Failed to send unsubscribe request")?;
+
+    if !status.is_success() {
+        anyhow::bail!("Unsubscribe request returned HTTP {}", status);
+    }
+
+    Ok(())
+}
+
+/// Unsubscribes from a mailing list by sending the message its mailto unsubscribe uri describes
+#[tracing::instrument(skip(db, provider, link), fields(link_id = %link.id), err)]
+pub async fn mailto_unsubscribe(
+    db: &PgPool,
+    provider: &dyn MailProvider,
+    link: &Link,
+    mailto: &str,
+) -> anyhow::Result<()> {
+    let url = Url::parse(mailto).context("Invalid unsubscribe mailto uri")?;
+    if url.scheme() != "mailto" {
+        anyhow::bail!("Unsubscribe uri is not a mailto uri");
+    }
+
+    // mailto:addr1,addr2?subject=...&body=...
+    let to = url
+        .path()
+        .split(',')
+        .next()
+        .map(|address| urlencoding::decode(address).map(|address| address.trim().to_string()))
+        .transpose()
+        .context("Invalid unsubscribe address")?
+        .filter(|address| !address.is_empty())
+        .context("Unsubscribe uri has no address")?;
+
+    let mut subject = None;
+    let mut body = None;
+    for (name, value) in url.query_pairs() {
+        if name.eq_ignore_ascii_case("subject") {
+            subject = Some(value.into_owned());
+        } else if name.eq_ignore_ascii_case("body") {
+            body = Some(value.into_owned());
+        }
+    }
+
+    let sender_contact = email_db_client::contacts::get::fetch_contact_by_email(
+        db,
+        link.id,
+        link.email_address.0.as_ref(),
+    )
+    .await?;
+
+    let from_contact = ContactInfo {
+        email: link.email_address.0.as_ref().to_string(),
+        name: sender_contact.as_ref().and_then(|c| c.name.clone()),
+        photo_url: sender_contact.and_then(|c| c.photo_url),
+    };
+
+    let mut message_to_send = MessageToSend {
+        db_id: None,
+        provider_id: None,
+        replying_to_id: None,
+        provider_thread_id: None,
+        thread_db_id: None,
+        link_id: link.id,
+        subject: subject.unwrap_or_else(|| "unsubscribe".to_string()),
+        to: Some(vec![ContactInfo {
+            email: to,
+            name: None,
+            photo_url: None,
+        }]),
+        cc: None,
+        bcc: None,
+        body_text: Some(body.unwrap_or_else(|| "unsubscribe".to_string())),
+        body_html: None,
+        body_macro: None,
+        attachments: None,
+        attachments_macro: None,
+        headers_json: None,
+        send_time: None,
+        auto_forwarded: false,
+    };
+
+    provider
+        .send_message(&mut message_to_send, &from_contact, None, None)
+        .await
+}
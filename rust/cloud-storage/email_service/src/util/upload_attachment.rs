@@ -4,7 +4,7 @@ use anyhow::{Context, anyhow};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
 use document_storage_service_client::DocumentStorageServiceClient;
-use gmail_client::GmailClient;
+use mail_provider::MailProvider;
 use model::document::response::{CreateDocumentRequest, CreateDocumentResponse};
 use models_email::gmail::operations::GmailApiOperation;
 use models_email::service::attachment::AttachmentUploadMetadata;
@@ -12,12 +12,11 @@ use models_email::service::link;
 use sha2::{Digest, Sha256};
 
 /// Upload an email attachment to DSS as a document.
-#[tracing::instrument(skip(redis_client, gmail_client, dss_client, access_token), err)]
+#[tracing::instrument(skip(redis_client, provider, dss_client), err)]
 pub async fn upload_attachment(
     redis_client: &RedisClient,
-    gmail_client: &GmailClient,
+    provider: &dyn MailProvider,
     dss_client: &DocumentStorageServiceClient,
-    access_token: &str,
     link: &link::Link,
     p: &AttachmentUploadMetadata,
 ) -> anyhow::Result<String> {
@@ -31,23 +30,44 @@ pub async fn upload_attachment(
     .await
     .context("Rate limit check failed")?;
 
-    // 2. Fetch the raw attachment data from Gmail.
-    let attachment_data = fetch_gmail_attachment_data(gmail_client, access_token, p).await?;
+    // 2. Fetch the raw attachment data from the provider.
+    let attachment_data = fetch_attachment_data(provider, p).await?;
 
-    // 3. Calculate hashes required for the upload process.
+    // 3. Upload the data as a document.
+    upload_attachment_data(dss_client, link, p, attachment_data).await
+}
+
+/// Upload attachment data that is already at hand, e.g. from an imported message, to DSS as a
+/// document.
+#[tracing::instrument(skip(dss_client, attachment_data), err)]
+pub async fn upload_attachment_data(
+    dss_client: &DocumentStorageServiceClient,
+    link: &link::Link,
+    p: &AttachmentUploadMetadata,
+    attachment_data: Vec<u8>,
+) -> anyhow::Result<String> {
+    // 1. Calculate hashes required for the upload process.
     let (hex_hash, base64_hash) = calculate_hashes(&attachment_data);
 
-    // 4. Determine file metadata from the payload.
+    // 2. Determine file metadata from the payload.
     let (file_name, file_type) = determine_file_metadata(p)?;
 
-    // 5. Create the document record in DSS and get a presigned URL for the upload.
-    let dss_response =
-        create_dss_document_record(dss_client, link, p, &hex_hash, &file_name, &file_type).await?;
+    // 3. Create the document record in DSS and get a presigned URL for the upload.
+    let dss_response = create_dss_document_record(
+        dss_client,
+        link,
+        p,
+        &hex_hash,
+        attachment_data.len() as i64,
+        &file_name,
+        &file_type,
+    )
+    .await?;
 
-    // 6. Upload the attachment data to the presigned URL.
+    // 4. Upload the attachment data to the presigned URL.
     upload_data_to_presigned_url(&dss_response, attachment_data, &base64_hash).await?;
 
-    // 7. Return document id to caller
+    // 5. Return document id to caller
     let document_id = dss_response
         .data
         .document_response
@@ -57,20 +77,15 @@ pub async fn upload_attachment(
     Ok(document_id)
 }
 
-/// Fetches the raw attachment data from the Gmail API.
-async fn fetch_gmail_attachment_data(
-    gmail_client: &GmailClient,
-    access_token: &str,
+/// Fetches the raw attachment data from the mail provider.
+async fn fetch_attachment_data(
+    provider: &dyn MailProvider,
     p: &AttachmentUploadMetadata,
 ) -> anyhow::Result<Vec<u8>> {
-    gmail_client
-        .get_attachment_data(
-            access_token,
-            &p.email_provider_id,
-            &p.provider_attachment_id,
-        )
+    provider
+        .get_attachment_data(&p.email_provider_id, &p.provider_attachment_id)
         .await
-        .context("Failed to fetch attachment data from Gmail")
+        .context("Failed to fetch attachment data from provider")
 }
 
 /// Calculates the SHA256 hash of the attachment data in both hex and base64 formats.
@@ -113,12 +128,14 @@ async fn create_dss_document_record(
     link: &link::Link,
     p: &AttachmentUploadMetadata,
     hex_hash: &str,
+    size_bytes: i64,
     file_name: &str,
     file_type: &str,
 ) -> anyhow::Result<CreateDocumentResponse> {
     let request = CreateDocumentRequest {
         id: None,
         sha: hex_hash.to_string(),
+        size_bytes,
         document_name: file_name.to_string(),
         file_type: Some(file_type.to_string()),
         mime_type: Some(p.mime_type.clone()),
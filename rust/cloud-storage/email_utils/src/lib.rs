@@ -195,3 +195,22 @@ pub fn dedupe_emails(emails: Vec<String>) -> Vec<String> {
         .into_iter()
         .collect()
 }
+
+/// Normalizes an email address to the mailbox it is delivered to, so different spellings of the
+/// same address compare equal. Addresses are lowercased and + aliases are removed. Gmail also
+/// ignores dots in the local part and treats googlemail.com as gmail.com.
+pub fn canonical_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    let Some((local_part, domain)) = email.rsplit_once('@') else {
+        return email;
+    };
+
+    let local_part = local_part
+        .split_once('+')
+        .map_or(local_part, |(local_part, _)| local_part);
+
+    match domain {
+        "gmail.com" | "googlemail.com" => format!("{}@gmail.com", local_part.replace('.', "")),
+        _ => format!("{}@{}", local_part, domain),
+    }
+}
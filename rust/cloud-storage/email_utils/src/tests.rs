@@ -1,4 +1,4 @@
-use crate::{dedupe_emails, is_generic_email};
+use crate::{canonical_email, dedupe_emails, is_generic_email};
 
 #[test]
 fn test_is_generic_email() {
@@ -147,3 +147,19 @@ fn test_dedupe_emails_all_same_base() {
     assert_eq!(result.len(), 1);
     assert_eq!(result[0], "user@example.com");
 }
+
+#[test]
+fn test_canonical_email() {
+    assert_eq!(canonical_email(" User@Example.com "), "user@example.com");
+    assert_eq!(canonical_email("user+news@example.com"), "user@example.com");
+    // dots are only ignored by gmail
+    assert_eq!(
+        canonical_email("first.last@example.com"),
+        "first.last@example.com"
+    );
+    assert_eq!(
+        canonical_email("First.Last+work@googlemail.com"),
+        "firstlast@gmail.com"
+    );
+    assert_eq!(canonical_email("not an email"), "not an email");
+}
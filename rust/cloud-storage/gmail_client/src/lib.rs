@@ -6,7 +6,7 @@ pub(crate) mod labels;
 pub(crate) mod messages;
 pub(crate) mod parse;
 pub(crate) mod profile;
-pub(crate) mod sanitizer;
+pub mod sanitizer;
 mod settings;
 pub(crate) mod threads;
 pub(crate) mod watch;
@@ -30,6 +30,11 @@ use models_email::gmail::webhook::{
 use models_email::service::contact::{Contact, ContactList};
 use uuid::Uuid;
 
+// Used to map messages from other providers that are converted into Gmail resources
+pub use parse::message::map_message_resource_to_service;
+pub use parse::message::{find_header, parse_address_header};
+pub use parse::thread::map_thread_resource_to_service;
+
 #[derive(Clone, Debug)]
 pub struct GmailClient {
     /// The inner client used to make requests
@@ -88,7 +93,7 @@ impl GmailClient {
         &self,
         link_id: Uuid,
         access_token: &str,
-        thread_ids: &Vec<String>,
+        thread_ids: &[String],
     ) -> anyhow::Result<Vec<thread::Thread>> {
         get_threads_with_retry(self, link_id, access_token, thread_ids, DEFAULT_BATCH_SIZE).await
     }
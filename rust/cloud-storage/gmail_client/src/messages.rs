@@ -4,6 +4,7 @@ use anyhow::Context;
 use base64::Engine;
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use mail_builder::headers::address::Address;
+use mail_builder::headers::raw::Raw;
 use models_email::email::service::address::ContactInfo;
 use models_email::email::service::message;
 use models_email::gmail::{
@@ -163,6 +164,10 @@ pub(crate) async fn send_message(
         builder = builder.references(references);
     }
 
+    for (name, value) in message.extra_headers() {
+        builder = builder.header(*name, Raw::new(*value));
+    }
+
     if let Some(text_body) = &message.body_text {
         builder = builder.text_body(text_body);
     }
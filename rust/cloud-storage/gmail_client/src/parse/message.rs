@@ -3,6 +3,7 @@ use anyhow::{Context, Result};
 use chrono::{TimeZone, Utc};
 use mailparse::{MailAddr, MailAddrList, addrparse};
 use models_email::email::service;
+use models_email::email::service::subscription::ListUnsubscribe;
 use models_email::gmail::labels::SystemLabelID;
 use models_email::gmail::{Header, MessageResource};
 use uuid::Uuid;
@@ -69,6 +70,11 @@ pub fn map_message_resource_to_service(
         })
         .collect();
 
+    let list_unsubscribe = parse_list_unsubscribe(
+        &parsed_payload.all_headers,
+        parsed_payload.from.as_ref().map(|from| from.email.as_str()),
+    );
+
     Ok(service::message::Message {
         db_id: None, // Omitted - needs to be associated later
         provider_id: Some(message.id),
@@ -100,6 +106,7 @@ pub fn map_message_resource_to_service(
         attachments,
         attachments_macro: Vec::new(),
         headers_json: Some(serde_json::to_value(parsed_payload.all_headers)?),
+        list_unsubscribe,
         created_at: Utc::now(), // Omitted - set default/ignored
         updated_at: Utc::now(), // Omitted - set default/ignored
     })
@@ -156,6 +163,74 @@ fn process_parsed_addresses(addrs: &MailAddrList) -> Vec<(Option<String>, String
     results
 }
 
+/// Parses the List-Unsubscribe (RFC 2369) and List-Unsubscribe-Post (RFC 8058) headers of a
+/// message. Returns None if the message has no usable unsubscribe address.
+pub fn parse_list_unsubscribe(
+    headers: &[Header],
+    from_email: Option<&str>,
+) -> Option<ListUnsubscribe> {
+    let header_value = find_header(headers, "List-Unsubscribe")?;
+
+    // the header is a comma separated list of <uri> entries
+    let uris = header_value
+        .split(',')
+        .filter_map(|entry| {
+            let entry = entry.trim();
+            entry
+                .strip_prefix('<')
+                .and_then(|entry| entry.strip_suffix('>'))
+        })
+        .map(str::trim);
+
+    let mut http_url = None;
+    let mut mailto = None;
+    for uri in uris {
+        let lowercase = uri.to_ascii_lowercase();
+        if http_url.is_none()
+            && (lowercase.starts_with("https://") || lowercase.starts_with("http://"))
+        {
+            http_url = Some(uri.to_string());
+        } else if mailto.is_none() && lowercase.starts_with("mailto:") {
+            mailto = Some(uri.to_string());
+        }
+    }
+
+    if http_url.is_none() && mailto.is_none() {
+        return None;
+    }
+
+    // one-click unsubscribe is only defined for https urls
+    let one_click = http_url
+        .as_deref()
+        .is_some_and(|url| url.to_ascii_lowercase().starts_with("https://"))
+        && find_header(headers, "List-Unsubscribe-Post").is_some_and(|value| {
+            value
+                .trim()
+                .eq_ignore_ascii_case("List-Unsubscribe=One-Click")
+        });
+
+    // List-Id: Optional Description <list-label.example.com>
+    let list_id = find_header(headers, "List-Id")
+        .map(|value| match (value.rfind('<'), value.rfind('>')) {
+            (Some(start), Some(end)) if start < end => &value[start + 1..end],
+            _ => value,
+        })
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty());
+
+    let list_key = list_id
+        .clone()
+        .or_else(|| from_email.map(str::to_ascii_lowercase))?;
+
+    Some(ListUnsubscribe {
+        list_key,
+        list_id,
+        http_url,
+        mailto,
+        one_click,
+    })
+}
+
 /// Find header value by name (case-insensitive)
 pub fn find_header<'a>(headers: &'a [Header], name: &str) -> Option<&'a str> {
     headers
@@ -361,4 +436,65 @@ mod tests {
             )
         );
     }
+
+    fn header(name: &str, value: &str) -> Header {
+        Header {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_list_unsubscribe_one_click() {
+        let headers = vec![
+            header("List-Id", "Weekly News <News.Example.com>"),
+            header(
+                "List-Unsubscribe",
+                "<mailto:unsubscribe@example.com?subject=unsubscribe>, <https://example.com/unsub?id=1>",
+            ),
+            header("List-Unsubscribe-Post", "List-Unsubscribe=One-Click"),
+        ];
+
+        let result = parse_list_unsubscribe(&headers, Some("news@example.com")).unwrap();
+        assert_eq!(result.list_key, "news.example.com");
+        assert_eq!(result.list_id.as_deref(), Some("news.example.com"));
+        assert_eq!(
+            result.http_url.as_deref(),
+            Some("https://example.com/unsub?id=1")
+        );
+        assert_eq!(
+            result.mailto.as_deref(),
+            Some("mailto:unsubscribe@example.com?subject=unsubscribe")
+        );
+        assert!(result.one_click);
+    }
+
+    #[test]
+    fn test_parse_list_unsubscribe_without_list_id() {
+        // one-click requires an https url
+        let headers = vec![
+            header("list-unsubscribe", "<http://example.com/unsub>"),
+            header("List-Unsubscribe-Post", "List-Unsubscribe=One-Click"),
+        ];
+
+        let result = parse_list_unsubscribe(&headers, Some("News@Example.com")).unwrap();
+        assert_eq!(result.list_key, "news@example.com");
+        assert_eq!(result.list_id, None);
+        assert_eq!(result.mailto, None);
+        assert!(!result.one_click);
+    }
+
+    #[test]
+    fn test_parse_list_unsubscribe_invalid() {
+        assert_eq!(parse_list_unsubscribe(&[], Some("news@example.com")), None);
+
+        let headers = vec![header("List-Unsubscribe", "https://example.com/unsub")];
+        assert_eq!(
+            parse_list_unsubscribe(&headers, Some("news@example.com")),
+            None
+        );
+
+        let headers = vec![header("List-Unsubscribe", "<https://example.com/unsub>")];
+        assert_eq!(parse_list_unsubscribe(&headers, None), None);
+    }
 }
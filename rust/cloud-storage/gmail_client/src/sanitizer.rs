@@ -1,8 +1,41 @@
 use ammonia::Builder;
+use anyhow::Context;
+use lol_html::html_content::{ContentType, Element, TextChunk};
+use lol_html::{HtmlRewriter, Settings, element, text};
 use once_cell::sync::Lazy;
 use scraper::{Html, Selector};
+use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 
+/// Hosts of services serving open-tracking pixels. Subdomains of these hosts match as well.
+const TRACKER_HOSTS: &[&str] = &[
+    "mailtrack.io",
+    "mltrk.io",
+    "t.yesware.com",
+    "r.superhuman.com",
+    "mailfoogae.appspot.com",
+    "track.hubspot.com",
+    "t.hubspotemail.net",
+    "t.hubspotfree.net",
+    "bl-1.com",
+];
+
+/// Paths of the open-tracking endpoints of email service providers
+const TRACKER_PATHS: &[&str] = &[
+    "/wf/open",
+    "/track/open",
+    "/tracking/open",
+    "/open.gif",
+    "/pixel.gif",
+];
+
+/// Sanitized email html with its tracking pixels removed and its remote images proxied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxiedHtml {
+    pub html: String,
+    pub trackers_blocked: usize,
+}
+
 pub fn sanitize_email_html(raw_html: &str) -> String {
     // Attempt 1: Parse as a full document. This is best for well-formed emails.
     let document = Html::parse_document(raw_html);
@@ -24,6 +57,351 @@ pub fn sanitize_email_html(raw_html: &str) -> String {
     CLEANER.clean(&content_to_clean).to_string()
 }
 
+/// Removes the tracking pixels from sanitized email html, and points the remaining remote images
+/// at the image proxy so opening the email doesn't reveal anything to the sender.
+/// `proxy_url` returns the proxied url of a remote image, or None to leave the image as is.
+pub fn block_trackers_and_proxy_images(
+    sanitized_html: &str,
+    proxy_url: impl Fn(&str) -> Option<String>,
+) -> anyhow::Result<ProxiedHtml> {
+    let trackers_blocked = Cell::new(0);
+    // the text of a <style> tag can arrive in several chunks
+    let style_text = RefCell::new(String::new());
+    let mut output = Vec::new();
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![
+                element!("img", |el: &mut Element| {
+                    if let Some(src) = el.get_attribute("src")
+                        && is_tracker_image(
+                            &src,
+                            el.get_attribute("width").as_deref(),
+                            el.get_attribute("height").as_deref(),
+                            el.get_attribute("style").as_deref(),
+                        )
+                    {
+                        el.remove();
+                        trackers_blocked.set(trackers_blocked.get() + 1);
+                        return Ok(());
+                    }
+
+                    if let Some(src) = el.get_attribute("src")
+                        && let Some(proxied) = proxy_remote_url(src.trim(), &proxy_url)
+                    {
+                        el.set_attribute("src", &proxied)?;
+                    }
+
+                    if let Some(srcset) = el.get_attribute("srcset") {
+                        let proxied_srcset = srcset
+                            .split(',')
+                            .map(|part| {
+                                let mut components = part.split_whitespace();
+                                let Some(url) = components.next() else {
+                                    return part.trim().to_string();
+                                };
+                                let url = proxy_remote_url(url, &proxy_url)
+                                    .unwrap_or_else(|| url.to_string());
+                                std::iter::once(url.as_str())
+                                    .chain(components)
+                                    .collect::<Vec<_>>()
+                                    .join(" ")
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        el.set_attribute("srcset", &proxied_srcset)?;
+                    }
+                    Ok(())
+                }),
+                element!("[style]", |el: &mut Element| {
+                    if let Some(style) = el.get_attribute("style") {
+                        match proxy_css_urls(&style, &proxy_url) {
+                            Some(proxied) if proxied != style => {
+                                el.set_attribute("style", &proxied)?
+                            }
+                            Some(_) => {}
+                            // the style loads remote resources the proxy can't rewrite
+                            None => el.remove_attribute("style"),
+                        }
+                    }
+                    Ok(())
+                }),
+                text!("style", |chunk: &mut TextChunk| {
+                    style_text.borrow_mut().push_str(chunk.as_str());
+                    if chunk.last_in_text_node() {
+                        let css = std::mem::take(&mut *style_text.borrow_mut());
+                        // a stylesheet that loads remote resources the proxy can't rewrite is
+                        // dropped as a whole
+                        let proxied = proxy_css_urls(&css, &proxy_url).unwrap_or_default();
+                        chunk.replace(&proxied, ContentType::Html);
+                    } else {
+                        chunk.remove();
+                    }
+                    Ok(())
+                }),
+            ],
+            ..Settings::default()
+        },
+        |c: &[u8]| output.extend_from_slice(c),
+    );
+
+    rewriter
+        .write(sanitized_html.as_bytes())
+        .context("HTML rewriting error during write")?;
+    rewriter
+        .end()
+        .context("HTML rewriting error during end phase")?;
+
+    Ok(ProxiedHtml {
+        html: String::from_utf8(output).context("Rewritten HTML output is not valid UTF-8")?,
+        trackers_blocked: trackers_blocked.get(),
+    })
+}
+
+/// Whether an <img> is an open-tracking pixel: a remote image served by a known tracker, or one
+/// too small or hidden to be meant to be seen.
+pub fn is_tracker_image(
+    src: &str,
+    width: Option<&str>,
+    height: Option<&str>,
+    style: Option<&str>,
+) -> bool {
+    let Some(url) = remote_url(src.trim()) else {
+        return false;
+    };
+
+    let is_known_tracker = url::Url::parse(&url).is_ok_and(|url| {
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        let path = url.path().to_ascii_lowercase();
+        TRACKER_HOSTS
+            .iter()
+            .any(|tracker| host == *tracker || host.ends_with(&format!(".{}", tracker)))
+            || TRACKER_PATHS.iter().any(|tracker| path.contains(tracker))
+    });
+
+    let style = style.unwrap_or_default().to_ascii_lowercase();
+    let declarations = css_declarations(&style);
+    // css takes precedence over the width and height attributes
+    let is_pixel = [
+        css_property(&declarations, "width").or(width),
+        css_property(&declarations, "height").or(height),
+    ]
+    .into_iter()
+    .all(|size| size.is_some_and(is_at_most_one_pixel));
+
+    let is_hidden = css_property(&declarations, "display").is_some_and(|v| v.starts_with("none"))
+        || css_property(&declarations, "visibility").is_some_and(|v| v.starts_with("hidden"));
+
+    is_known_tracker || is_pixel || is_hidden
+}
+
+/// The absolute url of a remote http(s) image, None for inline and attached images
+fn remote_url(src: &str) -> Option<String> {
+    let lowercase = src.to_ascii_lowercase();
+    if lowercase.starts_with("https://") || lowercase.starts_with("http://") {
+        Some(src.to_string())
+    } else if src.starts_with("//") {
+        Some(format!("https:{}", src))
+    } else {
+        None
+    }
+}
+
+fn proxy_remote_url(src: &str, proxy_url: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    remote_url(src).and_then(|url| proxy_url(&url))
+}
+
+/// Proxies the remote urls referenced by `url(...)` in css. The function name is matched the way
+/// a browser reads it, ignoring case and resolving css escapes, so `URL(` and `u\72l(` are
+/// proxied too. Returns None if the css references a remote url the proxy would rewrite some other
+/// way, e.g. `@import "..."` or `image-set("...")`.
+fn proxy_css_urls(css: &str, proxy_url: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    let chars: Vec<char> = css.chars().collect();
+    let mut result = String::with_capacity(css.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        match chars[i] {
+            // comments are never loaded
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i = chars[i + 2..]
+                    .windows(2)
+                    .position(|w| w == ['*', '/'])
+                    .map_or(chars.len(), |end| i + 2 + end + 2);
+            }
+            '"' | '\'' => {
+                let (value, end) = read_css_string(&chars, i);
+                if proxy_remote_url(value.trim(), proxy_url).is_some() {
+                    return None;
+                }
+                i = end;
+            }
+            c if is_css_ident_char(c) || c == '\\' => {
+                let (name, end) = read_css_ident(&chars, i);
+                i = end.max(i + 1);
+                if name.eq_ignore_ascii_case("url") && chars.get(i) == Some(&'(') {
+                    let (value, end) = read_css_url(&chars, i + 1);
+                    i = end;
+                    if let Some(proxied) = proxy_remote_url(value.trim(), proxy_url) {
+                        result.push_str(&format!("url(\"{}\")", proxied));
+                        continue;
+                    }
+                }
+            }
+            _ => i += 1,
+        }
+        result.extend(&chars[start..i]);
+    }
+    Some(result)
+}
+
+fn is_css_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+/// Reads the css escape whose backslash is just before `start`. Returns the character it stands
+/// for, if any, and where it ends.
+fn read_css_escape(chars: &[char], start: usize) -> (Option<char>, usize) {
+    let hex_len = chars[start..]
+        .iter()
+        .take(6)
+        .take_while(|c| c.is_ascii_hexdigit())
+        .count();
+    if hex_len == 0 {
+        return match chars.get(start) {
+            // an escaped newline continues a string onto the next line
+            Some('\n') => (None, start + 1),
+            Some(c) => (Some(*c), start + 1),
+            None => (None, start),
+        };
+    }
+
+    let hex: String = chars[start..start + hex_len].iter().collect();
+    let c = u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .filter(|c| *c != '\0')
+        .unwrap_or(char::REPLACEMENT_CHARACTER);
+    let mut end = start + hex_len;
+    // a single whitespace ends the escape
+    if chars.get(end).is_some_and(|c| c.is_ascii_whitespace()) {
+        end += 1;
+    }
+    (Some(c), end)
+}
+
+/// Reads the css identifier at `start`, resolving its escapes. Returns it and where it ends.
+fn read_css_ident(chars: &[char], start: usize) -> (String, usize) {
+    let mut ident = String::new();
+    let mut i = start;
+    while let Some(&c) = chars.get(i) {
+        if c == '\\' {
+            if matches!(chars.get(i + 1), None | Some('\n')) {
+                break;
+            }
+            let (escaped, end) = read_css_escape(chars, i + 1);
+            ident.extend(escaped);
+            i = end;
+        } else if is_css_ident_char(c) {
+            ident.push(c);
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    (ident, i)
+}
+
+/// Reads the css string whose opening quote is at `start`, resolving its escapes. Returns it and
+/// where it ends.
+fn read_css_string(chars: &[char], start: usize) -> (String, usize) {
+    let quote = chars[start];
+    let mut value = String::new();
+    let mut i = start + 1;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            c if c == quote => return (value, i + 1),
+            // an unescaped newline ends a string early
+            '\n' => break,
+            '\\' => {
+                let (escaped, end) = read_css_escape(chars, i + 1);
+                value.extend(escaped);
+                i = end;
+            }
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+    (value, i)
+}
+
+/// Reads the argument of the `url(` whose opening bracket is just before `start`, quoted or not,
+/// resolving its escapes. Returns it and where the closing bracket ends.
+fn read_css_url(chars: &[char], start: usize) -> (String, usize) {
+    let skip_whitespace = |mut i: usize| {
+        while chars.get(i).is_some_and(|c| c.is_ascii_whitespace()) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut i = skip_whitespace(start);
+    let value = if matches!(chars.get(i), Some('"' | '\'')) {
+        let (value, end) = read_css_string(chars, i);
+        i = skip_whitespace(end);
+        value
+    } else {
+        let mut value = String::new();
+        while let Some(&c) = chars.get(i) {
+            match c {
+                ')' => break,
+                '\\' => {
+                    let (escaped, end) = read_css_escape(chars, i + 1);
+                    value.extend(escaped);
+                    i = end;
+                }
+                c => {
+                    value.push(c);
+                    i += 1;
+                }
+            }
+        }
+        value
+    };
+
+    // browsers load a url left open at the end of the css as well
+    match chars.get(i) {
+        Some(')') => (value, i + 1),
+        _ => (value, i),
+    }
+}
+
+fn css_declarations(style: &str) -> Vec<(&str, &str)> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect()
+}
+
+fn css_property<'a>(declarations: &[(&str, &'a str)], property: &str) -> Option<&'a str> {
+    declarations
+        .iter()
+        .rev()
+        .find(|(name, _)| *name == property)
+        .map(|(_, value)| *value)
+}
+
+fn is_at_most_one_pixel(size: &str) -> bool {
+    size.trim()
+        .trim_end_matches("px")
+        .trim()
+        .parse::<f64>()
+        .is_ok_and(|size| size <= 1.0)
+}
+
 /// Extracts all <style> tags and the <body> tag from a parsed document,
 /// wherever they might be, and reconstructs a clean HTML string.
 fn find_and_reconstruct(document: &Html) -> Option<String> {
@@ -297,6 +675,172 @@ mod tests {
         test_html_sanitization(input_html, expected_html, "body-only");
     }
 
+    #[test]
+    fn test_is_tracker_image() {
+        // known trackers
+        assert!(is_tracker_image(
+            "https://mailtrack.io/trace/mail/abc.png",
+            None,
+            None,
+            None
+        ));
+        assert!(is_tracker_image(
+            "https://u123.ct.sendgrid.net/wf/open?upn=abc",
+            None,
+            None,
+            None
+        ));
+        // pixels
+        assert!(is_tracker_image(
+            "https://example.com/a.gif",
+            Some("1"),
+            Some("1"),
+            None
+        ));
+        assert!(is_tracker_image(
+            "https://example.com/a.gif",
+            Some("600"),
+            None,
+            Some("width: 0px; height: 1px")
+        ));
+        assert!(is_tracker_image(
+            "//example.com/a.gif",
+            Some("0"),
+            Some("0px"),
+            None
+        ));
+        // hidden
+        assert!(is_tracker_image(
+            "https://example.com/a.gif",
+            None,
+            None,
+            Some("display:none")
+        ));
+        // regular images
+        assert!(!is_tracker_image(
+            "https://example.com/logo.png",
+            Some("1"),
+            Some("40"),
+            None
+        ));
+        assert!(!is_tracker_image(
+            "https://example.com/logo.png",
+            None,
+            None,
+            None
+        ));
+        // inline images never reach the sender
+        assert!(!is_tracker_image("cid:logo", Some("1"), Some("1"), None));
+    }
+
+    #[test]
+    fn test_block_trackers_and_proxy_images() {
+        let html = r#"<style>.hero { background: url('https://example.com/bg.png') }</style><div style="background-image: url(https://example.com/td.png)"><img src="https://example.com/logo.png" srcset="https://example.com/logo.png 1x, https://example.com/logo@2x.png 2x"><img src="https://sfs.macro.com/abc"><img src="cid:inline"><img src="https://example.com/open.gif?id=1" width="1" height="1"><img src="https://mailtrack.io/trace/abc"></div>"#;
+
+        let result = block_trackers_and_proxy_images(html, |url| {
+            (!url.starts_with("https://sfs.macro.com"))
+                .then(|| format!("https://proxy/?url={}", url))
+        })
+        .unwrap();
+
+        assert_eq!(result.trackers_blocked, 2);
+        assert_eq!(
+            result.html,
+            r#"<style>.hero { background: url("https://proxy/?url=https://example.com/bg.png") }</style><div style="background-image: url(&quot;https://proxy/?url=https://example.com/td.png&quot;)"><img src="https://proxy/?url=https://example.com/logo.png" srcset="https://proxy/?url=https://example.com/logo.png 1x, https://proxy/?url=https://example.com/logo@2x.png 2x"><img src="https://sfs.macro.com/abc"><img src="cid:inline"></div>"#
+        );
+    }
+
+    #[test]
+    fn test_proxy_css_urls() {
+        let proxy_url = |url: &str| {
+            (!url.starts_with("https://sfs.macro.com"))
+                .then(|| format!("https://proxy/?url={}", url))
+        };
+        let proxied = r#"background: url("https://proxy/?url=https://example.com/a.png")"#;
+
+        let cases = [
+            ("background: url(https://example.com/a.png)", Some(proxied)),
+            ("background: URL(https://example.com/a.png)", Some(proxied)),
+            (
+                "background: uRl( 'https://example.com/a.png' )",
+                Some(proxied),
+            ),
+            (
+                r"background: u\72l(https://example.com/a.png)",
+                Some(proxied),
+            ),
+            (
+                r"background: u\000072 l(https://example.com/a.png)",
+                Some(proxied),
+            ),
+            (
+                r"background: \55 \52 \4c (https://example.com/a.png)",
+                Some(proxied),
+            ),
+            (
+                r"background: url(https\:\2f\2f example.com/a.png)",
+                Some(proxied),
+            ),
+            (
+                r#"background: url("\68ttps://example.com/a.png")"#,
+                Some(proxied),
+            ),
+            ("background: url(https://example.com/a.png", Some(proxied)),
+            // not remote, not a url function, or left alone by the proxy
+            (
+                "background: url(cid:logo)",
+                Some("background: url(cid:logo)"),
+            ),
+            (
+                "background: myurl(https://example.com/a.png)",
+                Some("background: myurl(https://example.com/a.png)"),
+            ),
+            (
+                "/* url(https://example.com/a.png) */",
+                Some("/* url(https://example.com/a.png) */"),
+            ),
+            (
+                "background: url(https://sfs.macro.com/a.png)",
+                Some("background: url(https://sfs.macro.com/a.png)"),
+            ),
+            (
+                "@import 'https://sfs.macro.com/a.css';",
+                Some("@import 'https://sfs.macro.com/a.css';"),
+            ),
+            // remote urls outside of url() can't be proxied
+            ("@import 'https://example.com/a.css';", None),
+            (
+                r#"background: image-set("https://example.com/a.png" 1x)"#,
+                None,
+            ),
+            (
+                r#"background: image-set("\68ttps://example.com/a.png" 1x)"#,
+                None,
+            ),
+        ];
+
+        for (css, expected) in cases {
+            assert_eq!(
+                proxy_css_urls(css, &proxy_url).as_deref(),
+                expected,
+                "{}",
+                css
+            );
+        }
+    }
+
+    #[test]
+    fn test_block_trackers_and_proxy_images_drops_unproxyable_styles() {
+        let html = r#"<style>@import "https://example.com/a.css";</style><div style="background: image-set('https://example.com/a.png' 1x)">hi</div>"#;
+
+        let result = block_trackers_and_proxy_images(html, |url| {
+            Some(format!("https://proxy/?url={}", url))
+        })
+        .unwrap();
+
+        assert_eq!(result.html, "<style></style><div>hi</div>");
+    }
+
     fn test_html_sanitization(input_html: &str, expected_html: &str, test_name: &str) {
         // Get the sanitizer and sanitize the HTML
         let sanitized_html = sanitize_email_html(input_html);
@@ -0,0 +1,103 @@
+//! Signed urls for the image proxy of unfurl_service. The email service signs the remote images
+//! of the emails it returns, so the proxy only fetches images it was asked to by a signed url.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// When a proxy url signed at `now` (unix seconds) expires. Rounded to the day, so the url of an
+/// image doesn't change between requests on the same day and the browser can cache it.
+pub fn expires_at(now: i64) -> i64 {
+    (now / DAY_SECS + 2) * DAY_SECS
+}
+
+/// The hex encoded HMAC-SHA256 of the url and its expiry
+pub fn signature(secret: &str, url: &str, expires: i64) -> String {
+    hex::encode(mac(secret, url, expires).finalize().into_bytes())
+}
+
+/// Builds the url fetching `url` through the image proxy at `proxy_url`
+pub fn proxy_url(proxy_url: &str, secret: &str, url: &str, expires: i64) -> String {
+    format!(
+        "{}?url={}&expires={}&signature={}",
+        proxy_url,
+        urlencoding::encode(url),
+        expires,
+        signature(secret, url, expires)
+    )
+}
+
+/// Whether the signature of a proxied url is valid and hasn't expired at `now` (unix seconds)
+pub fn verify(secret: &str, url: &str, expires: i64, signature: &str, now: i64) -> bool {
+    if expires < now {
+        return false;
+    }
+
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+
+    // constant time comparison
+    mac(secret, url, expires).verify_slice(&signature).is_ok()
+}
+
+fn mac(secret: &str, url: &str, expires: i64) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(expires.to_string().as_bytes());
+    mac.update(b":");
+    mac.update(url.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "secret";
+    const IMAGE: &str = "https://example.com/logo.png?size=2";
+
+    #[test]
+    fn test_verify_signed_url() {
+        let signature = signature(SECRET, IMAGE, 200);
+        assert!(verify(SECRET, IMAGE, 200, &signature, 100));
+        assert!(!verify("other", IMAGE, 200, &signature, 100));
+        assert!(!verify(
+            SECRET,
+            "https://example.com/other.png",
+            200,
+            &signature,
+            100
+        ));
+        assert!(!verify(SECRET, IMAGE, 300, &signature, 100));
+        assert!(!verify(SECRET, IMAGE, 200, "not hex", 100));
+    }
+
+    #[test]
+    fn test_verify_expired() {
+        let signature = signature(SECRET, IMAGE, 200);
+        assert!(!verify(SECRET, IMAGE, 200, &signature, 201));
+    }
+
+    #[test]
+    fn test_expires_at_is_stable_within_a_day() {
+        let now = 1_700_000_000;
+        assert_eq!(expires_at(now), expires_at(now - now % DAY_SECS));
+        assert!(expires_at(now) >= now + DAY_SECS);
+    }
+
+    #[test]
+    fn test_proxy_url() {
+        let url = proxy_url("https://unfurl.macro.com/proxy/image", SECRET, IMAGE, 200);
+        assert_eq!(
+            url,
+            format!(
+                "https://unfurl.macro.com/proxy/image?url=https%3A%2F%2Fexample.com%2Flogo.png%3Fsize%3D2&expires=200&signature={}",
+                signature(SECRET, IMAGE, 200)
+            )
+        );
+    }
+}
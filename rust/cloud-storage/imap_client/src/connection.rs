@@ -0,0 +1,430 @@
+use crate::ImapConfig;
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use imap_proto::{Response, ResponseCode, Status};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// The number of bytes reserved in the read buffer before reading from the server
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+
+/// How the connection to a mail server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionSecurity {
+    /// The connection uses TLS from the start, e.g. IMAP on port 993
+    Tls,
+    /// The connection is upgraded to TLS with STARTTLS, e.g. IMAP on port 143
+    StartTls,
+    /// The connection is not encrypted. Only meant for local test servers.
+    None,
+}
+
+impl ConnectionSecurity {
+    /// Unencrypted connections send the password in the clear, so release builds refuse them
+    /// unless they are built to run the tests against the local server.
+    pub fn ensure_allowed(self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self != ConnectionSecurity::None || cfg!(any(debug_assertions, feature = "imap_test")),
+            "unencrypted connections are only allowed in test and dev builds"
+        );
+        Ok(())
+    }
+}
+
+pub(crate) trait ImapStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> ImapStream for T {}
+
+/// The responses to a command that completed successfully
+#[derive(Debug)]
+pub(crate) struct CommandResponse {
+    /// The untagged responses the server sent while running the command
+    pub untagged: Vec<Response<'static>>,
+    /// The response code of the tagged completion response
+    pub code: Option<ResponseCode<'static>>,
+}
+
+/// A connection to an IMAP server that sends tagged commands and reads their responses
+pub(crate) struct ImapConnection<S> {
+    stream: S,
+    buffer: Vec<u8>,
+    next_tag: u32,
+}
+
+impl ImapConnection<Box<dyn ImapStream>> {
+    /// Connects to the server and reads its greeting
+    pub(crate) async fn connect(config: &ImapConfig) -> anyhow::Result<Self> {
+        config.security.ensure_allowed()?;
+
+        let tcp = TcpStream::connect((config.host.as_str(), config.port))
+            .await
+            .with_context(|| format!("unable to connect to {}:{}", config.host, config.port))?;
+
+        match config.security {
+            ConnectionSecurity::Tls => {
+                let tls = tls_connect(&config.host, tcp).await?;
+                let mut connection = ImapConnection::new(Box::new(tls) as Box<dyn ImapStream>);
+                connection.read_greeting().await?;
+                Ok(connection)
+            }
+            ConnectionSecurity::StartTls => {
+                let mut plain = ImapConnection::new(tcp);
+                plain.read_greeting().await?;
+                plain.run_command("STARTTLS").await?;
+                anyhow::ensure!(
+                    plain.buffer.is_empty(),
+                    "server sent data before the tls handshake"
+                );
+
+                let tls = tls_connect(&config.host, plain.stream).await?;
+                Ok(ImapConnection {
+                    stream: Box::new(tls) as Box<dyn ImapStream>,
+                    buffer: Vec::new(),
+                    next_tag: plain.next_tag,
+                })
+            }
+            ConnectionSecurity::None => {
+                let mut connection = ImapConnection::new(Box::new(tcp) as Box<dyn ImapStream>);
+                connection.read_greeting().await?;
+                Ok(connection)
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapConnection<S> {
+    pub(crate) fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buffer: Vec::with_capacity(READ_CHUNK_SIZE),
+            next_tag: 1,
+        }
+    }
+
+    /// Reads the greeting the server sends once a connection is opened
+    pub(crate) async fn read_greeting(&mut self) -> anyhow::Result<()> {
+        match self.read_response().await? {
+            Response::Data {
+                status: Status::Ok | Status::PreAuth,
+                ..
+            } => Ok(()),
+            response => Err(anyhow::anyhow!("unexpected imap greeting {response:?}")),
+        }
+    }
+
+    /// Authenticates with the SASL PLAIN mechanism. Unlike LOGIN this does not require the
+    /// username and password to be quoted, so any characters are supported.
+    pub(crate) async fn authenticate_plain(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> anyhow::Result<()> {
+        let tag = self.send_command("AUTHENTICATE PLAIN").await?;
+        let mut untagged = Vec::new();
+        self.read_continuation(&tag, &mut untagged)
+            .await
+            .context("server rejected AUTHENTICATE PLAIN")?;
+
+        let credentials = STANDARD.encode(format!("\0{username}\0{password}"));
+        self.write_line(credentials.as_bytes()).await?;
+
+        self.read_until_done(&tag, untagged)
+            .await
+            .context("imap authentication failed")?;
+        Ok(())
+    }
+
+    /// Runs a command and waits for it to complete
+    pub(crate) async fn run_command(&mut self, command: &str) -> anyhow::Result<CommandResponse> {
+        let tag = self.send_command(command).await?;
+        self.read_until_done(&tag, Vec::new())
+            .await
+            .with_context(|| format!("imap command {command} failed"))
+    }
+
+    /// Runs a command that ends with a literal, e.g. the message of an APPEND command
+    pub(crate) async fn run_command_with_literal(
+        &mut self,
+        command: &str,
+        literal: &[u8],
+    ) -> anyhow::Result<CommandResponse> {
+        let tag = self
+            .send_command(&format!("{command} {{{}}}", literal.len()))
+            .await?;
+
+        let mut untagged = Vec::new();
+        self.read_continuation(&tag, &mut untagged)
+            .await
+            .with_context(|| format!("server rejected literal for {command}"))?;
+
+        self.write_line(literal).await?;
+        self.read_until_done(&tag, untagged)
+            .await
+            .with_context(|| format!("imap command {command} failed"))
+    }
+
+    /// Sends IDLE and waits for the server to accept it.
+    /// Returns the tag that completes once [`Self::finish_idle`] is called.
+    pub(crate) async fn start_idle(&mut self) -> anyhow::Result<(String, Vec<Response<'static>>)> {
+        let tag = self.send_command("IDLE").await?;
+        let mut untagged = Vec::new();
+        self.read_continuation(&tag, &mut untagged)
+            .await
+            .context("server rejected IDLE")?;
+        Ok((tag, untagged))
+    }
+
+    /// Ends an IDLE command started with [`Self::start_idle`]
+    pub(crate) async fn finish_idle(&mut self, tag: &str) -> anyhow::Result<CommandResponse> {
+        self.write_line(b"DONE").await?;
+        self.read_until_done(tag, Vec::new()).await
+    }
+
+    async fn send_command(&mut self, command: &str) -> anyhow::Result<String> {
+        let tag = format!("A{:04}", self.next_tag);
+        self.next_tag += 1;
+        self.write_line(format!("{tag} {command}").as_bytes())
+            .await?;
+        Ok(tag)
+    }
+
+    async fn write_line(&mut self, line: &[u8]) -> anyhow::Result<()> {
+        self.stream.write_all(line).await?;
+        self.stream.write_all(b"\r\n").await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Reads responses until the server asks for the rest of the command
+    async fn read_continuation(
+        &mut self,
+        tag: &str,
+        untagged: &mut Vec<Response<'static>>,
+    ) -> anyhow::Result<()> {
+        loop {
+            match self.read_response().await? {
+                Response::Continue { .. } => return Ok(()),
+                Response::Done {
+                    tag: done_tag,
+                    status,
+                    information,
+                    ..
+                } if done_tag.0 == tag => {
+                    anyhow::bail!(
+                        "command completed with {status:?} before continuation: {}",
+                        information.unwrap_or_default()
+                    );
+                }
+                response => untagged.push(response),
+            }
+        }
+    }
+
+    /// Reads responses until the command with the given tag completes
+    async fn read_until_done(
+        &mut self,
+        tag: &str,
+        mut untagged: Vec<Response<'static>>,
+    ) -> anyhow::Result<CommandResponse> {
+        loop {
+            match self.read_response().await? {
+                Response::Done {
+                    tag: done_tag,
+                    status,
+                    code,
+                    information,
+                } if done_tag.0 == tag => {
+                    return match status {
+                        Status::Ok => Ok(CommandResponse { untagged, code }),
+                        status => Err(anyhow::anyhow!(
+                            "server responded with {status:?}: {}",
+                            information.unwrap_or_default()
+                        )),
+                    };
+                }
+                Response::Data {
+                    status: Status::Bye,
+                    information,
+                    ..
+                } => {
+                    anyhow::bail!(
+                        "server closed the connection: {}",
+                        information.unwrap_or_default()
+                    );
+                }
+                response => untagged.push(response),
+            }
+        }
+    }
+
+    /// Reads the next response from the server.
+    /// This is cancel safe, partially read responses are kept in the buffer.
+    pub(crate) async fn read_response(&mut self) -> anyhow::Result<Response<'static>> {
+        loop {
+            if !self.buffer.is_empty() {
+                let parsed = match imap_proto::parser::parse_response(&self.buffer) {
+                    Ok((remaining, response)) => {
+                        Some((self.buffer.len() - remaining.len(), response.into_owned()))
+                    }
+                    Err(e) if e.is_incomplete() => None,
+                    Err(_) => {
+                        let line_end = self
+                            .buffer
+                            .iter()
+                            .position(|b| *b == b'\n')
+                            .unwrap_or(self.buffer.len());
+                        anyhow::bail!(
+                            "unable to parse imap response: {}",
+                            String::from_utf8_lossy(&self.buffer[..line_end]).trim_end()
+                        );
+                    }
+                };
+
+                if let Some((consumed, response)) = parsed {
+                    self.buffer.drain(..consumed);
+                    return Ok(response);
+                }
+            }
+
+            self.buffer.reserve(READ_CHUNK_SIZE);
+            let read = self.stream.read_buf(&mut self.buffer).await?;
+            anyhow::ensure!(read > 0, "imap connection closed by server");
+        }
+    }
+}
+
+async fn tls_connect(
+    host: &str,
+    tcp: TcpStream,
+) -> anyhow::Result<tokio_native_tls::TlsStream<TcpStream>> {
+    let connector = tokio_native_tls::native_tls::TlsConnector::new()
+        .context("unable to create tls connector")?;
+    tokio_native_tls::TlsConnector::from(connector)
+        .connect(host, tcp)
+        .await
+        .with_context(|| format!("tls handshake with {host} failed"))
+}
+
+/// Quotes a string so it can be used as an argument to an IMAP command
+pub(crate) fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imap_proto::{AttributeValue, MailboxDatum};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("INBOX"), r#""INBOX""#);
+        assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+
+    #[tokio::test]
+    async fn test_run_command_reads_literals_across_reads() -> anyhow::Result<()> {
+        let (client, server) = tokio::io::duplex(8);
+        let mut connection = ImapConnection::new(client);
+
+        let server = tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server);
+            let mut lines = BufReader::new(read).lines();
+            write.write_all(b"* OK ready\r\n").await?;
+
+            let command = lines.next_line().await?.unwrap_or_default();
+            assert_eq!(command, "A0001 UID FETCH 4 (UID BODY.PEEK[])");
+            write
+                .write_all(
+                    b"* 1 FETCH (UID 4 BODY[] {12}\r\nhello\r\nworld)\r\n* 2 EXISTS\r\nA0001 OK done\r\n",
+                )
+                .await?;
+            anyhow::Ok(())
+        });
+
+        connection.read_greeting().await?;
+        let response = connection
+            .run_command("UID FETCH 4 (UID BODY.PEEK[])")
+            .await?;
+        server.await??;
+
+        assert_eq!(response.untagged.len(), 2);
+        let Response::Fetch(1, attributes) = &response.untagged[0] else {
+            anyhow::bail!("expected fetch response");
+        };
+        assert!(attributes.contains(&AttributeValue::Uid(4)));
+        assert!(attributes.iter().any(|attribute| matches!(
+            attribute,
+            AttributeValue::BodySection { data: Some(data), .. } if data.as_ref() == b"hello\r\nworld"
+        )));
+        assert_eq!(
+            response.untagged[1],
+            Response::MailboxData(MailboxDatum::Exists(2))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_command_returns_error_on_no() -> anyhow::Result<()> {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut connection = ImapConnection::new(client);
+        server
+            .write_all(b"* OK ready\r\nA0001 NO [NONEXISTENT] unknown mailbox\r\n")
+            .await?;
+
+        connection.read_greeting().await?;
+        let error = connection
+            .run_command("SELECT \"missing\"")
+            .await
+            .expect_err("command should fail");
+        assert!(format!("{error:#}").contains("unknown mailbox"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_command_with_literal() -> anyhow::Result<()> {
+        let (client, server) = tokio::io::duplex(1024);
+        let mut connection = ImapConnection::new(client);
+
+        let server = tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server);
+            let mut reader = BufReader::new(read);
+            let mut line = String::new();
+            reader.read_line(&mut line).await?;
+            assert_eq!(line, "A0001 APPEND \"Sent\" (\\Seen) {5}\r\n");
+            write.write_all(b"+ go ahead\r\n").await?;
+
+            line.clear();
+            reader.read_line(&mut line).await?;
+            assert_eq!(line, "hello\r\n");
+            write
+                .write_all(b"A0001 OK [APPENDUID 38505 3955] APPEND completed\r\n")
+                .await?;
+            anyhow::Ok(())
+        });
+
+        let response = connection
+            .run_command_with_literal("APPEND \"Sent\" (\\Seen)", b"hello")
+            .await?;
+        server.await??;
+
+        assert!(matches!(
+            response.code,
+            Some(ResponseCode::AppendUid(38505, _))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! A client for mailboxes that are accessed over IMAP and SMTP instead of a provider specific API.
+//! Mailboxes are synced incrementally with CONDSTORE/QRESYNC and watched for changes with IDLE.
+//!
+//! The command loop is our own rather than async-imap's. Syncing depends on `SELECT ... (QRESYNC ...)`
+//! and the `VANISHED` responses it produces, which async-imap has no typed API for, so we would be
+//! sending raw commands and picking their responses out of its unsolicited response channel.
+//! Responses are still parsed by imap-proto, the parser async-imap is built on.
+
+pub(crate) mod connection;
+pub mod mailbox;
+pub(crate) mod session;
+pub mod smtp;
+pub mod sync;
+
+pub use connection::ConnectionSecurity;
+pub use mailbox::{Mailbox, MailboxRole};
+pub use session::{FetchedMessage, IdleEvent, ImapSession};
+pub use smtp::{SmtpClient, SmtpConfig};
+pub use sync::{ImapMessageId, MailboxChanges, MailboxState, SyncState, UidSet};
+
+/// The settings used to connect to an IMAP server
+#[derive(Clone)]
+pub struct ImapConfig {
+    /// The hostname of the IMAP server
+    pub host: String,
+    /// The port of the IMAP server
+    pub port: u16,
+    /// How the connection to the server is secured
+    pub security: ConnectionSecurity,
+    /// The username used to log in
+    pub username: String,
+    /// The password used to log in. Usually an app specific password.
+    pub password: String,
+}
+
+impl std::fmt::Debug for ImapConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImapConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("security", &self.security)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ImapClient {
+    config: ImapConfig,
+}
+
+impl ImapClient {
+    pub fn new(config: ImapConfig) -> Self {
+        Self { config }
+    }
+
+    /// The username of the mailbox
+    pub fn username(&self) -> &str {
+        &self.config.username
+    }
+
+    /// Opens a new authenticated session with the server
+    #[tracing::instrument(skip(self), fields(host = %self.config.host))]
+    pub async fn connect(&self) -> anyhow::Result<ImapSession> {
+        ImapSession::connect(&self.config).await
+    }
+}
@@ -0,0 +1,143 @@
+use imap_proto::NameAttribute;
+
+/// What a mailbox is used for. Taken from the special-use attributes of the mailbox (RFC 6154)
+/// and falling back to well known mailbox names for servers that do not support them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MailboxRole {
+    Inbox,
+    Sent,
+    Drafts,
+    Trash,
+    Junk,
+    Archive,
+    /// A virtual mailbox containing every message
+    All,
+    /// A virtual mailbox containing every flagged message
+    Flagged,
+    /// A mailbox created by the user
+    Other,
+}
+
+/// A mailbox returned by LIST
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mailbox {
+    /// The full name of the mailbox as it is used in commands
+    pub name: String,
+    /// The hierarchy delimiter of the mailbox
+    pub delimiter: Option<String>,
+    /// What the mailbox is used for
+    pub role: MailboxRole,
+    /// Whether the mailbox can be selected
+    pub selectable: bool,
+}
+
+impl Mailbox {
+    pub(crate) fn from_list(
+        name: &str,
+        delimiter: Option<&str>,
+        attributes: &[NameAttribute<'_>],
+    ) -> Self {
+        let special_use = attributes.iter().find_map(|attribute| match attribute {
+            NameAttribute::Sent => Some(MailboxRole::Sent),
+            NameAttribute::Drafts => Some(MailboxRole::Drafts),
+            NameAttribute::Trash => Some(MailboxRole::Trash),
+            NameAttribute::Junk => Some(MailboxRole::Junk),
+            NameAttribute::Archive => Some(MailboxRole::Archive),
+            NameAttribute::All => Some(MailboxRole::All),
+            NameAttribute::Flagged => Some(MailboxRole::Flagged),
+            _ => None,
+        });
+
+        let role = if name.eq_ignore_ascii_case("INBOX") {
+            MailboxRole::Inbox
+        } else {
+            special_use.unwrap_or_else(|| role_from_name(leaf_name(name, delimiter)))
+        };
+
+        let selectable = !attributes.iter().any(|attribute| {
+            matches!(attribute, NameAttribute::NoSelect)
+                || matches!(attribute, NameAttribute::Extension(ext) if ext.eq_ignore_ascii_case("\\NonExistent"))
+        });
+
+        Self {
+            name: name.to_string(),
+            delimiter: delimiter.map(str::to_string),
+            role,
+            selectable,
+        }
+    }
+
+    /// Whether the messages in the mailbox should be synced.
+    /// Virtual mailboxes are skipped as their messages are already synced from the mailboxes
+    /// that store them.
+    pub fn is_synced(&self) -> bool {
+        self.selectable && !matches!(self.role, MailboxRole::All | MailboxRole::Flagged)
+    }
+
+    /// The name of the mailbox without its parents
+    pub fn display_name(&self) -> &str {
+        leaf_name(&self.name, self.delimiter.as_deref())
+    }
+}
+
+fn leaf_name<'a>(name: &'a str, delimiter: Option<&str>) -> &'a str {
+    match delimiter {
+        Some(delimiter) if !delimiter.is_empty() => name.rsplit(delimiter).next().unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Guesses the role of a mailbox from the names commonly used by servers without special-use
+fn role_from_name(name: &str) -> MailboxRole {
+    match name.to_lowercase().as_str() {
+        "sent" | "sent items" | "sent messages" | "sent mail" => MailboxRole::Sent,
+        "drafts" | "draft" => MailboxRole::Drafts,
+        "trash" | "deleted items" | "deleted messages" | "bin" => MailboxRole::Trash,
+        "junk" | "spam" | "junk e-mail" | "junk email" | "bulk mail" => MailboxRole::Junk,
+        "archive" | "archives" => MailboxRole::Archive,
+        _ => MailboxRole::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_mailbox_roles() {
+        let inbox = Mailbox::from_list("inbox", Some("/"), &[]);
+        assert_eq!(inbox.role, MailboxRole::Inbox);
+
+        let sent = Mailbox::from_list("Gesendet", Some("/"), &[NameAttribute::Sent]);
+        assert_eq!(sent.role, MailboxRole::Sent);
+
+        let trash = Mailbox::from_list("INBOX.Deleted Items", Some("."), &[]);
+        assert_eq!(trash.role, MailboxRole::Trash);
+        assert_eq!(trash.display_name(), "Deleted Items");
+
+        let user = Mailbox::from_list("Projects/Macro", Some("/"), &[]);
+        assert_eq!(user.role, MailboxRole::Other);
+        assert_eq!(user.display_name(), "Macro");
+        assert!(user.is_synced());
+    }
+
+    #[test]
+    fn test_virtual_and_unselectable_mailboxes_are_not_synced() {
+        let all = Mailbox::from_list("All Mail", Some("/"), &[NameAttribute::All]);
+        assert!(!all.is_synced());
+
+        let flagged = Mailbox::from_list("Starred", Some("/"), &[NameAttribute::Flagged]);
+        assert!(!flagged.is_synced());
+
+        let parent = Mailbox::from_list("Projects", Some("/"), &[NameAttribute::NoSelect]);
+        assert!(!parent.is_synced());
+
+        let missing = Mailbox::from_list(
+            "Gone",
+            Some("/"),
+            &[NameAttribute::Extension(Cow::Borrowed("\\NonExistent"))],
+        );
+        assert!(!missing.is_synced());
+    }
+}
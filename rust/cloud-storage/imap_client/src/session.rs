@@ -0,0 +1,773 @@
+use crate::ImapConfig;
+use crate::connection::{CommandResponse, ImapConnection, ImapStream, quote};
+use crate::mailbox::Mailbox;
+use crate::sync::{ImapMessageId, MailboxChanges, MailboxState, UidSet};
+use anyhow::Context;
+use imap_proto::{AttributeValue, Capability, MailboxDatum, Response, ResponseCode, UidSetMember};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// A message fetched from the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchedMessage {
+    pub id: ImapMessageId,
+    pub flags: Vec<String>,
+    /// The INTERNALDATE of the message, i.e. when the server received it
+    pub internal_date: Option<String>,
+    pub size: Option<u32>,
+    /// The fetched body section. Either the full message or the requested headers.
+    pub body: Vec<u8>,
+}
+
+/// Why IDLE returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleEvent {
+    /// The server reported a change to the mailbox
+    MailboxChanged,
+    /// Nothing changed before the timeout
+    Timeout,
+}
+
+/// The state reported by the server when a mailbox is selected
+#[derive(Debug, Clone, Default)]
+struct SelectedMailbox {
+    name: String,
+    uid_validity: u32,
+    uid_next: Option<u32>,
+    highest_modseq: Option<u64>,
+    /// UIDs reported as expunged through QRESYNC
+    vanished: Vec<u32>,
+}
+
+/// An authenticated IMAP session
+pub struct ImapSession {
+    connection: ImapConnection<Box<dyn ImapStream>>,
+    capabilities: HashSet<String>,
+    qresync_enabled: bool,
+    selected: Option<SelectedMailbox>,
+}
+
+impl ImapSession {
+    pub(crate) async fn connect(config: &ImapConfig) -> anyhow::Result<Self> {
+        let mut connection = ImapConnection::connect(config).await?;
+        connection
+            .authenticate_plain(&config.username, &config.password)
+            .await?;
+
+        let mut session = Self {
+            connection,
+            capabilities: HashSet::new(),
+            qresync_enabled: false,
+            selected: None,
+        };
+
+        // capabilities can change after authenticating so they are requested again
+        let response = session.connection.run_command("CAPABILITY").await?;
+        session.capabilities = response
+            .untagged
+            .iter()
+            .filter_map(|response| match response {
+                Response::Capabilities(capabilities) => Some(capabilities),
+                _ => None,
+            })
+            .flatten()
+            .map(|capability| match capability {
+                Capability::Imap4rev1 => "IMAP4REV1".to_string(),
+                Capability::Auth(mechanism) => format!("AUTH={}", mechanism.to_uppercase()),
+                Capability::Atom(atom) => atom.to_uppercase(),
+            })
+            .collect();
+
+        anyhow::ensure!(
+            session.has_capability("CONDSTORE") || session.has_capability("QRESYNC"),
+            "imap server does not support CONDSTORE which is required for syncing"
+        );
+
+        if session.has_capability("QRESYNC") {
+            session.connection.run_command("ENABLE QRESYNC").await?;
+            session.qresync_enabled = true;
+        }
+
+        Ok(session)
+    }
+
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.contains(&capability.to_uppercase())
+    }
+
+    /// Lists every mailbox of the user
+    #[tracing::instrument(skip(self), err)]
+    pub async fn list_mailboxes(&mut self) -> anyhow::Result<Vec<Mailbox>> {
+        let response = self.connection.run_command(r#"LIST "" "*""#).await?;
+
+        Ok(response
+            .untagged
+            .iter()
+            .filter_map(|response| match response {
+                Response::MailboxData(MailboxDatum::List {
+                    name_attributes,
+                    delimiter,
+                    name,
+                }) => Some(Mailbox::from_list(
+                    name,
+                    delimiter.as_deref(),
+                    name_attributes,
+                )),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Creates a mailbox
+    #[tracing::instrument(skip(self), err)]
+    pub async fn create_mailbox(&mut self, mailbox: &str) -> anyhow::Result<()> {
+        self.connection
+            .run_command(&format!("CREATE {}", quote(mailbox)))
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes a mailbox and every message in it
+    #[tracing::instrument(skip(self), err)]
+    pub async fn delete_mailbox(&mut self, mailbox: &str) -> anyhow::Result<()> {
+        // a selected mailbox can't be deleted on every server
+        if self
+            .selected
+            .as_ref()
+            .is_some_and(|selected| selected.name == mailbox)
+        {
+            self.connection.run_command("CLOSE").await?;
+            self.selected = None;
+        }
+
+        self.connection
+            .run_command(&format!("DELETE {}", quote(mailbox)))
+            .await?;
+        Ok(())
+    }
+
+    /// Gets the changes to a mailbox since the previous state.
+    /// If there is no previous state or the mailbox was recreated every message is reported
+    /// as new.
+    #[tracing::instrument(skip(self, previous), err)]
+    pub async fn sync_mailbox(
+        &mut self,
+        mailbox: &str,
+        previous: Option<&MailboxState>,
+    ) -> anyhow::Result<MailboxChanges> {
+        let selected = self.select(mailbox, previous).await?;
+        let highest_modseq = selected
+            .highest_modseq
+            .with_context(|| format!("mailbox {mailbox} does not support mod-sequences"))?;
+
+        let Some(previous) =
+            previous.filter(|previous| previous.uid_validity == selected.uid_validity)
+        else {
+            let uids: UidSet = self.search_uids("ALL").await?.into_iter().collect();
+            let state = MailboxState {
+                uid_validity: selected.uid_validity,
+                highest_modseq,
+                uid_next: uid_next(&selected, &uids),
+                uids,
+            };
+            return Ok(MailboxChanges::resync(mailbox, previous, state));
+        };
+
+        // every change, including expunges, increases the mod-sequence
+        if highest_modseq == previous.highest_modseq {
+            return Ok(MailboxChanges::incremental(
+                mailbox,
+                previous,
+                highest_modseq,
+                previous.uid_next,
+                [],
+                [],
+            ));
+        }
+
+        let vanished = if self.qresync_enabled {
+            selected.vanished.clone()
+        } else {
+            let current: HashSet<u32> = self.search_uids("ALL").await?.into_iter().collect();
+            previous
+                .uids
+                .iter()
+                .filter(|uid| !current.contains(uid))
+                .collect()
+        };
+
+        let changed = self
+            .connection
+            .run_command(&format!(
+                "UID FETCH 1:* (UID FLAGS) (CHANGEDSINCE {})",
+                previous.highest_modseq
+            ))
+            .await?
+            .untagged
+            .iter()
+            .filter_map(|response| match response {
+                Response::Fetch(_, attributes) => fetch_uid(attributes),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let uid_next = selected
+            .uid_next
+            .or_else(|| changed.iter().max().map(|uid| uid + 1))
+            .unwrap_or(previous.uid_next)
+            .max(previous.uid_next);
+
+        Ok(MailboxChanges::incremental(
+            mailbox,
+            previous,
+            highest_modseq,
+            uid_next,
+            changed,
+            vanished,
+        ))
+    }
+
+    /// Fetches the full content of a message.
+    /// Returns None if the message no longer exists.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn fetch_message(
+        &mut self,
+        id: &ImapMessageId,
+    ) -> anyhow::Result<Option<FetchedMessage>> {
+        let messages = self
+            .fetch(id, "(UID FLAGS INTERNALDATE RFC822.SIZE BODY.PEEK[])")
+            .await?;
+        Ok(messages.into_iter().next())
+    }
+
+    /// Fetches the flags of a message.
+    /// Returns None if the message no longer exists.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn fetch_flags(&mut self, id: &ImapMessageId) -> anyhow::Result<Option<Vec<String>>> {
+        let messages = self.fetch(id, "(UID FLAGS)").await?;
+        Ok(messages.into_iter().next().map(|message| message.flags))
+    }
+
+    /// Fetches the given headers of the messages in a mailbox
+    #[tracing::instrument(skip(self, headers), err)]
+    pub async fn fetch_headers(
+        &mut self,
+        mailbox: &str,
+        uids: &UidSet,
+        headers: &[&str],
+    ) -> anyhow::Result<Vec<FetchedMessage>> {
+        if uids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let selected = self.select_cached(mailbox).await?;
+        let response = self
+            .connection
+            .run_command(&format!(
+                "UID FETCH {uids} (UID FLAGS INTERNALDATE BODY.PEEK[HEADER.FIELDS ({})])",
+                headers.join(" ")
+            ))
+            .await?;
+
+        Ok(fetched_messages(&selected, response))
+    }
+
+    /// Searches a mailbox and returns the UIDs of the matching messages
+    #[tracing::instrument(skip(self), err)]
+    pub async fn search(&mut self, mailbox: &str, criteria: &str) -> anyhow::Result<Vec<u32>> {
+        self.select_cached(mailbox).await?;
+        self.search_uids(criteria).await
+    }
+
+    /// The UIDVALIDITY of a mailbox
+    pub async fn uid_validity(&mut self, mailbox: &str) -> anyhow::Result<u32> {
+        Ok(self.select_cached(mailbox).await?.uid_validity)
+    }
+
+    /// Adds a message to a mailbox.
+    /// Returns the id of the new message if the server supports UIDPLUS.
+    #[tracing::instrument(skip(self, message), err)]
+    pub async fn append(
+        &mut self,
+        mailbox: &str,
+        flags: &[&str],
+        message: &[u8],
+    ) -> anyhow::Result<Option<ImapMessageId>> {
+        let response = self
+            .connection
+            .run_command_with_literal(
+                &format!("APPEND {} ({})", quote(mailbox), flags.join(" ")),
+                message,
+            )
+            .await?;
+
+        Ok(match response.code {
+            Some(ResponseCode::AppendUid(uid_validity, uids)) => {
+                let uid = uids.into_iter().next().map(|member| match member {
+                    UidSetMember::Uid(uid) => uid,
+                    UidSetMember::UidRange(range) => *range.start(),
+                });
+                uid.map(|uid| ImapMessageId {
+                    mailbox: mailbox.to_string(),
+                    uid_validity,
+                    uid,
+                })
+            }
+            _ => None,
+        })
+    }
+
+    /// Adds or removes flags of a message.
+    /// Returns false if the message no longer exists.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn store_flags(
+        &mut self,
+        id: &ImapMessageId,
+        add: bool,
+        flags: &[&str],
+    ) -> anyhow::Result<bool> {
+        if !self.select_message(id).await? {
+            return Ok(false);
+        }
+        if flags.is_empty() {
+            return Ok(true);
+        }
+
+        self.connection
+            .run_command(&format!(
+                "UID STORE {} {}FLAGS.SILENT ({})",
+                id.uid,
+                if add { "+" } else { "-" },
+                flags.join(" ")
+            ))
+            .await?;
+        Ok(true)
+    }
+
+    /// Copies a message into another mailbox, keeping the original.
+    /// Returns false if the message no longer exists.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn copy_message(
+        &mut self,
+        id: &ImapMessageId,
+        mailbox: &str,
+    ) -> anyhow::Result<bool> {
+        if !self.select_message(id).await? {
+            return Ok(false);
+        }
+
+        self.connection
+            .run_command(&format!("UID COPY {} {}", id.uid, quote(mailbox)))
+            .await?;
+        Ok(true)
+    }
+
+    /// Moves a message into another mailbox.
+    /// Returns false if the message no longer exists.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn move_message(
+        &mut self,
+        id: &ImapMessageId,
+        mailbox: &str,
+    ) -> anyhow::Result<bool> {
+        if !self.select_message(id).await? {
+            return Ok(false);
+        }
+
+        if self.has_capability("MOVE") {
+            self.connection
+                .run_command(&format!("UID MOVE {} {}", id.uid, quote(mailbox)))
+                .await?;
+            return Ok(true);
+        }
+
+        self.connection
+            .run_command(&format!("UID COPY {} {}", id.uid, quote(mailbox)))
+            .await?;
+        self.connection
+            .run_command(&format!("UID STORE {} +FLAGS.SILENT (\\Deleted)", id.uid))
+            .await?;
+        // a plain EXPUNGE would also remove every other message the user marked as deleted, so
+        // without UIDPLUS the original is only marked as deleted and hidden by clients
+        if self.has_capability("UIDPLUS") {
+            self.connection
+                .run_command(&format!("UID EXPUNGE {}", id.uid))
+                .await?;
+        }
+        Ok(true)
+    }
+
+    /// Waits until the server reports a change to the mailbox or the timeout passes.
+    /// Servers may drop idle connections after 30 minutes so the timeout should be lower.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn idle(&mut self, mailbox: &str, timeout: Duration) -> anyhow::Result<IdleEvent> {
+        anyhow::ensure!(
+            self.has_capability("IDLE"),
+            "imap server does not support IDLE"
+        );
+
+        self.select_cached(mailbox).await?;
+        let (tag, untagged) = self.connection.start_idle().await?;
+
+        let event = if untagged.iter().any(is_mailbox_update) {
+            IdleEvent::MailboxChanged
+        } else {
+            let wait_for_update = async {
+                loop {
+                    if is_mailbox_update(&self.connection.read_response().await?) {
+                        return anyhow::Ok(());
+                    }
+                }
+            };
+
+            match tokio::time::timeout(timeout, wait_for_update).await {
+                Ok(result) => {
+                    result?;
+                    IdleEvent::MailboxChanged
+                }
+                Err(_) => IdleEvent::Timeout,
+            }
+        };
+
+        self.connection.finish_idle(&tag).await?;
+        Ok(event)
+    }
+
+    /// Ends the session
+    pub async fn logout(mut self) -> anyhow::Result<()> {
+        // the server closes the connection after LOGOUT which may race the tagged response
+        if let Err(e) = self.connection.run_command("LOGOUT").await {
+            tracing::debug!(error=?e, "logout did not complete cleanly");
+        }
+        Ok(())
+    }
+
+    async fn fetch(
+        &mut self,
+        id: &ImapMessageId,
+        items: &str,
+    ) -> anyhow::Result<Vec<FetchedMessage>> {
+        let selected = self.select_cached(&id.mailbox).await?;
+        if selected.uid_validity != id.uid_validity {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .connection
+            .run_command(&format!("UID FETCH {} {items}", id.uid))
+            .await?;
+
+        Ok(fetched_messages(&selected, response)
+            .into_iter()
+            .filter(|message| message.id.uid == id.uid)
+            .collect())
+    }
+
+    /// Selects the mailbox of a message.
+    /// Returns false if the mailbox was recreated since the id was assigned.
+    async fn select_message(&mut self, id: &ImapMessageId) -> anyhow::Result<bool> {
+        let selected = self.select_cached(&id.mailbox).await?;
+        Ok(selected.uid_validity == id.uid_validity)
+    }
+
+    async fn search_uids(&mut self, criteria: &str) -> anyhow::Result<Vec<u32>> {
+        let response = self
+            .connection
+            .run_command(&format!("UID SEARCH {criteria}"))
+            .await?;
+
+        Ok(response
+            .untagged
+            .into_iter()
+            .filter_map(|response| match response {
+                Response::MailboxData(MailboxDatum::Search(uids)) => Some(uids),
+                _ => None,
+            })
+            .flatten()
+            .collect())
+    }
+
+    /// Selects a mailbox unless it is already selected
+    async fn select_cached(&mut self, mailbox: &str) -> anyhow::Result<SelectedMailbox> {
+        match &self.selected {
+            Some(selected) if selected.name == mailbox => Ok(selected.clone()),
+            _ => self.select(mailbox, None).await,
+        }
+    }
+
+    /// Selects a mailbox. When QRESYNC is enabled and the previous state is known the server
+    /// reports the messages that were expunged since then.
+    async fn select(
+        &mut self,
+        mailbox: &str,
+        previous: Option<&MailboxState>,
+    ) -> anyhow::Result<SelectedMailbox> {
+        let command = match previous {
+            Some(previous) if self.qresync_enabled => format!(
+                "SELECT {} (QRESYNC ({} {} {}))",
+                quote(mailbox),
+                previous.uid_validity,
+                previous.highest_modseq,
+                if previous.uids.is_empty() {
+                    "1:*".to_string()
+                } else {
+                    previous.uids.to_string()
+                }
+            ),
+            _ => format!("SELECT {} (CONDSTORE)", quote(mailbox)),
+        };
+
+        self.selected = None;
+        let response = self.connection.run_command(&command).await?;
+
+        let mut selected = SelectedMailbox {
+            name: mailbox.to_string(),
+            ..Default::default()
+        };
+        let mut uid_validity = None;
+        for response in &response.untagged {
+            match response {
+                Response::Data {
+                    code: Some(code), ..
+                } => match code {
+                    ResponseCode::UidValidity(value) => uid_validity = Some(*value),
+                    ResponseCode::UidNext(value) => selected.uid_next = Some(*value),
+                    ResponseCode::HighestModSeq(value) => selected.highest_modseq = Some(*value),
+                    _ => {}
+                },
+                Response::Vanished { uids, .. } => {
+                    selected
+                        .vanished
+                        .extend(uids.iter().flat_map(|range| range.clone()));
+                }
+                _ => {}
+            }
+        }
+        selected.uid_validity =
+            uid_validity.with_context(|| format!("no UIDVALIDITY for mailbox {mailbox}"))?;
+
+        self.selected = Some(selected.clone());
+        Ok(selected)
+    }
+}
+
+fn uid_next(selected: &SelectedMailbox, uids: &UidSet) -> u32 {
+    selected
+        .uid_next
+        .unwrap_or_else(|| uids.iter().max().map_or(1, |uid| uid + 1))
+}
+
+fn is_mailbox_update(response: &Response<'_>) -> bool {
+    matches!(
+        response,
+        Response::MailboxData(MailboxDatum::Exists(_))
+            | Response::Expunge(_)
+            | Response::Vanished { .. }
+            | Response::Fetch(..)
+    )
+}
+
+fn fetch_uid(attributes: &[AttributeValue<'_>]) -> Option<u32> {
+    attributes.iter().find_map(|attribute| match attribute {
+        AttributeValue::Uid(uid) => Some(*uid),
+        _ => None,
+    })
+}
+
+fn fetched_messages(selected: &SelectedMailbox, response: CommandResponse) -> Vec<FetchedMessage> {
+    response
+        .untagged
+        .into_iter()
+        .filter_map(|response| match response {
+            Response::Fetch(_, attributes) => {
+                let uid = fetch_uid(&attributes)?;
+                let mut message = FetchedMessage {
+                    id: ImapMessageId {
+                        mailbox: selected.name.clone(),
+                        uid_validity: selected.uid_validity,
+                        uid,
+                    },
+                    flags: Vec::new(),
+                    internal_date: None,
+                    size: None,
+                    body: Vec::new(),
+                };
+
+                for attribute in attributes {
+                    match attribute {
+                        AttributeValue::Flags(flags) => {
+                            message.flags =
+                                flags.into_iter().map(|flag| flag.into_owned()).collect()
+                        }
+                        AttributeValue::InternalDate(date) => {
+                            message.internal_date = Some(date.into_owned())
+                        }
+                        AttributeValue::Rfc822Size(size) => message.size = Some(size),
+                        AttributeValue::BodySection {
+                            data: Some(data), ..
+                        }
+                        | AttributeValue::Rfc822(Some(data)) => message.body = data.into_owned(),
+                        _ => {}
+                    }
+                }
+
+                Some(message)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Runs against the local server from docker-compose: `docker compose up mailserver`
+#[cfg(feature = "imap_test")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionSecurity, ImapClient, MailboxRole};
+
+    fn client(user: &str) -> ImapClient {
+        ImapClient::new(ImapConfig {
+            host: std::env::var("IMAP_TEST_HOST").unwrap_or_else(|_| "localhost".to_string()),
+            port: 3143,
+            security: ConnectionSecurity::None,
+            // every test gets its own mailbox so they can run in parallel
+            username: format!("{user}-{}", std::process::id()),
+            password: "pass".to_string(),
+        })
+    }
+
+    fn message(subject: &str) -> Vec<u8> {
+        format!(
+            "From: sender@example.com\r\nTo: test@example.com\r\nSubject: {subject}\r\nMessage-ID: <{subject}@example.com>\r\n\r\nHello\r\n"
+        )
+        .into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_sync_mailbox() -> anyhow::Result<()> {
+        let mut session = client("sync").connect().await?;
+
+        let mailboxes = session.list_mailboxes().await?;
+        let sent = mailboxes
+            .iter()
+            .find(|mailbox| mailbox.role == MailboxRole::Sent)
+            .context("no sent mailbox")?;
+        assert_eq!(sent.name, "Sent");
+
+        let initial = session.sync_mailbox("INBOX", None).await?;
+
+        let id = session
+            .append("INBOX", &[], &message("first"))
+            .await?
+            .context("server did not return APPENDUID")?;
+
+        let changes = session.sync_mailbox("INBOX", Some(&initial.state)).await?;
+        assert_eq!(changes.new, vec![id.clone()]);
+        assert!(changes.vanished.is_empty());
+
+        let fetched = session
+            .fetch_message(&id)
+            .await?
+            .context("message not found")?;
+        assert!(String::from_utf8_lossy(&fetched.body).contains("Subject: first"));
+        assert!(!fetched.flags.iter().any(|flag| flag == "\\Seen"));
+
+        session
+            .connection
+            .run_command(&format!("UID STORE {} +FLAGS (\\Seen)", id.uid))
+            .await?;
+        let flagged = session.sync_mailbox("INBOX", Some(&changes.state)).await?;
+        assert_eq!(flagged.changed, vec![id.clone()]);
+
+        session
+            .connection
+            .run_command(&format!("UID STORE {} +FLAGS (\\Deleted)", id.uid))
+            .await?;
+        session.connection.run_command("EXPUNGE").await?;
+        let expunged = session.sync_mailbox("INBOX", Some(&flagged.state)).await?;
+        assert_eq!(expunged.vanished, vec![id.clone()]);
+        assert!(!expunged.state.uids.contains(id.uid));
+        assert_eq!(session.fetch_message(&id).await?, None);
+
+        session.logout().await
+    }
+
+    #[tokio::test]
+    async fn test_store_and_move() -> anyhow::Result<()> {
+        let mut session = client("move").connect().await?;
+
+        let id = session
+            .append("INBOX", &[], &message("move"))
+            .await?
+            .context("server did not return APPENDUID")?;
+
+        assert!(
+            session
+                .store_flags(&id, true, &["\\Seen", "\\Flagged"])
+                .await?
+        );
+        assert!(session.store_flags(&id, false, &["\\Flagged"]).await?);
+        let flags = session
+            .fetch_flags(&id)
+            .await?
+            .context("message not found")?;
+        assert!(flags.iter().any(|flag| flag == "\\Seen"));
+        assert!(!flags.iter().any(|flag| flag == "\\Flagged"));
+
+        assert!(session.copy_message(&id, "Junk").await?);
+        assert_eq!(session.search("Junk", "ALL").await?.len(), 1);
+
+        let archive_before = session.search("Archive", "ALL").await?;
+        assert!(session.move_message(&id, "Archive").await?);
+        assert_eq!(session.fetch_message(&id).await?, None);
+        assert_eq!(
+            session.search("Archive", "ALL").await?.len(),
+            archive_before.len() + 1
+        );
+
+        session.logout().await
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_mailbox() -> anyhow::Result<()> {
+        let mut session = client("mailbox").connect().await?;
+
+        session.create_mailbox("Receipts").await?;
+        session.append("Receipts", &[], &message("receipt")).await?;
+        assert_eq!(session.search("Receipts", "ALL").await?.len(), 1);
+
+        session.delete_mailbox("Receipts").await?;
+        assert!(
+            !session
+                .list_mailboxes()
+                .await?
+                .iter()
+                .any(|mailbox| mailbox.name == "Receipts")
+        );
+
+        session.logout().await
+    }
+
+    #[tokio::test]
+    async fn test_idle() -> anyhow::Result<()> {
+        let mut session = client("idle").connect().await?;
+        assert_eq!(
+            session.idle("INBOX", Duration::from_millis(500)).await?,
+            IdleEvent::Timeout
+        );
+
+        let mut other = client("idle").connect().await?;
+        let idle = tokio::spawn(async move {
+            let event = session.idle("INBOX", Duration::from_secs(10)).await;
+            (session, event)
+        });
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        other.append("INBOX", &[], &message("idle")).await?;
+
+        let (session, event) = idle.await?;
+        assert_eq!(event?, IdleEvent::MailboxChanged);
+
+        other.logout().await?;
+        session.logout().await
+    }
+}
@@ -0,0 +1,97 @@
+use crate::ConnectionSecurity;
+use anyhow::Context;
+use lettre::address::Envelope;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Address, AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+/// The settings used to connect to an SMTP server
+#[derive(Clone)]
+pub struct SmtpConfig {
+    /// The hostname of the SMTP server
+    pub host: String,
+    /// The port of the SMTP server
+    pub port: u16,
+    /// How the connection to the server is secured
+    pub security: ConnectionSecurity,
+    /// The username used to log in
+    pub username: String,
+    /// The password used to log in. Usually an app specific password.
+    pub password: String,
+}
+
+impl std::fmt::Debug for SmtpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("security", &self.security)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sends already built messages through an SMTP server
+#[derive(Clone)]
+pub struct SmtpClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpClient {
+    pub fn new(config: SmtpConfig) -> anyhow::Result<Self> {
+        config.security.ensure_allowed()?;
+
+        let builder = match config.security {
+            ConnectionSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            ConnectionSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            ConnectionSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+
+        let transport = builder
+            .port(config.port)
+            .credentials(Credentials::new(config.username, config.password))
+            .build();
+
+        Ok(Self { transport })
+    }
+
+    /// Connects and logs in to the server without sending anything
+    #[tracing::instrument(skip(self), err)]
+    pub async fn test_connection(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.transport.test_connection().await?,
+            "smtp server did not accept the connection"
+        );
+        Ok(())
+    }
+
+    /// Sends a raw RFC 5322 message to the given recipients.
+    /// Bcc recipients must be included in the recipients but not in the message headers.
+    #[tracing::instrument(skip(self, message), err)]
+    pub async fn send_raw(
+        &self,
+        from: &str,
+        recipients: &[String],
+        message: &[u8],
+    ) -> anyhow::Result<()> {
+        let from: Address = from
+            .parse()
+            .with_context(|| format!("invalid sender address {from}"))?;
+        let recipients = recipients
+            .iter()
+            .map(|recipient| {
+                recipient
+                    .parse::<Address>()
+                    .with_context(|| format!("invalid recipient address {recipient}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let envelope = Envelope::new(Some(from), recipients)?;
+        self.transport.send_raw(&envelope, message).await?;
+
+        Ok(())
+    }
+}
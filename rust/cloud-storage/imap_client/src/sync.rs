@@ -0,0 +1,380 @@
+//! Incremental sync state.
+//! Messages are identified by their mailbox, UIDVALIDITY and UID. A mailbox is synced by
+//! comparing its current state against the state recorded at the end of the previous sync.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Identifies a message stored in a mailbox.
+/// The UID of a message is only unique within a mailbox for a given UIDVALIDITY.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImapMessageId {
+    pub mailbox: String,
+    pub uid_validity: u32,
+    pub uid: u32,
+}
+
+impl Display for ImapMessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the mailbox goes last as it may contain the separator
+        write!(f, "{}:{}:{}", self.uid_validity, self.uid, self.mailbox)
+    }
+}
+
+impl FromStr for ImapMessageId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let (Some(uid_validity), Some(uid), Some(mailbox)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("invalid imap message id {s}");
+        };
+
+        Ok(Self {
+            mailbox: mailbox.to_string(),
+            uid_validity: uid_validity.parse()?,
+            uid: uid.parse()?,
+        })
+    }
+}
+
+/// A set of UIDs. Serialized as an IMAP sequence set, e.g. `1:4,7,9:12`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct UidSet(BTreeSet<u32>);
+
+impl UidSet {
+    pub fn contains(&self, uid: u32) -> bool {
+        self.0.contains(&uid)
+    }
+
+    pub fn insert(&mut self, uid: u32) {
+        self.0.insert(uid);
+    }
+
+    pub fn remove(&mut self, uid: u32) {
+        self.0.remove(&uid);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<u32> for UidSet {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Display for UidSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut uids = self.0.iter().copied().peekable();
+        let mut first = true;
+        while let Some(start) = uids.next() {
+            let mut end = start;
+            while uids.peek() == Some(&(end + 1)) {
+                end += 1;
+                uids.next();
+            }
+
+            if !first {
+                write!(f, ",")?;
+            }
+            first = false;
+
+            if start == end {
+                write!(f, "{start}")?;
+            } else {
+                write!(f, "{start}:{end}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for UidSet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut uids = BTreeSet::new();
+        for range in s.split(',').filter(|range| !range.is_empty()) {
+            match range.split_once(':') {
+                Some((start, end)) => {
+                    let (start, end): (u32, u32) = (start.parse()?, end.parse()?);
+                    uids.extend(start.min(end)..=start.max(end));
+                }
+                None => {
+                    uids.insert(range.parse()?);
+                }
+            }
+        }
+        Ok(Self(uids))
+    }
+}
+
+impl From<UidSet> for String {
+    fn from(value: UidSet) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for UidSet {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// The state of a mailbox at the end of a sync
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailboxState {
+    pub uid_validity: u32,
+    /// Every change to a message increases the mod-sequence of the mailbox (RFC 7162)
+    pub highest_modseq: u64,
+    pub uid_next: u32,
+    /// The UIDs that were in the mailbox. Used to detect expunged messages on servers
+    /// without QRESYNC.
+    pub uids: UidSet,
+}
+
+/// The sync state of every synced mailbox.
+/// Stored as an opaque cursor in place of the history id used by Gmail.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub mailboxes: BTreeMap<String, MailboxState>,
+}
+
+impl SyncState {
+    pub fn to_cursor(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_cursor(cursor: &str) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(cursor)?)
+    }
+}
+
+/// The changes to a mailbox since the previous sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailboxChanges {
+    /// The state of the mailbox after the changes
+    pub state: MailboxState,
+    /// Messages that were added to the mailbox
+    pub new: Vec<ImapMessageId>,
+    /// Existing messages whose flags changed
+    pub changed: Vec<ImapMessageId>,
+    /// Messages that were expunged from the mailbox
+    pub vanished: Vec<ImapMessageId>,
+}
+
+impl MailboxChanges {
+    /// The changes for a mailbox whose UIDVALIDITY changed or that was not synced before.
+    /// Every message in the previous state is gone and every current message is new.
+    pub(crate) fn resync(
+        mailbox: &str,
+        previous: Option<&MailboxState>,
+        state: MailboxState,
+    ) -> Self {
+        let id = |uid_validity, uid| ImapMessageId {
+            mailbox: mailbox.to_string(),
+            uid_validity,
+            uid,
+        };
+
+        Self {
+            new: state
+                .uids
+                .iter()
+                .map(|uid| id(state.uid_validity, uid))
+                .collect(),
+            changed: Vec::new(),
+            vanished: previous
+                .map(|previous| {
+                    previous
+                        .uids
+                        .iter()
+                        .map(|uid| id(previous.uid_validity, uid))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            state,
+        }
+    }
+
+    /// Applies the changes reported by the server to the previous state of a mailbox
+    pub(crate) fn incremental(
+        mailbox: &str,
+        previous: &MailboxState,
+        highest_modseq: u64,
+        uid_next: u32,
+        changed_uids: impl IntoIterator<Item = u32>,
+        vanished_uids: impl IntoIterator<Item = u32>,
+    ) -> Self {
+        let id = |uid| ImapMessageId {
+            mailbox: mailbox.to_string(),
+            uid_validity: previous.uid_validity,
+            uid,
+        };
+
+        let mut uids = previous.uids.clone();
+
+        // messages that arrived and were expunged between syncs are skipped entirely
+        let expunged_uids: BTreeSet<u32> = vanished_uids.into_iter().collect();
+        let vanished_uids: BTreeSet<u32> = expunged_uids
+            .iter()
+            .copied()
+            .filter(|uid| previous.uids.contains(*uid))
+            .collect();
+        for uid in &vanished_uids {
+            uids.remove(*uid);
+        }
+
+        let mut new = Vec::new();
+        let mut changed = Vec::new();
+        for uid in changed_uids.into_iter().collect::<BTreeSet<_>>() {
+            if expunged_uids.contains(&uid) {
+                continue;
+            }
+            if previous.uids.contains(uid) {
+                changed.push(id(uid));
+            } else {
+                uids.insert(uid);
+                new.push(id(uid));
+            }
+        }
+
+        Self {
+            state: MailboxState {
+                uid_validity: previous.uid_validity,
+                highest_modseq,
+                uid_next,
+                uids,
+            },
+            new,
+            changed,
+            vanished: vanished_uids.into_iter().map(id).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uid_set_round_trip() -> anyhow::Result<()> {
+        let uids: UidSet = [1, 2, 3, 4, 7, 9, 10, 11, 12].into_iter().collect();
+        assert_eq!(uids.to_string(), "1:4,7,9:12");
+        assert_eq!("1:4,7,9:12".parse::<UidSet>()?, uids);
+        assert_eq!("".parse::<UidSet>()?, UidSet::default());
+        assert!("1:x".parse::<UidSet>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_message_id_round_trip() -> anyhow::Result<()> {
+        let id = ImapMessageId {
+            mailbox: "Projects:2025/Macro".to_string(),
+            uid_validity: 1700000000,
+            uid: 42,
+        };
+        assert_eq!(id.to_string(), "1700000000:42:Projects:2025/Macro");
+        assert_eq!(id.to_string().parse::<ImapMessageId>()?, id);
+        assert!("42:INBOX".parse::<ImapMessageId>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_state_cursor_round_trip() -> anyhow::Result<()> {
+        let state = SyncState {
+            mailboxes: BTreeMap::from([(
+                "INBOX".to_string(),
+                MailboxState {
+                    uid_validity: 1,
+                    highest_modseq: 20,
+                    uid_next: 6,
+                    uids: [1, 2, 3, 5].into_iter().collect(),
+                },
+            )]),
+        };
+
+        let cursor = state.to_cursor()?;
+        assert_eq!(
+            cursor,
+            r#"{"mailboxes":{"INBOX":{"uidValidity":1,"highestModseq":20,"uidNext":6,"uids":"1:3,5"}}}"#
+        );
+        assert_eq!(SyncState::from_cursor(&cursor)?, state);
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_changes() {
+        let previous = MailboxState {
+            uid_validity: 1,
+            highest_modseq: 20,
+            uid_next: 6,
+            uids: [1, 2, 3, 5].into_iter().collect(),
+        };
+
+        // 2 was flagged, 3 and the unknown 4 were expunged, 6 and 7 arrived and 7 was
+        // expunged again before the sync
+        let changes = MailboxChanges::incremental("INBOX", &previous, 30, 8, [2, 6, 7], [3, 4, 7]);
+
+        let uids = |ids: &[ImapMessageId]| ids.iter().map(|id| id.uid).collect::<Vec<_>>();
+        assert_eq!(uids(&changes.new), vec![6]);
+        assert_eq!(uids(&changes.changed), vec![2]);
+        assert_eq!(uids(&changes.vanished), vec![3]);
+        assert_eq!(changes.state.uids.to_string(), "1:2,5:6");
+        assert_eq!(changes.state.highest_modseq, 30);
+        assert_eq!(changes.state.uid_next, 8);
+    }
+
+    #[test]
+    fn test_resync_changes() {
+        let previous = MailboxState {
+            uid_validity: 1,
+            highest_modseq: 20,
+            uid_next: 3,
+            uids: [1, 2].into_iter().collect(),
+        };
+        let state = MailboxState {
+            uid_validity: 2,
+            highest_modseq: 1,
+            uid_next: 2,
+            uids: [1].into_iter().collect(),
+        };
+
+        let changes = MailboxChanges::resync("INBOX", Some(&previous), state);
+        assert_eq!(
+            changes
+                .new
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["2:1:INBOX"]
+        );
+        assert_eq!(
+            changes
+                .vanished
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["1:1:INBOX", "1:2:INBOX"]
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use super::LexicalClient;
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct MarkdownResponse {
+    data: String,
+}
+
+impl LexicalClient {
+    /// Gets the markdown text of a document.
+    /// Internal references such as mentions are rendered for readers outside of macro.
+    #[tracing::instrument(skip(self), err)]
+    pub async fn get_markdown(&self, document_id: &str) -> Result<String> {
+        let full_url = format!("{}/markdown/{}", self.url, document_id);
+        let response = self
+            .client
+            .get(&full_url)
+            .query(&[("target", "external")])
+            .send()
+            .await?;
+
+        let status_code = response.status();
+        if status_code != reqwest::StatusCode::OK {
+            let body: String = response.text().await?;
+            tracing::error!(
+                body=%body,
+                status=%status_code,
+                "unexpected response from lexical service while getting markdown"
+            );
+            return Err(anyhow::anyhow!(body));
+        }
+
+        let response: MarkdownResponse = response.json().await.context("unexpected response")?;
+        Ok(response.data)
+    }
+}
@@ -4,10 +4,12 @@ use anyhow::Context;
 
 use crate::MacroCache;
 
-/// Generates the rate limit key for channel creation for a given user by their email
-macro_rules! macro_rate_limit_channel_invite {
-    ($email:expr) => {
-        format!("rtl_channel_invite:{}", $email)
+pub use macro_redis::sliding_window::{SlidingWindow, SlidingWindowHit, WhenFull};
+
+/// Generates the sliding window rate limit key for a notification rate limit policy
+macro_rules! macro_rate_limit_notification {
+    ($key:expr) => {
+        format!("rtl_notification:{}", $key)
     };
 }
 
@@ -26,75 +28,23 @@ macro_rules! macro_rate_limit_channel_invite_ip {
     };
 }
 
-/// Generates the rate limit key for invite to team for a given email
-macro_rules! macro_rate_limit_invite_to_team {
-    ($email:expr) => {
-        format!("rtl_invite_to_team:{}", $email)
-    };
-}
-
 impl MacroCache {
-    /// Get the channel invite rate limit for a given email.
-    /// This is the number of times a given email has made a channel invite event during the keys
-    /// lifetime.
-    pub async fn get_channel_invite_rate_limit(&self, email: &str) -> anyhow::Result<Option<u64>> {
-        let normalized_email = email_validator::normalize_email(email)
-            .with_context(|| format!("unable to normalize email {}", email))?;
-
-        macro_redis::get::get_optional::<u64>(
-            &self.inner,
-            &macro_rate_limit_channel_invite!(normalized_email),
-        )
-        .await
-    }
-
-    /// Increments the channel invite rate limit for a given email.
-    /// This is the number of times a given email has made a channel invite event during the keys
-    /// lifetime.
-    pub async fn increment_channel_invite_rate_limit(
+    /// Records a notification against the sliding window rate limit of each of its policies in
+    /// one step. The window keys are the policies' keys. If any window rejects the notification,
+    /// it is not counted against any of them.
+    pub async fn hit_notification_rate_limits(
         &self,
-        email: &str,
-        expiry_seconds: i64,
-    ) -> anyhow::Result<()> {
-        let normalized_email = email_validator::normalize_email(email)
-            .with_context(|| format!("unable to normalize email {}", email))?;
-        macro_redis::incr::incr_with_expiry(
-            &self.inner,
-            &macro_rate_limit_channel_invite!(normalized_email),
-            expiry_seconds,
-        )
-        .await
-    }
-
-    /// Get the invite to team rate limit for a given email.
-    /// This is the number of times a given email has been invited to a team during the keys
-    /// lifetime.
-    pub async fn get_invite_to_team_rate_limit(&self, email: &str) -> anyhow::Result<Option<u64>> {
-        let normalized_email = email_validator::normalize_email(email)
-            .with_context(|| format!("unable to normalize email {}", email))?;
-        macro_redis::get::get_optional::<u64>(
-            &self.inner,
-            &macro_rate_limit_invite_to_team!(normalized_email),
-        )
-        .await
-    }
+        windows: &[SlidingWindow],
+    ) -> anyhow::Result<Vec<SlidingWindowHit>> {
+        let windows = windows
+            .iter()
+            .map(|window| SlidingWindow {
+                key: macro_rate_limit_notification!(window.key),
+                ..window.clone()
+            })
+            .collect::<Vec<_>>();
 
-    /// Increments the invite to team rate limit for a given email.
-    /// This is the number of times a given email has been invited to a team during the keys
-    /// lifetime.
-    pub async fn increment_invite_to_team_rate_limit(
-        &self,
-        email: &str,
-        expiry_seconds: i64,
-    ) -> anyhow::Result<()> {
-        let normalized_email = email_validator::normalize_email(email)
-            .with_context(|| format!("unable to normalize email {}", email))?;
-        macro_redis::incr::incr_with_expiry(
-            &self.inner,
-            &macro_rate_limit_invite_to_team!(normalized_email),
-            expiry_seconds,
-        )
-        .await
+        macro_redis::sliding_window::hit(&self.inner, &windows).await
     }
 
     /// Get the channel invite rate limit for a given ip.
@@ -0,0 +1,14 @@
+INSERT INTO public.export_jobs (id, user_id, project_id, document_id, format, status, claimed_at, attempts, created_at)
+VALUES
+    -- pending
+    ('00000000-0000-0000-0000-000000000001', 'macro|user@user.com', 'project-one', NULL, 'pdf', 'pending', NULL, 0, NOW() - INTERVAL '5 minutes'),
+    -- its worker stopped an hour ago
+    ('00000000-0000-0000-0000-000000000002', 'macro|user@user.com', 'project-two', NULL, NULL, 'in_progress', NOW() - INTERVAL '1 hour', 1, NOW() - INTERVAL '4 minutes'),
+    -- pending document export
+    ('00000000-0000-0000-0000-000000000003', 'macro|user@user.com', NULL, 'document-one', 'docx', 'pending', NULL, 0, NOW() - INTERVAL '3 minutes'),
+    -- still running
+    ('00000000-0000-0000-0000-000000000004', 'macro|user@user.com', 'project-three', NULL, NULL, 'in_progress', NOW() - INTERVAL '1 minute', 1, NOW() - INTERVAL '10 minutes'),
+    -- its worker stopped on the last attempt
+    ('00000000-0000-0000-0000-000000000005', 'macro|user@user.com', 'project-four', NULL, NULL, 'in_progress', NOW() - INTERVAL '1 hour', 3, NOW() - INTERVAL '20 minutes'),
+    -- finished
+    ('00000000-0000-0000-0000-000000000006', 'macro|user@user.com', 'project-five', NULL, NULL, 'completed', NULL, 1, NOW() - INTERVAL '30 minutes');
@@ -0,0 +1,57 @@
+INSERT INTO "Organization" ("id", "name") VALUES (1, 'test organization');
+
+INSERT INTO "User" ("id", "email", "name", "organizationId") VALUES
+('macro|user@user.com', 'user@user.com', 'User', 1),
+('macro|user2@user.com', 'user2@user.com', 'User2', 1),
+('macro|user3@user.com', 'user3@user.com', 'User3', NULL);
+
+INSERT INTO "RolesOnUsers" ("userId", "roleId") VALUES
+('macro|user@user.com', 'professional_subscriber');
+
+INSERT INTO "RolesOnOrganizations" ("organizationId", "roleId") VALUES
+(1, 'corporate');
+
+INSERT INTO team (id, name, owner_id)
+VALUES ('11111111-1111-1111-1111-111111111111', 'team1', 'macro|user@user.com');
+
+INSERT INTO team_user (user_id, team_id, team_role)
+VALUES ('macro|user@user.com', '11111111-1111-1111-1111-111111111111', 'owner'),
+       ('macro|user3@user.com', '11111111-1111-1111-1111-111111111111', 'member');
+
+INSERT INTO "Project" ("id", "name", "userId") VALUES
+('p1', 'project one', 'macro|user@user.com');
+
+INSERT INTO "Document" ("id", "name", "fileType", "owner", "projectId", "deletedAt") VALUES
+('d1', 'pdf one', 'pdf', 'macro|user@user.com', 'p1', NULL),
+('d2', 'pdf copy', 'pdf', 'macro|user@user.com', NULL, NULL),
+('d3', 'docx', 'docx', 'macro|user@user.com', 'p1', NULL),
+('d4', 'deleted', 'pdf', 'macro|user@user.com', NULL, '2024-01-01 00:00:00'),
+('d5', 'other pdf', 'pdf', 'macro|user2@user.com', NULL, NULL),
+('d6', 'unsized', 'md', 'macro|user3@user.com', NULL, NULL);
+
+INSERT INTO "DocumentInstance" ("id", "documentId", "sha") VALUES
+(1, 'd1', 'sha-pdf'),
+(2, 'd1', 'sha-pdf'),
+(3, 'd2', 'sha-pdf'),
+(4, 'd4', 'sha-deleted'),
+(5, 'd5', 'sha-pdf'),
+(6, 'd5', 'sha-other'),
+(7, 'd6', 'sha-md-2'),
+(8, 'd6', 'sha-md-1');
+
+INSERT INTO "DocumentBom" ("id", "documentId") VALUES
+(1, 'd3'),
+(2, 'd3');
+
+INSERT INTO "BomPart" ("documentBomId", "sha", "path") VALUES
+(1, 'sha-part-1', 'word/document.xml'),
+(1, 'sha-part-2', 'word/styles.xml'),
+(2, 'sha-part-1', 'word/document.xml'),
+(2, 'sha-part-3', 'word/numbering.xml');
+
+INSERT INTO "sha_size" (sha, size_bytes) VALUES
+('sha-pdf', 1000),
+('sha-part-1', 200),
+('sha-part-2', 30),
+('sha-deleted', 5000),
+('sha-other', 4);
@@ -0,0 +1,28 @@
+-- The size of every piece of content stored in the document storage bucket, keyed by sha.
+-- Both raw uploads ("DocumentInstance".sha) and docx bom parts ("BomPart".sha) are content
+-- addressed so storage usage can be deduplicated by sha.
+CREATE TABLE "sha_size"
+(
+    sha        TEXT        NOT NULL PRIMARY KEY,
+    size_bytes BIGINT      NOT NULL CHECK (size_bytes >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- The storage limit granted by a role.
+-- A user gets the most generous plan out of their own roles and the roles of their organization.
+CREATE TABLE "storage_plan"
+(
+    role_id        TEXT        NOT NULL PRIMARY KEY REFERENCES "Role" (id) ON DELETE CASCADE,
+    limit_bytes    BIGINT      NOT NULL CHECK (limit_bytes >= 0),
+    -- soft limits warn the user once reached, hard limits block new uploads
+    limit_behavior TEXT        NOT NULL CHECK (limit_behavior IN ('soft', 'hard')),
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO "storage_plan" (role_id, limit_bytes, limit_behavior)
+VALUES ('professional_subscriber', 107374182400, 'soft'), -- 100 GiB
+       ('team_subscriber', 107374182400, 'soft'),         -- 100 GiB
+       ('corporate', 1099511627776, 'soft')               -- 1 TiB
+ON CONFLICT DO NOTHING;
+
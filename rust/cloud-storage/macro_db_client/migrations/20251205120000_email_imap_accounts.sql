@@ -0,0 +1,20 @@
+-- links can be backed by any IMAP/SMTP server in addition to gmail
+ALTER TYPE email_user_provider_enum ADD VALUE IF NOT EXISTS 'IMAP';
+
+CREATE TYPE email_connection_security_enum AS ENUM ('TLS', 'STARTTLS', 'NONE');
+
+-- connection settings for IMAP links. the password lives in secrets manager.
+CREATE TABLE "email_imap_accounts"
+(
+    link_id            UUID    NOT NULL PRIMARY KEY REFERENCES email_links (id) ON DELETE CASCADE,
+    imap_host          TEXT    NOT NULL,
+    imap_port          INTEGER NOT NULL,
+    imap_security      email_connection_security_enum NOT NULL DEFAULT 'TLS',
+    smtp_host          TEXT    NOT NULL,
+    smtp_port          INTEGER NOT NULL,
+    smtp_security      email_connection_security_enum NOT NULL DEFAULT 'TLS',
+    username           TEXT    NOT NULL,
+    password_secret_id TEXT    NOT NULL,
+    created_at timestamp DEFAULT now() NOT NULL,
+    updated_at timestamp DEFAULT now() NOT NULL
+);
@@ -0,0 +1,20 @@
+-- user defined rules that are applied to incoming messages of a link
+CREATE TABLE "email_rules"
+(
+    id              UUID    NOT NULL PRIMARY KEY,
+    link_id         UUID    NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    name            TEXT    NOT NULL,
+    is_enabled      BOOLEAN NOT NULL DEFAULT TRUE,
+    -- rules are evaluated in ascending position
+    position        INTEGER NOT NULL DEFAULT 0,
+    -- whether every condition has to match, or any of them
+    match_all       BOOLEAN NOT NULL DEFAULT TRUE,
+    -- whether rules after this one are skipped for messages it matched
+    stop_processing BOOLEAN NOT NULL DEFAULT FALSE,
+    conditions      JSONB   NOT NULL,
+    actions         JSONB   NOT NULL,
+    created_at timestamp DEFAULT now() NOT NULL,
+    updated_at timestamp DEFAULT now() NOT NULL
+);
+
+CREATE INDEX email_rules_link_id_position_idx ON email_rules (link_id, position);
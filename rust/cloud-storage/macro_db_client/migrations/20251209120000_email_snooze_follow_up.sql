@@ -0,0 +1,17 @@
+-- snoozed threads are hidden from the inbox views until this time passes
+ALTER TABLE email_threads ADD COLUMN snoozed_until TIMESTAMPTZ;
+
+-- reminders to follow up on a sent thread if nobody replies to it in time
+CREATE TABLE "email_follow_up_reminders"
+(
+    id         UUID        NOT NULL PRIMARY KEY,
+    link_id    UUID        NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    thread_id  UUID        NOT NULL UNIQUE REFERENCES email_threads (id) ON DELETE CASCADE,
+    remind_at  TIMESTAMPTZ NOT NULL,
+    -- set once the reminder has been processed, whether or not a notification was sent
+    fired      BOOLEAN     NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_email_follow_up_reminders_remind_at_fired ON email_follow_up_reminders (remind_at, fired);
@@ -0,0 +1,16 @@
+-- how long sent messages wait in the outbox, during which they can still be cancelled or edited
+ALTER TABLE email_settings ADD COLUMN undo_send_seconds INTEGER NOT NULL DEFAULT 0;
+
+-- messages that were sent through the API but haven't been handed to the provider yet
+CREATE TABLE "email_outbox"
+(
+    message_id UUID        NOT NULL PRIMARY KEY REFERENCES email_messages (id) ON DELETE CASCADE,
+    link_id    UUID        NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    send_at    TIMESTAMPTZ NOT NULL,
+    -- set once the scheduled worker picked the message up, after which it can't be cancelled anymore
+    dispatched BOOLEAN     NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_email_outbox_send_at ON email_outbox (send_at);
@@ -0,0 +1,26 @@
+-- the List-Unsubscribe information of received mailing list messages
+CREATE TABLE "email_list_messages"
+(
+    message_id         UUID    NOT NULL PRIMARY KEY REFERENCES email_messages (id) ON DELETE CASCADE,
+    link_id            UUID    NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    -- the List-Id of the list, or the sender's address for lists without one
+    list_key           TEXT    NOT NULL,
+    list_id            TEXT,
+    unsubscribe_url    TEXT,
+    unsubscribe_mailto TEXT,
+    -- whether the sender supports RFC 8058 one-click unsubscribe
+    one_click          BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX email_list_messages_link_id_list_key_idx ON email_list_messages (link_id, list_key);
+
+-- mailing lists the user unsubscribed from
+CREATE TABLE "email_list_unsubscribes"
+(
+    link_id         UUID NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    list_key        TEXT NOT NULL,
+    -- the rule archiving mail from the list, if the user chose to archive it
+    archive_rule_id UUID REFERENCES email_rules (id) ON DELETE SET NULL,
+    unsubscribed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (link_id, list_key)
+);
@@ -0,0 +1,20 @@
+-- reusable templates and snippets of a link, optionally shared with a team the owner belongs to
+CREATE TABLE "email_templates"
+(
+    id         UUID NOT NULL PRIMARY KEY,
+    link_id    UUID NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    -- the team the template is shared with, members can use it but only the owner can edit it
+    team_id    UUID REFERENCES team (id) ON DELETE SET NULL,
+    name       TEXT NOT NULL,
+    -- snippets are inserted into an existing draft and have no subject
+    subject    TEXT,
+    body_html  TEXT NOT NULL,
+    body_text  TEXT,
+    -- custom variables the template declares, with their default values
+    variables  JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at timestamp DEFAULT now() NOT NULL,
+    updated_at timestamp DEFAULT now() NOT NULL
+);
+
+CREATE INDEX email_templates_link_id_idx ON email_templates (link_id);
+CREATE INDEX email_templates_team_id_idx ON email_templates (team_id) WHERE team_id IS NOT NULL;
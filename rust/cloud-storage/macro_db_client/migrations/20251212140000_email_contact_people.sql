@@ -0,0 +1,29 @@
+-- a person known under several contacts of a link, one contact per address
+CREATE TABLE "email_contact_people"
+(
+    id         UUID NOT NULL PRIMARY KEY,
+    link_id    UUID NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    name       character varying(255),
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE INDEX email_contact_people_link_id_idx ON email_contact_people (link_id);
+
+ALTER TABLE email_contacts
+    ADD COLUMN person_id UUID REFERENCES email_contact_people (id) ON DELETE SET NULL;
+
+CREATE INDEX email_contacts_person_id_idx ON email_contacts (person_id) WHERE person_id IS NOT NULL;
+
+-- pairs of contacts the user said are not the same person, so they are no longer suggested
+CREATE TABLE "email_contact_duplicate_dismissals"
+(
+    link_id      UUID NOT NULL REFERENCES email_links (id) ON DELETE CASCADE,
+    contact_id_a UUID NOT NULL REFERENCES email_contacts (id) ON DELETE CASCADE,
+    contact_id_b UUID NOT NULL REFERENCES email_contacts (id) ON DELETE CASCADE,
+    created_at   timestamp with time zone DEFAULT now() NOT NULL,
+    PRIMARY KEY (contact_id_a, contact_id_b),
+    CHECK (contact_id_a < contact_id_b)
+);
+
+CREATE INDEX email_contact_duplicate_dismissals_link_id_idx ON email_contact_duplicate_dismissals (link_id);
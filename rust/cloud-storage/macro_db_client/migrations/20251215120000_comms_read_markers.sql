@@ -0,0 +1,19 @@
+-- the last message a user read in a channel, or in a thread of it when thread_id is set.
+-- markers only move forward, messages after last_read_at are unread
+CREATE TABLE "comms_read_markers"
+(
+    id                   UUID                                   NOT NULL PRIMARY KEY,
+    user_id              text                                   NOT NULL,
+    channel_id           UUID                                   NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    thread_id            UUID REFERENCES comms_messages (id) ON DELETE CASCADE,
+    last_read_message_id UUID                                   NOT NULL,
+    last_read_at         timestamp with time zone               NOT NULL,
+    created_at           timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at           timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE UNIQUE INDEX comms_read_markers_user_channel_idx
+    ON comms_read_markers (user_id, channel_id) WHERE thread_id IS NULL;
+
+CREATE UNIQUE INDEX comms_read_markers_user_thread_idx
+    ON comms_read_markers (user_id, thread_id) WHERE thread_id IS NOT NULL;
@@ -0,0 +1,54 @@
+-- prior versions of edited channel messages, removed when the message is deleted
+CREATE TABLE "comms_message_edits"
+(
+    id          UUID                                   NOT NULL PRIMARY KEY,
+    message_id  UUID                                   NOT NULL REFERENCES comms_messages (id) ON DELETE CASCADE,
+    content     text                                   NOT NULL,
+    -- when the version was written, and when an edit replaced it
+    written_at  timestamp with time zone               NOT NULL,
+    replaced_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE INDEX comms_message_edits_message_id_idx ON comms_message_edits (message_id, replaced_at);
+
+-- deleted messages are kept as tombstones, recording who removed them
+ALTER TABLE comms_messages
+    ADD COLUMN deleted_by       text,
+    ADD COLUMN pinned_at        timestamp with time zone,
+    ADD COLUMN pinned_by        text,
+    ADD COLUMN thread_locked_at timestamp with time zone,
+    ADD COLUMN thread_locked_by text;
+
+CREATE INDEX comms_messages_pinned_idx ON comms_messages (channel_id, pinned_at DESC) WHERE pinned_at IS NOT NULL;
+
+-- the least time between messages of a member, 0 turns slow mode off
+ALTER TABLE comms_channels
+    ADD COLUMN slow_mode_seconds integer DEFAULT 0 NOT NULL CHECK (slow_mode_seconds BETWEEN 0 AND 21600);
+
+CREATE TYPE comms_moderation_action AS ENUM (
+    'delete_message',
+    'pin_message',
+    'unpin_message',
+    'lock_thread',
+    'unlock_thread',
+    'set_slow_mode'
+);
+
+-- moderation by channel admins, reviewed by the admins of the channel's organization
+CREATE TABLE "comms_moderation_actions"
+(
+    id                UUID                                   NOT NULL PRIMARY KEY,
+    channel_id        UUID                                   NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    org_id            bigint,
+    actor_id          text                                   NOT NULL,
+    action            comms_moderation_action                NOT NULL,
+    target_message_id UUID REFERENCES comms_messages (id) ON DELETE SET NULL,
+    -- the sender of the target message
+    target_user_id    text,
+    -- the content of a deleted message, or the new slow mode
+    details           jsonb,
+    created_at        timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE INDEX comms_moderation_actions_org_idx ON comms_moderation_actions (org_id, created_at DESC) WHERE org_id IS NOT NULL;
+CREATE INDEX comms_moderation_actions_channel_idx ON comms_moderation_actions (channel_id, created_at DESC);
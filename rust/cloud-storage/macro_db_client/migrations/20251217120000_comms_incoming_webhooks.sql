@@ -0,0 +1,21 @@
+-- incoming webhooks let external services post to a channel as a bot, the bot's sender id is
+-- 'bot|<webhook id>'
+CREATE TABLE "comms_incoming_webhooks"
+(
+    id                UUID                                   NOT NULL PRIMARY KEY,
+    channel_id        UUID                                   NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    name              text                                   NOT NULL,
+    avatar_url        text,
+    -- sha256 of the secret token, the token itself is only shown when the webhook is created
+    token_hash        text                                   NOT NULL,
+    created_by        text                                   NOT NULL,
+    created_at        timestamp with time zone DEFAULT now() NOT NULL,
+    last_used_at      timestamp with time zone,
+    revoked_at        timestamp with time zone,
+    revoked_by        text,
+    -- fixed window rate limit of posts
+    window_started_at timestamp with time zone,
+    window_count      integer                  DEFAULT 0     NOT NULL
+);
+
+CREATE INDEX comms_incoming_webhooks_channel_id_idx ON comms_incoming_webhooks (channel_id);
@@ -0,0 +1,38 @@
+-- outgoing webhooks deliver signed channel events to external bots
+CREATE TABLE "comms_outgoing_webhooks"
+(
+    id                   UUID                                   NOT NULL PRIMARY KEY,
+    channel_id           UUID                                   NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    url                  text                                   NOT NULL,
+    -- kept to sign deliveries, the receiver checks the signature with its copy
+    secret               text                                   NOT NULL,
+    events               text[]                                 NOT NULL CHECK (
+        cardinality(events) > 0
+            AND events <@ ARRAY ['message_posted', 'reaction_added', 'member_joined']::text[]
+        ),
+    created_by           text                                   NOT NULL,
+    created_at           timestamp with time zone DEFAULT now() NOT NULL,
+    revoked_at           timestamp with time zone,
+    revoked_by           text,
+    last_delivered_at    timestamp with time zone,
+    last_failed_at       timestamp with time zone,
+    last_error           text,
+    consecutive_failures integer                  DEFAULT 0     NOT NULL
+);
+
+CREATE INDEX comms_outgoing_webhooks_channel_id_idx ON comms_outgoing_webhooks (channel_id) WHERE revoked_at IS NULL;
+
+-- reminders set with the /remind command, posted to their channel once due
+CREATE TABLE "comms_reminders"
+(
+    id           UUID                                   NOT NULL PRIMARY KEY,
+    channel_id   UUID                                   NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    thread_id    UUID REFERENCES comms_messages (id) ON DELETE SET NULL,
+    user_id      text                                   NOT NULL,
+    content      text                                   NOT NULL,
+    remind_at    timestamp with time zone               NOT NULL,
+    created_at   timestamp with time zone DEFAULT now() NOT NULL,
+    delivered_at timestamp with time zone
+);
+
+CREATE INDEX comms_reminders_due_idx ON comms_reminders (remind_at) WHERE delivered_at IS NULL;
@@ -0,0 +1,7 @@
+-- archived channels are read-only and hidden from the channel list, but stay searchable
+ALTER TABLE comms_channels
+    ADD COLUMN archived_at timestamp with time zone,
+    ADD COLUMN archived_by text;
+
+ALTER TYPE comms_moderation_action ADD VALUE 'archive_channel';
+ALTER TYPE comms_moderation_action ADD VALUE 'unarchive_channel';
@@ -0,0 +1,4 @@
+-- when the scheduled worker claimed the message for sending. a message is only claimed again
+-- once the claim has been released after a failed send, or has gone stale because the worker
+-- sending it stopped, so a message isn't sent twice
+ALTER TABLE email_outbox ADD COLUMN claimed_at TIMESTAMPTZ;
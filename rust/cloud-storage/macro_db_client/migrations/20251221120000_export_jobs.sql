@@ -0,0 +1,28 @@
+-- exports that run in the background. a job exports either a project into a zip archive or a
+-- single document that has to go through the convert service
+CREATE TABLE "export_jobs"
+(
+    id                  UUID                                   NOT NULL PRIMARY KEY,
+    user_id             text                                   NOT NULL,
+    project_id          text,
+    document_id         text,
+    -- the format markdown documents are exported to
+    format              text,
+    status              text                     DEFAULT 'pending' NOT NULL CHECK (
+        status IN ('pending', 'in_progress', 'completed', 'failed')
+        ),
+    processed           integer                  DEFAULT 0     NOT NULL,
+    total               integer                  DEFAULT 0     NOT NULL,
+    -- the temp file key of the finished export
+    result_key          text,
+    failed_document_ids text[]                   DEFAULT '{}'  NOT NULL,
+    -- when a worker claimed the job. the claim is refreshed as the export makes progress, so a
+    -- stale claim means the worker stopped and the job can be picked up again
+    claimed_at          timestamp with time zone,
+    attempts            integer                  DEFAULT 0     NOT NULL,
+    created_at          timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at          timestamp with time zone DEFAULT now() NOT NULL,
+    CHECK ((project_id IS NULL) <> (document_id IS NULL))
+);
+
+CREATE INDEX export_jobs_unfinished_idx ON export_jobs (created_at) WHERE status IN ('pending', 'in_progress');
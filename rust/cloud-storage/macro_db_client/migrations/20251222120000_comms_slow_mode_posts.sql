@@ -0,0 +1,17 @@
+-- when each member last posted to a channel in slow mode. a post claims the row with a
+-- conditional upsert, so two posts racing each other can't both get through
+CREATE TABLE "comms_slow_mode_posts"
+(
+    channel_id     UUID                     NOT NULL REFERENCES comms_channels (id) ON DELETE CASCADE,
+    user_id        text                     NOT NULL,
+    last_posted_at timestamp with time zone NOT NULL,
+    PRIMARY KEY (channel_id, user_id)
+);
+
+-- the last posts of members already in slow mode
+INSERT INTO comms_slow_mode_posts (channel_id, user_id, last_posted_at)
+SELECT m.channel_id, m.sender_id, MAX(m.created_at)
+FROM comms_messages m
+JOIN comms_channels c ON c.id = m.channel_id
+WHERE c.slow_mode_seconds > 0
+GROUP BY m.channel_id, m.sender_id;
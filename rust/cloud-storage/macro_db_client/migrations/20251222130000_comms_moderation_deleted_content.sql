@@ -0,0 +1,5 @@
+-- deleted messages were recorded with their content, which has to go with the message. only the
+-- id of the deleted message is kept
+UPDATE comms_moderation_actions
+SET details = jsonb_build_object('message_id', target_message_id)
+WHERE action = 'delete_message';
@@ -313,6 +313,7 @@ pub async fn get_document(
 }
 
 /// Gets the metadata for a provided document id and version id.
+/// Fails with [sqlx::Error::RowNotFound] if the document or the version does not exist.
 pub async fn get_document_version(
     db: &Pool<Postgres>,
     document_id: &str,
@@ -392,6 +393,8 @@ pub async fn get_document_version(
         ) p ON d."projectId" IS NOT NULL
         WHERE
             d.id = $1
+        AND
+            COALESCE(di.id, db.id) IS NOT NULL
         LIMIT 1
     "#,
         document_id,
@@ -591,6 +594,15 @@ mod tests {
         assert_eq!(document_metadata.owner, "macro|user@user.com".to_string());
     }
 
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("basic_user_with_documents")))]
+    async fn test_get_document_version_missing_version(pool: Pool<Postgres>) {
+        let missing_version = get_document_version(&pool, "document-four", 9999).await;
+        assert!(matches!(
+            missing_version.unwrap_err().downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::RowNotFound)
+        ));
+    }
+
     #[sqlx::test(fixtures(path = "../../fixtures", scripts("basic_user_with_documents")))]
     async fn test_get_document_sha(pool: Pool<Postgres>) {
         // document doesn't exist
@@ -0,0 +1,355 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+/// How long a claimed export job is left to the worker that claimed it. The claim is refreshed as
+/// the export makes progress, so it only runs out if the worker stopped.
+pub const EXPORT_JOB_CLAIM_LEASE_SECONDS: i32 = 15 * 60;
+
+/// How many times an export job is claimed before it is given up on
+pub const MAX_EXPORT_JOB_ATTEMPTS: i32 = 3;
+
+/// An export job claimed by a worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportJob {
+    pub id: Uuid,
+    pub user_id: String,
+    /// Set when the job exports a project
+    pub project_id: Option<String>,
+    /// Set when the job exports a single document
+    pub document_id: Option<String>,
+    /// The format markdown documents are exported to
+    pub format: Option<String>,
+    /// The attempt the job was claimed for. Each claim increments it, so it identifies the claim
+    /// when the job is updated.
+    pub attempts: i32,
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_project_export_job(
+    db: &Pool<Postgres>,
+    id: Uuid,
+    user_id: &str,
+    project_id: &str,
+    format: Option<&str>,
+    total: i32,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO export_jobs (id, user_id, project_id, format, total)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        id,
+        user_id,
+        project_id,
+        format,
+        total,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(db))]
+pub async fn create_document_export_job(
+    db: &Pool<Postgres>,
+    id: Uuid,
+    user_id: &str,
+    document_id: &str,
+    format: &str,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO export_jobs (id, user_id, document_id, format, total)
+        VALUES ($1, $2, $3, $4, 1)
+        "#,
+        id,
+        user_id,
+        document_id,
+        format,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Claims the oldest export job that is waiting to run, or whose worker stopped before it
+/// finished. Returns None if there is no job to run.
+#[tracing::instrument(skip(db))]
+pub async fn claim_export_job(db: &Pool<Postgres>) -> anyhow::Result<Option<ExportJob>> {
+    let job = sqlx::query_as!(
+        ExportJob,
+        r#"
+        WITH next AS (
+            SELECT id
+            FROM export_jobs
+            WHERE status IN ('pending', 'in_progress')
+                AND (claimed_at IS NULL OR claimed_at < NOW() - $1::INT * INTERVAL '1 second')
+                AND attempts < $2
+            ORDER BY created_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        UPDATE export_jobs
+        SET
+            status = 'in_progress',
+            claimed_at = NOW(),
+            attempts = export_jobs.attempts + 1,
+            updated_at = NOW()
+        FROM next
+        WHERE export_jobs.id = next.id
+        RETURNING export_jobs.id, export_jobs.user_id, export_jobs.project_id,
+            export_jobs.document_id, export_jobs.format, export_jobs.attempts
+        "#,
+        EXPORT_JOB_CLAIM_LEASE_SECONDS,
+        MAX_EXPORT_JOB_ATTEMPTS,
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(job)
+}
+
+/// Records the progress of a claimed export job and refreshes its claim.
+/// Returns an error if the job was claimed again since.
+#[tracing::instrument(skip(db))]
+pub async fn update_export_job_progress(
+    db: &Pool<Postgres>,
+    id: Uuid,
+    attempts: i32,
+    processed: i32,
+    total: i32,
+) -> anyhow::Result<()> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE export_jobs
+        SET processed = $3, total = $4, claimed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND attempts = $2 AND status = 'in_progress'
+        "#,
+        id,
+        attempts,
+        processed,
+        total,
+    )
+    .execute(db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("export job is no longer claimed");
+    }
+
+    Ok(())
+}
+
+/// Completes a claimed export job.
+/// Returns an error if the job was claimed again since.
+#[tracing::instrument(skip(db))]
+pub async fn complete_export_job(
+    db: &Pool<Postgres>,
+    id: Uuid,
+    attempts: i32,
+    result_key: &str,
+    failed_document_ids: &[String],
+) -> anyhow::Result<()> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE export_jobs
+        SET
+            status = 'completed',
+            processed = total,
+            result_key = $3,
+            failed_document_ids = $4,
+            claimed_at = NULL,
+            updated_at = NOW()
+        WHERE id = $1 AND attempts = $2 AND status = 'in_progress'
+        "#,
+        id,
+        attempts,
+        result_key,
+        failed_document_ids,
+    )
+    .execute(db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        anyhow::bail!("export job is no longer claimed");
+    }
+
+    Ok(())
+}
+
+/// Fails a claimed export job. Returns false if the job was claimed again since, in which case it
+/// is left to its new worker.
+#[tracing::instrument(skip(db))]
+pub async fn fail_export_job(db: &Pool<Postgres>, id: Uuid, attempts: i32) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE export_jobs
+        SET status = 'failed', claimed_at = NULL, updated_at = NOW()
+        WHERE id = $1 AND attempts = $2 AND status = 'in_progress'
+        "#,
+        id,
+        attempts,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Fails the export jobs whose worker stopped on their last attempt, so they are not left in
+/// progress forever. Returns the failed jobs.
+#[tracing::instrument(skip(db))]
+pub async fn fail_abandoned_export_jobs(db: &Pool<Postgres>) -> anyhow::Result<Vec<ExportJob>> {
+    let jobs = sqlx::query_as!(
+        ExportJob,
+        r#"
+        UPDATE export_jobs
+        SET status = 'failed', claimed_at = NULL, updated_at = NOW()
+        WHERE status = 'in_progress'
+            AND claimed_at < NOW() - $1::INT * INTERVAL '1 second'
+            AND attempts >= $2
+        RETURNING id, user_id, project_id, document_id, format, attempts
+        "#,
+        EXPORT_JOB_CLAIM_LEASE_SECONDS,
+        MAX_EXPORT_JOB_ATTEMPTS,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(jobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job_id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("export_jobs")))]
+    async fn test_claim_export_job(pool: Pool<Postgres>) {
+        // The oldest pending job is claimed first
+        let job = claim_export_job(&pool).await.unwrap().unwrap();
+        assert_eq!(job.id, job_id(1));
+        assert_eq!(job.project_id.as_deref(), Some("project-one"));
+        assert_eq!(job.format.as_deref(), Some("pdf"));
+
+        // Then the job whose worker stopped
+        let job = claim_export_job(&pool).await.unwrap().unwrap();
+        assert_eq!(job.id, job_id(2));
+
+        // Then the document export. The job with a fresh claim, the job that ran out of attempts
+        // and the finished job are left alone.
+        let job = claim_export_job(&pool).await.unwrap().unwrap();
+        assert_eq!(job.id, job_id(3));
+        assert_eq!(job.document_id.as_deref(), Some("document-one"));
+
+        assert_eq!(claim_export_job(&pool).await.unwrap(), None);
+
+        let attempts = sqlx::query_scalar!(
+            r#"SELECT attempts FROM export_jobs WHERE id = $1"#,
+            job_id(2)
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(attempts, 2);
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("export_jobs")))]
+    async fn test_complete_export_job(pool: Pool<Postgres>) {
+        let job = claim_export_job(&pool).await.unwrap().unwrap();
+        update_export_job_progress(&pool, job.id, job.attempts, 10, 20)
+            .await
+            .unwrap();
+        complete_export_job(
+            &pool,
+            job.id,
+            job.attempts,
+            "temp_files/export/a.zip",
+            &["doc".to_string()],
+        )
+        .await
+        .unwrap();
+
+        let result = sqlx::query!(
+            r#"
+            SELECT status, processed, total, result_key, failed_document_ids, claimed_at
+            FROM export_jobs WHERE id = $1
+            "#,
+            job.id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(result.status, "completed");
+        assert_eq!(result.processed, 20);
+        assert_eq!(result.total, 20);
+        assert_eq!(
+            result.result_key.as_deref(),
+            Some("temp_files/export/a.zip")
+        );
+        assert_eq!(result.failed_document_ids, vec!["doc".to_string()]);
+        assert_eq!(result.claimed_at, None);
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("export_jobs")))]
+    async fn test_stale_claim(pool: Pool<Postgres>) {
+        // The job whose worker stopped is claimed again, so its first worker can no longer
+        // update it
+        claim_export_job(&pool).await.unwrap().unwrap();
+        let job = claim_export_job(&pool).await.unwrap().unwrap();
+        assert_eq!(job.id, job_id(2));
+        let stale_attempts = job.attempts - 1;
+
+        assert!(
+            update_export_job_progress(&pool, job.id, stale_attempts, 10, 20)
+                .await
+                .is_err()
+        );
+        assert!(
+            complete_export_job(
+                &pool,
+                job.id,
+                stale_attempts,
+                "temp_files/export/a.zip",
+                &[]
+            )
+            .await
+            .is_err()
+        );
+        assert!(
+            !fail_export_job(&pool, job.id, stale_attempts)
+                .await
+                .unwrap()
+        );
+
+        let status = sqlx::query_scalar!(r#"SELECT status FROM export_jobs WHERE id = $1"#, job.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "in_progress");
+
+        // The current claim can still fail it
+        assert!(fail_export_job(&pool, job.id, job.attempts).await.unwrap());
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("export_jobs")))]
+    async fn test_fail_abandoned_export_jobs(pool: Pool<Postgres>) {
+        let jobs = fail_abandoned_export_jobs(&pool).await.unwrap();
+        assert_eq!(
+            jobs.into_iter().map(|job| job.id).collect::<Vec<_>>(),
+            vec![job_id(5)]
+        );
+
+        let status =
+            sqlx::query_scalar!(r#"SELECT status FROM export_jobs WHERE id = $1"#, job_id(5))
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "failed");
+    }
+}
@@ -1 +1,2 @@
+pub mod export_job;
 pub mod upload_job;
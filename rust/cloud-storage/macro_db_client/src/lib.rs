@@ -30,6 +30,7 @@ pub mod pins;
 pub mod projects;
 pub mod recents;
 pub mod share_permission;
+pub mod storage_usage;
 #[cfg(feature = "team")]
 pub mod team;
 pub mod user;
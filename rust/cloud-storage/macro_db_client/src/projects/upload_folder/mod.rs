@@ -305,6 +305,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha123".to_string(),
             relative_path: "/Root Test Folder".to_string(),
+            size_bytes: None,
         };
 
         let root_file2 = FolderItem {
@@ -313,6 +314,7 @@ mod tests {
             file_type: Some(FileType::Docx),
             sha: "sha456".to_string(),
             relative_path: "/Root Test Folder".to_string(),
+            size_bytes: None,
         };
 
         // Create a subfolder with its own files
@@ -322,6 +324,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             sha: "sha789".to_string(),
             relative_path: "/Root Test Folder/subfolder1".to_string(),
+            size_bytes: None,
         };
 
         let subfolder1_file2 = FolderItem {
@@ -330,6 +333,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha101".to_string(),
             relative_path: "/Root Test Folder/subfolder1".to_string(),
+            size_bytes: None,
         };
 
         // Create a sub-subfolder with its own files
@@ -339,6 +343,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha202".to_string(),
             relative_path: "/Root Test Folder/subfolder1/nested".to_string(),
+            size_bytes: None,
         };
 
         let mut subsubfolder_content = HashMap::new();
@@ -354,6 +359,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             sha: "sha303".to_string(),
             relative_path: "/Root Test Folder/subfolder2".to_string(),
+            size_bytes: None,
         };
 
         // ... rest of the structure creation remains the same ...
@@ -699,6 +705,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha_deepest".to_string(),
             relative_path: "/Deep Nested Structure/level1/level2/level3/level4/level5".to_string(),
+            size_bytes: None,
         };
 
         current_folder.insert(
@@ -730,6 +737,7 @@ mod tests {
                 file_type: Some(FileType::Txt),
                 sha: format!("sha_level{}", level),
                 relative_path: current_path.clone(),
+                size_bytes: None,
             };
 
             if level == 5 {
@@ -773,6 +781,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             sha: "sha_root".to_string(),
             relative_path: "/Deep Nested Structure".to_string(),
+            size_bytes: None,
         };
 
         root_content.insert("root_file.txt".to_string(), FileSystemNode::File(root_file));
@@ -891,6 +900,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha_root_doc".to_string(),
             relative_path: "/Test FileSystem Structure".to_string(),
+            size_bytes: None,
         };
 
         // Create a subfolder with a file
@@ -900,6 +910,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             sha: "sha_subfolder_doc".to_string(),
             relative_path: "/Test FileSystem Structure/subfolder".to_string(),
+            size_bytes: None,
         };
 
         // Build the subfolder
@@ -1203,6 +1214,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             sha: "sha_report_file".to_string(),
             relative_path: "/Conflicting Names Test".to_string(),
+            size_bytes: None,
         };
 
         // Create a folder called "Report" with a file inside
@@ -1212,6 +1224,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             sha: "sha_report_folder_contents".to_string(),
             relative_path: "/Conflicting Names Test/Report".to_string(),
+            size_bytes: None,
         };
 
         // Build the folder structure
@@ -0,0 +1,433 @@
+use macro_user_id::{lowercased::Lowercase, user_id::MacroUserId};
+use user_quota::storage::{
+    FileTypeStorageUsage, OrganizationStorageUsage, ProjectStorageUsage, StoragePlan, StorageQuota,
+    TeamStorageUsage,
+};
+
+#[cfg(test)]
+mod test;
+
+/// A document instance whose content has not been sized yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsizedDocumentInstance {
+    /// The id of the document
+    pub document_id: String,
+    /// The owner of the document
+    pub owner: String,
+    /// The id of the first document instance that stored the sha
+    pub document_version_id: i64,
+    /// The file type of the document
+    pub file_type: Option<String>,
+    /// The sha of the content
+    pub sha: String,
+}
+
+/// Records the size of stored content.
+/// The content of a sha never changes so shas that are already sized are left as is.
+#[tracing::instrument(skip(db, sha_sizes))]
+pub async fn upsert_sha_sizes(
+    db: &sqlx::PgPool,
+    sha_sizes: &[(String, i64)],
+) -> anyhow::Result<()> {
+    if sha_sizes.is_empty() {
+        return Ok(());
+    }
+
+    let (shas, sizes): (Vec<String>, Vec<i64>) = sha_sizes.iter().cloned().unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO "sha_size" (sha, size_bytes)
+        SELECT * FROM UNNEST($1::TEXT[], $2::BIGINT[])
+        ON CONFLICT (sha) DO NOTHING
+        "#,
+        &shas,
+        &sizes,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the document instances of a user that have not been sized yet.
+/// Only the first instance of every sha is returned as that is the one that was uploaded.
+/// Docx documents are excluded as their bom parts are sized when they are unzipped.
+#[tracing::instrument(skip(db))]
+pub async fn get_unsized_document_instances(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+    limit: i64,
+) -> anyhow::Result<Vec<UnsizedDocumentInstance>> {
+    let instances = sqlx::query_as!(
+        UnsizedDocumentInstance,
+        r#"
+        SELECT DISTINCT ON (di.sha)
+            d.id AS "document_id",
+            d.owner AS "owner",
+            di.id AS "document_version_id",
+            d."fileType" AS "file_type",
+            di.sha AS "sha"
+        FROM "DocumentInstance" di
+        JOIN "Document" d ON d.id = di."documentId"
+        LEFT JOIN "sha_size" s ON s.sha = di.sha
+        WHERE d.owner = $1
+            AND d."deletedAt" IS NULL
+            AND d."fileType" IS DISTINCT FROM 'docx'
+            AND s.sha IS NULL
+        ORDER BY di.sha, di.id ASC
+        LIMIT $2
+        "#,
+        user_id.as_ref(),
+        limit,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(instances)
+}
+
+/// Records the size of an uploaded document instance once it is stored.
+/// The size is only recorded if the instance belongs to the document and the stored file has
+/// the file type of the document, so files stored under the same key prefix (e.g. conversions)
+/// are not mistaken for the upload.
+#[tracing::instrument(skip(db))]
+pub async fn record_document_instance_size(
+    db: &sqlx::PgPool,
+    document_id: &str,
+    document_version_id: i64,
+    file_type: Option<&str>,
+    size_bytes: i64,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO "sha_size" (sha, size_bytes)
+        SELECT di.sha, $4
+        FROM "DocumentInstance" di
+        JOIN "Document" d ON d.id = di."documentId"
+        WHERE di.id = $2
+            AND di."documentId" = $1
+            AND d."fileType" IS NOT DISTINCT FROM $3
+        ON CONFLICT (sha) DO NOTHING
+        "#,
+        document_id,
+        document_version_id,
+        file_type,
+        size_bytes,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Gets the document instances of every user that have not been sized yet, ordered by sha.
+/// Only shas after `after_sha` are returned so the instances can be paged through even when
+/// some of them can't be sized.
+/// Docx documents are excluded as their content is stored in bom parts.
+#[tracing::instrument(skip(db))]
+pub async fn get_all_unsized_document_instances(
+    db: &sqlx::PgPool,
+    after_sha: &str,
+    limit: i64,
+) -> anyhow::Result<Vec<UnsizedDocumentInstance>> {
+    let instances = sqlx::query_as!(
+        UnsizedDocumentInstance,
+        r#"
+        SELECT DISTINCT ON (di.sha)
+            d.id AS "document_id",
+            d.owner AS "owner",
+            di.id AS "document_version_id",
+            d."fileType" AS "file_type",
+            di.sha AS "sha"
+        FROM "DocumentInstance" di
+        JOIN "Document" d ON d.id = di."documentId"
+        LEFT JOIN "sha_size" s ON s.sha = di.sha
+        WHERE di.sha > $1
+            AND d."deletedAt" IS NULL
+            AND d."fileType" IS DISTINCT FROM 'docx'
+            AND s.sha IS NULL
+        ORDER BY di.sha, di.id ASC
+        LIMIT $2
+        "#,
+        after_sha,
+        limit,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(instances)
+}
+
+/// Gets the shas of bom parts that have not been sized yet, ordered by sha.
+/// Only shas after `after_sha` are returned so the bom parts can be paged through even when
+/// some of them can't be sized.
+#[tracing::instrument(skip(db))]
+pub async fn get_unsized_bom_part_shas(
+    db: &sqlx::PgPool,
+    after_sha: &str,
+    limit: i64,
+) -> anyhow::Result<Vec<String>> {
+    let shas = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT bp.sha
+        FROM "BomPart" bp
+        LEFT JOIN "sha_size" s ON s.sha = bp.sha
+        WHERE bp.sha > $1 AND s.sha IS NULL
+        ORDER BY bp.sha
+        LIMIT $2
+        "#,
+        after_sha,
+        limit,
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(shas)
+}
+
+/// Gets the number of bytes stored by the documents of the provided owners.
+/// Content is deduplicated by sha across every document and version.
+#[tracing::instrument(skip(db))]
+async fn get_storage_usage_for_owners(db: &sqlx::PgPool, owners: &[String]) -> anyhow::Result<i64> {
+    let used_bytes = sqlx::query!(
+        r#"
+        WITH owner_shas AS (
+            SELECT di.sha
+            FROM "DocumentInstance" di
+            JOIN "Document" d ON d.id = di."documentId"
+            WHERE d.owner = ANY($1) AND d."deletedAt" IS NULL
+            UNION
+            SELECT bp.sha
+            FROM "BomPart" bp
+            JOIN "DocumentBom" db ON db.id = bp."documentBomId"
+            JOIN "Document" d ON d.id = db."documentId"
+            WHERE d.owner = ANY($1) AND d."deletedAt" IS NULL
+        )
+        SELECT COALESCE(SUM(s.size_bytes), 0)::BIGINT AS "used_bytes!"
+        FROM owner_shas os
+        JOIN "sha_size" s ON s.sha = os.sha
+        "#,
+        owners,
+    )
+    .map(|row| row.used_bytes)
+    .fetch_one(db)
+    .await?;
+
+    Ok(used_bytes)
+}
+
+/// Gets the number of bytes stored by a user
+#[tracing::instrument(skip(db))]
+pub async fn get_user_storage_usage(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<i64> {
+    get_storage_usage_for_owners(db, &[user_id.as_ref().to_string()]).await
+}
+
+/// Gets the storage plans granted to a user by their roles and the roles of their organization
+#[tracing::instrument(skip(db))]
+pub async fn get_storage_plans_for_user(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<Vec<StoragePlan>> {
+    let plans = sqlx::query!(
+        r#"
+        SELECT sp.limit_bytes, sp.limit_behavior
+        FROM "storage_plan" sp
+        WHERE sp.role_id IN (
+            SELECT ru."roleId"
+            FROM "RolesOnUsers" ru
+            WHERE ru."userId" = $1
+            UNION
+            SELECT ro."roleId"
+            FROM "RolesOnOrganizations" ro
+            JOIN "User" u ON u."organizationId" = ro."organizationId"
+            WHERE u.id = $1
+        )
+        "#,
+        user_id.as_ref(),
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(StoragePlan {
+            limit_bytes: row.limit_bytes,
+            limit_behavior: row.limit_behavior.parse()?,
+        })
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(plans)
+}
+
+/// Gets the storage quota of a user under their most generous plan
+#[tracing::instrument(skip(db))]
+pub async fn get_user_storage_quota(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<StorageQuota> {
+    let plans = get_storage_plans_for_user(db, user_id).await?;
+    let used_bytes = get_user_storage_usage(db, user_id).await?;
+
+    Ok(StorageQuota::new(
+        used_bytes,
+        StoragePlan::most_generous(plans),
+    ))
+}
+
+/// Gets the storage used by a user grouped by the file type of their documents.
+/// Content shared between file types counts towards each of them.
+#[tracing::instrument(skip(db))]
+pub async fn get_user_storage_usage_by_file_type(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<Vec<FileTypeStorageUsage>> {
+    let usage = sqlx::query_as!(
+        FileTypeStorageUsage,
+        r#"
+        WITH document_shas AS (
+            SELECT d."fileType" AS file_type, di.sha
+            FROM "DocumentInstance" di
+            JOIN "Document" d ON d.id = di."documentId"
+            WHERE d.owner = $1 AND d."deletedAt" IS NULL
+            UNION
+            SELECT d."fileType" AS file_type, bp.sha
+            FROM "BomPart" bp
+            JOIN "DocumentBom" db ON db.id = bp."documentBomId"
+            JOIN "Document" d ON d.id = db."documentId"
+            WHERE d.owner = $1 AND d."deletedAt" IS NULL
+        )
+        SELECT
+            ds.file_type AS "file_type?",
+            SUM(s.size_bytes)::BIGINT AS "used_bytes!"
+        FROM document_shas ds
+        JOIN "sha_size" s ON s.sha = ds.sha
+        GROUP BY ds.file_type
+        ORDER BY "used_bytes!" DESC
+        "#,
+        user_id.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(usage)
+}
+
+/// Gets the storage used by a user grouped by the project their documents are in.
+/// Documents are grouped under the project that directly contains them.
+#[tracing::instrument(skip(db))]
+pub async fn get_user_storage_usage_by_project(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<Vec<ProjectStorageUsage>> {
+    let usage = sqlx::query_as!(
+        ProjectStorageUsage,
+        r#"
+        WITH document_shas AS (
+            SELECT d."projectId" AS project_id, di.sha
+            FROM "DocumentInstance" di
+            JOIN "Document" d ON d.id = di."documentId"
+            WHERE d.owner = $1 AND d."deletedAt" IS NULL
+            UNION
+            SELECT d."projectId" AS project_id, bp.sha
+            FROM "BomPart" bp
+            JOIN "DocumentBom" db ON db.id = bp."documentBomId"
+            JOIN "Document" d ON d.id = db."documentId"
+            WHERE d.owner = $1 AND d."deletedAt" IS NULL
+        )
+        SELECT
+            ds.project_id AS "project_id?",
+            p.name AS "project_name?",
+            SUM(s.size_bytes)::BIGINT AS "used_bytes!"
+        FROM document_shas ds
+        JOIN "sha_size" s ON s.sha = ds.sha
+        LEFT JOIN "Project" p ON p.id = ds.project_id
+        GROUP BY ds.project_id, p.name
+        ORDER BY "used_bytes!" DESC
+        "#,
+        user_id.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(usage)
+}
+
+/// Gets the storage used by every team the user is a member of.
+/// Team usage is the deduplicated usage of all of the team members.
+#[tracing::instrument(skip(db))]
+pub async fn get_team_storage_usages_for_user(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<Vec<TeamStorageUsage>> {
+    let teams = sqlx::query!(
+        r#"
+        SELECT
+            t.id AS "team_id",
+            t.name AS "team_name",
+            ARRAY_AGG(members.user_id) AS "members!"
+        FROM "team_user" tu
+        JOIN "team" t ON t.id = tu.team_id
+        JOIN "team_user" members ON members.team_id = t.id
+        WHERE tu.user_id = $1
+        GROUP BY t.id, t.name
+        ORDER BY t.name
+        "#,
+        user_id.as_ref(),
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut usages = Vec::with_capacity(teams.len());
+    for team in teams {
+        let used_bytes = get_storage_usage_for_owners(db, &team.members).await?;
+        usages.push(TeamStorageUsage {
+            team_id: team.team_id.to_string(),
+            team_name: team.team_name,
+            used_bytes,
+        });
+    }
+
+    Ok(usages)
+}
+
+/// Gets the storage used by the organization of a user.
+/// Organization usage is the deduplicated usage of all of the organization members.
+/// Returns None if the user is not part of an organization.
+#[tracing::instrument(skip(db))]
+pub async fn get_organization_storage_usage_for_user(
+    db: &sqlx::PgPool,
+    user_id: &MacroUserId<Lowercase<'_>>,
+) -> anyhow::Result<Option<OrganizationStorageUsage>> {
+    let organization = sqlx::query!(
+        r#"
+        SELECT
+            o.id AS "organization_id",
+            o.name AS "organization_name",
+            ARRAY_AGG(members.id) AS "members!"
+        FROM "User" u
+        JOIN "Organization" o ON o.id = u."organizationId"
+        JOIN "User" members ON members."organizationId" = o.id
+        WHERE u.id = $1
+        GROUP BY o.id, o.name
+        "#,
+        user_id.as_ref(),
+    )
+    .fetch_optional(db)
+    .await?;
+
+    let Some(organization) = organization else {
+        return Ok(None);
+    };
+
+    let used_bytes = get_storage_usage_for_owners(db, &organization.members).await?;
+
+    Ok(Some(OrganizationStorageUsage {
+        organization_id: organization.organization_id,
+        organization_name: organization.organization_name,
+        used_bytes,
+    }))
+}
@@ -0,0 +1,203 @@
+use super::*;
+use sqlx::{Pool, Postgres};
+use user_quota::storage::StorageLimitBehavior;
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_get_user_storage_usage(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    // the pdf is stored twice and one bom part is shared between versions but each sha only
+    // counts once. deleted documents do not count.
+    let user_id = MacroUserId::parse_from_str("macro|user@user.com")?.lowercase();
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 1230);
+
+    let user_id = MacroUserId::parse_from_str("macro|user2@user.com")?.lowercase();
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 1004);
+
+    // content that has not been sized yet does not count
+    let user_id = MacroUserId::parse_from_str("macro|user3@user.com")?.lowercase();
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 0);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_upsert_sha_sizes(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    let user_id = MacroUserId::parse_from_str("macro|user3@user.com")?.lowercase();
+
+    let unsized_instances = get_unsized_document_instances(&pool, &user_id, 10).await?;
+    assert_eq!(
+        unsized_instances
+            .iter()
+            .map(|instance| (instance.sha.as_str(), instance.document_version_id))
+            .collect::<Vec<_>>(),
+        vec![("sha-md-1", 8), ("sha-md-2", 7)]
+    );
+
+    upsert_sha_sizes(
+        &pool,
+        &[
+            ("sha-md-1".to_string(), 10),
+            ("sha-md-2".to_string(), 20),
+            // existing sizes are not overwritten
+            ("sha-pdf".to_string(), 1),
+        ],
+    )
+    .await?;
+
+    assert!(
+        get_unsized_document_instances(&pool, &user_id, 10)
+            .await?
+            .is_empty()
+    );
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 30);
+
+    let user_id = MacroUserId::parse_from_str("macro|user@user.com")?.lowercase();
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 1230);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_record_document_instance_size(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    let user_id = MacroUserId::parse_from_str("macro|user3@user.com")?.lowercase();
+
+    // files that don't match the document are ignored
+    record_document_instance_size(&pool, "d6", 8, Some("pdf"), 10).await?;
+    record_document_instance_size(&pool, "d1", 8, Some("md"), 10).await?;
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 0);
+
+    record_document_instance_size(&pool, "d6", 8, Some("md"), 10).await?;
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 10);
+
+    // sizes are only recorded once
+    record_document_instance_size(&pool, "d6", 8, Some("md"), 99).await?;
+    assert_eq!(get_user_storage_usage(&pool, &user_id).await?, 10);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_get_unsized_content_for_backfill(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    let instances = get_all_unsized_document_instances(&pool, "", 10).await?;
+    assert_eq!(
+        instances
+            .iter()
+            .map(|instance| instance.sha.as_str())
+            .collect::<Vec<_>>(),
+        vec!["sha-md-1", "sha-md-2"]
+    );
+
+    let instances = get_all_unsized_document_instances(&pool, "sha-md-1", 10).await?;
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].sha, "sha-md-2");
+
+    // bom parts that were unzipped before sizes were recorded count as nothing until sized
+    assert_eq!(
+        get_unsized_bom_part_shas(&pool, "", 10).await?,
+        vec!["sha-part-3".to_string()]
+    );
+    assert!(
+        get_unsized_bom_part_shas(&pool, "sha-part-3", 10)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_get_user_storage_quota(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    // the organization plan is more generous than the user's own plan
+    let user_id = MacroUserId::parse_from_str("macro|user@user.com")?.lowercase();
+    let quota = get_user_storage_quota(&pool, &user_id).await?;
+    assert_eq!(quota.used_bytes, 1230);
+    assert_eq!(quota.limit_bytes, 1099511627776);
+    assert_eq!(quota.limit_behavior, StorageLimitBehavior::Soft);
+
+    // users without a plan get the default plan
+    let user_id = MacroUserId::parse_from_str("macro|user3@user.com")?.lowercase();
+    assert!(
+        get_storage_plans_for_user(&pool, &user_id)
+            .await?
+            .is_empty()
+    );
+    let quota = get_user_storage_quota(&pool, &user_id).await?;
+    assert_eq!(
+        quota.limit_bytes,
+        user_quota::storage::DEFAULT_STORAGE_PLAN.limit_bytes
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_get_user_storage_usage_breakdown(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    let user_id = MacroUserId::parse_from_str("macro|user@user.com")?.lowercase();
+
+    let by_file_type = get_user_storage_usage_by_file_type(&pool, &user_id).await?;
+    assert_eq!(
+        by_file_type,
+        vec![
+            FileTypeStorageUsage {
+                file_type: Some("pdf".to_string()),
+                used_bytes: 1000,
+            },
+            FileTypeStorageUsage {
+                file_type: Some("docx".to_string()),
+                used_bytes: 230,
+            },
+        ]
+    );
+
+    let by_project = get_user_storage_usage_by_project(&pool, &user_id).await?;
+    assert_eq!(
+        by_project,
+        vec![
+            ProjectStorageUsage {
+                project_id: Some("p1".to_string()),
+                project_name: Some("project one".to_string()),
+                used_bytes: 1230,
+            },
+            ProjectStorageUsage {
+                project_id: None,
+                project_name: None,
+                used_bytes: 1000,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures(path = "../../fixtures", scripts("storage_usage")))]
+async fn test_get_team_and_organization_storage_usage(pool: Pool<Postgres>) -> anyhow::Result<()> {
+    let user_id = MacroUserId::parse_from_str("macro|user@user.com")?.lowercase();
+
+    let teams = get_team_storage_usages_for_user(&pool, &user_id).await?;
+    assert_eq!(
+        teams,
+        vec![TeamStorageUsage {
+            team_id: "11111111-1111-1111-1111-111111111111".to_string(),
+            team_name: "team1".to_string(),
+            used_bytes: 1230,
+        }]
+    );
+
+    // the pdf shared between organization members only counts once
+    let organization = get_organization_storage_usage_for_user(&pool, &user_id).await?;
+    assert_eq!(
+        organization,
+        Some(OrganizationStorageUsage {
+            organization_id: 1,
+            organization_name: "test organization".to_string(),
+            used_bytes: 1234,
+        })
+    );
+
+    let user_id = MacroUserId::parse_from_str("macro|user3@user.com")?.lowercase();
+    assert_eq!(
+        get_organization_storage_usage_for_user(&pool, &user_id).await?,
+        None
+    );
+
+    Ok(())
+}
@@ -1,14 +1,31 @@
 use axum::{
     Extension, Json,
+    body::Body,
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use macro_user_id::user_id::MacroUserId;
 use model::{response::ErrorResponse, user::UserContext};
 use sqlx::PgPool;
-use user_quota::UserQuota;
+use user_quota::{
+    UserQuota,
+    storage::{StorageQuota, StorageQuotaStatus},
+};
+
+/// The response header set when the user is over their soft storage limit
+pub static STORAGE_QUOTA_WARNING_HEADER: &str = "x-storage-quota-warning";
+
+/// The largest request body [storage_handler] reads to find the size of the upload
+const MAX_STORAGE_REQUEST_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// The size of the content a request is about to store, declared in its json body
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct IncomingContent {
+    size_bytes: Option<i64>,
+}
 
 /// Checks if the user has a valid quota for document creation
 #[tracing::instrument(skip(db, user_context, req, next), fields(user_id=?user_context.user_id))]
@@ -77,6 +94,82 @@ pub async fn ai_chat_message_handler(
     }
 }
 
+/// Checks if the user has storage left under their plan for the new content the request stores.
+/// The size of that content is read from the `sizeBytes` field of the json body, which the
+/// request must declare.
+/// Users that would go over a hard limit are rejected while users that would go over a soft
+/// limit are let through with a warning header on the response.
+#[tracing::instrument(skip(db, user_context, req, next), fields(user_id=?user_context.user_id))]
+pub async fn storage_handler(
+    State(db): State<PgPool>,
+    user_context: Extension<UserContext>,
+    req: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    let (parts, body) = req.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_STORAGE_REQUEST_BODY_BYTES)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())?;
+
+    let incoming_bytes = serde_json::from_slice::<IncomingContent>(&bytes)
+        .unwrap_or_default()
+        .size_bytes
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "sizeBytes is required").into_response())?;
+
+    if incoming_bytes < 0 {
+        return Err((StatusCode::BAD_REQUEST, "sizeBytes must not be negative").into_response());
+    }
+
+    let req = Request::from_parts(parts, Body::from(bytes));
+
+    check_storage_quota(&db, &user_context.user_id, incoming_bytes, req, next).await
+}
+
+/// Checks if the user has storage left under their plan for a copy of content that is already
+/// stored. The copy isn't sized up front, so only users already over a hard limit are rejected.
+#[tracing::instrument(skip(db, user_context, req, next), fields(user_id=?user_context.user_id))]
+pub async fn storage_copy_handler(
+    State(db): State<PgPool>,
+    user_context: Extension<UserContext>,
+    req: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    check_storage_quota(&db, &user_context.user_id, 0, req, next).await
+}
+
+async fn check_storage_quota(
+    db: &PgPool,
+    user_id: &str,
+    incoming_bytes: i64,
+    req: Request,
+    next: Next,
+) -> Result<Response, Response> {
+    let storage_quota = get_user_storage_quota(db, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+
+    match storage_quota.check(incoming_bytes) {
+        StorageQuotaStatus::WithinLimit => Ok(next.run(req).await),
+        StorageQuotaStatus::OverSoftLimit => {
+            let mut response = next.run(req).await;
+            response.headers_mut().insert(
+                STORAGE_QUOTA_WARNING_HEADER,
+                HeaderValue::from_static("STORAGE_QUOTA_EXCEEDED"),
+            );
+            Ok(response)
+        }
+        StorageQuotaStatus::Exceeded => {
+            Err((StatusCode::FORBIDDEN, "STORAGE_QUOTA_EXCEEDED").into_response())
+        }
+    }
+}
+
+async fn get_user_storage_quota(db: &PgPool, user_id: &str) -> anyhow::Result<StorageQuota> {
+    let user_id = MacroUserId::parse_from_str(user_id)?.lowercase();
+
+    macro_db_client::storage_usage::get_user_storage_quota(db, &user_id).await
+}
+
 async fn get_user_quota(db: &PgPool, user_id: &str) -> anyhow::Result<UserQuota> {
     let user_id = MacroUserId::parse_from_str(user_id)?.lowercase();
 
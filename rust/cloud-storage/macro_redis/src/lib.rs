@@ -4,3 +4,4 @@ pub mod delete;
 pub mod get;
 pub mod incr;
 pub mod set;
+pub mod sliding_window;
@@ -0,0 +1,332 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+
+/// Tells hits apart when several land in the same millisecond
+static HIT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// What happens to a hit against a sliding window that already holds the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenFull {
+    /// The hit is rejected
+    Reject,
+    /// The hit is recorded at the first time the window has room for it, after any hits already
+    /// queued, so each queued hit is held until its own time
+    Queue,
+    /// The hit is not recorded and is held until the oldest hit leaves the window, so every hit
+    /// while the window is full is held until the same time
+    Wait,
+}
+
+impl WhenFull {
+    fn as_arg(&self) -> &'static str {
+        match self {
+            WhenFull::Reject => "reject",
+            WhenFull::Queue => "queue",
+            WhenFull::Wait => "wait",
+        }
+    }
+}
+
+/// A sliding window rate limit kept in a sorted set at the key. Unlike a fixed window, the limit
+/// holds across any span of the window's length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlidingWindow {
+    pub key: String,
+    pub limit: u64,
+    pub window: Duration,
+    pub when_full: WhenFull,
+}
+
+/// The result of a hit against one sliding window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlidingWindowHit {
+    /// The window had room for the hit
+    Allowed,
+    /// The window was full and rejects hits when full
+    Rejected,
+    /// The window was full and the hit should wait until the time
+    Held(SystemTime),
+}
+
+/// Records a hit against each of the sliding windows in one step. If any window rejects the hit,
+/// it is not recorded in any of them. Returns the result for each window in order.
+pub async fn hit(
+    client: &redis::Client,
+    windows: &[SlidingWindow],
+) -> anyhow::Result<Vec<SlidingWindowHit>> {
+    hit_at(client, windows, SystemTime::now()).await
+}
+
+async fn hit_at(
+    client: &redis::Client,
+    windows: &[SlidingWindow],
+    now: SystemTime,
+) -> anyhow::Result<Vec<SlidingWindowHit>> {
+    if windows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if let Some(window) = windows.iter().find(|window| window.limit == 0) {
+        anyhow::bail!("sliding window for key {} has no limit", window.key);
+    }
+
+    let mut redis_connection = client
+        .get_multiplexed_async_connection()
+        .await
+        .context("unable to connect to redis")?;
+
+    let now_ms = now
+        .duration_since(UNIX_EPOCH)
+        .context("system time is before the unix epoch")?
+        .as_millis() as u64;
+    let member = format!(
+        "{}:{}:{}",
+        now_ms,
+        std::process::id(),
+        HIT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let script = redis::Script::new(SLIDING_WINDOW_SCRIPT);
+    let mut invocation = script.prepare_invoke();
+    invocation.arg(now_ms).arg(member);
+    for window in windows {
+        invocation
+            .key(&window.key)
+            .arg(window.limit)
+            .arg(window.window.as_millis() as u64)
+            .arg(window.when_full.as_arg());
+    }
+
+    let results: Vec<(i64, u64)> = invocation
+        .invoke_async(&mut redis_connection)
+        .await
+        .context("unable to hit sliding windows")?;
+
+    anyhow::ensure!(
+        results.len() == windows.len(),
+        "expected {} sliding window results, got {}",
+        windows.len(),
+        results.len()
+    );
+
+    results
+        .into_iter()
+        .map(|(status, held_until_ms)| match status {
+            0 => Ok(SlidingWindowHit::Allowed),
+            1 => Ok(SlidingWindowHit::Rejected),
+            2 => Ok(SlidingWindowHit::Held(
+                UNIX_EPOCH + Duration::from_millis(held_until_ms),
+            )),
+            _ => Err(anyhow::anyhow!("unknown sliding window status {}", status)),
+        })
+        .collect()
+}
+
+/// KEYS[i]: the sorted set of hits for each window, scored by the time they happened (or, for
+/// queued hits, will happen) in milliseconds
+/// ARGV[1]: the time now in milliseconds
+/// ARGV[2]: a unique member for this hit
+/// ARGV[3i], ARGV[3i + 1], ARGV[3i + 2]: the limit, the window in milliseconds and what to do
+/// when full ("reject", "queue" or "wait") for KEYS[i]
+/// Returns {status, held_until_ms} for each key, where the status is 0 for allowed, 1 for rejected
+/// and 2 for held
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+    local now_ms = tonumber(ARGV[1])
+    local member = ARGV[2]
+    local results = {}
+    local rejected = false
+
+    for i, key in ipairs(KEYS) do
+        local limit = tonumber(ARGV[3 * i])
+        local window_ms = tonumber(ARGV[3 * i + 1])
+        local when_full = ARGV[3 * i + 2]
+
+        redis.call('ZREMRANGEBYSCORE', key, '-inf', now_ms - window_ms)
+        local count = redis.call('ZCARD', key)
+
+        if count < limit then
+            results[i] = {0, now_ms}
+        elseif when_full == 'reject' then
+            results[i] = {1, 0}
+            rejected = true
+        elseif when_full == 'queue' then
+            -- The hit can go once the hit `limit` places ahead of it leaves the window
+            local slot = redis.call('ZRANGE', key, count - limit, count - limit, 'WITHSCORES')
+            results[i] = {2, tonumber(slot[2]) + window_ms}
+        else
+            local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+            results[i] = {2, tonumber(oldest[2]) + window_ms}
+        end
+    end
+
+    if not rejected then
+        for i, key in ipairs(KEYS) do
+            local window_ms = tonumber(ARGV[3 * i + 1])
+            local when_full = ARGV[3 * i + 2]
+
+            if results[i][1] == 0 or when_full == 'queue' then
+                redis.call('ZADD', key, results[i][2], member)
+            end
+
+            local newest = redis.call('ZRANGE', key, -1, -1, 'WITHSCORES')
+            if newest[2] then
+                redis.call('PEXPIRE', key, tonumber(newest[2]) + window_ms - now_ms)
+            end
+        end
+    end
+
+    for _, result in ipairs(results) do
+        if result[1] ~= 2 then
+            result[2] = 0
+        end
+    end
+    return results
+"#;
+
+#[cfg(test)]
+#[cfg(feature = "redis_client_test")]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    async fn setup(keys: &[&str]) -> redis::Client {
+        let client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+        for key in keys {
+            let _: () = redis::cmd("DEL")
+                .arg(*key)
+                .query_async(&mut conn)
+                .await
+                .unwrap();
+        }
+        client
+    }
+
+    /// Now, to the millisecond the script works in
+    fn start() -> SystemTime {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        UNIX_EPOCH + Duration::from_millis(now.as_millis() as u64)
+    }
+
+    fn window(key: &str, limit: u64, when_full: WhenFull) -> SlidingWindow {
+        SlidingWindow {
+            key: key.to_string(),
+            limit,
+            window: 10 * SECOND,
+            when_full,
+        }
+    }
+
+    fn held_for(hit: SlidingWindowHit, start: SystemTime) -> Duration {
+        match hit {
+            SlidingWindowHit::Held(until) => until.duration_since(start).unwrap(),
+            _ => panic!("expected a held hit, got {:?}", hit),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reject() {
+        let key = "test_sliding_window_reject";
+        let client = setup(&[key]).await;
+        let windows = [window(key, 2, WhenFull::Reject)];
+        let start = start();
+
+        for _ in 0..2 {
+            let hits = hit_at(&client, &windows, start).await.unwrap();
+            assert_eq!(hits, vec![SlidingWindowHit::Allowed]);
+        }
+
+        let hits = hit_at(&client, &windows, start + SECOND).await.unwrap();
+        assert_eq!(hits, vec![SlidingWindowHit::Rejected]);
+
+        // Rejected hits are not recorded, so the window has room again once the first hits leave
+        let hits = hit_at(&client, &windows, start + 10 * SECOND)
+            .await
+            .unwrap();
+        assert_eq!(hits, vec![SlidingWindowHit::Allowed]);
+    }
+
+    #[tokio::test]
+    async fn test_queue() {
+        let key = "test_sliding_window_queue";
+        let client = setup(&[key]).await;
+        let windows = [window(key, 2, WhenFull::Queue)];
+        let start = start();
+
+        hit_at(&client, &windows, start).await.unwrap();
+        hit_at(&client, &windows, start + SECOND).await.unwrap();
+
+        // Each queued hit waits for its own slot
+        let held = [
+            hit_at(&client, &windows, start + 2 * SECOND).await.unwrap()[0],
+            hit_at(&client, &windows, start + 3 * SECOND).await.unwrap()[0],
+            hit_at(&client, &windows, start + 4 * SECOND).await.unwrap()[0],
+        ];
+        assert_eq!(held_for(held[0], start), 10 * SECOND);
+        assert_eq!(held_for(held[1], start), 11 * SECOND);
+        assert_eq!(held_for(held[2], start), 20 * SECOND);
+
+        // The first hits have left the window, but the queued hits still fill it
+        let hits = hit_at(&client, &windows, start + 12 * SECOND)
+            .await
+            .unwrap();
+        assert_eq!(held_for(hits[0], start), 21 * SECOND);
+    }
+
+    #[tokio::test]
+    async fn test_wait() {
+        let key = "test_sliding_window_wait";
+        let client = setup(&[key]).await;
+        let windows = [window(key, 2, WhenFull::Wait)];
+        let start = start();
+
+        hit_at(&client, &windows, start).await.unwrap();
+        hit_at(&client, &windows, start + SECOND).await.unwrap();
+
+        // Every hit while the window is full waits until the same time
+        for seconds in 2..5 {
+            let hits = hit_at(&client, &windows, start + seconds * SECOND)
+                .await
+                .unwrap();
+            assert_eq!(held_for(hits[0], start), 10 * SECOND);
+        }
+
+        let hits = hit_at(&client, &windows, start + 10 * SECOND)
+            .await
+            .unwrap();
+        assert_eq!(hits, vec![SlidingWindowHit::Allowed]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_hits_are_not_recorded_in_other_windows() {
+        let reject_key = "test_sliding_window_multi_reject";
+        let other_key = "test_sliding_window_multi_other";
+        let client = setup(&[reject_key, other_key]).await;
+        let start = start();
+
+        hit_at(&client, &[window(reject_key, 1, WhenFull::Reject)], start)
+            .await
+            .unwrap();
+
+        let windows = [
+            window(other_key, 1, WhenFull::Reject),
+            window(reject_key, 1, WhenFull::Reject),
+        ];
+        let hits = hit_at(&client, &windows, start).await.unwrap();
+        assert_eq!(
+            hits,
+            vec![SlidingWindowHit::Allowed, SlidingWindowHit::Rejected]
+        );
+
+        // The rejected hit was not counted against the other window
+        let hits = hit_at(&client, &[window(other_key, 1, WhenFull::Reject)], start)
+            .await
+            .unwrap();
+        assert_eq!(hits, vec![SlidingWindowHit::Allowed]);
+    }
+}
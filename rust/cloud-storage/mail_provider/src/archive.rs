@@ -0,0 +1,333 @@
+//! Reading and writing mail archives: mbox files and single RFC 5322 messages (.eml).
+//! Archived messages are converted into Gmail message resources with the same parser as IMAP
+//! messages, so imported mail is threaded by its Message-ID, In-Reply-To and References headers.
+
+use crate::imap::parse;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use mail_builder::MessageBuilder;
+use mail_builder::headers::raw::Raw;
+use mail_builder::headers::text::Text;
+use mail_builder::mime::{BodyPart, MimePart};
+use mailparse::MailHeaderMap;
+use models_email::gmail::{Header, MessageResource};
+use uuid::Uuid;
+
+/// The start of the line that precedes every message of an mbox file
+const MBOX_SEPARATOR: &[u8] = b"From ";
+
+/// The envelope sender of mbox messages without a sender
+const MBOX_UNKNOWN_SENDER: &str = "MAILER-DAEMON";
+
+/// A message read from an archive
+pub struct ArchivedMessage {
+    /// The raw message, attachments are read from it with [attachment_data]
+    pub raw: Vec<u8>,
+    /// The message without labels. Its id is the Message-ID of the message, or a random id for
+    /// messages without one.
+    pub resource: MessageResource,
+    /// The message ids of the In-Reply-To and References headers, without angle brackets
+    pub referenced_ids: Vec<String>,
+}
+
+/// An attachment of a message being exported
+pub struct ExportedAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Reads the messages of an archive, which is either an mbox file, a multipart/digest message
+/// as built by [build_digest], or a single message.
+pub fn read_archive(data: &[u8]) -> Vec<anyhow::Result<ArchivedMessage>> {
+    let messages = if data.starts_with(MBOX_SEPARATOR) {
+        split_mbox(data)
+    } else {
+        digest_messages(data).unwrap_or_else(|| vec![data.to_vec()])
+    };
+
+    messages.into_iter().map(archived_message).collect()
+}
+
+/// Extracts the content of an attachment of an archived message.
+/// The attachment id is the part id of the attachment.
+pub fn attachment_data(raw: &[u8], attachment_id: &str) -> anyhow::Result<Vec<u8>> {
+    parse::attachment_data(raw, attachment_id)
+}
+
+/// Rebuilds a message from its stored headers and bodies. Headers describing the MIME structure
+/// of the original message are dropped, since the body is rebuilt from the stored parts.
+pub fn build_message(
+    headers: &[Header],
+    body_text: Option<&str>,
+    body_html: Option<&str>,
+    attachments: &[ExportedAttachment],
+) -> anyhow::Result<Vec<u8>> {
+    let mut builder = MessageBuilder::new();
+
+    for header in headers {
+        let name = header.name.as_str();
+        if name.to_ascii_lowercase().starts_with("content-")
+            || name.eq_ignore_ascii_case("MIME-Version")
+        {
+            continue;
+        }
+
+        // stored values are unfolded, line breaks would start a new header
+        let value = header.value.replace(['\r', '\n'], " ");
+        builder = if name.eq_ignore_ascii_case("Subject") {
+            builder.header("Subject", Text::new(value))
+        } else {
+            builder.header(canonical_header_name(name), Raw::new(value))
+        };
+    }
+
+    match (body_text, body_html) {
+        (None, None) => builder = builder.text_body(""),
+        (text, html) => {
+            if let Some(text) = text {
+                builder = builder.text_body(text);
+            }
+            if let Some(html) = html {
+                builder = builder.html_body(html);
+            }
+        }
+    }
+
+    for attachment in attachments {
+        builder = builder.attachment(
+            attachment.mime_type.as_str(),
+            attachment.filename.as_str(),
+            attachment.data.as_slice(),
+        );
+    }
+
+    builder.write_to_vec().context("building message error")
+}
+
+/// Bundles messages into a single multipart/digest message, so a whole thread can be exported as
+/// one .eml file. [read_archive] reads the bundled messages back.
+pub fn build_digest(subject: &str, messages: Vec<Vec<u8>>) -> anyhow::Result<Vec<u8>> {
+    let parts = messages
+        .into_iter()
+        .map(|raw| {
+            MimePart::new("message/rfc822", BodyPart::Binary(raw.into())).transfer_encoding("8bit")
+        })
+        .collect();
+
+    MessageBuilder::new()
+        .subject(subject)
+        .body(MimePart::new(
+            "multipart/digest",
+            BodyPart::Multipart(parts),
+        ))
+        .write_to_vec()
+        .context("building digest error")
+}
+
+/// Appends a message to an mbox file. Line endings are converted to LF and lines starting with
+/// "From ", including ones that are already escaped, are escaped with `>` (mboxrd).
+pub fn append_mbox_message(mbox: &mut Vec<u8>, raw: &[u8], sender: &str, date: DateTime<Utc>) {
+    let sender = sender
+        .split_whitespace()
+        .next()
+        .unwrap_or(MBOX_UNKNOWN_SENDER);
+    mbox.extend_from_slice(
+        format!("From {} {}\n", sender, date.format("%a %b %e %H:%M:%S %Y")).as_bytes(),
+    );
+
+    for line in raw.split_inclusive(|&byte| byte == b'\n') {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if is_from_line(line) {
+            mbox.push(b'>');
+        }
+        mbox.extend_from_slice(line);
+        mbox.push(b'\n');
+    }
+
+    mbox.push(b'\n');
+}
+
+/// Splits an mbox file into its messages, unescaping the lines escaped by
+/// [append_mbox_message]
+fn split_mbox(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+
+    for line in data.split_inclusive(|&byte| byte == b'\n') {
+        if line.starts_with(MBOX_SEPARATOR) {
+            messages.extend(current.take());
+            current = Some(Vec::new());
+            continue;
+        }
+
+        let Some(message) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with(b">") && is_from_line(line) {
+            message.extend_from_slice(&line[1..]);
+        } else {
+            message.extend_from_slice(line);
+        }
+    }
+    messages.extend(current);
+
+    messages.retain(|message| !message.iter().all(u8::is_ascii_whitespace));
+    messages
+}
+
+/// Whether the line starts with "From ", optionally preceded by `>` characters
+fn is_from_line(line: &[u8]) -> bool {
+    line.iter()
+        .position(|&byte| byte != b'>')
+        .is_some_and(|start| line[start..].starts_with(MBOX_SEPARATOR))
+}
+
+/// The raw messages of a multipart/digest message, or None if the message isn't a digest
+fn digest_messages(data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let parsed = mailparse::parse_mail(data).ok()?;
+    if !parsed
+        .ctype
+        .mimetype
+        .eq_ignore_ascii_case("multipart/digest")
+    {
+        return None;
+    }
+
+    Some(
+        parsed
+            .subparts
+            .iter()
+            .filter(|part| part.ctype.mimetype.eq_ignore_ascii_case("message/rfc822"))
+            .filter_map(|part| part.get_body_raw().ok())
+            .collect(),
+    )
+}
+
+fn archived_message(raw: Vec<u8>) -> anyhow::Result<ArchivedMessage> {
+    let (headers, _) = mailparse::parse_headers(&raw).context("Failed to parse message headers")?;
+
+    let id = headers
+        .get_first_value("Message-ID")
+        .and_then(|value| parse::message_ids(&value).into_iter().next())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let referenced_ids = ["In-Reply-To", "References"]
+        .into_iter()
+        .filter_map(|name| headers.get_first_value(name))
+        .flat_map(|value| parse::message_ids(&value))
+        .collect();
+
+    let resource = parse::message_resource(&id, &raw, Vec::new(), None)?;
+
+    Ok(ArchivedMessage {
+        raw,
+        resource,
+        referenced_ids,
+    })
+}
+
+/// mail-builder only detects the Date and Message-ID headers by their canonical names, other
+/// spellings would get a second generated header
+fn canonical_header_name(name: &str) -> &str {
+    if name.eq_ignore_ascii_case("Message-ID") {
+        "Message-ID"
+    } else if name.eq_ignore_ascii_case("Date") {
+        "Date"
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const MESSAGE: &[u8] = b"From: Alice <alice@example.com>\r\n\
+To: bob@example.com\r\n\
+Subject: Quarterly report\r\n\
+Date: Fri, 07 Feb 2025 14:03:11 +0100\r\n\
+Message-ID: <reply@example.com>\r\n\
+In-Reply-To: <parent@example.com>\r\n\
+References: <root@example.com> <parent@example.com>\r\n\
+\r\n\
+From the numbers,\r\n\
+>From the top\r\n\
+ship it\r\n";
+
+    #[test]
+    fn test_mbox_round_trip() {
+        let date = Utc.with_ymd_and_hms(2025, 2, 7, 13, 3, 11).unwrap();
+        let mut mbox = Vec::new();
+        append_mbox_message(&mut mbox, MESSAGE, "alice@example.com", date);
+        append_mbox_message(&mut mbox, MESSAGE, "", date);
+
+        let text = String::from_utf8(mbox.clone()).unwrap();
+        assert!(text.starts_with("From alice@example.com Fri Feb  7 13:03:11 2025\n"));
+        assert!(text.contains("\n>From the numbers,\n>>From the top\n"));
+        assert!(text.contains("\nFrom MAILER-DAEMON Fri Feb  7 13:03:11 2025\n"));
+
+        let messages = split_mbox(&mbox);
+        assert_eq!(messages.len(), 2);
+
+        let expected = String::from_utf8(MESSAGE.to_vec())
+            .unwrap()
+            .replace("\r\n", "\n");
+        assert_eq!(
+            String::from_utf8(messages[0].clone()).unwrap(),
+            expected + "\n"
+        );
+    }
+
+    #[test]
+    fn test_read_archive() -> anyhow::Result<()> {
+        let messages = read_archive(MESSAGE);
+        assert_eq!(messages.len(), 1);
+
+        let message = messages.into_iter().next().unwrap()?;
+        assert_eq!(message.resource.id, "reply@example.com");
+        assert_eq!(message.resource.thread_id, "root@example.com");
+        assert_eq!(
+            message.referenced_ids,
+            vec![
+                "parent@example.com",
+                "root@example.com",
+                "parent@example.com"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_digest_round_trip() -> anyhow::Result<()> {
+        let headers = vec![
+            Header {
+                name: "Message-Id".to_string(),
+                value: "<root@example.com>".to_string(),
+            },
+            Header {
+                name: "Subject".to_string(),
+                value: "Quarterly report".to_string(),
+            },
+            Header {
+                name: "Content-Type".to_string(),
+                value: "multipart/alternative; boundary=\"b1\"".to_string(),
+            },
+        ];
+        let root = build_message(&headers, Some("numbers attached"), None, &[])?;
+        let root_text = String::from_utf8(root.clone())?;
+        assert_eq!(root_text.matches("Message-ID:").count(), 1);
+        assert!(!root_text.contains("b1"));
+
+        let digest = build_digest("Quarterly report", vec![root, MESSAGE.to_vec()])?;
+        let ids = read_archive(&digest)
+            .into_iter()
+            .map(|message| message.map(|message| message.resource.id))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(ids, vec!["root@example.com", "reply@example.com"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{LabelError, MailProvider};
+use async_trait::async_trait;
+use gmail_client::{GmailClient, GmailError};
+use models_email::email::service::address::ContactInfo;
+use models_email::email::service::label::Label;
+use models_email::email::service::link::UserProvider;
+use models_email::email::service::message::{Message, MessageToSend};
+use models_email::email::service::thread::{Thread, ThreadList};
+use models_email::gmail::history::InboxChanges;
+use uuid::Uuid;
+
+/// A mailbox accessed through the Gmail API
+pub struct GmailProvider {
+    client: GmailClient,
+    access_token: String,
+}
+
+impl GmailProvider {
+    pub fn new(client: GmailClient, access_token: String) -> Self {
+        Self {
+            client,
+            access_token,
+        }
+    }
+}
+
+#[async_trait]
+impl MailProvider for GmailProvider {
+    fn provider(&self) -> UserProvider {
+        UserProvider::Gmail
+    }
+
+    async fn get_total_threads(&self) -> anyhow::Result<i32> {
+        self.client
+            .get_profile_threads_total(&self.access_token)
+            .await
+    }
+
+    async fn list_threads(
+        &self,
+        num_threads: u32,
+        next_page_token: Option<&str>,
+    ) -> anyhow::Result<ThreadList> {
+        self.client
+            .list_threads(&self.access_token, num_threads, next_page_token)
+            .await
+    }
+
+    async fn get_message_ids_for_thread(&self, thread_id: &str) -> anyhow::Result<Vec<String>> {
+        self.client
+            .get_message_ids_for_thread(&self.access_token, thread_id)
+            .await
+    }
+
+    async fn get_threads(
+        &self,
+        link_id: Uuid,
+        thread_ids: &[String],
+    ) -> anyhow::Result<Vec<Thread>> {
+        self.client
+            .get_threads(link_id, &self.access_token, thread_ids)
+            .await
+    }
+
+    async fn get_message(
+        &self,
+        message_id: &str,
+        link_id: Uuid,
+    ) -> anyhow::Result<Option<Message>> {
+        self.client
+            .get_message(&self.access_token, message_id, link_id)
+            .await
+    }
+
+    async fn get_message_label_ids(
+        &self,
+        message_id: &str,
+        link_id: Uuid,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        self.client
+            .get_message_label_ids(&self.access_token, message_id, link_id)
+            .await
+    }
+
+    async fn modify_message_labels(
+        &self,
+        message_id: &str,
+        labels_to_add: &[String],
+        labels_to_remove: &[String],
+    ) -> anyhow::Result<()> {
+        self.client
+            .modify_message_labels(
+                &self.access_token,
+                message_id,
+                labels_to_add,
+                labels_to_remove,
+            )
+            .await
+    }
+
+    async fn batch_modify_labels(
+        &self,
+        messages: Vec<(Uuid, String)>,
+        labels_to_add: Vec<String>,
+        labels_to_remove: Vec<String>,
+    ) -> (Vec<Uuid>, Vec<Uuid>) {
+        self.client
+            .batch_modify_labels(
+                &self.access_token,
+                messages,
+                labels_to_add,
+                labels_to_remove,
+            )
+            .await
+    }
+
+    async fn create_label(&self, link_id: Uuid, label_name: &str) -> Result<Label, LabelError> {
+        self.client
+            .create_label(&self.access_token, link_id, label_name)
+            .await
+            .map_err(|e| match e {
+                GmailError::Conflict(_) => LabelError::Conflict,
+                e => LabelError::Other(e.into()),
+            })
+    }
+
+    async fn delete_label(&self, provider_label_id: &str) -> Result<(), LabelError> {
+        self.client
+            .delete_label(&self.access_token, provider_label_id)
+            .await
+            .map_err(|e| match e {
+                GmailError::NotFound(_) => LabelError::NotFound,
+                e => LabelError::Other(e.into()),
+            })
+    }
+
+    async fn fetch_user_labels(&self, link_id: Uuid) -> anyhow::Result<Vec<Label>> {
+        self.client
+            .fetch_user_labels(&self.access_token, link_id)
+            .await
+    }
+
+    async fn get_current_history_id(&self) -> anyhow::Result<String> {
+        self.client.get_current_history_id(&self.access_token).await
+    }
+
+    async fn get_history(&self, start_history_id: &str) -> anyhow::Result<InboxChanges> {
+        self.client
+            .get_history(&self.access_token, start_history_id)
+            .await
+    }
+
+    async fn get_attachment_data(
+        &self,
+        message_id: &str,
+        attachment_id: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.client
+            .get_attachment_data(&self.access_token, message_id, attachment_id)
+            .await
+    }
+
+    async fn send_message(
+        &self,
+        message: &mut MessageToSend,
+        from_contact: &ContactInfo,
+        parent_message_id: Option<String>,
+        references: Option<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        self.client
+            .send_message(
+                &self.access_token,
+                message,
+                from_contact,
+                parent_message_id,
+                references,
+            )
+            .await
+    }
+}
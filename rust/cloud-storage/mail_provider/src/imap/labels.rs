@@ -0,0 +1,343 @@
+//! Maps IMAP mailboxes and flags onto the Gmail style labels the rest of the email service uses.
+//! System mailboxes and flags become system labels, every other mailbox becomes a user label
+//! whose provider id is the mailbox name.
+
+use anyhow::Context;
+use imap_client::{Mailbox, MailboxRole};
+use models_email::email::service::label::{
+    Label, LabelListVisibility, LabelType, MessageListVisibility, system_labels,
+};
+use uuid::Uuid;
+
+const SEEN: &str = "\\Seen";
+const FLAGGED: &str = "\\Flagged";
+const DRAFT: &str = "\\Draft";
+const JUNK: &str = "$Junk";
+const IMPORTANT: [&str; 2] = ["$Important", "\\Important"];
+
+/// The label of messages stored in a mailbox.
+/// Archived messages and virtual mailboxes have no label, like archived messages in Gmail.
+pub(crate) fn mailbox_label(mailbox: &Mailbox) -> Option<&str> {
+    match mailbox.role {
+        MailboxRole::Inbox => Some(system_labels::INBOX),
+        MailboxRole::Sent => Some(system_labels::SENT),
+        MailboxRole::Drafts => Some(system_labels::DRAFT),
+        MailboxRole::Trash => Some(system_labels::TRASH),
+        MailboxRole::Junk => Some(system_labels::SPAM),
+        MailboxRole::Other => Some(mailbox.name.as_str()),
+        MailboxRole::Archive | MailboxRole::All | MailboxRole::Flagged => None,
+    }
+}
+
+/// The labels of a message stored in a mailbox with the given flags
+pub(crate) fn message_label_ids(mailbox: &Mailbox, flags: &[String]) -> Vec<String> {
+    let has_flag = |flag: &str| flags.iter().any(|f| f.eq_ignore_ascii_case(flag));
+
+    let mut label_ids: Vec<String> = mailbox_label(mailbox)
+        .map(str::to_string)
+        .into_iter()
+        .collect();
+
+    let flag_labels = [
+        (!has_flag(SEEN), system_labels::UNREAD),
+        (has_flag(FLAGGED), system_labels::STARRED),
+        (
+            IMPORTANT.iter().any(|flag| has_flag(flag)),
+            system_labels::IMPORTANT,
+        ),
+        (has_flag(DRAFT), system_labels::DRAFT),
+        (has_flag(JUNK), system_labels::SPAM),
+    ];
+
+    for (applies, label) in flag_labels {
+        if applies && !label_ids.iter().any(|id| id == label) {
+            label_ids.push(label.to_string());
+        }
+    }
+
+    label_ids
+}
+
+/// The IMAP operations that change the labels of a message
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct LabelChanges {
+    pub flags_to_add: Vec<&'static str>,
+    pub flags_to_remove: Vec<&'static str>,
+    /// Mailboxes the message is copied into, keeping it in its current mailbox
+    pub copy_to: Vec<String>,
+    /// The mailbox the message is moved into when the label of its current mailbox is removed
+    pub move_to: Option<String>,
+}
+
+/// Translates label changes of a message in `current` into flag changes, copies and moves.
+/// Removing the label of a mailbox the message was copied into is not supported because the
+/// copy is a separate message.
+pub(crate) fn label_changes(
+    mailboxes: &[Mailbox],
+    current: &str,
+    labels_to_add: &[String],
+    labels_to_remove: &[String],
+) -> anyhow::Result<LabelChanges> {
+    let mut changes = LabelChanges::default();
+
+    let flag_changes = |label: &str, add: bool| -> Option<(Vec<&'static str>, Vec<&'static str>)> {
+        match label {
+            // a message is unread when it is not seen
+            system_labels::UNREAD => Some(match add {
+                true => (vec![], vec![SEEN]),
+                false => (vec![SEEN], vec![]),
+            }),
+            system_labels::STARRED => Some(match add {
+                true => (vec![FLAGGED], vec![]),
+                false => (vec![], vec![FLAGGED]),
+            }),
+            system_labels::IMPORTANT => Some(match add {
+                true => (vec![IMPORTANT[0]], vec![]),
+                false => (vec![], IMPORTANT.to_vec()),
+            }),
+            _ => None,
+        }
+    };
+
+    let label_mailbox = |label: &str| {
+        mailboxes
+            .iter()
+            .find(|mailbox| mailbox_label(mailbox) == Some(label))
+            .map(|mailbox| mailbox.name.clone())
+            .with_context(|| format!("no mailbox for label {label}"))
+    };
+
+    for label in labels_to_add {
+        if let Some((add, remove)) = flag_changes(label.as_str(), true) {
+            changes.flags_to_add.extend(add);
+            changes.flags_to_remove.extend(remove);
+            continue;
+        }
+
+        let mailbox = label_mailbox(label)?;
+        if mailbox != current && !changes.copy_to.contains(&mailbox) {
+            changes.copy_to.push(mailbox);
+        }
+    }
+
+    let current_label = mailboxes
+        .iter()
+        .find(|mailbox| mailbox.name == current)
+        .and_then(mailbox_label);
+
+    for label in labels_to_remove {
+        if let Some((add, remove)) = flag_changes(label.as_str(), false) {
+            changes.flags_to_add.extend(add);
+            changes.flags_to_remove.extend(remove);
+            continue;
+        }
+
+        if current_label != Some(label.as_str()) || changes.move_to.is_some() {
+            continue;
+        }
+
+        // the message leaves its mailbox, into the first added label or else into the archive
+        changes.move_to = match changes.copy_to.is_empty() {
+            false => Some(changes.copy_to.remove(0)),
+            true => Some(
+                mailboxes
+                    .iter()
+                    .find(|mailbox| mailbox.role == MailboxRole::Archive)
+                    .map(|mailbox| mailbox.name.clone())
+                    .context("no archive mailbox to move the message into")?,
+            ),
+        };
+    }
+
+    Ok(changes)
+}
+
+/// Every label of the mailbox, system labels first
+pub(crate) fn user_labels(link_id: Uuid, mailboxes: &[Mailbox]) -> Vec<Label> {
+    let system = [
+        system_labels::INBOX,
+        system_labels::SENT,
+        system_labels::DRAFT,
+        system_labels::TRASH,
+        system_labels::SPAM,
+        system_labels::UNREAD,
+        system_labels::STARRED,
+        system_labels::IMPORTANT,
+    ]
+    .into_iter()
+    .map(|id| label(link_id, id, id, LabelType::System));
+
+    let user = mailboxes
+        .iter()
+        .filter(|mailbox| mailbox.role == MailboxRole::Other && mailbox.is_synced())
+        .map(|mailbox| {
+            // nested folders are shown like nested Gmail labels
+            let name = match mailbox.delimiter.as_deref() {
+                Some(delimiter) if delimiter != "/" => mailbox.name.replace(delimiter, "/"),
+                _ => mailbox.name.clone(),
+            };
+            label(link_id, &mailbox.name, &name, LabelType::User)
+        });
+
+    system.chain(user).collect()
+}
+
+/// The name of the mailbox that backs a new user label.
+/// Nested labels are separated by "/" and use the delimiter of the server instead.
+pub(crate) fn label_mailbox_name(mailboxes: &[Mailbox], label_name: &str) -> String {
+    let delimiter = mailboxes
+        .iter()
+        .find_map(|mailbox| mailbox.delimiter.as_deref())
+        .unwrap_or("/");
+    label_name.replace('/', delimiter)
+}
+
+/// The label of a user mailbox
+pub(crate) fn user_label(link_id: Uuid, mailbox_name: &str, label_name: &str) -> Label {
+    label(link_id, mailbox_name, label_name, LabelType::User)
+}
+
+fn label(link_id: Uuid, provider_label_id: &str, name: &str, type_: LabelType) -> Label {
+    Label {
+        id: None, // Generated at insert
+        link_id,
+        provider_label_id: provider_label_id.to_string(),
+        name: Some(name.to_string()),
+        created_at: chrono::Utc::now(),
+        message_list_visibility: Some(MessageListVisibility::Show),
+        label_list_visibility: Some(LabelListVisibility::LabelShow),
+        type_: Some(type_),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mailbox(name: &str, role: MailboxRole) -> Mailbox {
+        Mailbox {
+            name: name.to_string(),
+            delimiter: Some(".".to_string()),
+            role,
+            selectable: true,
+        }
+    }
+
+    #[test]
+    fn test_message_label_ids() {
+        let inbox = mailbox("INBOX", MailboxRole::Inbox);
+        assert_eq!(
+            message_label_ids(&inbox, &[]),
+            vec![system_labels::INBOX, system_labels::UNREAD]
+        );
+        assert_eq!(
+            message_label_ids(&inbox, &["\\Seen".to_string(), "\\Flagged".to_string()]),
+            vec![system_labels::INBOX, system_labels::STARRED]
+        );
+
+        let archive = mailbox("Archive", MailboxRole::Archive);
+        assert_eq!(
+            message_label_ids(&archive, &["\\Seen".to_string()]),
+            Vec::<String>::new()
+        );
+
+        let drafts = mailbox("Drafts", MailboxRole::Drafts);
+        assert_eq!(
+            message_label_ids(&drafts, &["\\Seen".to_string(), "\\Draft".to_string()]),
+            vec![system_labels::DRAFT]
+        );
+
+        let folder = mailbox("INBOX.Receipts", MailboxRole::Other);
+        assert_eq!(
+            message_label_ids(&folder, &["\\Seen".to_string(), "$Junk".to_string()]),
+            vec!["INBOX.Receipts", system_labels::SPAM]
+        );
+    }
+
+    #[test]
+    fn test_label_changes() -> anyhow::Result<()> {
+        let mailboxes = [
+            mailbox("INBOX", MailboxRole::Inbox),
+            mailbox("Archive", MailboxRole::Archive),
+            mailbox("INBOX.Receipts", MailboxRole::Other),
+        ];
+        let labels = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        // archiving and marking as read
+        let changes = label_changes(
+            &mailboxes,
+            "INBOX",
+            &[],
+            &labels(&[system_labels::INBOX, system_labels::UNREAD]),
+        )?;
+        assert_eq!(
+            changes,
+            LabelChanges {
+                flags_to_add: vec![SEEN],
+                move_to: Some("Archive".to_string()),
+                ..Default::default()
+            }
+        );
+
+        // labelling and starring
+        let changes = label_changes(
+            &mailboxes,
+            "INBOX",
+            &labels(&["INBOX.Receipts", system_labels::STARRED]),
+            &[],
+        )?;
+        assert_eq!(changes.copy_to, vec!["INBOX.Receipts"]);
+        assert_eq!(changes.flags_to_add, vec![FLAGGED]);
+        assert_eq!(changes.move_to, None);
+
+        // moving into a folder
+        let changes = label_changes(
+            &mailboxes,
+            "INBOX",
+            &labels(&["INBOX.Receipts"]),
+            &labels(&[system_labels::INBOX]),
+        )?;
+        assert!(changes.copy_to.is_empty());
+        assert_eq!(changes.move_to.as_deref(), Some("INBOX.Receipts"));
+
+        assert!(label_changes(&mailboxes, "INBOX", &labels(&["Missing"]), &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_label_mailbox_name() {
+        let mailboxes = [mailbox("INBOX", MailboxRole::Inbox)];
+        assert_eq!(
+            label_mailbox_name(&mailboxes, "Receipts/2025"),
+            "Receipts.2025"
+        );
+        assert_eq!(label_mailbox_name(&[], "Receipts/2025"), "Receipts/2025");
+    }
+
+    #[test]
+    fn test_user_labels() {
+        let link_id = Uuid::new_v4();
+        let labels = user_labels(
+            link_id,
+            &[
+                mailbox("INBOX", MailboxRole::Inbox),
+                mailbox("INBOX.Receipts", MailboxRole::Other),
+                mailbox("All Mail", MailboxRole::All),
+            ],
+        );
+
+        let user = labels
+            .iter()
+            .filter(|label| label.type_ == Some(LabelType::User))
+            .collect::<Vec<_>>();
+        assert_eq!(user.len(), 1);
+        assert_eq!(user[0].provider_label_id, "INBOX.Receipts");
+        assert_eq!(user[0].name.as_deref(), Some("INBOX/Receipts"));
+        assert!(
+            labels
+                .iter()
+                .any(|label| label.provider_label_id == system_labels::INBOX
+                    && label.type_ == Some(LabelType::System))
+        );
+    }
+}
@@ -0,0 +1,667 @@
+//! A mailbox accessed over IMAP for reading and SMTP for sending.
+//! Messages are converted into Gmail resources and mapped with the same parser as Gmail messages,
+//! mailboxes and flags are mapped onto labels by [labels].
+
+pub(crate) mod labels;
+pub(crate) mod parse;
+
+use crate::{LabelError, MailProvider};
+use anyhow::Context;
+use async_trait::async_trait;
+use imap_client::{
+    ImapClient, ImapMessageId, ImapSession, Mailbox, MailboxRole, SmtpClient, SyncState, UidSet,
+};
+use mail_builder::headers::address::Address;
+use mail_builder::headers::raw::Raw;
+use models_email::email::service::address::ContactInfo;
+use models_email::email::service::label::Label;
+use models_email::email::service::link::UserProvider;
+use models_email::email::service::message::{Message, MessageToSend};
+use models_email::email::service::thread::{Thread, ThreadList, ThreadSummary};
+use models_email::gmail::history::InboxChanges;
+use models_email::gmail::{MessageResource, ThreadResource};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// A mailbox accessed over IMAP and SMTP.
+/// Threads are identified by the first message id of the thread, see [parse::thread_id].
+pub struct ImapProvider {
+    imap: ImapClient,
+    smtp: SmtpClient,
+    /// The address messages are sent from
+    email_address: String,
+}
+
+/// A thread found while scanning the synced mailboxes
+struct ThreadEntry {
+    thread_id: String,
+    latest_internal_date: i64,
+}
+
+impl ImapProvider {
+    pub fn new(imap: ImapClient, smtp: SmtpClient, email_address: String) -> Self {
+        Self {
+            imap,
+            smtp,
+            email_address,
+        }
+    }
+
+    /// The selectable mailboxes whose messages are synced
+    async fn synced_mailboxes(session: &mut ImapSession) -> anyhow::Result<Vec<Mailbox>> {
+        Ok(session
+            .list_mailboxes()
+            .await?
+            .into_iter()
+            .filter(Mailbox::is_synced)
+            .collect())
+    }
+
+    /// Every thread in the synced mailboxes, most recent first.
+    /// Trash and junk are skipped like Gmail does when listing threads.
+    async fn list_all_threads(session: &mut ImapSession) -> anyhow::Result<Vec<ThreadEntry>> {
+        let mut threads: HashMap<String, i64> = HashMap::new();
+
+        for mailbox in Self::synced_mailboxes(session).await? {
+            if matches!(mailbox.role, MailboxRole::Trash | MailboxRole::Junk) {
+                continue;
+            }
+
+            let uids: UidSet = session
+                .search(&mailbox.name, "ALL")
+                .await?
+                .into_iter()
+                .collect();
+            let headers = session
+                .fetch_headers(&mailbox.name, &uids, &parse::THREADING_HEADERS)
+                .await?;
+
+            for message in headers {
+                let thread_id = parse::thread_id_from_headers(&message.body, &message.id);
+                let internal_date = message
+                    .internal_date
+                    .as_deref()
+                    .and_then(parse::parse_internal_date)
+                    .unwrap_or_default();
+
+                let latest = threads.entry(thread_id).or_insert(internal_date);
+                *latest = (*latest).max(internal_date);
+            }
+        }
+
+        let mut threads = threads
+            .into_iter()
+            .map(|(thread_id, latest_internal_date)| ThreadEntry {
+                thread_id,
+                latest_internal_date,
+            })
+            .collect::<Vec<_>>();
+        threads.sort_by(|a, b| {
+            b.latest_internal_date
+                .cmp(&a.latest_internal_date)
+                .then_with(|| a.thread_id.cmp(&b.thread_id))
+        });
+
+        Ok(threads)
+    }
+
+    /// Changes the labels of a message in an open session
+    async fn modify_labels(
+        session: &mut ImapSession,
+        mailboxes: &[Mailbox],
+        message_id: &str,
+        labels_to_add: &[String],
+        labels_to_remove: &[String],
+    ) -> anyhow::Result<()> {
+        let id: ImapMessageId = message_id.parse()?;
+        let changes =
+            labels::label_changes(mailboxes, &id.mailbox, labels_to_add, labels_to_remove)?;
+
+        // flags are changed first so copies and moves keep them
+        let exists = session
+            .store_flags(&id, true, &changes.flags_to_add)
+            .await?
+            && session
+                .store_flags(&id, false, &changes.flags_to_remove)
+                .await?;
+        anyhow::ensure!(exists, "message {message_id} not found");
+
+        for mailbox in &changes.copy_to {
+            session.copy_message(&id, mailbox).await?;
+        }
+        if let Some(mailbox) = &changes.move_to {
+            session.move_message(&id, mailbox).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The ids of the messages in the synced mailboxes that belong to the thread
+    async fn thread_message_ids(
+        session: &mut ImapSession,
+        mailboxes: &[Mailbox],
+        thread_id: &str,
+    ) -> anyhow::Result<Vec<ImapMessageId>> {
+        let quoted = format!(
+            "\"{}\"",
+            thread_id.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        let criteria = format!(
+            "OR OR HEADER Message-ID {quoted} HEADER References {quoted} HEADER In-Reply-To {quoted}"
+        );
+
+        let mut message_ids = Vec::new();
+        for mailbox in mailboxes {
+            let uids: UidSet = session
+                .search(&mailbox.name, &criteria)
+                .await?
+                .into_iter()
+                .collect();
+            // header searches match substrings so the thread id is checked
+            let headers = session
+                .fetch_headers(&mailbox.name, &uids, &parse::THREADING_HEADERS)
+                .await?;
+            message_ids.extend(
+                headers
+                    .into_iter()
+                    .filter(|message| {
+                        parse::thread_id_from_headers(&message.body, &message.id) == thread_id
+                    })
+                    .map(|message| message.id),
+            );
+        }
+
+        Ok(message_ids)
+    }
+
+    /// Fetches a message and converts it into a Gmail message resource
+    async fn message_resource(
+        session: &mut ImapSession,
+        mailboxes: &[Mailbox],
+        id: &ImapMessageId,
+    ) -> anyhow::Result<Option<MessageResource>> {
+        let Some(mailbox) = mailboxes.iter().find(|mailbox| mailbox.name == id.mailbox) else {
+            return Ok(None);
+        };
+        let Some(message) = session.fetch_message(id).await? else {
+            return Ok(None);
+        };
+
+        let label_ids = labels::message_label_ids(mailbox, &message.flags);
+        parse::message_resource(
+            id,
+            &message.body,
+            label_ids,
+            message.internal_date.as_deref(),
+        )
+        .map(Some)
+    }
+
+    fn build_message(
+        &self,
+        message: &MessageToSend,
+        from_contact: &ContactInfo,
+        message_id: &str,
+        parent_message_id: Option<String>,
+        references: Option<Vec<String>>,
+    ) -> anyhow::Result<Vec<u8>> {
+        // bcc recipients are only added to the envelope so they are not disclosed to others
+        let mut builder = mail_builder::MessageBuilder::new()
+            .message_id(message_id)
+            .from(contact_to_address(from_contact))
+            .to(contacts_to_address_list(&message.to))
+            .cc(contacts_to_address_list(&message.cc))
+            .subject(&message.subject);
+
+        if let Some(parent_message_id) = parent_message_id {
+            builder = builder.in_reply_to(parent_message_id);
+        }
+
+        if let Some(references) = references {
+            builder = builder.references(references);
+        }
+
+        for (name, value) in message.extra_headers() {
+            builder = builder.header(*name, Raw::new(*value));
+        }
+
+        if let Some(text_body) = &message.body_text {
+            builder = builder.text_body(text_body);
+        }
+
+        if let Some(html_body) = &message.body_html {
+            builder = builder.html_body(html_body);
+        }
+
+        builder.write_to_vec().context("building message error")
+    }
+}
+
+#[async_trait]
+impl MailProvider for ImapProvider {
+    fn provider(&self) -> UserProvider {
+        UserProvider::Imap
+    }
+
+    async fn get_total_threads(&self) -> anyhow::Result<i32> {
+        let mut session = self.imap.connect().await?;
+        let threads = Self::list_all_threads(&mut session).await?;
+        session.logout().await?;
+
+        Ok(i32::try_from(threads.len()).unwrap_or(i32::MAX))
+    }
+
+    async fn list_threads(
+        &self,
+        num_threads: u32,
+        next_page_token: Option<&str>,
+    ) -> anyhow::Result<ThreadList> {
+        // the page token is the offset into the threads, most recent first
+        let offset = next_page_token
+            .map(|token| token.parse::<usize>())
+            .transpose()
+            .context("invalid page token")?
+            .unwrap_or_default();
+
+        let mut session = self.imap.connect().await?;
+        let threads = Self::list_all_threads(&mut session).await?;
+        session.logout().await?;
+
+        let end = offset
+            .saturating_add(num_threads as usize)
+            .min(threads.len());
+        let page = threads
+            .get(offset..end)
+            .unwrap_or_default()
+            .iter()
+            .map(|thread| ThreadSummary {
+                provider_id: thread.thread_id.clone(),
+            })
+            .collect();
+
+        Ok(ThreadList {
+            threads: page,
+            next_page_token: (end < threads.len()).then(|| end.to_string()),
+        })
+    }
+
+    async fn get_message_ids_for_thread(&self, thread_id: &str) -> anyhow::Result<Vec<String>> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = Self::synced_mailboxes(&mut session).await?;
+        let message_ids = Self::thread_message_ids(&mut session, &mailboxes, thread_id).await?;
+        session.logout().await?;
+
+        Ok(message_ids.iter().map(ToString::to_string).collect())
+    }
+
+    async fn get_threads(
+        &self,
+        link_id: Uuid,
+        thread_ids: &[String],
+    ) -> anyhow::Result<Vec<Thread>> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = Self::synced_mailboxes(&mut session).await?;
+
+        let mut threads = Vec::with_capacity(thread_ids.len());
+        for thread_id in thread_ids {
+            let message_ids = Self::thread_message_ids(&mut session, &mailboxes, thread_id).await?;
+
+            let mut messages = Vec::with_capacity(message_ids.len());
+            for message_id in &message_ids {
+                if let Some(message) =
+                    Self::message_resource(&mut session, &mailboxes, message_id).await?
+                {
+                    messages.push(message);
+                }
+            }
+
+            if messages.is_empty() {
+                tracing::warn!(thread_id, "thread has no messages, skipping");
+                continue;
+            }
+
+            let thread = gmail_client::map_thread_resource_to_service(
+                ThreadResource {
+                    id: thread_id.clone(),
+                    messages,
+                },
+                link_id,
+            )
+            .await?;
+            threads.push(thread);
+        }
+
+        session.logout().await?;
+        Ok(threads)
+    }
+
+    async fn get_message(
+        &self,
+        message_id: &str,
+        link_id: Uuid,
+    ) -> anyhow::Result<Option<Message>> {
+        let id: ImapMessageId = message_id.parse()?;
+
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+        let resource = Self::message_resource(&mut session, &mailboxes, &id).await?;
+        session.logout().await?;
+
+        resource
+            .map(|resource| gmail_client::map_message_resource_to_service(resource, link_id))
+            .transpose()
+    }
+
+    async fn get_message_label_ids(
+        &self,
+        message_id: &str,
+        _link_id: Uuid,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let id: ImapMessageId = message_id.parse()?;
+
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+        let flags = match mailboxes.iter().any(|mailbox| mailbox.name == id.mailbox) {
+            true => session.fetch_flags(&id).await?,
+            false => None,
+        };
+        session.logout().await?;
+
+        Ok(flags.and_then(|flags| {
+            mailboxes
+                .iter()
+                .find(|mailbox| mailbox.name == id.mailbox)
+                .map(|mailbox| labels::message_label_ids(mailbox, &flags))
+        }))
+    }
+
+    async fn modify_message_labels(
+        &self,
+        message_id: &str,
+        labels_to_add: &[String],
+        labels_to_remove: &[String],
+    ) -> anyhow::Result<()> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+        Self::modify_labels(
+            &mut session,
+            &mailboxes,
+            message_id,
+            labels_to_add,
+            labels_to_remove,
+        )
+        .await?;
+        session.logout().await?;
+
+        Ok(())
+    }
+
+    async fn batch_modify_labels(
+        &self,
+        messages: Vec<(Uuid, String)>,
+        labels_to_add: Vec<String>,
+        labels_to_remove: Vec<String>,
+    ) -> (Vec<Uuid>, Vec<Uuid>) {
+        let mut successful_ids = Vec::new();
+        let mut failed_ids = Vec::new();
+
+        // servers limit the connections per user, so the whole batch shares one session
+        let session = async {
+            let mut session = self.imap.connect().await?;
+            let mailboxes = session.list_mailboxes().await?;
+            anyhow::Ok((session, mailboxes))
+        }
+        .await;
+
+        let (mut session, mailboxes) = match session {
+            Ok(session) => session,
+            Err(e) => {
+                tracing::error!(error=?e, "unable to connect to modify labels");
+                failed_ids.extend(messages.into_iter().map(|(db_id, _)| db_id));
+                return (successful_ids, failed_ids);
+            }
+        };
+
+        for (db_id, provider_id) in messages {
+            match Self::modify_labels(
+                &mut session,
+                &mailboxes,
+                &provider_id,
+                &labels_to_add,
+                &labels_to_remove,
+            )
+            .await
+            {
+                Ok(()) => successful_ids.push(db_id),
+                Err(e) => {
+                    tracing::error!(error=?e, message_id=%db_id, provider_id=%provider_id, "Failed to modify labels over imap");
+                    failed_ids.push(db_id);
+                }
+            }
+        }
+
+        if let Err(e) = session.logout().await {
+            tracing::debug!(error=?e, "logout failed");
+        }
+
+        (successful_ids, failed_ids)
+    }
+
+    async fn create_label(&self, link_id: Uuid, label_name: &str) -> Result<Label, LabelError> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+
+        let mailbox_name = labels::label_mailbox_name(&mailboxes, label_name);
+        if mailboxes.iter().any(|mailbox| mailbox.name == mailbox_name) {
+            return Err(LabelError::Conflict);
+        }
+
+        session.create_mailbox(&mailbox_name).await?;
+        session.logout().await?;
+
+        Ok(labels::user_label(link_id, &mailbox_name, label_name))
+    }
+
+    async fn delete_label(&self, provider_label_id: &str) -> Result<(), LabelError> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+
+        let mailbox = mailboxes
+            .iter()
+            .find(|mailbox| mailbox.name == provider_label_id)
+            .ok_or(LabelError::NotFound)?;
+        // system mailboxes back system labels which can't be deleted
+        if mailbox.role != MailboxRole::Other {
+            return Err(LabelError::Other(anyhow::anyhow!(
+                "mailbox {provider_label_id} is not a user label"
+            )));
+        }
+
+        session.delete_mailbox(provider_label_id).await?;
+        session.logout().await?;
+
+        Ok(())
+    }
+
+    async fn fetch_user_labels(&self, link_id: Uuid) -> anyhow::Result<Vec<Label>> {
+        let mut session = self.imap.connect().await?;
+        let mailboxes = session.list_mailboxes().await?;
+        session.logout().await?;
+
+        Ok(labels::user_labels(link_id, &mailboxes))
+    }
+
+    async fn get_current_history_id(&self) -> anyhow::Result<String> {
+        let mut session = self.imap.connect().await?;
+        let mut state = SyncState::default();
+        for mailbox in Self::synced_mailboxes(&mut session).await? {
+            let changes = session.sync_mailbox(&mailbox.name, None).await?;
+            state.mailboxes.insert(mailbox.name, changes.state);
+        }
+        session.logout().await?;
+
+        state.to_cursor()
+    }
+
+    async fn get_history(&self, start_history_id: &str) -> anyhow::Result<InboxChanges> {
+        let mut previous = SyncState::from_cursor(start_history_id)?;
+
+        let mut message_ids_to_upsert = HashSet::new();
+        let mut message_ids_to_delete = HashSet::new();
+        let mut labels_to_update = HashSet::new();
+        let mut state = SyncState::default();
+
+        let mut session = self.imap.connect().await?;
+        for mailbox in Self::synced_mailboxes(&mut session).await? {
+            let changes = session
+                .sync_mailbox(
+                    &mailbox.name,
+                    previous.mailboxes.remove(&mailbox.name).as_ref(),
+                )
+                .await?;
+
+            message_ids_to_upsert.extend(changes.new.iter().map(ToString::to_string));
+            labels_to_update.extend(changes.changed.iter().map(ToString::to_string));
+            message_ids_to_delete.extend(changes.vanished.iter().map(ToString::to_string));
+            state.mailboxes.insert(mailbox.name, changes.state);
+        }
+        session.logout().await?;
+
+        // the remaining mailboxes were deleted or are no longer synced
+        for (mailbox, mailbox_state) in previous.mailboxes {
+            message_ids_to_delete.extend(mailbox_state.uids.iter().map(|uid| {
+                ImapMessageId {
+                    mailbox: mailbox.clone(),
+                    uid_validity: mailbox_state.uid_validity,
+                    uid,
+                }
+                .to_string()
+            }));
+        }
+
+        Ok(InboxChanges {
+            message_ids_to_upsert,
+            message_ids_to_delete,
+            labels_to_update,
+            current_history_id: state.to_cursor()?,
+        })
+    }
+
+    async fn get_attachment_data(
+        &self,
+        message_id: &str,
+        attachment_id: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        let id: ImapMessageId = message_id.parse()?;
+
+        let mut session = self.imap.connect().await?;
+        let message = session.fetch_message(&id).await?;
+        session.logout().await?;
+
+        let message = message.with_context(|| format!("message {message_id} not found"))?;
+        parse::attachment_data(&message.body, attachment_id)
+    }
+
+    async fn send_message(
+        &self,
+        message: &mut MessageToSend,
+        from_contact: &ContactInfo,
+        parent_message_id: Option<String>,
+        references: Option<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        let domain = self
+            .email_address
+            .rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .unwrap_or("localhost");
+        let message_id = format!("{}@{}", Uuid::new_v4(), domain);
+
+        // same rule as parse::thread_id, the thread is identified by its first message
+        let thread_id = message.provider_thread_id.clone().unwrap_or_else(|| {
+            references
+                .as_ref()
+                .and_then(|references| references.first().cloned())
+                .or_else(|| parent_message_id.clone())
+                .map(|id| id.trim_matches(['<', '>']).to_string())
+                .unwrap_or_else(|| message_id.clone())
+        });
+
+        let raw = self.build_message(
+            message,
+            from_contact,
+            &message_id,
+            parent_message_id,
+            references,
+        )?;
+
+        let recipients = [&message.to, &message.cc, &message.bcc]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|contact| contact.email.clone())
+            .collect::<Vec<_>>();
+        self.smtp
+            .send_raw(&from_contact.email, &recipients, &raw)
+            .await?;
+
+        // SMTP does not store sent messages, they are appended to the sent mailbox like mail
+        // clients do
+        let mut session = self.imap.connect().await?;
+        let sent = session
+            .list_mailboxes()
+            .await?
+            .into_iter()
+            .find(|mailbox| mailbox.role == MailboxRole::Sent);
+
+        let provider_id = match sent {
+            Some(sent) => {
+                let appended = session.append(&sent.name, &["\\Seen"], &raw).await?;
+                match appended {
+                    Some(id) => Some(id),
+                    // without UIDPLUS the message is found by its Message-ID
+                    None => {
+                        let uid_validity = session.uid_validity(&sent.name).await?;
+                        session
+                            .search(&sent.name, &format!("HEADER Message-ID \"{message_id}\""))
+                            .await?
+                            .into_iter()
+                            .max()
+                            .map(|uid| ImapMessageId {
+                                mailbox: sent.name.clone(),
+                                uid_validity,
+                                uid,
+                            })
+                    }
+                }
+            }
+            None => {
+                tracing::warn!(link_id=%message.link_id, "no sent mailbox, sent message is not stored");
+                None
+            }
+        };
+        session.logout().await?;
+
+        message.provider_id = Some(provider_id.map(|id| id.to_string()).unwrap_or(message_id));
+        message.provider_thread_id = Some(thread_id);
+
+        Ok(())
+    }
+}
+
+fn contact_to_address(contact: &ContactInfo) -> Address<'_> {
+    match &contact.name {
+        Some(name) => Address::new_address(Some(name.as_str()), contact.email.as_str()),
+        None => Address::new_address(None::<&str>, contact.email.as_str()),
+    }
+}
+
+fn contacts_to_address_list(contacts: &Option<Vec<ContactInfo>>) -> Address<'_> {
+    let contacts = contacts.as_ref();
+    if contacts.is_none_or(|c| c.is_empty()) {
+        return Address::new_list(Vec::new());
+    }
+
+    let addresses: Vec<Address> = contacts.unwrap().iter().map(contact_to_address).collect();
+
+    Address::new_list(addresses)
+}
@@ -0,0 +1,318 @@
+//! Converts raw RFC 5322 messages into the Gmail message resource so they go through the same
+//! parsing, sanitizing and thread aggregation as messages fetched from Gmail.
+
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE;
+use chrono::DateTime;
+use imap_client::ImapMessageId;
+use mailparse::{MailHeader, MailHeaderMap, ParsedMail};
+use models_email::gmail::{Header, MessagePart, MessagePartBody, MessageResource};
+use std::fmt::Display;
+
+/// The number of characters of the text body used as the snippet
+const SNIPPET_LENGTH: usize = 200;
+
+/// The headers needed to group messages into threads
+pub(crate) const THREADING_HEADERS: [&str; 3] = ["MESSAGE-ID", "IN-REPLY-TO", "REFERENCES"];
+
+/// Builds the message resource for a raw message, e.g. one fetched over IMAP
+pub(crate) fn message_resource(
+    id: &impl Display,
+    raw: &[u8],
+    label_ids: Vec<String>,
+    internal_date: Option<&str>,
+) -> anyhow::Result<MessageResource> {
+    let parsed = mailparse::parse_mail(raw).context("Failed to parse message")?;
+
+    let internal_date_ms = internal_date
+        .and_then(parse_internal_date)
+        .or_else(|| {
+            parsed
+                .headers
+                .get_first_value("Date")
+                .and_then(|date| mailparse::dateparse(&date).ok())
+                .map(|secs| secs * 1000)
+        })
+        .unwrap_or_default();
+
+    Ok(MessageResource {
+        id: id.to_string(),
+        thread_id: thread_id(&parsed.headers, id),
+        label_ids,
+        snippet: snippet(&parsed),
+        size_estimate: raw.len() as u64,
+        // IMAP has no per message history, changes are tracked per mailbox
+        history_id: String::new(),
+        internal_date: internal_date_ms.to_string(),
+        payload: message_part(&parsed, String::new()),
+    })
+}
+
+/// The id of the thread a message belongs to: the first message id of its References header,
+/// falling back to the message it replies to and then to its own Message-ID.
+pub(crate) fn thread_id(headers: &[MailHeader<'_>], id: &impl Display) -> String {
+    ["References", "In-Reply-To", "Message-ID"]
+        .into_iter()
+        .find_map(|name| {
+            headers
+                .get_first_value(name)
+                .and_then(|value| message_ids(&value).into_iter().next())
+        })
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// The thread id of a message from its raw headers
+pub(crate) fn thread_id_from_headers(raw_headers: &[u8], id: &ImapMessageId) -> String {
+    match mailparse::parse_headers(raw_headers) {
+        Ok((headers, _)) => thread_id(&headers, id),
+        Err(e) => {
+            tracing::warn!(error=?e, message_id=%id, "unable to parse threading headers");
+            id.to_string()
+        }
+    }
+}
+
+/// The message ids in a header, without their angle brackets
+pub(crate) fn message_ids(value: &str) -> Vec<String> {
+    let bracketed = value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>').map(|(id, _)| id.trim()))
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    if !bracketed.is_empty() {
+        return bracketed;
+    }
+
+    value.split_whitespace().map(str::to_string).collect()
+}
+
+/// Extracts the content of an attachment. The attachment id is the part id of the attachment.
+pub(crate) fn attachment_data(raw: &[u8], attachment_id: &str) -> anyhow::Result<Vec<u8>> {
+    let parsed = mailparse::parse_mail(raw).context("Failed to parse message")?;
+
+    let mut part = &parsed;
+    for index in attachment_id.split('.').filter(|index| !index.is_empty()) {
+        let index: usize = index
+            .parse()
+            .with_context(|| format!("Invalid attachment id {attachment_id}"))?;
+        part = part
+            .subparts
+            .get(index)
+            .with_context(|| format!("Attachment {attachment_id} not found"))?;
+    }
+
+    part.get_body_raw()
+        .context("Failed to decode attachment body")
+}
+
+/// Parses an IMAP INTERNALDATE, e.g. ` 7-Feb-2025 14:03:11 +0100`, into milliseconds
+pub(crate) fn parse_internal_date(date: &str) -> Option<i64> {
+    DateTime::parse_from_str(date.trim(), "%d-%b-%Y %H:%M:%S %z")
+        .ok()
+        .map(|date| date.timestamp_millis())
+}
+
+fn snippet(parsed: &ParsedMail<'_>) -> String {
+    let Some(text) = find_text_body(parsed) else {
+        return String::new();
+    };
+
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(SNIPPET_LENGTH)
+        .collect()
+}
+
+fn find_text_body(part: &ParsedMail<'_>) -> Option<String> {
+    if part.subparts.is_empty() {
+        return (part.ctype.mimetype.eq_ignore_ascii_case("text/plain") && !is_attachment(part))
+            .then(|| part.get_body().ok())
+            .flatten();
+    }
+    part.subparts.iter().find_map(find_text_body)
+}
+
+fn is_attachment(part: &ParsedMail<'_>) -> bool {
+    let disposition = part.get_content_disposition();
+    let mime_type = part.ctype.mimetype.to_lowercase();
+
+    match disposition.disposition {
+        mailparse::DispositionType::Attachment => true,
+        mailparse::DispositionType::Inline => !mime_type.starts_with("text/"),
+        _ => !filename(part).is_empty() && !mime_type.starts_with("text/"),
+    }
+}
+
+fn filename(part: &ParsedMail<'_>) -> String {
+    part.get_content_disposition()
+        .params
+        .get("filename")
+        .or_else(|| part.ctype.params.get("name"))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Converts a MIME part into the Gmail representation. Part ids follow Gmail: the root part has
+/// an empty id and child ids are the indexes of the parts joined by dots.
+fn message_part(part: &ParsedMail<'_>, part_id: String) -> MessagePart {
+    let headers = part
+        .headers
+        .iter()
+        .map(|header| Header {
+            name: header.get_key(),
+            value: header.get_value(),
+        })
+        .collect();
+
+    let (body, parts) = if part.subparts.is_empty() {
+        (Some(part_body(part, &part_id)), None)
+    } else {
+        let parts = part
+            .subparts
+            .iter()
+            .enumerate()
+            .map(|(index, subpart)| {
+                let id = if part_id.is_empty() {
+                    index.to_string()
+                } else {
+                    format!("{part_id}.{index}")
+                };
+                message_part(subpart, id)
+            })
+            .collect();
+        (None, Some(parts))
+    };
+
+    MessagePart {
+        part_id,
+        mime_type: part.ctype.mimetype.clone(),
+        filename: filename(part),
+        headers,
+        body,
+        parts,
+    }
+}
+
+fn part_body(part: &ParsedMail<'_>, part_id: &str) -> MessagePartBody {
+    let size = part
+        .get_body_raw()
+        .map(|body| body.len())
+        .unwrap_or_default() as i64;
+
+    if is_attachment(part) {
+        return MessagePartBody {
+            attachment_id: Some(part_id.to_string()),
+            size,
+            data_base64: None,
+        };
+    }
+
+    // the text is decoded from its charset so the payload parser can read it as UTF-8
+    let data_base64 = part
+        .ctype
+        .mimetype
+        .to_lowercase()
+        .starts_with("text/")
+        .then(|| part.get_body().ok())
+        .flatten()
+        .map(|text| URL_SAFE.encode(text.as_bytes()));
+
+    MessagePartBody {
+        attachment_id: None,
+        size,
+        data_base64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"From: Alice <alice@example.com>\r\n\
+To: bob@example.com\r\n\
+Subject: =?UTF-8?B?UmU6IFF1YXJ0ZXJseSByZXBvcnQ=?=\r\n\
+Date: Fri, 07 Feb 2025 14:03:11 +0100\r\n\
+Message-ID: <reply@example.com>\r\n\
+In-Reply-To: <parent@example.com>\r\n\
+References: <root@example.com>\r\n <parent@example.com>\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"b1\"\r\n\
+\r\n\
+--b1\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+\r\n\
+Looks   good,\r\n\
+ship it\r\n\
+--b1\r\n\
+Content-Type: application/pdf; name=\"report.pdf\"\r\n\
+Content-Disposition: attachment; filename=\"report.pdf\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0xLjQ=\r\n\
+--b1--\r\n";
+
+    fn id() -> ImapMessageId {
+        ImapMessageId {
+            mailbox: "INBOX".to_string(),
+            uid_validity: 7,
+            uid: 42,
+        }
+    }
+
+    #[test]
+    fn test_message_resource() -> anyhow::Result<()> {
+        let resource = message_resource(
+            &id(),
+            MESSAGE,
+            vec!["INBOX".to_string()],
+            Some(" 7-Feb-2025 14:03:12 +0100"),
+        )?;
+
+        assert_eq!(resource.id, "7:42:INBOX");
+        assert_eq!(resource.thread_id, "root@example.com");
+        assert_eq!(resource.snippet, "Looks good, ship it");
+        assert_eq!(resource.internal_date, "1738933392000");
+
+        let subject = resource
+            .payload
+            .headers
+            .iter()
+            .find(|header| header.name == "Subject")
+            .map(|header| header.value.as_str());
+        assert_eq!(subject, Some("Re: Quarterly report"));
+
+        let parts = resource.payload.parts.context("no parts")?;
+        assert_eq!(parts[0].part_id, "0");
+        assert!(parts[0].body.as_ref().unwrap().data_base64.is_some());
+        assert_eq!(parts[1].filename, "report.pdf");
+        assert_eq!(
+            parts[1].body.as_ref().unwrap().attachment_id.as_deref(),
+            Some("1")
+        );
+
+        assert_eq!(attachment_data(MESSAGE, "1")?, b"%PDF-1.4");
+        assert!(attachment_data(MESSAGE, "5").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_thread_id_fallbacks() {
+        let thread_id = |raw: &str| thread_id_from_headers(raw.as_bytes(), &id());
+
+        assert_eq!(
+            thread_id("In-Reply-To: <parent@example.com>\r\nMessage-ID: <a@example.com>\r\n\r\n"),
+            "parent@example.com"
+        );
+        assert_eq!(
+            thread_id("Message-ID: <a@example.com>\r\n\r\n"),
+            "a@example.com"
+        );
+        assert_eq!(thread_id("Subject: no ids\r\n\r\n"), "7:42:INBOX");
+    }
+}
@@ -0,0 +1,119 @@
+//! The operations the email service needs from a mail provider, independent of whether the
+//! mailbox is accessed through the Gmail API or over IMAP and SMTP.
+//! A provider is built for a single link with its credentials already resolved.
+//! [archive] reads and writes mbox and .eml files with the same message parsing.
+
+pub mod archive;
+pub(crate) mod gmail;
+pub(crate) mod imap;
+
+pub use gmail::GmailProvider;
+pub use imap::ImapProvider;
+
+use async_trait::async_trait;
+use models_email::email::service::address::ContactInfo;
+use models_email::email::service::label::Label;
+use models_email::email::service::link::UserProvider;
+use models_email::email::service::message::{Message, MessageToSend};
+use models_email::email::service::thread::{Thread, ThreadList};
+use models_email::gmail::history::InboxChanges;
+use uuid::Uuid;
+
+/// Why a label could not be created or deleted
+#[derive(Debug, thiserror::Error)]
+pub enum LabelError {
+    #[error("label already exists")]
+    Conflict,
+
+    #[error("label not found")]
+    NotFound,
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[async_trait]
+pub trait MailProvider: Send + Sync {
+    /// The provider backing the mailbox
+    fn provider(&self) -> UserProvider;
+
+    /// The total number of threads in the mailbox
+    async fn get_total_threads(&self) -> anyhow::Result<i32>;
+
+    /// Lists the num_threads most recent threads, starting after next_page_token
+    async fn list_threads(
+        &self,
+        num_threads: u32,
+        next_page_token: Option<&str>,
+    ) -> anyhow::Result<ThreadList>;
+
+    /// Returns the provider ids of the messages belonging to the thread
+    async fn get_message_ids_for_thread(&self, thread_id: &str) -> anyhow::Result<Vec<String>>;
+
+    /// Fetches the threads and messages for the given thread ids
+    async fn get_threads(
+        &self,
+        link_id: Uuid,
+        thread_ids: &[String],
+    ) -> anyhow::Result<Vec<Thread>>;
+
+    /// Fetches a message by its provider id. Returns None if the message no longer exists.
+    async fn get_message(&self, message_id: &str, link_id: Uuid)
+    -> anyhow::Result<Option<Message>>;
+
+    /// Fetches the provider label ids of a message. Returns None if the message no longer exists.
+    async fn get_message_label_ids(
+        &self,
+        message_id: &str,
+        link_id: Uuid,
+    ) -> anyhow::Result<Option<Vec<String>>>;
+
+    /// Adds and removes labels of a message
+    async fn modify_message_labels(
+        &self,
+        message_id: &str,
+        labels_to_add: &[String],
+        labels_to_remove: &[String],
+    ) -> anyhow::Result<()>;
+
+    /// Adds and removes the same labels on a batch of messages, given as (db id, provider id).
+    /// Returns the db ids of the messages that were updated and of the ones that failed.
+    async fn batch_modify_labels(
+        &self,
+        messages: Vec<(Uuid, String)>,
+        labels_to_add: Vec<String>,
+        labels_to_remove: Vec<String>,
+    ) -> (Vec<Uuid>, Vec<Uuid>);
+
+    /// Creates a user label. Nested labels are separated by "/".
+    async fn create_label(&self, link_id: Uuid, label_name: &str) -> Result<Label, LabelError>;
+
+    /// Deletes a user label
+    async fn delete_label(&self, provider_label_id: &str) -> Result<(), LabelError>;
+
+    /// Fetches every label of the mailbox
+    async fn fetch_user_labels(&self, link_id: Uuid) -> anyhow::Result<Vec<Label>>;
+
+    /// Returns an opaque cursor for the current state of the mailbox.
+    /// For Gmail this is the history id.
+    async fn get_current_history_id(&self) -> anyhow::Result<String>;
+
+    /// Gets the changes to the mailbox since the state described by the cursor
+    async fn get_history(&self, start_history_id: &str) -> anyhow::Result<InboxChanges>;
+
+    /// Fetches the content of an attachment
+    async fn get_attachment_data(
+        &self,
+        message_id: &str,
+        attachment_id: &str,
+    ) -> anyhow::Result<Vec<u8>>;
+
+    /// Sends a message, setting its provider id and provider thread id
+    async fn send_message(
+        &self,
+        message: &mut MessageToSend,
+        from_contact: &ContactInfo,
+        parent_message_id: Option<String>,
+        references: Option<Vec<String>>,
+    ) -> anyhow::Result<()>;
+}
@@ -86,6 +86,9 @@ pub struct Channel {
     pub updated_at: chrono::DateTime<chrono::Utc>,
     /// id of the user who created the channel
     pub owner_id: String,
+    /// timestamp of when the channel was archived, archived channels are read-only
+    #[serde(default)]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -181,6 +184,9 @@ pub struct GetChannelMessageResponse {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GetMessageWithContextResponse {
     pub messages: Vec<Message>,
+    /// the first message the user hasn't read, when the context was opened at it
+    #[serde(default)]
+    pub first_unread_message_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -194,3 +200,49 @@ pub struct ChannelAttachmentTextRequest {
     pub since: Option<chrono::DateTime<chrono::Utc>>,
     pub limit: Option<i64>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type, ToSchema)]
+#[sqlx(type_name = "comms_moderation_action", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationActionType {
+    DeleteMessage,
+    PinMessage,
+    UnpinMessage,
+    LockThread,
+    UnlockThread,
+    SetSlowMode,
+    ArchiveChannel,
+    UnarchiveChannel,
+}
+
+/// A moderation action a channel admin took, recorded for the admins of the channel's
+/// organization
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerationAction {
+    pub id: Uuid,
+    pub channel_id: Uuid,
+    pub org_id: Option<i64>,
+    /// the admin who took the action
+    pub actor_id: String,
+    pub action: ModerationActionType,
+    pub target_message_id: Option<Uuid>,
+    /// the sender of the target message
+    pub target_user_id: Option<String>,
+    /// the id of a deleted message, or the new slow mode
+    pub details: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModerationActionsRequest {
+    pub org_id: i64,
+    /// only actions before this time, for paging
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetModerationActionsResponse {
+    /// newest first
+    pub actions: Vec<ModerationAction>,
+}
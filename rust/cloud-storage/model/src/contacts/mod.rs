@@ -19,6 +19,51 @@ pub struct CreateGroupMessageBody {
     pub group_id: Option<String>,
 }
 
+/// How two users interacted, each kind adds a different weight to their affinity
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionKind {
+    /// An email was sent between the users
+    Email,
+    /// The users are participants of the same channel
+    Channel,
+    /// A user shared a document with the other
+    DocumentShare,
+    /// A user mentioned the other
+    Mention,
+}
+
+impl InteractionKind {
+    pub const ALL: [InteractionKind; 4] = [
+        InteractionKind::Email,
+        InteractionKind::Channel,
+        InteractionKind::DocumentShare,
+        InteractionKind::Mention,
+    ];
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == kind)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InteractionKind::Email => "email",
+            InteractionKind::Channel => "channel",
+            InteractionKind::DocumentShare => "document_share",
+            InteractionKind::Mention => "mention",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordInteractionMessageBody {
+    /// The user that interacted
+    pub user_id: String,
+    /// The users they interacted with
+    pub counterparts: Vec<String>,
+    pub kind: InteractionKind,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type", content = "body")]
 pub enum Message {
@@ -28,4 +73,6 @@ pub enum Message {
     AddParticipants(AddParticipantsMessageBody),
     #[serde(rename = "create_group")]
     CreateGroup(CreateGroupMessageBody),
+    #[serde(rename = "record_interaction")]
+    RecordInteraction(RecordInteractionMessageBody),
 }
@@ -196,6 +196,9 @@ pub struct CreateDocumentRequest {
     pub id: Option<String>,
     /// The sha of the document.
     pub sha: String,
+    /// The size of the document in bytes.
+    /// The upload url only accepts a document of exactly this size.
+    pub size_bytes: i64,
     /// The name of the document without extension.
     pub document_name: String,
     /// Optional file type of the document.
@@ -62,6 +62,11 @@ pub struct FolderItem {
     pub relative_path: String,
     /// The sha of the file.
     pub sha: String,
+    /// The size of the file in bytes.
+    /// Required when the file is uploaded through a presigned url, which only accepts a file of
+    /// exactly this size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
 }
 
 pub struct Folder {
@@ -7,19 +7,25 @@ use strum::{Display, EnumDiscriminants, EnumString};
 use utoipa::ToSchema;
 mod device;
 mod metadata;
+mod preference;
 mod push;
 mod raw;
+mod schedule;
 mod unsubscribe;
+mod webhook;
 pub use device::*;
 pub use metadata::*;
+pub use preference::*;
 pub use push::*;
 pub use raw::*;
+pub use schedule::*;
 pub use unsubscribe::*;
 use uuid::Uuid;
+pub use webhook::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize, EnumDiscriminants, ToSchema)]
 #[strum_discriminants(name(NotificationEventType))]
-#[strum_discriminants(derive(Serialize, Deserialize, ToSchema, EnumString, Display))]
+#[strum_discriminants(derive(Serialize, Deserialize, ToSchema, EnumString, Display, Hash))]
 #[strum_discriminants(serde(rename_all = "snake_case"))]
 #[strum_discriminants(strum(serialize_all = "snake_case"))]
 #[serde(
@@ -46,6 +52,8 @@ pub enum NotificationEvent {
     ChannelMessageDocument(DocumentMentionMetadata),
     /// A new email has been sent to the user
     NewEmail(NewEmailMetadata),
+    /// Nobody replied to a thread the user asked to be reminded about
+    EmailFollowUp(NewEmailMetadata),
     /// A user was invited to a team
     InviteToTeam(InviteToTeamMetadata),
     /// A team invite was rejected
@@ -77,6 +85,7 @@ impl NotificationEvent {
             NotificationEvent::ChannelMessageReply(meta) => serde_json::to_value(meta).ok(),
             NotificationEvent::ChannelMessageDocument(meta) => serde_json::to_value(meta).ok(),
             NotificationEvent::NewEmail(meta) => serde_json::to_value(meta).ok(),
+            NotificationEvent::EmailFollowUp(meta) => serde_json::to_value(meta).ok(),
             NotificationEvent::InviteToTeam(meta) => serde_json::to_value(meta).ok(),
             NotificationEvent::RejectTeamInvite => None,
         }
@@ -117,6 +126,7 @@ impl NotificationEvent {
             ChannelMessageReply => deserialize_meta!(ChannelMessageReply),
             ChannelMessageDocument => deserialize_meta!(ChannelMessageDocument),
             NewEmail => deserialize_meta!(NewEmail),
+            EmailFollowUp => deserialize_meta!(EmailFollowUp),
             InviteToTeam => deserialize_meta!(InviteToTeam),
             RejectTeamInvite => match metadata {
                 None => Ok(Self::RejectTeamInvite),
@@ -126,6 +136,16 @@ impl NotificationEvent {
     }
 }
 
+impl NotificationEventType {
+    /// Whether the notification is about the user being mentioned
+    pub fn is_mention(&self) -> bool {
+        matches!(
+            self,
+            NotificationEventType::ChannelMention | NotificationEventType::DocumentMention
+        )
+    }
+}
+
 type TimestampOption = Option<chrono::DateTime<chrono::Utc>>;
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Default)]
@@ -181,7 +201,7 @@ impl NotificationEntity {
     impl_new_entity_with_type!(new_team, Team);
 }
 
-#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserNotification {
     /// The id of the notification. Self-generated uuidv7
@@ -237,7 +257,7 @@ pub struct Notification {
     pub notification_event: NotificationEvent,
 }
 
-#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationWithRecipient {
     #[serde(flatten)]
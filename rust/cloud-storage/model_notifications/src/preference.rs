@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use strum::{Display, EnumString};
+use utoipa::ToSchema;
+
+use crate::{NotificationEventType, UserNotificationSchedule};
+
+/// The ways a notification can reach a user
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    ToSchema,
+    Type,
+    EnumString,
+    Display,
+    Eq,
+    PartialEq,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_delivery_channel", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DeliveryChannel {
+    /// The notification inbox and live updates in the app
+    InApp,
+    Email,
+    Push,
+    /// The user's own endpoints, see [crate::NotificationWebhook]
+    Webhook,
+}
+
+/// How much a user wants to hear about a single channel, project or document
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ToSchema, Type, EnumString, Display, Eq, PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_entity_level", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum EntityNotificationLevel {
+    /// Every notification, as set by the user's event preferences
+    All,
+    /// Only notifications that mention the user
+    MentionsOnly,
+    /// No notifications at all
+    Mute,
+}
+
+/// How often a user's digest email goes out
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    ToSchema,
+    Type,
+    EnumString,
+    Display,
+    Eq,
+    PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_digest_cadence", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DigestCadence {
+    #[default]
+    Hourly,
+    Daily,
+}
+
+/// Whether the user gets an event type over a delivery channel. Anything not set is delivered.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    pub notification_event_type: NotificationEventType,
+    pub delivery_channel: DeliveryChannel,
+    pub enabled: bool,
+}
+
+/// The level a user picked for a single entity, which overrides their event preferences
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityNotificationPreference {
+    pub entity_id: String,
+    pub entity_type: String,
+    pub level: EntityNotificationLevel,
+}
+
+/// Everything that decides how a single user is notified
+#[derive(Debug, Clone, Default)]
+pub struct UserNotificationPreferences {
+    /// The user muted all notifications
+    pub muted: bool,
+    /// The user's email address is on the email unsubscribe list
+    pub email_unsubscribed: bool,
+    pub event_preferences: HashMap<(NotificationEventType, DeliveryChannel), bool>,
+    /// The level for each entity the user set one for, by entity id
+    pub entity_levels: HashMap<String, EntityNotificationLevel>,
+    /// The user's quiet hours, if they set any
+    pub schedule: Option<UserNotificationSchedule>,
+}
+
+impl UserNotificationPreferences {
+    /// Whether the user should get a notification of the given type, about the given entity, over
+    /// the delivery channel. Senders still decide which event types they deliver at all.
+    pub fn allows(
+        &self,
+        event_type: NotificationEventType,
+        event_item_id: &str,
+        delivery_channel: DeliveryChannel,
+    ) -> bool {
+        if self.muted {
+            return false;
+        }
+
+        match self.entity_levels.get(event_item_id) {
+            Some(EntityNotificationLevel::Mute) => return false,
+            Some(EntityNotificationLevel::MentionsOnly) if !event_type.is_mention() => {
+                return false;
+            }
+            _ => (),
+        }
+
+        if delivery_channel == DeliveryChannel::Email && self.email_unsubscribed {
+            return false;
+        }
+
+        self.event_preferences
+            .get(&(event_type, delivery_channel))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// When push and email delivery of the event should happen, or None if it can happen now.
+    /// In-app delivery is never held.
+    pub fn held_until(&self, event_type: NotificationEventType) -> Option<DateTime<Utc>> {
+        self.schedule
+            .as_ref()
+            .and_then(|schedule| schedule.held_until(event_type))
+    }
+
+    /// Whether the user should get the notification over any delivery channel
+    pub fn allows_any(&self, event_type: NotificationEventType, event_item_id: &str) -> bool {
+        [
+            DeliveryChannel::InApp,
+            DeliveryChannel::Email,
+            DeliveryChannel::Push,
+            DeliveryChannel::Webhook,
+        ]
+        .into_iter()
+        .any(|channel| self.allows(event_type, event_item_id, channel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANNEL: &str = "channel";
+
+    fn preferences(
+        configure: impl FnOnce(&mut UserNotificationPreferences),
+    ) -> UserNotificationPreferences {
+        let mut preferences = UserNotificationPreferences::default();
+        configure(&mut preferences);
+        preferences
+    }
+
+    fn entity_level(level: EntityNotificationLevel) -> UserNotificationPreferences {
+        preferences(|p| {
+            p.entity_levels.insert(CHANNEL.to_string(), level);
+        })
+    }
+
+    #[test]
+    fn test_allows() {
+        use DeliveryChannel::*;
+        use NotificationEventType::*;
+
+        let cases = [
+            // nothing set, so everything is delivered
+            (
+                UserNotificationPreferences::default(),
+                ChannelMessageSend,
+                Email,
+                true,
+            ),
+            (
+                UserNotificationPreferences::default(),
+                ChannelMention,
+                Push,
+                true,
+            ),
+            // muted users get nothing
+            (
+                preferences(|p| p.muted = true),
+                ChannelMessageSend,
+                InApp,
+                false,
+            ),
+            (preferences(|p| p.muted = true), ChannelMention, Push, false),
+            // a muted entity gets nothing, other entities are unaffected
+            (
+                entity_level(EntityNotificationLevel::Mute),
+                ChannelMention,
+                InApp,
+                false,
+            ),
+            (
+                entity_level(EntityNotificationLevel::All),
+                ChannelMessageSend,
+                Push,
+                true,
+            ),
+            // mentions only lets mentions through
+            (
+                entity_level(EntityNotificationLevel::MentionsOnly),
+                ChannelMessageSend,
+                InApp,
+                false,
+            ),
+            (
+                entity_level(EntityNotificationLevel::MentionsOnly),
+                ChannelMessageReply,
+                Push,
+                false,
+            ),
+            (
+                entity_level(EntityNotificationLevel::MentionsOnly),
+                ChannelMention,
+                Push,
+                true,
+            ),
+            // unsubscribing from email only stops email
+            (
+                preferences(|p| p.email_unsubscribed = true),
+                ChannelMention,
+                Email,
+                false,
+            ),
+            (
+                preferences(|p| p.email_unsubscribed = true),
+                ChannelMention,
+                Push,
+                true,
+            ),
+            // event preferences are per delivery channel
+            (
+                preferences(|p| {
+                    p.event_preferences
+                        .insert((ChannelMessageSend, Push), false);
+                }),
+                ChannelMessageSend,
+                Push,
+                false,
+            ),
+            (
+                preferences(|p| {
+                    p.event_preferences
+                        .insert((ChannelMessageSend, Push), false);
+                }),
+                ChannelMessageSend,
+                Email,
+                true,
+            ),
+        ];
+
+        for (preferences, event_type, delivery_channel, expected) in cases {
+            assert_eq!(
+                preferences.allows(event_type, CHANNEL, delivery_channel),
+                expected,
+                "{:?} {:?} {:?}",
+                preferences,
+                event_type,
+                delivery_channel
+            );
+        }
+    }
+
+    #[test]
+    fn test_entity_levels_only_apply_to_their_entity() {
+        let preferences = entity_level(EntityNotificationLevel::Mute);
+
+        assert!(!preferences.allows_any(NotificationEventType::ChannelMention, CHANNEL));
+        assert!(preferences.allows_any(NotificationEventType::ChannelMention, "other"));
+    }
+}
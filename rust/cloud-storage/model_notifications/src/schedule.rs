@@ -0,0 +1,442 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::NotificationEventType;
+
+/// How many back to back quiet periods we follow when working out when one ends
+const MAX_CHAINED_QUIET_PERIODS: usize = 16;
+
+/// A window of time that repeats each day, in the user's timezone. A window that ends before it
+/// starts runs past midnight.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalTimeWindow {
+    #[schema(value_type = String, example = "22:00:00")]
+    pub start: NaiveTime,
+    #[schema(value_type = String, example = "07:00:00")]
+    pub end: NaiveTime,
+    /// The days the window starts on. Every day if empty
+    #[serde(default)]
+    #[schema(value_type = Vec<String>, example = json!(["Mon", "Tue"]))]
+    pub days: Vec<Weekday>,
+}
+
+impl LocalTimeWindow {
+    fn starts_on(&self, date: NaiveDate) -> bool {
+        self.days.is_empty() || self.days.contains(&date.weekday())
+    }
+
+    fn end_for_start(&self, start_date: NaiveDate) -> Option<NaiveDateTime> {
+        if self.end > self.start {
+            Some(start_date.and_time(self.end))
+        } else {
+            Some(start_date.succ_opt()?.and_time(self.end))
+        }
+    }
+
+    /// The end of the window if the time is inside it
+    fn end_if_within(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        // A window that runs past midnight may have started the day before
+        [Some(time.date()), time.date().pred_opt()]
+            .into_iter()
+            .flatten()
+            .filter(|start_date| self.starts_on(*start_date))
+            .find_map(|start_date| {
+                let start = start_date.and_time(self.start);
+                let end = self.end_for_start(start_date)?;
+                (start <= time && time < end).then_some(end)
+            })
+    }
+
+    /// The next time the window starts after the time
+    fn next_start(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..=7)
+            .filter_map(|days| time.date().checked_add_days(Days::new(days)))
+            .filter(|date| self.starts_on(*date))
+            .map(|date| date.and_time(self.start))
+            .find(|start| *start > time)
+    }
+}
+
+/// When a user wants push and email notifications held back. In-app notifications are never held.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSchedule {
+    /// IANA timezone name the windows are in, e.g. "America/New_York"
+    pub timezone: String,
+    /// Notifications are held outside of working hours
+    pub working_hours: Option<LocalTimeWindow>,
+    /// Notifications are held during these windows
+    #[serde(default)]
+    pub quiet_windows: Vec<LocalTimeWindow>,
+    /// Notifications are held until this time
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[schema(value_type = Option<i64>)]
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Mentions are delivered even when notifications are held
+    pub mentions_break_through: bool,
+}
+
+impl Default for NotificationSchedule {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            working_hours: None,
+            quiet_windows: Vec::new(),
+            paused_until: None,
+            mentions_break_through: true,
+        }
+    }
+}
+
+impl NotificationSchedule {
+    /// The end of whichever quiet period the local time is in, if any
+    fn local_quiet_end(&self, local_time: NaiveDateTime) -> Option<NaiveDateTime> {
+        let outside_working_hours = self.working_hours.as_ref().and_then(|working_hours| {
+            match working_hours.end_if_within(local_time) {
+                Some(_) => None,
+                None => working_hours.next_start(local_time),
+            }
+        });
+
+        self.quiet_windows
+            .iter()
+            .filter_map(|window| window.end_if_within(local_time))
+            .chain(outside_working_hours)
+            .max()
+    }
+}
+
+/// A user's schedule along with the time it was read at, in UTC and in their timezone
+#[derive(Debug, Clone)]
+pub struct UserNotificationSchedule {
+    pub schedule: NotificationSchedule,
+    pub now: DateTime<Utc>,
+    pub local_now: NaiveDateTime,
+}
+
+impl UserNotificationSchedule {
+    /// When the user's current quiet period ends, or None if they can be notified now.
+    /// Times are moved between UTC and local time using the user's current offset, so a quiet
+    /// period spanning a daylight saving change ends up to an hour off.
+    pub fn quiet_until(&self) -> Option<DateTime<Utc>> {
+        let offset = self.local_now - self.now.naive_utc();
+
+        let mut until = self
+            .schedule
+            .paused_until
+            .filter(|paused_until| *paused_until > self.now)
+            .map(|paused_until| paused_until.naive_utc() + offset)
+            .unwrap_or(self.local_now);
+
+        // Quiet periods can overlap or follow on from each other
+        for _ in 0..MAX_CHAINED_QUIET_PERIODS {
+            match self.schedule.local_quiet_end(until) {
+                Some(end) if end > until => until = end,
+                _ => break,
+            }
+        }
+
+        (until > self.local_now).then(|| (until - offset).and_utc())
+    }
+
+    /// When push and email delivery of the event should happen, or None if it can happen now
+    pub fn held_until(&self, event_type: NotificationEventType) -> Option<DateTime<Utc>> {
+        if self.schedule.mentions_break_through && event_type.is_mention() {
+            return None;
+        }
+
+        self.quiet_until()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(date: &str, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn utc(date: &str, hour: u32, minute: u32) -> DateTime<Utc> {
+        local(date, hour, minute).and_utc()
+    }
+
+    fn window(start: u32, end: u32, days: &[Weekday]) -> LocalTimeWindow {
+        LocalTimeWindow {
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            days: days.to_vec(),
+        }
+    }
+
+    fn schedule(configure: impl FnOnce(&mut NotificationSchedule)) -> NotificationSchedule {
+        let mut schedule = NotificationSchedule::default();
+        configure(&mut schedule);
+        schedule
+    }
+
+    /// The schedule read at the local time, in a timezone the given minutes off UTC
+    fn read_at(
+        schedule: NotificationSchedule,
+        local_now: NaiveDateTime,
+        offset_minutes: i64,
+    ) -> UserNotificationSchedule {
+        UserNotificationSchedule {
+            schedule,
+            now: (local_now - chrono::Duration::minutes(offset_minutes)).and_utc(),
+            local_now,
+        }
+    }
+
+    // 2026-10-19 is a Monday
+    const MON: &str = "2026-10-19";
+    const TUE: &str = "2026-10-20";
+    const FRI: &str = "2026-10-23";
+    const SAT: &str = "2026-10-24";
+    const SUN: &str = "2026-10-25";
+    const NEXT_MON: &str = "2026-10-26";
+
+    #[test]
+    fn test_end_if_within() {
+        let day = window(9, 17, &[]);
+        assert_eq!(day.end_if_within(local(MON, 9, 0)), Some(local(MON, 17, 0)));
+        assert_eq!(
+            day.end_if_within(local(MON, 12, 0)),
+            Some(local(MON, 17, 0))
+        );
+        assert_eq!(day.end_if_within(local(MON, 8, 59)), None);
+        assert_eq!(day.end_if_within(local(MON, 17, 0)), None);
+
+        // a window that crosses midnight ends the day after it starts
+        let night = window(22, 7, &[]);
+        assert_eq!(
+            night.end_if_within(local(MON, 23, 0)),
+            Some(local(TUE, 7, 0))
+        );
+        assert_eq!(
+            night.end_if_within(local(TUE, 3, 0)),
+            Some(local(TUE, 7, 0))
+        );
+        assert_eq!(night.end_if_within(local(TUE, 7, 0)), None);
+        assert_eq!(night.end_if_within(local(MON, 21, 0)), None);
+
+        // the days are the days the window starts on
+        let friday_night = window(22, 7, &[Weekday::Fri]);
+        assert_eq!(
+            friday_night.end_if_within(local(FRI, 23, 0)),
+            Some(local(SAT, 7, 0))
+        );
+        assert_eq!(
+            friday_night.end_if_within(local(SAT, 2, 0)),
+            Some(local(SAT, 7, 0))
+        );
+        assert_eq!(friday_night.end_if_within(local(SAT, 23, 0)), None);
+        assert_eq!(friday_night.end_if_within(local(SUN, 2, 0)), None);
+    }
+
+    #[test]
+    fn test_next_start() {
+        let night = window(22, 7, &[]);
+        assert_eq!(night.next_start(local(MON, 12, 0)), Some(local(MON, 22, 0)));
+        assert_eq!(night.next_start(local(MON, 22, 0)), Some(local(TUE, 22, 0)));
+        assert_eq!(night.next_start(local(TUE, 3, 0)), Some(local(TUE, 22, 0)));
+
+        let monday_night = window(22, 7, &[Weekday::Mon]);
+        assert_eq!(
+            monday_night.next_start(local(TUE, 10, 0)),
+            Some(local(NEXT_MON, 22, 0))
+        );
+        assert_eq!(
+            monday_night.next_start(local(MON, 23, 0)),
+            Some(local(NEXT_MON, 22, 0))
+        );
+    }
+
+    #[test]
+    fn test_local_quiet_end() {
+        assert_eq!(
+            NotificationSchedule::default().local_quiet_end(local(MON, 23, 0)),
+            None
+        );
+
+        let weekdays = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        let working_hours = schedule(|s| s.working_hours = Some(window(9, 17, &weekdays)));
+        assert_eq!(working_hours.local_quiet_end(local(MON, 12, 0)), None);
+        assert_eq!(
+            working_hours.local_quiet_end(local(MON, 18, 0)),
+            Some(local(TUE, 9, 0))
+        );
+        assert_eq!(
+            working_hours.local_quiet_end(local(TUE, 8, 0)),
+            Some(local(TUE, 9, 0))
+        );
+        // the weekend is outside of working hours
+        assert_eq!(
+            working_hours.local_quiet_end(local(FRI, 18, 0)),
+            Some(local(NEXT_MON, 9, 0))
+        );
+        assert_eq!(
+            working_hours.local_quiet_end(local(SAT, 12, 0)),
+            Some(local(NEXT_MON, 9, 0))
+        );
+
+        // overlapping windows end with the later of the two
+        let overlapping =
+            schedule(|s| s.quiet_windows = vec![window(22, 7, &[]), window(6, 8, &[])]);
+        assert_eq!(
+            overlapping.local_quiet_end(local(TUE, 6, 30)),
+            Some(local(TUE, 8, 0))
+        );
+        assert_eq!(
+            overlapping.local_quiet_end(local(TUE, 3, 0)),
+            Some(local(TUE, 7, 0))
+        );
+        assert_eq!(overlapping.local_quiet_end(local(TUE, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_quiet_until() {
+        let nights = schedule(|s| s.quiet_windows = vec![window(22, 7, &[])]);
+
+        assert_eq!(
+            read_at(nights.clone(), local(MON, 12, 0), 0).quiet_until(),
+            None
+        );
+        assert_eq!(
+            read_at(nights.clone(), local(MON, 23, 0), 0).quiet_until(),
+            Some(utc(TUE, 7, 0))
+        );
+        assert_eq!(
+            read_at(nights.clone(), local(TUE, 3, 0), 0).quiet_until(),
+            Some(utc(TUE, 7, 0))
+        );
+
+        // windows are in local time, the result is in UTC
+        let new_york = -4 * 60;
+        assert_eq!(
+            read_at(nights.clone(), local(MON, 23, 0), new_york).quiet_until(),
+            Some(utc(TUE, 11, 0))
+        );
+        assert_eq!(
+            read_at(nights.clone(), local(MON, 20, 0), new_york).quiet_until(),
+            None
+        );
+        let kolkata = 5 * 60 + 30;
+        assert_eq!(
+            read_at(nights.clone(), local(MON, 23, 0), kolkata).quiet_until(),
+            Some(utc(TUE, 1, 30))
+        );
+
+        // a quiet window that runs into time outside of working hours lasts until work starts
+        let chained = schedule(|s| {
+            s.quiet_windows = vec![window(22, 7, &[])];
+            s.working_hours = Some(window(9, 17, &[]));
+        });
+        assert_eq!(
+            read_at(chained, local(MON, 23, 0), 0).quiet_until(),
+            Some(utc(TUE, 9, 0))
+        );
+    }
+
+    #[test]
+    fn test_quiet_until_across_daylight_saving() {
+        let nights = schedule(|s| s.quiet_windows = vec![window(22, 7, &[])]);
+
+        // New York springs forward at 2am on 2026-03-08, so 7am that morning is 11:00 UTC. The
+        // offset from when the schedule was read is used, which ends the period an hour late.
+        let est = -5 * 60;
+        assert_eq!(
+            read_at(nights.clone(), local("2026-03-07", 23, 0), est).quiet_until(),
+            Some(utc("2026-03-08", 12, 0))
+        );
+
+        // and falls back at 2am on 2026-11-01, so 7am that morning is 12:00 UTC. The period ends
+        // an hour early.
+        let edt = -4 * 60;
+        assert_eq!(
+            read_at(nights, local("2026-10-31", 23, 0), edt).quiet_until(),
+            Some(utc("2026-11-01", 11, 0))
+        );
+    }
+
+    #[test]
+    fn test_quiet_until_paused() {
+        let paused = |until| schedule(|s| s.paused_until = Some(until));
+
+        assert_eq!(
+            read_at(paused(utc(MON, 15, 0)), local(MON, 12, 0), 0).quiet_until(),
+            Some(utc(MON, 15, 0))
+        );
+        // a pause that already ended is ignored
+        assert_eq!(
+            read_at(paused(utc(MON, 11, 0)), local(MON, 12, 0), 0).quiet_until(),
+            None
+        );
+
+        let paused_nights = |until| {
+            schedule(|s| {
+                s.paused_until = Some(until);
+                s.quiet_windows = vec![window(22, 7, &[])];
+            })
+        };
+        // a pause that ends during quiet hours lasts until they end
+        assert_eq!(
+            read_at(paused_nights(utc(MON, 23, 0)), local(MON, 20, 0), 0).quiet_until(),
+            Some(utc(TUE, 7, 0))
+        );
+        // a pause that ends after quiet hours outlasts them
+        assert_eq!(
+            read_at(paused_nights(utc(TUE, 10, 0)), local(MON, 23, 0), 0).quiet_until(),
+            Some(utc(TUE, 10, 0))
+        );
+        // a pause is in UTC while the windows are in local time, 02:30 UTC is 22:30 in New York
+        assert_eq!(
+            read_at(paused_nights(utc(TUE, 2, 30)), local(MON, 20, 0), -4 * 60).quiet_until(),
+            Some(utc(TUE, 11, 0))
+        );
+        assert_eq!(
+            read_at(paused_nights(utc(TUE, 1, 0)), local(MON, 20, 0), -4 * 60).quiet_until(),
+            Some(utc(TUE, 1, 0))
+        );
+    }
+
+    #[test]
+    fn test_held_until() {
+        let nights = |mentions_break_through| {
+            schedule(|s| {
+                s.quiet_windows = vec![window(22, 7, &[])];
+                s.mentions_break_through = mentions_break_through;
+            })
+        };
+
+        let held = read_at(nights(true), local(MON, 23, 0), 0);
+        assert_eq!(
+            held.held_until(NotificationEventType::ChannelMessageSend),
+            Some(utc(TUE, 7, 0))
+        );
+        assert_eq!(held.held_until(NotificationEventType::ChannelMention), None);
+
+        let held = read_at(nights(false), local(MON, 23, 0), 0);
+        assert_eq!(
+            held.held_until(NotificationEventType::ChannelMention),
+            Some(utc(TUE, 7, 0))
+        );
+
+        let not_held = read_at(nights(false), local(MON, 12, 0), 0);
+        assert_eq!(
+            not_held.held_until(NotificationEventType::ChannelMessageSend),
+            None
+        );
+    }
+}
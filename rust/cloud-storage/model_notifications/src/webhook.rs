@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use strum::{Display, EnumString};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The shape of the JSON posted to a webhook
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    ToSchema,
+    Type,
+    EnumString,
+    Display,
+    Eq,
+    PartialEq,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_webhook_format", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookFormat {
+    /// The notification as the app receives it
+    #[default]
+    Macro,
+    /// A message a Slack incoming webhook can post
+    Slack,
+}
+
+/// An endpoint a user receives their notifications at
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationWebhook {
+    pub id: Uuid,
+    pub url: String,
+    pub format: WebhookFormat,
+    pub created_at: DateTime<Utc>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    /// Deliveries failed in a row since the last one that succeeded
+    pub consecutive_failures: i32,
+    /// Set once too many deliveries in a row fail. Nothing is delivered until it is enabled again.
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+/// The outcome of delivering a notification to a webhook, after any retries
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub notification_id: Uuid,
+    pub attempts: i32,
+    /// The status of the last attempt, if the endpoint responded
+    pub status_code: Option<i32>,
+    /// Why the last attempt failed, None if the delivery succeeded
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -14,6 +14,8 @@ pub struct S3ObjectInfo {
 #[serde(rename_all = "camelCase")]
 pub struct UploadExtractFolderRequest {
     pub sha: String,
+    /// The size of the zip in bytes, the upload url only accepts a zip of exactly this size
+    pub size_bytes: i64,
     pub name: Option<String>,
     pub parent_id: Option<String>,
 }
@@ -10,12 +10,14 @@ use uuid::Uuid;
 #[serde(rename_all = "UPPERCASE")]
 pub enum UserProvider {
     Gmail,
+    Imap,
 }
 
 impl UserProvider {
     pub fn as_str(&self) -> &'static str {
         match self {
             UserProvider::Gmail => "GMAIL",
+            UserProvider::Imap => "IMAP",
         }
     }
 }
@@ -30,6 +32,7 @@ impl From<crate::email::service::link::UserProvider> for UserProvider {
     fn from(provider: crate::email::service::link::UserProvider) -> Self {
         match provider {
             crate::email::service::link::UserProvider::Gmail => UserProvider::Gmail,
+            crate::email::service::link::UserProvider::Imap => UserProvider::Imap,
         }
     }
 }
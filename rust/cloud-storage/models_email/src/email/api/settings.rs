@@ -4,12 +4,16 @@ use utoipa::ToSchema;
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Settings {
     pub signature_on_replies_forwards: Option<bool>,
+    /// how many seconds sent messages can be undone for, 0 to send immediately. messages in the
+    /// outbox are picked up every minute, so they are sent up to a minute after this passes
+    pub undo_send_seconds: Option<i32>,
 }
 
 impl From<crate::email::service::settings::Settings> for Settings {
     fn from(service_settings: crate::email::service::settings::Settings) -> Self {
         Settings {
             signature_on_replies_forwards: Some(service_settings.signature_on_replies_forwards),
+            undo_send_seconds: Some(service_settings.undo_send_seconds),
         }
     }
 }
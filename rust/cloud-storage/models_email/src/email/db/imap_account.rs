@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Enum for how a connection to a mail server is secured
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "email_connection_security_enum", rename_all = "UPPERCASE")]
+pub enum ConnectionSecurity {
+    Tls,
+    StartTls,
+    None,
+}
+
+// Struct for the email_imap_accounts table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImapAccount {
+    pub link_id: Uuid,
+    pub imap_host: String,
+    pub imap_port: i32,
+    pub imap_security: ConnectionSecurity,
+    pub smtp_host: String,
+    pub smtp_port: i32,
+    pub smtp_security: ConnectionSecurity,
+    pub username: String,
+    // the id of the secret holding the password, never the password itself
+    pub password_secret_id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
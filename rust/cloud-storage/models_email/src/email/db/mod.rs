@@ -3,9 +3,12 @@ pub mod attachment;
 pub mod backfill;
 pub mod cache;
 pub mod contact;
+pub mod imap_account;
 pub mod label;
 pub mod link;
 pub mod message;
+pub mod rule;
+pub mod template;
 
 pub mod settings;
 pub mod sync_token;
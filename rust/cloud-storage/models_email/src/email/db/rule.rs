@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+// Struct for the email_rules table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Rule {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub name: String,
+    pub is_enabled: bool,
+    pub position: i32,
+    pub match_all: bool,
+    pub stop_processing: bool,
+    // serialized Vec<service::rule::RuleCondition>
+    pub conditions: JsonValue,
+    // serialized Vec<service::rule::RuleAction>
+    pub actions: JsonValue,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
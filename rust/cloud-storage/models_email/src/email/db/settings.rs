@@ -6,6 +6,7 @@ use uuid::Uuid;
 pub struct Settings {
     pub link_id: Uuid,
     pub signature_on_replies_forwards: bool,
+    pub undo_send_seconds: i32,
 }
 
 impl From<crate::email::service::settings::Settings> for Settings {
@@ -13,6 +14,7 @@ impl From<crate::email::service::settings::Settings> for Settings {
         Settings {
             link_id: service_settings.link_id,
             signature_on_replies_forwards: service_settings.signature_on_replies_forwards,
+            undo_send_seconds: service_settings.undo_send_seconds,
         }
     }
 }
@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+// Struct for the email_templates table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Template {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub team_id: Option<Uuid>,
+    pub name: String,
+    pub subject: Option<String>,
+    pub body_html: String,
+    pub body_text: Option<String>,
+    // serialized Vec<service::template::TemplateVariable>
+    pub variables: JsonValue,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
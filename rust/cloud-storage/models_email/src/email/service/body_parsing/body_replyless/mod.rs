@@ -115,6 +115,7 @@ mod tests {
             attachments: vec![],
             attachments_macro: vec![],
             headers_json: None,
+            list_unsubscribe: None,
             created_at: Default::default(),
             labels: vec![],
             body_macro: None,
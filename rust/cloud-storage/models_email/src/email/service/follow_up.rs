@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A reminder to follow up on a thread if nobody has replied to it by `remind_at`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FollowUpReminder {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub thread_id: Uuid,
+    pub remind_at: DateTime<Utc>,
+    /// whether the reminder has already been processed
+    pub fired: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The last message the user sent in a thread, which a follow up reminder notification is about
+#[derive(Debug, Clone)]
+pub struct FollowUpMessage {
+    pub message_id: Uuid,
+    pub subject: Option<String>,
+    pub snippet: Option<String>,
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How a connection to a mail server is secured
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionSecurity {
+    /// TLS from the start of the connection, e.g. IMAP on port 993
+    Tls,
+    /// Upgraded to TLS after connecting, e.g. IMAP on port 143
+    StartTls,
+    /// Unencrypted. Only used for local test servers.
+    None,
+}
+
+/// The IMAP and SMTP settings of a link whose provider is IMAP
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImapAccount {
+    pub link_id: Uuid,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub imap_security: ConnectionSecurity,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: ConnectionSecurity,
+    pub username: String,
+    /// The secrets manager id of the password used for both IMAP and SMTP
+    pub password_secret_id: String,
+}
+
+impl From<crate::email::db::imap_account::ConnectionSecurity> for ConnectionSecurity {
+    fn from(security: crate::email::db::imap_account::ConnectionSecurity) -> Self {
+        match security {
+            crate::email::db::imap_account::ConnectionSecurity::Tls => Self::Tls,
+            crate::email::db::imap_account::ConnectionSecurity::StartTls => Self::StartTls,
+            crate::email::db::imap_account::ConnectionSecurity::None => Self::None,
+        }
+    }
+}
+
+impl From<ConnectionSecurity> for crate::email::db::imap_account::ConnectionSecurity {
+    fn from(security: ConnectionSecurity) -> Self {
+        match security {
+            ConnectionSecurity::Tls => Self::Tls,
+            ConnectionSecurity::StartTls => Self::StartTls,
+            ConnectionSecurity::None => Self::None,
+        }
+    }
+}
+
+impl TryFrom<crate::email::db::imap_account::ImapAccount> for ImapAccount {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(
+        db_account: crate::email::db::imap_account::ImapAccount,
+    ) -> Result<Self, Self::Error> {
+        Ok(ImapAccount {
+            link_id: db_account.link_id,
+            imap_host: db_account.imap_host,
+            imap_port: u16::try_from(db_account.imap_port)?,
+            imap_security: db_account.imap_security.into(),
+            smtp_host: db_account.smtp_host,
+            smtp_port: u16::try_from(db_account.smtp_port)?,
+            smtp_security: db_account.smtp_security.into(),
+            username: db_account.username,
+            password_secret_id: db_account.password_secret_id,
+        })
+    }
+}
@@ -24,12 +24,14 @@ pub struct Link {
 #[dg(backward = email::domain::models::UserProvider)]
 pub enum UserProvider {
     Gmail,
+    Imap,
 }
 
 impl UserProvider {
     pub fn as_str(&self) -> &'static str {
         match self {
             UserProvider::Gmail => "GMAIL",
+            UserProvider::Imap => "IMAP",
         }
     }
 }
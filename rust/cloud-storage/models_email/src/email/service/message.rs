@@ -8,6 +8,7 @@ use crate::service::body_parsing::body_parsed::{
 };
 use crate::service::body_parsing::body_replyless::get_body_replyless_for_message;
 use crate::service::label::Label;
+use crate::service::subscription::ListUnsubscribe;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
@@ -88,6 +89,10 @@ pub struct Message {
     pub attachments: Vec<Attachment>,
     pub attachments_macro: Vec<AttachmentMacro>,
     pub headers_json: Option<JsonValue>,
+    /// how to unsubscribe from the mailing list the message was sent by, if any. only set when
+    /// parsing messages from the provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_unsubscribe: Option<ListUnsubscribe>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -98,6 +103,9 @@ pub struct MessageWithBodyReplyless {
     pub inner: Message,
     // the message body, without any replies nested underneath.
     pub body_replyless: Option<String>,
+    /// the number of tracking pixels removed from the message body
+    #[serde(default)]
+    pub trackers_blocked: usize,
 }
 
 impl From<Message> for MessageWithBodyReplyless {
@@ -105,6 +113,7 @@ impl From<Message> for MessageWithBodyReplyless {
         Self {
             body_replyless: get_body_replyless_for_message(&message),
             inner: message,
+            trackers_blocked: 0,
         }
     }
 }
@@ -279,6 +288,31 @@ pub struct MessageToSend {
     pub attachments_macro: Option<Vec<AttachmentMacro>>,
     pub headers_json: Option<JsonValue>,
     pub send_time: Option<DateTime<Utc>>,
+    /// Set when a rule forwards the message, so the copy is sent with the forwarded headers
+    #[serde(skip)]
+    pub auto_forwarded: bool,
+}
+
+/// Header added to messages forwarded by a rule
+pub const MACRO_FORWARDED_HEADER: &str = "X-Macro-Forwarded";
+
+/// The headers added to messages forwarded by a rule. Rules skip messages with the
+/// [`MACRO_FORWARDED_HEADER`], so forwarding rules on two accounts can't bounce a message between
+/// each other forever.
+pub const FORWARDED_HEADERS: [(&str, &str); 2] = [
+    (MACRO_FORWARDED_HEADER, "true"),
+    ("Auto-Submitted", "auto-forwarded"),
+];
+
+impl MessageToSend {
+    /// The headers to add on top of the ones built from the message fields
+    pub fn extra_headers(&self) -> &'static [(&'static str, &'static str)] {
+        if self.auto_forwarded {
+            &FORWARDED_HEADERS
+        } else {
+            &[]
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -300,6 +334,31 @@ impl From<db::message::ScheduledMessage> for ScheduledMessage {
     }
 }
 
+/// A sent message that is held in the outbox until `send_at`, so it can still be cancelled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub link_id: Uuid,
+    pub message_id: Uuid,
+    pub send_at: DateTime<Utc>,
+    /// whether the message was picked up for sending and can no longer be cancelled
+    pub dispatched: bool,
+}
+
+/// A stored message with what is needed to rebuild it when exporting mail as .eml or mbox
+#[derive(Debug, Clone)]
+pub struct ExportMessage {
+    pub db_id: Uuid,
+    pub thread_db_id: Uuid,
+    pub provider_id: Option<String>,
+    pub subject: Option<String>,
+    pub from_email: Option<String>,
+    pub internal_date_ts: Option<DateTime<Utc>>,
+    pub body_text: Option<String>,
+    pub body_html_sanitized: Option<String>,
+    pub headers_json: Option<JsonValue>,
+    pub attachments: Vec<Attachment>,
+}
+
 /// Information about an email used in search responses
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ThreadHistoryInfo {
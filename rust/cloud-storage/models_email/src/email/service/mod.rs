@@ -4,10 +4,17 @@ pub mod backfill;
 mod body_parsing;
 pub mod cache;
 pub mod contact;
+pub mod follow_up;
+pub mod imap_account;
 pub mod label;
 pub mod link;
 pub mod message;
+pub mod person;
 pub mod pubsub;
+pub mod rule;
 pub mod settings;
+pub mod subscription;
 pub mod sync_token;
+pub mod template;
 pub mod thread;
+pub mod vcard;
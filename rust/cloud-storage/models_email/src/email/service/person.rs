@@ -0,0 +1,323 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Contacts sharing a name with more contacts than this aren't compared by name, the name is too
+/// common to say anything about them
+const MAX_NAME_BUCKET: usize = 10;
+/// Local parts shorter than this are too ambiguous to compare across domains
+const MIN_LOCAL_PART_LEN: usize = 4;
+
+/// One person, known under one or more of the contacts of a link
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Person {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub name: Option<String>,
+    /// The contacts the person is known under, one per address
+    pub contacts: Vec<LinkContact>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A contact of a link and the person it belongs to, if it was merged
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct LinkContact {
+    pub id: Uuid,
+    pub email_address: String,
+    pub name: Option<String>,
+    pub photo_url: Option<String>,
+    pub person_id: Option<Uuid>,
+}
+
+/// Why two contacts are likely the same person, from the strongest signal to the weakest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    /// The addresses deliver to the same mailbox
+    SameAddress,
+    /// The contacts have the same full name
+    SameName,
+    /// The address of one contact is made of the name of the other
+    NameMatchesAddress,
+    /// The addresses have the same local part at different domains
+    SameLocalPart,
+}
+
+impl DuplicateReason {
+    /// How likely contacts matched for the reason are the same person, between 0 and 1
+    pub fn confidence(&self) -> f64 {
+        match self {
+            DuplicateReason::SameAddress => 0.95,
+            DuplicateReason::SameName => 0.8,
+            DuplicateReason::NameMatchesAddress => 0.6,
+            DuplicateReason::SameLocalPart => 0.5,
+        }
+    }
+}
+
+/// Two contacts that are likely the same person
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct DuplicateCandidate {
+    pub contacts: Vec<LinkContact>,
+    pub reason: DuplicateReason,
+    pub confidence: f64,
+}
+
+/// Orders a pair of contact ids, the way dismissed pairs are stored
+pub fn contact_pair(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// Finds the pairs of contacts that are likely the same person, most likely first. Generic
+/// addresses, contacts already merged into the same person and dismissed pairs are skipped.
+pub fn find_duplicate_candidates(
+    contacts: &[LinkContact],
+    dismissed: &HashSet<(Uuid, Uuid)>,
+) -> Vec<DuplicateCandidate> {
+    let contacts: Vec<&LinkContact> = contacts
+        .iter()
+        .filter(|contact| !email_utils::is_generic_email(&contact.email_address))
+        .collect();
+
+    let mut by_address: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut by_local_part: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, contact) in contacts.iter().enumerate() {
+        by_address
+            .entry(email_utils::canonical_email(&contact.email_address))
+            .or_default()
+            .push(index);
+        if let Some(name) = normalize_name(contact.name.as_deref()) {
+            by_name.entry(name).or_default().push(index);
+        }
+        if let Some(local_part) = local_part(&contact.email_address) {
+            by_local_part.entry(local_part).or_default().push(index);
+        }
+    }
+
+    let mut best: HashMap<(usize, usize), DuplicateReason> = HashMap::new();
+    let mut consider = |a: usize, b: usize, reason: DuplicateReason| {
+        if a == b {
+            return;
+        }
+        let key = if a < b { (a, b) } else { (b, a) };
+        best.entry(key)
+            .and_modify(|existing| {
+                if reason.confidence() > existing.confidence() {
+                    *existing = reason;
+                }
+            })
+            .or_insert(reason);
+    };
+
+    for indices in by_address.values() {
+        for_each_pair(indices, |a, b| consider(a, b, DuplicateReason::SameAddress));
+    }
+    for indices in by_name.values().filter(|i| i.len() <= MAX_NAME_BUCKET) {
+        for_each_pair(indices, |a, b| consider(a, b, DuplicateReason::SameName));
+    }
+    for indices in by_local_part
+        .values()
+        .filter(|i| i.len() <= MAX_NAME_BUCKET)
+    {
+        for_each_pair(indices, |a, b| {
+            // the same local part at the same domain is the same address, or a different person
+            if domain(&contacts[a].email_address) != domain(&contacts[b].email_address) {
+                consider(a, b, DuplicateReason::SameLocalPart);
+            }
+        });
+    }
+    for (index, contact) in contacts.iter().enumerate() {
+        for local_part in name_local_parts(contact.name.as_deref()) {
+            let Some(indices) = by_local_part.get(&local_part) else {
+                continue;
+            };
+            if indices.len() > MAX_NAME_BUCKET {
+                continue;
+            }
+            for &other in indices {
+                consider(index, other, DuplicateReason::NameMatchesAddress);
+            }
+        }
+    }
+
+    let mut candidates: Vec<DuplicateCandidate> = best
+        .into_iter()
+        .map(|((a, b), reason)| (contacts[a], contacts[b], reason))
+        .filter(|(a, b, _)| a.person_id.is_none() || a.person_id != b.person_id)
+        .filter(|(a, b, _)| !dismissed.contains(&contact_pair(a.id, b.id)))
+        .map(|(a, b, reason)| {
+            let (a, b) = if a.id < b.id { (a, b) } else { (b, a) };
+            DuplicateCandidate {
+                contacts: vec![a.clone(), b.clone()],
+                reason,
+                confidence: reason.confidence(),
+            }
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.contacts[0].id.cmp(&b.contacts[0].id))
+            .then_with(|| a.contacts[1].id.cmp(&b.contacts[1].id))
+    });
+    candidates
+}
+
+fn for_each_pair(indices: &[usize], mut f: impl FnMut(usize, usize)) {
+    for (i, &a) in indices.iter().enumerate() {
+        for &b in &indices[i + 1..] {
+            f(a, b);
+        }
+    }
+}
+
+/// The words of a name, lowercased and without punctuation. Names written as "Last, First" are
+/// put in the order of "First Last".
+fn name_words(name: Option<&str>) -> Vec<String> {
+    let name = name.unwrap_or_default();
+    let name = match name.split_once(',') {
+        Some((last, first)) => format!("{first} {last}"),
+        None => name.to_string(),
+    };
+
+    name.split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '-')
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// A full name normalized for comparison. Single word names are too ambiguous to compare.
+fn normalize_name(name: Option<&str>) -> Option<String> {
+    let words = name_words(name);
+    if words.len() < 2 {
+        return None;
+    }
+    Some(words.join(" "))
+}
+
+/// The local parts an address made of a name would have, without separators
+fn name_local_parts(name: Option<&str>) -> Vec<String> {
+    let words = name_words(name);
+    let (Some(first), Some(last)) = (words.first(), words.last()) else {
+        return vec![];
+    };
+    if words.len() < 2 {
+        return vec![];
+    }
+
+    let initial: String = first.chars().take(1).collect();
+    [
+        format!("{first}{last}"),
+        format!("{last}{first}"),
+        format!("{initial}{last}"),
+    ]
+    .into_iter()
+    .map(|local_part| local_part.replace('-', ""))
+    .filter(|local_part| local_part.len() >= MIN_LOCAL_PART_LEN)
+    .collect()
+}
+
+/// The local part of an address without its + alias and separators, if it is long enough to
+/// compare
+fn local_part(email: &str) -> Option<String> {
+    let canonical = email_utils::canonical_email(email);
+    let (local_part, _) = canonical.rsplit_once('@')?;
+    let local_part: String = local_part
+        .chars()
+        .filter(|c| !matches!(c, '.' | '_' | '-'))
+        .collect();
+    if local_part.len() < MIN_LOCAL_PART_LEN || local_part.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(local_part)
+}
+
+fn domain(email: &str) -> Option<String> {
+    email_utils::canonical_email(email)
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(id: u128, email_address: &str, name: Option<&str>) -> LinkContact {
+        LinkContact {
+            id: Uuid::from_u128(id),
+            email_address: email_address.to_string(),
+            name: name.map(str::to_string),
+            photo_url: None,
+            person_id: None,
+        }
+    }
+
+    fn pairs(candidates: &[DuplicateCandidate]) -> Vec<(u128, u128, DuplicateReason)> {
+        candidates
+            .iter()
+            .map(|candidate| {
+                (
+                    candidate.contacts[0].id.as_u128(),
+                    candidate.contacts[1].id.as_u128(),
+                    candidate.reason,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_duplicate_candidates() {
+        let contacts = vec![
+            contact(1, "jane.doe@gmail.com", Some("Jane Doe")),
+            contact(2, "janedoe+work@googlemail.com", None),
+            contact(3, "jane@acme.com", Some("Doe, Jane")),
+            contact(4, "jdoe@startup.io", None),
+            contact(5, "bob@acme.com", Some("Bob")),
+            contact(6, "bob@other.com", Some("Bob")),
+            contact(7, "noreply@acme.com", Some("Jane Doe")),
+            contact(8, "robert@acme.com", None),
+            contact(9, "robert@other.org", None),
+        ];
+
+        assert_eq!(
+            pairs(&find_duplicate_candidates(&contacts, &HashSet::new())),
+            vec![
+                (1, 2, DuplicateReason::SameAddress),
+                (1, 3, DuplicateReason::SameName),
+                (1, 4, DuplicateReason::NameMatchesAddress),
+                (2, 3, DuplicateReason::NameMatchesAddress),
+                (3, 4, DuplicateReason::NameMatchesAddress),
+                (8, 9, DuplicateReason::SameLocalPart),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_candidates_skips_merged_and_dismissed() {
+        let person_id = Some(Uuid::from_u128(100));
+        let mut contacts = vec![
+            contact(1, "jane.doe@gmail.com", Some("Jane Doe")),
+            contact(2, "janedoe@gmail.com", None),
+            contact(3, "jane@acme.com", Some("Jane Doe")),
+        ];
+        contacts[0].person_id = person_id;
+        contacts[1].person_id = person_id;
+
+        let dismissed = HashSet::from([contact_pair(Uuid::from_u128(3), Uuid::from_u128(1))]);
+
+        assert_eq!(
+            pairs(&find_duplicate_candidates(&contacts, &dismissed)),
+            vec![(2, 3, DuplicateReason::NameMatchesAddress)]
+        );
+    }
+}
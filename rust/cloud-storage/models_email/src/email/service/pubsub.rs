@@ -52,6 +52,23 @@ pub struct ScheduledPubsubMessage {
     pub message_id: Uuid,
 }
 
+/// The message we send from the email_scheduled_handler lambda to the service via SQS when a
+/// follow up reminder is due
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowUpPubsubMessage {
+    pub link_id: Uuid,
+    pub reminder_id: Uuid,
+}
+
+/// Every message that goes through the scheduled queue. Untagged so scheduled sends keep the
+/// shape they had before follow up reminders shared the queue.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScheduledQueueMessage {
+    Send(ScheduledPubsubMessage),
+    FollowUp(FollowUpPubsubMessage),
+}
+
 /// The message we send to the sfs_uploader telling it what image URL to upload
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SFSUploaderMessage {
@@ -0,0 +1,350 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A condition a message has to satisfy for a rule to apply.
+/// Text comparisons are case-insensitive substring matches, so a sender condition of
+/// `@example.com` matches every sender of that domain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// The sender's address contains the value
+    From {
+        value: String,
+    },
+    /// Any to or cc address contains the value
+    Recipient {
+        value: String,
+    },
+    SubjectContains {
+        value: String,
+    },
+    BodyContains {
+        value: String,
+    },
+    HasAttachment,
+    /// The message has the label with the given db id
+    HasLabel {
+        label_id: Uuid,
+    },
+}
+
+/// What happens to a message a rule applies to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Adds the label with the given db id
+    ApplyLabel {
+        label_id: Uuid,
+    },
+    Archive,
+    MarkRead,
+    Star,
+    /// Forwards the message to the address
+    Forward {
+        email: String,
+    },
+    /// No new email notification is sent for the message
+    SuppressNotification,
+    /// Moves the message's thread into the project
+    AddToProject {
+        project_id: String,
+    },
+}
+
+/// A user defined rule of a link
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Rule {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub name: String,
+    pub is_enabled: bool,
+    /// Rules are evaluated in ascending position
+    pub position: i32,
+    /// Whether every condition has to match, or any of them
+    pub match_all: bool,
+    /// Whether rules after this one are skipped for messages it applies to
+    pub stop_processing: bool,
+    pub conditions: Vec<RuleCondition>,
+    pub actions: Vec<RuleAction>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The parts of a message rules are evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct RuleMessage {
+    pub db_id: Uuid,
+    pub thread_db_id: Uuid,
+    pub provider_id: Option<String>,
+    pub from_email: Option<String>,
+    /// The to and cc addresses
+    pub recipient_emails: Vec<String>,
+    pub subject: Option<String>,
+    pub body_text: Option<String>,
+    pub has_attachments: bool,
+    /// The db ids of the message's labels
+    pub label_ids: Vec<Uuid>,
+    /// Whether the message was sent by a forwarding rule
+    pub is_auto_forwarded: bool,
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl RuleCondition {
+    pub fn matches(&self, message: &RuleMessage) -> bool {
+        match self {
+            RuleCondition::From { value } => message
+                .from_email
+                .as_deref()
+                .is_some_and(|from| contains_ignore_case(from, value)),
+            RuleCondition::Recipient { value } => message
+                .recipient_emails
+                .iter()
+                .any(|recipient| contains_ignore_case(recipient, value)),
+            RuleCondition::SubjectContains { value } => message
+                .subject
+                .as_deref()
+                .is_some_and(|subject| contains_ignore_case(subject, value)),
+            RuleCondition::BodyContains { value } => message
+                .body_text
+                .as_deref()
+                .is_some_and(|body| contains_ignore_case(body, value)),
+            RuleCondition::HasAttachment => message.has_attachments,
+            RuleCondition::HasLabel { label_id } => message.label_ids.contains(label_id),
+        }
+    }
+}
+
+impl Rule {
+    /// Whether the rule applies to the message. Disabled rules and rules without conditions
+    /// never apply.
+    pub fn matches(&self, message: &RuleMessage) -> bool {
+        if !self.is_enabled || self.conditions.is_empty() {
+            return false;
+        }
+
+        if self.match_all {
+            self.conditions.iter().all(|c| c.matches(message))
+        } else {
+            self.conditions.iter().any(|c| c.matches(message))
+        }
+    }
+}
+
+/// The rules that apply to a message, in the order they are evaluated.
+/// Evaluation stops after the first applying rule that stops processing.
+pub fn matching_rules<'a>(rules: &'a [Rule], message: &RuleMessage) -> Vec<&'a Rule> {
+    let mut ordered = rules.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|rule| rule.position);
+
+    let mut matching = Vec::new();
+    for rule in ordered {
+        if rule.matches(message) {
+            matching.push(rule);
+            if rule.stop_processing {
+                break;
+            }
+        }
+    }
+    matching
+}
+
+/// The actions of the rules that apply to a message, without duplicates.
+/// Messages sent by a forwarding rule get no actions, so forwarding rules can't loop.
+pub fn matching_actions(rules: &[Rule], message: &RuleMessage) -> Vec<RuleAction> {
+    let mut actions: Vec<RuleAction> = Vec::new();
+    if message.is_auto_forwarded {
+        return actions;
+    }
+    for rule in matching_rules(rules, message) {
+        for action in &rule.actions {
+            if !actions.contains(action) {
+                actions.push(action.clone());
+            }
+        }
+    }
+    actions
+}
+
+impl TryFrom<crate::email::db::rule::Rule> for Rule {
+    type Error = serde_json::Error;
+
+    fn try_from(db_rule: crate::email::db::rule::Rule) -> Result<Self, Self::Error> {
+        Ok(Rule {
+            id: db_rule.id,
+            link_id: db_rule.link_id,
+            name: db_rule.name,
+            is_enabled: db_rule.is_enabled,
+            position: db_rule.position,
+            match_all: db_rule.match_all,
+            stop_processing: db_rule.stop_processing,
+            conditions: serde_json::from_value(db_rule.conditions)?,
+            actions: serde_json::from_value(db_rule.actions)?,
+            created_at: db_rule.created_at.and_utc(),
+            updated_at: db_rule.updated_at.and_utc(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(position: i32, conditions: Vec<RuleCondition>, actions: Vec<RuleAction>) -> Rule {
+        Rule {
+            id: Uuid::new_v4(),
+            link_id: Uuid::nil(),
+            name: format!("rule {position}"),
+            is_enabled: true,
+            position,
+            match_all: true,
+            stop_processing: false,
+            conditions,
+            actions,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn message() -> RuleMessage {
+        RuleMessage {
+            from_email: Some("Billing@Example.com".to_string()),
+            recipient_emails: vec!["me@macro.com".to_string(), "team@macro.com".to_string()],
+            subject: Some("Your invoice for March".to_string()),
+            body_text: Some("Amount due: $20".to_string()),
+            has_attachments: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_condition_matches() {
+        let message = message();
+        let label_id = Uuid::new_v4();
+
+        assert!(
+            RuleCondition::From {
+                value: "@example.com".to_string()
+            }
+            .matches(&message)
+        );
+        assert!(
+            RuleCondition::Recipient {
+                value: "team@".to_string()
+            }
+            .matches(&message)
+        );
+        assert!(
+            RuleCondition::SubjectContains {
+                value: "INVOICE".to_string()
+            }
+            .matches(&message)
+        );
+        assert!(
+            !RuleCondition::BodyContains {
+                value: "refund".to_string()
+            }
+            .matches(&message)
+        );
+        assert!(RuleCondition::HasAttachment.matches(&message));
+        assert!(!RuleCondition::HasLabel { label_id }.matches(&message));
+        assert!(RuleCondition::HasLabel { label_id }.matches(&RuleMessage {
+            label_ids: vec![label_id],
+            ..message
+        }));
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let message = message();
+        let from = RuleCondition::From {
+            value: "example.com".to_string(),
+        };
+        let subject = RuleCondition::SubjectContains {
+            value: "receipt".to_string(),
+        };
+
+        let mut all = rule(0, vec![from.clone(), subject.clone()], vec![]);
+        assert!(!all.matches(&message));
+
+        all.match_all = false;
+        assert!(all.matches(&message));
+
+        all.is_enabled = false;
+        assert!(!all.matches(&message));
+
+        assert!(!rule(0, vec![], vec![RuleAction::Archive]).matches(&message));
+    }
+
+    #[test]
+    fn test_matching_actions() {
+        let message = message();
+        let has_attachment = vec![RuleCondition::HasAttachment];
+
+        let mut rules = vec![
+            rule(2, has_attachment.clone(), vec![RuleAction::Star]),
+            rule(
+                0,
+                has_attachment.clone(),
+                vec![RuleAction::MarkRead, RuleAction::Archive],
+            ),
+            rule(1, has_attachment.clone(), vec![RuleAction::Archive]),
+        ];
+        assert_eq!(
+            matching_actions(&rules, &message),
+            vec![RuleAction::MarkRead, RuleAction::Archive, RuleAction::Star]
+        );
+
+        rules[2].stop_processing = true;
+        assert_eq!(
+            matching_actions(&rules, &message),
+            vec![RuleAction::MarkRead, RuleAction::Archive]
+        );
+    }
+
+    #[test]
+    fn test_matching_actions_skips_auto_forwarded_messages() {
+        let rules = vec![rule(
+            0,
+            vec![RuleCondition::HasAttachment],
+            vec![RuleAction::Forward {
+                email: "me@other.com".to_string(),
+            }],
+        )];
+        let message = RuleMessage {
+            is_auto_forwarded: true,
+            ..message()
+        };
+
+        assert!(matching_actions(&rules, &message).is_empty());
+    }
+
+    #[test]
+    fn test_serialization() -> anyhow::Result<()> {
+        let conditions: Vec<RuleCondition> = serde_json::from_str(
+            r#"[{"type": "from", "value": "a@b.com"}, {"type": "has_attachment"}]"#,
+        )?;
+        assert_eq!(
+            conditions,
+            vec![
+                RuleCondition::From {
+                    value: "a@b.com".to_string()
+                },
+                RuleCondition::HasAttachment
+            ]
+        );
+
+        let action = serde_json::to_value(RuleAction::AddToProject {
+            project_id: "p1".to_string(),
+        })?;
+        assert_eq!(
+            action,
+            serde_json::json!({"type": "add_to_project", "project_id": "p1"})
+        );
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use uuid::Uuid;
 pub struct Settings {
     pub link_id: Uuid,
     pub signature_on_replies_forwards: bool,
+    pub undo_send_seconds: i32,
 }
 
 impl Settings {
@@ -15,6 +16,7 @@ impl Settings {
             signature_on_replies_forwards: api_settings
                 .signature_on_replies_forwards
                 .unwrap_or(false),
+            undo_send_seconds: api_settings.undo_send_seconds.unwrap_or(0),
         }
     }
 }
@@ -24,6 +26,7 @@ impl From<crate::email::db::settings::Settings> for Settings {
         Settings {
             link_id: db_settings.link_id,
             signature_on_replies_forwards: db_settings.signature_on_replies_forwards,
+            undo_send_seconds: db_settings.undo_send_seconds,
         }
     }
 }
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How a received mailing list message says to unsubscribe from the list, from its List-Id,
+/// List-Unsubscribe (RFC 2369) and List-Unsubscribe-Post (RFC 8058) headers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ListUnsubscribe {
+    /// Identifies the sending list. The List-Id of the list, or the sender's address for lists
+    /// without one.
+    pub list_key: String,
+    pub list_id: Option<String>,
+    pub http_url: Option<String>,
+    pub mailto: Option<String>,
+    /// whether the sender supports one-click unsubscribe by POSTing to the http url
+    pub one_click: bool,
+}
+
+/// A mailing list the link receives mail from
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Subscription {
+    pub list_key: String,
+    pub list_id: Option<String>,
+    /// the sender of the most recent message of the list
+    pub sender_email: Option<String>,
+    pub sender_name: Option<String>,
+    pub thread_count: i64,
+    pub message_count: i64,
+    /// messages received in the last 30 days
+    pub recent_message_count: i64,
+    pub unread_message_count: i64,
+    pub last_received_at: Option<DateTime<Utc>>,
+    /// whether the list can be unsubscribed from without opening a web page
+    pub can_unsubscribe: bool,
+    pub one_click: bool,
+    pub unsubscribed_at: Option<DateTime<Utc>>,
+}
+
+/// A thread containing messages of a mailing list
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionThread {
+    pub thread_id: Uuid,
+    pub subject: Option<String>,
+    pub snippet: Option<String>,
+    pub last_received_at: Option<DateTime<Utc>>,
+    pub is_read: bool,
+}
+
+/// The unsubscribe details of the most recent message of a mailing list
+#[derive(Debug, Clone)]
+pub struct SubscriptionTarget {
+    pub list_key: String,
+    pub list_id: Option<String>,
+    pub sender_email: Option<String>,
+    pub http_url: Option<String>,
+    pub mailto: Option<String>,
+    pub one_click: bool,
+}
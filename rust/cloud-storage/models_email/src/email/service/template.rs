@@ -0,0 +1,298 @@
+use chrono::{DateTime, Utc};
+use html_utils::escape_html;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The first name of the recipient
+pub const FIRST_NAME: &str = "first_name";
+/// The full name of the recipient
+pub const RECIPIENT_NAME: &str = "recipient_name";
+/// The email address of the recipient
+pub const RECIPIENT_EMAIL: &str = "recipient_email";
+/// The email address of the sender
+pub const SENDER_EMAIL: &str = "sender_email";
+/// The signature of the sender's email address
+pub const SIGNATURE: &str = "signature";
+
+/// Variables every template can use, their values are filled in when a template is rendered
+pub const BUILTIN_VARIABLES: &[&str] = &[
+    FIRST_NAME,
+    RECIPIENT_NAME,
+    RECIPIENT_EMAIL,
+    SENDER_EMAIL,
+    SIGNATURE,
+];
+
+/// A custom variable of a template, written as `{{name}}` in its subject and body
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct TemplateVariable {
+    pub name: String,
+    /// The value used when rendering doesn't provide one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// A reusable email of a link. Templates without a subject are snippets, which are inserted into
+/// an existing draft.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Template {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    /// The team the template is shared with
+    pub team_id: Option<Uuid>,
+    pub name: String,
+    pub subject: Option<String>,
+    pub body_html: String,
+    pub body_text: Option<String>,
+    pub variables: Vec<TemplateVariable>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<crate::email::db::template::Template> for Template {
+    type Error = serde_json::Error;
+
+    fn try_from(db_template: crate::email::db::template::Template) -> Result<Self, Self::Error> {
+        Ok(Template {
+            id: db_template.id,
+            link_id: db_template.link_id,
+            team_id: db_template.team_id,
+            name: db_template.name,
+            subject: db_template.subject,
+            body_html: db_template.body_html,
+            body_text: db_template.body_text,
+            variables: serde_json::from_value(db_template.variables)?,
+            created_at: db_template.created_at.and_utc(),
+            updated_at: db_template.updated_at.and_utc(),
+        })
+    }
+}
+
+/// The value of a variable, as it is inserted into html and into plain text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateValue {
+    pub html: String,
+    pub text: String,
+}
+
+impl TemplateValue {
+    /// A plain text value, escaped when inserted into html
+    pub fn text(value: &str) -> Self {
+        Self {
+            html: escape_html(value),
+            text: value.to_string(),
+        }
+    }
+
+    /// An html value, such as a signature. Its plain text version drops the markup.
+    pub fn html(value: &str) -> Self {
+        let text = html2text::config::plain()
+            .string_from_read(value.as_bytes(), usize::MAX)
+            .map(|text| text.trim_end().to_string())
+            .unwrap_or_default();
+
+        Self {
+            html: value.to_string(),
+            text,
+        }
+    }
+}
+
+/// The subject and body of a rendered template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct RenderedTemplate {
+    pub subject: Option<String>,
+    pub body_html: String,
+    pub body_text: Option<String>,
+}
+
+impl Template {
+    /// The names of the variables the template uses
+    pub fn used_variables(&self) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for text in [
+            self.subject.as_deref(),
+            Some(self.body_html.as_str()),
+            self.body_text.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for_each_variable(text, |name| {
+                names.insert(name.to_string());
+            });
+        }
+        names
+    }
+
+    /// Renders the template. Custom variables without a value use their default.
+    /// Returns the names of the variables that have no value when any is missing.
+    pub fn render(
+        &self,
+        values: &HashMap<String, TemplateValue>,
+    ) -> Result<RenderedTemplate, Vec<String>> {
+        let defaults = self
+            .variables
+            .iter()
+            .filter_map(|variable| {
+                let default = variable.default.as_deref()?;
+                Some((variable.name.clone(), TemplateValue::text(default)))
+            })
+            .collect::<HashMap<_, _>>();
+        let lookup = |name: &str| values.get(name).or_else(|| defaults.get(name));
+
+        let missing = self
+            .used_variables()
+            .into_iter()
+            .filter(|name| lookup(name).is_none())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        let render_text =
+            |text: &str| substitute(text, |name| lookup(name).map(|value| value.text.as_str()));
+
+        Ok(RenderedTemplate {
+            subject: self.subject.as_deref().map(render_text),
+            body_html: substitute(&self.body_html, |name| {
+                lookup(name).map(|value| value.html.as_str())
+            }),
+            body_text: self.body_text.as_deref().map(render_text),
+        })
+    }
+}
+
+/// Whether a variable name is valid, lowercase letters, digits and underscores
+pub fn is_valid_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The first word of a name
+pub fn first_name(name: &str) -> Option<&str> {
+    name.split_whitespace().next()
+}
+
+/// Calls `f` with the name of every `{{name}}` in a text. Braces around anything that isn't a
+/// valid variable name are left as they are.
+fn for_each_variable<'a>(text: &'a str, mut f: impl FnMut(&'a str)) {
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        let name = after_open[..end].trim();
+        if is_valid_variable_name(name) {
+            f(name);
+            rest = &after_open[end + 2..];
+        } else {
+            rest = after_open;
+        }
+    }
+}
+
+/// Replaces every `{{name}}` in a text with its value, variables without one are left as they are
+fn substitute<'a>(text: &str, value: impl Fn(&str) -> Option<&'a str>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        let name = after_open[..end].trim();
+        match value(name).filter(|_| is_valid_variable_name(name)) {
+            Some(value) => {
+                rendered.push_str(&rest[..start]);
+                rendered.push_str(value);
+                rest = &after_open[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[..start + 2]);
+                rest = after_open;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(subject: Option<&str>, body_html: &str, body_text: Option<&str>) -> Template {
+        Template {
+            id: Uuid::new_v4(),
+            link_id: Uuid::nil(),
+            team_id: None,
+            name: "template".to_string(),
+            subject: subject.map(str::to_string),
+            body_html: body_html.to_string(),
+            body_text: body_text.map(str::to_string),
+            variables: vec![TemplateVariable {
+                name: "company".to_string(),
+                default: Some("your team".to_string()),
+            }],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_used_variables() {
+        let template = template(
+            Some("Hi {{ first_name }}"),
+            "<p>{{first_name}}, {{company}} {{Not A Variable}} {{</p>",
+            Some("{{signature}}"),
+        );
+
+        assert_eq!(
+            template.used_variables().into_iter().collect::<Vec<_>>(),
+            vec!["company", "first_name", "signature"]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let template = template(
+            Some("Hi {{first_name}}"),
+            "<p>Hi {{ first_name }} from {{company}}</p>{{signature}}",
+            Some("Hi {{first_name}}\n{{signature}}"),
+        );
+        let values = HashMap::from([
+            (FIRST_NAME.to_string(), TemplateValue::text("Ann & Bo")),
+            (SIGNATURE.to_string(), TemplateValue::html("<b>Cat</b>")),
+        ]);
+
+        let rendered = template.render(&values).unwrap();
+        assert_eq!(rendered.subject.as_deref(), Some("Hi Ann & Bo"));
+        assert_eq!(
+            rendered.body_html,
+            "<p>Hi Ann &amp; Bo from your team</p><b>Cat</b>"
+        );
+        assert_eq!(rendered.body_text.as_deref(), Some("Hi Ann & Bo\n**Cat**"));
+    }
+
+    #[test]
+    fn test_render_missing_variables() {
+        let template = template(None, "{{first_name}} {{deal_size}} {{company}}", None);
+
+        assert_eq!(
+            template.render(&HashMap::new()),
+            Err(vec!["deal_size".to_string(), "first_name".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_first_name() {
+        assert_eq!(first_name("  Ann Marie Smith"), Some("Ann"));
+        assert_eq!(first_name(" "), None);
+    }
+}
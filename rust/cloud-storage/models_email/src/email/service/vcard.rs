@@ -0,0 +1,366 @@
+//! Reading and writing contacts as vCards (RFC 6350). Cards are written as vCard 4.0, and read
+//! from vCard 3.0 and 4.0, keeping only the properties contacts have.
+
+/// vCard lines longer than this many bytes are folded
+const MAX_LINE_LEN: usize = 75;
+
+/// A contact card, with the properties contacts have
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VCard {
+    pub uid: Option<String>,
+    pub full_name: Option<String>,
+    /// The email addresses of the contact, the preferred one first
+    pub emails: Vec<String>,
+    pub photo_url: Option<String>,
+}
+
+/// Writes cards as a vCard 4.0 file
+pub fn write_vcards(cards: &[VCard]) -> String {
+    let mut output = String::new();
+    for card in cards {
+        write_vcard(&mut output, card);
+    }
+    output
+}
+
+fn write_vcard(output: &mut String, card: &VCard) {
+    write_line(output, "BEGIN:VCARD");
+    write_line(output, "VERSION:4.0");
+    if let Some(uid) = &card.uid {
+        write_line(output, &format!("UID:{}", uid));
+    }
+    // FN is the only required property
+    write_line(
+        output,
+        &format!(
+            "FN:{}",
+            escape(
+                card.full_name
+                    .as_deref()
+                    .or(card.emails.first().map(String::as_str))
+                    .unwrap_or_default()
+            )
+        ),
+    );
+    for (index, email) in card.emails.iter().enumerate() {
+        if index == 0 && card.emails.len() > 1 {
+            write_line(output, &format!("EMAIL;PREF=1:{}", escape(email)));
+        } else {
+            write_line(output, &format!("EMAIL:{}", escape(email)));
+        }
+    }
+    if let Some(photo_url) = &card.photo_url {
+        write_line(output, &format!("PHOTO:{}", photo_url));
+    }
+    write_line(output, "END:VCARD");
+}
+
+/// Writes a content line, folded into lines of at most 75 bytes
+fn write_line(output: &mut String, line: &str) {
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > MAX_LINE_LEN {
+            output.push_str("\r\n ");
+            // the leading space counts towards the length of the continuation line
+            line_len = 1;
+        }
+        output.push(c);
+        line_len += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ',' => escaped.push_str("\\,"),
+            ';' => escaped.push_str("\\;"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Reads the cards of a vCard file. Properties that contacts don't have are ignored, and so are
+/// cards without an email address.
+pub fn parse_vcards(input: &str) -> Vec<VCard> {
+    let mut cards = Vec::new();
+    let mut card: Option<VCard> = None;
+    // the structured name, used when a card has no formatted name
+    let mut name: Option<String> = None;
+    let mut preferred_email: Option<String> = None;
+
+    for line in unfold(input) {
+        let Some((property, value)) = split_line(&line) else {
+            continue;
+        };
+        let (name_and_group, params) = property
+            .split_once(';')
+            .map_or((property, ""), |(name, params)| (name, params));
+        // properties can be grouped, as in item1.EMAIL
+        let property_name = name_and_group
+            .rsplit_once('.')
+            .map_or(name_and_group, |(_, name)| name)
+            .to_ascii_uppercase();
+
+        match property_name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VCARD") => {
+                card = Some(VCard::default());
+                name = None;
+                preferred_email = None;
+            }
+            "END" if value.eq_ignore_ascii_case("VCARD") => {
+                if let Some(mut finished) = card.take() {
+                    if finished.full_name.is_none() {
+                        finished.full_name = name.take();
+                    }
+                    if let Some(preferred) = preferred_email.take() {
+                        finished.emails.retain(|email| *email != preferred);
+                        finished.emails.insert(0, preferred);
+                    }
+                    if !finished.emails.is_empty() {
+                        cards.push(finished);
+                    }
+                }
+            }
+            _ => {
+                let Some(card) = card.as_mut() else {
+                    continue;
+                };
+                match property_name.as_str() {
+                    "UID" => card.uid = non_empty(value.to_string()),
+                    "FN" => card.full_name = non_empty(unescape(value).trim().to_string()),
+                    "N" => name = structured_name(value),
+                    "EMAIL" => {
+                        let email = unescape(value);
+                        let email = email.trim().trim_start_matches("mailto:").to_lowercase();
+                        if !email.contains('@') || card.emails.contains(&email) {
+                            continue;
+                        }
+                        if preferred_email.is_none() && is_preferred(params) {
+                            preferred_email = Some(email.clone());
+                        }
+                        card.emails.push(email);
+                    }
+                    "PHOTO" => {
+                        let value = value.trim();
+                        // inline photos aren't kept
+                        if value.starts_with("https://") || value.starts_with("http://") {
+                            card.photo_url = Some(value.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    cards
+}
+
+/// Joins folded lines, continuation lines start with a space or a tab
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in input.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits a content line into its property, with its group and parameters, and its value. The
+/// value starts at the first colon outside of a quoted parameter value.
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the parameters mark a property as preferred, PREF=1 in vCard 4.0 and TYPE=PREF in
+/// vCard 3.0
+fn is_preferred(params: &str) -> bool {
+    params.split(';').any(|param| {
+        let Some((name, value)) = param.split_once('=') else {
+            return param.eq_ignore_ascii_case("PREF");
+        };
+        match name.to_ascii_uppercase().as_str() {
+            "PREF" => value.trim_matches('"') == "1",
+            "TYPE" => value
+                .trim_matches('"')
+                .split(',')
+                .any(|value| value.eq_ignore_ascii_case("pref")),
+            _ => false,
+        }
+    })
+}
+
+/// The full name of a structured name, family;given;additional;prefixes;suffixes
+fn structured_name(value: &str) -> Option<String> {
+    let components: Vec<String> = split_unescaped(value, ';')
+        .into_iter()
+        .map(|component| unescape(&component).replace(',', " ").trim().to_string())
+        .collect();
+    let component = |index: usize| components.get(index).map(String::as_str).unwrap_or("");
+
+    let name = [
+        component(3),
+        component(1),
+        component(2),
+        component(0),
+        component(4),
+    ]
+    .into_iter()
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+    non_empty(name)
+}
+
+/// Splits a value at a separator that isn't escaped
+fn split_unescaped(value: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().expect("parts is never empty");
+        if c == '\\' {
+            part.push(c);
+            if let Some(escaped) = chars.next() {
+                part.push(escaped);
+            }
+        } else if c == separator {
+            parts.push(String::new());
+        } else {
+            part.push(c);
+        }
+    }
+    parts
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() { None } else { Some(value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_vcards() {
+        let cards = vec![
+            VCard {
+                uid: Some("urn:uuid:0195f6d4-4c8e-7000-8000-000000000001".to_string()),
+                full_name: Some("Doe, Jane; PhD".to_string()),
+                emails: vec!["jane@acme.com".to_string(), "jane@gmail.com".to_string()],
+                photo_url: None,
+            },
+            VCard {
+                emails: vec!["bob@acme.com".to_string()],
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            write_vcards(&cards),
+            "BEGIN:VCARD\r\n\
+             VERSION:4.0\r\n\
+             UID:urn:uuid:0195f6d4-4c8e-7000-8000-000000000001\r\n\
+             FN:Doe\\, Jane\\; PhD\r\n\
+             EMAIL;PREF=1:jane@acme.com\r\n\
+             EMAIL:jane@gmail.com\r\n\
+             END:VCARD\r\n\
+             BEGIN:VCARD\r\n\
+             VERSION:4.0\r\n\
+             FN:bob@acme.com\r\n\
+             EMAIL:bob@acme.com\r\n\
+             END:VCARD\r\n"
+        );
+    }
+
+    #[test]
+    fn test_write_folds_long_lines() {
+        let card = VCard {
+            full_name: Some("é".repeat(50)),
+            emails: vec!["a@b.com".to_string()],
+            ..Default::default()
+        };
+
+        let written = write_vcards(std::slice::from_ref(&card));
+        assert!(written.split("\r\n").all(|line| line.len() <= MAX_LINE_LEN));
+        assert_eq!(parse_vcards(&written), vec![card]);
+    }
+
+    #[test]
+    fn test_parse_vcards() {
+        let input = "BEGIN:VCARD\n\
+                     VERSION:3.0\n\
+                     N:Doe;Jane;;Dr.;\n\
+                     item1.EMAIL;TYPE=INTERNET:Jane@Acme.com\n\
+                     EMAIL;TYPE=\"INTERNET,pref\":jane.doe@gmail.com\n\
+                     EMAIL:jane@acme.com\n\
+                     PHOTO;ENCODING=b;TYPE=JPEG:MIIC\n\
+                     NOTE:a long note that is\n  folded: with a colon\n\
+                     END:VCARD\n\
+                     BEGIN:VCARD\n\
+                     VERSION:4.0\n\
+                     FN:No Email\n\
+                     END:VCARD\n\
+                     BEGIN:VCARD\r\n\
+                     VERSION:4.0\r\n\
+                     UID:urn:uuid:1\r\n\
+                     FN:Bob\\, the\r\n  Builder\r\n\
+                     EMAIL:mailto:bob@acme.com\r\n\
+                     PHOTO:https://acme.com/bob.jpg\r\n\
+                     END:VCARD\r\n";
+
+        assert_eq!(
+            parse_vcards(input),
+            vec![
+                VCard {
+                    uid: None,
+                    full_name: Some("Dr. Jane Doe".to_string()),
+                    emails: vec![
+                        "jane.doe@gmail.com".to_string(),
+                        "jane@acme.com".to_string()
+                    ],
+                    photo_url: None,
+                },
+                VCard {
+                    uid: Some("urn:uuid:1".to_string()),
+                    full_name: Some("Bob, the Builder".to_string()),
+                    emails: vec!["bob@acme.com".to_string()],
+                    photo_url: Some("https://acme.com/bob.jpg".to_string()),
+                },
+            ]
+        );
+    }
+}
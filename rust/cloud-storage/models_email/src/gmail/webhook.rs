@@ -67,6 +67,11 @@ pub enum WebhookOperation {
     DeleteMessage(DeleteMessagePayload),
     // Operation to add/remove labels from a message
     UpdateLabels(UpdateLabelsPayload),
+    // Sent by the IMAP watcher when a mailbox of an IMAP link may have changed.
+    // The changes are found by diffing against the stored sync state.
+    ImapMessage,
+    // Applies a rule to the messages the link already has
+    ApplyRule(ApplyRulePayload),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -89,6 +94,11 @@ pub struct UpdateLabelsPayload {
     pub provider_message_id: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ApplyRulePayload {
+    pub rule_id: Uuid,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,3 @@
-INSERT INTO user_notification_item_unsubscribe (user_id, item_id, item_type) VALUES ('macro|user@user.com', 'document-one', 'document');
+INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level) VALUES ('macro|user@user.com', 'document-one', 'document', 'mute');
 
-INSERT INTO user_notification_item_unsubscribe (user_id, item_id, item_type) VALUES ('macro|user2@user.com', 'document-one', 'document');
+INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level) VALUES ('macro|user2@user.com', 'document-one', 'document', 'mute');
@@ -0,0 +1,27 @@
+-- Per user notification preferences. Anything without a row is delivered.
+CREATE TYPE notification_delivery_channel AS ENUM ('in_app', 'email', 'push');
+
+CREATE TABLE user_notification_preference (
+  user_id TEXT NOT NULL,
+  notification_event_type TEXT NOT NULL,
+  delivery_channel notification_delivery_channel NOT NULL,
+  enabled BOOLEAN NOT NULL,
+  PRIMARY KEY (user_id, notification_event_type, delivery_channel)
+);
+
+-- Overrides for a single channel, project or document
+CREATE TYPE notification_entity_level AS ENUM ('all', 'mentions_only', 'mute');
+
+CREATE TABLE user_notification_entity_preference (
+  user_id TEXT NOT NULL,
+  entity_id TEXT NOT NULL,
+  entity_type TEXT NOT NULL,
+  level notification_entity_level NOT NULL,
+  PRIMARY KEY (user_id, entity_id)
+);
+
+CREATE INDEX idx_user_notification_entity_preference_entity_id ON user_notification_entity_preference (entity_id);
+
+-- Unsubscribing from an item is muting it
+INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level)
+SELECT user_id, item_id, item_type, 'mute' FROM user_notification_item_unsubscribe;
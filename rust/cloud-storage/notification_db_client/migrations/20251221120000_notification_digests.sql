@@ -0,0 +1,17 @@
+-- How often each user gets digest emails. Users without a row get hourly digests.
+CREATE TYPE notification_digest_cadence AS ENUM ('hourly', 'daily');
+
+CREATE TABLE user_notification_digest_preference (
+  user_id TEXT PRIMARY KEY,
+  cadence notification_digest_cadence NOT NULL
+);
+
+-- Notifications waiting to go out in the user's next digest email
+CREATE TABLE notification_digest_item (
+  user_id TEXT NOT NULL,
+  notification_id UUID NOT NULL REFERENCES notification(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (user_id, notification_id)
+);
+
+CREATE INDEX idx_notification_digest_item_created_at ON notification_digest_item (created_at);
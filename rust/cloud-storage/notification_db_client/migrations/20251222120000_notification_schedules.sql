@@ -0,0 +1,20 @@
+-- Quiet hours. Push and email are held while a user is in one and sent once it ends.
+CREATE TABLE user_notification_schedule (
+  user_id TEXT PRIMARY KEY,
+  timezone TEXT NOT NULL DEFAULT 'UTC',
+  working_hours JSONB,
+  quiet_windows JSONB NOT NULL DEFAULT '[]',
+  paused_until TIMESTAMPTZ,
+  mentions_break_through BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+-- Push and email deliveries held until the user's quiet hours end
+CREATE TABLE notification_deferred_delivery (
+  user_id TEXT NOT NULL,
+  notification_id UUID NOT NULL REFERENCES notification(id) ON DELETE CASCADE,
+  delivery_channel notification_delivery_channel NOT NULL,
+  deliver_after TIMESTAMPTZ NOT NULL,
+  PRIMARY KEY (user_id, notification_id, delivery_channel)
+);
+
+CREATE INDEX idx_notification_deferred_delivery_deliver_after ON notification_deferred_delivery (deliver_after);
@@ -0,0 +1,35 @@
+-- Webhooks are another way to receive notifications
+ALTER TYPE notification_delivery_channel ADD VALUE IF NOT EXISTS 'webhook';
+
+CREATE TYPE notification_webhook_format AS ENUM ('macro', 'slack');
+
+-- Endpoints users receive their notifications at
+CREATE TABLE notification_webhook (
+  id UUID PRIMARY KEY,
+  user_id TEXT NOT NULL,
+  url TEXT NOT NULL,
+  secret TEXT NOT NULL,
+  format notification_webhook_format NOT NULL DEFAULT 'macro',
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  last_delivered_at TIMESTAMPTZ,
+  last_failed_at TIMESTAMPTZ,
+  last_error TEXT,
+  consecutive_failures INTEGER NOT NULL DEFAULT 0,
+  -- Set once too many deliveries in a row fail, nothing is delivered until it is enabled again
+  disabled_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_notification_webhook_user_id ON notification_webhook (user_id);
+
+-- The outcome of delivering a notification to a webhook, after any retries
+CREATE TABLE notification_webhook_delivery (
+  id UUID PRIMARY KEY,
+  webhook_id UUID NOT NULL REFERENCES notification_webhook(id) ON DELETE CASCADE,
+  notification_id UUID NOT NULL REFERENCES notification(id) ON DELETE CASCADE,
+  attempts INTEGER NOT NULL,
+  status_code INTEGER,
+  error TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notification_webhook_delivery_webhook_id ON notification_webhook_delivery (webhook_id, created_at DESC);
@@ -0,0 +1,16 @@
+-- Deliveries to webhooks that haven't succeeded or run out of attempts yet. They are kept here
+-- rather than in memory so retries survive restarts.
+CREATE TABLE notification_webhook_pending_delivery (
+  webhook_id UUID NOT NULL REFERENCES notification_webhook(id) ON DELETE CASCADE,
+  notification_id UUID NOT NULL REFERENCES notification(id) ON DELETE CASCADE,
+  -- Kept so every attempt posts the same body
+  body BYTEA NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  -- When the next attempt is due. Claiming an attempt pushes this back, so an attempt that is
+  -- never finished is made again.
+  attempt_after TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (webhook_id, notification_id)
+);
+
+CREATE INDEX idx_notification_webhook_pending_delivery_attempt_after ON notification_webhook_pending_delivery (attempt_after);
@@ -0,0 +1,8 @@
+-- Item unsubscribes were copied into user_notification_entity_preference as mutes. The table is
+-- dropped separately so it stays around while services still reading it are replaced. Anything
+-- unsubscribed in the meantime is copied over before it goes.
+INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level)
+SELECT user_id, item_id, item_type, 'mute' FROM user_notification_item_unsubscribe
+ON CONFLICT (user_id, entity_id) DO NOTHING;
+
+DROP TABLE user_notification_item_unsubscribe;
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use model_notifications::DeliveryChannel;
+use sqlx::types::Uuid;
+
+/// Holds back delivery of the notification over the delivery channel until the given time for
+/// each user
+#[tracing::instrument(skip(db))]
+pub async fn defer_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    notification_id: &Uuid,
+    delivery_channel: DeliveryChannel,
+    deferrals: &[(String, DateTime<Utc>)],
+) -> anyhow::Result<()> {
+    if deferrals.is_empty() {
+        return Ok(());
+    }
+
+    let (user_ids, deliver_after): (Vec<String>, Vec<DateTime<Utc>>) =
+        deferrals.iter().cloned().unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO notification_deferred_delivery
+            (user_id, notification_id, delivery_channel, deliver_after)
+        SELECT user_id, $3, $4, deliver_after
+        FROM UNNEST($1::TEXT[], $2::TIMESTAMPTZ[]) AS d(user_id, deliver_after)
+        ON CONFLICT (user_id, notification_id, delivery_channel)
+        DO UPDATE SET deliver_after = EXCLUDED.deliver_after
+        "#,
+        &user_ids,
+        &deliver_after,
+        notification_id,
+        delivery_channel as DeliveryChannel
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Holds back delivery of the user's notifications over the delivery channel until the given time
+#[tracing::instrument(skip(db))]
+pub async fn defer_user_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    delivery_channel: DeliveryChannel,
+    notification_ids: &[Uuid],
+    deliver_after: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    if notification_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO notification_deferred_delivery
+            (user_id, notification_id, delivery_channel, deliver_after)
+        SELECT $1, notification_id, $3, $4
+        FROM UNNEST($2::UUID[]) AS notification_id
+        ON CONFLICT (user_id, notification_id, delivery_channel)
+        DO UPDATE SET deliver_after = EXCLUDED.deliver_after
+        "#,
+        user_id,
+        notification_ids,
+        delivery_channel as DeliveryChannel,
+        deliver_after
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Makes all of the user's held back deliveries due now, so they are held back again only if the
+/// user is still in their quiet hours
+#[tracing::instrument(skip(db))]
+pub async fn release_user_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE notification_deferred_delivery
+        SET deliver_after = NOW()
+        WHERE user_id = $1 AND deliver_after > NOW()
+        "#,
+        user_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
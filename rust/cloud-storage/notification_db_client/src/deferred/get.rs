@@ -0,0 +1,144 @@
+use model_notifications::{DeliveryChannel, RawNotification};
+
+/// A notification that was held back from a delivery channel
+#[derive(Debug)]
+pub struct DeferredDelivery {
+    pub delivery_channel: DeliveryChannel,
+    pub notification: RawNotification,
+}
+
+/// Gets the users who have held back deliveries that are now due, longest waiting first
+#[tracing::instrument(skip(db))]
+pub async fn get_due_deferred_users(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    limit: i64,
+) -> anyhow::Result<Vec<String>> {
+    let result = sqlx::query_scalar!(
+        r#"
+        SELECT user_id
+        FROM notification_deferred_delivery
+        WHERE deliver_after <= NOW()
+        GROUP BY user_id
+        ORDER BY MIN(deliver_after)
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Removes the user's held back deliveries that are due and returns the ones whose notification
+/// they have not already seen or marked done, oldest first
+#[tracing::instrument(skip(db))]
+pub async fn take_due_deferred_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Vec<DeferredDelivery>> {
+    let result = sqlx::query!(
+        r#"
+        WITH taken AS (
+            DELETE FROM notification_deferred_delivery
+            WHERE user_id = $1 AND deliver_after <= NOW()
+            RETURNING notification_id, delivery_channel
+        )
+        SELECT
+            t.delivery_channel as "delivery_channel: DeliveryChannel",
+            n.id,
+            n.notification_event_type,
+            n.event_item_id,
+            n.event_item_type,
+            n.service_sender,
+            n.created_at::timestamptz as created_at,
+            n.metadata,
+            n.sender_id
+        FROM notification n
+        JOIN taken t ON t.notification_id = n.id
+        WHERE NOT EXISTS (
+            SELECT 1 FROM user_notification un
+            WHERE un.notification_id = n.id
+                AND un.user_id = $1
+                AND (un.seen_at IS NOT NULL OR un.done OR un.deleted_at IS NOT NULL)
+        )
+        ORDER BY n.created_at
+        "#,
+        user_id
+    )
+    .map(|row| DeferredDelivery {
+        delivery_channel: row.delivery_channel,
+        notification: RawNotification {
+            id: row.id,
+            notification_event_type: row.notification_event_type,
+            event_item_id: row.event_item_id,
+            event_item_type: row.event_item_type,
+            service_sender: row.service_sender,
+            created_at: row.created_at,
+            metadata: row.metadata,
+            sender_id: row.sender_id,
+        },
+    })
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deferred::create::defer_deliveries;
+    use chrono::{Duration, Utc};
+    use sqlx::{Pool, Postgres};
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("user_notifications")))]
+    async fn test_take_due_deferred_deliveries(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com".to_string();
+        let later_user_id = "macro|later@user.com".to_string();
+        let notification_id =
+            macro_uuid::string_to_uuid("0193b1ea-a542-7589-893b-2b4a509c1e76").unwrap();
+        // already seen in the fixture
+        let seen_notification_id =
+            macro_uuid::string_to_uuid("0193b1ea-a542-7589-893b-2b4a509c1e73").unwrap();
+        let due = Utc::now() - Duration::minutes(1);
+
+        defer_deliveries(
+            &pool,
+            &notification_id,
+            DeliveryChannel::Push,
+            &[
+                (user_id.clone(), due),
+                (later_user_id.clone(), Utc::now() + Duration::hours(1)),
+            ],
+        )
+        .await
+        .unwrap();
+        defer_deliveries(
+            &pool,
+            &seen_notification_id,
+            DeliveryChannel::Email,
+            &[(user_id.clone(), due)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_due_deferred_users(&pool, 10).await.unwrap(),
+            vec![user_id.clone()]
+        );
+
+        let deliveries = take_due_deferred_deliveries(&pool, &user_id).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].delivery_channel, DeliveryChannel::Push);
+        assert_eq!(deliveries[0].notification.id, notification_id);
+
+        assert!(get_due_deferred_users(&pool, 10).await.unwrap().is_empty());
+        assert!(
+            take_due_deferred_deliveries(&pool, &later_user_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use sqlx::types::Uuid;
+
+/// Queues the notification for each user's next digest email
+#[tracing::instrument(skip(db))]
+pub async fn enqueue_digest_items(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    notification_id: &Uuid,
+    user_ids: &[String],
+) -> anyhow::Result<()> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO notification_digest_item (user_id, notification_id)
+        SELECT user_id, $2 FROM UNNEST($1::TEXT[]) AS user_id
+        ON CONFLICT DO NOTHING
+        "#,
+        user_ids,
+        notification_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
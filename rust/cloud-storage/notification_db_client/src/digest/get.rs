@@ -0,0 +1,145 @@
+use model_notifications::{DigestCadence, RawNotification};
+
+/// Gets the users whose oldest queued notification has waited a full period of their cadence,
+/// oldest first
+#[tracing::instrument(skip(db))]
+pub async fn get_due_digest_users(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    limit: i64,
+) -> anyhow::Result<Vec<(String, DigestCadence)>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            i.user_id,
+            COALESCE(p.cadence, 'hourly') as "cadence!: DigestCadence"
+        FROM notification_digest_item i
+        LEFT JOIN user_notification_digest_preference p ON p.user_id = i.user_id
+        GROUP BY i.user_id, p.cadence
+        HAVING MIN(i.created_at) <= NOW() - CASE
+            WHEN p.cadence = 'daily' THEN INTERVAL '1 day'
+            ELSE INTERVAL '1 hour'
+        END
+        ORDER BY MIN(i.created_at)
+        LIMIT $1
+        "#,
+        limit
+    )
+    .map(|row| (row.user_id, row.cadence))
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Removes everything queued for the user's digest and returns the notifications they have not
+/// already seen or marked done, oldest first
+#[tracing::instrument(skip(db))]
+pub async fn take_digest_notifications(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Vec<RawNotification>> {
+    let result = sqlx::query_as!(
+        RawNotification,
+        r#"
+        WITH taken AS (
+            DELETE FROM notification_digest_item
+            WHERE user_id = $1
+            RETURNING notification_id
+        )
+        SELECT
+            n.id,
+            n.notification_event_type,
+            n.event_item_id,
+            n.event_item_type,
+            n.service_sender,
+            n.created_at::timestamptz as created_at,
+            n.metadata,
+            n.sender_id
+        FROM notification n
+        JOIN taken t ON t.notification_id = n.id
+        WHERE NOT EXISTS (
+            SELECT 1 FROM user_notification un
+            WHERE un.notification_id = n.id
+                AND un.user_id = $1
+                AND (un.seen_at IS NOT NULL OR un.done OR un.deleted_at IS NOT NULL)
+        )
+        ORDER BY n.created_at
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::create::enqueue_digest_items;
+    use crate::preference::upsert::upsert_digest_cadence;
+    use sqlx::{Pool, Postgres};
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("user_notifications")))]
+    async fn test_get_due_digest_users(pool: Pool<Postgres>) {
+        let hourly_user = "macro|hourly@user.com".to_string();
+        let daily_user = "macro|daily@user.com".to_string();
+        let recent_user = "macro|recent@user.com".to_string();
+
+        upsert_digest_cadence(&pool, &daily_user, DigestCadence::Daily)
+            .await
+            .unwrap();
+        enqueue_digest_items(
+            &pool,
+            &macro_uuid::string_to_uuid("0193b1ea-a542-7589-893b-2b4a509c1e76").unwrap(),
+            &[hourly_user.clone(), daily_user.clone(), recent_user.clone()],
+        )
+        .await
+        .unwrap();
+        sqlx::query!(
+            r#"
+            UPDATE notification_digest_item
+            SET created_at = NOW() - INTERVAL '2 hours'
+            WHERE user_id = ANY($1)
+            "#,
+            &[hourly_user.clone(), daily_user.clone()]
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let due = get_due_digest_users(&pool, 10).await.unwrap();
+        assert_eq!(due, vec![(hourly_user, DigestCadence::Hourly)]);
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("user_notifications")))]
+    async fn test_take_digest_notifications(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com".to_string();
+        let unseen = "0193b1ea-a542-7589-893b-2b4a509c1e76";
+        // already seen in the fixture
+        let seen = "0193b1ea-a542-7589-893b-2b4a509c1e73";
+
+        for notification_id in [unseen, seen] {
+            let notification_id = macro_uuid::string_to_uuid(notification_id).unwrap();
+            enqueue_digest_items(&pool, &notification_id, std::slice::from_ref(&user_id))
+                .await
+                .unwrap();
+        }
+
+        let notifications = take_digest_notifications(&pool, &user_id).await.unwrap();
+        assert_eq!(
+            notifications
+                .iter()
+                .map(|n| n.id.to_string())
+                .collect::<Vec<_>>(),
+            vec![unseen.to_string()]
+        );
+
+        assert!(
+            take_digest_notifications(&pool, &user_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
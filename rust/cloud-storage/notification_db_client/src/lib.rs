@@ -1,8 +1,13 @@
 pub mod channel_notification_email_sent;
+pub mod deferred;
 pub mod device;
+pub mod digest;
 pub mod email_unsubscribe_code;
 pub mod notification;
 pub mod notification_email_sent;
+pub mod preference;
+pub mod schedule;
 pub mod unsubscribe;
 pub mod user_mute_notification;
 pub mod user_notification;
+pub mod webhook;
@@ -0,0 +1,21 @@
+/// Removes the user's level for the given entity, so their event preferences apply to it again
+/// Returns false if there was no level to remove
+#[tracing::instrument(skip(db))]
+pub async fn delete_entity_preference(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    entity_id: &str,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM user_notification_entity_preference
+        WHERE user_id = $1 AND entity_id = $2
+        "#,
+        user_id,
+        entity_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
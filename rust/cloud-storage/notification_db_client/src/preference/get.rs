@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use model_notifications::{
+    DeliveryChannel, DigestCadence, EntityNotificationLevel, EntityNotificationPreference,
+    NotificationEventType, NotificationPreference, UserNotificationPreferences,
+};
+
+use crate::{
+    schedule::get::get_user_notification_schedules_bulk,
+    unsubscribe::email::is_email_unsubscribed_batch, user_mute_notification,
+};
+
+/// Gets the event preferences the user has set
+#[tracing::instrument(skip(db))]
+pub async fn get_notification_preferences(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Vec<NotificationPreference>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            notification_event_type,
+            delivery_channel as "delivery_channel: DeliveryChannel",
+            enabled
+        FROM user_notification_preference
+        WHERE user_id = $1
+        ORDER BY notification_event_type, delivery_channel
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let notification_event_type = parse_event_type(&row.notification_event_type, user_id)?;
+            Some(NotificationPreference {
+                notification_event_type,
+                delivery_channel: row.delivery_channel,
+                enabled: row.enabled,
+            })
+        })
+        .collect())
+}
+
+/// Gets the levels the user has set for individual entities
+#[tracing::instrument(skip(db))]
+pub async fn get_entity_preferences(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Vec<EntityNotificationPreference>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT entity_id, entity_type, level as "level: EntityNotificationLevel"
+        FROM user_notification_entity_preference
+        WHERE user_id = $1
+        ORDER BY entity_type, entity_id
+        "#,
+        user_id
+    )
+    .map(|row| EntityNotificationPreference {
+        entity_id: row.entity_id,
+        entity_type: row.entity_type,
+        level: row.level,
+    })
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Gets how often the user gets digest emails
+#[tracing::instrument(skip(db))]
+pub async fn get_digest_cadence(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<DigestCadence> {
+    let cadence = sqlx::query_scalar!(
+        r#"
+        SELECT cadence as "cadence: DigestCadence"
+        FROM user_notification_digest_preference
+        WHERE user_id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(cadence.unwrap_or_default())
+}
+
+/// Gets everything that decides how each of the given users is notified. Every user id is in the
+/// returned map, with the defaults if they have set nothing.
+/// This is what every sender should check before delivering a notification.
+#[tracing::instrument(skip(db))]
+pub async fn get_user_notification_preferences_bulk(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_ids: &[String],
+) -> anyhow::Result<HashMap<String, UserNotificationPreferences>> {
+    let mut preferences: HashMap<String, UserNotificationPreferences> = user_ids
+        .iter()
+        .map(|user_id| (user_id.clone(), UserNotificationPreferences::default()))
+        .collect();
+
+    if user_ids.is_empty() {
+        return Ok(preferences);
+    }
+
+    for user_id in user_mute_notification::get_user_mute_notification_bulk(db, user_ids).await? {
+        if let Some(preference) = preferences.get_mut(&user_id) {
+            preference.muted = true;
+        }
+    }
+
+    let emails: Vec<String> = user_ids
+        .iter()
+        .map(|user_id| user_id.replace("macro|", "").to_lowercase())
+        .collect();
+    let email_unsubscribed: HashMap<String, bool> = is_email_unsubscribed_batch(db, &emails)
+        .await?
+        .into_iter()
+        .collect();
+    for (user_id, preference) in preferences.iter_mut() {
+        let email = user_id.replace("macro|", "").to_lowercase();
+        preference.email_unsubscribed = email_unsubscribed.get(&email).copied().unwrap_or(false);
+    }
+
+    let event_preferences = sqlx::query!(
+        r#"
+        SELECT
+            user_id,
+            notification_event_type,
+            delivery_channel as "delivery_channel: DeliveryChannel",
+            enabled
+        FROM user_notification_preference
+        WHERE user_id = ANY($1)
+        "#,
+        user_ids
+    )
+    .fetch_all(db)
+    .await?;
+
+    for row in event_preferences {
+        let Some(event_type) = parse_event_type(&row.notification_event_type, &row.user_id) else {
+            continue;
+        };
+        if let Some(preference) = preferences.get_mut(&row.user_id) {
+            preference
+                .event_preferences
+                .insert((event_type, row.delivery_channel), row.enabled);
+        }
+    }
+
+    let entity_preferences = sqlx::query!(
+        r#"
+        SELECT user_id, entity_id, level as "level: EntityNotificationLevel"
+        FROM user_notification_entity_preference
+        WHERE user_id = ANY($1)
+        "#,
+        user_ids
+    )
+    .fetch_all(db)
+    .await?;
+
+    for row in entity_preferences {
+        if let Some(preference) = preferences.get_mut(&row.user_id) {
+            preference.entity_levels.insert(row.entity_id, row.level);
+        }
+    }
+
+    for (user_id, schedule) in get_user_notification_schedules_bulk(db, user_ids).await? {
+        if let Some(preference) = preferences.get_mut(&user_id) {
+            preference.schedule = Some(schedule);
+        }
+    }
+
+    Ok(preferences)
+}
+
+/// Event types that no longer exist are ignored
+fn parse_event_type(event_type: &str, user_id: &str) -> Option<NotificationEventType> {
+    NotificationEventType::from_str(event_type)
+        .inspect_err(|e| {
+            tracing::warn!(
+                error=?e,
+                user_id=%user_id,
+                event_type=%event_type,
+                "unknown event type in preferences"
+            );
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preference::upsert::{
+        upsert_digest_cadence, upsert_entity_preference, upsert_notification_preferences,
+    };
+    use crate::unsubscribe::email::upsert_email_unsubscribe;
+    use crate::user_mute_notification::upsert_user_mute_notification;
+    use sqlx::{Pool, Postgres};
+
+    #[sqlx::test]
+    async fn test_get_user_notification_preferences_bulk(pool: Pool<Postgres>) {
+        let user = "macro|user@user.com".to_string();
+        let muted_user = "macro|muted@user.com".to_string();
+        let unsubscribed_user = "macro|unsubscribed@user.com".to_string();
+
+        upsert_notification_preferences(
+            &pool,
+            &user,
+            &[NotificationPreference {
+                notification_event_type: NotificationEventType::ChannelMessageSend,
+                delivery_channel: DeliveryChannel::Push,
+                enabled: false,
+            }],
+        )
+        .await
+        .unwrap();
+        upsert_entity_preference(
+            &pool,
+            &user,
+            "channel-one",
+            "channel",
+            EntityNotificationLevel::MentionsOnly,
+        )
+        .await
+        .unwrap();
+        upsert_entity_preference(
+            &pool,
+            &user,
+            "document-one",
+            "document",
+            EntityNotificationLevel::Mute,
+        )
+        .await
+        .unwrap();
+        upsert_user_mute_notification(&pool, &muted_user)
+            .await
+            .unwrap();
+        upsert_email_unsubscribe(&pool, "unsubscribed@user.com")
+            .await
+            .unwrap();
+
+        let preferences = get_user_notification_preferences_bulk(
+            &pool,
+            &[user.clone(), muted_user.clone(), unsubscribed_user.clone()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(preferences.len(), 3);
+
+        let user = &preferences[&user];
+        assert!(user.allows(
+            NotificationEventType::ChannelMessageSend,
+            "channel-two",
+            DeliveryChannel::InApp
+        ));
+        assert!(!user.allows(
+            NotificationEventType::ChannelMessageSend,
+            "channel-two",
+            DeliveryChannel::Push
+        ));
+        // mentions only
+        assert!(!user.allows_any(NotificationEventType::ChannelMessageSend, "channel-one"));
+        assert!(user.allows(
+            NotificationEventType::ChannelMention,
+            "channel-one",
+            DeliveryChannel::Push
+        ));
+        assert!(!user.allows_any(NotificationEventType::DocumentMention, "document-one"));
+
+        assert!(
+            !preferences[&muted_user].allows_any(NotificationEventType::ChannelInvite, "channel")
+        );
+
+        let unsubscribed_user = &preferences[&unsubscribed_user];
+        assert!(!unsubscribed_user.allows(
+            NotificationEventType::ChannelInvite,
+            "channel",
+            DeliveryChannel::Email
+        ));
+        assert!(unsubscribed_user.allows(
+            NotificationEventType::ChannelInvite,
+            "channel",
+            DeliveryChannel::InApp
+        ));
+    }
+
+    #[sqlx::test]
+    async fn test_upsert_notification_preferences(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com";
+        let mut preference = NotificationPreference {
+            notification_event_type: NotificationEventType::ItemSharedUser,
+            delivery_channel: DeliveryChannel::Email,
+            enabled: false,
+        };
+        upsert_notification_preferences(&pool, user_id, std::slice::from_ref(&preference))
+            .await
+            .unwrap();
+
+        preference.enabled = true;
+        upsert_notification_preferences(&pool, user_id, std::slice::from_ref(&preference))
+            .await
+            .unwrap();
+
+        let result = get_notification_preferences(&pool, user_id).await.unwrap();
+        assert_eq!(result, vec![preference]);
+    }
+
+    #[sqlx::test]
+    async fn test_digest_cadence(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com";
+        assert_eq!(
+            get_digest_cadence(&pool, user_id).await.unwrap(),
+            DigestCadence::Hourly
+        );
+
+        upsert_digest_cadence(&pool, user_id, DigestCadence::Daily)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_digest_cadence(&pool, user_id).await.unwrap(),
+            DigestCadence::Daily
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use model_notifications::{
+    DeliveryChannel, DigestCadence, EntityNotificationLevel, NotificationPreference,
+};
+
+/// Upserts the given event preferences for the user
+#[tracing::instrument(skip(db))]
+pub async fn upsert_notification_preferences(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    preferences: &[NotificationPreference],
+) -> anyhow::Result<()> {
+    let mut transaction = db.begin().await?;
+
+    for preference in preferences {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_notification_preference
+                (user_id, notification_event_type, delivery_channel, enabled)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, notification_event_type, delivery_channel)
+            DO UPDATE SET enabled = $4
+            "#,
+            user_id,
+            preference.notification_event_type.to_string(),
+            preference.delivery_channel as DeliveryChannel,
+            preference.enabled
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Upserts the user's level for the given entity
+#[tracing::instrument(skip(db))]
+pub async fn upsert_entity_preference(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    entity_id: &str,
+    entity_type: &str,
+    level: EntityNotificationLevel,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, entity_id) DO UPDATE SET level = $4
+        "#,
+        user_id,
+        entity_id,
+        entity_type,
+        level as EntityNotificationLevel
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Upserts how often the user gets digest emails
+#[tracing::instrument(skip(db))]
+pub async fn upsert_digest_cadence(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    cadence: DigestCadence,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_notification_digest_preference (user_id, cadence)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET cadence = $2
+        "#,
+        user_id,
+        cadence as DigestCadence
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
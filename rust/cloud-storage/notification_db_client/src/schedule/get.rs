@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use model_notifications::{LocalTimeWindow, NotificationSchedule, UserNotificationSchedule};
+use sqlx::types::Json;
+
+/// Gets the user's quiet hours, if they set any
+#[tracing::instrument(skip(db))]
+pub async fn get_notification_schedule(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Option<NotificationSchedule>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            timezone,
+            working_hours as "working_hours: Json<LocalTimeWindow>",
+            quiet_windows as "quiet_windows: Json<Vec<LocalTimeWindow>>",
+            paused_until,
+            mentions_break_through
+        FROM user_notification_schedule
+        WHERE user_id = $1
+        "#,
+        user_id
+    )
+    .map(|row| NotificationSchedule {
+        timezone: row.timezone,
+        working_hours: row.working_hours.map(|Json(window)| window),
+        quiet_windows: row.quiet_windows.0,
+        paused_until: row.paused_until,
+        mentions_break_through: row.mentions_break_through,
+    })
+    .fetch_optional(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Gets the quiet hours of each of the given users who set any, along with the time now in their
+/// timezone
+#[tracing::instrument(skip(db))]
+pub async fn get_user_notification_schedules_bulk(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_ids: &[String],
+) -> anyhow::Result<HashMap<String, UserNotificationSchedule>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            user_id,
+            timezone,
+            working_hours as "working_hours: Json<LocalTimeWindow>",
+            quiet_windows as "quiet_windows: Json<Vec<LocalTimeWindow>>",
+            paused_until,
+            mentions_break_through,
+            NOW() as "now!",
+            NOW() AT TIME ZONE timezone as "local_now!"
+        FROM user_notification_schedule
+        WHERE user_id = ANY($1)
+        "#,
+        user_ids
+    )
+    .map(|row| {
+        (
+            row.user_id,
+            UserNotificationSchedule {
+                schedule: NotificationSchedule {
+                    timezone: row.timezone,
+                    working_hours: row.working_hours.map(|Json(window)| window),
+                    quiet_windows: row.quiet_windows.0,
+                    paused_until: row.paused_until,
+                    mentions_break_through: row.mentions_break_through,
+                },
+                now: row.now,
+                local_now: row.local_now,
+            },
+        )
+    })
+    .fetch_all(db)
+    .await?;
+
+    Ok(result.into_iter().collect())
+}
+
+/// Whether postgres knows the timezone name
+#[tracing::instrument(skip(db))]
+pub async fn is_valid_timezone(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    timezone: &str,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) as "exists!"
+        "#,
+        timezone
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::upsert::{upsert_notification_schedule, upsert_paused_until};
+    use chrono::{NaiveTime, Utc};
+    use sqlx::{Pool, Postgres};
+
+    #[sqlx::test]
+    async fn test_get_user_notification_schedules_bulk(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com".to_string();
+        let schedule = NotificationSchedule {
+            timezone: "America/New_York".to_string(),
+            working_hours: None,
+            quiet_windows: vec![LocalTimeWindow {
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+                days: vec![],
+            }],
+            paused_until: None,
+            mentions_break_through: true,
+        };
+        upsert_notification_schedule(&pool, &user_id, &schedule)
+            .await
+            .unwrap();
+
+        let schedules = get_user_notification_schedules_bulk(
+            &pool,
+            &[user_id.clone(), "macro|other@user.com".to_string()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(schedules.len(), 1);
+        let user_schedule = &schedules[&user_id];
+        assert_eq!(user_schedule.schedule, schedule);
+        // New York is between 4 and 5 hours behind UTC
+        let offset = user_schedule.now.naive_utc() - user_schedule.local_now;
+        assert!(offset.num_hours() == 4 || offset.num_hours() == 5);
+
+        // Pausing keeps the rest of the schedule
+        let paused_until = Utc::now() + chrono::Duration::hours(2);
+        upsert_paused_until(&pool, &user_id, Some(paused_until))
+            .await
+            .unwrap();
+        let result = get_notification_schedule(&pool, &user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.quiet_windows, schedule.quiet_windows);
+        assert_eq!(
+            result.paused_until.map(|p| p.timestamp()),
+            Some(paused_until.timestamp())
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_is_valid_timezone(pool: Pool<Postgres>) {
+        assert!(is_valid_timezone(&pool, "Europe/London").await.unwrap());
+        assert!(!is_valid_timezone(&pool, "Mars/Olympus_Mons").await.unwrap());
+    }
+}
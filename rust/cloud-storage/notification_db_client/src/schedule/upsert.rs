@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use model_notifications::NotificationSchedule;
+use sqlx::types::Json;
+
+/// Upserts the user's quiet hours
+#[tracing::instrument(skip(db))]
+pub async fn upsert_notification_schedule(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    schedule: &NotificationSchedule,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_notification_schedule
+            (user_id, timezone, working_hours, quiet_windows, paused_until, mentions_break_through)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id) DO UPDATE SET
+            timezone = $2,
+            working_hours = $3,
+            quiet_windows = $4,
+            paused_until = $5,
+            mentions_break_through = $6
+        "#,
+        user_id,
+        schedule.timezone,
+        schedule.working_hours.as_ref().map(Json) as _,
+        Json(&schedule.quiet_windows) as _,
+        schedule.paused_until,
+        schedule.mentions_break_through
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Pauses the user's push and email notifications until the given time, or unpauses them
+#[tracing::instrument(skip(db))]
+pub async fn upsert_paused_until(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    paused_until: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_notification_schedule (user_id, paused_until)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET paused_until = $2
+        "#,
+        user_id,
+        paused_until
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
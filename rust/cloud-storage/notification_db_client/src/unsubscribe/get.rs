@@ -1,6 +1,6 @@
 use model_notifications::UserUnsubscribe;
 
-/// Gets the combined list of a users unsubscribe items and unsubscribe item events
+/// Gets the items the user has unsubscribed from (muted)
 #[tracing::instrument(skip(db))]
 pub async fn get_user_unsubscribes(
     db: &sqlx::Pool<sqlx::Postgres>,
@@ -10,12 +10,12 @@ pub async fn get_user_unsubscribes(
         r#"
         SELECT
             'item' as unsubscribe_type,
-            unsubscribe_item.item_id  as "item_id!",
-            unsubscribe_item.item_type as "item_type!"
+            unsubscribe_item.entity_id as "item_id!",
+            unsubscribe_item.entity_type as "item_type!"
         FROM
-            user_notification_item_unsubscribe unsubscribe_item
+            user_notification_entity_preference unsubscribe_item
         WHERE
-            unsubscribe_item.user_id = $1
+            unsubscribe_item.user_id = $1 AND unsubscribe_item.level = 'mute'
     "#,
         user_id
     )
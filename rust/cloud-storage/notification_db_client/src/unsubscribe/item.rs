@@ -1,4 +1,4 @@
-/// Gets all user_ids that are unsubscribed from the given item
+/// Gets all user_ids that are unsubscribed from (have muted) the given item
 #[tracing::instrument(skip(db))]
 pub async fn get_unsubscribed_item_users(
     db: &sqlx::Pool<sqlx::Postgres>,
@@ -7,8 +7,9 @@ pub async fn get_unsubscribed_item_users(
     let result: Vec<String> = sqlx::query!(
         r#"
         SELECT u.user_id
-        FROM user_notification_item_unsubscribe u
-        WHERE u.item_id = $1
+        FROM user_notification_entity_preference u
+        WHERE u.entity_id = $1 AND u.level = 'mute'
+        ORDER BY u.user_id
         "#,
         item_id
     )
@@ -19,7 +20,7 @@ pub async fn get_unsubscribed_item_users(
     Ok(result)
 }
 
-/// Upserts the given user into the user_notification_item_unsubscribe table
+/// Mutes the given item for the user
 #[tracing::instrument(skip(db))]
 pub async fn upsert_unsubscribed_item_user(
     db: &sqlx::Pool<sqlx::Postgres>,
@@ -29,9 +30,9 @@ pub async fn upsert_unsubscribed_item_user(
 ) -> anyhow::Result<()> {
     sqlx::query!(
         r#"
-        INSERT INTO user_notification_item_unsubscribe (user_id, item_id, item_type)
-        VALUES ($1, $2, $3)
-        ON CONFLICT (user_id, item_id) DO NOTHING
+        INSERT INTO user_notification_entity_preference (user_id, entity_id, entity_type, level)
+        VALUES ($1, $2, $3, 'mute')
+        ON CONFLICT (user_id, entity_id) DO UPDATE SET level = 'mute'
         "#,
         user_id,
         item_id,
@@ -43,7 +44,7 @@ pub async fn upsert_unsubscribed_item_user(
     Ok(())
 }
 
-/// Removes the given user from the user_notification_item_unsubscribe table
+/// Unmutes the given item for the user
 #[tracing::instrument(skip(db))]
 pub async fn remove_unsubscribed_item_user(
     db: &sqlx::Pool<sqlx::Postgres>,
@@ -52,8 +53,8 @@ pub async fn remove_unsubscribed_item_user(
 ) -> anyhow::Result<()> {
     sqlx::query!(
         r#"
-        DELETE FROM user_notification_item_unsubscribe
-        WHERE user_id = $1 AND item_id = $2
+        DELETE FROM user_notification_entity_preference
+        WHERE user_id = $1 AND entity_id = $2 AND level = 'mute'
         "#,
         user_id,
         item_id,
@@ -93,7 +94,7 @@ mod tests {
 
         let result = sqlx::query!(
             r#"
-            SELECT user_id, item_id, item_type FROM user_notification_item_unsubscribe WHERE item_id = 'document-one' AND user_id = 'macro|user@user.com'
+            SELECT user_id, entity_id, entity_type FROM user_notification_entity_preference WHERE entity_id = 'document-one' AND user_id = 'macro|user@user.com'
             "#,
         )
         .fetch_optional(&pool)
@@ -112,7 +113,7 @@ mod tests {
 
         let result = sqlx::query!(
             r#"
-            SELECT user_id, item_id, item_type FROM user_notification_item_unsubscribe WHERE item_id = 'document-one' AND user_id = 'macro|user@user.com'
+            SELECT user_id, entity_id, entity_type FROM user_notification_entity_preference WHERE entity_id = 'document-one' AND user_id = 'macro|user@user.com'
             "#,
         )
         .fetch_optional(&pool)
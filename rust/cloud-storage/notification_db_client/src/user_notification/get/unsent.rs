@@ -6,6 +6,7 @@ pub struct UnsentNotification {
     pub user_id: String,
     pub event_item_id: String,
     pub event_item_type: String,
+    pub notification_event_type: String,
     pub notification_id: Uuid,
     pub created_at: chrono::NaiveDateTime,
 }
@@ -31,6 +32,7 @@ pub async fn get_unsent_notifications_for_users(
             DISTINCT ON (un.user_id, n.event_item_id) un.user_id, n.event_item_id,
             n.created_at,
             n.event_item_type,
+            n.notification_event_type,
             n.id
         FROM user_notification un
         JOIN notification n ON un.notification_id = n.id
@@ -50,6 +52,7 @@ pub async fn get_unsent_notifications_for_users(
             user_id: row.user_id,
             event_item_id: row.event_item_id,
             event_item_type: row.event_item_type,
+            notification_event_type: row.notification_event_type,
             notification_id: row.id,
             created_at: row.created_at,
         }
@@ -0,0 +1,82 @@
+use model_notifications::{NotificationWebhook, WebhookFormat};
+use sqlx::types::Uuid;
+
+/// Adds an endpoint the user receives their notifications at
+#[tracing::instrument(skip(db, secret))]
+pub async fn create_webhook(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    url: &str,
+    secret: &str,
+    format: WebhookFormat,
+) -> anyhow::Result<NotificationWebhook> {
+    let result = sqlx::query_as!(
+        NotificationWebhook,
+        r#"
+        INSERT INTO notification_webhook (id, user_id, url, secret, format)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING
+            id,
+            url,
+            format as "format: WebhookFormat",
+            created_at,
+            last_delivered_at,
+            last_failed_at,
+            last_error,
+            consecutive_failures,
+            disabled_at
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        user_id,
+        url,
+        secret,
+        format as WebhookFormat
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// A delivery of a notification to a webhook
+#[derive(Debug)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: Uuid,
+    pub notification_id: Uuid,
+    pub body: Vec<u8>,
+}
+
+/// Queues deliveries to be attempted as soon as possible
+#[tracing::instrument(skip(db, deliveries), fields(num_deliveries = deliveries.len()))]
+pub async fn enqueue_webhook_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    deliveries: Vec<NewWebhookDelivery>,
+) -> anyhow::Result<()> {
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    let mut webhook_ids = Vec::with_capacity(deliveries.len());
+    let mut notification_ids = Vec::with_capacity(deliveries.len());
+    let mut bodies = Vec::with_capacity(deliveries.len());
+    for delivery in deliveries {
+        webhook_ids.push(delivery.webhook_id);
+        notification_ids.push(delivery.notification_id);
+        bodies.push(delivery.body);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO notification_webhook_pending_delivery (webhook_id, notification_id, body)
+        SELECT * FROM UNNEST($1::UUID[], $2::UUID[], $3::BYTEA[])
+        ON CONFLICT (webhook_id, notification_id) DO NOTHING
+        "#,
+        &webhook_ids,
+        &notification_ids,
+        &bodies
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use sqlx::types::Uuid;
+
+/// Removes one of the user's webhooks along with its delivery log
+/// Returns false if the user has no such webhook
+#[tracing::instrument(skip(db))]
+pub async fn delete_webhook(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    webhook_id: &Uuid,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM notification_webhook
+        WHERE user_id = $1 AND id = $2
+        "#,
+        user_id,
+        webhook_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+
+use model_notifications::{NotificationWebhook, WebhookDelivery, WebhookFormat};
+use sqlx::types::Uuid;
+
+/// What is needed to deliver notifications to a webhook
+#[derive(Debug, Clone)]
+pub struct WebhookTarget {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub format: WebhookFormat,
+}
+
+/// A delivery to a webhook that is due to be attempted
+#[derive(Debug)]
+pub struct PendingWebhookDelivery {
+    pub webhook_id: Uuid,
+    pub notification_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub body: Vec<u8>,
+    /// How many times delivery has been attempted, including the attempt being claimed
+    pub attempts: i32,
+}
+
+/// Gets all of the user's webhooks, oldest first
+#[tracing::instrument(skip(db))]
+pub async fn get_user_webhooks(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+) -> anyhow::Result<Vec<NotificationWebhook>> {
+    let result = sqlx::query_as!(
+        NotificationWebhook,
+        r#"
+        SELECT
+            id,
+            url,
+            format as "format: WebhookFormat",
+            created_at,
+            last_delivered_at,
+            last_failed_at,
+            last_error,
+            consecutive_failures,
+            disabled_at
+        FROM notification_webhook
+        WHERE user_id = $1
+        ORDER BY created_at
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Gets the webhooks that are not disabled for each of the given users who have any
+#[tracing::instrument(skip(db))]
+pub async fn get_webhook_targets_bulk(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_ids: &[String],
+) -> anyhow::Result<HashMap<String, Vec<WebhookTarget>>> {
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            user_id,
+            url,
+            secret,
+            format as "format: WebhookFormat"
+        FROM notification_webhook
+        WHERE user_id = ANY($1) AND disabled_at IS NULL
+        "#,
+        user_ids
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut targets: HashMap<String, Vec<WebhookTarget>> = HashMap::new();
+    for row in result {
+        targets.entry(row.user_id).or_default().push(WebhookTarget {
+            id: row.id,
+            url: row.url,
+            secret: row.secret,
+            format: row.format,
+        });
+    }
+
+    Ok(targets)
+}
+
+/// Gets the most recent deliveries to one of the user's webhooks, newest first
+#[tracing::instrument(skip(db))]
+pub async fn get_webhook_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    webhook_id: &Uuid,
+    limit: i64,
+) -> anyhow::Result<Vec<WebhookDelivery>> {
+    let result = sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT
+            d.id,
+            d.notification_id,
+            d.attempts,
+            d.status_code,
+            d.error,
+            d.created_at
+        FROM notification_webhook_delivery d
+        JOIN notification_webhook w ON w.id = d.webhook_id
+        WHERE w.user_id = $1 AND d.webhook_id = $2
+        ORDER BY d.created_at DESC
+        LIMIT $3
+        "#,
+        user_id,
+        webhook_id,
+        limit
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+/// Claims the deliveries that are due to webhooks that are not disabled, longest waiting first.
+/// A claimed delivery isn't due again until `lease_seconds` have passed, so one whose attempt is
+/// never finished, e.g. because the service restarted, is attempted again.
+#[tracing::instrument(skip(db))]
+pub async fn claim_due_webhook_deliveries(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    limit: i64,
+    lease_seconds: i32,
+) -> anyhow::Result<Vec<PendingWebhookDelivery>> {
+    let result = sqlx::query_as!(
+        PendingWebhookDelivery,
+        r#"
+        WITH due AS (
+            SELECT p.webhook_id, p.notification_id
+            FROM notification_webhook_pending_delivery p
+            JOIN notification_webhook w ON w.id = p.webhook_id
+            WHERE p.attempt_after <= NOW() AND w.disabled_at IS NULL
+            ORDER BY p.attempt_after
+            LIMIT $1
+            FOR UPDATE OF p SKIP LOCKED
+        )
+        UPDATE notification_webhook_pending_delivery p
+        SET
+            attempts = p.attempts + 1,
+            attempt_after = NOW() + $2::INT * INTERVAL '1 second'
+        FROM due, notification_webhook w
+        WHERE p.webhook_id = due.webhook_id
+            AND p.notification_id = due.notification_id
+            AND w.id = p.webhook_id
+        RETURNING
+            p.webhook_id,
+            p.notification_id,
+            w.url,
+            w.secret,
+            p.body,
+            p.attempts
+        "#,
+        limit,
+        lease_seconds
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhook::create::{NewWebhookDelivery, create_webhook, enqueue_webhook_deliveries};
+    use crate::webhook::delete::delete_webhook;
+    use crate::webhook::update::{
+        WebhookDeliveryOutcome, enable_webhook, record_webhook_delivery, retry_webhook_delivery,
+    };
+    use sqlx::{Pool, Postgres};
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("user_notifications")))]
+    async fn test_webhook_deliveries(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com".to_string();
+        let notification_id =
+            macro_uuid::string_to_uuid("0193b1ea-a542-7589-893b-2b4a509c1e76").unwrap();
+
+        let webhook = create_webhook(
+            &pool,
+            &user_id,
+            "https://example.com/notifications",
+            "secret",
+            WebhookFormat::Slack,
+        )
+        .await
+        .unwrap();
+        assert_eq!(webhook.format, WebhookFormat::Slack);
+        assert!(webhook.disabled_at.is_none());
+
+        let failure = WebhookDeliveryOutcome {
+            notification_id,
+            attempts: 4,
+            status_code: Some(500),
+            error: Some("internal server error"),
+        };
+        assert!(
+            !record_webhook_delivery(&pool, &webhook.id, &failure, 2)
+                .await
+                .unwrap()
+        );
+        // The second failure in a row disables it
+        assert!(
+            record_webhook_delivery(&pool, &webhook.id, &failure, 2)
+                .await
+                .unwrap()
+        );
+
+        let webhooks = get_user_webhooks(&pool, &user_id).await.unwrap();
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].consecutive_failures, 2);
+        assert!(webhooks[0].disabled_at.is_some());
+        assert!(
+            get_webhook_targets_bulk(&pool, std::slice::from_ref(&user_id))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(enable_webhook(&pool, &user_id, &webhook.id).await.unwrap());
+        let success = WebhookDeliveryOutcome {
+            notification_id,
+            attempts: 1,
+            status_code: Some(200),
+            error: None,
+        };
+        assert!(
+            !record_webhook_delivery(&pool, &webhook.id, &success, 2)
+                .await
+                .unwrap()
+        );
+
+        let targets = get_webhook_targets_bulk(&pool, std::slice::from_ref(&user_id))
+            .await
+            .unwrap();
+        assert_eq!(targets[&user_id].len(), 1);
+        assert_eq!(targets[&user_id][0].secret, "secret");
+
+        let deliveries = get_webhook_deliveries(&pool, &user_id, &webhook.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 3);
+        assert!(deliveries[0].error.is_none());
+        assert_eq!(deliveries[2].status_code, Some(500));
+
+        // Other users can't see or remove it
+        assert!(
+            get_webhook_deliveries(&pool, "macro|other@user.com", &webhook.id, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            !delete_webhook(&pool, "macro|other@user.com", &webhook.id)
+                .await
+                .unwrap()
+        );
+        assert!(delete_webhook(&pool, &user_id, &webhook.id).await.unwrap());
+        assert!(get_user_webhooks(&pool, &user_id).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures(path = "../../fixtures", scripts("user_notifications")))]
+    async fn test_pending_webhook_deliveries(pool: Pool<Postgres>) {
+        let user_id = "macro|user@user.com".to_string();
+        let notification_id =
+            macro_uuid::string_to_uuid("0193b1ea-a542-7589-893b-2b4a509c1e76").unwrap();
+
+        let webhook = create_webhook(
+            &pool,
+            &user_id,
+            "https://example.com/notifications",
+            "secret",
+            WebhookFormat::Macro,
+        )
+        .await
+        .unwrap();
+
+        let delivery = || NewWebhookDelivery {
+            webhook_id: webhook.id,
+            notification_id,
+            body: b"{}".to_vec(),
+        };
+        enqueue_webhook_deliveries(&pool, vec![delivery()])
+            .await
+            .unwrap();
+        // Queueing the same delivery again doesn't deliver it twice
+        enqueue_webhook_deliveries(&pool, vec![delivery()])
+            .await
+            .unwrap();
+
+        let claimed = claim_due_webhook_deliveries(&pool, 10, 60).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 1);
+        assert_eq!(claimed[0].url, "https://example.com/notifications");
+        assert_eq!(claimed[0].body, b"{}");
+
+        // It is leased until the attempt finishes or the lease runs out
+        assert!(
+            claim_due_webhook_deliveries(&pool, 10, 60)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        retry_webhook_delivery(&pool, &webhook.id, &notification_id, 0)
+            .await
+            .unwrap();
+        let claimed = claim_due_webhook_deliveries(&pool, 10, 0).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 2);
+
+        // An abandoned attempt is claimed again once its lease has run out
+        let claimed = claim_due_webhook_deliveries(&pool, 10, 60).await.unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].attempts, 3);
+
+        let success = WebhookDeliveryOutcome {
+            notification_id,
+            attempts: claimed[0].attempts,
+            status_code: Some(200),
+            error: None,
+        };
+        record_webhook_delivery(&pool, &webhook.id, &success, 2)
+            .await
+            .unwrap();
+        retry_webhook_delivery(&pool, &webhook.id, &notification_id, 0)
+            .await
+            .unwrap();
+        assert!(
+            claim_due_webhook_deliveries(&pool, 10, 60)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Disabling the webhook drops what is pending to it
+        enqueue_webhook_deliveries(&pool, vec![delivery()])
+            .await
+            .unwrap();
+        let failure = WebhookDeliveryOutcome {
+            notification_id,
+            attempts: 1,
+            status_code: Some(500),
+            error: Some("internal server error"),
+        };
+        record_webhook_delivery(&pool, &webhook.id, &failure, 2)
+            .await
+            .unwrap();
+        enqueue_webhook_deliveries(&pool, vec![delivery()])
+            .await
+            .unwrap();
+        assert!(
+            record_webhook_delivery(&pool, &webhook.id, &failure, 2)
+                .await
+                .unwrap()
+        );
+        enable_webhook(&pool, &user_id, &webhook.id).await.unwrap();
+        assert!(
+            claim_due_webhook_deliveries(&pool, 10, 60)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
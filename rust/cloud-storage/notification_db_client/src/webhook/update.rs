@@ -0,0 +1,144 @@
+use sqlx::types::Uuid;
+
+/// The outcome of delivering a notification to a webhook
+#[derive(Debug)]
+pub struct WebhookDeliveryOutcome<'a> {
+    pub notification_id: Uuid,
+    pub attempts: i32,
+    /// The status of the last attempt, if the endpoint responded
+    pub status_code: Option<i32>,
+    /// Why the last attempt failed, None if the delivery succeeded
+    pub error: Option<&'a str>,
+}
+
+/// Turns a disabled webhook of the user's back on and clears its failures
+/// Returns false if the user has no such webhook
+#[tracing::instrument(skip(db))]
+pub async fn enable_webhook(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    user_id: &str,
+    webhook_id: &Uuid,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE notification_webhook
+        SET disabled_at = NULL, consecutive_failures = 0
+        WHERE user_id = $1 AND id = $2
+        "#,
+        user_id,
+        webhook_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Schedules another attempt at a delivery that failed
+#[tracing::instrument(skip(db))]
+pub async fn retry_webhook_delivery(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    webhook_id: &Uuid,
+    notification_id: &Uuid,
+    retry_in_seconds: i32,
+) -> anyhow::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE notification_webhook_pending_delivery
+        SET attempt_after = NOW() + $3::INT * INTERVAL '1 second'
+        WHERE webhook_id = $1 AND notification_id = $2
+        "#,
+        webhook_id,
+        notification_id,
+        retry_in_seconds
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Finishes a delivery: it is no longer pending, it is logged and the webhook's delivery status
+/// is updated. The webhook is disabled once `max_consecutive_failures` deliveries in a row have
+/// failed, and the deliveries still pending to it are dropped.
+/// Returns true if this delivery disabled the webhook.
+#[tracing::instrument(skip(db))]
+pub async fn record_webhook_delivery(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    webhook_id: &Uuid,
+    outcome: &WebhookDeliveryOutcome<'_>,
+    max_consecutive_failures: i32,
+) -> anyhow::Result<bool> {
+    let mut transaction = db.begin().await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM notification_webhook_pending_delivery
+        WHERE webhook_id = $1 AND notification_id = $2
+        "#,
+        webhook_id,
+        outcome.notification_id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO notification_webhook_delivery
+            (id, webhook_id, notification_id, attempts, status_code, error)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        macro_uuid::generate_uuid_v7(),
+        webhook_id,
+        outcome.notification_id,
+        outcome.attempts,
+        outcome.status_code,
+        outcome.error
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    let disabled = sqlx::query_scalar!(
+        r#"
+        UPDATE notification_webhook
+        SET
+            last_delivered_at = CASE WHEN $2::text IS NULL THEN NOW() ELSE last_delivered_at END,
+            last_failed_at = CASE WHEN $2::text IS NULL THEN last_failed_at ELSE NOW() END,
+            last_error = COALESCE($2, last_error),
+            consecutive_failures = CASE
+                WHEN $2::text IS NULL THEN 0
+                ELSE consecutive_failures + 1
+            END,
+            disabled_at = CASE
+                WHEN disabled_at IS NULL AND $2::text IS NOT NULL AND consecutive_failures + 1 >= $3
+                THEN NOW()
+                ELSE disabled_at
+            END
+        WHERE id = $1
+        RETURNING (disabled_at IS NOT NULL AND consecutive_failures = $3) as "disabled!"
+        "#,
+        webhook_id,
+        outcome.error,
+        max_consecutive_failures
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .unwrap_or(false);
+
+    // Nothing more is delivered to a disabled webhook, even once it is enabled again
+    if disabled {
+        sqlx::query!(
+            r#"
+            DELETE FROM notification_webhook_pending_delivery
+            WHERE webhook_id = $1
+            "#,
+            webhook_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(disabled)
+}
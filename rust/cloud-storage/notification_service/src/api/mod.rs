@@ -15,8 +15,10 @@ pub mod context;
 mod device;
 mod health;
 mod notification;
+mod preference;
 mod unsubscribe;
 mod user_notification;
+mod webhook;
 
 mod swagger;
 
@@ -90,6 +92,20 @@ fn api_router(state: ApiContext) -> Router<ApiContext> {
                 macro_middleware::auth::decode_jwt::handler,
             )),
         )
+        .nest(
+            "/preferences",
+            preference::router().layer(axum::middleware::from_fn_with_state(
+                state.jwt_args.clone(),
+                macro_middleware::auth::decode_jwt::handler,
+            )),
+        )
+        .nest(
+            "/webhooks",
+            webhook::router().layer(axum::middleware::from_fn_with_state(
+                state.jwt_args.clone(),
+                macro_middleware::auth::decode_jwt::handler,
+            )),
+        )
         .nest(
             "/notifications",
             notification::router().layer(
@@ -0,0 +1,61 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+use super::put_entity_preference::EntityPreferencePathParams;
+
+/// Removes the user's level for a channel, project or document, so their event preferences apply
+/// to it again.
+#[utoipa::path(
+        delete,
+        operation_id = "delete_entity_notification_preference",
+        path = "/preferences/entity/:entity_type/:entity_id",
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(EntityPreferencePathParams { entity_id, .. }): Path<EntityPreferencePathParams>,
+) -> Result<Response, Response> {
+    let removed = notification_db_client::preference::delete::delete_entity_preference(
+        &ctx.db,
+        &user_context.user_id,
+        &entity_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to remove entity notification preference");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to remove entity notification preference",
+            }),
+        )
+            .into_response()
+    })?;
+
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "no notification preference for entity",
+            }),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
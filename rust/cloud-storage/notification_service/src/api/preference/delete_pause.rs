@@ -0,0 +1,56 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+/// Resumes the user's paused push and email notifications. Anything held back is delivered
+/// shortly after, unless the user is in their quiet hours.
+#[utoipa::path(
+        delete,
+        operation_id = "delete_notification_pause",
+        path = "/preferences/pause",
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+) -> Result<Response, Response> {
+    let internal_error = |e: anyhow::Error| {
+        tracing::error!(error=?e, "unable to resume notifications");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to resume notifications",
+            }),
+        )
+            .into_response()
+    };
+
+    notification_db_client::schedule::upsert::upsert_paused_until(
+        &ctx.db,
+        &user_context.user_id,
+        None,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    notification_db_client::deferred::create::release_user_deliveries(
+        &ctx.db,
+        &user_context.user_id,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
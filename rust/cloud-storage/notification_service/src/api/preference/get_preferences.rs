@@ -0,0 +1,65 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::ErrorResponse;
+
+use crate::{api::context::ApiContext, model::preference::NotificationPreferencesResponse};
+use model::user::UserContext;
+
+/// Gets the user's notification preferences.
+#[utoipa::path(
+        get,
+        operation_id = "get_notification_preferences",
+        path = "/preferences",
+        responses(
+            (status = 200, body=NotificationPreferencesResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+) -> Result<Response, Response> {
+    let internal_error = |e: anyhow::Error| {
+        tracing::error!(error=?e, "unable to get notification preferences");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to get notification preferences",
+            }),
+        )
+            .into_response()
+    };
+
+    let (preferences, entity_preferences, digest_cadence) =
+        tokio::try_join!(
+            notification_db_client::preference::get::get_notification_preferences(
+                &ctx.db,
+                &user_context.user_id,
+            ),
+            notification_db_client::preference::get::get_entity_preferences(
+                &ctx.db,
+                &user_context.user_id,
+            ),
+            notification_db_client::preference::get::get_digest_cadence(
+                &ctx.db,
+                &user_context.user_id,
+            ),
+        )
+        .map_err(internal_error)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(NotificationPreferencesResponse {
+            preferences,
+            entity_preferences,
+            digest_cadence,
+        }),
+    )
+        .into_response())
+}
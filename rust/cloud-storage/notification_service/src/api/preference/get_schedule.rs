@@ -0,0 +1,46 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::ErrorResponse;
+use model_notifications::NotificationSchedule;
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+/// Gets the user's quiet hours.
+#[utoipa::path(
+        get,
+        operation_id = "get_notification_schedule",
+        path = "/preferences/schedule",
+        responses(
+            (status = 200, body=NotificationSchedule),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+) -> Result<Response, Response> {
+    let schedule = notification_db_client::schedule::get::get_notification_schedule(
+        &ctx.db,
+        &user_context.user_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to get notification schedule");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to get notification schedule",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((StatusCode::OK, Json(schedule.unwrap_or_default())).into_response())
+}
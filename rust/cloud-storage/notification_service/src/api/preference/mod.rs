@@ -0,0 +1,35 @@
+use axum::{
+    Router,
+    routing::{delete, get, patch, put},
+};
+
+use crate::api::context::ApiContext;
+
+pub(in crate::api) mod delete_entity_preference;
+pub(in crate::api) mod delete_pause;
+pub(in crate::api) mod get_preferences;
+pub(in crate::api) mod get_schedule;
+pub(in crate::api) mod patch_preferences;
+pub(in crate::api) mod put_digest_cadence;
+pub(in crate::api) mod put_entity_preference;
+pub(in crate::api) mod put_pause;
+pub(in crate::api) mod put_schedule;
+
+pub fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/", get(get_preferences::handler))
+        .route("/", patch(patch_preferences::handler))
+        .route("/digest", put(put_digest_cadence::handler))
+        .route("/schedule", get(get_schedule::handler))
+        .route("/schedule", put(put_schedule::handler))
+        .route("/pause", put(put_pause::handler))
+        .route("/pause", delete(delete_pause::handler))
+        .route(
+            "/entity/:entity_type/:entity_id",
+            put(put_entity_preference::handler),
+        )
+        .route(
+            "/entity/:entity_type/:entity_id",
+            delete(delete_entity_preference::handler),
+        )
+}
@@ -0,0 +1,48 @@
+use axum::{
+    Extension, Json,
+    extract::{self, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::{api::context::ApiContext, model::preference::PatchNotificationPreferencesRequest};
+use model::user::UserContext;
+
+/// Sets whether the user gets each event type over each delivery channel.
+#[utoipa::path(
+        patch,
+        operation_id = "patch_notification_preferences",
+        path = "/preferences",
+        request_body = PatchNotificationPreferencesRequest,
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    extract::Json(req): extract::Json<PatchNotificationPreferencesRequest>,
+) -> Result<Response, Response> {
+    notification_db_client::preference::upsert::upsert_notification_preferences(
+        &ctx.db,
+        &user_context.user_id,
+        &req.preferences,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to update notification preferences");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to update notification preferences",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
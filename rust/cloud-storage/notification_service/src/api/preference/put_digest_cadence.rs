@@ -0,0 +1,48 @@
+use axum::{
+    Extension, Json,
+    extract::{self, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::{api::context::ApiContext, model::preference::PutDigestCadenceRequest};
+use model::user::UserContext;
+
+/// Sets how often the user's digest email goes out.
+#[utoipa::path(
+        put,
+        operation_id = "put_digest_cadence",
+        path = "/preferences/digest",
+        request_body = PutDigestCadenceRequest,
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    extract::Json(req): extract::Json<PutDigestCadenceRequest>,
+) -> Result<Response, Response> {
+    notification_db_client::preference::upsert::upsert_digest_cadence(
+        &ctx.db,
+        &user_context.user_id,
+        req.cadence,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to set digest cadence");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to set digest cadence",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
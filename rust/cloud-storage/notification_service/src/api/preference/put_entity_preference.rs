@@ -0,0 +1,62 @@
+use axum::{
+    Extension, Json,
+    extract::{self, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{api::context::ApiContext, model::preference::PutEntityPreferenceRequest};
+use model::user::UserContext;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct EntityPreferencePathParams {
+    pub entity_type: String,
+    pub entity_id: String,
+}
+
+/// Sets how much the user is notified about a channel, project or document.
+#[utoipa::path(
+        put,
+        operation_id = "put_entity_notification_preference",
+        path = "/preferences/entity/:entity_type/:entity_id",
+        request_body = PutEntityPreferenceRequest,
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(EntityPreferencePathParams {
+        entity_type,
+        entity_id,
+    }): Path<EntityPreferencePathParams>,
+    extract::Json(req): extract::Json<PutEntityPreferenceRequest>,
+) -> Result<Response, Response> {
+    notification_db_client::preference::upsert::upsert_entity_preference(
+        &ctx.db,
+        &user_context.user_id,
+        &entity_id,
+        &entity_type,
+        req.level,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to set entity notification preference");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to set entity notification preference",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
@@ -0,0 +1,74 @@
+use axum::{
+    Extension, Json,
+    extract::{self, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::{api::context::ApiContext, model::preference::PutPauseRequest};
+use model::user::UserContext;
+
+/// The longest the user can pause notifications for, one week
+const MAX_PAUSE_MINUTES: u32 = 7 * 24 * 60;
+
+/// Pauses the user's push and email notifications for a while, e.g. for 2 hours.
+#[utoipa::path(
+        put,
+        operation_id = "put_notification_pause",
+        path = "/preferences/pause",
+        request_body = PutPauseRequest,
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    extract::Json(req): extract::Json<PutPauseRequest>,
+) -> Result<Response, Response> {
+    if req.minutes == 0 || req.minutes > MAX_PAUSE_MINUTES {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "notifications can be paused for up to a week",
+            }),
+        )
+            .into_response());
+    }
+
+    let paused_until = chrono::Utc::now() + chrono::Duration::minutes(req.minutes.into());
+
+    let internal_error = |e: anyhow::Error| {
+        tracing::error!(error=?e, "unable to pause notifications");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to pause notifications",
+            }),
+        )
+            .into_response()
+    };
+
+    notification_db_client::schedule::upsert::upsert_paused_until(
+        &ctx.db,
+        &user_context.user_id,
+        Some(paused_until),
+    )
+    .await
+    .map_err(internal_error)?;
+
+    // A shorter pause than before ends sooner
+    notification_db_client::deferred::create::release_user_deliveries(
+        &ctx.db,
+        &user_context.user_id,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
@@ -0,0 +1,75 @@
+use axum::{
+    Extension, Json,
+    extract::{self, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+use model_notifications::NotificationSchedule;
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+/// Sets the user's quiet hours. Push and email notifications are held back until they end.
+#[utoipa::path(
+        put,
+        operation_id = "put_notification_schedule",
+        path = "/preferences/schedule",
+        request_body = NotificationSchedule,
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    extract::Json(req): extract::Json<NotificationSchedule>,
+) -> Result<Response, Response> {
+    let internal_error = |e: anyhow::Error| {
+        tracing::error!(error=?e, "unable to set notification schedule");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to set notification schedule",
+            }),
+        )
+            .into_response()
+    };
+
+    let is_valid_timezone =
+        notification_db_client::schedule::get::is_valid_timezone(&ctx.db, &req.timezone)
+            .await
+            .map_err(internal_error)?;
+
+    if !is_valid_timezone {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                message: "unknown timezone",
+            }),
+        )
+            .into_response());
+    }
+
+    notification_db_client::schedule::upsert::upsert_notification_schedule(
+        &ctx.db,
+        &user_context.user_id,
+        &req,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    // The new schedule may end the user's quiet hours sooner
+    notification_db_client::deferred::create::release_user_deliveries(
+        &ctx.db,
+        &user_context.user_id,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
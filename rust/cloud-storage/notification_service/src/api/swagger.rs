@@ -4,21 +4,35 @@ use model::{
 };
 use model_notifications::{
     ChannelInviteMetadata, ChannelMentionMetadata, ChannelMessageSendMetadata,
-    ChannelReplyMetadata, CommonChannelMetadata, DeviceType, DocumentMentionMetadata,
-    InviteToTeamMetadata, ItemSharedMetadata, NewEmailMetadata, Notification, NotificationEvent,
-    NotificationEventType, PushNotificationData, UserNotification, UserUnsubscribe,
+    ChannelReplyMetadata, CommonChannelMetadata, DeliveryChannel, DeviceType, DigestCadence,
+    DocumentMentionMetadata, EntityNotificationLevel, EntityNotificationPreference,
+    InviteToTeamMetadata, ItemSharedMetadata, LocalTimeWindow, NewEmailMetadata, Notification,
+    NotificationEvent, NotificationEventType, NotificationPreference, NotificationSchedule,
+    NotificationWebhook, PushNotificationData, UserNotification, UserUnsubscribe, WebhookDelivery,
+    WebhookFormat,
 };
 use utoipa::OpenApi;
 
 use crate::{
     api::{
         device, health, notification,
+        preference::{self, put_entity_preference::EntityPreferencePathParams},
         unsubscribe::{self, unsubscribe_item::UnsubscribeItemPathParams},
         user_notification::{self, get_user_notification::GetAllUserNotificationsResponse},
+        webhook::{self, delete_webhook::WebhookPathParams},
     },
     model::{
-        device::DeviceRequest, notification::CreateNotification,
+        device::DeviceRequest,
+        notification::CreateNotification,
+        preference::{
+            NotificationPreferencesResponse, PatchNotificationPreferencesRequest,
+            PutDigestCadenceRequest, PutEntityPreferenceRequest, PutPauseRequest,
+        },
         user_notification::NotificationBulkRequest,
+        webhook::{
+            CreateWebhookRequest, CreateWebhookResponse, GetWebhookDeliveriesResponse,
+            GetWebhooksResponse,
+        },
     },
 };
 
@@ -56,6 +70,24 @@ use crate::{
                 unsubscribe::unsubscribe_email::handler,
                 unsubscribe::remove_unsubscribe_all::handler,
                 unsubscribe::unsubscribe_all::handler,
+
+                /// /preferences
+                preference::get_preferences::handler,
+                preference::patch_preferences::handler,
+                preference::put_digest_cadence::handler,
+                preference::put_entity_preference::handler,
+                preference::delete_entity_preference::handler,
+                preference::get_schedule::handler,
+                preference::put_schedule::handler,
+                preference::put_pause::handler,
+                preference::delete_pause::handler,
+
+                /// /webhooks
+                webhook::get_webhooks::handler,
+                webhook::create_webhook::handler,
+                webhook::delete_webhook::handler,
+                webhook::enable_webhook::handler,
+                webhook::get_webhook_deliveries::handler,
         ),
         components(
             schemas(
@@ -69,6 +101,27 @@ use crate::{
                         NotificationBulkRequest,
                         UnsubscribeItemPathParams,
                         UserUnsubscribe,
+                        NotificationPreference,
+                        EntityNotificationPreference,
+                        DeliveryChannel,
+                        EntityNotificationLevel,
+                        DigestCadence,
+                        NotificationPreferencesResponse,
+                        PatchNotificationPreferencesRequest,
+                        PutEntityPreferenceRequest,
+                        PutDigestCadenceRequest,
+                        NotificationSchedule,
+                        LocalTimeWindow,
+                        PutPauseRequest,
+                        EntityPreferencePathParams,
+                        NotificationWebhook,
+                        WebhookDelivery,
+                        WebhookFormat,
+                        CreateWebhookRequest,
+                        CreateWebhookResponse,
+                        GetWebhooksResponse,
+                        GetWebhookDeliveriesResponse,
+                        WebhookPathParams,
                         DeviceType,
                         DeviceRequest,
                         PushNotificationData,
@@ -0,0 +1,95 @@
+use axum::{
+    Extension, Json,
+    extract::{self, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::ErrorResponse;
+use outbound_http::{FetchError, webhook::validate_webhook_url};
+use rand::{Rng, distr::Alphanumeric};
+
+use crate::{
+    api::context::ApiContext,
+    model::webhook::{CreateWebhookRequest, CreateWebhookResponse},
+};
+use model::user::UserContext;
+
+/// The most webhooks a user can have
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+
+/// How long the secret deliveries are signed with is
+const SECRET_LENGTH: usize = 32;
+
+fn generate_secret() -> String {
+    rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Adds an endpoint the user receives their notifications at. The secret deliveries are signed
+/// with is only returned here.
+#[utoipa::path(
+        post,
+        operation_id = "create_notification_webhook",
+        path = "/webhooks",
+        request_body = CreateWebhookRequest,
+        responses(
+            (status = 201, body=CreateWebhookResponse),
+            (status = 400, body=ErrorResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    extract::Json(req): extract::Json<CreateWebhookRequest>,
+) -> Result<Response, Response> {
+    let bad_request = |message: &'static str| {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse { message })).into_response()
+    };
+
+    let url = validate_webhook_url(&req.url).map_err(|e| match e {
+        FetchError::Blocked => bad_request("url must be publicly reachable"),
+        _ => bad_request("url must be https"),
+    })?;
+
+    let internal_error = |e: anyhow::Error| {
+        tracing::error!(error=?e, "unable to create webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to create webhook",
+            }),
+        )
+            .into_response()
+    };
+
+    let webhooks =
+        notification_db_client::webhook::get::get_user_webhooks(&ctx.db, &user_context.user_id)
+            .await
+            .map_err(internal_error)?;
+    if webhooks.len() >= MAX_WEBHOOKS_PER_USER {
+        return Err(bad_request("too many webhooks"));
+    }
+
+    let secret = generate_secret();
+    let webhook = notification_db_client::webhook::create::create_webhook(
+        &ctx.db,
+        &user_context.user_id,
+        url.as_str(),
+        &secret,
+        req.format,
+    )
+    .await
+    .map_err(internal_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWebhookResponse { webhook, secret }),
+    )
+        .into_response())
+}
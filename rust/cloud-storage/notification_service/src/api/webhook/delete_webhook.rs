@@ -0,0 +1,66 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use utoipa::ToSchema;
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct WebhookPathParams {
+    pub webhook_id: Uuid,
+}
+
+/// Removes one of the user's webhooks along with its delivery log.
+#[utoipa::path(
+        delete,
+        operation_id = "delete_notification_webhook",
+        path = "/webhooks/:webhook_id",
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(WebhookPathParams { webhook_id }): Path<WebhookPathParams>,
+) -> Result<Response, Response> {
+    let removed = notification_db_client::webhook::delete::delete_webhook(
+        &ctx.db,
+        &user_context.user_id,
+        &webhook_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to remove webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to remove webhook",
+            }),
+        )
+            .into_response()
+    })?;
+
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "webhook not found",
+            }),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
@@ -0,0 +1,60 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::{EmptyResponse, ErrorResponse};
+
+use crate::api::context::ApiContext;
+use model::user::UserContext;
+
+use super::delete_webhook::WebhookPathParams;
+
+/// Turns a webhook that was disabled after repeated failures back on.
+#[utoipa::path(
+        post,
+        operation_id = "enable_notification_webhook",
+        path = "/webhooks/:webhook_id/enable",
+        responses(
+            (status = 200, body=EmptyResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 404, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(WebhookPathParams { webhook_id }): Path<WebhookPathParams>,
+) -> Result<Response, Response> {
+    let enabled = notification_db_client::webhook::update::enable_webhook(
+        &ctx.db,
+        &user_context.user_id,
+        &webhook_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to enable webhook");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to enable webhook",
+            }),
+        )
+            .into_response()
+    })?;
+
+    if !enabled {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                message: "webhook not found",
+            }),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::OK, Json(EmptyResponse {})).into_response())
+}
@@ -0,0 +1,57 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::ErrorResponse;
+
+use crate::{api::context::ApiContext, model::webhook::GetWebhookDeliveriesResponse};
+use model::user::UserContext;
+
+use super::delete_webhook::WebhookPathParams;
+
+/// How many of the most recent deliveries are returned
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+/// Gets the most recent deliveries to one of the user's webhooks, newest first.
+#[utoipa::path(
+        get,
+        operation_id = "get_notification_webhook_deliveries",
+        path = "/webhooks/:webhook_id/deliveries",
+        responses(
+            (status = 200, body=GetWebhookDeliveriesResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+    Path(WebhookPathParams { webhook_id }): Path<WebhookPathParams>,
+) -> Result<Response, Response> {
+    let deliveries = notification_db_client::webhook::get::get_webhook_deliveries(
+        &ctx.db,
+        &user_context.user_id,
+        &webhook_id,
+        DELIVERY_LOG_LIMIT,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error=?e, "unable to get webhook deliveries");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                message: "unable to get webhook deliveries",
+            }),
+        )
+            .into_response()
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(GetWebhookDeliveriesResponse { deliveries }),
+    )
+        .into_response())
+}
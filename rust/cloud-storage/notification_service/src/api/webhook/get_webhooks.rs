@@ -0,0 +1,44 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use model::response::ErrorResponse;
+
+use crate::{api::context::ApiContext, model::webhook::GetWebhooksResponse};
+use model::user::UserContext;
+
+/// Gets the endpoints the user receives their notifications at, along with how delivering to
+/// them is going.
+#[utoipa::path(
+        get,
+        operation_id = "get_notification_webhooks",
+        path = "/webhooks",
+        responses(
+            (status = 200, body=GetWebhooksResponse),
+            (status = 401, body=ErrorResponse),
+            (status = 500, body=ErrorResponse),
+        )
+    )]
+#[tracing::instrument(skip(ctx, user_context), fields(user_id=?user_context.user_id))]
+pub async fn handler(
+    State(ctx): State<ApiContext>,
+    user_context: Extension<UserContext>,
+) -> Result<Response, Response> {
+    let webhooks =
+        notification_db_client::webhook::get::get_user_webhooks(&ctx.db, &user_context.user_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error=?e, "unable to get webhooks");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        message: "unable to get webhooks",
+                    }),
+                )
+                    .into_response()
+            })?;
+
+    Ok((StatusCode::OK, Json(GetWebhooksResponse { webhooks })).into_response())
+}
@@ -0,0 +1,24 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+use crate::api::context::ApiContext;
+
+pub(in crate::api) mod create_webhook;
+pub(in crate::api) mod delete_webhook;
+pub(in crate::api) mod enable_webhook;
+pub(in crate::api) mod get_webhook_deliveries;
+pub(in crate::api) mod get_webhooks;
+
+pub fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/", get(get_webhooks::handler))
+        .route("/", post(create_webhook::handler))
+        .route("/:webhook_id", delete(delete_webhook::handler))
+        .route("/:webhook_id/enable", post(enable_webhook::handler))
+        .route(
+            "/:webhook_id/deliveries",
+            get(get_webhook_deliveries::handler),
+        )
+}
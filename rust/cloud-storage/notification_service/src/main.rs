@@ -128,6 +128,10 @@ pub async fn main() -> anyhow::Result<()> {
     #[cfg(feature = "notification_worker")]
     {
         use std::sync::Arc;
+        // deliveries only reach public addresses and don't follow redirects, so a registered
+        // endpoint can't point them at the internal network
+        let webhook_fetcher =
+            outbound_http::Fetcher::new().context("unable to build webhook fetcher")?;
         let queue_worker_context = notification::context::QueueWorkerContext {
             db: db.clone(),
             worker: Arc::new(notification_worker),
@@ -135,7 +139,29 @@ pub async fn main() -> anyhow::Result<()> {
             ses_client: Arc::new(ses_client),
             sns_client: Arc::new(sns_client.clone()),
             macro_cache_client: Arc::new(macro_cache_client),
+            webhook_fetcher,
         };
+        #[cfg(feature = "send_email_digests")]
+        tokio::spawn({
+            let queue_worker_context = queue_worker_context.clone();
+            async move {
+                notification::send::email::digest::run_digest_worker(queue_worker_context).await
+            }
+        });
+        tokio::spawn({
+            let queue_worker_context = queue_worker_context.clone();
+            async move {
+                notification::send::deferred::run_deferred_delivery_worker(queue_worker_context)
+                    .await
+            }
+        });
+        #[cfg(feature = "send_webhook_notifications")]
+        tokio::spawn({
+            let queue_worker_context = queue_worker_context.clone();
+            async move {
+                notification::send::webhook::run_webhook_delivery_worker(queue_worker_context).await
+            }
+        });
         // Spawn the runner in a task of it's own so we don't block the main thread
         tokio::spawn(
             async move { notification::run_notification_worker(queue_worker_context).await },
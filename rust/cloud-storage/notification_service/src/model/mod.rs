@@ -1,3 +1,5 @@
 pub mod device;
 pub mod notification;
+pub mod preference;
 pub mod user_notification;
+pub mod webhook;
@@ -0,0 +1,42 @@
+use model_notifications::{
+    DigestCadence, EntityNotificationLevel, EntityNotificationPreference, NotificationPreference,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesResponse {
+    /// The event preferences the user has set, anything missing is delivered
+    pub preferences: Vec<NotificationPreference>,
+    /// The levels the user has set for individual channels, projects and documents
+    pub entity_preferences: Vec<EntityNotificationPreference>,
+    /// How often the user's digest email goes out
+    pub digest_cadence: DigestCadence,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchNotificationPreferencesRequest {
+    /// The event preferences to set, others are left as they are
+    pub preferences: Vec<NotificationPreference>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PutEntityPreferenceRequest {
+    pub level: EntityNotificationLevel,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PutDigestCadenceRequest {
+    pub cadence: DigestCadence,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PutPauseRequest {
+    /// How long to pause push and email notifications for
+    pub minutes: u32,
+}
@@ -0,0 +1,34 @@
+use model_notifications::{NotificationWebhook, WebhookDelivery, WebhookFormat};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    /// The https url notifications are posted to
+    pub url: String,
+    /// The shape of what is posted, the notification itself by default
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookResponse {
+    pub webhook: NotificationWebhook,
+    /// The secret deliveries are signed with, it can't be retrieved again
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWebhooksResponse {
+    pub webhooks: Vec<NotificationWebhook>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWebhookDeliveriesResponse {
+    /// The most recent deliveries, newest first
+    pub deliveries: Vec<WebhookDelivery>,
+}
@@ -10,4 +10,6 @@ pub struct QueueWorkerContext {
     pub ses_client: Arc<ses_client::Ses>,
     pub sns_client: Arc<sns_client::SNS>,
     pub macro_cache_client: Arc<macro_cache_client::MacroCache>,
+    /// Delivers notifications to users' webhooks
+    pub webhook_fetcher: outbound_http::Fetcher,
 }
@@ -2,12 +2,15 @@ use std::collections::HashSet;
 
 use super::context::QueueWorkerContext;
 use crate::notification::create::create_notification;
+use crate::notification::rate_limit::{self, RateLimitOutcome};
+use crate::notification::send;
 use crate::notification::user_data::populate_user_data;
 use crate::notification::user_ids::utils::filter_sender_id_from_recipient_ids;
-use crate::notification::{rate_limit, send};
 use anyhow::Context;
-use futures::StreamExt;
-use model_notifications::{Notification, NotificationEventType, NotificationQueueMessage};
+use chrono::{DateTime, Utc};
+use model_notifications::{
+    DeliveryChannel, Notification, NotificationEventType, NotificationQueueMessage,
+};
 
 /// Processes a message from the notification queue.
 /// If the processing  is successful, the message is deleted.
@@ -79,11 +82,12 @@ pub(crate) async fn process_message(
         notification_event: message_body.notification_event,
     };
 
-    // perform basic rate limiting check
-    if rate_limit::rate_limit(&ctx.macro_cache_client, &notification)
+    // check the notification against the rate limit policies
+    let rate_limit_outcome = rate_limit::rate_limit(&ctx.macro_cache_client, &notification)
         .await
-        .context("unable to rate limit notification")?
-    {
+        .context("unable to rate limit notification")?;
+
+    if rate_limit_outcome == RateLimitOutcome::Drop {
         tracing::info!(
             notification_id=%notification_id,
             notification_event_type=?notification.notification_event.event_type(),
@@ -114,14 +118,40 @@ pub(crate) async fn process_message(
         return Ok(());
     }
 
-    // Filter out all user ids that have this type of notification muted or all notifications muted
-    let user_ids = filter_user_ids(
-        &ctx.db,
-        &notification.notification_entity.event_item_id,
-        user_ids,
-    )
-    .await
-    .context("failed to filter user ids")?;
+    // Filter out all user ids whose preferences don't let this notification through at all
+    let event_type = notification.notification_event.event_type();
+    let event_item_id = notification.notification_entity.event_item_id.clone();
+    let preferences =
+        notification_db_client::preference::get::get_user_notification_preferences_bulk(
+            &ctx.db, &user_ids,
+        )
+        .await
+        .context("unable to get notification preferences")?;
+    let allows = |user_id: &String, delivery_channel: DeliveryChannel| {
+        preferences
+            .get(user_id)
+            .is_some_and(|p| p.allows(event_type, &event_item_id, delivery_channel))
+    };
+    let user_ids: Vec<String> = user_ids
+        .into_iter()
+        .filter(|user_id| {
+            preferences
+                .get(user_id)
+                .is_some_and(|p| p.allows_any(event_type, &event_item_id))
+        })
+        .collect();
+
+    // When push and email delivery is held back for the user's quiet hours or a rate limit
+    let rate_limited_until = match rate_limit_outcome {
+        RateLimitOutcome::Defer(until) => Some(until),
+        RateLimitOutcome::Allow | RateLimitOutcome::Drop => None,
+    };
+    let held_until = |user_id: &String| {
+        preferences
+            .get(user_id)
+            .and_then(|p| p.held_until(event_type))
+            .max(rate_limited_until)
+    };
 
     // Handle cleanup
     if notification.notification_event.event_type() == NotificationEventType::RejectTeamInvite {
@@ -170,10 +200,18 @@ pub(crate) async fn process_message(
 
     tracing::trace!(user_ids=?user_ids, "got user ids to notify");
 
+    // Only users who want in-app notifications get one in their inbox
+    let in_app_user_ids: Vec<String> = user_ids
+        .iter()
+        .filter(|user_id| allows(*user_id, DeliveryChannel::InApp))
+        .cloned()
+        .collect();
+
     // Create notification/user_notification(s)
-    let notification = create_notification(&ctx.db, notification, &user_ids, is_important_v0)
-        .await
-        .context("unable to create notification")?;
+    let notification =
+        create_notification(&ctx.db, notification, &in_app_user_ids, is_important_v0)
+            .await
+            .context("unable to create notification")?;
 
     // If there is no notification returned, it means the notification already exists
     let notification = match notification {
@@ -187,15 +225,24 @@ pub(crate) async fn process_message(
 
     let notification_id = notification.id;
 
-    let mut notifications_with_user_data =
-        populate_user_data(notification, &user_ids, is_important_v0).await;
+    let (in_app_notifications, other_notifications): (Vec<_>, Vec<_>) =
+        populate_user_data(notification, &user_ids, is_important_v0)
+            .await
+            .into_iter()
+            .partition(|n| allows(&n.recipient_id, DeliveryChannel::InApp));
 
-    let users_sent_connection_gateway = send::connection_gateway::send_connection_gateway(
-        &ctx.conn_gateway_client,
-        &notifications_with_user_data,
-    )
-    .await
-    .context("unable to process connection gateway")?;
+    let connection_gateway = send::connection_gateway::ConnectionGatewayChannel {
+        conn_gateway_client: ctx.conn_gateway_client.clone(),
+    };
+    let users_sent_connection_gateway =
+        send::channel::deliver(&connection_gateway, &in_app_notifications)
+            .await
+            .context("unable to process connection gateway")?;
+
+    let mut notifications_with_user_data = in_app_notifications
+        .into_iter()
+        .chain(other_notifications)
+        .collect::<Vec<_>>();
 
     tracing::trace!(users_sent_connection_gateway=?users_sent_connection_gateway, "users sent via connection gateway");
 
@@ -207,32 +254,41 @@ pub(crate) async fn process_message(
     });
 
     // Get the user ids to attempt sending push notifications to
-    let users_to_push = user_ids
+    let (users_to_push, users_held_push): (HashSet<&String>, HashSet<&String>) = user_ids
         .iter()
         .filter(|user_id| !users_sent_connection_gateway.contains(*user_id))
-        .collect::<HashSet<&String>>();
+        .filter(|user_id| allows(*user_id, DeliveryChannel::Push))
+        .partition(|user_id| held_until(*user_id).is_none());
+
+    tracing::trace!(users_to_push=?users_to_push, users_held_push=?users_held_push, "users to push");
 
-    tracing::trace!(users_to_push=?users_to_push, "users to push");
+    // Users in their quiet hours get the push notification once they end
+    let push_deferrals: Vec<(String, DateTime<Utc>)> = users_held_push
+        .iter()
+        .filter_map(|user_id| Some(((*user_id).clone(), held_until(*user_id)?)))
+        .collect();
+    notification_db_client::deferred::create::defer_deliveries(
+        &ctx.db,
+        &notification_id,
+        DeliveryChannel::Push,
+        &push_deferrals,
+    )
+    .await
+    .context("unable to hold back push notifications")?;
 
     // Send push notifications
-    let users_sent_push = {
-        #[cfg(feature = "push_notification")]
-        {
-            send::push::process::process_push_notifications(
-                &ctx.db,
-                &ctx.sns_client,
-                &notifications_with_user_data,
-                &users_to_push,
-            )
-            .await
-            .context("unable to process push notifications")?
-        }
-        #[cfg(not(feature = "push_notification"))]
-        {
-            tracing::info!("bypassing push notifications");
-            vec![]
-        }
+    let push_notifications: Vec<_> = notifications_with_user_data
+        .iter()
+        .filter(|n| users_to_push.contains(&n.recipient_id))
+        .cloned()
+        .collect();
+    let push = send::push::process::PushChannel {
+        db: ctx.db.clone(),
+        sns_client: ctx.sns_client.clone(),
     };
+    let users_sent_push = send::channel::deliver(&push, &push_notifications)
+        .await
+        .context("unable to process push notifications")?;
 
     let users_to_email = users_to_push
         .iter()
@@ -246,9 +302,6 @@ pub(crate) async fn process_message(
         .collect::<HashSet<&String>>();
     tracing::trace!(users_to_email=?users_to_email, "users to email");
 
-    // Transform the Vec into a HashSet
-    let users_sent_push = users_sent_push.into_iter().collect::<HashSet<String>>();
-
     tracing::trace!(users_sent_push=?users_sent_push, "users sent push notification");
 
     let users_sent_notification = users_sent_connection_gateway
@@ -267,38 +320,86 @@ pub(crate) async fn process_message(
         tracing::error!(error=?e, "failed to update notifications sent status");
     }
 
-    #[cfg(feature = "send_email_notifications")]
+    // Webhooks get every notification the user allows them as it happens. Quiet hours and rate
+    // limits don't hold them back, the endpoint decides what to do with what it receives.
+    #[cfg(feature = "send_webhook_notifications")]
     {
-        // TODO: update email processing logic to handle NotificationsWithUserData instead of calling concurrently
-        // Process email notifications concurrently for each notification
-        let email_results: Vec<anyhow::Result<()>> =
-            futures::stream::iter(notifications_with_user_data.iter())
-                .then(|notification| {
-                    let ctx_clone = ctx.clone();
-                    async move {
-                        send::email::process_email_notifications(
-                            &ctx_clone,
-                            notification,
-                            std::slice::from_ref(&notification.recipient_id),
-                        )
-                        .await
-                        .context("unable to process email notification")
-                    }
-                })
-                .collect()
-                .await;
-
-        for (idx, result) in email_results.iter().enumerate() {
-            if let Err(e) = result {
-                tracing::error!(
-                    error=?e,
-                    notification_idx=idx,
-                    "failed to process email notification"
-                );
+        let webhook_notifications: Vec<_> = notifications_with_user_data
+            .iter()
+            .filter(|n| allows(&n.recipient_id, DeliveryChannel::Webhook))
+            .cloned()
+            .collect();
+        let webhook = send::webhook::WebhookChannel { db: ctx.db.clone() };
+        // The notification already exists, so failing the message wouldn't queue the webhooks
+        match send::channel::deliver(&webhook, &webhook_notifications).await {
+            Ok(users_sent_webhook) => {
+                tracing::trace!(users_sent_webhook=?users_sent_webhook, "users sent to webhooks");
             }
+            Err(e) => tracing::error!(error=?e, "unable to process webhooks"),
+        }
+    }
+    #[cfg(not(feature = "send_webhook_notifications"))]
+    tracing::info!("bypassing webhook notifications");
+
+    #[cfg(feature = "send_email_notifications")]
+    {
+        // Users already reached live or on their phone aren't emailed about digested events or
+        // mentions
+        let reached = |user_id: &String| {
+            users_sent_connection_gateway.contains(user_id) || users_sent_push.contains(user_id)
+        };
+
+        #[cfg(feature = "send_email_digests")]
+        if send::email::digest::is_digested(event_type) {
+            let digest_user_ids: Vec<String> = notifications_with_user_data
+                .iter()
+                .map(|n| &n.recipient_id)
+                .filter(|user_id| allows(*user_id, DeliveryChannel::Email) && !reached(*user_id))
+                .cloned()
+                .collect();
+            tracing::trace!(digest_user_ids=?digest_user_ids, "queueing for digest");
+
+            notification_db_client::digest::create::enqueue_digest_items(
+                &ctx.db,
+                &notification_id,
+                &digest_user_ids,
+            )
+            .await
+            .context("unable to queue notification for digest")?;
         }
 
-        if email_results.iter().any(|r| r.is_err()) {
+        let (notifications_to_email, notifications_held_email): (Vec<_>, Vec<_>) =
+            notifications_with_user_data
+                .iter()
+                .filter(|n| allows(&n.recipient_id, DeliveryChannel::Email))
+                .filter(|n| !event_type.is_mention() || !reached(&n.recipient_id))
+                .cloned()
+                .partition(|n| held_until(&n.recipient_id).is_none());
+
+        // Digested events wait for the digest, which is held back on its own
+        if send::email::is_emailed_immediately(event_type) {
+            let email_deferrals: Vec<(String, DateTime<Utc>)> = notifications_held_email
+                .iter()
+                .filter_map(|n| Some((n.recipient_id.clone(), held_until(&n.recipient_id)?)))
+                .collect();
+            notification_db_client::deferred::create::defer_deliveries(
+                &ctx.db,
+                &notification_id,
+                DeliveryChannel::Email,
+                &email_deferrals,
+            )
+            .await
+            .context("unable to hold back emails")?;
+        }
+
+        let email = send::email::EmailChannel {
+            queue_worker_context: ctx.clone(),
+        };
+        let users_emailed = send::channel::deliver(&email, &notifications_to_email)
+            .await
+            .context("unable to process email notifications")?;
+
+        if users_emailed.len() < notifications_to_email.len() {
             anyhow::bail!("one or more email notifications failed to process");
         }
     }
@@ -308,29 +409,3 @@ pub(crate) async fn process_message(
     tracing::trace!(time_elapsed=?original_start_time.elapsed(), "message processed");
     Ok(())
 }
-
-/// Filters the user ids to only include users that are not unsubscribed from the given item/event
-async fn filter_user_ids(
-    db: &sqlx::Pool<sqlx::Postgres>,
-    item_id: &str,
-    user_ids: Vec<String>,
-) -> anyhow::Result<Vec<String>> {
-    let mut unsubscribed_users =
-        notification_db_client::user_mute_notification::get_user_mute_notification_bulk(
-            db,
-            user_ids.as_slice(),
-        )
-        .await
-        .context("unable to get user mute notifications")?;
-
-    unsubscribed_users.extend(
-        notification_db_client::unsubscribe::item::get_unsubscribed_item_users(db, item_id)
-            .await
-            .context("unable to get unsubscribed item users")?,
-    );
-
-    Ok(user_ids
-        .into_iter()
-        .filter(|user_id| !unsubscribed_users.contains(user_id))
-        .collect())
-}
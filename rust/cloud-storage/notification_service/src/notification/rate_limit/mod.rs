@@ -0,0 +1,125 @@
+mod policy;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use macro_cache_client::{MacroCache, notification_rate_limit::SlidingWindowHit};
+use model_notifications::Notification;
+use policy::{RATE_LIMIT_POLICIES, RateLimitPolicy};
+
+/// What to do with a notification after checking it against the rate limit policies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitOutcome {
+    /// The notification can proceed as normal
+    Allow,
+    /// The notification should be skipped
+    Drop,
+    /// The notification should be created, but push and email delivery held back until the time
+    Defer(DateTime<Utc>),
+}
+
+/// Checks the notification against each rate limit policy for its event type. The notification is
+/// counted against every policy at once, and against none of them if it is dropped.
+#[tracing::instrument(skip(macro_cache_client, notification), fields(notification_id=?notification.id, notification_event_type=?notification.notification_event.event_type(), event_item_id=?notification.notification_entity.event_item_id, event_item_type=?notification.notification_entity.event_item_type, sender_id=?notification.sender_id))]
+pub async fn rate_limit(
+    macro_cache_client: &MacroCache,
+    notification: &Notification,
+) -> anyhow::Result<RateLimitOutcome> {
+    let event_type = notification.notification_event.event_type();
+    let policies: Vec<&RateLimitPolicy> = RATE_LIMIT_POLICIES
+        .iter()
+        .filter(|policy| policy.applies_to(event_type))
+        .collect();
+
+    if policies.is_empty() {
+        return Ok(RateLimitOutcome::Allow);
+    }
+
+    let windows = policies
+        .iter()
+        .map(|policy| policy.window_for(notification))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let hits = macro_cache_client
+        .hit_notification_rate_limits(&windows)
+        .await
+        .context("unable to check rate limits")?;
+
+    let outcome = outcome(&hits);
+
+    for ((policy, window), hit) in policies.iter().zip(&windows).zip(&hits) {
+        let limited = match outcome {
+            RateLimitOutcome::Allow => false,
+            RateLimitOutcome::Drop => *hit == SlidingWindowHit::Rejected,
+            RateLimitOutcome::Defer(_) => matches!(hit, SlidingWindowHit::Held(_)),
+        };
+
+        if limited {
+            tracing::info!(
+                metric = policy.action.metric(),
+                policy = policy.key,
+                key = %window.key,
+                "rate limit exceeded"
+            );
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Works out what to do with a notification from its hit against each policy's window
+fn outcome(hits: &[SlidingWindowHit]) -> RateLimitOutcome {
+    if hits.contains(&SlidingWindowHit::Rejected) {
+        return RateLimitOutcome::Drop;
+    }
+
+    // The latest time of every policy that holds the notification back
+    hits.iter()
+        .filter_map(|hit| match hit {
+            SlidingWindowHit::Held(until) => Some(*until),
+            SlidingWindowHit::Allowed | SlidingWindowHit::Rejected => None,
+        })
+        .max()
+        .map(|until| RateLimitOutcome::Defer(until.into()))
+        .unwrap_or(RateLimitOutcome::Allow)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn test_outcome() {
+        let soon = UNIX_EPOCH + Duration::from_secs(60);
+        let later = UNIX_EPOCH + Duration::from_secs(120);
+
+        let cases = [
+            (vec![], RateLimitOutcome::Allow),
+            (
+                vec![SlidingWindowHit::Allowed, SlidingWindowHit::Allowed],
+                RateLimitOutcome::Allow,
+            ),
+            (
+                vec![SlidingWindowHit::Allowed, SlidingWindowHit::Rejected],
+                RateLimitOutcome::Drop,
+            ),
+            (
+                vec![SlidingWindowHit::Held(later), SlidingWindowHit::Rejected],
+                RateLimitOutcome::Drop,
+            ),
+            (
+                vec![SlidingWindowHit::Allowed, SlidingWindowHit::Held(soon)],
+                RateLimitOutcome::Defer(soon.into()),
+            ),
+            (
+                vec![SlidingWindowHit::Held(later), SlidingWindowHit::Held(soon)],
+                RateLimitOutcome::Defer(later.into()),
+            ),
+        ];
+
+        for (hits, expected) in cases {
+            assert_eq!(outcome(&hits), expected, "{:?}", hits);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use macro_cache_client::notification_rate_limit::{SlidingWindow, WhenFull};
+use model_notifications::{Notification, NotificationEventType};
+
+/// What happens to a notification once its policy's limit is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitAction {
+    /// The notification is not created or delivered at all
+    Drop,
+    /// The notification is created, but push and email delivery waits until the window has room
+    /// for it. Each delayed notification takes its own turn.
+    Delay,
+    /// The notification is created, but push and email delivery waits until the oldest
+    /// notification leaves the window. Everything held back for the key goes out at the same time,
+    /// collapsed with everything else held back for the user.
+    Collapse,
+}
+
+impl RateLimitAction {
+    /// The metric logged each time the action is taken
+    pub fn metric(&self) -> &'static str {
+        match self {
+            RateLimitAction::Drop => "NOTIFICATION-RATE-LIMIT-DROPPED",
+            RateLimitAction::Delay => "NOTIFICATION-RATE-LIMIT-DELAYED",
+            RateLimitAction::Collapse => "NOTIFICATION-RATE-LIMIT-COLLAPSED",
+        }
+    }
+
+    /// What the sliding window does with a notification once it holds the limit
+    pub fn when_full(&self) -> WhenFull {
+        match self {
+            RateLimitAction::Drop => WhenFull::Reject,
+            RateLimitAction::Delay => WhenFull::Queue,
+            RateLimitAction::Collapse => WhenFull::Wait,
+        }
+    }
+}
+
+/// Limits how many notifications of the event types can go out for a key within a sliding window
+#[derive(Debug)]
+pub struct RateLimitPolicy {
+    pub event_types: &'static [NotificationEventType],
+    /// The key notifications are counted against. Can contain {sender}, {event_item_id} and
+    /// {event_item_type}, e.g. "channel_invite:{sender}"
+    pub key: &'static str,
+    pub limit: u64,
+    pub window: Duration,
+    pub action: RateLimitAction,
+}
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Every rate limit policy. A notification is checked against each policy for its event type.
+pub static RATE_LIMIT_POLICIES: &[RateLimitPolicy] = &[
+    RateLimitPolicy {
+        event_types: &[NotificationEventType::ChannelInvite],
+        key: "channel_invite:{sender}",
+        limit: 10,
+        window: HOUR,
+        action: RateLimitAction::Drop,
+    },
+    RateLimitPolicy {
+        event_types: &[NotificationEventType::InviteToTeam],
+        key: "invite_to_team:{sender}",
+        limit: 5,
+        window: HOUR,
+        action: RateLimitAction::Drop,
+    },
+    // Busy channels send one push and email summing up the messages instead of one each
+    RateLimitPolicy {
+        event_types: &[
+            NotificationEventType::ChannelMessageSend,
+            NotificationEventType::ChannelMessageReply,
+        ],
+        key: "channel_message:{event_item_id}",
+        limit: 20,
+        window: Duration::from_secs(60),
+        action: RateLimitAction::Collapse,
+    },
+];
+
+impl RateLimitPolicy {
+    /// Whether the policy applies to notifications of the event type
+    pub fn applies_to(&self, event_type: NotificationEventType) -> bool {
+        self.event_types.contains(&event_type)
+    }
+
+    /// Fills in the policy's key for the notification
+    pub fn key_for(&self, notification: &Notification) -> anyhow::Result<String> {
+        let mut key = self
+            .key
+            .replace(
+                "{event_item_id}",
+                &notification.notification_entity.event_item_id,
+            )
+            .replace(
+                "{event_item_type}",
+                &notification.notification_entity.event_item_type.to_string(),
+            );
+
+        if key.contains("{sender}") {
+            let sender_id = notification
+                .sender_id
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("rate limit key {} needs a sender id", self.key))?;
+
+            // Aliases of the same email share a limit
+            let email = sender_id.replace("macro|", "");
+            let sender = email_validator::normalize_email(&email)
+                .map(|email| email.to_string())
+                .unwrap_or(email);
+
+            key = key.replace("{sender}", &sender);
+        }
+
+        if key.contains('{') {
+            anyhow::bail!("rate limit key {} has an unknown placeholder", self.key);
+        }
+
+        Ok(key)
+    }
+
+    /// The sliding window the notification is counted against
+    pub fn window_for(&self, notification: &Notification) -> anyhow::Result<SlidingWindow> {
+        Ok(SlidingWindow {
+            key: self.key_for(notification)?,
+            limit: self.limit,
+            window: self.window,
+            when_full: self.action.when_full(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model_entity::EntityType;
+    use model_notifications::{ChannelInviteMetadata, CommonChannelMetadata, NotificationEntity};
+    use models_comms::ChannelType;
+
+    fn channel_invite(sender_id: Option<&str>) -> Notification {
+        Notification {
+            id: macro_uuid::generate_uuid_v7(),
+            notification_entity: NotificationEntity {
+                event_item_id: "channel".to_string(),
+                event_item_type: EntityType::Channel,
+            },
+            service_sender: "test".to_string(),
+            sender_id: sender_id.map(|sender_id| sender_id.to_string()),
+            temporal: Default::default(),
+            notification_event: model_notifications::NotificationEvent::ChannelInvite(
+                ChannelInviteMetadata {
+                    invited_by: "a@macro.com".to_string(),
+                    common: CommonChannelMetadata {
+                        channel_type: ChannelType::Public,
+                        channel_name: "design".to_string(),
+                    },
+                },
+            ),
+        }
+    }
+
+    fn policy(key: &'static str) -> RateLimitPolicy {
+        RateLimitPolicy {
+            event_types: &[NotificationEventType::ChannelInvite],
+            key,
+            limit: 1,
+            window: HOUR,
+            action: RateLimitAction::Drop,
+        }
+    }
+
+    #[test]
+    fn test_key_for() {
+        let notification = channel_invite(Some("macro|a+alias@macro.com"));
+
+        assert_eq!(
+            policy("invite:{sender}").key_for(&notification).unwrap(),
+            "invite:a@macro.com"
+        );
+        assert_eq!(
+            policy("invite:{event_item_type}:{event_item_id}")
+                .key_for(&notification)
+                .unwrap(),
+            "invite:channel:channel"
+        );
+        assert!(policy("invite:{recipient}").key_for(&notification).is_err());
+        assert!(
+            policy("invite:{sender}")
+                .key_for(&channel_invite(None))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_policy_keys_are_valid() {
+        let notification = channel_invite(Some("macro|a@macro.com"));
+
+        for policy in RATE_LIMIT_POLICIES {
+            assert!(!policy.event_types.is_empty(), "{}", policy.key);
+            assert!(policy.limit > 0, "{}", policy.key);
+            assert!(policy.key_for(&notification).is_ok(), "{}", policy.key);
+        }
+    }
+}
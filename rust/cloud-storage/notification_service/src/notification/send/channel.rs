@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use model_notifications::{DeliveryChannel, NotificationWithRecipient};
+
+/// A way of getting notifications to their recipients
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// The channel users set their preferences for
+    fn delivery_channel(&self) -> DeliveryChannel;
+
+    /// Delivers each notification to its recipient
+    /// Returns the recipients the notifications reached
+    async fn deliver(
+        &self,
+        notifications: &[NotificationWithRecipient],
+    ) -> anyhow::Result<HashSet<String>>;
+}
+
+/// Delivers the notifications over the channel, skipping it when there is nothing to deliver
+#[tracing::instrument(skip(channel, notifications), fields(delivery_channel=%channel.delivery_channel(), num_notifications=notifications.len()))]
+pub async fn deliver(
+    channel: &dyn NotificationChannel,
+    notifications: &[NotificationWithRecipient],
+) -> anyhow::Result<HashSet<String>> {
+    if notifications.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let start_time = std::time::Instant::now();
+    let reached = channel.deliver(notifications).await?;
+    tracing::trace!(time_elapsed=?start_time.elapsed(), num_reached=reached.len(), "delivered notifications");
+
+    Ok(reached)
+}
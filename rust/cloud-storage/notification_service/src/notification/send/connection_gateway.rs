@@ -1,11 +1,35 @@
+use async_trait::async_trait;
+use connection_gateway_client::client::ConnectionGatewayClient;
 use connection_gateway_client::model::sender::MessageReceipt;
-use model_notifications::NotificationWithRecipient;
+use model_notifications::{DeliveryChannel, NotificationWithRecipient};
 use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::channel::NotificationChannel;
+
+/// Live delivery to the app over connection gateway
+pub struct ConnectionGatewayChannel {
+    pub conn_gateway_client: Arc<ConnectionGatewayClient>,
+}
+
+#[async_trait]
+impl NotificationChannel for ConnectionGatewayChannel {
+    fn delivery_channel(&self) -> DeliveryChannel {
+        DeliveryChannel::InApp
+    }
+
+    async fn deliver(
+        &self,
+        notifications: &[NotificationWithRecipient],
+    ) -> anyhow::Result<HashSet<String>> {
+        send_connection_gateway(&self.conn_gateway_client, notifications).await
+    }
+}
 
 /// Sends the notification to all users via connection gateway
 /// Returns a list of users who received the notification successfully
 pub async fn send_connection_gateway(
-    conn_gateway_client: &connection_gateway_client::client::ConnectionGatewayClient,
+    conn_gateway_client: &ConnectionGatewayClient,
     notifications: &[NotificationWithRecipient],
 ) -> anyhow::Result<HashSet<String>> {
     let start_time = std::time::Instant::now();
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use model_notifications::{DeliveryChannel, Notification};
+use notification_db_client::deferred::get::DeferredDelivery;
+
+use crate::notification::context::QueueWorkerContext;
+
+/// How often we check for held back notifications that are due
+const DEFERRED_DELIVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The most users caught up on each check. Anyone left over is picked up by the next check.
+const DEFERRED_DELIVERY_BATCH_SIZE: i64 = 500;
+
+/// Delivers the push and email notifications held back during users' quiet hours once they end.
+/// Everything held back for a user is collapsed into a single push notification and email.
+pub async fn run_deferred_delivery_worker(queue_worker_context: QueueWorkerContext) {
+    tracing::info!("deferred delivery worker started");
+    let mut interval = tokio::time::interval(DEFERRED_DELIVERY_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = deliver_due(&queue_worker_context).await {
+            tracing::error!(error=?e, "unable to deliver held back notifications");
+        }
+    }
+}
+
+async fn deliver_due(queue_worker_context: &QueueWorkerContext) -> anyhow::Result<()> {
+    let user_ids = notification_db_client::deferred::get::get_due_deferred_users(
+        &queue_worker_context.db,
+        DEFERRED_DELIVERY_BATCH_SIZE,
+    )
+    .await
+    .context("unable to get users with due deliveries")?;
+
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(num_users=%user_ids.len(), "delivering held back notifications");
+
+    for user_id in user_ids {
+        if let Err(e) = deliver_to_user(queue_worker_context, &user_id).await {
+            tracing::error!(error=?e, user_id=%user_id, "unable to deliver held back notifications");
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(queue_worker_context))]
+async fn deliver_to_user(
+    queue_worker_context: &QueueWorkerContext,
+    user_id: &str,
+) -> anyhow::Result<()> {
+    // Taking the deliveries removes them, so ones that fail to send are dropped rather than
+    // retried on every check
+    let deliveries = notification_db_client::deferred::get::take_due_deferred_deliveries(
+        &queue_worker_context.db,
+        user_id,
+    )
+    .await
+    .context("unable to take due deliveries")?;
+
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    // The user may have changed their preferences or schedule since the deliveries were held back
+    let preferences =
+        notification_db_client::preference::get::get_user_notification_preferences_bulk(
+            &queue_worker_context.db,
+            &[user_id.to_string()],
+        )
+        .await
+        .context("unable to get notification preferences")?;
+    let Some(preferences) = preferences.get(user_id) else {
+        return Ok(());
+    };
+
+    let (email_notifications, push_notifications) = split_by_channel(deliveries);
+
+    // The user moved or extended their quiet hours, so hold everything back again
+    if let Some(quiet_until) = preferences
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedule.quiet_until())
+    {
+        for (delivery_channel, notifications) in [
+            (DeliveryChannel::Email, &email_notifications),
+            (DeliveryChannel::Push, &push_notifications),
+        ] {
+            let notification_ids: Vec<_> = notifications.iter().map(|n| n.id).collect();
+            notification_db_client::deferred::create::defer_user_deliveries(
+                &queue_worker_context.db,
+                user_id,
+                delivery_channel,
+                &notification_ids,
+                quiet_until,
+            )
+            .await
+            .context("unable to hold back deliveries")?;
+        }
+
+        tracing::trace!(quiet_until=%quiet_until, "still quiet, holding back again");
+        return Ok(());
+    }
+
+    let allowed = |notifications: Vec<Notification>, delivery_channel: DeliveryChannel| {
+        notifications
+            .into_iter()
+            .filter(|notification| {
+                preferences.allows(
+                    notification.notification_event.event_type(),
+                    &notification.notification_entity.event_item_id,
+                    delivery_channel,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let email_notifications = allowed(email_notifications, DeliveryChannel::Email);
+    let push_notifications = allowed(push_notifications, DeliveryChannel::Push);
+
+    #[cfg(feature = "push_notification")]
+    if let Some(push_notification) =
+        super::push::generate::generate_summary_push_notification(&push_notifications)
+    {
+        let sent = super::push::process::send_push_notification(
+            &queue_worker_context.db,
+            &queue_worker_context.sns_client,
+            user_id,
+            &push_notification,
+        )
+        .await
+        .context("unable to send push notification")?;
+        tracing::trace!(sent=%sent, num_notifications=%push_notifications.len(), "sent held push");
+    }
+    #[cfg(not(feature = "push_notification"))]
+    if !push_notifications.is_empty() {
+        tracing::info!("bypassing push notifications");
+    }
+
+    #[cfg(feature = "send_email_notifications")]
+    if !email_notifications.is_empty() {
+        super::email::send_held_email(queue_worker_context, user_id, &email_notifications)
+            .await
+            .context("unable to send held email")?;
+    }
+    #[cfg(not(feature = "send_email_notifications"))]
+    if !email_notifications.is_empty() {
+        tracing::info!("bypassing email notifications");
+    }
+
+    Ok(())
+}
+
+/// Splits the deliveries into the notifications held back from email and from push
+fn split_by_channel(deliveries: Vec<DeferredDelivery>) -> (Vec<Notification>, Vec<Notification>) {
+    let mut email_notifications = Vec::new();
+    let mut push_notifications = Vec::new();
+
+    for delivery in deliveries {
+        let notification = match Notification::try_from(delivery.notification) {
+            Ok(notification) => notification,
+            Err(e) => {
+                tracing::warn!(error=?e, "unable to parse held back notification");
+                continue;
+            }
+        };
+
+        match delivery.delivery_channel {
+            DeliveryChannel::Email => email_notifications.push(notification),
+            DeliveryChannel::Push => push_notifications.push(notification),
+            // Neither is ever held back
+            DeliveryChannel::InApp | DeliveryChannel::Webhook => (),
+        }
+    }
+
+    (email_notifications, push_notifications)
+}
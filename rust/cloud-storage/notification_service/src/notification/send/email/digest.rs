@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use model_notifications::{DeliveryChannel, DigestCadence, Notification, NotificationEventType};
+
+use crate::{
+    env::SENDER_ADDRESS,
+    notification::{
+        context::QueueWorkerContext,
+        send::email::{summary_email_address, template},
+    },
+};
+
+/// How often we check for digests that are due
+const DIGEST_INTERVAL: Duration = Duration::from_secs(300);
+
+/// The most digests sent on each check. Anyone left over is picked up by the next check.
+const DIGEST_BATCH_SIZE: i64 = 500;
+
+/// Whether the event waits for the user's next digest email instead of being emailed on its own
+pub fn is_digested(event_type: NotificationEventType) -> bool {
+    matches!(
+        event_type,
+        NotificationEventType::ChannelMessageSend
+            | NotificationEventType::ChannelMessageReply
+            | NotificationEventType::ItemSharedUser
+            | NotificationEventType::ItemSharedOrganization
+    )
+}
+
+/// Sends digest emails to users as their cadence comes due
+pub async fn run_digest_worker(queue_worker_context: QueueWorkerContext) {
+    tracing::info!("digest worker started");
+    let mut interval = tokio::time::interval(DIGEST_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = send_due_digests(&queue_worker_context).await {
+            tracing::error!(error=?e, "unable to send digests");
+        }
+    }
+}
+
+async fn send_due_digests(queue_worker_context: &QueueWorkerContext) -> anyhow::Result<()> {
+    let users = notification_db_client::digest::get::get_due_digest_users(
+        &queue_worker_context.db,
+        DIGEST_BATCH_SIZE,
+    )
+    .await
+    .context("unable to get users with due digests")?;
+
+    if users.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!(num_users=%users.len(), "sending digests");
+
+    for (user_id, cadence) in users {
+        if let Err(e) = send_digest(queue_worker_context, &user_id, cadence).await {
+            tracing::error!(error=?e, user_id=%user_id, "unable to send digest");
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip(queue_worker_context))]
+async fn send_digest(
+    queue_worker_context: &QueueWorkerContext,
+    user_id: &str,
+    cadence: DigestCadence,
+) -> anyhow::Result<()> {
+    // Taking the notifications empties the user's queue, so a digest that fails to send is
+    // dropped rather than retried on every check
+    let notifications: Vec<Notification> =
+        notification_db_client::digest::get::take_digest_notifications(
+            &queue_worker_context.db,
+            user_id,
+        )
+        .await
+        .context("unable to take digest notifications")?
+        .into_iter()
+        .filter_map(|raw| {
+            Notification::try_from(raw)
+                .inspect_err(|e| tracing::warn!(error=?e, "unable to parse digest notification"))
+                .ok()
+        })
+        .collect();
+
+    // The user may have changed their preferences since the notifications were queued
+    let preferences =
+        notification_db_client::preference::get::get_user_notification_preferences_bulk(
+            &queue_worker_context.db,
+            &[user_id.to_string()],
+        )
+        .await
+        .context("unable to get notification preferences")?;
+    let Some(preferences) = preferences.get(user_id) else {
+        return Ok(());
+    };
+    let notifications: Vec<Notification> = notifications
+        .into_iter()
+        .filter(|notification| {
+            preferences.allows(
+                notification.notification_event.event_type(),
+                &notification.notification_entity.event_item_id,
+                DeliveryChannel::Email,
+            )
+        })
+        .collect();
+
+    if notifications.is_empty() {
+        tracing::trace!("nothing left to put in the digest");
+        return Ok(());
+    }
+
+    // A digest that comes due during the user's quiet hours goes out when they end instead
+    if let Some(quiet_until) = preferences
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedule.quiet_until())
+    {
+        let notification_ids: Vec<_> = notifications.iter().map(|n| n.id).collect();
+        notification_db_client::deferred::create::defer_user_deliveries(
+            &queue_worker_context.db,
+            user_id,
+            DeliveryChannel::Email,
+            &notification_ids,
+            quiet_until,
+        )
+        .await
+        .context("unable to hold back digest")?;
+
+        tracing::trace!(quiet_until=%quiet_until, "holding back digest");
+        return Ok(());
+    }
+
+    let Some(email) = summary_email_address(user_id) else {
+        return Ok(());
+    };
+
+    let (email_content, subject) = template::fill_digest_email_template(&notifications, cadence)
+        .context("unable to fill digest email template")?;
+
+    queue_worker_context
+        .ses_client
+        .send_email(&SENDER_ADDRESS, &email, &subject, &email_content)
+        .await
+        .context("unable to send email")?;
+
+    tracing::info!(num_notifications=%notifications.len(), "digest sent");
+
+    Ok(())
+}
@@ -45,30 +45,8 @@ pub async fn filter_emails(
         .collect();
     tracing::debug!(emails=?emails, "filtered valid emails");
 
-    // Get unsubscribed status for emails
-    let email_unsubscribed =
-        notification_db_client::unsubscribe::email::is_email_unsubscribed_batch(
-            &queue_worker_context.db,
-            &emails,
-        )
-        .await
-        .context("unable to check if emails are unsubscribed")?;
-    tracing::debug!(email_unsubscribed=?email_unsubscribed, "got email unsubscribed");
-
-    // Get a list of all matching emails that are subscribed
-    let email: Vec<String> = email_unsubscribed
-        .iter()
-        .filter_map(|(email, is_unsubscribed)| {
-            if *is_unsubscribed {
-                None
-            } else {
-                Some(email.clone())
-            }
-        })
-        .collect();
-    tracing::debug!(emails=?emails, "filtered unsubscribed emails");
-
-    let emails = filter_by_rate_limit(queue_worker_context, notification, &email).await?;
+    // Users who unsubscribed from emails were already left out by their notification preferences
+    let emails = filter_by_rate_limit(queue_worker_context, notification, &emails).await?;
     tracing::debug!(emails=?emails, "filtered by rate limit");
 
     Ok(emails)
@@ -105,6 +83,13 @@ async fn should_email(
         )
         .await
         .context("unable to get should email channel notification"),
+        // Every mention gets its own email
+        NotificationEventType::ChannelMention | NotificationEventType::DocumentMention => {
+            Ok(user_ids
+                .iter()
+                .map(|user_id| (user_id.to_string(), true))
+                .collect())
+        }
         _ => anyhow::bail!("unsupported notification event type"),
     }
 }
@@ -1,18 +1,87 @@
+#[cfg_attr(not(feature = "send_email_digests"), allow(dead_code))]
+pub mod digest;
 mod filter;
 mod template;
 
+use std::collections::HashSet;
+
 use anyhow::Context;
+use async_trait::async_trait;
 use filter::filter_emails;
 use futures::StreamExt;
 use macro_env::Environment;
-use model_notifications::{NotificationEventType, NotificationWithRecipient};
+use model_notifications::{
+    DeliveryChannel, Notification, NotificationEventType, NotificationWithRecipient,
+};
 
+use super::channel::NotificationChannel;
 use crate::{env::SENDER_ADDRESS, notification::context::QueueWorkerContext};
 
+/// Immediate emails, see [process_email_notifications]
+pub struct EmailChannel {
+    pub queue_worker_context: QueueWorkerContext,
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn delivery_channel(&self) -> DeliveryChannel {
+        DeliveryChannel::Email
+    }
+
+    /// Processes the notifications concurrently
+    /// Returns the recipients whose notification was processed without error
+    async fn deliver(
+        &self,
+        notifications: &[NotificationWithRecipient],
+    ) -> anyhow::Result<HashSet<String>> {
+        // TODO: update email processing logic to handle NotificationsWithUserData instead of calling concurrently
+        let email_results: Vec<(&String, anyhow::Result<()>)> =
+            futures::stream::iter(notifications)
+                .then(|notification| async move {
+                    let result = process_email_notifications(
+                        &self.queue_worker_context,
+                        notification,
+                        std::slice::from_ref(&notification.recipient_id),
+                    )
+                    .await
+                    .context("unable to process email notification");
+                    (&notification.recipient_id, result)
+                })
+                .collect()
+                .await;
+
+        let mut processed = HashSet::new();
+        for (user_id, result) in email_results {
+            match result {
+                Ok(()) => {
+                    processed.insert(user_id.clone());
+                }
+                Err(e) => {
+                    tracing::error!(error=?e, user_id=%user_id, "failed to process email notification");
+                }
+            }
+        }
+
+        Ok(processed)
+    }
+}
+
+/// Whether the event is emailed on its own as soon as it happens
+pub fn is_emailed_immediately(event_type: NotificationEventType) -> bool {
+    matches!(
+        event_type,
+        // NotificationEventType::CloudStorageItemSharedUser
+        NotificationEventType::ChannelInvite
+            | NotificationEventType::ChannelMention
+            | NotificationEventType::DocumentMention
+    )
+}
+
 /// Given a notification and list of user ids, this will send any necessary emails to the user.
-/// This is used for *immediate* notifications, such as when a user is added to a channel.
-/// This is not meant to be used for notifications that are polled such as when a user is sent
-/// messages.
+/// This is used for *immediate* notifications, such as when a user is added to a channel or
+/// mentioned.
+/// This is not meant to be used for notifications that go out in digests such as when a user is
+/// sent messages.
 pub async fn process_email_notifications(
     queue_worker_context: &QueueWorkerContext,
     notification: &NotificationWithRecipient,
@@ -23,11 +92,8 @@ pub async fn process_email_notifications(
         .map(|user_id| user_id.to_string())
         .collect::<Vec<String>>();
     // Only include valid events we want to send emails for
-    match notification.inner.notification_event.event_type() {
-        // NotificationEventType::CloudStorageItemSharedUser => {}
-        NotificationEventType::ChannelInvite => {}
-        // NotificationEventType::ChannelMessageSend => {}
-        _ => return Ok(()),
+    if !is_emailed_immediately(notification.inner.notification_event.event_type()) {
+        return Ok(());
     }
     tracing::trace!("sending email notifications");
 
@@ -124,3 +190,50 @@ pub async fn process_email_notifications(
 
     Ok(())
 }
+
+/// Sends one email summing up the notifications that were held back during the user's quiet hours
+#[tracing::instrument(skip(queue_worker_context, notifications))]
+pub async fn send_held_email(
+    queue_worker_context: &QueueWorkerContext,
+    user_id: &str,
+    notifications: &[Notification],
+) -> anyhow::Result<()> {
+    let Some(email) = summary_email_address(user_id) else {
+        return Ok(());
+    };
+
+    let (email_content, subject) = template::fill_held_email_template(notifications)
+        .context("unable to fill held email template")?;
+
+    queue_worker_context
+        .ses_client
+        .send_email(&SENDER_ADDRESS, &email, &subject, &email_content)
+        .await
+        .context("unable to send email")?;
+
+    tracing::info!(num_notifications=%notifications.len(), "held email sent");
+
+    Ok(())
+}
+
+/// The address to send a user their summary emails at, or None if they shouldn't get them
+fn summary_email_address(user_id: &str) -> Option<String> {
+    let email = user_id.replace("macro|", "");
+    if !email_validator::is_valid_email(&email) {
+        tracing::warn!("invalid email {}", email);
+        return None;
+    }
+
+    match Environment::new_or_prod() {
+        Environment::Develop | Environment::Local => {
+            // In dev, we don't want to spam all our our alias emails with notifications
+            if email.contains('+') {
+                tracing::debug!("not sending summary email to alias email {}", email);
+                return None;
+            }
+        }
+        Environment::Production => (),
+    }
+
+    Some(email)
+}
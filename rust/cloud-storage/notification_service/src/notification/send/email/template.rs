@@ -1,16 +1,19 @@
 use crate::{
     config::BASE_URL,
     notification::metadata_utils,
-    templates::{channel_invite, channel_message, item_share},
+    notification::send::summary,
+    templates::{channel_invite, channel_message, digest, item_share},
 };
 use anyhow::Context;
 use macro_env::{Environment, ext::frontend_url::FrontendUrl};
 use model_notifications::{
-    ChannelInviteMetadata, ChannelMessageSendMetadata, NotificationEventType,
-    NotificationWithRecipient,
+    ChannelInviteMetadata, ChannelMessageSendMetadata, DigestCadence, Notification,
+    NotificationEventType, NotificationWithRecipient,
 };
 use url::Url;
 
+static MENTION_FOOTER: &str = "You have received this email because you were mentioned in Macro.";
+
 /// Gets the unsubscribe url for an email
 #[allow(dead_code)]
 fn get_email_unsubscribe_url(email_unsubscribe_code: &str) -> String {
@@ -18,11 +21,14 @@ fn get_email_unsubscribe_url(email_unsubscribe_code: &str) -> String {
     format!("{}/unsubscribe/email/{}", base_url, email_unsubscribe_code)
 }
 
+/// Returns the url to the login page
+fn get_login_url(env: Environment) -> anyhow::Result<Url> {
+    Ok(env.get_frontend_url().join("login")?)
+}
+
 /// Returns the url to the item share page
 fn get_item_share_url(_notification: &NotificationWithRecipient) -> anyhow::Result<Url> {
-    Ok(Environment::new_or_prod()
-        .get_frontend_url()
-        .join("login")?)
+    get_login_url(Environment::new_or_prod())
     // // Depending on the item type we'll need to construct the url differently...
     // let macro_app_url = &*MACRO_APP_URL;
     // let item_id: &str = &notification.inner.notification_entity.event_item_id;
@@ -130,6 +136,128 @@ pub fn fill_email_template(
                 &metadata.common.channel_name,
             ))
         }
+        NotificationEventType::ChannelMention | NotificationEventType::DocumentMention => {
+            // Mentions are sent right away, as a digest of one
+            let line = summary::summarize_one(
+                notification.inner.sender_id.as_deref(),
+                &notification.inner.notification_event,
+            )
+            .context("unable to summarize mention")?;
+
+            let item_url = get_item_share_url(notification).context("unable to create item url")?;
+
+            let content = digest::fill_digest_template(
+                &item_url,
+                std::slice::from_ref(&line),
+                MENTION_FOOTER,
+            );
+
+            Ok((content, line))
+        }
         _ => Err(anyhow::anyhow!("unsupported notification event type")),
     }
 }
+
+/// Returns the filled digest email template and the subject
+#[cfg_attr(not(feature = "send_email_digests"), allow(dead_code))]
+pub fn fill_digest_email_template(
+    notifications: &[Notification],
+    cadence: DigestCadence,
+) -> anyhow::Result<(String, String)> {
+    fill_summary_email_template(
+        notifications,
+        &format!("Your {} Macro digest", cadence),
+        &format!(
+            "You have received this email because of your {} digest settings in Macro.",
+            cadence
+        ),
+    )
+}
+
+/// Returns the filled email template and the subject for notifications held back during quiet
+/// hours
+pub fn fill_held_email_template(
+    notifications: &[Notification],
+) -> anyhow::Result<(String, String)> {
+    fill_summary_email_template(
+        notifications,
+        "Notifications from your quiet hours",
+        "You have received this email because your quiet hours in Macro have ended.",
+    )
+}
+
+/// Fills the digest template with one line per item. The subject is the line itself when there is
+/// only one.
+fn fill_summary_email_template(
+    notifications: &[Notification],
+    subject: &str,
+    footer: &str,
+) -> anyhow::Result<(String, String)> {
+    let lines = summary::summarize(notifications);
+
+    let subject = match lines.as_slice() {
+        [] => anyhow::bail!("no notifications to summarize"),
+        [line] => line.clone(),
+        _ => subject.to_string(),
+    };
+
+    let url = get_login_url(Environment::new_or_prod()).context("unable to create login url")?;
+
+    Ok((digest::fill_digest_template(&url, &lines, footer), subject))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::send::summary::test_notifications::{common, message, share};
+    use models_comms::ChannelType;
+
+    #[test]
+    fn test_fill_digest_email_template_subject() {
+        let design = common(ChannelType::Public, "design");
+        let notifications = vec![
+            message("design", "macro|a@macro.com", design.clone()),
+            message("design", "macro|a@macro.com", design),
+        ];
+        let (_, subject) =
+            fill_digest_email_template(&notifications, DigestCadence::Hourly).unwrap();
+        assert_eq!(subject, "2 new messages in #design");
+
+        let notifications = vec![
+            notifications.into_iter().next().unwrap(),
+            share("roadmap", "Roadmap", "macro|a@macro.com"),
+        ];
+        let (_, subject) =
+            fill_digest_email_template(&notifications, DigestCadence::Daily).unwrap();
+        assert_eq!(subject, "Your daily Macro digest");
+
+        let (_, subject) = fill_held_email_template(&notifications).unwrap();
+        assert_eq!(subject, "Notifications from your quiet hours");
+
+        assert!(fill_digest_email_template(&[], DigestCadence::Hourly).is_err());
+    }
+
+    #[test]
+    fn it_should_create_local_url() {
+        assert_eq!(
+            get_login_url(Environment::Local).unwrap().as_str(),
+            "http://localhost:3000/app/login"
+        );
+    }
+
+    #[test]
+    fn it_should_create_dev_url() {
+        assert_eq!(
+            get_login_url(Environment::Develop).unwrap().as_str(),
+            "https://dev.macro.com/app/login"
+        );
+    }
+
+    #[test]
+    fn it_should_create_prod_url() {
+        assert_eq!(
+            get_login_url(Environment::Production).unwrap().as_str(),
+            "https://macro.com/app/login"
+        );
+    }
+}
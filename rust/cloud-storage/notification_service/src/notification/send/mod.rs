@@ -1,3 +1,7 @@
+pub mod channel;
 pub mod connection_gateway;
+pub mod deferred;
 pub mod email;
 pub mod push;
+pub mod summary;
+pub mod webhook;
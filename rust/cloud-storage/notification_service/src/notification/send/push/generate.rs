@@ -1,13 +1,15 @@
 use crate::config::APPLE_BUNDLE_ID;
+use crate::notification::send::summary;
 use anyhow::Context;
 use aws_sdk_sns::types::MessageAttributeValue;
 use model::document::{FileType, FileTypeExt};
-use model_notifications::NotificationWithRecipient;
+use model_entity::EntityType;
 use model_notifications::{
     APNSPushNotification, ChannelInviteMetadata, ChannelMentionMetadata,
     ChannelMessageSendMetadata, ChannelReplyMetadata, DocumentMentionMetadata,
     NotificationEventType, PushNotificationData,
 };
+use model_notifications::{Notification, NotificationWithRecipient};
 use models_comms::ChannelType;
 use std::str::FromStr;
 use std::{
@@ -55,10 +57,12 @@ pub fn build_message_attributes(
     ]))
 }
 
-type PushNotificationResult = Option<(
+pub type PushNotification = (
     serde_json::Value,
     Option<HashMap<String, MessageAttributeValue>>,
-)>;
+);
+
+type PushNotificationResult = Option<PushNotification>;
 
 /// Given a notification, this generates a push notification object
 /// Returns (message_json, message_attributes) if the notification is valid
@@ -294,18 +298,81 @@ pub fn generate_push_notification(
         notification.inner.notification_event.event_type()
     );
 
+    let push_notification_data = PushNotificationData {
+        notification_entity: notification.inner.notification_entity.clone(),
+        sender_id: notification.inner.sender_id.clone(),
+        open_route,
+    };
+
+    Ok(Some(build_push_notification(
+        &title,
+        &message,
+        &collapse_key,
+        push_notification_data,
+    )))
+}
+
+/// Generates a single push notification summing up the notifications, e.g. the ones held back
+/// during a user's quiet hours
+/// Returns None if none of the notifications can be summarized
+pub fn generate_summary_push_notification(
+    notifications: &[Notification],
+) -> PushNotificationResult {
+    let lines = summary::summarize(notifications);
+
+    let title = match lines.as_slice() {
+        [] => return None,
+        [line] => line.clone(),
+        _ => summary::pluralize(notifications.len(), "new notification"),
+    };
+    let message = if lines.len() > 1 {
+        lines.join("\n")
+    } else {
+        "".to_string()
+    };
+
+    let first = &notifications[0];
+    let same_item = notifications
+        .iter()
+        .all(|n| n.notification_entity.event_item_id == first.notification_entity.event_item_id);
+
+    // Open the channel when everything happened in it, otherwise the app
+    let open_route =
+        if same_item && first.notification_entity.event_item_type == EntityType::Channel {
+            format!("/channel/{}", first.notification_entity.event_item_id)
+        } else {
+            "/".to_string()
+        };
+
+    let push_notification_data = PushNotificationData {
+        notification_entity: first.notification_entity.clone(),
+        sender_id: None,
+        open_route,
+    };
+
+    let collapse_key = format!("summary{}", first.id);
+
+    Some(build_push_notification(
+        &title,
+        &message,
+        &collapse_key,
+        push_notification_data,
+    ))
+}
+
+/// Builds the message json for every platform and the message attributes for a push notification
+fn build_push_notification(
+    title: &str,
+    message: &str,
+    collapse_key: &str,
+    push_notification_data: PushNotificationData,
+) -> PushNotification {
     // hash the collapse key to shorten it
     let mut hasher = DefaultHasher::new();
     collapse_key.hash(&mut hasher);
     let hash = hasher.finish();
     let collapse_key = format!("{:x}", hash);
 
-    let push_notification_data = PushNotificationData {
-        notification_entity: notification.inner.notification_entity.clone(),
-        sender_id: notification.inner.sender_id.clone(),
-        open_route: open_route.clone(),
-    };
-
     let notification_body = serde_json::json!({
         "title": title,
         "body": message,
@@ -339,8 +406,5 @@ pub fn generate_push_notification(
         }).to_string()
     });
 
-    Ok(Some((
-        message_json,
-        build_message_attributes(&collapse_key),
-    )))
+    (message_json, build_message_attributes(&collapse_key))
 }
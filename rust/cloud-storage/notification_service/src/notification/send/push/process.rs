@@ -1,10 +1,49 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use anyhow::Context;
+use async_trait::async_trait;
 use futures::StreamExt;
-use model_notifications::NotificationWithRecipient;
+use model_notifications::{DeliveryChannel, NotificationWithRecipient};
 
-use super::generate::generate_push_notification;
+use super::generate::{PushNotification, generate_push_notification};
+use crate::notification::send::channel::NotificationChannel;
+
+/// Push notifications to the recipients' devices
+pub struct PushChannel {
+    pub db: sqlx::Pool<sqlx::Postgres>,
+    pub sns_client: Arc<sns_client::SNS>,
+}
+
+#[async_trait]
+impl NotificationChannel for PushChannel {
+    fn delivery_channel(&self) -> DeliveryChannel {
+        DeliveryChannel::Push
+    }
+
+    async fn deliver(
+        &self,
+        notifications: &[NotificationWithRecipient],
+    ) -> anyhow::Result<HashSet<String>> {
+        #[cfg(feature = "push_notification")]
+        {
+            let user_ids = notifications
+                .iter()
+                .map(|n| &n.recipient_id)
+                .collect::<HashSet<&String>>();
+            let users_sent_push =
+                process_push_notifications(&self.db, &self.sns_client, notifications, &user_ids)
+                    .await
+                    .context("unable to process push notifications")?;
+            Ok(users_sent_push.into_iter().collect())
+        }
+        #[cfg(not(feature = "push_notification"))]
+        {
+            tracing::info!(num_notifications=%notifications.len(), "bypassing push notifications");
+            Ok(HashSet::new())
+        }
+    }
+}
 
 /// Attempts to send push notifications to provided users
 /// Returns a list of users who were sent push notifications.
@@ -52,33 +91,9 @@ pub async fn process_push_notifications(
     .then(|(user_id, endpoints)| {
         // safe to unwrap because we filter above
         let push_notification = notification_map.get(user_id).unwrap();
-        let message_json = push_notification.0.clone();
-        let message_attributes = push_notification.1.clone();
         async move {
             tracing::trace!(user_id=%user_id, "sending push notification");
-            if endpoints.is_empty() {
-                return (user_id.clone(), PushNotificationStatus::Empty);
-            }
-            let mut success = false;
-
-            // while most users will have 1 endpoint, we need to handle the case where a user
-            // has multiple endpoints
-            for item in endpoints {
-                if let Err(e) = sns_client
-                    .push_notification(item, &message_json.to_string(), message_attributes.clone())
-                    .await
-                {
-                    tracing::warn!(error=?e, "unable to send push notification");
-                    continue;
-                }
-                // we successfully sent at least one push notification to a user's device
-                success = true;
-            }
-            let status = if success {
-                PushNotificationStatus::Success
-            } else {
-                PushNotificationStatus::Fail
-            };
+            let status = send_to_endpoints(sns_client, endpoints, push_notification).await;
             (user_id.clone(), status)
         }
     })
@@ -99,6 +114,62 @@ pub async fn process_push_notifications(
     Ok(users_notified)
 }
 
+/// Sends an already generated push notification to all of a user's devices
+/// Returns whether it reached at least one of them
+#[tracing::instrument(skip(db, sns_client, push_notification))]
+pub async fn send_push_notification(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    sns_client: &sns_client::SNS,
+    user_id: &str,
+    push_notification: &PushNotification,
+) -> anyhow::Result<bool> {
+    let user_device_endpoints =
+        notification_db_client::device::get_users_device_endpoints(db, &[user_id.to_string()])
+            .await?;
+
+    let Some(endpoints) = user_device_endpoints.get(user_id) else {
+        return Ok(false);
+    };
+
+    let status = send_to_endpoints(sns_client, endpoints, push_notification).await;
+
+    Ok(matches!(status, PushNotificationStatus::Success))
+}
+
+/// Sends the push notification to each of the endpoints
+async fn send_to_endpoints(
+    sns_client: &sns_client::SNS,
+    endpoints: &[String],
+    push_notification: &PushNotification,
+) -> PushNotificationStatus {
+    if endpoints.is_empty() {
+        return PushNotificationStatus::Empty;
+    }
+
+    let (message_json, message_attributes) = push_notification;
+    let mut success = false;
+
+    // while most users will have 1 endpoint, we need to handle the case where a user
+    // has multiple endpoints
+    for item in endpoints {
+        if let Err(e) = sns_client
+            .push_notification(item, &message_json.to_string(), message_attributes.clone())
+            .await
+        {
+            tracing::warn!(error=?e, "unable to send push notification");
+            continue;
+        }
+        // we successfully sent at least one push notification to a user's device
+        success = true;
+    }
+
+    if success {
+        PushNotificationStatus::Success
+    } else {
+        PushNotificationStatus::Fail
+    }
+}
+
 pub enum PushNotificationStatus {
     /// No push notifications were sent
     Empty,
@@ -0,0 +1,315 @@
+use model_notifications::{CommonChannelMetadata, Notification, NotificationEvent};
+use models_comms::ChannelType;
+
+/// What a summary line counts. Notifications about the same item are only collapsed into one
+/// line when they count the same thing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SummaryKind {
+    Messages,
+    Mentions,
+    Shares,
+    Invites,
+}
+
+impl SummaryKind {
+    fn from_event(event: &NotificationEvent) -> Option<Self> {
+        match event {
+            NotificationEvent::ChannelMessageSend(_)
+            | NotificationEvent::ChannelMessageReply(_) => Some(SummaryKind::Messages),
+            NotificationEvent::ChannelMention(_) | NotificationEvent::DocumentMention(_) => {
+                Some(SummaryKind::Mentions)
+            }
+            NotificationEvent::ItemSharedUser(_) | NotificationEvent::ItemSharedOrganization(_) => {
+                Some(SummaryKind::Shares)
+            }
+            NotificationEvent::ChannelInvite(_) => Some(SummaryKind::Invites),
+            _ => None,
+        }
+    }
+}
+
+/// Collapses the notifications into one line per item, in the order the items first show up.
+/// e.g. "12 new messages in #design"
+/// Used for digests and for everything held back during quiet hours.
+pub fn summarize(notifications: &[Notification]) -> Vec<String> {
+    let mut groups: Vec<((&str, SummaryKind), Vec<(Option<&str>, &NotificationEvent)>)> =
+        Vec::new();
+
+    for notification in notifications {
+        let Some(kind) = SummaryKind::from_event(&notification.notification_event) else {
+            continue;
+        };
+
+        let key = (
+            notification.notification_entity.event_item_id.as_str(),
+            kind,
+        );
+        let entry = (
+            notification.sender_id.as_deref(),
+            &notification.notification_event,
+        );
+
+        match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+            Some((_, group)) => group.push(entry),
+            None => groups.push((key, vec![entry])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, kind), group)| summarize_group(kind, &group))
+        .collect()
+}
+
+/// The summary line for a single notification, if it can be summarized
+pub fn summarize_one(sender_id: Option<&str>, event: &NotificationEvent) -> Option<String> {
+    let kind = SummaryKind::from_event(event)?;
+    Some(summarize_group(kind, &[(sender_id, event)]))
+}
+
+/// Writes the summary line for notifications of the same kind about the same item
+fn summarize_group(kind: SummaryKind, group: &[(Option<&str>, &NotificationEvent)]) -> String {
+    let count = group.len();
+    let event = group[0].1;
+    let location = summary_location(event);
+
+    let mut senders: Vec<String> = Vec::new();
+    for (sender_id, _) in group {
+        let sender = sender_id
+            .map(|sender_id| sender_id.replace("macro|", ""))
+            .unwrap_or_else(|| "Someone".to_string());
+        if !senders.contains(&sender) {
+            senders.push(sender);
+        }
+    }
+
+    // e.g. "a@macro.com and 2 others"
+    let people = match senders.len() {
+        1 => senders[0].clone(),
+        n => format!("{} and {}", senders[0], pluralize(n - 1, "other")),
+    };
+
+    match kind {
+        SummaryKind::Messages => {
+            let is_direct_message = channel_metadata(event)
+                .is_some_and(|common| common.channel_type == ChannelType::DirectMessage);
+
+            if is_direct_message && senders.len() == 1 {
+                format!("{} from {}", pluralize(count, "new message"), senders[0])
+            } else {
+                format!("{} in {}", pluralize(count, "new message"), location)
+            }
+        }
+        SummaryKind::Mentions if count == 1 => {
+            format!("{} mentioned you in {}", senders[0], location)
+        }
+        SummaryKind::Mentions => format!("{} in {}", pluralize(count, "mention"), location),
+        SummaryKind::Shares => format!("{} shared {}", people, location),
+        SummaryKind::Invites => format!("{} invited you to {}", people, location),
+    }
+}
+
+fn channel_metadata(event: &NotificationEvent) -> Option<&CommonChannelMetadata> {
+    match event {
+        NotificationEvent::ChannelMessageSend(metadata) => Some(&metadata.common),
+        NotificationEvent::ChannelMessageReply(metadata) => Some(&metadata.common),
+        NotificationEvent::ChannelMention(metadata) => Some(&metadata.common),
+        NotificationEvent::ChannelInvite(metadata) => Some(&metadata.common),
+        _ => None,
+    }
+}
+
+/// Where the notification happened, as it reads in a summary line
+fn summary_location(event: &NotificationEvent) -> String {
+    if let Some(common) = channel_metadata(event) {
+        return match common.channel_type {
+            ChannelType::DirectMessage => "a direct message".to_string(),
+            _ if !common.channel_name.is_empty() => format!("#{}", common.channel_name),
+            ChannelType::Private => "a group channel".to_string(),
+            ChannelType::Public => "a public channel".to_string(),
+            ChannelType::Organization => "an organization channel".to_string(),
+        };
+    }
+
+    let name = match event {
+        NotificationEvent::DocumentMention(metadata) => Some(metadata.document_name.clone()),
+        NotificationEvent::ItemSharedUser(metadata) => metadata.item_name.clone(),
+        NotificationEvent::ItemSharedOrganization(metadata) => metadata.item_name.clone(),
+        _ => None,
+    };
+
+    name.filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "an item".to_string())
+}
+
+pub fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_notifications {
+    use model_entity::EntityType;
+    use model_notifications::{
+        ChannelMessageSendMetadata, CommonChannelMetadata, ItemSharedMetadata, Notification,
+        NotificationEntity, NotificationEvent,
+    };
+    use models_comms::ChannelType;
+
+    pub fn common(channel_type: ChannelType, channel_name: &str) -> CommonChannelMetadata {
+        CommonChannelMetadata {
+            channel_type,
+            channel_name: channel_name.to_string(),
+        }
+    }
+
+    pub fn notification(
+        event_item_id: &str,
+        event_item_type: EntityType,
+        sender_id: &str,
+        notification_event: NotificationEvent,
+    ) -> Notification {
+        Notification {
+            id: macro_uuid::generate_uuid_v7(),
+            notification_entity: NotificationEntity {
+                event_item_id: event_item_id.to_string(),
+                event_item_type,
+            },
+            service_sender: "test".to_string(),
+            sender_id: Some(sender_id.to_string()),
+            temporal: Default::default(),
+            notification_event,
+        }
+    }
+
+    pub fn message(
+        channel_id: &str,
+        sender_id: &str,
+        common: CommonChannelMetadata,
+    ) -> Notification {
+        notification(
+            channel_id,
+            EntityType::Channel,
+            sender_id,
+            NotificationEvent::ChannelMessageSend(ChannelMessageSendMetadata {
+                sender: sender_id.to_string(),
+                message_content: "hello".to_string(),
+                message_id: "message".to_string(),
+                common,
+            }),
+        )
+    }
+
+    pub fn share(item_id: &str, item_name: &str, sender_id: &str) -> Notification {
+        notification(
+            item_id,
+            EntityType::Document,
+            sender_id,
+            NotificationEvent::ItemSharedUser(ItemSharedMetadata {
+                user_ids: vec![],
+                item_type: EntityType::Document,
+                item_id: item_id.to_string(),
+                item_name: Some(item_name.to_string()),
+                shared_by: sender_id.to_string(),
+                permission_level: None,
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_notifications::*;
+    use super::*;
+    use model_entity::EntityType;
+    use model_notifications::{ChannelInviteMetadata, ChannelReplyMetadata};
+
+    #[test]
+    fn test_summarize_collapses_channel_messages() {
+        let design = common(ChannelType::Public, "design");
+        let mut notifications: Vec<Notification> = (0..11)
+            .map(|_| message("design", "macro|a@macro.com", design.clone()))
+            .collect();
+        notifications.push(notification(
+            "design",
+            EntityType::Channel,
+            "macro|b@macro.com",
+            NotificationEvent::ChannelMessageReply(ChannelReplyMetadata {
+                thread_id: "thread".to_string(),
+                message_id: "reply".to_string(),
+                user_id: "macro|b@macro.com".to_string(),
+                message_content: "hi".to_string(),
+                common: design,
+            }),
+        ));
+        notifications.insert(
+            1,
+            message(
+                "other",
+                "macro|a@macro.com",
+                common(ChannelType::Private, ""),
+            ),
+        );
+
+        assert_eq!(
+            summarize(&notifications),
+            vec![
+                "12 new messages in #design".to_string(),
+                "1 new message in a group channel".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_direct_messages() {
+        let direct_message = common(ChannelType::DirectMessage, "");
+        let notifications = vec![
+            message("dm", "macro|a@macro.com", direct_message.clone()),
+            message("dm", "macro|a@macro.com", direct_message),
+        ];
+
+        assert_eq!(
+            summarize(&notifications),
+            vec!["2 new messages from a@macro.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_summarize_shares() {
+        let notifications = vec![
+            share("roadmap", "Roadmap", "macro|a@macro.com"),
+            share("roadmap", "Roadmap", "macro|b@macro.com"),
+            share("roadmap", "Roadmap", "macro|c@macro.com"),
+            share("notes", "Notes", "macro|a@macro.com"),
+        ];
+
+        assert_eq!(
+            summarize(&notifications),
+            vec![
+                "a@macro.com and 2 others shared Roadmap".to_string(),
+                "a@macro.com shared Notes".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_invites() {
+        let invite = notification(
+            "design",
+            EntityType::Channel,
+            "macro|a@macro.com",
+            NotificationEvent::ChannelInvite(ChannelInviteMetadata {
+                invited_by: "macro|a@macro.com".to_string(),
+                common: common(ChannelType::Public, "design"),
+            }),
+        );
+
+        assert_eq!(
+            summarize(std::slice::from_ref(&invite)),
+            vec!["a@macro.com invited you to #design".to_string()]
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use model_notifications::{DeliveryChannel, NotificationWithRecipient, WebhookFormat};
+use notification_db_client::webhook::create::NewWebhookDelivery;
+use notification_db_client::webhook::get::PendingWebhookDelivery;
+use notification_db_client::webhook::update::WebhookDeliveryOutcome;
+use outbound_http::webhook::is_retryable;
+use outbound_http::{FetchError, Fetcher};
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use super::channel::NotificationChannel;
+use super::summary;
+use crate::notification::context::QueueWorkerContext;
+
+/// How long to wait before each retry of a failed delivery
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(2),
+    Duration::from_secs(10),
+    Duration::from_secs(60),
+];
+
+/// How often we check for deliveries that are due
+const WEBHOOK_DELIVERY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The most deliveries attempted on each check. Anything left over is picked up by the next check.
+const WEBHOOK_DELIVERY_BATCH_SIZE: i64 = 100;
+
+/// How long a claimed delivery is left to its attempt before it is attempted again. Longer than
+/// an attempt can take, so a delivery is only attempted again if its attempt was abandoned.
+const WEBHOOK_DELIVERY_LEASE_SECONDS: i32 = 60;
+
+/// A webhook is disabled once this many deliveries to it in a row have failed
+const MAX_CONSECUTIVE_FAILURES: i32 = 10;
+
+/// What the text of a Slack message falls back to when the notification can't be summarized
+const SLACK_FALLBACK_TEXT: &str = "You have a new notification in Macro";
+
+/// Signed JSON posted to the recipients' own endpoints
+pub struct WebhookChannel {
+    pub db: sqlx::Pool<sqlx::Postgres>,
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn delivery_channel(&self) -> DeliveryChannel {
+        DeliveryChannel::Webhook
+    }
+
+    /// Queues the deliveries, they are attempted by the webhook delivery worker since retries
+    /// can take over a minute
+    /// Returns the recipients with a webhook to deliver to
+    async fn deliver(
+        &self,
+        notifications: &[NotificationWithRecipient],
+    ) -> anyhow::Result<HashSet<String>> {
+        let user_ids: Vec<String> = notifications
+            .iter()
+            .map(|n| n.recipient_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let targets =
+            notification_db_client::webhook::get::get_webhook_targets_bulk(&self.db, &user_ids)
+                .await
+                .context("unable to get webhook targets")?;
+
+        let mut deliveries = Vec::new();
+        let mut reached = HashSet::new();
+        for notification in notifications {
+            let Some(targets) = targets.get(&notification.recipient_id) else {
+                continue;
+            };
+
+            for target in targets {
+                let body = match payload(notification, target.format) {
+                    Ok(body) => body,
+                    Err(e) => {
+                        tracing::error!(error=?e, "unable to serialize webhook payload");
+                        continue;
+                    }
+                };
+
+                deliveries.push(NewWebhookDelivery {
+                    webhook_id: target.id,
+                    notification_id: notification.inner.id,
+                    body,
+                });
+            }
+
+            reached.insert(notification.recipient_id.clone());
+        }
+
+        notification_db_client::webhook::create::enqueue_webhook_deliveries(&self.db, deliveries)
+            .await
+            .context("unable to queue webhook deliveries")?;
+
+        Ok(reached)
+    }
+}
+
+/// The notification as the app receives it, along with who it is for
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MacroPayload<'a> {
+    recipient_id: &'a str,
+    #[serde(flatten)]
+    notification: &'a NotificationWithRecipient,
+}
+
+/// A message for a Slack incoming webhook
+#[derive(Debug, Serialize)]
+struct SlackPayload {
+    text: String,
+}
+
+/// The body posted to a webhook in the given format
+fn payload(
+    notification: &NotificationWithRecipient,
+    format: WebhookFormat,
+) -> serde_json::Result<Vec<u8>> {
+    match format {
+        WebhookFormat::Macro => serde_json::to_vec(&MacroPayload {
+            recipient_id: &notification.recipient_id,
+            notification,
+        }),
+        WebhookFormat::Slack => serde_json::to_vec(&SlackPayload {
+            text: summary::summarize_one(
+                notification.inner.sender_id.as_deref(),
+                &notification.inner.notification_event,
+            )
+            .unwrap_or_else(|| SLACK_FALLBACK_TEXT.to_string()),
+        }),
+    }
+}
+
+#[derive(Debug)]
+enum DeliveryError {
+    /// the endpoint may accept the notification later
+    Retryable(Option<StatusCode>, String),
+    /// the endpoint rejected the notification, or can't be reached
+    Permanent(Option<StatusCode>, String),
+}
+
+/// Attempts the webhook deliveries that are due, and retries failed ones with backoff. Deliveries
+/// are queued in the database, so they are still attempted after a restart.
+pub async fn run_webhook_delivery_worker(queue_worker_context: QueueWorkerContext) {
+    tracing::info!("webhook delivery worker started");
+    let mut interval = tokio::time::interval(WEBHOOK_DELIVERY_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = deliver_due(&queue_worker_context).await {
+            tracing::error!(error=?e, "unable to deliver to webhooks");
+        }
+    }
+}
+
+async fn deliver_due(queue_worker_context: &QueueWorkerContext) -> anyhow::Result<()> {
+    let deliveries = notification_db_client::webhook::get::claim_due_webhook_deliveries(
+        &queue_worker_context.db,
+        WEBHOOK_DELIVERY_BATCH_SIZE,
+        WEBHOOK_DELIVERY_LEASE_SECONDS,
+    )
+    .await
+    .context("unable to claim due webhook deliveries")?;
+
+    futures::future::join_all(deliveries.iter().map(|delivery| {
+        deliver_to_webhook(
+            &queue_worker_context.db,
+            &queue_worker_context.webhook_fetcher,
+            delivery,
+        )
+    }))
+    .await;
+
+    Ok(())
+}
+
+/// Makes one attempt at a delivery, and either schedules a retry or records the outcome
+#[tracing::instrument(
+    skip(db, fetcher, delivery),
+    fields(
+        webhook_id = %delivery.webhook_id,
+        notification_id = %delivery.notification_id,
+        attempt = delivery.attempts
+    )
+)]
+async fn deliver_to_webhook(
+    db: &sqlx::Pool<sqlx::Postgres>,
+    fetcher: &Fetcher,
+    delivery: &PendingWebhookDelivery,
+) {
+    let (status_code, error) = match send(fetcher, delivery).await {
+        Ok(status) => (Some(status), None),
+        Err(DeliveryError::Retryable(status, e)) => {
+            // attempts count the first delivery, which isn't a retry
+            if let Some(delay) = RETRY_DELAYS.get(delivery.attempts as usize - 1) {
+                tracing::warn!(error = e, "webhook delivery failed, retrying");
+                if let Err(e) = notification_db_client::webhook::update::retry_webhook_delivery(
+                    db,
+                    &delivery.webhook_id,
+                    &delivery.notification_id,
+                    delay.as_secs() as i32,
+                )
+                .await
+                {
+                    tracing::error!(error=?e, "unable to schedule webhook delivery retry");
+                }
+                return;
+            }
+            (status, Some(e))
+        }
+        Err(DeliveryError::Permanent(status, e)) => (status, Some(e)),
+    };
+
+    let outcome = WebhookDeliveryOutcome {
+        notification_id: delivery.notification_id,
+        attempts: delivery.attempts,
+        status_code: status_code.map(|status| i32::from(status.as_u16())),
+        error: error.as_deref(),
+    };
+    match notification_db_client::webhook::update::record_webhook_delivery(
+        db,
+        &delivery.webhook_id,
+        &outcome,
+        MAX_CONSECUTIVE_FAILURES,
+    )
+    .await
+    {
+        Ok(true) => {
+            tracing::warn!(
+                metric = "NOTIFICATION-WEBHOOK-DISABLED",
+                error = ?error,
+                "disabled webhook after repeated failures"
+            );
+        }
+        Ok(false) => (),
+        Err(e) => {
+            tracing::error!(error=?e, "unable to record webhook delivery");
+        }
+    }
+}
+
+async fn send(
+    fetcher: &Fetcher,
+    delivery: &PendingWebhookDelivery,
+) -> Result<StatusCode, DeliveryError> {
+    let status = outbound_http::webhook::deliver(
+        fetcher,
+        &delivery.url,
+        &delivery.secret,
+        &delivery.notification_id,
+        &delivery.body,
+    )
+    .await
+    .map_err(|e| match e {
+        // the url can't be reached however many times it is retried
+        FetchError::InvalidUrl | FetchError::Blocked => {
+            DeliveryError::Permanent(None, e.to_string())
+        }
+        e => DeliveryError::Retryable(None, e.to_string()),
+    })?;
+
+    if status.is_success() {
+        Ok(status)
+    } else if is_retryable(status) {
+        Err(DeliveryError::Retryable(Some(status), status.to_string()))
+    } else {
+        Err(DeliveryError::Permanent(Some(status), status.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::send::summary::test_notifications::*;
+    use model_notifications::UserNotification;
+    use models_comms::ChannelType;
+
+    fn with_recipient(recipient_id: &str) -> NotificationWithRecipient {
+        let notification = message(
+            "design",
+            "macro|a@macro.com",
+            common(ChannelType::Public, "design"),
+        );
+        NotificationWithRecipient {
+            inner: UserNotification::from_new_notification(notification, false, false, false),
+            recipient_id: recipient_id.to_string(),
+            is_important_v0: false,
+        }
+    }
+
+    #[test]
+    fn test_macro_payload() {
+        let notification = with_recipient("macro|b@macro.com");
+        let body: serde_json::Value =
+            serde_json::from_slice(&payload(&notification, WebhookFormat::Macro).unwrap()).unwrap();
+
+        assert_eq!(body["recipientId"], "macro|b@macro.com");
+        assert_eq!(body["id"], notification.inner.id.to_string());
+        assert_eq!(body["senderId"], "macro|a@macro.com");
+        assert_eq!(body["eventItemId"], "design");
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let notification = with_recipient("macro|b@macro.com");
+        let body: serde_json::Value =
+            serde_json::from_slice(&payload(&notification, WebhookFormat::Slack).unwrap()).unwrap();
+
+        assert_eq!(
+            body,
+            serde_json::json!({ "text": "1 new message in #design" })
+        );
+    }
+}
@@ -1,21 +1,19 @@
 #![allow(warnings, reason = "waiting on #1805 to be merged")]
 use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 
 use anyhow::Context;
 use env::SENDER_ADDRESS;
 use futures::StreamExt;
 use macro_entrypoint::MacroEntrypoint;
 use macro_env::{Environment, ext::frontend_url::FrontendUrl};
-use model_notifications::NotificationEventType;
+use model_notifications::{DeliveryChannel, NotificationEventType};
 use notification_db_client::user_notification::get::unsent::UnsentNotification;
 use sqlx::postgres::PgPoolOptions;
 use url::Url;
 
 mod env;
 
-#[cfg(test)]
-mod poller_tests;
-
 /// Handles polling notifications db for users that have unread channel notifications
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
@@ -151,59 +149,39 @@ async fn process_unsent_notifications_for_users(
             })
             .collect::<Vec<UnsentNotification>>();
 
-        // Filter out users that have notifications muted
-        let muted_users =
-            notification_db_client::user_mute_notification::get_user_mute_notification_bulk(
+        // Filter out notifications the user doesn't want emailed
+        let preferences =
+            notification_db_client::preference::get::get_user_notification_preferences_bulk(
                 db,
                 &unsent_notifications
                     .iter()
                     .map(|n| n.user_id.clone())
+                    .collect::<HashSet<String>>()
+                    .into_iter()
                     .collect::<Vec<String>>(),
             )
             .await
-            .context("unable to get muted users")?;
+            .context("unable to get notification preferences")?;
 
         let unsent_notifications = unsent_notifications
             .into_iter()
             .filter(|notification| {
-                if muted_users.contains(&notification.user_id) {
-                    tracing::debug!("muted user {}", notification.user_id);
-                    false
-                } else {
-                    true
-                }
-            })
-            .collect::<Vec<UnsentNotification>>();
-
-        // Filter out emails that are unsubscribed
-        let emails = unsent_notifications
-            .iter()
-            .map(|n| n.user_id.replace("macro|", ""))
-            .collect::<Vec<String>>();
-        // Get unsubscribed status for emails
-        let email_unsubscribed =
-            notification_db_client::unsubscribe::email::is_email_unsubscribed_batch(db, &emails)
-                .await
-                .context("unable to check if emails are unsubscribed")?;
-        let email_unsubscribed: HashSet<String> = email_unsubscribed
-            .into_iter()
-            .filter_map(
-                |(email, is_unsubscribed)| {
-                    if is_unsubscribed { Some(email) } else { None }
-                },
-            )
-            .collect();
-
-        let unsent_notifications = unsent_notifications
-            .into_iter()
-            .filter(|notification| {
-                let email = notification.user_id.replace("macro|", "");
-                if email_unsubscribed.contains(&email) {
-                    tracing::debug!("unsubscribed email {}", email);
-                    false
-                } else {
-                    true
+                let Ok(event_type) =
+                    NotificationEventType::from_str(&notification.notification_event_type)
+                else {
+                    return false;
+                };
+                let allowed = preferences.get(&notification.user_id).is_some_and(|p| {
+                    p.allows(
+                        event_type,
+                        &notification.event_item_id,
+                        DeliveryChannel::Email,
+                    )
+                });
+                if !allowed {
+                    tracing::debug!(notification=?notification, "email not wanted");
                 }
+                allowed
             })
             .collect::<Vec<UnsentNotification>>();
 
@@ -281,39 +259,6 @@ async fn process_user_notifications(
         return Ok(());
     }
 
-    // Filter out channels users have unsubscribed to
-    let user_unsubscribes =
-        notification_db_client::unsubscribe::get::get_user_unsubscribes(db, user_id)
-            .await
-            .context("unable to get user unsubscribes")?;
-    tracing::trace!(user_unsubscribes=?user_unsubscribes, "got user unsubscribes");
-
-    let user_unsubscribed_channel_ids = user_unsubscribes
-        .iter()
-        .filter_map(|unsubscribe| match unsubscribe.item_type.as_str() {
-            "channel" => Some(unsubscribe.item_id.clone()),
-            _ => None,
-        })
-        .collect::<HashSet<String>>();
-    tracing::trace!(user_unsubscribed_channel_ids=?user_unsubscribed_channel_ids, "got user unsubscribed channel ids");
-
-    let notifications = notifications
-        .into_iter()
-        .filter(|notification| {
-            if user_unsubscribed_channel_ids.contains(&notification.event_item_id) {
-                tracing::debug!(notification=?notification, "unsubscribed channel {}", notification.event_item_id);
-                false
-            } else {
-                true
-            }
-        })
-        .collect::<Vec<&UnsentNotification>>();
-
-    if notifications.is_empty() {
-        tracing::debug!("no notifications to send");
-        return Ok(());
-    }
-
     // Optimistically mark user as having sent email
     notification_db_client::notification_email_sent::create::create_notification_email_sent(
         db, user_id,
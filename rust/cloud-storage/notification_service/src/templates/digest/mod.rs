@@ -0,0 +1,40 @@
+use html_utils::escape_html;
+use url::Url;
+
+static DIGEST_TEMPLATE: &str = include_str!("./_digest_template.html");
+
+static DIGEST_LINE_TEMPLATE: &str = include_str!("./_digest_line_template.html");
+
+/// Fills the digest template with a row for each line
+pub fn fill_digest_template(url: &Url, lines: &[String], footer: &str) -> String {
+    // Channel, document and user names are written by users so they need escaping
+    let lines = lines
+        .iter()
+        .map(|line| DIGEST_LINE_TEMPLATE.replace("{{LINE}}", &escape_html(line)))
+        .collect::<String>();
+
+    // The lines go in last so nothing in them is taken for a placeholder
+    DIGEST_TEMPLATE
+        .replace("{{URL}}", url.as_str())
+        .replace("{{FOOTER}}", footer)
+        .replace("{{DIGEST_LINES}}", &lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_digest_template_escapes_lines() {
+        let url = Url::parse("https://example.com/login").unwrap();
+        let content = fill_digest_template(
+            &url,
+            &["2 new messages in #<b>design</b> {{URL}}".to_string()],
+            "footer",
+        );
+
+        assert!(content.contains("2 new messages in #&lt;b&gt;design&lt;/b&gt; {{URL}}"));
+        assert!(content.contains("href=\"https://example.com/login\""));
+        assert!(!content.contains("{{DIGEST_LINES}}"));
+    }
+}
@@ -2,6 +2,7 @@ use url::Url;
 
 pub mod channel_invite;
 pub mod channel_message;
+pub mod digest;
 pub mod item_share;
 
 /// Adds a query param to the URL to skip offboarding.
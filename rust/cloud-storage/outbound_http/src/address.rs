@@ -0,0 +1,128 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Resolves hosts to their public addresses only, so a fetched url can't reach the internal
+/// network however its host resolves. Connections are made to the returned addresses, so a
+/// host can't be re-resolved to an internal address after it was checked.
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} does not resolve to a public address", name.as_str()).into(),
+                );
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether an address is reachable on the public internet
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "this network"
+        || a == 0
+        // shared address space (carrier-grade NAT)
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // benchmarking
+        || (a == 198 && (18..20).contains(&b))
+        // reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    // addresses embedding an ipv4 address are as public as the address they embed
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(ipv4);
+    }
+
+    let segments = ip.segments();
+    // NAT64
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [a, b] = segments[6].to_be_bytes();
+        let [c, d] = segments[7].to_be_bytes();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local
+        || (segments[0] & 0xfe00) == 0xfc00
+        // link local
+        || (segments[0] & 0xffc0) == 0xfe80
+        // site local (deprecated)
+        || (segments[0] & 0xffc0) == 0xfec0
+        // documentation
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // ipv4 compatible (deprecated)
+        || segments[..6] == [0, 0, 0, 0, 0, 0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_public(ip: &str) -> bool {
+        is_public_ip(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_public_ipv4() {
+        assert!(is_public("8.8.8.8"));
+        assert!(is_public("93.184.216.34"));
+        assert!(is_public("100.63.255.255"));
+
+        assert!(!is_public("127.0.0.1"));
+        assert!(!is_public("10.0.0.1"));
+        assert!(!is_public("172.16.5.4"));
+        assert!(!is_public("192.168.1.1"));
+        assert!(!is_public("169.254.169.254"));
+        assert!(!is_public("0.0.0.0"));
+        assert!(!is_public("100.64.0.1"));
+        assert!(!is_public("198.18.0.1"));
+        assert!(!is_public("224.0.0.1"));
+        assert!(!is_public("255.255.255.255"));
+    }
+
+    #[test]
+    fn test_public_ipv6() {
+        assert!(is_public("2606:4700:4700::1111"));
+        assert!(is_public("::ffff:8.8.8.8"));
+
+        assert!(!is_public("::1"));
+        assert!(!is_public("::"));
+        assert!(!is_public("fd00::1"));
+        assert!(!is_public("fe80::1"));
+        assert!(!is_public("ff02::1"));
+        assert!(!is_public("2001:db8::1"));
+        assert!(!is_public("::ffff:127.0.0.1"));
+        assert!(!is_public("::ffff:169.254.169.254"));
+        assert!(!is_public("64:ff9b::a9fe:a9fe"));
+        assert!(!is_public("::7f00:1"));
+    }
+}
@@ -0,0 +1,127 @@
+//! Signed deliveries to webhooks that users register. Receivers verify a delivery by computing
+//! the signature of its body with the secret they were given when the webhook was created.
+
+use crate::{FetchError, Fetcher, validate_url};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use http::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use sha2::Sha256;
+use url::Url;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `v1=` followed by the hex hmac-sha256 of `{timestamp}.{body}`, keyed with the webhook secret
+pub const SIGNATURE_HEADER: &str = "x-macro-signature";
+/// unix seconds the delivery was signed at, receivers should reject old deliveries
+pub const TIMESTAMP_HEADER: &str = "x-macro-timestamp";
+/// the same for every attempt at delivering an event, for receivers to dedupe retries
+pub const EVENT_ID_HEADER: &str = "x-macro-event-id";
+
+/// Signs a delivery body for the given timestamp
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("v1={:x}", mac.finalize().into_bytes())
+}
+
+/// Whether a failed delivery is worth retrying
+pub fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Checks a url can be registered as a webhook. Deliveries carry user content, so they are only
+/// sent encrypted, and only to hosts that could be reached when delivering.
+pub fn validate_webhook_url(url: &str) -> Result<Url, FetchError> {
+    let url = validate_url(url.trim())?;
+    if url.scheme() != "https" {
+        return Err(FetchError::InvalidUrl);
+    }
+    Ok(url)
+}
+
+/// Posts a signed json body to a webhook and returns the status it responded with
+pub async fn deliver(
+    fetcher: &Fetcher,
+    url: &str,
+    secret: &str,
+    event_id: &Uuid,
+    body: &[u8],
+) -> Result<StatusCode, FetchError> {
+    let timestamp = Utc::now().timestamp();
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    for (name, value) in [
+        (EVENT_ID_HEADER, event_id.to_string()),
+        (TIMESTAMP_HEADER, timestamp.to_string()),
+        (SIGNATURE_HEADER, sign(secret, timestamp, body)),
+    ] {
+        headers.insert(
+            name,
+            HeaderValue::from_str(&value).expect("ids, timestamps and signatures are ascii"),
+        );
+    }
+
+    fetcher.post(url, headers, body.to_vec()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // matches python's hmac.new(b"secret", b"1700000000.{}", hashlib.sha256).hexdigest()
+        assert_eq!(
+            sign("secret", 1_700_000_000, b"{}"),
+            "v1=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+        assert_ne!(
+            sign("secret", 1_700_000_001, b"{}"),
+            sign("secret", 1_700_000_000, b"{}")
+        );
+        assert_ne!(
+            sign("other", 1_700_000_000, b"{}"),
+            sign("secret", 1_700_000_000, b"{}")
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+        assert!(!is_retryable(StatusCode::GONE));
+    }
+
+    #[test]
+    fn test_validate_webhook_url() {
+        assert_eq!(
+            validate_webhook_url(" https://hooks.example.com/a ")
+                .unwrap()
+                .as_str(),
+            "https://hooks.example.com/a"
+        );
+
+        assert!(validate_webhook_url("http://hooks.example.com/a").is_err());
+        assert!(validate_webhook_url("not a url").is_err());
+        assert!(matches!(
+            validate_webhook_url("https://169.254.169.254/latest/meta-data/"),
+            Err(FetchError::Blocked)
+        ));
+        assert!(matches!(
+            validate_webhook_url("https://[::1]/"),
+            Err(FetchError::Blocked)
+        ));
+    }
+}
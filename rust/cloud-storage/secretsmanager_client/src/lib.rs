@@ -27,6 +27,38 @@ impl SecretsManagerClient {
     pub fn new(inner: secretsmanager::Client) -> Self {
         Self { inner }
     }
+
+    /// Creates a secret holding the value and returns its arn
+    #[tracing::instrument(err, skip(self, value))]
+    pub async fn create_secret(&self, name: &str, value: &str) -> Result<String, SecretErr> {
+        let result = self
+            .inner
+            .create_secret()
+            .name(name)
+            .secret_string(value)
+            .send()
+            .await
+            .map_err(aws_sdk_secretsmanager::Error::from)?;
+
+        result
+            .arn()
+            .map(str::to_string)
+            .ok_or(SecretErr::NotPresent)
+    }
+
+    /// Deletes a secret without a recovery window
+    #[tracing::instrument(err, skip(self))]
+    pub async fn delete_secret(&self, secret_id: &str) -> Result<(), SecretErr> {
+        self.inner
+            .delete_secret()
+            .secret_id(secret_id)
+            .force_delete_without_recovery(true)
+            .send()
+            .await
+            .map_err(aws_sdk_secretsmanager::Error::from)?;
+
+        Ok(())
+    }
 }
 
 impl SecretManager for SecretsManagerClient {
@@ -76,3 +76,8 @@ where
 
 /// A type which indicates a Json Encoded T
 pub type JsonEncoded<T> = Container<T, JsonEncoding>;
+
+/// Defaults a bool field to true, use with `#[serde(default = "serde_utils::default_true")]`
+pub fn default_true() -> bool {
+    true
+}
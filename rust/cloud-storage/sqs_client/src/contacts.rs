@@ -1,6 +1,7 @@
 use crate::SQS;
 use model::contacts::{
-    AddParticipantsMessageBody, ConnectionsMessage, CreateGroupMessageBody, Message,
+    AddParticipantsMessageBody, ConnectionsMessage, CreateGroupMessageBody, InteractionKind,
+    Message, RecordInteractionMessageBody,
 };
 
 impl SQS {
@@ -63,6 +64,26 @@ impl SQS {
         }
         Err(anyhow::anyhow!("contacts_queue is not configured"))
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn enqueue_contacts_record_interaction(
+        &self,
+        user_id: &str,
+        counterparts: Vec<String>,
+        kind: InteractionKind,
+    ) -> anyhow::Result<()> {
+        if let Some(contacts_queue) = &self.contacts_queue {
+            return enqueue_contacts_record_interaction(
+                &self.inner,
+                contacts_queue,
+                user_id,
+                counterparts,
+                kind,
+            )
+            .await;
+        }
+        Err(anyhow::anyhow!("contacts_queue is not configured"))
+    }
 }
 
 #[tracing::instrument(skip(sqs_client, participants))]
@@ -127,3 +148,27 @@ pub async fn enqueue_contacts_add_connection(
         .await?;
     Ok(())
 }
+
+#[tracing::instrument(skip(sqs_client, queue_url))]
+pub async fn enqueue_contacts_record_interaction(
+    sqs_client: &aws_sdk_sqs::Client,
+    queue_url: &str,
+    user_id: &str,
+    counterparts: Vec<String>,
+    kind: InteractionKind,
+) -> anyhow::Result<()> {
+    let body = RecordInteractionMessageBody {
+        user_id: user_id.to_string(),
+        counterparts,
+        kind,
+    };
+    let message = Message::RecordInteraction(body);
+    let message_str = serde_json::to_string(&message)?;
+    sqs_client
+        .send_message()
+        .queue_url(queue_url)
+        .message_body(message_str)
+        .send()
+        .await?;
+    Ok(())
+}
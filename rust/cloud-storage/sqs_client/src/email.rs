@@ -1,7 +1,9 @@
 use crate::SQS;
 use models_email::email::service::backfill::BackfillPubsubMessage;
 use models_email::email::service::pubsub::RefreshMessage;
-use models_email::service::pubsub::{SFSUploaderMessage, ScheduledPubsubMessage};
+use models_email::service::pubsub::{
+    FollowUpPubsubMessage, SFSUploaderMessage, ScheduledPubsubMessage,
+};
 
 impl SQS {
     pub fn email_refresh_queue(mut self, email_refresh_queue: &str) -> Self {
@@ -60,6 +62,18 @@ impl SQS {
         Err(anyhow::anyhow!("email_scheduled_queue is not configured"))
     }
 
+    /// Sends a due follow up reminder to the email scheduled queue
+    #[tracing::instrument(skip(self))]
+    pub async fn enqueue_email_follow_up_message(
+        &self,
+        message: FollowUpPubsubMessage,
+    ) -> anyhow::Result<()> {
+        if let Some(email_scheduled_queue) = &self.email_scheduled_queue {
+            return enqueue_follow_up_message(&self.inner, email_scheduled_queue, message).await;
+        }
+        Err(anyhow::anyhow!("email_scheduled_queue is not configured"))
+    }
+
     /// Sends a notification message to the email sfs uploader queue
     #[tracing::instrument(skip(self))]
     pub async fn enqueue_email_sfs_uploader_message(
@@ -133,6 +147,28 @@ pub async fn enqueue_scheduled_message(
     Ok(())
 }
 
+#[tracing::instrument(skip(sqs_client))]
+pub async fn enqueue_follow_up_message(
+    sqs_client: &aws_sdk_sqs::Client,
+    queue_url: &str,
+    message: FollowUpPubsubMessage,
+) -> anyhow::Result<()> {
+    let message_str = serde_json::to_string(&message)?;
+    let reminder_id = message.reminder_id.to_string();
+
+    // Send the message with the serialized body
+    sqs_client
+        .send_message()
+        .queue_url(queue_url)
+        .message_body(message_str)
+        .message_group_id(reminder_id.clone())
+        .message_deduplication_id(reminder_id)
+        .send()
+        .await?;
+
+    Ok(())
+}
+
 #[tracing::instrument(skip(sqs_client))]
 pub async fn enqueue_sfs_uploader_message(
     sqs_client: &aws_sdk_sqs::Client,
@@ -1 +1,12 @@
+use crate::fetch::{FetchCache, Fetcher};
+use crate::unfurl::macro_link::MacroItemClient;
+use std::sync::Arc;
 
+#[derive(Clone)]
+pub struct ApiContext {
+    /// The secret image proxy urls are signed with, shared with the email service
+    pub image_proxy_secret_key: Arc<str>,
+    pub fetcher: Fetcher,
+    pub cache: FetchCache,
+    pub macro_items: MacroItemClient,
+}
@@ -1,6 +1,14 @@
 use crate::config::Config;
+use crate::fetch::{FetchCache, Fetcher};
+use crate::unfurl::macro_link::MacroItemClient;
 use anyhow::Context;
 use axum::Router;
+use comms_service_client::CommsServiceClient;
+use context::ApiContext;
+use document_storage_service_client::DocumentStorageServiceClient;
+use email_service_client::EmailServiceClient;
+use macro_env::ext::frontend_url::FrontendUrl;
+use std::sync::Arc;
 use tower::ServiceBuilder;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -14,7 +22,31 @@ mod unfurl;
 pub async fn setup_and_serve(config: &Config) -> anyhow::Result<()> {
     let cors = macro_cors::cors_layer();
 
-    let app = api_router()
+    let redis_client =
+        redis::Client::open(config.redis_uri.as_str()).context("invalid redis uri")?;
+
+    let ctx = ApiContext {
+        image_proxy_secret_key: config.image_proxy_secret_key.as_str().into(),
+        fetcher: Fetcher::new().context("unable to build fetcher")?,
+        cache: FetchCache::new(redis_client),
+        macro_items: MacroItemClient::new(
+            config.environment.get_frontend_url(),
+            Arc::new(DocumentStorageServiceClient::new(
+                config.internal_api_secret_key.clone(),
+                config.document_storage_service_url.clone(),
+            )),
+            Arc::new(CommsServiceClient::new(
+                config.internal_api_secret_key.clone(),
+                config.comms_service_url.clone(),
+            )),
+            Arc::new(EmailServiceClient::new(
+                config.internal_api_secret_key.clone(),
+                config.email_service_url.clone(),
+            )),
+        ),
+    };
+
+    let app = api_router(ctx)
         .layer(cors.clone())
         .merge(health::router().layer(cors))
         .merge(SwaggerUi::new("/docs").url("/api-doc/openapi.json", swagger::ApiDoc::openapi()));
@@ -34,10 +66,13 @@ pub async fn setup_and_serve(config: &Config) -> anyhow::Result<()> {
         .context("error starting service")
 }
 
-fn api_router() -> Router {
+fn api_router(ctx: ApiContext) -> Router {
     Router::new()
-        .nest("/unfurl", unfurl::router().layer(ServiceBuilder::new()))
-        .nest("/proxy", proxy::router().layer(ServiceBuilder::new()))
+        .nest(
+            "/unfurl",
+            unfurl::router(ctx.clone()).layer(ServiceBuilder::new()),
+        )
+        .nest("/proxy", proxy::router(ctx).layer(ServiceBuilder::new()))
 }
 
 #[cfg(test)]
@@ -53,9 +88,93 @@ mod tests {
     use http_body_util::BodyExt; // for `collect`
     use tower::ServiceExt;
 
+    const IMAGE_PROXY_SECRET_KEY: &str = "secret";
+
+    fn test_context() -> ApiContext {
+        ApiContext {
+            image_proxy_secret_key: IMAGE_PROXY_SECRET_KEY.into(),
+            fetcher: Fetcher::new().unwrap(),
+            // nothing listens here, so every lookup misses the cache
+            cache: FetchCache::new(redis::Client::open("redis://127.0.0.1:1").unwrap()),
+            macro_items: MacroItemClient::new(
+                "https://macro.com/app/".parse().unwrap(),
+                Arc::new(DocumentStorageServiceClient::new(
+                    "dummy_auth_key".into(),
+                    "http://localhost".into(),
+                )),
+                Arc::new(CommsServiceClient::new(
+                    "dummy_auth_key".into(),
+                    "http://localhost".into(),
+                )),
+                Arc::new(EmailServiceClient::new(
+                    "dummy_auth_key".into(),
+                    "http://localhost".into(),
+                )),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proxy_image_invalid_signature() {
+        let image = "https://example.com/logo.png";
+        let expires = i64::MAX;
+        let signature = image_proxy::signature(IMAGE_PROXY_SECRET_KEY, image, expires);
+
+        let response = api_router(test_context())
+            .oneshot(
+                Request::builder()
+                    .uri(image_proxy::proxy_url(
+                        "/proxy/image",
+                        "other",
+                        image,
+                        expires,
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = api_router(test_context())
+            .oneshot(
+                Request::builder()
+                    .uri(format!(
+                        "/proxy/image?url=https://example.com/other.png&expires={}&signature={}",
+                        expires, signature
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_proxy_image_expired_signature() {
+        let image = "https://example.com/logo.png";
+
+        let response = api_router(test_context())
+            .oneshot(
+                Request::builder()
+                    .uri(image_proxy::proxy_url(
+                        "/proxy/image",
+                        IMAGE_PROXY_SECRET_KEY,
+                        image,
+                        1,
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
     #[tokio::test]
     async fn test_not_found() {
-        let api = api_router();
+        let api = api_router(test_context());
 
         let response = api
             .oneshot(
@@ -74,7 +193,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_unfurl_url_nonexistent() {
-        let api = api_router();
+        let api = api_router(test_context());
 
         let response = api
             .oneshot(
@@ -101,7 +220,7 @@ mod tests {
     #[ignore]
     #[tokio::test]
     async fn test_unfurl_hello_url() {
-        let api = api_router();
+        let api = api_router(test_context());
 
         let response = api
             .oneshot(
@@ -133,7 +252,7 @@ mod tests {
     #[ignore]
     #[tokio::test]
     async fn test_bulk() {
-        let api = api_router();
+        let api = api_router(test_context());
 
         let body = GetUnfurlBulkBody {
             url_list: ["https://hello.com", "https://example.com"]
@@ -171,7 +290,7 @@ mod tests {
     #[ignore]
     #[tokio::test]
     async fn test_bulk_404_links() {
-        let api = api_router();
+        let api = api_router(test_context());
 
         let body = GetUnfurlBulkBody {
             url_list: [
@@ -0,0 +1,59 @@
+use super::proxy_image;
+use crate::api::context::ApiContext;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
+
+#[derive(Debug, ToSchema, Deserialize)]
+pub struct ImageProxyParams {
+    pub url: String,
+    /// when the signature expires, in unix seconds
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Proxies a remote email image, so the sender doesn't learn anything about who opened the email.
+/// Only serves images the email service signed a url for.
+#[utoipa::path(
+  get,
+  path="/proxy/image",
+  params(
+    ("url" = String, Query, description = "The image to proxy"),
+    ("expires" = i64, Query, description = "When the signature expires, in unix seconds"),
+    ("signature" = String, Query, description = "The signature of the url and its expiry"),
+  ),
+  responses(
+    (status = 200, description = "The image"),
+    (status = 400, body=String),
+    (status = 403, body=String),
+    (status = 413, body=String),
+    (status = 415, body=String),
+    (status = 502, body=String),
+    (status = 504, body=String),
+  )
+)]
+#[tracing::instrument(skip(ctx))]
+pub async fn proxy_image_handler(
+    State(ctx): State<ApiContext>,
+    Query(params): Query<ImageProxyParams>,
+) -> Result<Response, (StatusCode, String)> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    if !image_proxy::verify(
+        &ctx.image_proxy_secret_key,
+        &params.url,
+        params.expires,
+        &params.signature,
+        now,
+    ) {
+        return Err((StatusCode::FORBIDDEN, "invalid signature".to_string()));
+    }
+
+    proxy_image(&ctx, &params.url).await
+}
@@ -1,14 +1,20 @@
+use crate::api::context::ApiContext;
+use crate::fetch::FetchPolicy;
 use axum::Router;
 use axum::body::Body;
-use axum::extract::Query;
-use axum::http::Request;
-use axum::response::{IntoResponse, Response};
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::Response;
 use axum::routing::get;
-use reqwest::{self, StatusCode};
 use serde::Deserialize;
-use std::collections::HashSet;
 use utoipa::{self, ToSchema};
 
+pub mod image;
+
+const CACHE_MAX_AGE_SECONDS: u64 = 24 * 60 * 60;
+/// larger images are fetched again every time rather than taking up the cache
+const MAX_CACHED_IMAGE_BYTES: usize = 1024 * 1024; // 1 MB
+
 #[derive(Debug, ToSchema, Deserialize)]
 pub struct ProxyParams {
     pub url: String,
@@ -18,54 +24,68 @@ pub struct ProxyParams {
   get,
   path="/proxy",
   params(("url" = String, Query, description = "The url to proxy from")),
+  responses(
+    (status = 200, description = "The image"),
+    (status = 400, body=String),
+    (status = 403, body=String),
+    (status = 413, body=String),
+    (status = 415, body=String),
+    (status = 502, body=String),
+    (status = 504, body=String),
+  )
 )]
-#[tracing::instrument]
+#[tracing::instrument(skip(ctx))]
 pub async fn proxy_request_handler(
+    State(ctx): State<ApiContext>,
     Query(params): Query<ProxyParams>,
-    request: Request<Body>,
-) -> Result<impl IntoResponse, impl IntoResponse> {
-    let client = reqwest::Client::new();
-    let excluded_headers: HashSet<&str> = [
-        "connection",
-        "keep-alive",
-        "proxy-authenticate",
-        "proxy-authorization",
-        "te",
-        "trailer",
-        "transfer-encoding",
-        "upgrade",
-        "host",
-        "content-length",
-    ]
-    .into_iter()
-    .collect();
+) -> Result<Response, (StatusCode, String)> {
+    proxy_image(&ctx, &params.url).await
+}
 
-    // Build proxied request with forwarded headers
-    let mut req_builder = client.get(&params.url);
-    for (key, value) in request.headers().iter() {
-        if !excluded_headers.contains(key.as_str()) {
-            req_builder = req_builder.header(key, value);
-        }
-    }
-    let response = req_builder
-        .send()
-        .await
-        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+/// Fetches an image through the cache, and serves it so it can't run anything from our domain
+async fn proxy_image(ctx: &ApiContext, url: &str) -> Result<Response, (StatusCode, String)> {
+    let image = match ctx.cache.get_response(url).await {
+        Some(image) => image,
+        None => {
+            let image = ctx
+                .fetcher
+                .fetch(url, &FetchPolicy::IMAGE)
+                .await
+                .map_err(|e| (e.status_code(), e.to_string()))?;
 
-    let mut response_builder = Response::builder().status(response.status());
+            if image.body.len() <= MAX_CACHED_IMAGE_BYTES {
+                ctx.cache
+                    .set_response(url, &image, CACHE_MAX_AGE_SECONDS)
+                    .await;
+            }
+            image
+        }
+    };
 
-    for (header, value) in response.headers() {
-        response_builder = response_builder.header(header, value);
-    }
-    response_builder
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, image.content_type)
+        .header(
+            header::CACHE_CONTROL,
+            format!("private, max-age={}", CACHE_MAX_AGE_SECONDS),
+        )
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        // svg images can carry scripts
+        .header(
+            header::CONTENT_SECURITY_POLICY,
+            "default-src 'none'; style-src 'unsafe-inline'; sandbox",
+        )
         .header("Cross-Origin-Resource-Policy", "cross-origin")
-        .body(Body::from_stream(response.bytes_stream()))
+        .body(Body::from(image.body))
         .map_err(|e| {
-            tracing::error!(error=?e, "could not stream chunks");
+            tracing::error!(error=?e, "could not build image response");
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })
 }
 
-pub fn router() -> Router {
-    Router::new().route("/", get(proxy_request_handler))
+pub fn router(ctx: ApiContext) -> Router {
+    Router::new()
+        .route("/", get(proxy_request_handler))
+        .route("/image", get(image::proxy_image_handler))
+        .with_state(ctx)
 }
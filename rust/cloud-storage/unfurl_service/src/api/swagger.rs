@@ -1,10 +1,13 @@
 use utoipa::OpenApi;
 
+use super::proxy::image::{self, ImageProxyParams};
 use super::proxy::{self, ProxyParams};
 use super::unfurl::get_unfurl::{
     self, GetUnfurlBulkBody, GetUnfurlBulkResponse, GetUnfurlQueryParams,
 };
 use crate::unfurl::GetUnfurlResponse;
+use crate::unfurl::macro_link::{MacroItem, MacroItemType};
+use crate::unfurl::oembed::Embed;
 
 #[derive(OpenApi)]
 #[openapi(
@@ -15,14 +18,19 @@ use crate::unfurl::GetUnfurlResponse;
             get_unfurl::get_unfurl_handler,
             get_unfurl::get_bulk_unfurl_handler,
             proxy::proxy_request_handler,
+            image::proxy_image_handler,
         ),
         components(
             schemas(
                 GetUnfurlResponse,
+                Embed,
+                MacroItem,
+                MacroItemType,
                 GetUnfurlQueryParams,
                 GetUnfurlBulkResponse,
                 GetUnfurlBulkBody,
                 ProxyParams,
+                ImageProxyParams,
             ),
         ),
         tags(
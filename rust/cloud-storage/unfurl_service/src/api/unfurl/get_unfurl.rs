@@ -1,14 +1,16 @@
 use axum::extract::Json;
 use axum::extract::Query;
-use axum::http::StatusCode;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
 
-use crate::unfurl::{
-    GetUnfurlResponse, GetUnfurlResponseList, append_optimistic_favico, extract_meta_tags,
-    fetch_links_async,
-};
+use crate::api::context::ApiContext;
+use crate::unfurl::{GetUnfurlResponse, GetUnfurlResponseList, fetch_links_async, unfurl_link};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+/// the most links unfurled by one bulk request
+const MAX_BULK_URLS: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, Default)]
 pub struct GetUnfurlBulkResponse {
     pub responses: GetUnfurlResponseList,
@@ -36,17 +38,25 @@ pub struct GetUnfurlQueryParams {
         ("url"=String, Query, description="URL to unfold"),
     )
 )]
-#[tracing::instrument]
+#[tracing::instrument(skip(ctx, headers))]
 pub async fn get_unfurl_handler(
+    State(ctx): State<ApiContext>,
+    headers: HeaderMap,
     Query(params): Query<GetUnfurlQueryParams>,
 ) -> (StatusCode, Json<Option<GetUnfurlResponse>>) {
-    let tags = match extract_meta_tags(&params.url).await {
-        Ok(t) => append_optimistic_favico(t, params.url.as_str()),
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(None)),
-    };
-
-    let response = GetUnfurlResponse::new(&params.url, &tags);
-    (StatusCode::OK, Json(Some(response)))
+    let jwt_token = viewer_token(&headers);
+    match unfurl_link(
+        &ctx.fetcher,
+        &ctx.cache,
+        &ctx.macro_items,
+        &params.url,
+        jwt_token.as_deref(),
+    )
+    .await
+    {
+        Some(response) => (StatusCode::OK, Json(Some(response))),
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(None)),
+    }
 }
 
 #[utoipa::path(post,
@@ -54,6 +64,7 @@ pub async fn get_unfurl_handler(
     operation_id = "get_unfurl_bulk",
     path = "/unfurl/bulk", responses(
     (status = 200, body=GetUnfurlBulkResponse),
+    (status = 400, body=GetUnfurlBulkResponse),
     (status = 401, body=String),
     (status = 404, body=String),
     (status = 500, body=String)),
@@ -63,11 +74,36 @@ pub async fn get_unfurl_handler(
         // TODO: update
         //example=json!(["https://macro.com", "https://github.com"])
     ))]
-#[tracing::instrument]
+#[tracing::instrument(skip(ctx, headers))]
 pub async fn get_bulk_unfurl_handler(
+    State(ctx): State<ApiContext>,
+    headers: HeaderMap,
     body: Json<GetUnfurlBulkBody>,
 ) -> (StatusCode, Json<GetUnfurlBulkResponse>) {
-    let links = fetch_links_async(&body.url_list).await;
+    if body.url_list.len() > MAX_BULK_URLS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(GetUnfurlBulkResponse::default()),
+        );
+    }
+
+    let jwt_token = viewer_token(&headers);
+    let links = fetch_links_async(
+        &ctx.fetcher,
+        &ctx.cache,
+        &ctx.macro_items,
+        &body.url_list,
+        jwt_token.as_deref(),
+    )
+    .await;
     let response = GetUnfurlBulkResponse { responses: links };
     (StatusCode::OK, Json(response))
 }
+
+/// The access token of the viewer, Macro links are unfurled with it. Other links are unfurled
+/// for viewers that aren't signed in as well.
+fn viewer_token(headers: &HeaderMap) -> Option<String> {
+    macro_auth::headers::extract_access_token_from_request_headers(headers)
+        .inspect_err(|e| tracing::trace!(error=?e, "no access token"))
+        .ok()
+}
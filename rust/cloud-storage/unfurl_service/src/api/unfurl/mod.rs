@@ -1,9 +1,11 @@
+use crate::api::context::ApiContext;
 use axum::{Router, routing::get, routing::post};
 
 pub mod get_unfurl;
 
-pub fn router() -> Router {
+pub fn router(ctx: ApiContext) -> Router {
     Router::new()
         .route("/", get(get_unfurl::get_unfurl_handler))
         .route("/bulk", post(get_unfurl::get_bulk_unfurl_handler))
+        .with_state(ctx)
 }
@@ -1,3 +1,4 @@
+use anyhow::Context;
 pub use macro_env::Environment;
 
 /// The configuration parameters for the application.
@@ -12,6 +13,15 @@ pub struct Config {
     pub port: usize,
     /// The environment we are in
     pub environment: Environment,
+    /// The secret image proxy urls are signed with, shared with the email service
+    pub image_proxy_secret_key: String,
+    /// The redis fetched responses and unfurled links are cached in
+    pub redis_uri: String,
+    /// The key used to authenticate with the other services
+    pub internal_api_secret_key: String,
+    pub document_storage_service_url: String,
+    pub comms_service_url: String,
+    pub email_service_url: String,
 }
 
 impl Config {
@@ -21,7 +31,27 @@ impl Config {
             .parse::<usize>()
             .unwrap();
         let environment = Environment::new_or_prod();
+        let image_proxy_secret_key = std::env::var("IMAGE_PROXY_SECRET_KEY")
+            .context("IMAGE_PROXY_SECRET_KEY must be provided")?;
+        let redis_uri = std::env::var("REDIS_URI").context("REDIS_URI must be provided")?;
+        let internal_api_secret_key = std::env::var("INTERNAL_API_SECRET_KEY")
+            .context("INTERNAL_API_SECRET_KEY must be provided")?;
+        let document_storage_service_url = std::env::var("DOCUMENT_STORAGE_SERVICE_URL")
+            .context("DOCUMENT_STORAGE_SERVICE_URL must be provided")?;
+        let comms_service_url =
+            std::env::var("COMMS_SERVICE_URL").context("COMMS_SERVICE_URL must be provided")?;
+        let email_service_url =
+            std::env::var("EMAIL_SERVICE_URL").context("EMAIL_SERVICE_URL must be provided")?;
 
-        Ok(Config { port, environment })
+        Ok(Config {
+            port,
+            environment,
+            image_proxy_secret_key,
+            redis_uri,
+            internal_api_secret_key,
+            document_storage_service_url,
+            comms_service_url,
+            email_service_url,
+        })
     }
 }
@@ -0,0 +1,132 @@
+use super::FetchedResponse;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::time::Duration;
+
+/// a cache that can't be reached is skipped rather than slowing down every fetch
+const CACHE_TIMEOUT_MILLIS: u64 = 500;
+const RESPONSE_KEY_PREFIX: &str = "unfurl_service:response:";
+
+/// Caches fetched responses and values derived from them in redis. Cache failures are logged and
+/// treated as misses.
+#[derive(Clone)]
+pub struct FetchCache {
+    inner: redis::Client,
+}
+
+impl FetchCache {
+    pub fn new(inner: redis::Client) -> Self {
+        Self { inner }
+    }
+
+    /// Gets a cached response of a url
+    pub async fn get_response(&self, url: &str) -> Option<FetchedResponse> {
+        let key = format!("{}{}", RESPONSE_KEY_PREFIX, url);
+        let value = self
+            .with_timeout(macro_redis::get::get_optional::<Vec<u8>>(&self.inner, &key))
+            .await??;
+
+        decode_response(url, &value)
+    }
+
+    /// Caches the response of a url
+    pub async fn set_response(&self, url: &str, response: &FetchedResponse, ttl_seconds: u64) {
+        let key = format!("{}{}", RESPONSE_KEY_PREFIX, url);
+        self.with_timeout(macro_redis::set::set_with_expiry(
+            &self.inner,
+            &key,
+            encode_response(response),
+            ttl_seconds,
+        ))
+        .await;
+    }
+
+    /// Gets a cached json value
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self
+            .with_timeout(macro_redis::get::get_optional::<String>(&self.inner, key))
+            .await??;
+
+        serde_json::from_str(&value)
+            .inspect_err(|e| tracing::warn!(error=?e, key, "invalid cached value"))
+            .ok()
+    }
+
+    /// Caches a json value
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: u64) {
+        let value = match serde_json::to_string(value) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!(error=?e, key, "unable to serialize cached value");
+                return;
+            }
+        };
+
+        self.with_timeout(macro_redis::set::set_with_expiry(
+            &self.inner,
+            key,
+            value,
+            ttl_seconds,
+        ))
+        .await;
+    }
+
+    async fn with_timeout<T>(
+        &self,
+        operation: impl Future<Output = anyhow::Result<T>>,
+    ) -> Option<T> {
+        match tokio::time::timeout(Duration::from_millis(CACHE_TIMEOUT_MILLIS), operation).await {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                tracing::warn!(error=?e, "cache operation failed");
+                None
+            }
+            Err(_) => {
+                tracing::warn!("cache operation timed out");
+                None
+            }
+        }
+    }
+}
+
+/// `<content type>\n<body>`, content types can't contain a newline
+fn encode_response(response: &FetchedResponse) -> Vec<u8> {
+    let mut value = Vec::with_capacity(response.content_type.len() + 1 + response.body.len());
+    value.extend_from_slice(response.content_type.as_bytes());
+    value.push(b'\n');
+    value.extend_from_slice(&response.body);
+    value
+}
+
+fn decode_response(url: &str, value: &[u8]) -> Option<FetchedResponse> {
+    let separator = value.iter().position(|b| *b == b'\n')?;
+    let content_type = String::from_utf8(value[..separator].to_vec()).ok()?;
+
+    Some(FetchedResponse {
+        url: url.to_string(),
+        content_type,
+        body: value[separator + 1..].to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_response() {
+        let response = FetchedResponse {
+            url: "https://example.com/logo.png".to_string(),
+            content_type: "image/png".to_string(),
+            body: vec![0x89, b'P', b'N', b'G', b'\n', 0],
+        };
+
+        let encoded = encode_response(&response);
+        assert_eq!(decode_response(&response.url, &encoded), Some(response));
+        assert_eq!(
+            decode_response("https://example.com", b"no separator"),
+            None
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Fetching of user provided urls through [outbound_http], with the responses cached in redis
+
+mod cache;
+
+pub use cache::FetchCache;
+pub use outbound_http::{FetchPolicy, FetchedResponse, Fetcher};
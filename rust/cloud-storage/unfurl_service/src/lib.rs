@@ -1,2 +1,3 @@
+pub mod fetch;
 mod unfurl;
 pub use unfurl::*;
@@ -1,5 +1,6 @@
 mod api;
 mod config;
+mod fetch;
 mod unfurl;
 use anyhow::Context;
 use config::Config;
@@ -0,0 +1,340 @@
+//! Unfurling of links to items in the Macro app. Items are looked up with the viewer's token, so a
+//! link only unfurls for viewers that have access to what it points to.
+
+use super::GetUnfurlResponse;
+use comms_service_client::CommsServiceClient;
+use document_storage_service_client::DocumentStorageServiceClient;
+use email_service_client::EmailServiceClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use url::Url;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The number of characters of an item's content shown in its preview
+const PREVIEW_CHARS: usize = 200;
+
+/// The blocks of the app that display documents
+const DOCUMENT_BLOCKS: &[&str] = &[
+    "canvas", "code", "image", "md", "pdf", "unknown", "video", "write",
+];
+
+/// An item of the Macro app a link points to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroLink {
+    Document(String),
+    Project(String),
+    Channel(Uuid),
+    EmailThread(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroItemType {
+    Document,
+    Project,
+    Channel,
+    Email,
+}
+
+/// The Macro item an unfurled link points to
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Eq, PartialEq)]
+pub struct MacroItem {
+    pub item_type: MacroItemType,
+    pub id: String,
+    /// The user id of the owner of a document or project, or the sender of an email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// The file type of a document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<String>,
+    /// The type of a channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_type: Option<String>,
+}
+
+/// Parses a link to an item of the app, `<app url>/<block>/<id>`
+pub fn parse_macro_link(url: &str, app_url: &Url) -> Option<MacroLink> {
+    let url = Url::parse(url).ok()?;
+    if url.scheme() != app_url.scheme()
+        || url.host_str() != app_url.host_str()
+        || url.port_or_known_default() != app_url.port_or_known_default()
+    {
+        return None;
+    }
+
+    let path = url.path().strip_prefix(app_url.path())?;
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let block = segments.next()?;
+    let id = segments.next()?.to_string();
+
+    match block {
+        "project" => Some(MacroLink::Project(id)),
+        "channel" => Uuid::parse_str(&id).ok().map(MacroLink::Channel),
+        "email" => Some(MacroLink::EmailThread(id)),
+        block if DOCUMENT_BLOCKS.contains(&block) => Some(MacroLink::Document(id)),
+        _ => None,
+    }
+}
+
+/// Looks up the items of the app links point to
+#[derive(Clone)]
+pub struct MacroItemClient {
+    app_url: Url,
+    document_storage_client: Arc<DocumentStorageServiceClient>,
+    comms_client: Arc<CommsServiceClient>,
+    email_client: Arc<EmailServiceClient>,
+}
+
+impl MacroItemClient {
+    pub fn new(
+        app_url: Url,
+        document_storage_client: Arc<DocumentStorageServiceClient>,
+        comms_client: Arc<CommsServiceClient>,
+        email_client: Arc<EmailServiceClient>,
+    ) -> Self {
+        Self {
+            app_url,
+            document_storage_client,
+            comms_client,
+            email_client,
+        }
+    }
+
+    pub fn parse_link(&self, url: &str) -> Option<MacroLink> {
+        parse_macro_link(url, &self.app_url)
+    }
+
+    /// Unfurls a link to an item, `None` when the item doesn't exist or the viewer can't see it
+    #[tracing::instrument(skip(self, jwt_token), err)]
+    pub async fn unfurl(
+        &self,
+        url: &str,
+        link: MacroLink,
+        jwt_token: &str,
+    ) -> anyhow::Result<Option<GetUnfurlResponse>> {
+        let unfurled = match link {
+            MacroLink::Document(id) => self.unfurl_document(url, id, jwt_token).await?,
+            MacroLink::Project(id) => self.unfurl_project(url, id, jwt_token).await?,
+            MacroLink::Channel(id) => self.unfurl_channel(url, id, jwt_token).await?,
+            MacroLink::EmailThread(id) => self.unfurl_email_thread(url, id, jwt_token).await?,
+        };
+
+        Ok(unfurled)
+    }
+
+    async fn unfurl_document(
+        &self,
+        url: &str,
+        id: String,
+        jwt_token: &str,
+    ) -> anyhow::Result<Option<GetUnfurlResponse>> {
+        let Some(document) = self
+            .document_storage_client
+            .get_document_basic_external(&id, jwt_token)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if document.deleted_at.is_some() {
+            return Ok(None);
+        }
+
+        // the preview is best effort, not every document has text
+        let description = self
+            .document_storage_client
+            .get_document_text_external(&id, jwt_token)
+            .await
+            .inspect_err(|e| tracing::debug!(error=?e, "unable to get document text"))
+            .ok()
+            .and_then(|text| preview(&text));
+
+        Ok(Some(GetUnfurlResponse {
+            url: url.to_string(),
+            title: document.document_name,
+            description,
+            macro_item: Some(MacroItem {
+                item_type: MacroItemType::Document,
+                id,
+                owner: Some(document.owner),
+                file_type: document.file_type,
+                channel_type: None,
+            }),
+            ..Default::default()
+        }))
+    }
+
+    async fn unfurl_project(
+        &self,
+        url: &str,
+        id: String,
+        jwt_token: &str,
+    ) -> anyhow::Result<Option<GetUnfurlResponse>> {
+        let Some(project) = self
+            .document_storage_client
+            .get_project_external(&id, jwt_token)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let project = project.project_metadata;
+        if project.deleted_at.is_some() {
+            return Ok(None);
+        }
+
+        Ok(Some(GetUnfurlResponse {
+            url: url.to_string(),
+            title: project.name,
+            macro_item: Some(MacroItem {
+                item_type: MacroItemType::Project,
+                id,
+                owner: Some(project.user_id),
+                file_type: None,
+                channel_type: None,
+            }),
+            ..Default::default()
+        }))
+    }
+
+    async fn unfurl_channel(
+        &self,
+        url: &str,
+        id: Uuid,
+        jwt_token: &str,
+    ) -> anyhow::Result<Option<GetUnfurlResponse>> {
+        let channel = match self
+            .comms_client
+            .get_channel_metadata_external(&id, jwt_token)
+            .await
+        {
+            Ok(channel) => channel,
+            Err(comms_service_client::error::ClientError::NetworkError {
+                status_code: 401 | 403 | 404,
+                ..
+            }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Some(GetUnfurlResponse {
+            url: url.to_string(),
+            title: channel.channel_name,
+            macro_item: Some(MacroItem {
+                item_type: MacroItemType::Channel,
+                id: id.to_string(),
+                owner: None,
+                file_type: None,
+                channel_type: Some(channel.channel_type.to_string()),
+            }),
+            ..Default::default()
+        }))
+    }
+
+    async fn unfurl_email_thread(
+        &self,
+        url: &str,
+        id: String,
+        jwt_token: &str,
+    ) -> anyhow::Result<Option<GetUnfurlResponse>> {
+        // the email service answers threads the viewer can't see with an error
+        let Some(message) = self
+            .email_client
+            .get_messages_by_thread_id_external(&id, 0, 1, jwt_token)
+            .await
+            .inspect_err(|e| tracing::debug!(error=?e, "unable to get email thread"))
+            .ok()
+            .and_then(|messages| messages.into_iter().next())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(GetUnfurlResponse {
+            url: url.to_string(),
+            title: message.subject.unwrap_or_default(),
+            description: message.body_parsed.as_deref().and_then(preview),
+            macro_item: Some(MacroItem {
+                item_type: MacroItemType::Email,
+                id,
+                owner: message.from.map(|from| from.email),
+                file_type: None,
+                channel_type: None,
+            }),
+            ..Default::default()
+        }))
+    }
+}
+
+/// The start of a text on a single line
+fn preview(text: &str) -> Option<String> {
+    let words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut chars = words.chars();
+    let preview: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        Some(format!("{}…", preview.trim_end()))
+    } else {
+        Some(preview)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_macro_link() {
+        let app_url = Url::parse("https://macro.com/app/").unwrap();
+        let channel_id = Uuid::new_v4();
+
+        assert_eq!(
+            parse_macro_link("https://macro.com/app/md/doc-id", &app_url),
+            Some(MacroLink::Document("doc-id".to_string()))
+        );
+        assert_eq!(
+            parse_macro_link("https://macro.com/app/pdf/doc-id?page=2", &app_url),
+            Some(MacroLink::Document("doc-id".to_string()))
+        );
+        assert_eq!(
+            parse_macro_link("https://macro.com/app/project/project-id", &app_url),
+            Some(MacroLink::Project("project-id".to_string()))
+        );
+        assert_eq!(
+            parse_macro_link(
+                &format!("https://macro.com/app/channel/{}?message_id=1", channel_id),
+                &app_url
+            ),
+            Some(MacroLink::Channel(channel_id))
+        );
+        assert_eq!(
+            parse_macro_link("https://macro.com/app/email/thread-id", &app_url),
+            Some(MacroLink::EmailThread("thread-id".to_string()))
+        );
+
+        assert_eq!(
+            parse_macro_link("https://macro.com/app/channel/not-a-uuid", &app_url),
+            None
+        );
+        assert_eq!(parse_macro_link("https://macro.com/app/md", &app_url), None);
+        assert_eq!(
+            parse_macro_link("https://dev.macro.com/app/md/doc-id", &app_url),
+            None
+        );
+        assert_eq!(
+            parse_macro_link("https://macro.com/blog/md/doc-id", &app_url),
+            None
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("  \n "), None);
+        assert_eq!(preview("a short\n\ntext").as_deref(), Some("a short text"));
+
+        let long = "word ".repeat(100);
+        let long_preview = preview(&long).unwrap();
+        assert!(long_preview.ends_with('…'));
+        assert!(long_preview.chars().count() <= PREVIEW_CHARS + 1);
+    }
+}
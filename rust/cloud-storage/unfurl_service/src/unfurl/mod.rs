@@ -1,15 +1,25 @@
+pub mod macro_link;
+pub mod oembed;
 pub mod url_parsers;
 
 use anyhow::{Context, Error};
-use http::StatusCode;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use url::Url;
 use utoipa::ToSchema;
 
+#[cfg(not(feature = "mock"))]
+use crate::fetch::FetchPolicy;
+use crate::fetch::{FetchCache, Fetcher};
+use macro_link::{MacroItem, MacroItemClient};
+use oembed::{Embed, OEmbed};
 use url_parsers::parse_custom_title;
 
+const UNFURL_KEY_PREFIX: &str = "unfurl_service:unfurl:";
+const UNFURL_TTL_SECONDS: u64 = 24 * 60 * 60;
+const FAILED_UNFURL_TTL_SECONDS: u64 = 10 * 60;
+
 pub type GetUnfurlResponseList = Vec<Option<GetUnfurlResponse>>;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Default, Clone, Eq, PartialEq)]
@@ -22,6 +32,12 @@ pub struct GetUnfurlResponse {
     pub image_url: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub favicon_url: Option<String>,
+    /// A rich embed of the link, from its oEmbed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embed: Option<Embed>,
+    /// The Macro item the link points to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub macro_item: Option<MacroItem>,
 }
 
 fn no_tag(tag: &str) -> Error {
@@ -64,6 +80,10 @@ fn parse_document(html_content: &str, url: &Url) -> Result<HashMap<String, Strin
     if let Some(favicon) = find_favicon(&document, url) {
         meta_tags.insert("favicon".to_string(), favicon);
     }
+
+    if let Some(oembed) = oembed::discover_endpoint(&document, url) {
+        meta_tags.insert("oembed".to_string(), oembed);
+    }
     Ok(meta_tags)
 }
 
@@ -90,19 +110,16 @@ fn find_favicon(document: &Html, base_url: &Url) -> Option<String> {
     None
 }
 #[cfg(not(feature = "mock"))]
-#[tracing::instrument]
+#[tracing::instrument(skip(fetcher))]
 pub async fn extract_meta_tags(
+    fetcher: &Fetcher,
     url: &str,
 ) -> Result<HashMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
-    // Fetch the HTML content
-    // TODO: how to handle malicious input?
-    let url = Url::parse(url).context("invalid url")?;
+    let response = fetcher.fetch(url, &FetchPolicy::PAGE).await?;
+    let html_content = String::from_utf8_lossy(&response.body);
 
-    let response = reqwest::get(url.clone()).await?;
-    if response.status() != StatusCode::OK {
-        return Err(anyhow::anyhow!("Site refused with code: {}", response.status()).into());
-    }
-    let html_content = response.text().await?;
+    // relative links in the page are relative to where it was redirected to
+    let url = Url::parse(&response.url).context("invalid url")?;
     let meta_tags = parse_document(&html_content, &url)?;
 
     Ok(meta_tags)
@@ -137,8 +154,9 @@ pub fn append_optimistic_favico(
 
 #[cfg(feature = "mock")]
 pub async fn extract_meta_tags(
+    _fetcher: &Fetcher,
     url: &str,
-) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+) -> Result<HashMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
     if url == "https://hello.com" {
         let mut m = HashMap::new();
         m.insert("property:og:title".to_string(), "Hello".to_string());
@@ -219,18 +237,122 @@ impl GetUnfurlResponse {
             description,
             image_url,
             favicon_url,
+            ..Default::default()
+        }
+    }
+
+    /// Fills in what the page's tags lack from its oEmbed
+    pub fn with_oembed(mut self, oembed: OEmbed) -> Self {
+        if self.title == self.url
+            && let Some(title) = &oembed.title
+        {
+            self.title = title.clone();
+        }
+
+        if self.image_url.is_none() {
+            self.image_url = oembed.thumbnail_url.clone();
         }
+
+        self.embed = oembed.embed();
+        self
     }
 }
 
-pub async fn fetch_links_async(links: &[String]) -> GetUnfurlResponseList {
-    let futures = links.iter().map(|url| async move {
-        extract_meta_tags(url)
+/// Unfurls a link. Links to Macro items are unfurled with the viewer's token and never cached,
+/// other links come from the cache when they were unfurled recently.
+pub async fn unfurl_link(
+    fetcher: &Fetcher,
+    cache: &FetchCache,
+    macro_items: &MacroItemClient,
+    url: &str,
+    jwt_token: Option<&str>,
+) -> Option<GetUnfurlResponse> {
+    if let Some(link) = macro_items.parse_link(url) {
+        // nothing of a Macro item is shown to viewers that aren't signed in
+        return macro_items
+            .unfurl(url, link, jwt_token?)
             .await
             .ok()
-            .map(|tags| append_optimistic_favico(tags, url))
-            .map(|tags| GetUnfurlResponse::new(url, &tags))
-    });
+            .flatten();
+    }
+
+    unfurl_external_link(fetcher, cache, url).await
+}
+
+/// Unfurls a link outside of Macro, from the cache when it was unfurled recently. Links that
+/// couldn't be unfurled are cached as well, for less time.
+async fn unfurl_external_link(
+    fetcher: &Fetcher,
+    cache: &FetchCache,
+    url: &str,
+) -> Option<GetUnfurlResponse> {
+    let key = format!("{}{}", UNFURL_KEY_PREFIX, url);
+    if let Some(unfurled) = cache.get_json::<Option<GetUnfurlResponse>>(&key).await {
+        return unfurled;
+    }
+
+    // the oembed of known providers is fetched alongside the page, others are discovered from it
+    let provider_oembed = async {
+        let endpoint = oembed::provider_endpoint(url)?;
+        fetch_oembed(fetcher, &endpoint).await
+    };
+    let (tags, provider_oembed) =
+        futures::future::join(extract_meta_tags(fetcher, url), provider_oembed).await;
+
+    let oembed = match (provider_oembed, &tags) {
+        (Some(oembed), _) => Some(oembed),
+        (None, Ok(tags)) => match tags.get("oembed") {
+            Some(endpoint) => fetch_oembed(fetcher, endpoint).await,
+            None => None,
+        },
+        (None, Err(_)) => None,
+    };
+
+    let unfurled = match (tags, oembed) {
+        (Ok(tags), oembed) => {
+            let unfurled = GetUnfurlResponse::new(url, &append_optimistic_favico(tags, url));
+            Some(match oembed {
+                Some(oembed) => unfurled.with_oembed(oembed),
+                None => unfurled,
+            })
+        }
+        // some providers can only be unfurled through their oembed
+        (Err(_), Some(oembed)) => {
+            Some(GetUnfurlResponse::new(url, &HashMap::new()).with_oembed(oembed))
+        }
+        (Err(e), None) => {
+            tracing::debug!(error=?e, url, "unable to unfurl link");
+            None
+        }
+    };
+
+    let ttl_seconds = if unfurled.is_some() {
+        UNFURL_TTL_SECONDS
+    } else {
+        FAILED_UNFURL_TTL_SECONDS
+    };
+    cache.set_json(&key, &unfurled, ttl_seconds).await;
+
+    unfurled
+}
+
+async fn fetch_oembed(fetcher: &Fetcher, endpoint: &str) -> Option<OEmbed> {
+    oembed::fetch_oembed(fetcher, endpoint)
+        .await
+        .inspect_err(|e| tracing::debug!(error=?e, endpoint, "unable to get oembed"))
+        .ok()
+}
+
+pub async fn fetch_links_async(
+    fetcher: &Fetcher,
+    cache: &FetchCache,
+    macro_items: &MacroItemClient,
+    links: &[String],
+    jwt_token: Option<&str>,
+) -> GetUnfurlResponseList {
+    let futures = links
+        .iter()
+        .map(|url| async move { unfurl_link(fetcher, cache, macro_items, url, jwt_token).await });
 
     futures::future::join_all(futures).await
 }
@@ -282,6 +404,39 @@ mod tests {
         assert_eq!(link.image_url.unwrap(), "foo.jpg");
     }
 
+    // the oembed fills in what the page's tags lack
+    #[test]
+    fn test_with_oembed() {
+        let oembed: OEmbed = serde_json::from_str(
+            r#"{
+                "type": "rich",
+                "title": "A post",
+                "author_name": "Macro",
+                "thumbnail_url": "https://example.com/thumbnail.jpg",
+                "html": "<blockquote>A post</blockquote>"
+            }"#,
+        )
+        .unwrap();
+
+        let url = "https://x.com/macro/status/1";
+        let response = GetUnfurlResponse::new(url, &HashMap::new()).with_oembed(oembed.clone());
+        assert_eq!(response.title, "A post");
+        assert_eq!(
+            response.image_url.as_deref(),
+            Some("https://example.com/thumbnail.jpg")
+        );
+        let embed = response.embed.unwrap();
+        assert_eq!(embed.embed_type, "rich");
+        assert_eq!(embed.author_name.as_deref(), Some("Macro"));
+
+        let mut tags = HashMap::new();
+        tags.insert("property:og:title".to_string(), "hello".to_string());
+        tags.insert("property:og:image".to_string(), "foo.jpg".to_string());
+        let response = GetUnfurlResponse::new(url, &tags).with_oembed(oembed);
+        assert_eq!(response.title, "hello");
+        assert_eq!(response.image_url.as_deref(), Some("foo.jpg"));
+    }
+
     // use og:site_name as fallback for title
     #[test]
     fn test_extract_site_name() {
@@ -390,7 +545,10 @@ mod tests {
     #[cfg(feature = "mock")]
     #[tokio::test]
     async fn test_extract_meta_tags_mock() {
-        let tags = extract_meta_tags("https://hello.com").await.unwrap();
+        let fetcher = Fetcher::new().unwrap();
+        let tags = extract_meta_tags(&fetcher, "https://hello.com")
+            .await
+            .unwrap();
         assert!(tags.contains_key("property:og:title"));
         let title = tags.get("property:og:title").unwrap();
         assert_eq!(title, "Hello");
@@ -0,0 +1,235 @@
+//! oEmbed support, see <https://oembed.com>. Links of known providers are embedded through the
+//! provider's endpoint, other links through the endpoint their page advertises.
+
+use crate::fetch::{FetchPolicy, Fetcher};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Deserializer, Serialize};
+use url::Url;
+use utoipa::ToSchema;
+
+struct Provider {
+    hosts: &'static [&'static str],
+    endpoint: &'static str,
+}
+
+/// Providers whose pages don't advertise their endpoint, or can't be fetched without a browser
+const PROVIDERS: &[Provider] = &[
+    Provider {
+        hosts: &[
+            "youtube.com",
+            "www.youtube.com",
+            "m.youtube.com",
+            "youtu.be",
+        ],
+        endpoint: "https://www.youtube.com/oembed",
+    },
+    Provider {
+        hosts: &["vimeo.com", "player.vimeo.com"],
+        endpoint: "https://vimeo.com/api/oembed.json",
+    },
+    Provider {
+        hosts: &[
+            "twitter.com",
+            "www.twitter.com",
+            "mobile.twitter.com",
+            "x.com",
+        ],
+        endpoint: "https://publish.twitter.com/oembed",
+    },
+    Provider {
+        hosts: &["loom.com", "www.loom.com"],
+        endpoint: "https://www.loom.com/v1/oembed",
+    },
+    Provider {
+        hosts: &["open.spotify.com"],
+        endpoint: "https://open.spotify.com/oembed",
+    },
+    Provider {
+        hosts: &["soundcloud.com"],
+        endpoint: "https://soundcloud.com/oembed",
+    },
+    Provider {
+        hosts: &["codepen.io"],
+        endpoint: "https://codepen.io/api/oembed",
+    },
+    Provider {
+        hosts: &["codesandbox.io"],
+        endpoint: "https://codesandbox.io/oembed",
+    },
+];
+
+/// The oEmbed response of a provider
+#[derive(Debug, Clone, Deserialize)]
+pub struct OEmbed {
+    /// photo, video, link or rich
+    #[serde(rename = "type")]
+    pub oembed_type: String,
+    pub title: Option<String>,
+    pub author_name: Option<String>,
+    pub provider_name: Option<String>,
+    pub thumbnail_url: Option<String>,
+    /// the image of a photo
+    pub url: Option<String>,
+    /// the html of a video or rich embed
+    pub html: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_dimension")]
+    pub width: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_dimension")]
+    pub height: Option<u32>,
+}
+
+/// A rich embed of an unfurled link
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Eq, PartialEq)]
+pub struct Embed {
+    /// photo, video or rich
+    #[serde(rename = "type")]
+    pub embed_type: String,
+    /// The html of a video or rich embed. It is provided by a third party, so it must only be
+    /// rendered in a sandboxed frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// The image of a photo embed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+}
+
+impl OEmbed {
+    /// The embed of the response, links have nothing to embed
+    pub fn embed(&self) -> Option<Embed> {
+        let has_content = match self.oembed_type.as_str() {
+            "photo" => self.url.is_some(),
+            "video" | "rich" => self.html.is_some(),
+            _ => false,
+        };
+        if !has_content {
+            return None;
+        }
+
+        Some(Embed {
+            embed_type: self.oembed_type.clone(),
+            html: self.html.clone(),
+            url: self.url.clone(),
+            width: self.width,
+            height: self.height,
+            provider_name: self.provider_name.clone(),
+            author_name: self.author_name.clone(),
+        })
+    }
+}
+
+/// Dimensions are numbers, though some providers send them as strings
+fn deserialize_dimension<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::Number(n)) => n.as_f64().map(|n| n as u32),
+        Some(serde_json::Value::String(s)) => s.parse().ok(),
+        _ => None,
+    })
+}
+
+/// The endpoint of a known provider to get the oEmbed of a url from
+pub fn provider_endpoint(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    let provider = PROVIDERS
+        .iter()
+        .find(|provider| provider.hosts.contains(&host))?;
+
+    oembed_endpoint(provider.endpoint, url.as_str())
+}
+
+fn oembed_endpoint(endpoint: &str, url: &str) -> Option<String> {
+    Url::parse_with_params(endpoint, [("url", url), ("format", "json")])
+        .ok()
+        .map(|endpoint| endpoint.to_string())
+}
+
+/// The json oEmbed endpoint a page advertises, made absolute
+pub fn discover_endpoint(document: &Html, base_url: &Url) -> Option<String> {
+    let selector =
+        Selector::parse(r#"link[type="application/json+oembed"], link[type="text/json+oembed"]"#)
+            .ok()?;
+
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("href"))
+        .find_map(|href| base_url.join(href).ok())
+        .map(|endpoint| endpoint.to_string())
+}
+
+/// Gets the oEmbed of a url from an endpoint
+#[tracing::instrument(skip(fetcher))]
+pub async fn fetch_oembed(fetcher: &Fetcher, endpoint: &str) -> anyhow::Result<OEmbed> {
+    let response = fetcher.fetch(endpoint, &FetchPolicy::OEMBED).await?;
+    let oembed = serde_json::from_slice(&response.body)?;
+    Ok(oembed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_endpoint() {
+        assert_eq!(
+            provider_endpoint("https://www.youtube.com/watch?v=dQw4w9WgXcQ").as_deref(),
+            Some(
+                "https://www.youtube.com/oembed?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3DdQw4w9WgXcQ&format=json"
+            )
+        );
+        assert!(provider_endpoint("https://x.com/macro/status/1").is_some());
+        assert!(provider_endpoint("https://example.com/watch").is_none());
+        assert!(provider_endpoint("not a url").is_none());
+    }
+
+    #[test]
+    fn test_discover_endpoint() {
+        let document = Html::parse_document(
+            r#"<html><head>
+            <link rel="alternate" type="text/xml+oembed" href="/oembed?format=xml" />
+            <link rel="alternate" type="application/json+oembed" href="/oembed?format=json" />
+            </head></html>"#,
+        );
+        let base_url = Url::parse("https://example.com/page").unwrap();
+
+        assert_eq!(
+            discover_endpoint(&document, &base_url).as_deref(),
+            Some("https://example.com/oembed?format=json")
+        );
+    }
+
+    #[test]
+    fn test_embed() {
+        let video: OEmbed = serde_json::from_str(
+            r#"{
+                "type": "video",
+                "version": "1.0",
+                "title": "A video",
+                "provider_name": "YouTube",
+                "html": "<iframe src=\"https://www.youtube.com/embed/1\"></iframe>",
+                "width": 200,
+                "height": "113"
+            }"#,
+        )
+        .unwrap();
+        let embed = video.embed().unwrap();
+        assert_eq!(embed.embed_type, "video");
+        assert_eq!(embed.width, Some(200));
+        assert_eq!(embed.height, Some(113));
+
+        let link: OEmbed =
+            serde_json::from_str(r#"{"type": "link", "title": "A page", "width": null}"#).unwrap();
+        assert_eq!(link.embed(), None);
+    }
+}
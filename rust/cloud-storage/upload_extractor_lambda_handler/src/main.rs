@@ -534,6 +534,7 @@ async fn build_file_system(extract_dir: &Path) -> Result<Vec<FolderItem>, Error>
                 file_type,
                 relative_path: relative_path_without_filename,
                 sha,
+                size_bytes: None,
             })
         };
 
@@ -694,6 +695,7 @@ mod tests {
             file_type: Some(FileType::Pdf),
             relative_path: "folder/subfolder".to_string(),
             sha: "abcd1234".to_string(),
+            size_bytes: None,
         };
 
         let path = get_file_path_for_item(&extract_dir, &item);
@@ -712,6 +714,7 @@ mod tests {
             file_type: Some(FileType::TarGz),
             relative_path: "folder/subfolder".to_string(),
             sha: "abcd1234".to_string(),
+            size_bytes: None,
         };
 
         let path = get_file_path_for_item(&extract_dir, &item);
@@ -730,6 +733,7 @@ mod tests {
             file_type: None,
             relative_path: "settings".to_string(),
             sha: "efgh5678".to_string(),
+            size_bytes: None,
         };
 
         let path = get_file_path_for_item(&extract_dir, &item);
@@ -745,6 +749,7 @@ mod tests {
             file_type: Some(FileType::TarGz),
             relative_path: "settings".to_string(),
             sha: "efgh5678".to_string(),
+            size_bytes: None,
         };
 
         let path = get_file_path_for_item(&extract_dir, &item);
@@ -763,6 +768,7 @@ mod tests {
             file_type: Some(FileType::Txt),
             relative_path: "".to_string(),
             sha: "ijkl9012".to_string(),
+            size_bytes: None,
         };
 
         let path = get_file_path_for_item(&extract_dir, &item);
@@ -1,6 +1,8 @@
 #![deny(missing_docs)]
 //! This crate contains model information for the user quota
 
+pub mod storage;
+
 /// The UserQuota represents the user's current quota
 #[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, utoipa::ToSchema)]
 pub struct UserQuota {
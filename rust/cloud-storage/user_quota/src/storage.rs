@@ -0,0 +1,228 @@
+//! Storage quota models.
+//! Storage is accounted in bytes and deduplicated by sha, so content that is stored more than
+//! once only counts towards the quota a single time.
+
+use std::{fmt::Display, str::FromStr};
+
+/// The number of bytes in a gibibyte
+const GIB: i64 = 1024 * 1024 * 1024;
+
+/// How a storage limit is enforced once it has been reached
+#[derive(
+    serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, Copy, utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLimitBehavior {
+    /// The user is warned but can keep storing documents
+    Soft,
+    /// The user cannot store any more documents
+    Hard,
+}
+
+impl FromStr for StorageLimitBehavior {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "soft" => Ok(Self::Soft),
+            "hard" => Ok(Self::Hard),
+            _ => Err(anyhow::anyhow!("invalid storage limit behavior {s}")),
+        }
+    }
+}
+
+impl Display for StorageLimitBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageLimitBehavior::Soft => write!(f, "soft"),
+            StorageLimitBehavior::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+/// The storage limit granted by a plan
+#[derive(
+    serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, Copy, utoipa::ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePlan {
+    /// The number of bytes that can be stored
+    pub limit_bytes: i64,
+    /// How the limit is enforced
+    pub limit_behavior: StorageLimitBehavior,
+}
+
+impl StoragePlan {
+    /// Picks the most generous out of the provided plans and the default plan.
+    /// Plans with the same limit prefer a soft limit.
+    pub fn most_generous(plans: impl IntoIterator<Item = StoragePlan>) -> StoragePlan {
+        plans
+            .into_iter()
+            .chain(std::iter::once(DEFAULT_STORAGE_PLAN))
+            .max_by_key(|plan| {
+                (
+                    plan.limit_bytes,
+                    plan.limit_behavior == StorageLimitBehavior::Soft,
+                )
+            })
+            .unwrap_or(DEFAULT_STORAGE_PLAN)
+    }
+}
+
+/// The plan used for users that do not have a role which grants a storage plan
+pub static DEFAULT_STORAGE_PLAN: StoragePlan = StoragePlan {
+    limit_bytes: 5 * GIB,
+    limit_behavior: StorageLimitBehavior::Hard,
+};
+
+/// The storage used by a user measured against their plan
+#[derive(
+    serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, Copy, utoipa::ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuota {
+    /// The number of bytes the user is currently storing
+    pub used_bytes: i64,
+    /// The number of bytes the user can store
+    pub limit_bytes: i64,
+    /// How the limit is enforced
+    pub limit_behavior: StorageLimitBehavior,
+}
+
+/// The result of checking a storage quota
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum StorageQuotaStatus {
+    /// The user is within their limit
+    WithinLimit,
+    /// The user is over their soft limit and should be warned
+    OverSoftLimit,
+    /// The user is over their hard limit and cannot store more
+    Exceeded,
+}
+
+impl StorageQuota {
+    /// Creates the storage quota for the used bytes under the provided plan
+    pub fn new(used_bytes: i64, plan: StoragePlan) -> Self {
+        Self {
+            used_bytes,
+            limit_bytes: plan.limit_bytes,
+            limit_behavior: plan.limit_behavior,
+        }
+    }
+
+    /// Checks if the user can store the additional bytes
+    pub fn check(&self, additional_bytes: i64) -> StorageQuotaStatus {
+        if self.used_bytes.saturating_add(additional_bytes) <= self.limit_bytes {
+            return StorageQuotaStatus::WithinLimit;
+        }
+
+        match self.limit_behavior {
+            StorageLimitBehavior::Soft => StorageQuotaStatus::OverSoftLimit,
+            StorageLimitBehavior::Hard => StorageQuotaStatus::Exceeded,
+        }
+    }
+}
+
+/// The storage used by documents of a file type
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTypeStorageUsage {
+    /// The file type of the documents. None for documents without a file type.
+    pub file_type: Option<String>,
+    /// The number of bytes stored by documents of the file type
+    pub used_bytes: i64,
+}
+
+/// The storage used by the documents directly inside of a project
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStorageUsage {
+    /// The id of the project. None for documents that are not in a project.
+    pub project_id: Option<String>,
+    /// The name of the project
+    pub project_name: Option<String>,
+    /// The number of bytes stored by documents in the project
+    pub used_bytes: i64,
+}
+
+/// The storage used by all members of a team
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamStorageUsage {
+    /// The id of the team
+    pub team_id: String,
+    /// The name of the team
+    pub team_name: String,
+    /// The number of bytes stored by the team members
+    pub used_bytes: i64,
+}
+
+/// The storage used by all members of an organization
+#[derive(serde::Serialize, serde::Deserialize, Eq, PartialEq, Debug, Clone, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationStorageUsage {
+    /// The id of the organization
+    pub organization_id: i32,
+    /// The name of the organization
+    pub organization_name: String,
+    /// The number of bytes stored by the organization members
+    pub used_bytes: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_generous_plan() {
+        assert_eq!(StoragePlan::most_generous([]), DEFAULT_STORAGE_PLAN);
+
+        let small = StoragePlan {
+            limit_bytes: 1,
+            limit_behavior: StorageLimitBehavior::Soft,
+        };
+        assert_eq!(StoragePlan::most_generous([small]), DEFAULT_STORAGE_PLAN);
+
+        let hard = StoragePlan {
+            limit_bytes: 100 * GIB,
+            limit_behavior: StorageLimitBehavior::Hard,
+        };
+        let soft = StoragePlan {
+            limit_bytes: 100 * GIB,
+            limit_behavior: StorageLimitBehavior::Soft,
+        };
+        assert_eq!(StoragePlan::most_generous([small, hard, soft]), soft);
+    }
+
+    #[test]
+    fn test_check_quota() {
+        let hard = StorageQuota::new(
+            90,
+            StoragePlan {
+                limit_bytes: 100,
+                limit_behavior: StorageLimitBehavior::Hard,
+            },
+        );
+        assert_eq!(hard.check(10), StorageQuotaStatus::WithinLimit);
+        assert_eq!(hard.check(11), StorageQuotaStatus::Exceeded);
+
+        let soft = StorageQuota {
+            limit_behavior: StorageLimitBehavior::Soft,
+            ..hard
+        };
+        assert_eq!(soft.check(11), StorageQuotaStatus::OverSoftLimit);
+        assert_eq!(soft.check(i64::MAX), StorageQuotaStatus::OverSoftLimit);
+    }
+
+    #[test]
+    fn test_limit_behavior_round_trip() -> anyhow::Result<()> {
+        for behavior in [StorageLimitBehavior::Soft, StorageLimitBehavior::Hard] {
+            assert_eq!(
+                behavior.to_string().parse::<StorageLimitBehavior>()?,
+                behavior
+            );
+        }
+        assert!("none".parse::<StorageLimitBehavior>().is_err());
+        Ok(())
+    }
+}